@@ -188,7 +188,9 @@ impl From<LanceError> for Error {
             | LanceError::InvalidInput { .. } => Self::input_error(err.to_string()),
             LanceError::IO { .. } => Self::io_error(err.to_string()),
             LanceError::Timeout { .. } => Self::timeout_error(err.to_string()),
-            LanceError::NotSupported { .. } => Self::unsupported_error(err.to_string()),
+            LanceError::NotSupported { .. } | LanceError::UnsupportedVersion { .. } => {
+                Self::unsupported_error(err.to_string())
+            }
             LanceError::NotFound { .. } => Self::io_error(err.to_string()),
             LanceError::Namespace { source, .. } => {
                 // Try to downcast to NamespaceError and get the error code
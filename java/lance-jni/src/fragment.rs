@@ -909,6 +909,8 @@ impl FromJObjectWithEnv<DataFile> for JObject<'_> {
             file_minor_version,
             file_size_bytes,
             base_id,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         })
     }
 }
@@ -81,7 +81,9 @@ impl<T> PythonErrorExt<T> for std::result::Result<T, LanceError> {
             Ok(_) => Ok(self.unwrap()),
             Err(err) => match err {
                 LanceError::InvalidInput { .. } => self.value_error(),
-                LanceError::NotSupported { .. } => self.not_implemented(),
+                LanceError::NotSupported { .. } | LanceError::UnsupportedVersion { .. } => {
+                    self.not_implemented()
+                }
                 LanceError::IO { .. } => self.io_error(),
                 LanceError::Timeout { .. } => self.timeout_error(),
                 LanceError::NotFound { .. } => self.value_error(),
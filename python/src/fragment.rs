@@ -863,6 +863,8 @@ impl FromPyObject<'_, '_> for PyLance<DataFile> {
             file_minor_version: ob.getattr("file_minor_version")?.extract()?,
             file_size_bytes,
             base_id: ob.getattr("base_id")?.extract()?,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         }))
     }
 }
@@ -417,11 +417,16 @@ impl FFILanceTableProvider {
         session: Bound<PyAny>,
     ) -> PyResult<Bound<'py, PyCapsule>> {
         let name = CString::new("datafusion_table_provider").unwrap();
-        let a_lance_table_provider = Arc::new(LanceTableProvider::new(
-            self.dataset.clone(),
-            self.with_row_id,
-            self.with_row_addr,
-        ));
+        let a_lance_table_provider = Arc::new(
+            rt().block_on(
+                Some(py),
+                LanceTableProvider::builder(self.dataset.clone())
+                    .with_row_id(self.with_row_id)
+                    .with_row_address(self.with_row_addr)
+                    .build(),
+            )?
+            .map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))?,
+        );
 
         let codec = ffi_logical_codec_from_pycapsule(session)?;
         let ffi_provider = FFI_TableProvider::new_with_ffi_codec(
@@ -27,6 +27,7 @@ pub mod schema;
 pub use schema::*;
 pub mod bfloat16;
 pub mod floats;
+use crate::bfloat16::BFloat16Type;
 use crate::list::ListArrayExt;
 pub use floats::*;
 
@@ -256,7 +257,7 @@ pub trait FixedSizeListArrayExt {
     /// ```
     fn sample(&self, n: usize) -> Result<FixedSizeListArray>;
 
-    /// Ensure the [FixedSizeListArray] of Float16, Float32, Float64,
+    /// Ensure the [FixedSizeListArray] of Float16, Float32, Float64, BFloat16,
     /// Int8, Int16, Int32, Int64, UInt8, UInt32 type to its closest floating point type.
     fn convert_to_floating_point(&self) -> Result<FixedSizeListArray>;
 }
@@ -282,6 +283,22 @@ impl FixedSizeListArrayExt for FixedSizeListArray {
         match self.data_type() {
             DataType::FixedSizeList(field, size) => match field.data_type() {
                 DataType::Float16 | DataType::Float32 | DataType::Float64 => Ok(self.clone()),
+                DataType::FixedSizeBinary(2) => Ok(Self::new(
+                    Arc::new(arrow_schema::Field::new(
+                        field.name(),
+                        DataType::Float32,
+                        field.is_nullable(),
+                    )),
+                    *size,
+                    Arc::new(Float32Array::from_iter_values(
+                        <FixedSizeBinaryArray as FloatArray<BFloat16Type>>::as_slice(
+                            self.values().as_fixed_size_binary(),
+                        )
+                        .iter()
+                        .map(|v| v.to_f32()),
+                    )),
+                    self.nulls().cloned(),
+                )),
                 DataType::Int8 => Ok(Self::new(
                     Arc::new(arrow_schema::Field::new(
                         field.name(),
@@ -9,6 +9,7 @@
 //! backends directly.
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -64,6 +65,13 @@ impl InternalCacheKey {
     }
 }
 
+/// Number of entries, and their total size, stored for one value type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheUsage {
+    pub num_entries: usize,
+    pub size_bytes: usize,
+}
+
 /// Low-level pluggable cache backend.
 ///
 /// Implementations store entries keyed by [`InternalCacheKey`] and return
@@ -122,6 +130,14 @@ pub trait CacheBackend: Send + Sync + std::fmt::Debug {
     /// Total weighted size in bytes of all stored entries (may flush pending operations).
     async fn size_bytes(&self) -> usize;
 
+    /// Entry counts and sizes keyed by [`InternalCacheKey::type_name`]
+    /// (may flush pending operations).
+    ///
+    /// Backends that cannot break down their usage should return an empty map.
+    async fn usage_by_type(&self) -> HashMap<&'static str, CacheUsage> {
+        HashMap::new()
+    }
+
     /// Approximate number of entries, callable from synchronous contexts.
     /// Backends that cannot provide this cheaply should return 0.
     fn approx_num_entries(&self) -> usize {
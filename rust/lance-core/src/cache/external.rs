@@ -0,0 +1,412 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Cache backend that shares entries through an external store.
+//!
+//! [`ExternalCacheBackend`] layers a byte-oriented [`ExternalCacheStore`]
+//! (e.g. Redis, memcached, or a local mmap file) beneath an in-memory
+//! [`CacheBackend`].  Entries whose [`CacheKey`](super::CacheKey) provides a
+//! codec are serialized into the external store on insert and read back on a
+//! local miss, so a fleet of stateless query nodes can share warmed manifests
+//! and index metadata.  Entries without a codec stay in the local tier only.
+//! In `lance`, install it with `Session::with_metadata_cache_backend` or
+//! `Session::with_index_cache_backend`.
+//!
+//! The external store is best effort: errors talking to it are logged and
+//! treated as misses, so an unavailable store never fails a query.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Future;
+
+use crate::Result;
+
+use super::CacheCodec;
+use super::backend::{CacheBackend, CacheEntry, CacheUsage, InternalCacheKey};
+
+/// Byte-level key-value store shared by many processes.
+///
+/// This is the trait deployments implement to plug in a shared cache.  Keys
+/// are opaque strings built by [`ExternalCacheBackend`]; values are the
+/// serialized entries.  The store is free to evict entries at any time.
+#[async_trait]
+pub trait ExternalCacheStore: Send + Sync + std::fmt::Debug {
+    /// Fetch the value stored under `key`, if any.
+    async fn get(&self, key: &str) -> Result<Option<Bytes>>;
+
+    /// Store `value` under `key`, replacing any existing value.
+    async fn put(&self, key: &str, value: Bytes) -> Result<()>;
+
+    /// Remove all values whose key starts with `prefix`.
+    async fn delete_prefix(&self, prefix: &str) -> Result<()>;
+}
+
+/// A [`CacheBackend`] that shares serializable entries through an
+/// [`ExternalCacheStore`].
+///
+/// Lookups check the local backend first, then the external store.  Entries
+/// found externally are promoted into the local backend.  Inserts write to
+/// both tiers.  Size and entry counts only describe the local tier.
+///
+/// The serialized format of cache entries is not stable across releases, so
+/// external keys are namespaced by the Lance version that wrote them.
+pub struct ExternalCacheBackend {
+    local: Arc<dyn CacheBackend>,
+    store: Arc<dyn ExternalCacheStore>,
+    namespace: String,
+}
+
+impl std::fmt::Debug for ExternalCacheBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalCacheBackend")
+            .field("local", &self.local)
+            .field("store", &self.store)
+            .field("namespace", &self.namespace)
+            .finish()
+    }
+}
+
+impl ExternalCacheBackend {
+    pub fn new(local: Arc<dyn CacheBackend>, store: Arc<dyn ExternalCacheStore>) -> Self {
+        Self {
+            local,
+            store,
+            namespace: format!("lance/{}/", env!("CARGO_PKG_VERSION")),
+        }
+    }
+
+    /// Prepend `namespace` to every external key, e.g. to keep the entries of
+    /// several deployments sharing one store apart.
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = format!("{}{}", namespace, self.namespace);
+        self
+    }
+
+    fn external_key(&self, key: &InternalCacheKey) -> String {
+        // The prefix comes first so that `invalidate_prefix` maps onto a
+        // prefix delete in the store.
+        format!(
+            "{}{}\u{0}{}\u{0}{}",
+            self.namespace,
+            key.prefix(),
+            key.type_name(),
+            key.key()
+        )
+    }
+
+    async fn get_external(&self, key: &InternalCacheKey, codec: CacheCodec) -> Option<CacheEntry> {
+        let external_key = self.external_key(key);
+        let data = match self.store.get(&external_key).await {
+            Ok(data) => data?,
+            Err(err) => {
+                log::warn!(
+                    "Failed to read {} from external cache: {}",
+                    external_key,
+                    err
+                );
+                return None;
+            }
+        };
+        match codec.deserialize(&data) {
+            Ok(entry) => {
+                self.local
+                    .insert(key, entry.clone(), data.len(), Some(codec))
+                    .await;
+                Some(entry)
+            }
+            Err(err) => {
+                log::warn!(
+                    "Failed to deserialize {} from external cache: {}",
+                    external_key,
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    async fn put_external(&self, key: &InternalCacheKey, entry: &CacheEntry, codec: CacheCodec) {
+        let external_key = self.external_key(key);
+        let mut buf = Vec::new();
+        if let Err(err) = codec.serialize(entry, &mut buf) {
+            log::warn!(
+                "Failed to serialize {} for external cache: {}",
+                external_key,
+                err
+            );
+            return;
+        }
+        if let Err(err) = self.store.put(&external_key, Bytes::from(buf)).await {
+            log::warn!(
+                "Failed to write {} to external cache: {}",
+                external_key,
+                err
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for ExternalCacheBackend {
+    async fn get(&self, key: &InternalCacheKey, codec: Option<CacheCodec>) -> Option<CacheEntry> {
+        if let Some(entry) = self.local.get(key, codec).await {
+            return Some(entry);
+        }
+        self.get_external(key, codec?).await
+    }
+
+    async fn insert(
+        &self,
+        key: &InternalCacheKey,
+        entry: CacheEntry,
+        size_bytes: usize,
+        codec: Option<CacheCodec>,
+    ) {
+        if let Some(codec) = codec {
+            self.put_external(key, &entry, codec).await;
+        }
+        self.local.insert(key, entry, size_bytes, codec).await;
+    }
+
+    async fn get_or_insert<'a>(
+        &self,
+        key: &InternalCacheKey,
+        loader: Pin<Box<dyn Future<Output = Result<(CacheEntry, usize)>> + Send + 'a>>,
+        codec: Option<CacheCodec>,
+    ) -> Result<(CacheEntry, bool)> {
+        let Some(codec) = codec else {
+            return self.local.get_or_insert(key, loader, None).await;
+        };
+
+        // Only a run of the original loader counts as a miss; an entry found
+        // in the external store was cached, just not locally.
+        let loaded = Arc::new(AtomicBool::new(false));
+        let loaded_clone = loaded.clone();
+        let external_key = self.external_key(key);
+        let store = self.store.clone();
+        let wrapped = async move {
+            match store.get(&external_key).await {
+                Ok(Some(data)) => match codec.deserialize(&data) {
+                    Ok(entry) => return Ok((entry, data.len())),
+                    Err(err) => log::warn!(
+                        "Failed to deserialize {} from external cache: {}",
+                        external_key,
+                        err
+                    ),
+                },
+                Ok(None) => {}
+                Err(err) => {
+                    log::warn!(
+                        "Failed to read {} from external cache: {}",
+                        external_key,
+                        err
+                    )
+                }
+            }
+            loaded_clone.store(true, Ordering::Relaxed);
+            loader.await
+        };
+        let (entry, was_cached) = self
+            .local
+            .get_or_insert(key, Box::pin(wrapped), Some(codec))
+            .await?;
+        let loaded = loaded.load(Ordering::Relaxed);
+        if loaded {
+            self.put_external(key, &entry, codec).await;
+        }
+        Ok((entry, was_cached || !loaded))
+    }
+
+    async fn invalidate_prefix(&self, prefix: &str) {
+        let external_prefix = format!("{}{}", self.namespace, prefix);
+        if let Err(err) = self.store.delete_prefix(&external_prefix).await {
+            log::warn!(
+                "Failed to invalidate {} in external cache: {}",
+                external_prefix,
+                err
+            );
+        }
+        self.local.invalidate_prefix(prefix).await;
+    }
+
+    /// Clears the local tier only.
+    ///
+    /// The external store is shared with other processes, so it is left
+    /// untouched; use [`invalidate_prefix`](Self::invalidate_prefix) to remove
+    /// shared entries.
+    async fn clear(&self) {
+        self.local.clear().await;
+    }
+
+    async fn num_entries(&self) -> usize {
+        self.local.num_entries().await
+    }
+
+    async fn size_bytes(&self) -> usize {
+        self.local.size_bytes().await
+    }
+
+    async fn usage_by_type(&self) -> HashMap<&'static str, CacheUsage> {
+        self.local.usage_by_type().await
+    }
+
+    fn approx_num_entries(&self) -> usize {
+        self.local.approx_num_entries()
+    }
+
+    fn approx_size_bytes(&self) -> usize {
+        self.local.approx_size_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::sync::atomic::AtomicUsize;
+
+    use tokio::sync::Mutex;
+
+    use super::*;
+    use crate::Error;
+    use crate::cache::{
+        CacheCodecImpl, CacheKey, Context, DeepSizeOf, LanceCache, MokaCacheBackend,
+    };
+
+    #[derive(Debug, Default)]
+    struct MapStore {
+        map: Mutex<HashMap<String, Bytes>>,
+        fail: AtomicBool,
+    }
+
+    #[async_trait]
+    impl ExternalCacheStore for MapStore {
+        async fn get(&self, key: &str) -> Result<Option<Bytes>> {
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(Error::io("store unavailable"));
+            }
+            Ok(self.map.lock().await.get(key).cloned())
+        }
+
+        async fn put(&self, key: &str, value: Bytes) -> Result<()> {
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(Error::io("store unavailable"));
+            }
+            self.map.lock().await.insert(key.to_string(), value);
+            Ok(())
+        }
+
+        async fn delete_prefix(&self, prefix: &str) -> Result<()> {
+            self.map.lock().await.retain(|k, _| !k.starts_with(prefix));
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Value(u64);
+
+    impl DeepSizeOf for Value {
+        fn deep_size_of_children(&self, _: &mut Context) -> usize {
+            0
+        }
+    }
+
+    impl CacheCodecImpl for Value {
+        fn serialize(&self, writer: &mut dyn std::io::Write) -> Result<()> {
+            writer.write_all(&self.0.to_le_bytes())?;
+            Ok(())
+        }
+
+        fn deserialize(data: &Bytes) -> Result<Self> {
+            let bytes: [u8; 8] = data
+                .as_ref()
+                .try_into()
+                .map_err(|_| Error::invalid_input("expected 8 bytes"))?;
+            Ok(Self(u64::from_le_bytes(bytes)))
+        }
+    }
+
+    struct ValueKey(&'static str);
+
+    impl CacheKey for ValueKey {
+        type ValueType = Value;
+        fn key(&self) -> Cow<'_, str> {
+            Cow::Borrowed(self.0)
+        }
+        fn type_name() -> &'static str {
+            "Value"
+        }
+        fn codec() -> Option<CacheCodec> {
+            Some(CacheCodec::from_impl::<Value>())
+        }
+    }
+
+    fn node(store: &Arc<MapStore>) -> LanceCache {
+        LanceCache::with_backend(Arc::new(ExternalCacheBackend::new(
+            Arc::new(MokaCacheBackend::with_capacity(1024 * 1024)),
+            store.clone(),
+        )))
+        .with_key_prefix("s3://bucket/dataset")
+    }
+
+    #[tokio::test]
+    async fn test_shared_between_nodes() {
+        let store = Arc::new(MapStore::default());
+        let first = node(&store);
+        let second = node(&store);
+
+        first
+            .insert_with_key(&ValueKey("a"), Arc::new(Value(42)))
+            .await;
+        assert_eq!(store.map.lock().await.len(), 1);
+
+        // The second node has never seen the entry but finds it in the store
+        let value = second.get_with_key(&ValueKey("a")).await.unwrap();
+        assert_eq!(*value, Value(42));
+
+        // get_or_insert on a third node doesn't run the loader either
+        let third = node(&store);
+        let loads = AtomicUsize::new(0);
+        let value = third
+            .get_or_insert_with_key(ValueKey("a"), || async {
+                loads.fetch_add(1, Ordering::Relaxed);
+                Ok(Value(0))
+            })
+            .await
+            .unwrap();
+        assert_eq!(*value, Value(42));
+        assert_eq!(loads.load(Ordering::Relaxed), 0);
+
+        // Loaded entries are published for other nodes
+        third
+            .get_or_insert_with_key(ValueKey("b"), || async { Ok(Value(7)) })
+            .await
+            .unwrap();
+        assert_eq!(
+            *second.get_with_key(&ValueKey("b")).await.unwrap(),
+            Value(7)
+        );
+
+        first.invalidate_prefix("").await;
+        assert!(store.map.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_store_errors_are_misses() {
+        let store = Arc::new(MapStore::default());
+        store.fail.store(true, Ordering::Relaxed);
+        let cache = node(&store);
+
+        let value = cache
+            .get_or_insert_with_key(ValueKey("a"), || async { Ok(Value(1)) })
+            .await
+            .unwrap();
+        assert_eq!(*value, Value(1));
+        // Still cached locally
+        assert!(cache.get_with_key(&ValueKey("a")).await.is_some());
+    }
+}
@@ -35,6 +35,10 @@
 //! [`CacheEntry`] values — the typed wrapping is handled by [`LanceCache`].
 //! See the [`backend`] module for details.
 //!
+//! To share serializable entries between processes, wrap a local backend in
+//! an [`ExternalCacheBackend`] and implement [`ExternalCacheStore`] on top of
+//! the shared store.
+//!
 //! ## Serialization flow
 //!
 //! When a [`CacheKey`] provides a codec via [`CacheKey::codec`]:
@@ -47,15 +51,18 @@
 
 pub mod backend;
 pub mod codec;
+mod external;
 mod moka;
 
-pub use backend::{CacheBackend, CacheEntry, InternalCacheKey};
+pub use backend::{CacheBackend, CacheEntry, CacheUsage, InternalCacheKey};
 pub use codec::{CacheCodec, CacheCodecImpl};
+pub use external::{ExternalCacheBackend, ExternalCacheStore};
 pub use moka::MokaCacheBackend;
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::{
-    Arc,
+    Arc, RwLock,
     atomic::{AtomicU64, Ordering},
 };
 
@@ -147,6 +154,69 @@ fn build_key(prefix: &Arc<str>, key: &str, type_name: &'static str) -> InternalC
     InternalCacheKey::new(prefix.clone(), Arc::from(key), type_name)
 }
 
+#[derive(Debug, Default)]
+struct HitCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl HitCounters {
+    fn load(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    fn reset(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Hit and miss counters, in total and per value type.
+///
+/// Shared by all caches derived from the same [`LanceCache`] (e.g. through
+/// [`LanceCache::with_key_prefix`] or [`WeakLanceCache::from`]).
+#[derive(Debug, Default)]
+struct CacheCounters {
+    total: HitCounters,
+    by_type: RwLock<HashMap<&'static str, Arc<HitCounters>>>,
+}
+
+impl CacheCounters {
+    fn for_type(&self, type_name: &'static str) -> Arc<HitCounters> {
+        if let Some(counters) = self.by_type.read().unwrap().get(type_name) {
+            return counters.clone();
+        }
+        self.by_type
+            .write()
+            .unwrap()
+            .entry(type_name)
+            .or_default()
+            .clone()
+    }
+
+    fn record_hit(&self, type_name: &'static str) {
+        self.total.hits.fetch_add(1, Ordering::Relaxed);
+        self.for_type(type_name)
+            .hits
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_miss(&self, type_name: &'static str) {
+        self.total.misses.fetch_add(1, Ordering::Relaxed);
+        self.for_type(type_name)
+            .misses
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        self.total.reset();
+        self.by_type.write().unwrap().clear();
+    }
+}
+
 // ---------------------------------------------------------------------------
 // LanceCache — typed wrapper around dyn CacheBackend
 // ---------------------------------------------------------------------------
@@ -159,8 +229,7 @@ fn build_key(prefix: &Arc<str>, key: &str, type_name: &'static str) -> InternalC
 pub struct LanceCache {
     cache: Arc<dyn CacheBackend>,
     prefix: Arc<str>,
-    hits: Arc<AtomicU64>,
-    misses: Arc<AtomicU64>,
+    counters: Arc<CacheCounters>,
 }
 
 impl std::fmt::Debug for LanceCache {
@@ -182,18 +251,33 @@ impl LanceCache {
         Self {
             cache: Arc::new(MokaCacheBackend::with_capacity(capacity)),
             prefix: Arc::from(""),
-            hits: Arc::new(AtomicU64::new(0)),
-            misses: Arc::new(AtomicU64::new(0)),
+            counters: Default::default(),
         }
     }
 
+    /// Create a cache where some components have their own capacity budget.
+    ///
+    /// `type_capacities` maps a [`CacheKey::type_name`] to the number of bytes
+    /// entries of that type may use. Entries of those types do not count towards
+    /// (and are not evicted by) the shared `capacity`, which bounds everything
+    /// else.
+    pub fn with_type_capacities(
+        capacity: usize,
+        type_capacities: impl IntoIterator<Item = (&'static str, usize)>,
+    ) -> Self {
+        let backend = type_capacities.into_iter().fold(
+            MokaCacheBackend::with_capacity(capacity),
+            |backend, (type_name, capacity)| backend.with_type_capacity(type_name, capacity),
+        );
+        Self::with_backend(Arc::new(backend))
+    }
+
     /// Create a cache backed by a custom [`CacheBackend`].
     pub fn with_backend(backend: Arc<dyn CacheBackend>) -> Self {
         Self {
             cache: backend,
             prefix: Arc::from(""),
-            hits: Arc::new(AtomicU64::new(0)),
-            misses: Arc::new(AtomicU64::new(0)),
+            counters: Default::default(),
         }
     }
 
@@ -201,8 +285,7 @@ impl LanceCache {
         Self {
             cache: Arc::new(MokaCacheBackend::no_cache()),
             prefix: Arc::from(""),
-            hits: Arc::new(AtomicU64::new(0)),
-            misses: Arc::new(AtomicU64::new(0)),
+            counters: Default::default(),
         }
     }
 
@@ -212,8 +295,7 @@ impl LanceCache {
         Self {
             cache: backend,
             prefix: Arc::from(prefix),
-            hits: Arc::new(AtomicU64::new(0)),
-            misses: Arc::new(AtomicU64::new(0)),
+            counters: Default::default(),
         }
     }
 
@@ -222,8 +304,7 @@ impl LanceCache {
         Self {
             cache: self.cache.clone(),
             prefix: Arc::from(format!("{}{}/", self.prefix, prefix)),
-            hits: self.hits.clone(),
-            misses: self.misses.clone(),
+            counters: self.counters.clone(),
         }
     }
 
@@ -269,19 +350,19 @@ impl LanceCache {
         if let Some(entry) = self.cache.get(&cache_key, codec).await {
             match entry.downcast::<T>() {
                 Ok(val) => {
-                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    self.counters.record_hit(type_name);
                     Some(val)
                 }
                 Err(_) => {
                     // Type mismatch: the backend returned a different concrete
                     // type than expected (e.g. a disk cache may store
                     // intermediate state). Treat as a miss.
-                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    self.counters.record_miss(type_name);
                     None
                 }
             }
         } else {
-            self.misses.fetch_add(1, Ordering::Relaxed);
+            self.counters.record_miss(type_name);
             None
         }
     }
@@ -290,17 +371,45 @@ impl LanceCache {
 
     pub async fn stats(&self) -> CacheStats {
         CacheStats {
-            hits: self.hits.load(Ordering::Relaxed),
-            misses: self.misses.load(Ordering::Relaxed),
+            hits: self.counters.total.hits.load(Ordering::Relaxed),
+            misses: self.counters.total.misses.load(Ordering::Relaxed),
             num_entries: self.cache.num_entries().await,
             size_bytes: self.cache.size_bytes().await,
         }
     }
 
+    /// Statistics broken down by component, keyed by [`CacheKey::type_name`]
+    /// (e.g. `"Manifest"`, `"Vec<IndexMetadata>"`).
+    ///
+    /// Entry counts and sizes are only reported if the backend implements
+    /// [`CacheBackend::usage_by_type`].
+    pub async fn stats_by_type(&self) -> HashMap<&'static str, CacheStats> {
+        let mut stats = self
+            .cache
+            .usage_by_type()
+            .await
+            .into_iter()
+            .map(|(type_name, usage)| {
+                let stats = CacheStats {
+                    num_entries: usage.num_entries,
+                    size_bytes: usage.size_bytes,
+                    ..Default::default()
+                };
+                (type_name, stats)
+            })
+            .collect::<HashMap<_, _>>();
+        for (type_name, counters) in self.counters.by_type.read().unwrap().iter() {
+            let (hits, misses) = counters.load();
+            let entry = stats.entry(type_name).or_default();
+            entry.hits = hits;
+            entry.misses = misses;
+        }
+        stats
+    }
+
     pub async fn clear(&self) {
         self.cache.clear().await;
-        self.hits.store(0, Ordering::Relaxed);
-        self.misses.store(0, Ordering::Relaxed);
+        self.counters.reset();
     }
 
     // -- CacheKey-based methods -----------------------------------------------
@@ -351,9 +460,9 @@ impl LanceCache {
             .await?;
 
         if was_cached {
-            self.hits.fetch_add(1, Ordering::Relaxed);
+            self.counters.record_hit(K::type_name());
         } else {
-            self.misses.fetch_add(1, Ordering::Relaxed);
+            self.counters.record_miss(K::type_name());
         }
 
         Ok(entry.downcast::<K::ValueType>().unwrap())
@@ -392,8 +501,7 @@ impl LanceCache {
 pub struct WeakLanceCache {
     inner: std::sync::Weak<dyn CacheBackend>,
     prefix: Arc<str>,
-    hits: Arc<AtomicU64>,
-    misses: Arc<AtomicU64>,
+    counters: Arc<CacheCounters>,
 }
 
 impl WeakLanceCache {
@@ -401,8 +509,7 @@ impl WeakLanceCache {
         Self {
             inner: Arc::downgrade(&cache.cache),
             prefix: cache.prefix.clone(),
-            hits: cache.hits.clone(),
-            misses: cache.misses.clone(),
+            counters: cache.counters.clone(),
         }
     }
 
@@ -410,8 +517,7 @@ impl WeakLanceCache {
         Self {
             inner: self.inner.clone(),
             prefix: Arc::from(format!("{}{}/", self.prefix, prefix)),
-            hits: self.hits.clone(),
-            misses: self.misses.clone(),
+            counters: self.counters.clone(),
         }
     }
 
@@ -428,10 +534,10 @@ impl WeakLanceCache {
         let cache = self.inner.upgrade()?;
         let key = build_key(&self.prefix, &cache_key.key(), K::type_name());
         if let Some(entry) = cache.get(&key, K::codec()).await {
-            self.hits.fetch_add(1, Ordering::Relaxed);
+            self.counters.record_hit(K::type_name());
             Some(entry.downcast::<K::ValueType>().unwrap())
         } else {
-            self.misses.fetch_add(1, Ordering::Relaxed);
+            self.counters.record_miss(K::type_name());
             None
         }
     }
@@ -476,9 +582,9 @@ impl WeakLanceCache {
             });
             let (entry, was_cached) = cache.get_or_insert(&key, typed_loader, K::codec()).await?;
             if was_cached {
-                self.hits.fetch_add(1, Ordering::Relaxed);
+                self.counters.record_hit(K::type_name());
             } else {
-                self.misses.fetch_add(1, Ordering::Relaxed);
+                self.counters.record_miss(K::type_name());
             }
             Ok(entry.downcast::<K::ValueType>().unwrap())
         } else {
@@ -524,7 +630,7 @@ impl WeakLanceCache {
 // CacheStats
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct CacheStats {
     /// Number of times `get`, `get_unsized`, or `get_or_insert` found an item in the cache.
     pub hits: u64,
@@ -718,6 +824,68 @@ mod tests {
         assert_eq!(base.stats().await.hits, 1);
     }
 
+    #[tokio::test]
+    async fn test_cache_stats_by_type() {
+        let cache = LanceCache::with_capacity(10000);
+        let dataset = cache.with_key_prefix("dataset");
+        let ints = TestKey::<Vec<i32>>::new("ints");
+        let bytes = TestKey::<Vec<u8>>::new("bytes");
+
+        dataset
+            .insert_with_key(&ints, Arc::new(vec![1, 2, 3]))
+            .await;
+        dataset
+            .insert_with_key(&bytes, Arc::new(vec![1; 100]))
+            .await;
+        assert!(dataset.get_with_key(&ints).await.is_some());
+        assert!(dataset.get_with_key(&ints).await.is_some());
+        assert!(cache.get_with_key(&bytes).await.is_none());
+
+        let stats = cache.stats_by_type().await;
+        assert_eq!(stats.len(), 2);
+        let int_stats = &stats[TestKey::<Vec<i32>>::type_name()];
+        assert_eq!((int_stats.hits, int_stats.misses), (2, 0));
+        assert_eq!(int_stats.num_entries, 1);
+        let byte_stats = &stats[TestKey::<Vec<u8>>::type_name()];
+        assert_eq!((byte_stats.hits, byte_stats.misses), (0, 1));
+        assert_eq!(byte_stats.num_entries, 1);
+        assert!(byte_stats.size_bytes > int_stats.size_bytes);
+        assert_eq!(
+            int_stats.size_bytes + byte_stats.size_bytes,
+            cache.size_bytes().await
+        );
+
+        cache.clear().await;
+        assert!(cache.stats_by_type().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cache_type_capacity() {
+        let item_size = cache_entry_size(&vec![0u8; 100]);
+        let cache = LanceCache::with_type_capacities(
+            100 * item_size,
+            [(TestKey::<Vec<u8>>::type_name(), 5 * item_size)],
+        );
+
+        for i in 0..50 {
+            let key = format!("key_{}", i);
+            cache
+                .insert_with_key(&TestKey::<Vec<u8>>::new(&key), Arc::new(vec![0; 100]))
+                .await;
+            cache
+                .insert_with_key(&TestKey::<Vec<i8>>::new(&key), Arc::new(vec![0; 100]))
+                .await;
+        }
+
+        // Each type is bounded by its own budget
+        let stats = cache.stats_by_type().await;
+        assert!(stats[TestKey::<Vec<u8>>::type_name()].size_bytes <= 5 * item_size);
+        assert_eq!(stats[TestKey::<Vec<i8>>::type_name()].num_entries, 50);
+
+        cache.invalidate_prefix("").await;
+        assert_eq!(cache.size().await, 0);
+    }
+
     #[tokio::test]
     async fn test_cache_get_or_insert() {
         let cache = LanceCache::with_capacity(1000);
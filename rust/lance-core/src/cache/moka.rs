@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -11,7 +12,7 @@ use futures::Future;
 use crate::Result;
 
 use super::CacheCodec;
-use super::backend::{CacheBackend, CacheEntry, InternalCacheKey};
+use super::backend::{CacheBackend, CacheEntry, CacheUsage, InternalCacheKey};
 
 /// Internal record stored in the moka cache.
 #[derive(Clone, Debug)]
@@ -20,35 +21,76 @@ struct MokaCacheEntry {
     size_bytes: usize,
 }
 
+type MokaCache = moka::future::Cache<InternalCacheKey, MokaCacheEntry>;
+
+fn build_cache(capacity: usize) -> MokaCache {
+    moka::future::Cache::builder()
+        .max_capacity(capacity as u64)
+        .weigher(|_, v: &MokaCacheEntry| v.size_bytes.try_into().unwrap_or(u32::MAX))
+        .support_invalidation_closures()
+        .build()
+}
+
 /// Default [`CacheBackend`] backed by a [moka](https://crates.io/crates/moka) cache.
 ///
 /// Provides weighted-capacity eviction and concurrent-load deduplication
 /// via moka's built-in `optionally_get_with`.
+///
+/// Value types can be given their own capacity with
+/// [`with_type_capacity`](Self::with_type_capacity), so that e.g. large index
+/// pages cannot evict manifests.
 pub struct MokaCacheBackend {
-    cache: moka::future::Cache<InternalCacheKey, MokaCacheEntry>,
+    cache: MokaCache,
+    /// Caches for value types with their own capacity, keyed by type name.
+    type_caches: HashMap<&'static str, MokaCache>,
 }
 
 impl std::fmt::Debug for MokaCacheBackend {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MokaCacheBackend")
-            .field("entry_count", &self.cache.entry_count())
+            .field(
+                "entry_count",
+                &self.caches().map(|c| c.entry_count()).sum::<u64>(),
+            )
             .finish()
     }
 }
 
 impl MokaCacheBackend {
     pub fn with_capacity(capacity: usize) -> Self {
-        let cache = moka::future::Cache::builder()
-            .max_capacity(capacity as u64)
-            .weigher(|_, v: &MokaCacheEntry| v.size_bytes.try_into().unwrap_or(u32::MAX))
-            .support_invalidation_closures()
-            .build();
-        Self { cache }
+        Self {
+            cache: build_cache(capacity),
+            type_caches: HashMap::new(),
+        }
     }
 
     pub fn no_cache() -> Self {
         Self {
             cache: moka::future::Cache::new(0),
+            type_caches: HashMap::new(),
+        }
+    }
+
+    /// Give entries with the given type name their own capacity, in bytes.
+    ///
+    /// These entries are evicted independently of, and do not count towards,
+    /// the capacity shared by all other entries.
+    pub fn with_type_capacity(mut self, type_name: &'static str, capacity: usize) -> Self {
+        self.type_caches.insert(type_name, build_cache(capacity));
+        self
+    }
+
+    fn cache_for(&self, key: &InternalCacheKey) -> &MokaCache {
+        self.type_caches.get(key.type_name()).unwrap_or(&self.cache)
+    }
+
+    fn caches(&self) -> impl Iterator<Item = &MokaCache> {
+        std::iter::once(&self.cache).chain(self.type_caches.values())
+    }
+
+    async fn run_pending_tasks(&self) {
+        for cache in self.caches() {
+            cache.run_pending_tasks().await;
         }
     }
 }
@@ -56,7 +98,7 @@ impl MokaCacheBackend {
 #[async_trait]
 impl CacheBackend for MokaCacheBackend {
     async fn get(&self, key: &InternalCacheKey, _codec: Option<CacheCodec>) -> Option<CacheEntry> {
-        self.cache.get(key).await.map(|r| r.entry)
+        self.cache_for(key).get(key).await.map(|r| r.entry)
     }
 
     async fn insert(
@@ -66,7 +108,7 @@ impl CacheBackend for MokaCacheBackend {
         size_bytes: usize,
         _codec: Option<CacheCodec>,
     ) {
-        self.cache
+        self.cache_for(key)
             .insert(key.clone(), MokaCacheEntry { entry, size_bytes })
             .await;
     }
@@ -97,7 +139,11 @@ impl CacheBackend for MokaCacheBackend {
         };
 
         let owned_key = key.clone();
-        match self.cache.optionally_get_with(owned_key, init).await {
+        match self
+            .cache_for(key)
+            .optionally_get_with(owned_key, init)
+            .await
+        {
             Some(record) => {
                 let was_cached = !was_miss.load(Ordering::Relaxed);
                 Ok((record.entry, was_cached))
@@ -112,35 +158,53 @@ impl CacheBackend for MokaCacheBackend {
     }
 
     async fn invalidate_prefix(&self, prefix: &str) {
-        let prefix = prefix.to_owned();
-        self.cache
-            .invalidate_entries_if(move |key, _value| key.starts_with(&prefix))
-            .expect("Cache configured correctly");
+        for cache in self.caches() {
+            let prefix = prefix.to_owned();
+            cache
+                .invalidate_entries_if(move |key, _value| key.starts_with(&prefix))
+                .expect("Cache configured correctly");
+        }
     }
 
     async fn clear(&self) {
-        self.cache.invalidate_all();
-        self.cache.run_pending_tasks().await;
+        for cache in self.caches() {
+            cache.invalidate_all();
+        }
+        self.run_pending_tasks().await;
     }
 
     async fn num_entries(&self) -> usize {
-        self.cache.run_pending_tasks().await;
-        self.cache.entry_count() as usize
+        self.run_pending_tasks().await;
+        self.caches().map(|c| c.entry_count() as usize).sum()
     }
 
     async fn size_bytes(&self) -> usize {
-        self.cache.run_pending_tasks().await;
-        self.cache.weighted_size() as usize
+        self.run_pending_tasks().await;
+        self.caches().map(|c| c.weighted_size() as usize).sum()
+    }
+
+    async fn usage_by_type(&self) -> HashMap<&'static str, CacheUsage> {
+        self.run_pending_tasks().await;
+        let mut usage = HashMap::<&'static str, CacheUsage>::new();
+        for (key, value) in self.caches().flat_map(|c| c.iter()) {
+            let entry = usage.entry(key.type_name()).or_default();
+            entry.num_entries += 1;
+            entry.size_bytes += value.size_bytes;
+        }
+        usage
     }
 
     fn approx_num_entries(&self) -> usize {
-        self.cache.entry_count() as usize
+        self.caches().map(|c| c.entry_count() as usize).sum()
     }
 
     fn approx_size_bytes(&self) -> usize {
         // Iterate rather than using `weighted_size()` because moka's
         // weighted_size can be stale without `run_pending_tasks()`, which
         // is async and can't be called from this synchronous context.
-        self.cache.iter().map(|(_, v)| v.size_bytes).sum()
+        self.caches()
+            .flat_map(|c| c.iter())
+            .map(|(_, v)| v.size_bytes)
+            .sum()
     }
 }
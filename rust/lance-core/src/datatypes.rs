@@ -14,6 +14,7 @@ use lance_arrow::bfloat16::{BFLOAT16_EXT_NAME, is_bfloat16_field};
 use lance_arrow::{ARROW_EXT_META_KEY, ARROW_EXT_NAME_KEY};
 
 mod field;
+mod metadata;
 mod schema;
 
 use crate::{Error, Result};
@@ -22,6 +23,10 @@ pub use field::{
     LANCE_UNENFORCED_PRIMARY_KEY, LANCE_UNENFORCED_PRIMARY_KEY_POSITION, NullabilityComparison,
     OnTypeMismatch, SchemaCompareOptions,
 };
+pub use metadata::{
+    ColumnStatistics, EmbeddingModel, LANCE_COLUMN_STATISTICS_KEY, LANCE_DESCRIPTION_KEY,
+    LANCE_EMBEDDING_MODEL_KEY, LANCE_SEMANTIC_TYPE_KEY, SemanticType, WellKnownMetadata,
+};
 pub use schema::{
     BlobHandling, FieldRef, OnMissing, Projectable, Projection, Schema,
     escape_field_path_for_project, format_field_path, parse_field_path,
@@ -211,6 +216,14 @@ impl TryFrom<&DataType> for LogicalType {
                     false
                 )
             }
+            DataType::RunEndEncoded(run_ends, values) => {
+                // The value type goes last because it may contain `:` itself
+                format!(
+                    "run_end_encoded:{}:{}",
+                    Self::try_from(run_ends.data_type())?.0,
+                    Self::try_from(values.data_type())?.0
+                )
+            }
             DataType::List(elem) => match elem.data_type() {
                 DataType::Struct(_) => "list.struct".to_string(),
                 _ => "list".to_string(),
@@ -354,6 +367,21 @@ impl TryFrom<&LogicalType> for DataType {
                         Ok(Dictionary(Box::new(index_type), Box::new(value_type)))
                     }
                 }
+                "run_end_encoded" => {
+                    if splits.len() < 3 {
+                        Err(Error::schema(format!(
+                            "Unsupported run-end encoded type: {}",
+                            lt
+                        )))
+                    } else {
+                        let run_end_type: Self = (&LogicalType::from(splits[1])).try_into()?;
+                        let value_type: Self = (&LogicalType(splits[2..].join(":"))).try_into()?;
+                        Ok(RunEndEncoded(
+                            Arc::new(ArrowField::new("run_ends", run_end_type, false)),
+                            Arc::new(ArrowField::new("values", value_type, true)),
+                        ))
+                    }
+                }
                 "decimal" => {
                     if splits.len() != 4 {
                         Err(Error::schema(format!("Unsupported decimal type: {}", lt)))
@@ -21,11 +21,12 @@ use arrow_array::{
 use arrow_schema::{DataType, Field as ArrowField};
 use lance_arrow::{
     ARROW_EXT_NAME_KEY, BLOB_META_KEY, BLOB_V2_EXT_NAME, DataTypeExt,
+    bfloat16::is_bfloat16_field,
     json::{is_arrow_json_field, is_json_field},
 };
 
 use super::{
-    Dictionary, LogicalType, Projection,
+    Dictionary, LogicalType, Projection, WellKnownMetadata,
     schema::{compare_fields, explain_fields_difference},
 };
 use crate::{
@@ -58,6 +59,38 @@ pub const LANCE_UNENFORCED_CLUSTERING_KEY_POSITION: &str =
 /// The value should be non-negative i32 value. Any negative value will be seen as -1.
 pub const LANCE_FIELD_ID_KEY: &str = "lance:field_id";
 
+/// Lance field metadata recording the name and nullability of the item field of a
+/// fixed size list, if they differ from the defaults (`item`, nullable).
+///
+/// The logical type of a fixed size list only records the item type.  Some layouts,
+/// e.g. GeoArrow interleaved coordinates (`FixedSizeList<xy: Float64 not null>`), rely
+/// on the item field so it is stored here and restored by [`Field::data_type`].  The
+/// key never appears in the metadata of the Arrow field.
+pub const LANCE_FIXED_SIZE_LIST_ITEM_KEY: &str = "lance-schema:fixed-size-list-item";
+
+/// The metadata value recording a non-default fixed size list item field
+fn fixed_size_list_item_metadata(data_type: &DataType) -> Option<String> {
+    let DataType::FixedSizeList(item, _) = data_type else {
+        return None;
+    };
+    if matches!(item.data_type(), DataType::Struct(_)) || is_bfloat16_field(item) {
+        return None;
+    }
+    if item.name() == "item" && item.is_nullable() {
+        return None;
+    }
+    Some(serde_json::json!({"name": item.name(), "nullable": item.is_nullable()}).to_string())
+}
+
+/// Parses the value written by [`fixed_size_list_item_metadata`]
+fn parse_fixed_size_list_item_metadata(value: &str) -> Option<(String, bool)> {
+    let value = serde_json::from_str::<serde_json::Value>(value).ok()?;
+    Some((
+        value.get("name")?.as_str()?.to_string(),
+        value.get("nullable")?.as_bool()?,
+    ))
+}
+
 fn has_blob_v2_extension(field: &ArrowField) -> bool {
     field
         .metadata()
@@ -187,7 +220,24 @@ impl Field {
             lt if lt.is_map() => {
                 DataType::Map(Arc::new(ArrowField::from(&self.children[0])), false)
             }
-            lt => DataType::try_from(lt).unwrap(),
+            lt => {
+                let data_type = DataType::try_from(lt).unwrap();
+                if let DataType::FixedSizeList(item, size) = &data_type
+                    && let Some((name, nullable)) = self
+                        .metadata
+                        .get(LANCE_FIXED_SIZE_LIST_ITEM_KEY)
+                        .and_then(|value| parse_fixed_size_list_item_metadata(value))
+                {
+                    let item = item
+                        .as_ref()
+                        .clone()
+                        .with_name(name)
+                        .with_nullable(nullable);
+                    DataType::FixedSizeList(Arc::new(item), *size)
+                } else {
+                    data_type
+                }
+            }
         }
     }
 
@@ -1035,6 +1085,13 @@ impl Field {
         })
     }
 
+    /// The well-known entries of the field metadata, e.g. its description.
+    ///
+    /// Returns an error if a well-known entry is malformed.
+    pub fn well_known_metadata(&self) -> Result<WellKnownMetadata> {
+        WellKnownMetadata::from_metadata(&self.metadata)
+    }
+
     /// Return true if the field is a leaf field.
     ///
     /// A leaf field is a field that is not a struct or a list.
@@ -1144,6 +1201,14 @@ impl TryFrom<&ArrowField> for Field {
             .get(LANCE_UNENFORCED_CLUSTERING_KEY_POSITION)
             .and_then(|s| s.parse::<u32>().ok());
         let is_blob_v2 = has_blob_v2_extension(field);
+        match fixed_size_list_item_metadata(field.data_type()) {
+            Some(item_metadata) => {
+                metadata.insert(LANCE_FIXED_SIZE_LIST_ITEM_KEY.to_string(), item_metadata);
+            }
+            None => {
+                metadata.remove(LANCE_FIXED_SIZE_LIST_ITEM_KEY);
+            }
+        }
 
         if is_blob_v2 {
             metadata
@@ -1197,6 +1262,7 @@ impl From<&Field> for ArrowField {
     fn from(field: &Field) -> Self {
         let out = Self::new(&field.name, field.data_type(), field.nullable);
         let mut metadata = field.metadata.clone();
+        metadata.remove(LANCE_FIXED_SIZE_LIST_ITEM_KEY);
 
         if field.logical_type.is_blob() {
             metadata
@@ -1243,6 +1309,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fixed_size_list_item_field_round_trip() {
+        let data_type =
+            DataType::FixedSizeList(Arc::new(ArrowField::new("xy", DataType::Float64, false)), 2);
+        let arrow_field = ArrowField::new("point", data_type.clone(), true);
+        let field = Field::try_from(&arrow_field).unwrap();
+        assert_eq!(field.logical_type.0, "fixed_size_list:double:2");
+        assert_eq!(field.data_type(), data_type);
+        assert_eq!(ArrowField::from(&field), arrow_field);
+
+        // The default item field is not recorded
+        let data_type = DataType::FixedSizeList(
+            Arc::new(ArrowField::new("item", DataType::Float32, true)),
+            4,
+        );
+        let field = Field::new_arrow("vec", data_type.clone(), true).unwrap();
+        assert!(!field.metadata.contains_key(LANCE_FIXED_SIZE_LIST_ITEM_KEY));
+        assert_eq!(field.data_type(), data_type);
+    }
+
     #[test]
     fn arrow_field_to_field() {
         for (name, data_type) in [
@@ -1293,6 +1379,24 @@ mod tests {
                     10,
                 ),
             ),
+            (
+                "run_end_encoded:int16:string",
+                DataType::RunEndEncoded(
+                    Arc::new(ArrowField::new("run_ends", DataType::Int16, false)),
+                    Arc::new(ArrowField::new("values", DataType::Utf8, true)),
+                ),
+            ),
+            (
+                "run_end_encoded:int64:timestamp:s:America/New_York",
+                DataType::RunEndEncoded(
+                    Arc::new(ArrowField::new("run_ends", DataType::Int64, false)),
+                    Arc::new(ArrowField::new(
+                        "values",
+                        DataType::Timestamp(TimeUnit::Second, Some("America/New_York".into())),
+                        true,
+                    )),
+                ),
+            ),
         ] {
             let arrow_field = ArrowField::new(name, data_type.clone(), true);
             let field = Field::try_from(&arrow_field).unwrap();
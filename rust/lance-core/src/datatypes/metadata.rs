@@ -0,0 +1,404 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Well-known schema and field metadata
+//!
+//! Schema and field metadata are free-form string maps.  This module defines a
+//! small set of well-known keys so that tools describing columns agree on where
+//! and how the information is stored.  [`WellKnownMetadata`] parses and validates
+//! those keys and can produce the entries to write back.  Other keys are left
+//! untouched.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{Map, Value, json};
+
+use crate::{Error, Result};
+
+/// A human readable description of a schema or field.
+pub const LANCE_DESCRIPTION_KEY: &str = "lance-schema:description";
+
+/// What the values of a field represent, see [`SemanticType`].
+pub const LANCE_SEMANTIC_TYPE_KEY: &str = "lance-schema:semantic-type";
+
+/// The model that produced the values of an embedding field, see [`EmbeddingModel`].
+/// The value is a JSON object.
+pub const LANCE_EMBEDDING_MODEL_KEY: &str = "lance-schema:embedding-model";
+
+/// A snapshot of the statistics of a field, see [`ColumnStatistics`].
+/// The value is a JSON object.
+pub const LANCE_COLUMN_STATISTICS_KEY: &str = "lance-schema:column-statistics";
+
+/// What the values of a field represent
+///
+/// This is independent of the physical type, e.g. a string field may hold text,
+/// URLs or identifiers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SemanticType {
+    Text,
+    Identifier,
+    Category,
+    Url,
+    Image,
+    Audio,
+    Video,
+    Embedding,
+    Geometry,
+    /// An application specific type, must start with `x-`
+    Custom(String),
+}
+
+impl SemanticType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Text => "text",
+            Self::Identifier => "identifier",
+            Self::Category => "category",
+            Self::Url => "url",
+            Self::Image => "image",
+            Self::Audio => "audio",
+            Self::Video => "video",
+            Self::Embedding => "embedding",
+            Self::Geometry => "geometry",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl FromStr for SemanticType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "identifier" => Ok(Self::Identifier),
+            "category" => Ok(Self::Category),
+            "url" => Ok(Self::Url),
+            "image" => Ok(Self::Image),
+            "audio" => Ok(Self::Audio),
+            "video" => Ok(Self::Video),
+            "embedding" => Ok(Self::Embedding),
+            "geometry" => Ok(Self::Geometry),
+            custom if custom.len() > 2 && custom.starts_with("x-") => {
+                Ok(Self::Custom(custom.to_string()))
+            }
+            other => Err(Error::invalid_input(format!(
+                "Unknown semantic type '{other}', custom semantic types must start with 'x-'"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for SemanticType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The model that produced the values of an embedding field
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EmbeddingModel {
+    /// The name of the model, e.g. `text-embedding-3-small`
+    pub name: String,
+    /// The version of the model, if the name does not pin it
+    pub version: Option<String>,
+    /// The provider serving the model, e.g. `openai`
+    pub provider: Option<String>,
+    /// The number of dimensions of the embeddings
+    pub dimension: Option<u32>,
+}
+
+impl EmbeddingModel {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut value = Map::new();
+        value.insert("name".to_string(), json!(self.name));
+        if let Some(version) = &self.version {
+            value.insert("version".to_string(), json!(version));
+        }
+        if let Some(provider) = &self.provider {
+            value.insert("provider".to_string(), json!(provider));
+        }
+        if let Some(dimension) = self.dimension {
+            value.insert("dimension".to_string(), json!(dimension));
+        }
+        Value::Object(value)
+    }
+
+    fn from_json(value: &str) -> Result<Self> {
+        let value = parse_object(LANCE_EMBEDDING_MODEL_KEY, value)?;
+        let name = get_str(LANCE_EMBEDDING_MODEL_KEY, &value, "name")?.ok_or_else(|| {
+            Error::invalid_input(format!("{LANCE_EMBEDDING_MODEL_KEY} must have a name"))
+        })?;
+        if name.is_empty() {
+            return Err(Error::invalid_input(format!(
+                "{LANCE_EMBEDDING_MODEL_KEY} must have a non-empty name"
+            )));
+        }
+        let dimension = get_u64(LANCE_EMBEDDING_MODEL_KEY, &value, "dimension")?
+            .map(|dimension| {
+                u32::try_from(dimension).map_err(|_| {
+                    Error::invalid_input(format!(
+                        "{LANCE_EMBEDDING_MODEL_KEY} dimension {dimension} is too large"
+                    ))
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            name,
+            version: get_str(LANCE_EMBEDDING_MODEL_KEY, &value, "version")?,
+            provider: get_str(LANCE_EMBEDDING_MODEL_KEY, &value, "provider")?,
+            dimension,
+        })
+    }
+}
+
+/// A snapshot of the statistics of a field
+///
+/// The snapshot is taken at `dataset_version` and is not updated by later
+/// writes, readers should compare it with the current version to decide if it
+/// is still useful.  `min` and `max` are the display form of the values.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ColumnStatistics {
+    /// The dataset version the statistics were computed at
+    pub dataset_version: u64,
+    pub num_rows: u64,
+    pub null_count: u64,
+    pub distinct_count: Option<u64>,
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+impl ColumnStatistics {
+    fn to_json(&self) -> Value {
+        let mut value = Map::new();
+        value.insert("dataset_version".to_string(), json!(self.dataset_version));
+        value.insert("num_rows".to_string(), json!(self.num_rows));
+        value.insert("null_count".to_string(), json!(self.null_count));
+        if let Some(distinct_count) = self.distinct_count {
+            value.insert("distinct_count".to_string(), json!(distinct_count));
+        }
+        if let Some(min) = &self.min {
+            value.insert("min".to_string(), json!(min));
+        }
+        if let Some(max) = &self.max {
+            value.insert("max".to_string(), json!(max));
+        }
+        Value::Object(value)
+    }
+
+    fn from_json(value: &str) -> Result<Self> {
+        let key = LANCE_COLUMN_STATISTICS_KEY;
+        let value = parse_object(key, value)?;
+        let required = |name: &str| {
+            get_u64(key, &value, name)?
+                .ok_or_else(|| Error::invalid_input(format!("{key} must have {name}")))
+        };
+        let stats = Self {
+            dataset_version: required("dataset_version")?,
+            num_rows: required("num_rows")?,
+            null_count: required("null_count")?,
+            distinct_count: get_u64(key, &value, "distinct_count")?,
+            min: get_str(key, &value, "min")?,
+            max: get_str(key, &value, "max")?,
+        };
+        if stats.null_count > stats.num_rows {
+            return Err(Error::invalid_input(format!(
+                "{key} has null_count {} greater than num_rows {}",
+                stats.null_count, stats.num_rows
+            )));
+        }
+        Ok(stats)
+    }
+}
+
+/// The well-known entries of a schema or field metadata map
+///
+/// Use [`Self::from_metadata`] to read and validate them, and
+/// [`Self::to_metadata`] to get the entries to write.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WellKnownMetadata {
+    pub description: Option<String>,
+    pub semantic_type: Option<SemanticType>,
+    pub embedding_model: Option<EmbeddingModel>,
+    pub statistics: Option<ColumnStatistics>,
+}
+
+impl WellKnownMetadata {
+    /// Parse the well-known entries of `metadata`
+    ///
+    /// Returns an error if any well-known entry is malformed.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Result<Self> {
+        Ok(Self {
+            description: metadata.get(LANCE_DESCRIPTION_KEY).cloned(),
+            semantic_type: metadata
+                .get(LANCE_SEMANTIC_TYPE_KEY)
+                .map(|value| value.parse())
+                .transpose()?,
+            embedding_model: metadata
+                .get(LANCE_EMBEDDING_MODEL_KEY)
+                .map(|value| EmbeddingModel::from_json(value))
+                .transpose()?,
+            statistics: metadata
+                .get(LANCE_COLUMN_STATISTICS_KEY)
+                .map(|value| ColumnStatistics::from_json(value))
+                .transpose()?,
+        })
+    }
+
+    /// Check that the well-known entries of `metadata` are valid
+    pub fn validate(metadata: &HashMap<String, String>) -> Result<()> {
+        Self::from_metadata(metadata).map(|_| ())
+    }
+
+    /// Check that the well-known entries among `entries` are valid, ignoring
+    /// entries without a value (i.e. removals).
+    pub fn validate_entries<'a>(
+        entries: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+    ) -> Result<()> {
+        let metadata = entries
+            .into_iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value?.to_string())))
+            .collect();
+        Self::validate(&metadata)
+    }
+
+    /// The metadata entries for the fields that are set
+    pub fn to_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        if let Some(description) = &self.description {
+            metadata.insert(LANCE_DESCRIPTION_KEY.to_string(), description.clone());
+        }
+        if let Some(semantic_type) = &self.semantic_type {
+            metadata.insert(
+                LANCE_SEMANTIC_TYPE_KEY.to_string(),
+                semantic_type.to_string(),
+            );
+        }
+        if let Some(embedding_model) = &self.embedding_model {
+            metadata.insert(
+                LANCE_EMBEDDING_MODEL_KEY.to_string(),
+                embedding_model.to_json().to_string(),
+            );
+        }
+        if let Some(statistics) = &self.statistics {
+            metadata.insert(
+                LANCE_COLUMN_STATISTICS_KEY.to_string(),
+                statistics.to_json().to_string(),
+            );
+        }
+        metadata
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn parse_object(key: &str, value: &str) -> Result<Map<String, Value>> {
+    match serde_json::from_str::<Value>(value) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(_) => Err(Error::invalid_input(format!(
+            "{key} must be a JSON object, got '{value}'"
+        ))),
+        Err(err) => Err(Error::invalid_input(format!(
+            "{key} is not valid JSON: {err}"
+        ))),
+    }
+}
+
+fn get_str(key: &str, object: &Map<String, Value>, name: &str) -> Result<Option<String>> {
+    match object.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(other) => Err(Error::invalid_input(format!(
+            "{key} field {name} must be a string, got {other}"
+        ))),
+    }
+}
+
+fn get_u64(key: &str, object: &Map<String, Value>, name: &str) -> Result<Option<u64>> {
+    match object.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| {
+            Error::invalid_input(format!(
+                "{key} field {name} must be a non-negative integer, got {value}"
+            ))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_well_known_metadata_roundtrip() {
+        let metadata = WellKnownMetadata {
+            description: Some("The review text".to_string()),
+            semantic_type: Some(SemanticType::Custom("x-review".to_string())),
+            embedding_model: Some(EmbeddingModel {
+                provider: Some("openai".to_string()),
+                dimension: Some(1536),
+                ..EmbeddingModel::new("text-embedding-3-small")
+            }),
+            statistics: Some(ColumnStatistics {
+                dataset_version: 3,
+                num_rows: 100,
+                null_count: 2,
+                distinct_count: Some(50),
+                min: Some("a".to_string()),
+                max: None,
+            }),
+        };
+        let mut entries = metadata.to_metadata();
+        entries.insert("other".to_string(), "ignored".to_string());
+        assert_eq!(
+            WellKnownMetadata::from_metadata(&entries).unwrap(),
+            metadata
+        );
+
+        let empty = WellKnownMetadata::from_metadata(&HashMap::new()).unwrap();
+        assert!(empty.is_empty());
+        assert!(empty.to_metadata().is_empty());
+    }
+
+    #[test]
+    fn test_well_known_metadata_validation() {
+        let invalid = [
+            (LANCE_SEMANTIC_TYPE_KEY, "review"),
+            (LANCE_EMBEDDING_MODEL_KEY, "not json"),
+            (LANCE_EMBEDDING_MODEL_KEY, r#"{"version": "1"}"#),
+            (
+                LANCE_EMBEDDING_MODEL_KEY,
+                r#"{"name": "m", "dimension": -1}"#,
+            ),
+            (LANCE_COLUMN_STATISTICS_KEY, "[]"),
+            (
+                LANCE_COLUMN_STATISTICS_KEY,
+                r#"{"dataset_version": 1, "num_rows": 1, "null_count": 2}"#,
+            ),
+        ];
+        for (key, value) in invalid {
+            let result = WellKnownMetadata::validate_entries([(key, Some(value))]);
+            assert!(
+                matches!(result, Err(Error::InvalidInput { .. })),
+                "{key}={value}"
+            );
+        }
+
+        WellKnownMetadata::validate_entries([
+            (LANCE_SEMANTIC_TYPE_KEY, Some("image")),
+            (LANCE_EMBEDDING_MODEL_KEY, None),
+        ])
+        .unwrap();
+    }
+}
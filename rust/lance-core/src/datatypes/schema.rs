@@ -14,6 +14,7 @@ use arrow_array::RecordBatch;
 use arrow_schema::{Field as ArrowField, Schema as ArrowSchema};
 use lance_arrow::*;
 
+use super::WellKnownMetadata;
 use super::field::{Field, OnTypeMismatch, SchemaCompareOptions};
 use crate::{
     Error, ROW_ADDR, ROW_ADDR_FIELD, ROW_CREATED_AT_VERSION, ROW_CREATED_AT_VERSION_FIELD, ROW_ID,
@@ -111,6 +112,13 @@ impl<'a> Iterator for SchemaFieldIterPreOrder<'a> {
 }
 
 impl Schema {
+    /// The well-known entries of the schema metadata, e.g. the table description.
+    ///
+    /// Returns an error if a well-known entry is malformed.
+    pub fn well_known_metadata(&self) -> Result<WellKnownMetadata> {
+        WellKnownMetadata::from_metadata(&self.metadata)
+    }
+
     /// The unenforced primary key fields in the schema, ordered by position.
     ///
     /// Fields with explicit positions (1, 2, 3, ...) are ordered by their position value.
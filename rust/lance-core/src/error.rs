@@ -51,6 +51,183 @@ pub fn box_error(e: impl std::error::Error + Send + Sync + 'static) -> BoxedErro
     Box::new(e)
 }
 
+/// A stable, machine-readable classification of an [`Error`].
+///
+/// The variants of [`Error`] describe where a failure came from, which is not
+/// always what a caller needs to know: an `IO` error may be a missing object
+/// or a throttled request.  The code describes what the caller can do about
+/// the error instead.  Codes and their string forms are stable across
+/// releases, so services wrapping Lance can implement retries and map errors
+/// to HTTP statuses without matching on messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The request was malformed or referenced something that is invalid.
+    InvalidInput,
+    /// The dataset, version, index, or object does not exist.
+    NotFound,
+    /// The dataset or object being created already exists.
+    AlreadyExists,
+    /// The operation conflicts with a concurrent change and cannot be retried
+    /// as is.
+    Conflict,
+    /// The operation conflicts with a concurrent change but can be retried.
+    RetryableConflict,
+    /// The operation is not supported.
+    NotSupported,
+    /// The caller lacks permission for the operation.
+    PermissionDenied,
+    /// Credentials are missing or invalid.
+    Unauthenticated,
+    /// The request was throttled or there is too much contention.
+    Throttled,
+    /// The operation timed out.
+    Timeout,
+    /// A storage or namespace service is temporarily unavailable.
+    Unavailable,
+    /// The request was well formed but cannot be processed.
+    Unprocessable,
+    /// A file or manifest is corrupt.
+    Corrupt,
+    /// A non-transient I/O failure.
+    Io,
+    /// An unexpected internal failure.
+    Internal,
+    /// An error passed through from user code.
+    External,
+}
+
+impl ErrorCode {
+    /// Returns the stable string form of the code, e.g. `"NOT_FOUND"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidInput => "INVALID_INPUT",
+            Self::NotFound => "NOT_FOUND",
+            Self::AlreadyExists => "ALREADY_EXISTS",
+            Self::Conflict => "CONFLICT",
+            Self::RetryableConflict => "RETRYABLE_CONFLICT",
+            Self::NotSupported => "NOT_SUPPORTED",
+            Self::PermissionDenied => "PERMISSION_DENIED",
+            Self::Unauthenticated => "UNAUTHENTICATED",
+            Self::Throttled => "THROTTLED",
+            Self::Timeout => "TIMEOUT",
+            Self::Unavailable => "UNAVAILABLE",
+            Self::Unprocessable => "UNPROCESSABLE",
+            Self::Corrupt => "CORRUPT",
+            Self::Io => "IO",
+            Self::Internal => "INTERNAL",
+            Self::External => "EXTERNAL",
+        }
+    }
+
+    /// Returns true if errors with this code are transient, meaning the same
+    /// operation may succeed when retried.  All other codes are permanent.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RetryableConflict | Self::Throttled | Self::Timeout | Self::Unavailable
+        )
+    }
+
+    /// Returns the HTTP status code that best describes errors with this code.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::InvalidInput => 400,
+            Self::Unauthenticated => 401,
+            Self::PermissionDenied => 403,
+            Self::NotFound => 404,
+            Self::NotSupported => 406,
+            Self::AlreadyExists | Self::Conflict | Self::RetryableConflict => 409,
+            Self::Unprocessable => 422,
+            Self::Throttled => 429,
+            Self::Corrupt | Self::Io | Self::Internal | Self::External => 500,
+            Self::Unavailable => 503,
+            Self::Timeout => 504,
+        }
+    }
+
+    /// Classify an object store error.
+    ///
+    /// Errors the object store reports explicitly (missing objects, failed
+    /// preconditions, denied access, ...) are permanent.  Everything else,
+    /// such as a request that failed after the client exhausted its retries,
+    /// is treated as the store being unavailable.
+    pub fn from_object_store(error: &object_store::Error) -> Self {
+        match error {
+            object_store::Error::NotFound { .. } => Self::NotFound,
+            object_store::Error::AlreadyExists { .. } => Self::AlreadyExists,
+            object_store::Error::Precondition { .. } | object_store::Error::NotModified { .. } => {
+                Self::Conflict
+            }
+            object_store::Error::InvalidPath { .. }
+            | object_store::Error::UnknownConfigurationKey { .. } => Self::InvalidInput,
+            object_store::Error::NotSupported { .. }
+            | object_store::Error::NotImplemented { .. } => Self::NotSupported,
+            object_store::Error::PermissionDenied { .. } => Self::PermissionDenied,
+            object_store::Error::Unauthenticated { .. } => Self::Unauthenticated,
+            _ => Self::Unavailable,
+        }
+    }
+
+    fn from_io_error(error: &std::io::Error) -> Self {
+        use std::io::ErrorKind;
+        match error.kind() {
+            ErrorKind::NotFound => Self::NotFound,
+            ErrorKind::AlreadyExists => Self::AlreadyExists,
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            ErrorKind::InvalidInput => Self::InvalidInput,
+            ErrorKind::Unsupported => Self::NotSupported,
+            ErrorKind::TimedOut => Self::Timeout,
+            ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe => Self::Unavailable,
+            _ => Self::Io,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Classify the first error in the source chain that Lance recognizes.
+fn classify_source(source: &(dyn std::error::Error + 'static)) -> Option<ErrorCode> {
+    let mut current = Some(source);
+    while let Some(err) = current {
+        if let Some(err) = err.downcast_ref::<Error>() {
+            return Some(err.code());
+        }
+        if let Some(err) = err.downcast_ref::<object_store::Error>() {
+            return Some(ErrorCode::from_object_store(err));
+        }
+        if let Some(err) = err.downcast_ref::<std::io::Error>() {
+            // `std::io::Error::other` is often used to smuggle other errors
+            // through I/O interfaces, look through it for the real cause.
+            if err.kind() == std::io::ErrorKind::Other
+                && let Some(inner) = err.get_ref()
+            {
+                current = Some(inner as &(dyn std::error::Error + 'static));
+                continue;
+            }
+            return Some(ErrorCode::from_io_error(err));
+        }
+        if err.is::<prost::DecodeError>() || err.is::<prost::UnknownEnumValue>() {
+            return Some(ErrorCode::Corrupt);
+        }
+        if err.is::<url::ParseError>() || err.is::<object_store::path::Error>() {
+            return Some(ErrorCode::InvalidInput);
+        }
+        current = err.source();
+    }
+    None
+}
+
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
 pub enum Error {
@@ -93,6 +270,17 @@ pub enum Error {
         #[snafu(implicit)]
         location: Location,
     },
+    /// The dataset uses features unknown to this version of Lance.
+    #[snafu(display("Unsupported version: {message}, {location}"))]
+    UnsupportedVersion {
+        message: String,
+        /// The feature flags of the dataset that this version does not know.
+        unsupported_flags: u64,
+        /// The oldest library version known to support the flags, if known.
+        min_library_version: Option<String>,
+        #[snafu(implicit)]
+        location: Location,
+    },
     #[snafu(display("Commit conflict for version {version}: {source}, {location}"))]
     CommitConflict {
         version: u64,
@@ -224,6 +412,8 @@ pub enum Error {
     #[snafu(display("Namespace error: {source}, {location}"))]
     Namespace {
         source: BoxedError,
+        /// Classification reported by the namespace implementation, if any.
+        code: Option<ErrorCode>,
         #[snafu(implicit)]
         location: Location,
     },
@@ -318,6 +508,20 @@ impl Error {
         NotSupportedSnafu.into_error(source)
     }
 
+    #[track_caller]
+    pub fn unsupported_version(
+        message: impl Into<String>,
+        unsupported_flags: u64,
+        min_library_version: Option<String>,
+    ) -> Self {
+        UnsupportedVersionSnafu {
+            message: message.into(),
+            unsupported_flags,
+            min_library_version,
+        }
+        .build()
+    }
+
     #[track_caller]
     pub fn internal(message: impl Into<String>) -> Self {
         InternalSnafu {
@@ -336,12 +540,19 @@ impl Error {
 
     #[track_caller]
     pub fn namespace(message: impl Into<String>) -> Self {
-        NamespaceSnafu.into_error(message.into().into())
+        NamespaceSnafu { code: None }.into_error(message.into().into())
     }
 
     #[track_caller]
     pub fn namespace_source(source: Box<dyn std::error::Error + Send + Sync + 'static>) -> Self {
-        NamespaceSnafu.into_error(source)
+        NamespaceSnafu { code: None }.into_error(source)
+    }
+
+    /// Create a Namespace error that carries the classification reported by the
+    /// namespace implementation, so it survives the conversion into [`Error`].
+    #[track_caller]
+    pub fn namespace_with_code(code: ErrorCode, source: BoxedError) -> Self {
+        NamespaceSnafu { code: Some(code) }.into_error(source)
     }
 
     #[track_caller]
@@ -456,6 +667,65 @@ impl Error {
         }
     }
 
+    /// Returns the stable [`ErrorCode`] classifying this error.
+    ///
+    /// Errors that wrap another error (I/O, namespace, wrapped and external
+    /// errors) are classified by inspecting their source chain, so an object
+    /// store or namespace failure keeps its meaning as it propagates up
+    /// through the dataset layer.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidInput { .. }
+            | Self::SchemaMismatch { .. }
+            | Self::Schema { .. }
+            | Self::InvalidTableLocation { .. }
+            | Self::InvalidRef { .. }
+            | Self::FieldNotFound { .. } => ErrorCode::InvalidInput,
+            Self::DatasetNotFound { .. }
+            | Self::NotFound { .. }
+            | Self::IndexNotFound { .. }
+            | Self::RefNotFound { .. }
+            | Self::VersionNotFound { .. } => ErrorCode::NotFound,
+            Self::DatasetAlreadyExists { .. } => ErrorCode::AlreadyExists,
+            Self::CommitConflict { .. }
+            | Self::IncompatibleTransaction { .. }
+            | Self::RefConflict { .. }
+            | Self::VersionConflict { .. } => ErrorCode::Conflict,
+            Self::RetryableCommitConflict { .. } => ErrorCode::RetryableConflict,
+            Self::TooMuchWriteContention { .. } => ErrorCode::Throttled,
+            Self::Timeout { .. } => ErrorCode::Timeout,
+            Self::NotSupported { .. } | Self::UnsupportedVersion { .. } => ErrorCode::NotSupported,
+            Self::Unprocessable { .. } => ErrorCode::Unprocessable,
+            Self::CorruptFile { .. } => ErrorCode::Corrupt,
+            Self::IO { source, .. } => classify_source(source.as_ref()).unwrap_or(ErrorCode::Io),
+            Self::Namespace { source, code, .. } => code
+                .or_else(|| classify_source(source.as_ref()))
+                .unwrap_or(ErrorCode::Internal),
+            Self::Wrapped { error, .. } => {
+                classify_source(error.as_ref()).unwrap_or(ErrorCode::Internal)
+            }
+            Self::External { source } => {
+                classify_source(source.as_ref()).unwrap_or(ErrorCode::External)
+            }
+            Self::Internal { .. }
+            | Self::PrerequisiteFailed { .. }
+            | Self::Arrow { .. }
+            | Self::Index { .. }
+            | Self::Stop
+            | Self::Cloned { .. }
+            | Self::Execution { .. }
+            | Self::Cleanup { .. } => ErrorCode::Internal,
+        }
+    }
+
+    /// Returns true if the operation that produced this error may succeed when
+    /// retried unchanged.
+    ///
+    /// This is a shortcut for `self.code().is_retryable()`.
+    pub fn is_retryable(&self) -> bool {
+        self.code().is_retryable()
+    }
+
     /// Consumes the error and returns the external source if this is an `External` variant.
     ///
     /// Returns `Err(self)` if this is not an `External` variant, allowing for chained handling.
@@ -929,4 +1199,69 @@ mod test {
             _ => panic!("Expected InvalidInput variant, got {:?}", recovered),
         }
     }
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(Error::invalid_input("bad").code(), ErrorCode::InvalidInput);
+        assert_eq!(Error::not_found("uri").code(), ErrorCode::NotFound);
+        assert_eq!(Error::internal("oops").code(), ErrorCode::Internal);
+        assert_eq!(
+            Error::unsupported_version("too new", 128, None).code(),
+            ErrorCode::NotSupported
+        );
+        assert_eq!(
+            Error::commit_conflict_source(1, "conflict".into()).code(),
+            ErrorCode::Conflict
+        );
+
+        let retryable = [
+            Error::retryable_commit_conflict_source(1, "conflict".into()),
+            Error::too_much_write_contention("busy"),
+            Error::timeout("slow"),
+        ];
+        for err in retryable {
+            assert!(err.is_retryable(), "{err:?} should be retryable");
+        }
+        assert!(!Error::commit_conflict_source(1, "conflict".into()).is_retryable());
+        assert_eq!(ErrorCode::Throttled.as_str(), "THROTTLED");
+        assert_eq!(ErrorCode::Throttled.http_status(), 429);
+    }
+
+    #[test]
+    fn test_error_code_from_source() {
+        let err: Error = object_store::Error::NotFound {
+            path: "a".to_string(),
+            source: "missing".into(),
+        }
+        .into();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert!(!err.is_retryable());
+
+        let err: Error = object_store::Error::Generic {
+            store: "s3",
+            source: "503 Slow Down".into(),
+        }
+        .into();
+        assert_eq!(err.code(), ErrorCode::Unavailable);
+        assert!(err.is_retryable());
+
+        let err: Error = std::io::Error::from(std::io::ErrorKind::TimedOut).into();
+        assert_eq!(err.code(), ErrorCode::Timeout);
+
+        // Errors smuggled through `std::io::Error::other` keep their code
+        let err: Error = std::io::Error::other(Error::not_found("uri")).into();
+        assert_eq!(err.code(), ErrorCode::NotFound);
+
+        // Wrapping a lance error in another layer keeps its code
+        let err = Error::namespace_source(Box::new(Error::timeout("slow")));
+        assert!(err.is_retryable());
+        let err = Error::namespace_with_code(ErrorCode::PermissionDenied, "denied".into());
+        assert_eq!(err.code(), ErrorCode::PermissionDenied);
+
+        let err = Error::external(Box::new(MyCustomError {
+            code: 1,
+            message: "user".to_string(),
+        }));
+        assert_eq!(err.code(), ErrorCode::External);
+    }
 }
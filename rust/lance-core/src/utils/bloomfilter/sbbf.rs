@@ -291,6 +291,14 @@ impl Sbbf {
         (((hash >> 32).saturating_mul(self.blocks.len() as u64)) >> 32) as usize
     }
 
+    /// The hash used by [`Self::insert`] and [`Self::check`] for a value
+    ///
+    /// Useful to hash values once and then insert them with [`Self::insert_hash`]
+    /// (or probe them with [`Self::check_hash`]).
+    pub fn hash<T: AsBytes + ?Sized>(value: &T) -> u64 {
+        hash_as_bytes(value)
+    }
+
     /// Insert an AsBytes value into the filter
     pub fn insert<T: AsBytes + ?Sized>(&mut self, value: &T) {
         self.insert_hash(hash_as_bytes(value));
@@ -7,7 +7,7 @@ use std::{
     collections::HashMap,
     fmt::{self, Formatter},
     sync::{Arc, Mutex, OnceLock},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use chrono::{DateTime, Utc};
@@ -55,8 +55,9 @@ use crate::udf::register_functions;
 use crate::{
     chunker::StrictBatchSizeStream,
     utils::{
-        BYTES_READ_METRIC, INDEX_COMPARISONS_METRIC, INDICES_LOADED_METRIC, IOPS_METRIC,
-        MetricsExt, PARTS_LOADED_METRIC, REQUESTS_METRIC,
+        BYTES_READ_METRIC, FRAGMENTS_PRUNED_METRIC, FRAGMENTS_SCANNED_METRIC,
+        INDEX_COMPARISONS_METRIC, INDICES_LOADED_METRIC, IOPS_METRIC, MetricsExt,
+        PARTS_LOADED_METRIC, REQUESTS_METRIC, ROWS_SCANNED_METRIC,
     },
 };
 
@@ -484,6 +485,19 @@ pub struct ExecutionSummaryCounts {
     pub parts_loaded: usize,
     /// The number of index comparisons performed (the exact meaning depends on the index type)
     pub index_comparisons: usize,
+    /// The number of rows read from storage, before any filter is applied
+    pub rows_scanned: usize,
+    /// The number of fragments read from storage
+    pub fragments_scanned: usize,
+    /// The number of fragments skipped without reading any data, because a scalar index, the
+    /// fragment's zone maps, or the scan range ruled them out
+    pub fragments_pruned: usize,
+    /// The number of rows output by the plan
+    pub output_rows: usize,
+    /// Wall time from the start of execution until the output stream finished
+    pub elapsed: Duration,
+    /// The compute time of each operator in the plan, in pre-order, keyed by operator name
+    pub stage_times: Vec<(String, Duration)>,
     /// Additional metrics for more detailed statistics.  These are subject to change in the future
     /// and should only be used for debugging purposes.
     pub all_counts: HashMap<String, usize>,
@@ -503,6 +517,12 @@ pub fn collect_execution_metrics(node: &dyn ExecutionPlan, counts: &mut Executio
                 PARTS_LOADED_METRIC => counts.parts_loaded += count.value(),
                 INDEX_COMPARISONS_METRIC => counts.index_comparisons += count.value(),
                 _ => {
+                    match metric_name.as_ref() {
+                        ROWS_SCANNED_METRIC => counts.rows_scanned += count.value(),
+                        FRAGMENTS_SCANNED_METRIC => counts.fragments_scanned += count.value(),
+                        FRAGMENTS_PRUNED_METRIC => counts.fragments_pruned += count.value(),
+                        _ => {}
+                    }
                     let existing = counts
                         .all_counts
                         .entry(metric_name.as_ref().to_string())
@@ -511,6 +531,12 @@ pub fn collect_execution_metrics(node: &dyn ExecutionPlan, counts: &mut Executio
                 }
             }
         }
+        if let Some(elapsed_compute) = metrics.elapsed_compute() {
+            counts.stage_times.push((
+                node.name().to_string(),
+                Duration::from_nanos(elapsed_compute as u64),
+            ));
+        }
         for (metric_name, time) in metrics.iter_times() {
             let existing = counts
                 .all_times
@@ -533,12 +559,20 @@ pub fn collect_execution_metrics(node: &dyn ExecutionPlan, counts: &mut Executio
     }
 }
 
-fn report_plan_summary_metrics(plan: &dyn ExecutionPlan, options: &LanceExecutionOptions) {
+fn report_plan_summary_metrics(
+    plan: &dyn ExecutionPlan,
+    options: &LanceExecutionOptions,
+    elapsed: Duration,
+) {
     let output_rows = plan
         .metrics()
         .map(|m| m.output_rows().unwrap_or(0))
         .unwrap_or(0);
-    let mut counts = ExecutionSummaryCounts::default();
+    let mut counts = ExecutionSummaryCounts {
+        output_rows,
+        elapsed,
+        ..Default::default()
+    };
     collect_execution_metrics(plan, &mut counts);
     if !options.skip_logging {
         tracing::info!(
@@ -616,12 +650,13 @@ pub fn execute_plan(
         Arc::new(CoalescePartitionsExec::new(plan))
     };
 
+    let start = Instant::now();
     let stream = plan.execute(0, get_task_context(&session_ctx, &options))?;
 
     let schema = stream.schema();
     let stream = stream.finally(move || {
         if !options.skip_logging || options.execution_stats_callback.is_some() {
-            report_plan_summary_metrics(plan.as_ref(), &options);
+            report_plan_summary_metrics(plan.as_ref(), &options, start.elapsed());
         }
     });
     Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
@@ -11,13 +11,20 @@ use datafusion_substrait::logical_plan::consumer::{
     DefaultSubstraitConsumer, from_substrait_agg_func, from_substrait_rex, from_substrait_sorts,
 };
 use datafusion_substrait::substrait::proto::{
-    AggregateRel, Expression, ExpressionReference, ExtendedExpression, NamedStruct, Plan, Type,
+    AggregateRel, Expression, ExpressionReference, ExtendedExpression, FunctionArgument,
+    NamedStruct, Plan, Type,
     expression::{
-        RexType,
+        IfThen, Literal, RexType, ScalarFunction,
         field_reference::{ReferenceType, RootType},
+        if_then::IfClause,
+        literal::LiteralType,
         reference_segment,
     },
     expression_reference::ExprType,
+    extensions::{
+        SimpleExtensionDeclaration,
+        simple_extension_declaration::{ExtensionFunction, MappingType},
+    },
     function_argument::ArgType,
     rel::RelType,
     r#type::{Kind, Struct},
@@ -258,6 +265,298 @@ fn remap_expr_references(expr: &mut Expression, mapping: &HashMap<usize, usize>)
     }
 }
 
+/// The function declarations of a Substrait message, by anchor.
+///
+/// New declarations can be added for functions that a rewrite introduces.
+struct FunctionAnchors {
+    names: HashMap<u32, String>,
+    declarations: Vec<SimpleExtensionDeclaration>,
+}
+
+impl FunctionAnchors {
+    fn new(declarations: Vec<SimpleExtensionDeclaration>) -> Self {
+        let names = declarations
+            .iter()
+            .filter_map(|declaration| match &declaration.mapping_type {
+                Some(MappingType::ExtensionFunction(function)) => {
+                    Some((function.function_anchor, function.name.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        Self {
+            names,
+            declarations,
+        }
+    }
+
+    /// The name of the function, without the signature (e.g. `equal` for `equal:any_any`)
+    fn name(&self, anchor: u32) -> Option<&str> {
+        self.names
+            .get(&anchor)
+            .map(|name| name.split(':').next().unwrap_or(name))
+    }
+
+    /// The anchor of the function `name`, declaring it if needed
+    fn anchor(&mut self, name: &str) -> u32 {
+        if let Some(anchor) = self
+            .names
+            .iter()
+            .find_map(|(anchor, existing)| (existing == name).then_some(*anchor))
+        {
+            return anchor;
+        }
+        let anchor = self.names.keys().max().map_or(1, |max| max + 1);
+        self.names.insert(anchor, name.to_string());
+        self.declarations.push(SimpleExtensionDeclaration {
+            mapping_type: Some(MappingType::ExtensionFunction(ExtensionFunction {
+                #[allow(deprecated)]
+                extension_uri_reference: 0,
+                extension_urn_reference: 0,
+                function_anchor: anchor,
+                name: name.to_string(),
+            })),
+        });
+        anchor
+    }
+
+    fn call(&mut self, name: &str, args: Vec<Expression>) -> Expression {
+        Expression {
+            rex_type: Some(RexType::ScalarFunction(ScalarFunction {
+                function_reference: self.anchor(name),
+                arguments: args
+                    .into_iter()
+                    .map(|arg| FunctionArgument {
+                        arg_type: Some(ArgType::Value(arg)),
+                    })
+                    .collect(),
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Combine `exprs` with a binary boolean function such as `and`
+    fn fold(&mut self, name: &str, exprs: Vec<Expression>, empty: bool) -> Expression {
+        exprs
+            .into_iter()
+            .reduce(|acc, expr| self.call(name, vec![acc, expr]))
+            .unwrap_or_else(|| Expression {
+                rex_type: Some(RexType::Literal(Literal {
+                    literal_type: Some(LiteralType::Boolean(empty)),
+                    ..Default::default()
+                })),
+            })
+    }
+}
+
+/// Substrait functions that DataFusion knows under another name
+const FUNCTION_ALIASES: &[(&str, &str)] =
+    &[("is_nan", "isnan"), ("char_length", "character_length")];
+
+/// The `date_part` part for a Substrait `extract` component.
+///
+/// Components whose meaning differs between the two (e.g. the day of the week or
+/// the sub-second components) are not mapped.
+fn date_part_of_component(component: &str) -> Option<&'static str> {
+    Some(match component {
+        "YEAR" => "year",
+        "QUARTER" => "quarter",
+        "MONTH" => "month",
+        "DAY" => "day",
+        "DAY_OF_YEAR" => "doy",
+        "ISO_WEEK" => "week",
+        "HOUR" => "hour",
+        "MINUTE" => "minute",
+        _ => return None,
+    })
+}
+
+fn value_args(func: &ScalarFunction) -> Vec<Expression> {
+    func.arguments
+        .iter()
+        .filter_map(|arg| match &arg.arg_type {
+            Some(ArgType::Value(expr)) => Some(expr.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Rewrite the parts of `expr` that DataFusion's Substrait consumer does not support
+/// into equivalent expressions that it does:
+///
+/// * `extract(<component>, x)` becomes `date_part('<component>', x)`
+/// * `between(x, low, high)` becomes `x >= low AND x <= high`
+/// * Substrait names of functions such as `is_nan` are mapped to DataFusion's names
+/// * Switch expressions (`CASE x WHEN ...`) become if-then expressions
+/// * Multi-column `IN` lists become disjunctions of equalities
+///
+/// Anything else is left for the consumer, which fails on what it cannot convert
+/// rather than dropping the predicate.
+fn normalize_expression(expr: &mut Expression, functions: &mut FunctionAnchors) -> Result<()> {
+    let Some(rex_type) = expr.rex_type.as_mut() else {
+        return Ok(());
+    };
+    // Children first, so the rewrites below only see normalized expressions
+    match rex_type {
+        RexType::ScalarFunction(func) => {
+            for arg in func.arguments.iter_mut() {
+                if let Some(ArgType::Value(arg)) = arg.arg_type.as_mut() {
+                    normalize_expression(arg, functions)?;
+                }
+            }
+            #[allow(deprecated)]
+            for arg in func.args.iter_mut() {
+                normalize_expression(arg, functions)?;
+            }
+        }
+        RexType::IfThen(if_then) => {
+            for clause in if_then.ifs.iter_mut() {
+                for expr in clause.r#if.iter_mut().chain(clause.then.iter_mut()) {
+                    normalize_expression(expr, functions)?;
+                }
+            }
+            if let Some(r#else) = if_then.r#else.as_mut() {
+                normalize_expression(r#else, functions)?;
+            }
+        }
+        RexType::SwitchExpression(switch) => {
+            if let Some(r#match) = switch.r#match.as_mut() {
+                normalize_expression(r#match, functions)?;
+            }
+            for clause in switch.ifs.iter_mut() {
+                if let Some(then) = clause.then.as_mut() {
+                    normalize_expression(then, functions)?;
+                }
+            }
+            if let Some(r#else) = switch.r#else.as_mut() {
+                normalize_expression(r#else, functions)?;
+            }
+        }
+        RexType::SingularOrList(or_list) => {
+            if let Some(value) = or_list.value.as_mut() {
+                normalize_expression(value, functions)?;
+            }
+            for option in or_list.options.iter_mut() {
+                normalize_expression(option, functions)?;
+            }
+        }
+        RexType::MultiOrList(or_list) => {
+            for value in or_list.value.iter_mut() {
+                normalize_expression(value, functions)?;
+            }
+            for option in or_list.options.iter_mut() {
+                for field in option.fields.iter_mut() {
+                    normalize_expression(field, functions)?;
+                }
+            }
+        }
+        RexType::Cast(cast) => {
+            if let Some(input) = cast.input.as_mut() {
+                normalize_expression(input, functions)?;
+            }
+        }
+        _ => {}
+    }
+
+    let rewritten = match rex_type {
+        RexType::ScalarFunction(func) => match functions
+            .name(func.function_reference)
+            .map(str::to_string)
+            .as_deref()
+        {
+            Some("extract") => {
+                let component = func.arguments.iter().find_map(|arg| match &arg.arg_type {
+                    Some(ArgType::Enum(component)) => Some(component.clone()),
+                    _ => None,
+                });
+                let Some(component) = component else {
+                    return Ok(());
+                };
+                let part = date_part_of_component(&component).ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "extracting {component} is not supported in Substrait filters"
+                    ))
+                })?;
+                let mut args = vec![Expression {
+                    rex_type: Some(RexType::Literal(Literal {
+                        literal_type: Some(LiteralType::String(part.to_string())),
+                        ..Default::default()
+                    })),
+                }];
+                args.extend(value_args(func));
+                Some(functions.call("date_part", args))
+            }
+            Some("between") => {
+                let [value, low, high] = <[Expression; 3]>::try_from(value_args(func))
+                    .map_err(|_| Error::invalid_input("between expects 3 arguments"))?;
+                let lower = functions.call("gte", vec![value.clone(), low]);
+                let upper = functions.call("lte", vec![value, high]);
+                Some(functions.call("and", vec![lower, upper]))
+            }
+            Some(name) => {
+                if let Some((_, alias)) = FUNCTION_ALIASES.iter().find(|(from, _)| *from == name) {
+                    func.function_reference = functions.anchor(alias);
+                }
+                None
+            }
+            None => None,
+        },
+        RexType::SwitchExpression(switch) => {
+            let r#match = switch
+                .r#match
+                .as_deref()
+                .cloned()
+                .ok_or_else(|| Error::invalid_input("switch expression without a value"))?;
+            let ifs = std::mem::take(&mut switch.ifs)
+                .into_iter()
+                .map(|clause| {
+                    let value = Expression {
+                        rex_type: Some(RexType::Literal(clause.r#if.ok_or_else(|| {
+                            Error::invalid_input("switch expression without a case value")
+                        })?)),
+                    };
+                    Ok(IfClause {
+                        r#if: Some(functions.call("equal", vec![r#match.clone(), value])),
+                        then: clause.then,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Some(Expression {
+                rex_type: Some(RexType::IfThen(Box::new(IfThen {
+                    ifs,
+                    r#else: switch.r#else.take(),
+                }))),
+            })
+        }
+        RexType::MultiOrList(or_list) => {
+            let values = std::mem::take(&mut or_list.value);
+            let options = std::mem::take(&mut or_list.options)
+                .into_iter()
+                .map(|option| {
+                    if option.fields.len() != values.len() {
+                        return Err(Error::invalid_input(
+                            "multi-column IN list option does not match the number of columns",
+                        ));
+                    }
+                    let equalities = values
+                        .iter()
+                        .cloned()
+                        .zip(option.fields)
+                        .map(|(value, field)| functions.call("equal", vec![value, field]))
+                        .collect();
+                    Ok(functions.fold("and", equalities, true))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Some(functions.fold("or", options, false))
+        }
+        _ => None,
+    };
+    if let Some(rewritten) = rewritten {
+        *expr = rewritten;
+    }
+    Ok(())
+}
+
 /// Convert a Substrait ExtendedExpressions message into a DF Expr
 ///
 /// The ExtendedExpressions message must contain a single scalar expression
@@ -317,12 +616,16 @@ pub async fn parse_substrait(
         envelope.base_schema.as_ref().unwrap().clone()
     };
 
+    let mut functions = FunctionAnchors::new(envelope.extensions.clone());
+    normalize_expression(&mut expr, &mut functions)?;
+
     let extended_expr = ExtendedExpression {
         base_schema: Some(substrait_schema),
         referred_expr: vec![ExpressionReference {
             output_names: envelope.referred_expr[0].output_names.clone(),
             expr_type: Some(ExprType::Expression(expr)),
         }],
+        extensions: functions.declarations,
         ..envelope
     };
 
@@ -540,6 +843,9 @@ mod tests {
         prelude::{Expr, SessionContext},
     };
     use datafusion_common::{Column, ScalarValue};
+    use datafusion_substrait::substrait::proto::expression::{
+        MultiOrList, SwitchExpression, multi_or_list::Record, switch_expression::IfValue,
+    };
     use datafusion_substrait::substrait::proto::{
         Expression, ExpressionReference, ExtendedExpression, FunctionArgument, NamedStruct, Type,
         Version,
@@ -555,7 +861,7 @@ mod tests {
             simple_extension_declaration::{ExtensionFunction, MappingType},
         },
         function_argument::ArgType,
-        r#type::{Boolean, I32, Kind, Nullability, Struct},
+        r#type::{Boolean, Date, I32, Kind, Nullability, Struct},
     };
     use prost::Message;
 
@@ -1163,4 +1469,208 @@ mod tests {
 
         assert_substrait_roundtrip(schema, starts_with_expr).await;
     }
+
+    // ==================== Function normalization tests ====================
+
+    /// Parse `expr` as a filter over the i32 columns `x` and `y`, or a date `x` if `date_column`
+    async fn parse_normalized(
+        expr: Expression,
+        functions: &[(u32, &str)],
+        date_column: bool,
+    ) -> lance_core::Result<Expr> {
+        let column_type = |name: &str| {
+            if date_column && name == "x" {
+                (
+                    Type {
+                        kind: Some(Kind::Date(Date {
+                            type_variation_reference: 0,
+                            nullability: Nullability::Nullable as i32,
+                        })),
+                    },
+                    DataType::Date32,
+                )
+            } else {
+                (
+                    Type {
+                        kind: Some(Kind::I32(I32 {
+                            type_variation_reference: 0,
+                            nullability: Nullability::Nullable as i32,
+                        })),
+                    },
+                    DataType::Int32,
+                )
+            }
+        };
+        let envelope = ExtendedExpression {
+            extensions: functions
+                .iter()
+                .map(|(anchor, name)| agg_extension(*anchor, name))
+                .collect(),
+            referred_expr: vec![ExpressionReference {
+                output_names: vec!["filter_mask".to_string()],
+                expr_type: Some(ExprType::Expression(expr)),
+            }],
+            base_schema: Some(NamedStruct {
+                names: vec!["x".to_string(), "y".to_string()],
+                r#struct: Some(Struct {
+                    types: vec![column_type("x").0, column_type("y").0],
+                    type_variation_reference: 0,
+                    nullability: Nullability::Required as i32,
+                }),
+            }),
+            ..Default::default()
+        };
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("x", column_type("x").1, true),
+            Field::new("y", column_type("y").1, true),
+        ]));
+        parse_substrait(&envelope.encode_to_vec(), schema, &session_state()).await
+    }
+
+    fn i32_literal(value: i32) -> Literal {
+        Literal {
+            literal_type: Some(LiteralType::I32(value)),
+            ..Default::default()
+        }
+    }
+
+    fn value_arg(expr: Expression) -> FunctionArgument {
+        FunctionArgument {
+            arg_type: Some(ArgType::Value(expr)),
+        }
+    }
+
+    fn call(anchor: u32, arguments: Vec<FunctionArgument>) -> Expression {
+        Expression {
+            rex_type: Some(RexType::ScalarFunction(ScalarFunction {
+                function_reference: anchor,
+                arguments,
+                ..Default::default()
+            })),
+        }
+    }
+
+    fn lit_expr(value: i32) -> Expression {
+        Expression {
+            rex_type: Some(RexType::Literal(i32_literal(value))),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_substrait_extract() {
+        use datafusion::functions::datetime::date_part;
+        use datafusion::prelude::{col, lit};
+
+        let extract = |component: &str| {
+            call(
+                1,
+                vec![
+                    FunctionArgument {
+                        arg_type: Some(ArgType::Enum(component.to_string())),
+                    },
+                    value_arg(agg_field_ref(0)),
+                ],
+            )
+        };
+        let expr = parse_normalized(extract("YEAR"), &[(1, "extract:req_date")], true)
+            .await
+            .unwrap();
+        assert_eq!(expr, date_part().call(vec![lit("year"), col("x")]));
+
+        // Components without an equivalent date part are rejected, not dropped
+        assert!(
+            parse_normalized(extract("DAY_OF_WEEK"), &[(1, "extract")], true)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_substrait_between() {
+        use datafusion::prelude::{col, lit};
+
+        let between = call(
+            1,
+            vec![
+                value_arg(agg_field_ref(0)),
+                value_arg(lit_expr(1)),
+                value_arg(lit_expr(5)),
+            ],
+        );
+        let expr = parse_normalized(between, &[(1, "between:any_any_any")], false)
+            .await
+            .unwrap();
+        assert_eq!(
+            expr,
+            col("x").gt_eq(lit(1i32)).and(col("x").lt_eq(lit(5i32)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_substrait_switch() {
+        use datafusion::prelude::{col, lit, when};
+
+        // CASE x WHEN 1 THEN y = 1 ELSE y = 2 END
+        let switch = Expression {
+            rex_type: Some(RexType::SwitchExpression(Box::new(SwitchExpression {
+                r#match: Some(Box::new(agg_field_ref(0))),
+                ifs: vec![IfValue {
+                    r#if: Some(i32_literal(1)),
+                    then: Some(call(
+                        1,
+                        vec![value_arg(agg_field_ref(1)), value_arg(lit_expr(1))],
+                    )),
+                }],
+                r#else: Some(Box::new(call(
+                    1,
+                    vec![value_arg(agg_field_ref(1)), value_arg(lit_expr(2))],
+                ))),
+            }))),
+        };
+        let expr = parse_normalized(switch, &[(1, "equal")], false)
+            .await
+            .unwrap();
+        let expected = when(col("x").eq(lit(1i32)), col("y").eq(lit(1i32)))
+            .otherwise(col("y").eq(lit(2i32)))
+            .unwrap();
+        assert_eq!(expr, expected);
+    }
+
+    #[tokio::test]
+    async fn test_substrait_multi_or_list() {
+        use datafusion::prelude::{col, lit};
+
+        // (x, y) IN ((1, 2), (3, 4))
+        let in_list = Expression {
+            rex_type: Some(RexType::MultiOrList(MultiOrList {
+                value: vec![agg_field_ref(0), agg_field_ref(1)],
+                options: vec![
+                    Record {
+                        fields: vec![lit_expr(1), lit_expr(2)],
+                    },
+                    Record {
+                        fields: vec![lit_expr(3), lit_expr(4)],
+                    },
+                ],
+            })),
+        };
+        let expr = parse_normalized(in_list, &[], false).await.unwrap();
+        let expected = col("x")
+            .eq(lit(1i32))
+            .and(col("y").eq(lit(2i32)))
+            .or(col("x").eq(lit(3i32)).and(col("y").eq(lit(4i32))));
+        assert_eq!(expr, expected);
+    }
+
+    #[tokio::test]
+    async fn test_substrait_function_alias() {
+        use datafusion::functions::math::isnan;
+        use datafusion::prelude::col;
+
+        let is_nan = call(1, vec![value_arg(agg_field_ref(0))]);
+        let expr = parse_normalized(is_nan, &[(1, "is_nan:fp64")], false)
+            .await
+            .unwrap();
+        assert_eq!(expr, isnan().call(vec![col("x")]));
+    }
 }
@@ -10,6 +10,7 @@ use datafusion::prelude::SessionContext;
 use datafusion_functions::utils::make_scalar_function;
 use std::sync::{Arc, LazyLock};
 
+pub mod distance;
 pub mod json;
 
 /// Register UDF functions to datafusion context.
@@ -26,53 +27,14 @@ pub fn register_functions(ctx: &SessionContext) {
     ctx.register_udf(json::json_get_bool_udf());
     ctx.register_udf(json::json_array_contains_udf());
     ctx.register_udf(json::json_array_length_udf());
+    // Vector distance functions
+    ctx.register_udf(distance::l2_distance_udf());
+    ctx.register_udf(distance::cosine_distance_udf());
+    ctx.register_udf(distance::dot_distance_udf());
+    ctx.register_udf(distance::hamming_distance_udf());
+    ctx.register_udf(distance::dot_product_udf());
     // GEO functions
-    #[cfg(feature = "geo")]
     lance_geo::register_functions(ctx);
-    #[cfg(not(feature = "geo"))]
-    register_geo_stub_functions(ctx);
-}
-
-/// When the `geo` feature is disabled, register stub UDFs for spatial SQL functions
-/// so that users get a clear error mentioning the feature flag instead of
-/// DataFusion's generic "Unknown function" error.
-#[cfg(not(feature = "geo"))]
-fn register_geo_stub_functions(ctx: &SessionContext) {
-    let geo_funcs = [
-        "st_intersects",
-        "st_contains",
-        "st_within",
-        "st_touches",
-        "st_crosses",
-        "st_overlaps",
-        "st_covers",
-        "st_coveredby",
-        "st_distance",
-        "st_area",
-        "st_length",
-    ];
-
-    for name in geo_funcs {
-        let func_name = name.to_string();
-        let stub = Arc::new(make_scalar_function(
-            move |_args: &[ArrayRef]| {
-                Err(datafusion::error::DataFusionError::Plan(format!(
-                    "Function '{}' requires the `geo` feature. \
-                     Rebuild with `--features geo` to enable geospatial functions.",
-                    func_name
-                )))
-            },
-            vec![],
-        ));
-
-        ctx.register_udf(create_udf(
-            name,
-            vec![DataType::Binary, DataType::Binary],
-            DataType::Boolean,
-            Volatility::Immutable,
-            stub,
-        ));
-    }
 }
 
 /// This method checks whether a string contains all specified tokens. The tokens are separated by
@@ -90,7 +52,7 @@ fn register_geo_stub_functions(ctx: &SessionContext) {
 /// let ctx = SessionContext::new();
 /// ctx.register_table(
 ///     "table",
-///     Arc::new(LanceTableProvider::new(dataset, false, false)),
+///     Arc::new(LanceTableProvider::builder(dataset).build().await?),
 /// )?;
 /// register_functions(&ctx);
 /// let df = ctx.sql(sql).await?;
@@ -207,4 +169,336 @@ mod tests {
             panic!("Expected an Array but got {:?}", values);
         }
     }
+
+    #[cfg(not(feature = "geo"))]
+    #[tokio::test]
+    async fn test_fallback_geo_functions() {
+        use arrow_array::{Float64Array, RecordBatch};
+        use datafusion::prelude::SessionContext;
+
+        let ctx = SessionContext::new();
+        super::register_functions(&ctx);
+
+        let batches = ctx
+            .sql(
+                "WITH t AS (
+                    SELECT st_geomfromtext(column1) AS geom
+                    FROM (VALUES ('POINT(1 1)'), ('POINT(5 5)'), ('LINESTRING(0 0, 3 4)'), (NULL))
+                 )
+                 SELECT
+                    st_intersects(geom, st_geomfromtext('POLYGON((0 0, 2 0, 2 2, 0 2, 0 0))')) AS intersects,
+                    st_within(geom, st_geomfromtext('POLYGON((0 0, 2 0, 2 2, 0 2, 0 0))')) AS within,
+                    st_distance(geom, st_point(5, 5)) AS distance,
+                    st_length(geom) AS length,
+                    st_geometrytype(geom) AS geometry_type,
+                    st_astext(geom) AS text
+                 FROM t",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let batch = arrow_select::concat::concat_batches(&batches[0].schema(), &batches).unwrap();
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+
+        assert_eq!(
+            column("intersects").as_ref(),
+            &BooleanArray::from(vec![Some(true), Some(false), Some(true), None]) as &dyn Array
+        );
+        assert_eq!(
+            column("within").as_ref(),
+            &BooleanArray::from(vec![Some(true), Some(false), Some(false), None]) as &dyn Array
+        );
+        let distance = column("distance");
+        let distance = distance.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert!((distance.value(0) - 32f64.sqrt()).abs() < 1e-9);
+        assert_eq!(distance.value(1), 0.0);
+        assert!(distance.is_null(3));
+        assert_eq!(
+            column("length").as_ref(),
+            &Float64Array::from(vec![Some(0.0), Some(0.0), Some(5.0), None]) as &dyn Array
+        );
+        assert_eq!(
+            column("geometry_type").as_ref(),
+            &StringArray::from(vec![
+                Some("ST_Point"),
+                Some("ST_Point"),
+                Some("ST_LineString"),
+                None
+            ]) as &dyn Array
+        );
+        assert_eq!(
+            column("text").as_ref(),
+            &StringArray::from(vec![
+                Some("POINT(1 1)"),
+                Some("POINT(5 5)"),
+                Some("LINESTRING(0 0,3 4)"),
+                None
+            ]) as &dyn Array
+        );
+
+        let batch: RecordBatch = ctx
+            .sql("SELECT st_x(st_point(1.5, 2)) AS x, st_y(st_point(1.5, 2)) AS y, st_area(st_geomfromtext('POLYGON((0 0, 2 0, 2 2, 0 2, 0 0))')) AS area")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            batch.column(0).as_ref(),
+            &Float64Array::from(vec![1.5]) as &dyn Array
+        );
+        assert_eq!(
+            batch.column(1).as_ref(),
+            &Float64Array::from(vec![2.0]) as &dyn Array
+        );
+        assert_eq!(
+            batch.column(2).as_ref(),
+            &Float64Array::from(vec![4.0]) as &dyn Array
+        );
+    }
+
+    #[cfg(not(feature = "geo"))]
+    #[tokio::test]
+    async fn test_geometry_crs() {
+        use arrow_array::{BinaryArray, Float64Array, RecordBatch};
+        use arrow_schema::Schema;
+        use datafusion::prelude::SessionContext;
+        use lance_geo::crs::with_crs;
+
+        let ctx = SessionContext::new();
+        super::register_functions(&ctx);
+
+        let point = |x: f64, y: f64| {
+            let mut wkb = vec![1u8, 1, 0, 0, 0];
+            wkb.extend_from_slice(&x.to_le_bytes());
+            wkb.extend_from_slice(&y.to_le_bytes());
+            wkb
+        };
+        let table = |crs: &str, points: Vec<Vec<u8>>| {
+            let field = with_crs(Field::new("geom", DataType::Binary, true), crs);
+            let geometries = BinaryArray::from_iter_values(points);
+            RecordBatch::try_new(
+                Arc::new(Schema::new(vec![field])),
+                vec![Arc::new(geometries)],
+            )
+            .unwrap()
+        };
+        ctx.register_batch("lonlat", table("OGC:CRS84", vec![point(180.0, 0.0)]))
+            .unwrap();
+        ctx.register_batch(
+            "mercator",
+            table("EPSG:3857", vec![point(20037508.342789244, 0.0)]),
+        )
+        .unwrap();
+
+        let err = ctx
+            .sql("SELECT st_intersects(a.geom, b.geom) FROM lonlat a, mercator b")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("different coordinate reference systems (EPSG:4326 and EPSG:3857)"),
+            "{err}"
+        );
+
+        let batch = ctx
+            .sql(
+                "SELECT st_x(st_transform(geom, 3857)) AS x,
+                    st_y(st_transform(geom, 'epsg:3857')) AS y
+                 FROM lonlat",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap()
+            .remove(0);
+        let x = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert!((x.value(0) - 20037508.342789244).abs() < 1e-6);
+        let y = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert!(y.value(0).abs() < 1e-6);
+
+        let batch = ctx
+            .sql(
+                "SELECT st_distance(st_transform(a.geom, 3857), b.geom) AS distance,
+                    st_x(st_transform(b.geom, 'EPSG:3857', 'EPSG:4326')) AS lon
+                 FROM lonlat a, mercator b",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap()
+            .remove(0);
+        let distance = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert!(distance.value(0) < 1e-6);
+        let lon = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert!((lon.value(0) - 180.0).abs() < 1e-9);
+
+        let err = ctx
+            .sql("SELECT st_transform(geom, 'EPSG:27700') FROM lonlat")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not supported"), "{err}");
+    }
+
+    #[cfg(not(feature = "geo"))]
+    #[tokio::test]
+    async fn test_geometry_cells() {
+        use arrow_array::{RecordBatch, UInt64Array};
+        use datafusion::prelude::SessionContext;
+
+        let ctx = SessionContext::new();
+        super::register_functions(&ctx);
+
+        let batch: RecordBatch = ctx
+            .sql(
+                "WITH t AS (
+                    SELECT st_geomfromtext(column1) AS geom
+                    FROM (VALUES ('POINT(-5.6 42.6)'), ('LINESTRING(-5.7 42.5, -5.5 42.7)'), (NULL))
+                 )
+                 SELECT
+                    st_geohash(geom, 5) AS geohash,
+                    h3_cell_to_parent(st_h3cell(geom, 9), 7) AS parent,
+                    st_h3cell(geom, 7) AS cell,
+                    h3_cell_to_string(st_h3cell(geom, 7)) AS cell_string
+                 FROM t",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap()
+            .remove(0);
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+
+        assert_eq!(
+            column("geohash").as_ref(),
+            &StringArray::from(vec![Some("ezs42"), Some("ezs42"), None]) as &dyn Array
+        );
+        let cell = column("cell");
+        assert_eq!(column("parent").as_ref(), cell.as_ref());
+        let cell = cell.as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert!(cell.is_null(2));
+        let cell_string = column("cell_string");
+        let cell_string = cell_string.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(cell_string.value(0), format!("{:x}", cell.value(0)));
+
+        let err = ctx
+            .sql("SELECT st_geohash(st_point(0, 0), 13)")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("precision must be between 1 and 12"),
+            "{err}"
+        );
+        let err = ctx
+            .sql("SELECT st_h3cell(st_transform(st_point(0, 0), 4326, 3857), 7)")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("requires geometries in EPSG:4326"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_vector_distance() {
+        use arrow_array::types::Float32Type;
+        use arrow_array::{FixedSizeListArray, Float32Array, RecordBatch};
+        use datafusion::prelude::SessionContext;
+
+        let ctx = SessionContext::new();
+        super::register_functions(&ctx);
+        let vectors = FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+            vec![
+                Some(vec![Some(1.0), Some(0.0)]),
+                Some(vec![Some(0.0), Some(1.0)]),
+                Some(vec![Some(3.0), Some(4.0)]),
+                None,
+            ],
+            2,
+        );
+        let batch = RecordBatch::try_from_iter(vec![("vec", Arc::new(vectors) as _)]).unwrap();
+        ctx.register_batch("t", batch).unwrap();
+
+        let batch = ctx
+            .sql(
+                "SELECT
+                    l2_distance(vec, [1.0, 0.0]) AS l2,
+                    cosine_distance(vec, [1.0, 0.0]) AS cosine,
+                    dot_distance(vec, [1, 0]) AS dot,
+                    dot_product(vec, [1.0, 0.0]) AS dot_product,
+                    l2_distance(vec, vec) AS self
+                 FROM t",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap()
+            .remove(0);
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+        assert_eq!(
+            column("l2").as_ref(),
+            &Float32Array::from(vec![Some(0.0), Some(2.0), Some(20.0), None]) as &dyn Array
+        );
+        let cosine = column("cosine");
+        let cosine = cosine.as_any().downcast_ref::<Float32Array>().unwrap();
+        assert!((cosine.value(1) - 1.0).abs() < 1e-6);
+        assert!((cosine.value(2) - 0.4).abs() < 1e-6);
+        assert!(cosine.is_null(3));
+        assert_eq!(
+            column("dot").as_ref(),
+            &Float32Array::from(vec![Some(0.0), Some(1.0), Some(-2.0), None]) as &dyn Array
+        );
+        assert_eq!(
+            column("dot_product").as_ref(),
+            &Float32Array::from(vec![Some(1.0), Some(0.0), Some(3.0), None]) as &dyn Array
+        );
+        assert_eq!(
+            column("self").as_ref(),
+            &Float32Array::from(vec![Some(0.0), Some(0.0), Some(0.0), None]) as &dyn Array
+        );
+
+        let err = ctx
+            .sql("SELECT l2_distance(vec, 'abc') FROM t")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("list query vector"), "{err}");
+        let err = ctx
+            .sql("SELECT l2_distance(vec, [1.0, 0.0, 0.0]) FROM t")
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Cannot cast to FixedSizeList(2)"),
+            "{err}"
+        );
+    }
 }
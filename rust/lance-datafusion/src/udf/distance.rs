@@ -0,0 +1,234 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Vector distance functions.
+//!
+//! `l2_distance(vector, query)`, `cosine_distance(vector, query)`,
+//! `dot_distance(vector, query)` and `hamming_distance(vector, query)` compute
+//! the same distances as vector search, so `ORDER BY l2_distance(vector, query)
+//! LIMIT k` returns the nearest neighbors of `query`. Lance rewrites that shape
+//! into a vector search over the vector index when one is available.
+//!
+//! `dot_product(vector, query)` is the plain inner product of the two vectors.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, Float32Array};
+use arrow_schema::{DataType, Field};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion::scalar::ScalarValue;
+use lance_linalg::distance::{ArrowBatchDistanceFunc, DistanceType, dot_arrow_batch};
+
+/// The `l2_distance` function, the squared euclidean distance.
+pub fn l2_distance_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(VectorDistance::new(DistanceType::L2))
+}
+
+/// The `cosine_distance` function, one minus the cosine similarity.
+pub fn cosine_distance_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(VectorDistance::new(DistanceType::Cosine))
+}
+
+/// The `dot_distance` function, one minus the dot product.
+pub fn dot_distance_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(VectorDistance::new(DistanceType::Dot))
+}
+
+/// The `hamming_distance` function, the number of differing bits of two
+/// `uint8` vectors.
+pub fn hamming_distance_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(VectorDistance::new(DistanceType::Hamming))
+}
+
+/// The `dot_product` function, the inner product of two vectors.
+pub fn dot_product_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(DotProduct::new())
+}
+
+/// The distance from a fixed size list vector column to a query vector.
+///
+/// The query is cast to the type of the vector column, so it can be given as
+/// an array literal such as `[0.1, 0.2, 0.3]`.
+#[derive(Debug)]
+pub struct VectorDistance {
+    distance_type: DistanceType,
+    signature: Signature,
+}
+
+impl VectorDistance {
+    fn new(distance_type: DistanceType) -> Self {
+        Self {
+            distance_type,
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+
+    /// The distance computed by this function.
+    pub fn distance_type(&self) -> DistanceType {
+        self.distance_type
+    }
+}
+
+// `DistanceType` is neither `Eq` nor `Hash`
+impl PartialEq for VectorDistance {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance_type == other.distance_type
+    }
+}
+
+impl Eq for VectorDistance {}
+
+impl std::hash::Hash for VectorDistance {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.distance_type.to_string().hash(state);
+    }
+}
+
+impl ScalarUDFImpl for VectorDistance {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        match self.distance_type {
+            DistanceType::L2 => "l2_distance",
+            DistanceType::Cosine => "cosine_distance",
+            DistanceType::Dot => "dot_distance",
+            DistanceType::Hamming => "hamming_distance",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_vector_types(self.name(), arg_types)
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float32)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_vector_function(self.distance_type.arrow_batch_func(), &args)
+    }
+}
+
+/// The inner product of a fixed size list vector column and a query vector.
+///
+/// Unlike `dot_distance`, larger values mean more similar vectors, so this is
+/// not rewritten into a vector search.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct DotProduct {
+    signature: Signature,
+}
+
+impl DotProduct {
+    fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for DotProduct {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "dot_product"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_vector_types(self.name(), arg_types)
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float32)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        invoke_vector_function(dot_arrow_batch, &args)
+    }
+}
+
+/// Coerce the arguments of a vector function: a fixed size list vector and a
+/// list query of the same dimension and item type.
+fn coerce_vector_types(name: &str, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+    let [vector, query] = arg_types else {
+        return Err(DataFusionError::Plan(format!(
+            "{} expects a vector and a query vector, got {arg_types:?}",
+            name
+        )));
+    };
+    let DataType::FixedSizeList(item, dim) = vector else {
+        return Err(DataFusionError::Plan(format!(
+            "{} expects a fixed size list vector, got {vector}",
+            name
+        )));
+    };
+    if !matches!(
+        query,
+        DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(_, _) | DataType::Null
+    ) {
+        return Err(DataFusionError::Plan(format!(
+            "{} expects a list query vector, got {query}",
+            name
+        )));
+    }
+    // bfloat16 vectors are compared with float32 queries
+    let query_item = match item.data_type() {
+        DataType::FixedSizeBinary(2) => DataType::Float32,
+        data_type => data_type.clone(),
+    };
+    let query = DataType::FixedSizeList(Arc::new(Field::new("item", query_item, true)), *dim);
+    Ok(vec![vector.clone(), query])
+}
+
+/// Apply `func` to each vector and its query.
+fn invoke_vector_function(
+    func: ArrowBatchDistanceFunc,
+    args: &ScalarFunctionArgs,
+) -> Result<ColumnarValue> {
+    let (vectors, query) = (&args.args[0], &args.args[1]);
+    let result: ArrayRef = match (vectors, query) {
+        // The common case, one query against a column of vectors
+        (
+            ColumnarValue::Array(vectors),
+            ColumnarValue::Scalar(ScalarValue::FixedSizeList(query)),
+        ) if query.is_valid(0) => func(query.value(0).as_ref(), vectors.as_fixed_size_list())?,
+        _ => {
+            let vectors = vectors.to_array(args.number_rows)?;
+            let vectors = vectors.as_fixed_size_list();
+            let queries = query.to_array(args.number_rows)?;
+            let queries = queries.as_fixed_size_list();
+            let distances = (0..args.number_rows)
+                .map(|row| {
+                    if queries.is_null(row) || vectors.is_null(row) {
+                        return Ok(None);
+                    }
+                    let distances = func(queries.value(row).as_ref(), &vectors.slice(row, 1))?;
+                    Ok(Some(distances.value(0)))
+                })
+                .collect::<Result<Float32Array>>()?;
+            Arc::new(distances)
+        }
+    };
+    match (vectors, query) {
+        (ColumnarValue::Scalar(_), ColumnarValue::Scalar(_)) => Ok(ColumnarValue::Scalar(
+            ScalarValue::try_from_array(&result, 0)?,
+        )),
+        _ => Ok(ColumnarValue::Array(result)),
+    }
+}
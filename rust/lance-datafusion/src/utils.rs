@@ -245,6 +245,7 @@ pub const PARTS_LOADED_METRIC: &str = "parts_loaded";
 pub const PARTITIONS_RANKED_METRIC: &str = "partitions_ranked";
 pub const INDEX_COMPARISONS_METRIC: &str = "index_comparisons";
 pub const FRAGMENTS_SCANNED_METRIC: &str = "fragments_scanned";
+pub const FRAGMENTS_PRUNED_METRIC: &str = "fragments_pruned";
 pub const RANGES_SCANNED_METRIC: &str = "ranges_scanned";
 pub const ROWS_SCANNED_METRIC: &str = "rows_scanned";
 pub const TASK_WAIT_TIME_METRIC: &str = "task_wait_time";
@@ -20,9 +20,10 @@
 use crate::encodings::physical::bitpacking::{InlineBitpacking, OutOfLineBitpacking};
 use crate::{
     buffer::LanceBuffer,
-    compression_config::{BssMode, CompressionFieldParams, CompressionParams},
+    compression_config::{BssMode, CompressionFieldParams, CompressionParams, DeltaMode},
     constants::{
-        BSS_META_KEY, COMPRESSION_LEVEL_META_KEY, COMPRESSION_META_KEY, RLE_THRESHOLD_META_KEY,
+        BSS_META_KEY, COMPRESSION_LEVEL_META_KEY, COMPRESSION_META_KEY, DELTA_META_KEY,
+        RLE_THRESHOLD_META_KEY,
     },
     data::{DataBlock, FixedWidthDataBlock, VariableWidthBlock},
     encodings::{
@@ -40,6 +41,7 @@ use crate::{
                 ByteStreamSplitDecompressor, ByteStreamSplitEncoder, should_use_bss,
             },
             constant::ConstantDecompressor,
+            delta::{DeltaDecompressor, DeltaEncoder, estimate_delta_bytes, supports_delta},
             fsst::{
                 FsstMiniBlockDecompressor, FsstMiniBlockEncoder, FsstPerValueDecompressor,
                 FsstPerValueEncoder,
@@ -162,16 +164,63 @@ fn try_bss_for_mini_block(
     None
 }
 
+fn try_delta_for_mini_block(
+    data_type: &DataType,
+    data: &FixedWidthDataBlock,
+    version: LanceFileVersion,
+    params: &CompressionFieldParams,
+) -> Option<Box<dyn MiniBlockCompressor>> {
+    if version.resolve() < LanceFileVersion::V2_3
+        || !(data_type.is_integer() || data_type.is_temporal())
+        || !supports_delta(data.bits_per_value)
+        || data.num_values < 2
+    {
+        return None;
+    }
+
+    let order = match params.delta.unwrap_or(DeltaMode::Auto) {
+        DeltaMode::Off => return None,
+        DeltaMode::Delta => 1,
+        DeltaMode::DeltaOfDelta => 2,
+        DeltaMode::Auto => {
+            // Only pick delta encoding when it beats the encodings we would otherwise use
+            let mut best_alternative = data.data_size();
+            let run_count = data.expect_single_stat::<UInt64Type>(Stat::RunCount);
+            best_alternative =
+                best_alternative.min(run_count.saturating_mul(data.bits_per_value / 8 + 1));
+            #[cfg(feature = "bitpacking")]
+            if let Some(bitpack_bytes) = estimate_inline_bitpacking_bytes(data) {
+                best_alternative = best_alternative.min(bitpack_bytes);
+            }
+
+            let (order, delta_bytes) = [1, 2]
+                .into_iter()
+                .map(|order| (order, estimate_delta_bytes(data, order)))
+                .min_by_key(|(_, bytes)| *bytes)
+                .unwrap();
+            if delta_bytes >= best_alternative {
+                return None;
+            }
+            order
+        }
+    };
+    Some(Box::new(DeltaEncoder::new(order)))
+}
+
 fn try_rle_for_mini_block(
     data: &FixedWidthDataBlock,
+    version: LanceFileVersion,
     params: &CompressionFieldParams,
 ) -> Option<Box<dyn MiniBlockCompressor>> {
     let bits = data.bits_per_value;
-    if !matches!(bits, 8 | 16 | 32 | 64) {
+    // Boolean runs (e.g. flags) are supported starting with 2.3
+    let is_boolean = bits == 1 && version.resolve() >= LanceFileVersion::V2_3;
+    if !matches!(bits, 8 | 16 | 32 | 64) && !is_boolean {
         return None;
     }
 
-    let type_size = bits / 8;
+    // Boolean run values are stored in a byte
+    let type_size = bits.div_ceil(8);
     let run_count = data.expect_single_stat::<UInt64Type>(Stat::RunCount);
     let threshold = params
         .rle_threshold
@@ -196,7 +245,7 @@ fn try_rle_for_mini_block(
     let num_values = data.num_values;
     let estimated_pairs = (run_count.saturating_add(num_values / 255)).min(num_values);
 
-    let raw_bytes = (num_values as u128) * (type_size as u128);
+    let raw_bytes = (num_values as u128 * bits as u128).div_ceil(8);
     let rle_bytes = (estimated_pairs as u128) * ((type_size + 1) as u128);
 
     if rle_bytes < raw_bytes {
@@ -422,6 +471,16 @@ impl DefaultCompressionStrategy {
             }
         }
 
+        // Parse delta mode
+        if let Some(delta_str) = field.metadata.get(DELTA_META_KEY) {
+            match DeltaMode::parse(delta_str) {
+                Some(mode) => params.delta = Some(mode),
+                None => {
+                    log::warn!("Invalid delta mode '{}', using default", delta_str);
+                }
+            }
+        }
+
         // Parse minichunk size
         if let Some(minichunk_size_str) = field
             .metadata
@@ -448,6 +507,7 @@ impl DefaultCompressionStrategy {
 
     fn build_fixed_width_compressor(
         &self,
+        field: &Field,
         params: &CompressionFieldParams,
         data: &FixedWidthDataBlock,
     ) -> Result<Box<dyn MiniBlockCompressor>> {
@@ -456,7 +516,8 @@ impl DefaultCompressionStrategy {
         }
 
         let base = try_bss_for_mini_block(data, params)
-            .or_else(|| try_rle_for_mini_block(data, params))
+            .or_else(|| try_delta_for_mini_block(&field.data_type(), data, self.version, params))
+            .or_else(|| try_rle_for_mini_block(data, self.version, params))
             .or_else(|| try_bitpack_for_mini_block(data))
             .unwrap_or_else(|| Box::new(ValueEncoder::default()));
 
@@ -495,7 +556,13 @@ impl DefaultCompressionStrategy {
 
         // Choose base encoder (FSST or Binary) once.
         let mut base_encoder: Box<dyn MiniBlockCompressor> = if use_fsst {
-            Box::new(FsstMiniBlockEncoder::new(params.minichunk_size))
+            let encoder = FsstMiniBlockEncoder::new(params.minichunk_size);
+            // Automatically chosen FSST must earn its place, explicit FSST is always honored
+            if compression.is_none() {
+                Box::new(encoder.only_if_smaller())
+            } else {
+                Box::new(encoder)
+            }
         } else {
             Box::new(BinaryMiniBlockEncoder::new(params.minichunk_size))
         };
@@ -534,7 +601,7 @@ impl CompressionStrategy for DefaultCompressionStrategy {
         match data {
             DataBlock::FixedWidth(fixed_width_data) => {
                 let field_params = self.get_merged_field_params(field);
-                self.build_fixed_width_compressor(&field_params, fixed_width_data)
+                self.build_fixed_width_compressor(field, &field_params, fixed_width_data)
             }
             DataBlock::VariableWidth(variable_width_data) => {
                 self.build_variable_width_compressor(field, variable_width_data)
@@ -636,7 +703,12 @@ impl CompressionStrategy for DefaultCompressionStrategy {
 
                     // Use FSST if explicitly requested or if data characteristics warrant it.
                     if use_fsst {
-                        Ok(Box::new(FsstPerValueEncoder::new(variable_compression)))
+                        let encoder = FsstPerValueEncoder::new(variable_compression);
+                        if compression.is_none() {
+                            Ok(Box::new(encoder.only_if_smaller()))
+                        } else {
+                            Ok(Box::new(encoder))
+                        }
                     } else {
                         Ok(variable_compression)
                     }
@@ -828,6 +900,10 @@ impl DecompressionStrategy for DefaultDecompressionStrategy {
                     values.bits_per_value as usize,
                 )))
             }
+            Compression::Delta(delta) => Ok(Box::new(DeltaDecompressor::try_new(
+                delta.uncompressed_bits_per_value,
+                delta.order,
+            )?)),
             Compression::General(general) => {
                 // Create inner decompressor
                 let inner_decompressor = self.create_miniblock_decompressor(
@@ -1217,6 +1293,7 @@ mod tests {
                 compression_level: None,
                 bss: Some(BssMode::Off), // Explicitly disable BSS to test RLE
                 minichunk_size: None,
+                delta: None,
             },
         );
 
@@ -1249,6 +1326,7 @@ mod tests {
                 compression_level: Some(3),
                 bss: Some(BssMode::Off), // Disable BSS to test RLE
                 minichunk_size: None,
+                delta: None,
             },
         );
 
@@ -1528,6 +1606,7 @@ mod tests {
                 compression_level: Some(6),
                 bss: None,
                 minichunk_size: None,
+                delta: None,
             },
         );
 
@@ -1671,6 +1750,7 @@ mod tests {
                 compression_level: None,
                 bss: None,
                 minichunk_size: None,
+                delta: None,
             },
         );
 
@@ -1875,6 +1955,103 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_boolean_rle_selection() {
+        let field = create_test_field("flag", DataType::Boolean);
+        let values = (0..8192).map(|i| i < 4000).collect::<Vec<_>>();
+        let mut block = FixedWidthDataBlock {
+            bits_per_value: 1,
+            data: LanceBuffer::from(arrow_buffer::BooleanBuffer::from_iter(values).into_inner()),
+            num_values: 8192,
+            block_info: BlockInfo::default(),
+        };
+        block.compute_stat();
+        let data = DataBlock::FixedWidth(block);
+
+        for (version, expect_rle) in [
+            (LanceFileVersion::V2_2, false),
+            (LanceFileVersion::V2_3, true),
+        ] {
+            let strategy = DefaultCompressionStrategy::new().with_version(version);
+            let compressor = strategy.create_miniblock_compressor(&field, &data).unwrap();
+            assert_eq!(
+                format!("{:?}", compressor).contains("RleEncoder"),
+                expect_rle,
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_delta_selection() {
+        // Sorted timestamps where consecutive values differ by roughly a second
+        let values = (0..4096_i64)
+            .map(|i| 1_700_000_000_000 + i * 1000 + i % 7)
+            .collect::<Vec<_>>();
+        let mut block = FixedWidthDataBlock {
+            bits_per_value: 64,
+            data: LanceBuffer::reinterpret_vec(values),
+            num_values: 4096,
+            block_info: BlockInfo::default(),
+        };
+        block.compute_stat();
+        let data = DataBlock::FixedWidth(block);
+
+        let compressor_for = |version: LanceFileVersion, field: &Field| {
+            let strategy = DefaultCompressionStrategy::new().with_version(version);
+            let compressor = strategy.create_miniblock_compressor(field, &data).unwrap();
+            format!("{:?}", compressor)
+        };
+
+        let field = create_test_field("ts", DataType::Int64);
+        assert!(compressor_for(LanceFileVersion::V2_3, &field).contains("DeltaEncoder"));
+        // Older versions cannot read delta encoded pages
+        assert!(!compressor_for(LanceFileVersion::V2_2, &field).contains("DeltaEncoder"));
+        // Floating point values are never delta encoded
+        let float_field = create_test_field("ts", DataType::Float64);
+        assert!(!compressor_for(LanceFileVersion::V2_3, &float_field).contains("DeltaEncoder"));
+
+        let mut field = create_test_field("ts", DataType::Int64);
+        field
+            .metadata
+            .insert(DELTA_META_KEY.to_string(), "off".to_string());
+        assert!(!compressor_for(LanceFileVersion::V2_3, &field).contains("DeltaEncoder"));
+
+        // Random data is only delta encoded when requested explicitly
+        let mut block = FixedWidthDataBlock {
+            bits_per_value: 64,
+            data: LanceBuffer::reinterpret_vec(
+                (0..4096_u64)
+                    .map(|i| {
+                        // splitmix64 finalizer
+                        let x = i.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                        let x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+                        let x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+                        x ^ (x >> 31)
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            num_values: 4096,
+            block_info: BlockInfo::default(),
+        };
+        block.compute_stat();
+        let random = DataBlock::FixedWidth(block);
+        let strategy = DefaultCompressionStrategy::new().with_version(LanceFileVersion::V2_3);
+        let field = create_test_field("id", DataType::UInt64);
+        let compressor = strategy
+            .create_miniblock_compressor(&field, &random)
+            .unwrap();
+        assert!(!format!("{:?}", compressor).contains("DeltaEncoder"));
+        let mut field = create_test_field("id", DataType::UInt64);
+        field
+            .metadata
+            .insert(DELTA_META_KEY.to_string(), "delta-of-delta".to_string());
+        let compressor = strategy
+            .create_miniblock_compressor(&field, &random)
+            .unwrap();
+        assert!(format!("{:?}", compressor).contains("DeltaEncoder { order: 2 }"));
+    }
+
     #[test]
     fn test_rle_block_used_for_version_v2_2() {
         let field = create_test_field("test_repdef", DataType::UInt16);
@@ -10,6 +10,11 @@ use std::collections::HashMap;
 
 use arrow_schema::DataType;
 
+use crate::constants::{
+    BSS_META_KEY, COMPRESSION_LEVEL_META_KEY, COMPRESSION_META_KEY, DELTA_META_KEY,
+    MINICHUNK_SIZE_META_KEY, RLE_THRESHOLD_META_KEY,
+};
+
 /// Byte stream split encoding mode
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BssMode {
@@ -40,6 +45,51 @@ impl BssMode {
             _ => None,
         }
     }
+
+    /// The string form accepted by [`Self::parse`]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::On => "on",
+            Self::Auto => "auto",
+        }
+    }
+}
+
+/// Delta encoding mode for integer data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeltaMode {
+    /// Never use delta encoding
+    Off,
+    /// Always store the differences between consecutive values
+    Delta,
+    /// Always store the differences between consecutive deltas
+    DeltaOfDelta,
+    /// Use delta or delta-of-delta encoding when it is smaller than the alternatives
+    Auto,
+}
+
+impl DeltaMode {
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Some(Self::Off),
+            "delta" => Some(Self::Delta),
+            "delta-of-delta" => Some(Self::DeltaOfDelta),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+
+    /// The string form accepted by [`Self::parse`]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Delta => "delta",
+            Self::DeltaOfDelta => "delta-of-delta",
+            Self::Auto => "auto",
+        }
+    }
 }
 
 /// Compression parameter configuration
@@ -70,6 +120,9 @@ pub struct CompressionFieldParams {
 
     /// Minichunk size threshold for encoding
     pub minichunk_size: Option<i64>,
+
+    /// Delta encoding mode for integer data
+    pub delta: Option<DeltaMode>,
 }
 
 impl CompressionParams {
@@ -137,6 +190,39 @@ impl CompressionFieldParams {
         if other.minichunk_size.is_some() {
             self.minichunk_size = other.minichunk_size;
         }
+        if other.delta.is_some() {
+            self.delta = other.delta;
+        }
+    }
+
+    /// Convert the parameters into `lance-encoding:*` field metadata entries
+    ///
+    /// Field metadata is read back by the encoder, so recording parameters this way
+    /// keeps them attached to the column for every later write.
+    pub fn to_field_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        if let Some(compression) = &self.compression {
+            metadata.insert(COMPRESSION_META_KEY.to_string(), compression.clone());
+        }
+        if let Some(level) = self.compression_level {
+            metadata.insert(COMPRESSION_LEVEL_META_KEY.to_string(), level.to_string());
+        }
+        if let Some(threshold) = self.rle_threshold {
+            metadata.insert(RLE_THRESHOLD_META_KEY.to_string(), threshold.to_string());
+        }
+        if let Some(bss) = self.bss {
+            metadata.insert(BSS_META_KEY.to_string(), bss.as_str().to_string());
+        }
+        if let Some(minichunk_size) = self.minichunk_size {
+            metadata.insert(
+                MINICHUNK_SIZE_META_KEY.to_string(),
+                minichunk_size.to_string(),
+            );
+        }
+        if let Some(delta) = self.delta {
+            metadata.insert(DELTA_META_KEY.to_string(), delta.as_str().to_string());
+        }
+        metadata
     }
 }
 
@@ -204,6 +290,7 @@ mod tests {
             compression_level: None,
             bss: Some(BssMode::On),
             minichunk_size: None,
+            delta: None,
         };
 
         params.merge(&other);
@@ -218,6 +305,7 @@ mod tests {
             compression_level: Some(3),
             bss: Some(BssMode::Auto),
             minichunk_size: None,
+            delta: None,
         };
 
         params.merge(&another);
@@ -227,6 +315,32 @@ mod tests {
         assert_eq!(params.bss, Some(BssMode::Auto)); // Overridden
     }
 
+    #[test]
+    fn test_to_field_metadata() {
+        assert!(
+            CompressionFieldParams::default()
+                .to_field_metadata()
+                .is_empty()
+        );
+
+        let params = CompressionFieldParams {
+            compression: Some("zstd".to_string()),
+            compression_level: Some(9),
+            bss: Some(BssMode::Off),
+            delta: Some(DeltaMode::DeltaOfDelta),
+            ..Default::default()
+        };
+        let metadata = params.to_field_metadata();
+        assert_eq!(metadata.len(), 4);
+        assert_eq!(metadata[COMPRESSION_META_KEY], "zstd");
+        assert_eq!(metadata[COMPRESSION_LEVEL_META_KEY], "9");
+        assert_eq!(BssMode::parse(&metadata[BSS_META_KEY]), Some(BssMode::Off));
+        assert_eq!(
+            DeltaMode::parse(&metadata[DELTA_META_KEY]),
+            Some(DeltaMode::DeltaOfDelta)
+        );
+    }
+
     #[test]
     fn test_get_field_params() {
         let mut params = CompressionParams::new();
@@ -250,6 +364,7 @@ mod tests {
                 compression_level: Some(3),
                 bss: None,
                 minichunk_size: None,
+                delta: None,
             },
         );
 
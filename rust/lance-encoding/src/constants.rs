@@ -16,6 +16,17 @@ pub const RLE_THRESHOLD_META_KEY: &str = "lance-encoding:rle-threshold";
 /// Metadata key for specifying minichunk size
 pub const MINICHUNK_SIZE_META_KEY: &str = "lance-encoding:minichunk-size";
 
+// Page layout metadata keys
+/// Metadata key for specifying how many bytes of (unencoded) data to buffer before
+/// writing a page of the column, overrides the writer's per-column cache size
+pub const PAGE_SIZE_META_KEY: &str = "lance-encoding:page-size";
+/// Metadata key for specifying a number of rows that every page of the column (except
+/// the last) should be a multiple of
+pub const PAGE_ROW_ALIGNMENT_META_KEY: &str = "lance-encoding:page-row-alignment";
+/// Metadata key for specifying a size (in bytes) above which binary and string values are
+/// stored out-of-line, split into chunks, instead of inside the column's pages
+pub const LARGE_VALUE_THRESHOLD_META_KEY: &str = "lance-encoding:large-value-threshold";
+
 // Dictionary encoding metadata keys
 /// Metadata key for specifying dictionary encoding threshold divisor
 /// Set to a large value to discourage dictionary encoding
@@ -61,3 +72,7 @@ pub const STRUCTURAL_ENCODING_FULLZIP: &str = "fullzip";
 pub const BSS_META_KEY: &str = "lance-encoding:bss";
 /// Default BSS mode
 pub const DEFAULT_BSS_MODE: &str = "auto";
+
+// Delta encoding metadata keys
+/// Metadata key for delta encoding configuration ("off", "delta", "delta-of-delta", "auto")
+pub const DELTA_META_KEY: &str = "lance-encoding:delta";
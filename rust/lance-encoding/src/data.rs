@@ -22,7 +22,7 @@ use std::{
 use arrow_array::{
     Array, ArrayRef, OffsetSizeTrait, UInt64Array,
     cast::AsArray,
-    new_empty_array, new_null_array,
+    make_array, new_empty_array, new_null_array,
     types::{ArrowDictionaryKeyType, UInt8Type, UInt16Type, UInt32Type, UInt64Type},
 };
 use arrow_buffer::{ArrowNativeType, BooleanBuffer, BooleanBufferBuilder, NullBuffer};
@@ -774,11 +774,29 @@ impl DictionaryDataBlock {
     }
 
     fn into_arrow(self, data_type: DataType, validate: bool) -> Result<ArrayData> {
-        if let DataType::Dictionary(key_type, value_type) = data_type {
-            self.into_arrow_dict(key_type, value_type, validate)
-        } else {
-            self.decode()?.into_arrow(data_type, validate)
+        let DataType::Dictionary(key_type, value_type) = data_type else {
+            return self.decode()?.into_arrow(data_type, validate);
+        };
+        if key_type.byte_width() as u64 * 8 == self.indices.bits_per_value {
+            return self.into_arrow_dict(key_type, value_type, validate);
         }
+        // The indices were written with a different width than the requested key
+        // type, keep the dictionary and cast the keys
+        let index_type = match self.indices.bits_per_value {
+            8 => DataType::UInt8,
+            16 => DataType::UInt16,
+            32 => DataType::UInt32,
+            64 => DataType::UInt64,
+            bits_per_value => {
+                return Err(Error::internal(format!(
+                    "Unsupported dictionary index bit width: {} bits",
+                    bits_per_value
+                )));
+            }
+        };
+        let dict =
+            make_array(self.into_arrow_dict(Box::new(index_type), value_type.clone(), validate)?);
+        Ok(arrow_cast::cast(&dict, &DataType::Dictionary(key_type, value_type))?.to_data())
     }
 
     fn into_buffers(self) -> Vec<LanceBuffer> {
@@ -829,7 +847,17 @@ pub enum DataBlock {
 
 impl DataBlock {
     /// Convert self into an Arrow ArrayData
+    ///
+    /// A dictionary type can be requested for any block whose values can be converted
+    /// to the dictionary's value type.  Dictionary blocks keep their dictionary, other
+    /// blocks are dictionary encoded after conversion.
     pub fn into_arrow(self, data_type: DataType, validate: bool) -> Result<ArrayData> {
+        if let DataType::Dictionary(_, value_type) = &data_type
+            && !matches!(self, Self::Empty() | Self::AllNull(_) | Self::Dictionary(_))
+        {
+            let values = make_array(self.into_arrow(value_type.as_ref().clone(), validate)?);
+            return Ok(arrow_cast::cast(&values, &data_type)?.to_data());
+        }
         match self {
             Self::Empty() => Ok(new_empty_array(&data_type).to_data()),
             Self::Constant(inner) => inner.into_arrow(data_type, validate),
@@ -1666,7 +1694,7 @@ mod tests {
 
     use super::{AllNullDataBlock, DataBlock};
 
-    use arrow_array::Array;
+    use arrow_array::{Array, cast::AsArray};
 
     #[test]
     fn test_sliced_to_data_block() {
@@ -1864,6 +1892,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_into_arrow_as_dictionary() {
+        let dict_type = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let expected = StringArray::from(vec!["a", "b", "a", "c"]);
+
+        // Dictionary blocks with narrower indices than the requested key type
+        let dict = DictionaryArray::<Int8Type>::from_iter(["a", "b", "a", "c"]);
+        let data = DataBlock::from_array(dict);
+        let array = make_array(data.into_arrow(dict_type.clone(), true).unwrap());
+        assert_eq!(array.data_type(), &dict_type);
+        assert_eq!(array.as_dictionary::<Int32Type>().values().len(), 3);
+        let values = arrow_cast::cast(&array, &DataType::Utf8).unwrap();
+        assert_eq!(values.as_ref(), &expected as &dyn Array);
+
+        // Plain blocks are dictionary encoded
+        let data = DataBlock::from_array(expected.clone());
+        let array = make_array(data.into_arrow(dict_type.clone(), true).unwrap());
+        assert_eq!(array.data_type(), &dict_type);
+        let values = arrow_cast::cast(&array, &DataType::Utf8).unwrap();
+        assert_eq!(values.as_ref(), &expected as &dyn Array);
+    }
+
     #[test]
     fn test_dictionary_nulls() {
         // Test both ways of encoding nulls
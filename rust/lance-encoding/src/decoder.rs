@@ -226,7 +226,7 @@ use futures::stream::{self, BoxStream};
 use futures::{FutureExt, StreamExt};
 use lance_arrow::DataTypeExt;
 use lance_core::cache::LanceCache;
-use lance_core::datatypes::{BLOB_DESC_LANCE_FIELD, Field, Schema};
+use lance_core::datatypes::{BLOB_DESC_LANCE_FIELD, Field, LogicalType, Schema};
 use lance_core::utils::futures::{FinallyStreamExt, StreamOnDropExt};
 use lance_core::utils::parse::parse_env_as_bool;
 use log::{debug, trace, warn};
@@ -247,6 +247,7 @@ use crate::encodings::logical::primitive::StructuralPrimitiveFieldScheduler;
 use crate::encodings::logical::r#struct::{StructuralStructDecoder, StructuralStructScheduler};
 use crate::format::pb::{self, column_encoding};
 use crate::format::pb21;
+use crate::metrics::DecodeMetricsRecorder;
 use crate::previous::decoder::LogicalPageDecoder;
 use crate::previous::encodings::logical::list::OffsetPageInfo;
 use crate::previous::encodings::logical::r#struct::{SimpleStructDecoder, SimpleStructScheduler};
@@ -503,6 +504,7 @@ pub struct CoreFieldDecoderStrategy {
     pub validate_data: bool,
     pub decompressor_strategy: Arc<dyn DecompressionStrategy>,
     pub cache_repetition_index: bool,
+    pub decode_metrics: Option<Arc<dyn DecodeMetricsRecorder>>,
 }
 
 impl Default for CoreFieldDecoderStrategy {
@@ -511,6 +513,7 @@ impl Default for CoreFieldDecoderStrategy {
             validate_data: false,
             decompressor_strategy: Arc::new(DefaultDecompressionStrategy {}),
             cache_repetition_index: false,
+            decode_metrics: None,
         }
     }
 }
@@ -528,6 +531,7 @@ impl CoreFieldDecoderStrategy {
             validate_data: config.validate_on_decode,
             decompressor_strategy: Arc::new(DefaultDecompressionStrategy {}),
             cache_repetition_index: config.cache_repetition_index,
+            decode_metrics: config.decode_metrics.clone(),
         }
     }
 
@@ -730,6 +734,7 @@ impl CoreFieldDecoderStrategy {
                 self.decompressor_strategy.as_ref(),
                 self.cache_repetition_index,
                 field,
+                self.decode_metrics.clone(),
             )?);
 
             // advance to the next top level column
@@ -738,6 +743,14 @@ impl CoreFieldDecoderStrategy {
             return Ok(scheduler);
         }
         match &data_type {
+            DataType::RunEndEncoded(_, values)
+                if Self::is_structural_primitive(values.data_type()) =>
+            {
+                // The column stores the values, the decoder encodes them into runs
+                let mut value_field = field.clone();
+                value_field.logical_type = LogicalType::try_from(values.data_type())?;
+                self.create_structural_field_scheduler(&value_field, column_infos)
+            }
             DataType::Struct(fields) => {
                 if field.is_packed_struct() {
                     // Packed struct
@@ -747,6 +760,7 @@ impl CoreFieldDecoderStrategy {
                         self.decompressor_strategy.as_ref(),
                         self.cache_repetition_index,
                         field,
+                        self.decode_metrics.clone(),
                     )?);
 
                     // advance to the next top level column
@@ -771,6 +785,7 @@ impl CoreFieldDecoderStrategy {
                             self.decompressor_strategy.as_ref(),
                             self.cache_repetition_index,
                             field,
+                            self.decode_metrics.clone(),
                         )?);
                         column_infos.next_top_level();
                         return Ok(scheduler);
@@ -1730,6 +1745,7 @@ fn estimate_bytes_per_row(data_type: &DataType) -> f64 {
             *dim as f64 * estimate_bytes_per_row(child.data_type())
         }
         DataType::Dictionary(_, value_type) => estimate_bytes_per_row(value_type),
+        DataType::RunEndEncoded(_, values) => estimate_bytes_per_row(values.data_type()),
         DataType::Map(entries, _) => 5.0 * estimate_bytes_per_row(entries.data_type()),
         _ => 64.0,
     }
@@ -1989,6 +2005,11 @@ pub struct DecoderConfig {
     /// * `Some(false)` - always spawn a task for scheduling so that it can
     ///   overlap with consumption of the decode stream.
     pub inline_scheduling: Option<bool>,
+    /// If set, the number of pages decoded, the decoded bytes and the decode time of
+    /// every column are recorded here, see [`crate::metrics`]
+    ///
+    /// Only files with version 2.1 or later report decode metrics.
+    pub decode_metrics: Option<Arc<dyn DecodeMetricsRecorder>>,
 }
 
 impl Default for DecoderConfig {
@@ -1997,6 +2018,7 @@ impl Default for DecoderConfig {
             cache_repetition_index: default_cache_repetition_index(),
             validate_on_decode: false,
             inline_scheduling: None,
+            decode_metrics: None,
         }
     }
 }
@@ -19,7 +19,7 @@ use arrow_array::{Array, ArrayRef, RecordBatch};
 use arrow_schema::DataType;
 use bytes::{Bytes, BytesMut};
 use futures::future::BoxFuture;
-use lance_core::datatypes::{Field, Schema};
+use lance_core::datatypes::{Field, LogicalType, Schema};
 use lance_core::error::LanceOptionExt;
 use lance_core::utils::bit::{is_pwr_two, pad_bytes_to};
 use lance_core::{Error, Result};
@@ -27,12 +27,16 @@ use lance_core::{Error, Result};
 use crate::buffer::LanceBuffer;
 use crate::compression::{CompressionStrategy, DefaultCompressionStrategy};
 use crate::compression_config::CompressionParams;
+use crate::constants::LARGE_VALUE_THRESHOLD_META_KEY;
 use crate::decoder::PageEncoding;
-use crate::encodings::logical::blob::{BlobStructuralEncoder, BlobV2StructuralEncoder};
+use crate::encodings::logical::blob::{
+    BlobStructuralEncoder, BlobV2StructuralEncoder, ChunkedBinaryStructuralEncoder,
+};
 use crate::encodings::logical::fixed_size_list::FixedSizeListStructuralEncoder;
 use crate::encodings::logical::list::ListStructuralEncoder;
 use crate::encodings::logical::map::MapStructuralEncoder;
 use crate::encodings::logical::primitive::PrimitiveStructuralEncoder;
+use crate::encodings::logical::run_end::RunEndEncodedStructuralEncoder;
 use crate::encodings::logical::r#struct::StructStructuralEncoder;
 use crate::repdef::RepDefBuilder;
 use crate::version::LanceFileVersion;
@@ -122,6 +126,28 @@ impl OutOfLineBuffers {
         position
     }
 
+    /// Adds a buffer that is written in chunks of (roughly) `max_chunk_size` bytes
+    ///
+    /// The chunk size is rounded down to a multiple of the buffer alignment so that no
+    /// padding is inserted between chunks.  The chunks are contiguous in the file and the
+    /// returned position is the position of the first chunk.
+    pub fn add_chunked_buffer(&mut self, buffer: LanceBuffer, max_chunk_size: u64) -> u64 {
+        let chunk_size =
+            (max_chunk_size - max_chunk_size % self.buffer_alignment).max(self.buffer_alignment);
+        let chunk_size = chunk_size as usize;
+        if buffer.len() <= chunk_size {
+            return self.add_buffer(buffer);
+        }
+        let position = self.position;
+        let mut offset = 0;
+        while offset < buffer.len() {
+            let length = chunk_size.min(buffer.len() - offset);
+            self.add_buffer(buffer.slice_with_length(offset, length));
+            offset += length;
+        }
+        position
+    }
+
     pub fn take_buffers(self) -> Vec<LanceBuffer> {
         self.buffers
     }
@@ -238,6 +264,14 @@ pub struct EncodingOptions {
 
     /// The Lance file version being written
     pub version: LanceFileVersion,
+    /// Pages (except the last page of a column) hold a multiple of this many rows
+    ///
+    /// Aligning pages with the batch size used by readers avoids batches that straddle
+    /// pages.  This is best-effort: pages of nested columns are only aligned if the rows
+    /// happen to line up and very large pages may still be split.  Only used by 2.1+
+    /// files.  Can be overridden per column with the `lance-encoding:page-row-alignment`
+    /// field metadata.
+    pub page_row_alignment: u64,
 }
 
 impl Default for EncodingOptions {
@@ -248,6 +282,7 @@ impl Default for EncodingOptions {
             keep_original_array: true,
             buffer_alignment: 64,
             version: LanceFileVersion::default(),
+            page_row_alignment: 1,
         }
     }
 }
@@ -426,6 +461,24 @@ impl StructuralEncodingStrategy {
             }
         }
 
+        if matches!(
+            data_type,
+            DataType::Binary | DataType::LargeBinary | DataType::Utf8 | DataType::LargeUtf8
+        ) && let Some(threshold) = PrimitiveStructuralEncoder::page_layout_option(
+            field,
+            &field.metadata,
+            LARGE_VALUE_THRESHOLD_META_KEY,
+        )? {
+            return Ok(Box::new(ChunkedBinaryStructuralEncoder::try_new(
+                options,
+                self.compression_strategy.clone(),
+                column_index.next_column_index(field.id as u32),
+                field.clone(),
+                Arc::new(root_field_metadata.clone()),
+                threshold,
+            )?));
+        }
+
         if Self::is_primitive_type(&data_type) {
             Ok(Box::new(PrimitiveStructuralEncoder::try_new(
                 options,
@@ -569,6 +622,25 @@ impl StructuralEncodingStrategy {
                         Err(Error::not_supported_source(format!("cannot encode a dictionary column whose value type is a logical type ({})", value_type).into()))
                     }
                 }
+                DataType::RunEndEncoded(_, values) => {
+                    if !Self::is_primitive_type(values.data_type()) {
+                        return Err(Error::not_supported_source(format!("cannot encode a run-end encoded column whose value type is a logical type ({})", values.data_type()).into()));
+                    }
+                    // The runs are expanded and stored as a column of the value type, the
+                    // compression strategy picks up the runs (e.g. with RLE)
+                    let mut value_field = field.clone();
+                    value_field.logical_type = LogicalType::try_from(values.data_type())?;
+                    let values_encoder = PrimitiveStructuralEncoder::try_new(
+                        options,
+                        self.compression_strategy.clone(),
+                        column_index.next_column_index(field.id as u32),
+                        value_field,
+                        Arc::new(root_field_metadata.clone()),
+                    )?;
+                    Ok(Box::new(RunEndEncodedStructuralEncoder::new(Box::new(
+                        values_encoder,
+                    ))))
+                }
                 _ => todo!("Implement encoding for field {}", field),
             }
         }
@@ -783,6 +855,7 @@ mod tests {
                 compression_level: None,
                 bss: None,
                 minichunk_size: None,
+                delta: None,
             },
         );
 
@@ -6,4 +6,5 @@ pub mod fixed_size_list;
 pub mod list;
 pub mod map;
 pub mod primitive;
+pub mod run_end;
 pub mod r#struct;
@@ -4,7 +4,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use arrow_array::{
-    Array, ArrayRef, StructArray, UInt64Array,
+    Array, ArrayRef, OffsetSizeTrait, StructArray, UInt64Array,
     builder::{PrimitiveBuilder, StringBuilder},
     cast::AsArray,
     types::{UInt8Type, UInt32Type, UInt64Type},
@@ -37,6 +37,8 @@ pub struct BlobStructuralEncoder {
     descriptor_encoder: Box<dyn FieldEncoder>,
     // Set when we first see data
     def_meaning: Option<Arc<[DefinitionInterpretation]>>,
+    // Values larger than this are written to the file in several chunks
+    max_chunk_size: u64,
 }
 
 impl BlobStructuralEncoder {
@@ -74,6 +76,7 @@ impl BlobStructuralEncoder {
         Ok(Self {
             descriptor_encoder,
             def_meaning: None,
+            max_chunk_size: options.max_page_bytes,
         })
     }
 
@@ -170,9 +173,12 @@ impl FieldEncoder for BlobStructuralEncoder {
                     positions.push(0);
                     sizes.push(0);
                 } else {
-                    // Add data to external buffers
-                    let position =
-                        external_buffers.add_buffer(LanceBuffer::from(Buffer::from(value)));
+                    // Add data to external buffers, the chunks are contiguous so a single
+                    // description still covers the entire value
+                    let position = external_buffers.add_chunked_buffer(
+                        LanceBuffer::from(Buffer::from(value)),
+                        self.max_chunk_size,
+                    );
                     positions.push(position);
                     sizes.push(value.len() as u64);
                 }
@@ -228,6 +234,141 @@ impl FieldEncoder for BlobStructuralEncoder {
     }
 }
 
+/// Structural encoder for binary and string columns that contain some very large values
+///
+/// Batches where every value is at most `threshold` bytes are encoded into regular pages.
+/// Batches with a larger value are encoded like a blob column: the values are written out
+/// of line (in chunks of at most `max_page_bytes`) and the pages only hold the position and
+/// size of each value.  The column is still read back as binary / string data.
+///
+/// Any buffered data is flushed when switching between the two so that pages stay in row
+/// order.
+pub struct ChunkedBinaryStructuralEncoder {
+    inline_encoder: PrimitiveStructuralEncoder,
+    out_of_line_encoder: BlobStructuralEncoder,
+    threshold: u64,
+    // True if the last batch was given to the out-of-line encoder
+    out_of_line: bool,
+}
+
+impl ChunkedBinaryStructuralEncoder {
+    pub fn try_new(
+        options: &crate::encoder::EncodingOptions,
+        compression_strategy: Arc<dyn crate::compression::CompressionStrategy>,
+        column_index: u32,
+        field: Field,
+        field_metadata: Arc<HashMap<String, String>>,
+        threshold: u64,
+    ) -> Result<Self> {
+        let out_of_line_encoder = BlobStructuralEncoder::new(
+            &field,
+            column_index,
+            options,
+            compression_strategy.clone(),
+        )?;
+        let inline_encoder = PrimitiveStructuralEncoder::try_new(
+            options,
+            compression_strategy,
+            column_index,
+            field,
+            field_metadata,
+        )?;
+        Ok(Self {
+            inline_encoder,
+            out_of_line_encoder,
+            threshold,
+            out_of_line: false,
+        })
+    }
+
+    fn has_large_value(&self, array: &dyn Array) -> Result<bool> {
+        fn any_larger<O: OffsetSizeTrait>(offsets: &[O], threshold: u64) -> bool {
+            offsets
+                .windows(2)
+                .any(|w| (w[1] - w[0]).as_usize() as u64 > threshold)
+        }
+        Ok(match array.data_type() {
+            DataType::Binary => {
+                any_larger(array.as_binary::<i32>().value_offsets(), self.threshold)
+            }
+            DataType::LargeBinary => {
+                any_larger(array.as_binary::<i64>().value_offsets(), self.threshold)
+            }
+            DataType::Utf8 => any_larger(array.as_string::<i32>().value_offsets(), self.threshold),
+            DataType::LargeUtf8 => {
+                any_larger(array.as_string::<i64>().value_offsets(), self.threshold)
+            }
+            data_type => {
+                return Err(Error::invalid_input(format!(
+                    "Chunked binary encoding does not support {}",
+                    data_type
+                )));
+            }
+        })
+    }
+}
+
+impl FieldEncoder for ChunkedBinaryStructuralEncoder {
+    fn maybe_encode(
+        &mut self,
+        array: ArrayRef,
+        external_buffers: &mut OutOfLineBuffers,
+        repdef: RepDefBuilder,
+        row_number: u64,
+        num_rows: u64,
+    ) -> Result<Vec<EncodeTask>> {
+        let out_of_line = self.has_large_value(array.as_ref())?;
+        let mut tasks = Vec::new();
+        if out_of_line != self.out_of_line {
+            if self.out_of_line {
+                tasks.extend(self.out_of_line_encoder.flush(external_buffers)?);
+            } else {
+                tasks.extend(self.inline_encoder.flush(external_buffers)?);
+            }
+            self.out_of_line = out_of_line;
+        }
+        if out_of_line {
+            let array = arrow_cast::cast(&array, &DataType::LargeBinary)?;
+            tasks.extend(self.out_of_line_encoder.maybe_encode(
+                array,
+                external_buffers,
+                repdef,
+                row_number,
+                num_rows,
+            )?);
+        } else {
+            tasks.extend(self.inline_encoder.maybe_encode(
+                array,
+                external_buffers,
+                repdef,
+                row_number,
+                num_rows,
+            )?);
+        }
+        Ok(tasks)
+    }
+
+    fn flush(&mut self, external_buffers: &mut OutOfLineBuffers) -> Result<Vec<EncodeTask>> {
+        if self.out_of_line {
+            self.out_of_line_encoder.flush(external_buffers)
+        } else {
+            self.inline_encoder.flush(external_buffers)
+        }
+    }
+
+    fn finish(
+        &mut self,
+        external_buffers: &mut OutOfLineBuffers,
+    ) -> BoxFuture<'_, Result<Vec<EncodedColumn>>> {
+        // Both encoders write pages to the same column and neither adds any column metadata
+        self.inline_encoder.finish(external_buffers)
+    }
+
+    fn num_columns(&self) -> u32 {
+        1
+    }
+}
+
 /// Blob v2 structural encoder
 pub struct BlobV2StructuralEncoder {
     descriptor_encoder: Box<dyn FieldEncoder>,
@@ -436,6 +577,7 @@ mod tests {
     use super::*;
     use crate::{
         compression::DefaultCompressionStrategy,
+        constants::LARGE_VALUE_THRESHOLD_META_KEY,
         encoder::{ColumnIndexSequence, EncodingOptions},
         testing::{
             TestCases, check_round_trip_encoding_of_data,
@@ -535,6 +677,98 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_chunked_binary_large_values() {
+        let field = Field::try_from(ArrowField::new("data", DataType::LargeBinary, true)).unwrap();
+        let options = EncodingOptions {
+            max_page_bytes: 4096,
+            ..Default::default()
+        };
+        let mut encoder = ChunkedBinaryStructuralEncoder::try_new(
+            &options,
+            Arc::new(DefaultCompressionStrategy::new()),
+            0,
+            field,
+            Arc::new(HashMap::new()),
+            1024,
+        )
+        .unwrap();
+
+        // Nothing is written out-of-line when all values are small
+        let small: Vec<Option<&[u8]>> = vec![Some(b"abc"), None, Some(b"")];
+        let mut external_buffers = OutOfLineBuffers::new(0, 8);
+        encoder
+            .maybe_encode(
+                Arc::new(LargeBinaryArray::from(small)),
+                &mut external_buffers,
+                RepDefBuilder::default(),
+                0,
+                3,
+            )
+            .unwrap();
+        assert!(external_buffers.take_buffers().is_empty());
+
+        // A 10000 byte value is written as three contiguous chunks
+        let large_data = vec![7u8; 10000];
+        let large: Vec<Option<&[u8]>> = vec![Some(b"abc"), Some(&large_data)];
+        let mut external_buffers = OutOfLineBuffers::new(0, 8);
+        let tasks = encoder
+            .maybe_encode(
+                Arc::new(LargeBinaryArray::from(large)),
+                &mut external_buffers,
+                RepDefBuilder::default(),
+                3,
+                2,
+            )
+            .unwrap();
+        // Switching to out-of-line storage flushes the pending small values
+        assert_eq!(tasks.len(), 1);
+        let buffers = external_buffers.take_buffers();
+        let sizes = buffers.iter().map(|b| b.len()).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![3, 4096, 4096, 1808]);
+    }
+
+    #[rstest::rstest]
+    #[case::binary(DataType::Binary)]
+    #[case::large_binary(DataType::LargeBinary)]
+    #[case::utf8(DataType::Utf8)]
+    #[case::large_utf8(DataType::LargeUtf8)]
+    #[tokio::test]
+    async fn test_chunked_binary_round_trip(#[case] data_type: DataType) {
+        let metadata = HashMap::from([(
+            LARGE_VALUE_THRESHOLD_META_KEY.to_string(),
+            "1000".to_string(),
+        )]);
+
+        let large = "x".repeat(20000);
+        let small_batch = StringArray::from(vec![Some("a"), None, Some("bcd")]);
+        let large_batch = StringArray::from(vec![
+            Some("abc"),
+            Some(large.as_str()),
+            None,
+            Some(""),
+            Some(&large[..5000]),
+        ]);
+        let data = vec![
+            small_batch.clone(),
+            large_batch.clone(),
+            large_batch,
+            small_batch,
+        ]
+        .into_iter()
+        .map(|array| arrow_cast::cast(&array, &data_type).unwrap())
+        .collect::<Vec<_>>();
+
+        check_round_trip_encoding_of_data(
+            data,
+            &TestCases::default()
+                .with_min_file_version(LanceFileVersion::V2_1)
+                .with_max_page_size(4096),
+            metadata,
+        )
+        .await;
+    }
+
     #[tokio::test]
     async fn test_blob_v2_external_round_trip() {
         let blob_metadata = HashMap::from([(
@@ -9,12 +9,14 @@ use std::{
     iter,
     ops::Range,
     sync::Arc,
+    time::Instant,
     vec,
 };
 
 use crate::{
     constants::{
-        STRUCTURAL_ENCODING_FULLZIP, STRUCTURAL_ENCODING_META_KEY, STRUCTURAL_ENCODING_MINIBLOCK,
+        PAGE_ROW_ALIGNMENT_META_KEY, PAGE_SIZE_META_KEY, STRUCTURAL_ENCODING_FULLZIP,
+        STRUCTURAL_ENCODING_META_KEY, STRUCTURAL_ENCODING_MINIBLOCK,
     },
     data::DictionaryDataBlock,
     encodings::logical::primitive::blob::{BlobDescriptionPageScheduler, BlobPageScheduler},
@@ -22,6 +24,7 @@ use crate::{
         ProtobufUtils21,
         pb21::{self, CompressiveEncoding, PageLayout, compressive_encoding::Compression},
     },
+    metrics::DecodeMetricsRecorder,
 };
 use arrow_array::{Array, ArrayRef, PrimitiveArray, cast::AsArray, make_array, types::UInt64Type};
 use arrow_buffer::{BooleanBuffer, BooleanBufferBuilder, NullBuffer, ScalarBuffer};
@@ -3265,8 +3268,18 @@ impl StructuralSchedulingJob for StructuralPrimitiveFieldSchedulingJob<'_> {
             .map(|page_load_task| {
                 let cur_path = cur_path.clone();
                 let page_decoder = page_load_task.decoder_fut;
+                let column_index = self.scheduler.column_index;
+                let decode_metrics = self.scheduler.decode_metrics.clone();
                 let unloaded_page = async move {
-                    let page_decoder = page_decoder.await?;
+                    let mut page_decoder = page_decoder.await?;
+                    if let Some(decode_metrics) = decode_metrics {
+                        decode_metrics.record_page_loaded(column_index);
+                        page_decoder = Box::new(MeteredPageDecoder {
+                            inner: page_decoder,
+                            column_index,
+                            decode_metrics,
+                        });
+                    }
                     Ok(LoadedPageShard {
                         decoder: page_decoder,
                         path: cur_path,
@@ -3282,6 +3295,48 @@ impl StructuralSchedulingJob for StructuralPrimitiveFieldSchedulingJob<'_> {
     }
 }
 
+/// Wraps a page decoder to record the time spent decoding and the decoded bytes
+#[derive(Debug)]
+struct MeteredPageDecoder {
+    inner: Box<dyn StructuralPageDecoder>,
+    column_index: u32,
+    decode_metrics: Arc<dyn DecodeMetricsRecorder>,
+}
+
+impl StructuralPageDecoder for MeteredPageDecoder {
+    fn drain(&mut self, num_rows: u64) -> Result<Box<dyn DecodePageTask>> {
+        Ok(Box::new(MeteredDecodePageTask {
+            inner: self.inner.drain(num_rows)?,
+            column_index: self.column_index,
+            decode_metrics: self.decode_metrics.clone(),
+        }))
+    }
+
+    fn num_rows(&self) -> u64 {
+        self.inner.num_rows()
+    }
+}
+
+#[derive(Debug)]
+struct MeteredDecodePageTask {
+    inner: Box<dyn DecodePageTask>,
+    column_index: u32,
+    decode_metrics: Arc<dyn DecodeMetricsRecorder>,
+}
+
+impl DecodePageTask for MeteredDecodePageTask {
+    fn decode(self: Box<Self>) -> Result<DecodedPage> {
+        let start = Instant::now();
+        let decoded = self.inner.decode()?;
+        self.decode_metrics.record_decode(
+            self.column_index,
+            decoded.data.data_size(),
+            start.elapsed(),
+        );
+        Ok(decoded)
+    }
+}
+
 #[derive(Debug)]
 struct PageInfoAndScheduler {
     page_index: usize,
@@ -3297,6 +3352,7 @@ struct PageInfoAndScheduler {
 pub struct StructuralPrimitiveFieldScheduler {
     page_schedulers: Vec<PageInfoAndScheduler>,
     column_index: u32,
+    decode_metrics: Option<Arc<dyn DecodeMetricsRecorder>>,
     // Identifies the requested decode shape (e.g. blob descriptor struct vs
     // raw bytes). Blob columns can produce multiple page scheduler variants
     // for the same physical column depending on the target field's data type,
@@ -3311,6 +3367,7 @@ impl StructuralPrimitiveFieldScheduler {
         decompressors: &dyn DecompressionStrategy,
         cache_repetition_index: bool,
         target_field: &Field,
+        decode_metrics: Option<Arc<dyn DecodeMetricsRecorder>>,
     ) -> Result<Self> {
         let page_schedulers = column_info
             .page_infos
@@ -3329,6 +3386,7 @@ impl StructuralPrimitiveFieldScheduler {
         Ok(Self {
             page_schedulers,
             column_index: column_info.index,
+            decode_metrics,
             view_tag: format!("{:?}", target_field.data_type()),
         })
     }
@@ -3426,11 +3484,16 @@ impl StructuralPrimitiveFieldScheduler {
                     ))
                 } else {
                     // User wants to decode blob into binary data
+                    let bits_per_offset = match target_field.data_type() {
+                        DataType::Binary | DataType::Utf8 => 32,
+                        _ => 64,
+                    };
                     Box::new(BlobPageScheduler::new(
                         inner_scheduler,
                         page_info.priority,
                         page_info.num_rows,
                         def_meaning.into(),
+                        bits_per_offset,
                     ))
                 }
             }
@@ -3600,6 +3663,12 @@ impl StructuralCompositeDecodeArrayTask {
 
 impl StructuralDecodeArrayTask for StructuralCompositeDecodeArrayTask {
     fn decode(self: Box<Self>) -> Result<DecodedArray> {
+        // Run-end encoded arrays are decoded as their values (which carry the validity)
+        // and encoded into runs at the end
+        let value_type = match &self.data_type {
+            DataType::RunEndEncoded(_, values) => values.data_type().clone(),
+            data_type => data_type.clone(),
+        };
         let mut arrays = Vec::with_capacity(self.tasks.len());
         let mut unravelers = Vec::with_capacity(self.tasks.len());
         let mut data_size = 0u64;
@@ -3611,7 +3680,7 @@ impl StructuralDecodeArrayTask for StructuralCompositeDecodeArrayTask {
             let array = make_array(
                 decoded
                     .data
-                    .into_arrow(self.data_type.clone(), self.should_validate)?,
+                    .into_arrow(value_type.clone(), self.should_validate)?,
             );
 
             arrays.push(array);
@@ -3621,6 +3690,11 @@ impl StructuralDecodeArrayTask for StructuralCompositeDecodeArrayTask {
         let mut repdef = CompositeRepDefUnraveler::new(unravelers);
 
         let array = Self::restore_validity(array, &mut repdef);
+        let array = if value_type != self.data_type {
+            arrow_cast::cast(&array, &self.data_type)?
+        } else {
+            array
+        };
 
         Ok(DecodedArray {
             array,
@@ -3827,12 +3901,18 @@ impl PrimitiveStructuralEncoder {
         field: Field,
         encoding_metadata: Arc<HashMap<String, String>>,
     ) -> Result<Self> {
+        let page_size = Self::page_layout_option(&field, &encoding_metadata, PAGE_SIZE_META_KEY)?
+            .unwrap_or(options.cache_bytes_per_column);
+        let row_alignment =
+            Self::page_layout_option(&field, &encoding_metadata, PAGE_ROW_ALIGNMENT_META_KEY)?
+                .unwrap_or(options.page_row_alignment);
         Ok(Self {
             accumulation_queue: AccumulationQueue::new(
-                options.cache_bytes_per_column,
+                page_size,
                 column_index,
                 options.keep_original_array,
-            ),
+            )
+            .with_row_alignment(row_alignment),
             support_large_chunk: options.support_large_chunk(),
             keep_original_array: options.keep_original_array,
             accumulated_repdefs: Vec::new(),
@@ -3844,6 +3924,24 @@ impl PrimitiveStructuralEncoder {
         })
     }
 
+    // Parses a positive integer page layout setting from the (top-level) field metadata
+    pub(crate) fn page_layout_option(
+        field: &Field,
+        encoding_metadata: &HashMap<String, String>,
+        key: &str,
+    ) -> Result<Option<u64>> {
+        encoding_metadata
+            .get(key)
+            .map(|value| match value.parse::<u64>() {
+                Ok(value) if value > 0 => Ok(value),
+                _ => Err(Error::invalid_input(format!(
+                    "Invalid value '{}' for {} on field {}, expected a positive integer",
+                    value, key, field.name
+                ))),
+            })
+            .transpose()
+    }
+
     // TODO: This is a heuristic we may need to tune at some point
     //
     // As data gets narrow then the "zipping" process gets too expensive
@@ -5671,6 +5769,26 @@ impl PrimitiveStructuralEncoder {
         Ok(tasks)
     }
 
+    fn accumulate(
+        &mut self,
+        array: ArrayRef,
+        mut repdef: RepDefBuilder,
+        row_number: u64,
+        num_rows: u64,
+    ) -> Result<Vec<EncodeTask>> {
+        let array = Self::extract_validity(array, &mut repdef, self.keep_original_array)?;
+        self.accumulated_repdefs.push(repdef);
+
+        if let Some((arrays, row_number, num_rows)) =
+            self.accumulation_queue.insert(array, row_number, num_rows)
+        {
+            let accumulated_repdefs = std::mem::take(&mut self.accumulated_repdefs);
+            Ok(self.do_flush(arrays, accumulated_repdefs, row_number, num_rows)?)
+        } else {
+            Ok(vec![])
+        }
+    }
+
     fn extract_validity_buf(
         array: Arc<dyn Array>,
         repdef: &mut RepDefBuilder,
@@ -5723,21 +5841,28 @@ impl FieldEncoder for PrimitiveStructuralEncoder {
         &mut self,
         array: ArrayRef,
         _external_buffers: &mut OutOfLineBuffers,
-        mut repdef: RepDefBuilder,
+        repdef: RepDefBuilder,
         row_number: u64,
         num_rows: u64,
     ) -> Result<Vec<EncodeTask>> {
-        let array = Self::extract_validity(array, &mut repdef, self.keep_original_array)?;
-        self.accumulated_repdefs.push(repdef);
-
-        if let Some((arrays, row_number, num_rows)) =
-            self.accumulation_queue.insert(array, row_number, num_rows)
+        // Top-level rows map 1:1 to items when there is no parent rep/def information so
+        // we can split the array to end the page on an aligned row
+        if repdef.is_empty()
+            && array.len() as u64 == num_rows
+            && let Some(split) = self.accumulation_queue.aligned_split(&array, num_rows)
         {
-            let accumulated_repdefs = std::mem::take(&mut self.accumulated_repdefs);
-            Ok(self.do_flush(arrays, accumulated_repdefs, row_number, num_rows)?)
-        } else {
-            Ok(vec![])
+            let head = array.slice(0, split as usize);
+            let tail = array.slice(split as usize, (num_rows - split) as usize);
+            let mut tasks = self.accumulate(head, repdef, row_number, split)?;
+            tasks.extend(self.accumulate(
+                tail,
+                RepDefBuilder::default(),
+                row_number + split,
+                num_rows - split,
+            )?);
+            return Ok(tasks);
         }
+        self.accumulate(array, repdef, row_number, num_rows)
     }
 
     // If there is any data left in the buffer then create an encode task from it
@@ -7586,6 +7711,7 @@ mod tests {
             keep_original_array: true,
             buffer_alignment: MIN_PAGE_BUFFER_ALIGNMENT,
             version,
+            page_row_alignment: 1,
         };
 
         let mut encoder = encoding_strategy
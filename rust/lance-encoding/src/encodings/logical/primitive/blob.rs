@@ -224,6 +224,8 @@ pub(super) struct BlobPageScheduler {
     row_number: u64,
     num_rows: u64,
     def_meaning: Arc<[DefinitionInterpretation]>,
+    // The width of the offsets in the decoded data (32 for Binary / Utf8, 64 for the large types)
+    bits_per_offset: u8,
     positions: Option<Arc<UInt64Array>>,
     sizes: Option<Arc<UInt64Array>>,
 }
@@ -234,12 +236,14 @@ impl BlobPageScheduler {
         row_number: u64,
         num_rows: u64,
         def_meaning: Arc<[DefinitionInterpretation]>,
+        bits_per_offset: u8,
     ) -> Self {
         Self {
             inner_scheduler,
             row_number,
             num_rows,
             def_meaning,
+            bits_per_offset,
             positions: None,
             sizes: None,
         }
@@ -251,6 +255,7 @@ impl BlobPageScheduler {
         first_row_number: u64,
         io: &dyn EncodingsIo,
         def_meaning: Arc<[DefinitionInterpretation]>,
+        bits_per_offset: u8,
     ) -> Result<PageLoadTask> {
         let num_rows = loaded_blobs.len() as u64;
         let read_fut = io.submit_request(ranges_to_read, first_row_number);
@@ -258,13 +263,16 @@ impl BlobPageScheduler {
             let bytes = read_fut.await?;
             let mut bytes_iter = bytes.into_iter();
             for blob in loaded_blobs.iter_mut() {
-                if blob.def == 0 {
+                if blob.needs_bytes {
                     blob.set_bytes(bytes_iter.next().expect_ok()?);
                 }
             }
             debug_assert!(bytes_iter.next().is_none());
-            Ok(Box::new(BlobPageDecoder::new(loaded_blobs, def_meaning))
-                as Box<dyn StructuralPageDecoder>)
+            Ok(Box::new(BlobPageDecoder::new(
+                loaded_blobs,
+                def_meaning,
+                bits_per_offset,
+            )) as Box<dyn StructuralPageDecoder>)
         }
         .boxed();
         Ok(PageLoadTask {
@@ -366,7 +374,7 @@ impl StructuralPageScheduler for BlobPageScheduler {
                     let def = ((position >> 16) & 0xFFFF) as u16;
                     loaded_blobs.push(LoadedBlob::new(rep, def));
                 } else {
-                    loaded_blobs.push(LoadedBlob::new(0, 0));
+                    loaded_blobs.push(LoadedBlob::with_data());
                     ranges_to_read.push(position..(position + size));
                     bytes_so_far += size;
                 }
@@ -378,6 +386,7 @@ impl StructuralPageScheduler for BlobPageScheduler {
                         first_row_number.unwrap(),
                         io.as_ref(),
                         self.def_meaning.clone(),
+                        self.bits_per_offset,
                     )?;
                     page_load_tasks.push(page_load_task);
                     bytes_so_far = 0;
@@ -392,6 +401,7 @@ impl StructuralPageScheduler for BlobPageScheduler {
                 first_row_number.unwrap(),
                 io.as_ref(),
                 self.def_meaning.clone(),
+                self.bits_per_offset,
             )?;
             page_load_tasks.push(page_load_task);
         }
@@ -403,6 +413,8 @@ impl StructuralPageScheduler for BlobPageScheduler {
 #[derive(Debug)]
 struct LoadedBlob {
     bytes: Option<Bytes>,
+    // False for null and empty values, which have nothing to read
+    needs_bytes: bool,
     rep: u16,
     def: u16,
 }
@@ -411,11 +423,21 @@ impl LoadedBlob {
     fn new(rep: u16, def: u16) -> Self {
         Self {
             bytes: None,
+            needs_bytes: false,
             rep,
             def,
         }
     }
 
+    fn with_data() -> Self {
+        Self {
+            bytes: None,
+            needs_bytes: true,
+            rep: 0,
+            def: 0,
+        }
+    }
+
     fn set_bytes(&mut self, bytes: Bytes) {
         self.bytes = Some(bytes);
     }
@@ -425,15 +447,21 @@ impl LoadedBlob {
 struct BlobPageDecoder {
     blobs: VecDeque<LoadedBlob>,
     def_meaning: Arc<[DefinitionInterpretation]>,
+    bits_per_offset: u8,
     num_rows: u64,
 }
 
 impl BlobPageDecoder {
-    fn new(blobs: Vec<LoadedBlob>, def_meaning: Arc<[DefinitionInterpretation]>) -> Self {
+    fn new(
+        blobs: Vec<LoadedBlob>,
+        def_meaning: Arc<[DefinitionInterpretation]>,
+        bits_per_offset: u8,
+    ) -> Self {
         Self {
             num_rows: blobs.len() as u64,
             blobs: blobs.into_iter().collect(),
             def_meaning,
+            bits_per_offset,
         }
     }
 }
@@ -444,6 +472,7 @@ impl StructuralPageDecoder for BlobPageDecoder {
         Ok(Box::new(BlobDecodePageTask::new(
             blobs,
             self.def_meaning.clone(),
+            self.bits_per_offset,
         )))
     }
 
@@ -456,11 +485,20 @@ impl StructuralPageDecoder for BlobPageDecoder {
 struct BlobDecodePageTask {
     blobs: Vec<LoadedBlob>,
     def_meaning: Arc<[DefinitionInterpretation]>,
+    bits_per_offset: u8,
 }
 
 impl BlobDecodePageTask {
-    fn new(blobs: Vec<LoadedBlob>, def_meaning: Arc<[DefinitionInterpretation]>) -> Self {
-        Self { blobs, def_meaning }
+    fn new(
+        blobs: Vec<LoadedBlob>,
+        def_meaning: Arc<[DefinitionInterpretation]>,
+        bits_per_offset: u8,
+    ) -> Self {
+        Self {
+            blobs,
+            def_meaning,
+            bits_per_offset,
+        }
     }
 }
 
@@ -489,12 +527,26 @@ impl DecodePageTask for BlobDecodePageTask {
                 offsets.push(*offsets.last().unwrap());
             }
         }
-        let offsets = LanceBuffer::reinterpret_vec(offsets);
+        let offsets = if self.bits_per_offset == 32 {
+            let offsets = offsets
+                .into_iter()
+                .map(|offset| {
+                    u32::try_from(offset).map_err(|_| {
+                        Error::invalid_input(
+                            "Too much binary data in a single batch for 32-bit offsets, use the large type instead",
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            LanceBuffer::reinterpret_vec(offsets)
+        } else {
+            LanceBuffer::reinterpret_vec(offsets)
+        };
         let data = LanceBuffer::from(buffer);
         let data_block = DataBlock::VariableWidth(VariableWidthBlock {
             data,
             offsets,
-            bits_per_offset: 64,
+            bits_per_offset: self.bits_per_offset,
             num_values,
             block_info: BlockInfo::new(),
         });
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Encoding support for run-end encoded arrays
+//!
+//! Run-end encoded columns are stored as a column of their value type.  The runs are
+//! expanded when writing and the compression strategy is left to detect them again
+//! (runs of fixed-width values are run-length encoded and runs of variable-width values
+//! are usually dictionary encoded) so the column stays compact on disk.  On read the
+//! values are decoded and re-encoded into runs, see
+//! [`crate::encodings::logical::primitive::StructuralCompositeDecodeArrayTask`].
+
+use arrow_array::{Array, ArrayRef};
+use arrow_schema::DataType;
+use futures::future::BoxFuture;
+use lance_core::{Error, Result};
+
+use crate::{
+    encoder::{EncodeTask, EncodedColumn, FieldEncoder, OutOfLineBuffers},
+    repdef::RepDefBuilder,
+};
+
+/// A structural encoder for run-end encoded fields
+///
+/// Each array is expanded to its value type and passed to the values encoder.
+pub struct RunEndEncodedStructuralEncoder {
+    values: Box<dyn FieldEncoder>,
+}
+
+impl RunEndEncodedStructuralEncoder {
+    pub fn new(values: Box<dyn FieldEncoder>) -> Self {
+        Self { values }
+    }
+}
+
+impl FieldEncoder for RunEndEncodedStructuralEncoder {
+    fn maybe_encode(
+        &mut self,
+        array: ArrayRef,
+        external_buffers: &mut OutOfLineBuffers,
+        repdef: RepDefBuilder,
+        row_number: u64,
+        num_rows: u64,
+    ) -> Result<Vec<EncodeTask>> {
+        let DataType::RunEndEncoded(_, values) = array.data_type() else {
+            return Err(Error::internal(format!(
+                "Run-end encoded encoder used for {} data",
+                array.data_type()
+            )));
+        };
+        let values = arrow_cast::cast(&array, values.data_type())?;
+        self.values
+            .maybe_encode(values, external_buffers, repdef, row_number, num_rows)
+    }
+
+    fn flush(&mut self, external_buffers: &mut OutOfLineBuffers) -> Result<Vec<EncodeTask>> {
+        self.values.flush(external_buffers)
+    }
+
+    fn num_columns(&self) -> u32 {
+        self.values.num_columns()
+    }
+
+    fn finish(
+        &mut self,
+        external_buffers: &mut OutOfLineBuffers,
+    ) -> BoxFuture<'_, Result<Vec<EncodedColumn>>> {
+        self.values.finish(external_buffers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use arrow_array::{
+        ArrayRef, Int32Array, Int64Array, RunArray, StringArray,
+        types::{Int16Type, Int32Type},
+    };
+
+    use crate::{
+        testing::{TestCases, check_round_trip_encoding_of_data},
+        version::LanceFileVersion,
+    };
+
+    #[test_log::test(tokio::test)]
+    async fn test_run_end_encoded_round_trip() {
+        let run_ends = Int32Array::from(vec![1000, 1500, 1501, 4000]);
+        let values = Int64Array::from(vec![Some(7), None, Some(-3), Some(7)]);
+        let ints: ArrayRef = Arc::new(RunArray::<Int32Type>::try_new(&run_ends, &values).unwrap());
+        let run_ends = arrow_array::Int16Array::from(vec![10, 300, 4000]);
+        let values = StringArray::from(vec![Some("alpha"), Some("beta"), None]);
+        let strings: ArrayRef =
+            Arc::new(RunArray::<Int16Type>::try_new(&run_ends, &values).unwrap());
+
+        let test_cases = TestCases::default()
+            .with_min_file_version(LanceFileVersion::V2_1)
+            .with_range(0..10)
+            .with_range(900..1600)
+            .with_indices(vec![0, 1499, 1500, 3999]);
+        for array in [ints, strings] {
+            check_round_trip_encoding_of_data(
+                vec![array.clone(), array.slice(100, 2000)],
+                &test_cases,
+                HashMap::new(),
+            )
+            .await;
+        }
+    }
+}
@@ -297,7 +297,11 @@ impl StructuralStructDecoder {
                     field.data_type().clone(),
                 )))
             }
-            DataType::RunEndEncoded(_, _) => todo!(),
+            // Decoded as the values and encoded into runs afterwards
+            DataType::RunEndEncoded(_, _) => Ok(Box::new(StructuralPrimitiveFieldDecoder::new(
+                field,
+                should_validate,
+            ))),
             DataType::ListView(_) | DataType::LargeListView(_) => todo!(),
             DataType::Union(_, _) => todo!(),
             _ => Ok(Box::new(StructuralPrimitiveFieldDecoder::new(
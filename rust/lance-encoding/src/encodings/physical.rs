@@ -7,6 +7,7 @@ pub mod bitpacking;
 pub mod block;
 pub mod byte_stream_split;
 pub mod constant;
+pub mod delta;
 pub mod fsst;
 pub mod general;
 pub mod packed;
@@ -0,0 +1,456 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! # Delta Miniblock Format
+//!
+//! Delta encoding replaces each integer with its difference from the previous
+//! value.  Delta-of-delta encoding applies the same transformation a second
+//! time.  For monotonically increasing columns (ids, timestamps) the resulting
+//! residuals are tiny and can be bitpacked with only a few bits per value.
+//!
+//! ### Example
+//!
+//! Input data (i64 timestamps): `[1000, 1010, 1020, 1031, 1041]`
+//!
+//! - Delta: first value `1000`, residuals `[10, 10, 11, 10]`
+//! - Delta-of-delta: first value `1000`, first delta `10`, residuals `[0, 1, -1]`
+//!
+//! ## Chunk Layout
+//!
+//! Every chunk is independent so that chunks can be decoded without the
+//! preceding chunks.  A chunk is a single buffer:
+//!
+//! - The first value (and the first delta for delta-of-delta) in the original
+//!   width, little endian
+//! - One byte with the bit width of the residuals
+//! - The zig-zag encoded residuals, bitpacked (LSB first) with that bit width
+//!
+//! Differences are computed with wrapping arithmetic in the width of the values
+//! so the encoding is lossless for signed and unsigned integers alike.
+//!
+//! ## Chunk Handling
+//!
+//! - 8, 16 and 32-bit values use 1024 values per chunk, 64-bit values use 512
+//!   values per chunk so that a chunk always fits in a mini-block
+//! - Non-last chunks always contain power-of-2 values
+
+use crate::buffer::LanceBuffer;
+use crate::compression::MiniBlockDecompressor;
+use crate::data::{BlockInfo, DataBlock, FixedWidthDataBlock};
+use crate::encodings::logical::primitive::miniblock::{
+    MAX_MINIBLOCK_VALUES, MiniBlockChunk, MiniBlockCompressed, MiniBlockCompressor,
+};
+use crate::format::ProtobufUtils21;
+use crate::format::pb21::CompressiveEncoding;
+
+use lance_core::{Error, Result};
+
+/// Whether delta encoding can be applied to values with the given bit width
+pub fn supports_delta(bits_per_value: u64) -> bool {
+    matches!(bits_per_value, 8 | 16 | 32 | 64)
+}
+
+fn max_chunk_size(bits_per_value: u64) -> usize {
+    // A chunk of 64-bit values with 64-bit residuals must fit in MAX_MINIBLOCK_BYTES
+    let max_chunk_size = if bits_per_value == 64 { 512 } else { 1024 };
+    let max_values = *MAX_MINIBLOCK_VALUES as usize;
+    max_chunk_size.min(1 << max_values.ilog2())
+}
+
+fn value_mask(bits_per_value: u64) -> u64 {
+    u64::MAX >> (64 - bits_per_value)
+}
+
+fn read_values(data: &[u8], bits_per_value: u64) -> Vec<u64> {
+    let bytes_per_value = (bits_per_value / 8) as usize;
+    data.chunks_exact(bytes_per_value)
+        .map(|value| {
+            let mut buf = [0_u8; 8];
+            buf[..bytes_per_value].copy_from_slice(value);
+            u64::from_le_bytes(buf)
+        })
+        .collect()
+}
+
+/// Interprets a `bits_per_value` wide difference as a signed value and zig-zag encodes it
+fn zigzag(value: u64, bits_per_value: u64) -> u64 {
+    let shift = 64 - bits_per_value;
+    let signed = ((value << shift) as i64) >> shift;
+    ((signed << 1) ^ (signed >> 63)) as u64
+}
+
+fn unzigzag(value: u64, bits_per_value: u64) -> u64 {
+    (((value >> 1) as i64) ^ -((value & 1) as i64)) as u64 & value_mask(bits_per_value)
+}
+
+/// Differences `values` `order` times
+///
+/// Returns the first value of every level (fewer than `order` if there are not enough
+/// values) and the zig-zag encoded residuals.
+fn residuals(values: &[u64], order: u32, bits_per_value: u64) -> (Vec<u64>, Vec<u64>) {
+    let mask = value_mask(bits_per_value);
+    let mut heads = Vec::with_capacity(order as usize);
+    let mut current = values.to_vec();
+    for _ in 0..order {
+        let Some(first) = current.first() else {
+            break;
+        };
+        heads.push(*first);
+        current = current
+            .windows(2)
+            .map(|pair| pair[1].wrapping_sub(pair[0]) & mask)
+            .collect();
+    }
+    for value in current.iter_mut() {
+        *value = zigzag(*value, bits_per_value);
+    }
+    (heads, current)
+}
+
+fn residual_bit_width(residuals: &[u64]) -> u64 {
+    let max = residuals.iter().fold(0, |acc, value| acc | value);
+    64 - max.leading_zeros() as u64
+}
+
+fn chunk_bytes(num_heads: usize, num_residuals: usize, bits_per_value: u64, bit_width: u64) -> u64 {
+    num_heads as u64 * (bits_per_value / 8) + 1 + (num_residuals as u64 * bit_width).div_ceil(8)
+}
+
+/// Estimates the number of bytes needed to delta encode `data` with the given order
+pub fn estimate_delta_bytes(data: &FixedWidthDataBlock, order: u32) -> u64 {
+    let bits_per_value = data.bits_per_value;
+    let values = read_values(data.data.as_ref(), bits_per_value);
+    values
+        .chunks(max_chunk_size(bits_per_value))
+        .map(|chunk| {
+            let (heads, residuals) = residuals(chunk, order, bits_per_value);
+            chunk_bytes(
+                heads.len(),
+                residuals.len(),
+                bits_per_value,
+                residual_bit_width(&residuals),
+            )
+        })
+        .sum()
+}
+
+/// Delta (order 1) or delta-of-delta (order 2) encoder for integer values
+#[derive(Debug, Clone)]
+pub struct DeltaEncoder {
+    order: u32,
+}
+
+impl DeltaEncoder {
+    pub fn new(order: u32) -> Self {
+        assert!(
+            order == 1 || order == 2,
+            "Delta encoding only supports delta (1) and delta-of-delta (2)"
+        );
+        Self { order }
+    }
+
+    fn encode_chunk(&self, values: &[u64], bits_per_value: u64, output: &mut Vec<u8>) -> u32 {
+        let start = output.len();
+        let bytes_per_value = (bits_per_value / 8) as usize;
+        let (heads, residuals) = residuals(values, self.order, bits_per_value);
+        for head in heads {
+            output.extend_from_slice(&head.to_le_bytes()[..bytes_per_value]);
+        }
+        let bit_width = residual_bit_width(&residuals);
+        output.push(bit_width as u8);
+
+        let mut buffer = 0_u128;
+        let mut buffered_bits = 0;
+        for residual in residuals {
+            buffer |= (residual as u128) << buffered_bits;
+            buffered_bits += bit_width;
+            while buffered_bits >= 8 {
+                output.push(buffer as u8);
+                buffer >>= 8;
+                buffered_bits -= 8;
+            }
+        }
+        if buffered_bits > 0 {
+            output.push(buffer as u8);
+        }
+        (output.len() - start) as u32
+    }
+}
+
+impl MiniBlockCompressor for DeltaEncoder {
+    fn compress(&self, page: DataBlock) -> Result<(MiniBlockCompressed, CompressiveEncoding)> {
+        let DataBlock::FixedWidth(data) = page else {
+            return Err(Error::invalid_input_source(
+                "Delta encoding only supports FixedWidth data blocks".into(),
+            ));
+        };
+        let bits_per_value = data.bits_per_value;
+        if !supports_delta(bits_per_value) {
+            return Err(Error::invalid_input_source(
+                format!("Delta encoding does not support {bits_per_value}-bit values").into(),
+            ));
+        }
+
+        let values = read_values(data.data.as_ref(), bits_per_value);
+        let chunk_size = max_chunk_size(bits_per_value);
+        let mut buffer = Vec::new();
+        let mut chunks = Vec::with_capacity(values.len().div_ceil(chunk_size));
+        for (chunk_idx, chunk) in values.chunks(chunk_size).enumerate() {
+            let chunk_bytes = self.encode_chunk(chunk, bits_per_value, &mut buffer);
+            let is_last = (chunk_idx + 1) * chunk_size >= values.len();
+            chunks.push(MiniBlockChunk {
+                buffer_sizes: vec![chunk_bytes],
+                log_num_values: if is_last { 0 } else { chunk_size.ilog2() as u8 },
+            });
+        }
+
+        Ok((
+            MiniBlockCompressed {
+                data: vec![LanceBuffer::from(buffer)],
+                chunks,
+                num_values: data.num_values,
+            },
+            ProtobufUtils21::delta(bits_per_value, self.order),
+        ))
+    }
+}
+
+/// Decompressor for delta and delta-of-delta encoded mini-blocks
+#[derive(Debug)]
+pub struct DeltaDecompressor {
+    bits_per_value: u64,
+    order: u32,
+}
+
+impl DeltaDecompressor {
+    pub fn try_new(bits_per_value: u64, order: u32) -> Result<Self> {
+        if !supports_delta(bits_per_value) || !(order == 1 || order == 2) {
+            return Err(Error::invalid_input_source(
+                format!("Invalid delta encoding: order {order} with {bits_per_value}-bit values")
+                    .into(),
+            ));
+        }
+        Ok(Self {
+            bits_per_value,
+            order,
+        })
+    }
+}
+
+impl MiniBlockDecompressor for DeltaDecompressor {
+    fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
+        let bits_per_value = self.bits_per_value;
+        let bytes_per_value = (bits_per_value / 8) as usize;
+        if num_values == 0 {
+            return Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+                data: LanceBuffer::empty(),
+                bits_per_value,
+                num_values: 0,
+                block_info: BlockInfo::new(),
+            }));
+        }
+        if data.len() != 1 {
+            return Err(Error::invalid_input_source(
+                format!(
+                    "Delta decompression expects 1 buffer, but got {}",
+                    data.len()
+                )
+                .into(),
+            ));
+        }
+
+        let num_values = num_values as usize;
+        let num_heads = num_values.min(self.order as usize);
+        let num_residuals = num_values - num_heads;
+        let chunk = data[0].as_ref();
+        let header_bytes = num_heads * bytes_per_value + 1;
+        let bit_width = chunk.get(header_bytes - 1).copied().unwrap_or(u8::MAX) as u64;
+        if bit_width > bits_per_value
+            || chunk.len() as u64
+                != chunk_bytes(num_heads, num_residuals, bits_per_value, bit_width)
+        {
+            return Err(Error::invalid_input_source(
+                format!(
+                    "Invalid delta chunk of {} bytes for {} values",
+                    chunk.len(),
+                    num_values
+                )
+                .into(),
+            ));
+        }
+        let heads = read_values(&chunk[..header_bytes - 1], bits_per_value);
+
+        let residual_mask = value_mask(bit_width.max(1)) * (bit_width > 0) as u64;
+        let mut packed = chunk[header_bytes..].iter();
+        let mut buffer = 0_u128;
+        let mut buffered_bits = 0;
+        let mut current = Vec::with_capacity(num_values);
+        for _ in 0..num_residuals {
+            while buffered_bits < bit_width {
+                buffer |= (*packed.next().unwrap() as u128) << buffered_bits;
+                buffered_bits += 8;
+            }
+            current.push(unzigzag(buffer as u64 & residual_mask, bits_per_value));
+            buffer >>= bit_width;
+            buffered_bits -= bit_width;
+        }
+
+        // Undo the differencing, innermost level first
+        let mask = value_mask(bits_per_value);
+        for head in heads.into_iter().rev() {
+            let mut value = head;
+            let mut integrated = Vec::with_capacity(current.len() + 1);
+            integrated.push(value);
+            for delta in current {
+                value = value.wrapping_add(delta) & mask;
+                integrated.push(value);
+            }
+            current = integrated;
+        }
+
+        let mut output = Vec::with_capacity(num_values * bytes_per_value);
+        for value in current {
+            output.extend_from_slice(&value.to_le_bytes()[..bytes_per_value]);
+        }
+        Ok(DataBlock::FixedWidth(FixedWidthDataBlock {
+            data: LanceBuffer::from(output),
+            bits_per_value,
+            num_values: num_values as u64,
+            block_info: BlockInfo::new(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_width(bytes: Vec<u8>, bits_per_value: u64) -> FixedWidthDataBlock {
+        FixedWidthDataBlock {
+            num_values: bytes.len() as u64 / (bits_per_value / 8),
+            data: LanceBuffer::from(bytes),
+            bits_per_value,
+            block_info: BlockInfo::new(),
+        }
+    }
+
+    fn round_trip(bytes: Vec<u8>, bits_per_value: u64, order: u32) -> MiniBlockCompressed {
+        let data = fixed_width(bytes.clone(), bits_per_value);
+        let (compressed, _) = DeltaEncoder::new(order)
+            .compress(DataBlock::FixedWidth(data))
+            .unwrap();
+        let decompressor = DeltaDecompressor::try_new(bits_per_value, order).unwrap();
+
+        let bytes_per_value = (bits_per_value / 8) as usize;
+        let mut offset = 0;
+        let mut decoded = Vec::new();
+        for (idx, chunk) in compressed.chunks.iter().enumerate() {
+            let chunk_values = if idx + 1 == compressed.chunks.len() {
+                bytes.len() / bytes_per_value - decoded.len() / bytes_per_value
+            } else {
+                1 << chunk.log_num_values
+            };
+            let size = chunk.buffer_sizes[0] as usize;
+            let buffer =
+                LanceBuffer::from(compressed.data[0].as_ref()[offset..offset + size].to_vec());
+            offset += size;
+            let DataBlock::FixedWidth(block) = decompressor
+                .decompress(vec![buffer], chunk_values as u64)
+                .unwrap()
+            else {
+                panic!("Expected a fixed width block")
+            };
+            decoded.extend_from_slice(block.data.as_ref());
+        }
+        assert_eq!(decoded, bytes);
+        compressed
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let timestamps = (0..5000_i64)
+            .map(|i| 1_700_000_000_000 + i * 1000 + i % 3)
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        for order in [1, 2] {
+            let compressed = round_trip(timestamps.clone(), 64, order);
+            // 512 values per chunk for 64-bit values
+            assert_eq!(compressed.chunks.len(), 10);
+            assert!(compressed.data[0].len() < timestamps.len() / 4);
+        }
+
+        // Decreasing values, wrapping differences and extreme values
+        let values = [i32::MAX, i32::MIN, 0, -1, 5, 4, 3, i32::MIN, i32::MAX]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        round_trip(values, 32, 1);
+        let values = [u16::MAX, 0, u16::MAX, 7]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        round_trip(values, 16, 2);
+        round_trip((0..=255_u8).rev().collect(), 8, 2);
+
+        // Fewer values than the order and constant values
+        round_trip(vec![42], 8, 2);
+        round_trip(vec![7; 3000], 8, 1);
+        round_trip(vec![], 32, 1);
+    }
+
+    #[test]
+    fn test_estimate() {
+        let sequential = fixed_width((0..2048_u32).flat_map(|v| v.to_le_bytes()).collect(), 32);
+        // Two chunks, each with a 4-byte head, a bit width and 1023 residuals of one
+        // (two bits once zig-zag encoded)
+        assert_eq!(estimate_delta_bytes(&sequential, 1), 2 * (4 + 1 + 256));
+        // The deltas are all one so the residuals take no space at all
+        assert_eq!(estimate_delta_bytes(&sequential, 2), 2 * (8 + 1));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_delta_encoding_verification() {
+        use crate::constants::DELTA_META_KEY;
+        use crate::testing::{TestCases, check_round_trip_encoding_of_data};
+        use crate::version::LanceFileVersion;
+        use arrow_array::{ArrayRef, Int64Array, TimestampMicrosecondArray, UInt32Array};
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let test_cases = TestCases::default()
+            .with_expected_encoding("delta")
+            .with_min_file_version(LanceFileVersion::V2_3);
+
+        // Sorted timestamps with a little jitter are picked automatically
+        let timestamps: ArrayRef = Arc::new(TimestampMicrosecondArray::from_iter_values(
+            (0..10_000_i64).map(|i| 1_700_000_000_000_000 + i * 1_000_000 + (i * 7) % 13),
+        ));
+        check_round_trip_encoding_of_data(vec![timestamps], &test_cases, HashMap::new()).await;
+
+        // Nullable ids with gaps
+        let ids: ArrayRef = Arc::new(Int64Array::from_iter((0..10_000_i64).map(|i| {
+            if i % 100 == 0 {
+                None
+            } else {
+                Some(i64::MIN / 2 + i * 3)
+            }
+        })));
+        check_round_trip_encoding_of_data(vec![ids], &test_cases, HashMap::new()).await;
+
+        // Delta encoding can be forced on data that does not benefit from it
+        let metadata = HashMap::from([(DELTA_META_KEY.to_string(), "delta-of-delta".to_string())]);
+        let random: ArrayRef = Arc::new(UInt32Array::from_iter_values(
+            (0..5_000_u32).map(|i| i.wrapping_mul(2_654_435_761)),
+        ));
+        check_round_trip_encoding_of_data(vec![random], &test_cases, metadata).await;
+    }
+
+    #[test]
+    fn test_invalid_chunk() {
+        let decompressor = DeltaDecompressor::try_new(32, 1).unwrap();
+        let buffer = LanceBuffer::from(vec![1, 0, 0, 0, 4, 0xFF]);
+        assert!(decompressor.decompress(vec![buffer], 4).is_err());
+        assert!(DeltaDecompressor::try_new(32, 3).is_err());
+        assert!(DeltaDecompressor::try_new(24, 1).is_err());
+    }
+}
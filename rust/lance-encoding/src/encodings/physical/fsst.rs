@@ -14,6 +14,11 @@
 //! create multiple symbol tables for a single value!
 //!
 //! FSST encoding is transparent.
+//!
+//! When FSST is chosen automatically (rather than requested by the user) the encoders
+//! can be configured to check that compression actually paid off.  If the compressed
+//! values plus the symbol table are not smaller than the input we write the data with
+//! the plain binary encoding instead.
 
 use lance_core::{Error, Result};
 
@@ -39,6 +44,12 @@ struct FsstCompressed {
 }
 
 impl FsstCompressed {
+    /// Whether the compressed values (and the symbol table needed to decode them) take
+    /// up less space than the original values
+    fn is_smaller_than(&self, original: &VariableWidthBlock) -> bool {
+        (self.data.data.len() + self.symbol_table.len()) < original.data.len()
+    }
+
     fn fsst_compress(data: DataBlock) -> Result<Self> {
         match data {
             DataBlock::VariableWidth(variable_width) => {
@@ -129,18 +140,40 @@ impl FsstCompressed {
 #[derive(Debug, Default)]
 pub struct FsstMiniBlockEncoder {
     minichunk_size: Option<i64>,
+    only_if_smaller: bool,
 }
 
 impl FsstMiniBlockEncoder {
     pub fn new(minichunk_size: Option<i64>) -> Self {
-        Self { minichunk_size }
+        Self {
+            minichunk_size,
+            only_if_smaller: false,
+        }
+    }
+
+    /// Fall back to plain binary encoding if FSST does not shrink the data
+    pub fn only_if_smaller(mut self) -> Self {
+        self.only_if_smaller = true;
+        self
     }
 }
 
 impl MiniBlockCompressor for FsstMiniBlockEncoder {
     fn compress(&self, data: DataBlock) -> Result<(MiniBlockCompressed, CompressiveEncoding)> {
+        let original = if self.only_if_smaller {
+            data.as_variable_width_ref().cloned()
+        } else {
+            None
+        };
         let compressed = FsstCompressed::fsst_compress(data)?;
 
+        if let Some(original) = original
+            && !compressed.is_smaller_than(&original)
+        {
+            return BinaryMiniBlockEncoder::new(self.minichunk_size)
+                .compress(DataBlock::VariableWidth(original));
+        }
+
         let data_block = DataBlock::VariableWidth(compressed.data);
 
         // compress the fsst compressed data using `BinaryMiniBlockEncoder`
@@ -160,18 +193,39 @@ impl MiniBlockCompressor for FsstMiniBlockEncoder {
 #[derive(Debug)]
 pub struct FsstPerValueEncoder {
     inner: Box<dyn PerValueCompressor>,
+    only_if_smaller: bool,
 }
 
 impl FsstPerValueEncoder {
     pub fn new(inner: Box<dyn PerValueCompressor>) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            only_if_smaller: false,
+        }
+    }
+
+    /// Fall back to the inner encoder alone if FSST does not shrink the data
+    pub fn only_if_smaller(mut self) -> Self {
+        self.only_if_smaller = true;
+        self
     }
 }
 
 impl PerValueCompressor for FsstPerValueEncoder {
     fn compress(&self, data: DataBlock) -> Result<(PerValueDataBlock, CompressiveEncoding)> {
+        let original = if self.only_if_smaller {
+            data.as_variable_width_ref().cloned()
+        } else {
+            None
+        };
         let compressed = FsstCompressed::fsst_compress(data)?;
 
+        if let Some(original) = original
+            && !compressed.is_smaller_than(&original)
+        {
+            return self.inner.compress(DataBlock::VariableWidth(original));
+        }
+
         let data_block = DataBlock::VariableWidth(compressed.data);
 
         let (binary_compressed, binary_array_encoding) = self.inner.compress(data_block)?;
@@ -375,13 +429,19 @@ impl MiniBlockDecompressor for FsstMiniBlockDecompressor {
 mod tests {
     use std::collections::HashMap;
 
+    use arrow_schema::DataType;
     use lance_datagen::{ByteCount, RowCount};
 
     use crate::{
+        data::DataBlock,
+        encodings::logical::primitive::miniblock::MiniBlockCompressor,
+        format::pb21::compressive_encoding::Compression,
         testing::{TestCases, check_round_trip_encoding_of_data},
         version::LanceFileVersion,
     };
 
+    use super::FsstMiniBlockEncoder;
+
     #[test_log::test(tokio::test)]
     async fn test_fsst() {
         let test_cases = TestCases::default()
@@ -404,6 +464,35 @@ mod tests {
 
         // 2. Test automatic FSST selection based on data characteristics
         // FSST should be chosen automatically: max_len >= 5 and total_size >= 32KB
-        check_round_trip_encoding_of_data(vec![arr], &test_cases, HashMap::new()).await;
+        // and the data is repetitive enough for the symbol table to pay off
+        let urls = lance_datagen::gen_batch()
+            .anon_col(lance_datagen::array::utf8_prefix_plus_counter(
+                "https://example.com/catalog/items/",
+                false,
+            ))
+            .into_batch_rows(RowCount::from(5000))
+            .unwrap()
+            .column(0)
+            .clone();
+        check_round_trip_encoding_of_data(vec![urls], &test_cases, HashMap::new()).await;
+    }
+
+    #[test]
+    fn test_fsst_only_if_smaller() {
+        // Random bytes do not compress, FSST output would be larger than the input
+        let arr = lance_datagen::array::rand_type(&DataType::Binary)
+            .generate_default(RowCount::from(2000))
+            .unwrap();
+        let block = DataBlock::from_array(arr);
+
+        let (_, encoding) = FsstMiniBlockEncoder::new(None)
+            .only_if_smaller()
+            .compress(block.try_clone().unwrap())
+            .unwrap();
+        assert!(!matches!(encoding.compression, Some(Compression::Fsst(_))));
+
+        // Without the check the encoder always uses FSST
+        let (_, encoding) = FsstMiniBlockEncoder::new(None).compress(block).unwrap();
+        assert!(matches!(encoding.compression, Some(Compression::Fsst(_))));
     }
 }
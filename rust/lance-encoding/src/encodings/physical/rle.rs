@@ -29,6 +29,7 @@
 //! ## Supported Types
 //!
 //! RLE supports all fixed-width primitive types:
+//! - 1-bit: boolean (each run value is stored in a byte, Lance 2.3+)
 //! - 8-bit: u8, i8
 //! - 16-bit: u16, i16
 //! - 32-bit: u32, i32, f32
@@ -49,12 +50,18 @@
 //! NOTE: The current encoder uses a 2048-value cap per chunk as a workaround for
 //! <https://github.com/lancedb/lance/issues/4429>.
 //!
+//! ## Run-Aware Filtering
+//!
+//! [`RleDecompressor::filter`] evaluates a predicate once per run instead of once per
+//! value and expands the result into a row selection, so low-cardinality columns with
+//! long runs can be filtered without materializing the values.
+//!
 //! ## Block Format
 //!
 //! When used in the block compression path, the encoded output is a single buffer:
 //! `[8-byte header: values buffer size][values buffer][run_lengths buffer]`.
 
-use arrow_buffer::ArrowNativeType;
+use arrow_buffer::{ArrowNativeType, BooleanBuffer, BooleanBufferBuilder};
 use log::trace;
 
 use crate::buffer::LanceBuffer;
@@ -89,6 +96,15 @@ impl RleEncoder {
             return Ok((Vec::new(), Vec::new()));
         }
 
+        if bits_per_value == 1 {
+            // Booleans are run length encoded as one byte per value
+            let bytes = BooleanBuffer::new(data.clone().into_buffer(), 0, num_values as usize)
+                .iter()
+                .map(u8::from)
+                .collect::<Vec<_>>();
+            return self.encode_data(&LanceBuffer::from(bytes), num_values, 8);
+        }
+
         let bytes_per_value = (bits_per_value / 8) as usize;
 
         // Pre-allocate global buffers with estimated capacity
@@ -449,6 +465,11 @@ impl RleDecompressor {
         let lengths_buffer = &data[1];
 
         let decoded_data = match self.bits_per_value {
+            1 => {
+                let bytes = self.decode_generic::<u8>(values_buffer, lengths_buffer, num_values)?;
+                let bits = BooleanBuffer::from_iter(bytes.iter().map(|value| *value != 0));
+                LanceBuffer::from(bits.into_inner())
+            }
             8 => self.decode_generic::<u8>(values_buffer, lengths_buffer, num_values)?,
             16 => self.decode_generic::<u16>(values_buffer, lengths_buffer, num_values)?,
             32 => self.decode_generic::<u32>(values_buffer, lengths_buffer, num_values)?,
@@ -552,6 +573,86 @@ impl RleDecompressor {
     }
 }
 
+impl RleDecompressor {
+    /// Evaluates a predicate against RLE encoded values without expanding the runs
+    ///
+    /// `data` holds the values and run lengths buffers of a mini-block chunk.  The
+    /// `predicate` is called once with a block holding the value of every run and must
+    /// return one boolean per run.  The result is expanded into one boolean per value.
+    pub fn filter(
+        &self,
+        data: Vec<LanceBuffer>,
+        num_values: u64,
+        predicate: impl FnOnce(DataBlock) -> Result<BooleanBuffer>,
+    ) -> Result<BooleanBuffer> {
+        if num_values == 0 {
+            return Ok(BooleanBuffer::new_unset(0));
+        }
+        if data.len() != 2 {
+            return Err(Error::invalid_input_source(
+                format!(
+                    "RLE decompressor expects exactly 2 buffers, got {}",
+                    data.len()
+                )
+                .into(),
+            ));
+        }
+        let lengths: &[u8] = data[1].as_ref();
+        let bytes_per_value = self.bits_per_value.div_ceil(8) as usize;
+        if data[0].len() != lengths.len() * bytes_per_value {
+            return Err(Error::invalid_input_source(
+                format!(
+                    "Inconsistent RLE buffers: {} value bytes but {} length entries",
+                    data[0].len(),
+                    lengths.len()
+                )
+                .into(),
+            ));
+        }
+
+        let run_values = if self.bits_per_value == 1 {
+            let bits = BooleanBuffer::from_iter(data[0].as_ref().iter().map(|value| *value != 0));
+            LanceBuffer::from(bits.into_inner())
+        } else {
+            data[0].clone()
+        };
+        let matches = predicate(DataBlock::FixedWidth(FixedWidthDataBlock {
+            bits_per_value: self.bits_per_value,
+            data: run_values,
+            num_values: lengths.len() as u64,
+            block_info: BlockInfo::default(),
+        }))?;
+        if matches.len() != lengths.len() {
+            return Err(Error::invalid_input_source(
+                format!(
+                    "RLE filter predicate returned {} results for {} runs",
+                    matches.len(),
+                    lengths.len()
+                )
+                .into(),
+            ));
+        }
+
+        let num_values = num_values as usize;
+        let mut selection = BooleanBufferBuilder::new(num_values);
+        for (is_match, &length) in matches.iter().zip(lengths) {
+            let length = (length as usize).min(num_values - selection.len());
+            selection.append_n(length, is_match);
+        }
+        if selection.len() != num_values {
+            return Err(Error::invalid_input_source(
+                format!(
+                    "RLE runs cover {} values, expected {}",
+                    selection.len(),
+                    num_values
+                )
+                .into(),
+            ));
+        }
+        Ok(selection.finish())
+    }
+}
+
 impl MiniBlockDecompressor for RleDecompressor {
     fn decompress(&self, data: Vec<LanceBuffer>, num_values: u64) -> Result<DataBlock> {
         self.decode_data(data, num_values)
@@ -658,6 +759,90 @@ mod tests {
         test_round_trip_helper(vec![1_000_000_000u64; 5], 64);
     }
 
+    #[test]
+    fn test_boolean_round_trip() {
+        let values = (0..5000).map(|i| (i / 700) % 2 == 0).collect::<Vec<_>>();
+        let bits = BooleanBuffer::from_iter(values.iter().copied());
+        let block = DataBlock::FixedWidth(FixedWidthDataBlock {
+            bits_per_value: 1,
+            data: LanceBuffer::from(bits.clone().into_inner()),
+            num_values: values.len() as u64,
+            block_info: BlockInfo::default(),
+        });
+
+        let (compressed, _) = MiniBlockCompressor::compress(&RleEncoder::new(), block).unwrap();
+        // 8 runs of (at most) 700 values need a few entries each
+        assert!(compressed.data[1].len() < 30);
+
+        let decompressed = MiniBlockDecompressor::decompress(
+            &RleDecompressor::new(1),
+            compressed.data,
+            compressed.num_values,
+        )
+        .unwrap();
+        let DataBlock::FixedWidth(decompressed) = decompressed else {
+            panic!("Expected FixedWidth block")
+        };
+        assert_eq!(decompressed.bits_per_value, 1);
+        let decoded = BooleanBuffer::new(decompressed.data.into_buffer(), 0, values.len());
+        assert_eq!(decoded, bits);
+    }
+
+    #[test]
+    fn test_run_aware_filter() {
+        let values = [3_i32, 3, 3, 7, 7, 3, 9, 9, 9, 9]
+            .iter()
+            .flat_map(|value| std::iter::repeat_n(*value, 100))
+            .collect::<Vec<_>>();
+        let block = DataBlock::FixedWidth(FixedWidthDataBlock {
+            bits_per_value: 32,
+            data: LanceBuffer::reinterpret_vec(values.clone()),
+            num_values: values.len() as u64,
+            block_info: BlockInfo::default(),
+        });
+        let (compressed, _) = MiniBlockCompressor::compress(&RleEncoder::new(), block).unwrap();
+        assert_eq!(compressed.chunks.len(), 1);
+
+        let mut evaluated = 0;
+        let selection = RleDecompressor::new(32)
+            .filter(compressed.data, values.len() as u64, |runs| {
+                let DataBlock::FixedWidth(runs) = runs else {
+                    panic!("Expected FixedWidth block")
+                };
+                evaluated = runs.num_values;
+                let runs = runs.data.borrow_to_typed_slice::<i32>();
+                Ok(BooleanBuffer::from_iter(
+                    runs.as_ref().iter().map(|value| *value == 3),
+                ))
+            })
+            .unwrap();
+        // The predicate only sees one value per run (long runs are split every 255 values)
+        assert!(evaluated < 20);
+        assert_eq!(
+            selection,
+            BooleanBuffer::from_iter(values.iter().map(|value| *value == 3))
+        );
+
+        // The predicate must return one result per run
+        let (compressed, _) = MiniBlockCompressor::compress(
+            &RleEncoder::new(),
+            DataBlock::FixedWidth(FixedWidthDataBlock {
+                bits_per_value: 32,
+                data: LanceBuffer::reinterpret_vec(values.clone()),
+                num_values: values.len() as u64,
+                block_info: BlockInfo::default(),
+            }),
+        )
+        .unwrap();
+        assert!(
+            RleDecompressor::new(32)
+                .filter(compressed.data, values.len() as u64, |_| Ok(
+                    BooleanBuffer::new_set(1)
+                ))
+                .is_err()
+        );
+    }
+
     fn test_round_trip_helper<T>(data: Vec<T>, bits_per_value: u64)
     where
         T: bytemuck::Pod + PartialEq + std::fmt::Debug,
@@ -1051,13 +1236,14 @@ mod tests {
             .with_min_file_version(LanceFileVersion::V2_1);
 
         // Test both explicit metadata and automatic selection
-        // 1. Test with explicit RLE threshold metadata (also disable BSS)
+        // 1. Test with explicit RLE threshold metadata (also disable BSS and delta encoding)
         let mut metadata_explicit = HashMap::new();
         metadata_explicit.insert(
             "lance-encoding:rle-threshold".to_string(),
             "0.8".to_string(),
         );
         metadata_explicit.insert("lance-encoding:bss".to_string(), "off".to_string());
+        metadata_explicit.insert("lance-encoding:delta".to_string(), "off".to_string());
 
         let mut generator = RleDataGenerator::new(vec![
             i32::MIN,
@@ -1081,9 +1267,10 @@ mod tests {
         // 80% repetition should trigger RLE (> default 50% threshold).
         //
         // Use values with the high bit set so bitpacking can't shrink the values.
-        // Explicitly disable BSS to ensure RLE is tested
+        // Explicitly disable BSS and delta encoding to ensure RLE is tested
         let mut metadata = HashMap::new();
         metadata.insert("lance-encoding:bss".to_string(), "off".to_string());
+        metadata.insert("lance-encoding:delta".to_string(), "off".to_string());
 
         let mut values = vec![i32::MIN; 8000]; // 80% repetition
         values.extend(
@@ -1100,6 +1287,40 @@ mod tests {
         check_round_trip_encoding_of_data(vec![arr], &test_cases, metadata).await;
     }
 
+    #[test_log::test(tokio::test)]
+    async fn test_boolean_rle_encoding_verification() {
+        use crate::testing::{TestCases, check_round_trip_encoding_of_data};
+        use crate::version::LanceFileVersion;
+        use arrow_array::{Array, BooleanArray};
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let test_cases = TestCases::default()
+            .with_expected_encoding("rle")
+            .with_min_file_version(LanceFileVersion::V2_3);
+
+        // Flags that rarely change
+        let flags = BooleanArray::from_iter((0..10_000).map(|i| Some((i / 1000) % 3 == 0)));
+        check_round_trip_encoding_of_data(
+            vec![Arc::new(flags) as Arc<dyn Array>],
+            &test_cases,
+            HashMap::new(),
+        )
+        .await;
+
+        let nullable_flags = BooleanArray::from_iter((0..10_000).map(|i| match (i / 500) % 4 {
+            0 => None,
+            1 => Some(false),
+            _ => Some(true),
+        }));
+        check_round_trip_encoding_of_data(
+            vec![Arc::new(nullable_flags) as Arc<dyn Array>],
+            &test_cases,
+            HashMap::new(),
+        )
+        .await;
+    }
+
     /// Generator that produces repetitive patterns suitable for RLE
     #[derive(Debug)]
     struct RleDataGenerator {
@@ -1173,9 +1173,10 @@ mod tests {
 
         // 2. Test automatic fallback to flat encoding when bitpacking conditions aren't met
         // Use unique values to avoid RLE encoding
-        // Explicitly disable BSS to ensure value encoding is tested
+        // Explicitly disable BSS and delta encoding to ensure value encoding is tested
         let mut metadata = HashMap::new();
         metadata.insert("lance-encoding:bss".to_string(), "off".to_string());
+        metadata.insert("lance-encoding:delta".to_string(), "off".to_string());
 
         let arr_fallback = Arc::new(Int32Array::from(
             (0..100).map(|i| i * 73 + 19).collect::<Vec<i32>>(),
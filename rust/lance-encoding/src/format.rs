@@ -460,6 +460,22 @@ macro_rules! impl_common_protobuf_utils {
                 }
             }
 
+            pub fn delta(
+                uncompressed_bits_per_value: u64,
+                order: u32,
+            ) -> crate::format::$module::CompressiveEncoding {
+                crate::format::$module::CompressiveEncoding {
+                    compression: Some(
+                        crate::format::$module::compressive_encoding::Compression::Delta(
+                            crate::format::$module::Delta {
+                                uncompressed_bits_per_value,
+                                order,
+                            },
+                        ),
+                    ),
+                }
+            }
+
             pub fn fsst(
                 data: crate::format::$module::CompressiveEncoding,
                 symbol_table: Vec<u8>,
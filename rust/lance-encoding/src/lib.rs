@@ -17,6 +17,7 @@ pub mod decoder;
 pub mod encoder;
 pub mod encodings;
 pub mod format;
+pub mod metrics;
 pub mod previous;
 pub mod repdef;
 pub mod statistics;
@@ -76,6 +77,13 @@ pub trait EncodingsIo: std::fmt::Debug + Send + Sync {
             .boxed()
     }
 
+    /// Hint that the given byte ranges will be requested soon
+    ///
+    /// Implementations may start reading the ranges in the background so that later
+    /// requests for them can be served without waiting on storage.  The default
+    /// implementation ignores the hint.
+    fn prefetch(&self, _ranges: Vec<Range<u64>>) {}
+
     /// Returns a version of this I/O service that bypasses backpressure for all requests.
     ///
     /// This is intended for indirect I/O (e.g. fetching items after decoding offsets) where
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Metrics describing the decode work done by a reader
+//!
+//! Attach a [`DecodeMetricsRecorder`] to a read (see [`crate::decoder::DecoderConfig::decode_metrics`])
+//! to find out how many pages were decoded, how much data they decoded into, and how long the
+//! decoding took, per column.  Comparing the decode time with the wall time of the scan (and
+//! the I/O statistics of the scheduler) tells whether a slow scan is bound by I/O or by decoding.
+//!
+//! Only files with version 2.1 or later report decode metrics.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// A sink that receives the decode metrics of a read
+///
+/// Implementations must be cheap and non-blocking since they are called inline by the
+/// decode tasks.
+pub trait DecodeMetricsRecorder: std::fmt::Debug + Send + Sync {
+    /// Record that a page of the column has been loaded and is ready to be decoded
+    ///
+    /// Very large pages may be loaded (and recorded) in several shards.
+    fn record_page_loaded(&self, column_index: u32);
+
+    /// Record one decode of (part of) a page of the column
+    ///
+    /// `num_bytes` is the size of the decoded (decompressed) data.
+    fn record_decode(&self, column_index: u32, num_bytes: u64, elapsed: Duration);
+}
+
+/// The decode metrics of a single column
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnDecodeMetrics {
+    /// The number of pages (or page shards) that were loaded for decoding
+    pub pages_loaded: u64,
+    /// The number of decode tasks that were run, a page may be decoded in several tasks
+    pub decode_tasks: u64,
+    /// The size of the decoded (decompressed) data in bytes
+    pub bytes_decoded: u64,
+    /// The total time spent decoding
+    ///
+    /// Decode tasks run in parallel so this can exceed the wall time of the read.
+    pub decode_time: Duration,
+}
+
+impl ColumnDecodeMetrics {
+    fn add(&mut self, other: &Self) {
+        self.pages_loaded += other.pages_loaded;
+        self.decode_tasks += other.decode_tasks;
+        self.bytes_decoded += other.bytes_decoded;
+        self.decode_time += other.decode_time;
+    }
+}
+
+/// A snapshot of the decode metrics of a read, keyed by column index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeStats {
+    pub columns: BTreeMap<u32, ColumnDecodeMetrics>,
+}
+
+impl DecodeStats {
+    /// The metrics summed over all columns
+    pub fn total(&self) -> ColumnDecodeMetrics {
+        let mut total = ColumnDecodeMetrics::default();
+        for column in self.columns.values() {
+            total.add(column);
+        }
+        total
+    }
+}
+
+#[derive(Debug, Default)]
+struct DecodeMetricsCollector {
+    columns: Mutex<BTreeMap<u32, ColumnDecodeMetrics>>,
+}
+
+impl DecodeMetricsRecorder for DecodeMetricsCollector {
+    fn record_page_loaded(&self, column_index: u32) {
+        let mut columns = self.columns.lock().unwrap();
+        columns.entry(column_index).or_default().pages_loaded += 1;
+    }
+
+    fn record_decode(&self, column_index: u32, num_bytes: u64, elapsed: Duration) {
+        let mut columns = self.columns.lock().unwrap();
+        let column = columns.entry(column_index).or_default();
+        column.decode_tasks += 1;
+        column.bytes_decoded += num_bytes;
+        column.decode_time += elapsed;
+    }
+}
+
+/// Collects decode metrics per column
+///
+/// All clones share the same counters.  Attach the [`Self::recorder`] to one or more reads
+/// and read the result back with [`Self::snapshot`].
+#[derive(Debug, Clone, Default)]
+pub struct DecodeMetrics(Arc<DecodeMetricsCollector>);
+
+impl DecodeMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a snapshot of the current cumulative metrics
+    pub fn snapshot(&self) -> DecodeStats {
+        DecodeStats {
+            columns: self.0.columns.lock().unwrap().clone(),
+        }
+    }
+
+    /// Return this handle as a type-erased [`DecodeMetricsRecorder`] that shares the same
+    /// counters as `self`
+    pub fn recorder(&self) -> Arc<dyn DecodeMetricsRecorder> {
+        self.0.clone()
+    }
+}
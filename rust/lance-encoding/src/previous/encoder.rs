@@ -259,6 +259,9 @@ impl FieldEncodingStrategy for CoreFieldEncodingStrategy {
                         Err(Error::not_supported_source(format!("cannot encode a dictionary column whose value type is a logical type ({})", value_type).into()))
                     }
                 }
+                DataType::RunEndEncoded(_, _) => Err(Error::not_supported_source(
+                    "Run-end encoded columns require Lance file version 2.1 or later".into(),
+                )),
                 _ => todo!("Implement encoding for field {}", field),
             }
         }
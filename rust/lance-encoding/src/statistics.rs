@@ -8,6 +8,7 @@ use std::{
 };
 
 use arrow_array::{Array, ArrowPrimitiveType, UInt64Array, cast::AsArray, types::UInt64Type};
+use arrow_buffer::BooleanBuffer;
 use hyperloglogplus::{HyperLogLog, HyperLogLogPlus};
 use num_traits::PrimInt;
 
@@ -475,6 +476,14 @@ impl FixedWidthDataBlock {
         }
 
         let run_count = match self.bits_per_value {
+            1 => {
+                let bits = BooleanBuffer::new(
+                    self.data.clone().into_buffer(),
+                    0,
+                    self.num_values as usize,
+                );
+                count_runs(&bits.iter().collect::<Vec<_>>())
+            }
             8 => {
                 let u8_slice = self.data.borrow_to_typed_slice::<u8>();
                 count_runs(u8_slice.as_ref())
@@ -599,8 +608,8 @@ mod tests {
     use std::sync::Arc;
 
     use arrow_array::{
-        ArrayRef, Int8Array, Int16Array, Int32Array, Int64Array, LargeStringArray, StringArray,
-        UInt8Array, UInt16Array, UInt32Array, UInt64Array,
+        ArrayRef, BooleanArray, Int8Array, Int16Array, Int32Array, Int64Array, LargeStringArray,
+        StringArray, UInt8Array, UInt16Array, UInt32Array, UInt64Array,
     };
     use arrow_schema::{DataType, Field};
     use lance_arrow::DataTypeExt;
@@ -1211,6 +1220,12 @@ mod tests {
         let expected_run_count = 3;
         let actual_run_count = block.expect_single_stat::<UInt64Type>(Stat::RunCount);
         assert_eq!(actual_run_count, expected_run_count);
+
+        let boolean_array = BooleanArray::from(vec![true, true, false, false, false, true]);
+        let block = DataBlock::from_array(boolean_array);
+        let expected_run_count = 3;
+        let actual_run_count = block.expect_single_stat::<UInt64Type>(Stat::RunCount);
+        assert_eq!(actual_run_count, expected_run_count);
     }
 
     #[test]
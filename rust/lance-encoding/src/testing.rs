@@ -351,6 +351,7 @@ pub async fn check_round_trip_encoding_generated(
                 keep_original_array: true,
                 buffer_alignment: MIN_PAGE_BUFFER_ALIGNMENT,
                 version,
+                page_row_alignment: 1,
             };
             encoding_strategy
                 .create_field_encoder(
@@ -545,6 +546,7 @@ fn tag(e: &Compression) -> &'static str {
         FixedSizeList(_) => "fixed_size_list",
         PackedStruct(_) => "packed_struct",
         VariablePackedStruct(_) => "variable_packed_struct",
+        Delta(_) => "delta",
     }
 }
 
@@ -753,6 +755,7 @@ pub async fn check_round_trip_encoding_of_data_with_expected(
                 keep_original_array: true,
                 buffer_alignment: MIN_PAGE_BUFFER_ALIGNMENT,
                 version: file_version,
+                page_row_alignment: 1,
             };
             let encoder = encoding_strategy
                 .create_field_encoder(
@@ -17,6 +17,8 @@ pub struct AccumulationQueue {
     row_number: u64,
     // Number of top level rows represented in buffered_arrays, reset on flush
     num_rows: u64,
+    // Flushed pages (except the final one) hold a multiple of this many rows
+    row_alignment: u64,
     // This is only for logging / debugging purposes
     column_index: u32,
 }
@@ -31,9 +33,35 @@ impl AccumulationQueue {
             keep_original_array,
             row_number: u64::MAX,
             num_rows: 0,
+            row_alignment: 1,
         }
     }
 
+    /// Only flush once the queue holds a multiple of `row_alignment` rows
+    ///
+    /// The queue keeps accumulating past the cache size until the rows line up.  Callers
+    /// that can split arrays should use [`Self::aligned_split`] so that this happens
+    /// as soon as possible.
+    pub fn with_row_alignment(mut self, row_alignment: u64) -> Self {
+        self.row_alignment = row_alignment.max(1);
+        self
+    }
+
+    /// If inserting `array` (with `num_rows` rows) would fill the queue, returns how many
+    /// of its rows to insert first so that the flushed page is aligned
+    ///
+    /// Returns `None` if no alignment is configured, if the queue would not be flushed, or
+    /// if the queue is already aligned after inserting the entire array.
+    pub fn aligned_split(&self, array: &ArrayRef, num_rows: u64) -> Option<u64> {
+        if self.row_alignment <= 1
+            || self.current_bytes + array.get_array_memory_size() as u64 <= self.cache_bytes
+        {
+            return None;
+        }
+        let split = (self.row_alignment - self.num_rows % self.row_alignment) % self.row_alignment;
+        (split > 0 && split < num_rows).then_some(split)
+    }
+
     /// Adds an array to the queue, if there is enough data then the queue is flushed
     /// and returned
     pub fn insert(
@@ -47,7 +75,8 @@ impl AccumulationQueue {
         }
         self.num_rows += num_rows;
         self.current_bytes += array.get_array_memory_size() as u64;
-        if self.current_bytes > self.cache_bytes {
+        if self.current_bytes > self.cache_bytes && self.num_rows.is_multiple_of(self.row_alignment)
+        {
             debug!(
                 "Flushing column {} page of size {} bytes (unencoded)",
                 self.column_index, self.current_bytes
@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Page-level bloom filters
+//!
+//! When a column is listed in [`crate::writer::FileWriterOptions::bloom_filter_columns`]
+//! the writer builds a split block bloom filter over the values of every page of that
+//! column.  The filters for all columns are stored in a single global buffer (as a
+//! [`pbfile::BloomFilters`] message) and the index of that buffer is recorded in the
+//! schema metadata under [`BLOOM_FILTER_META_KEY`].
+//!
+//! Readers consult the filters for equality predicates so that pages which cannot
+//! contain a value are never decoded, see [`crate::reader::FileReader::bloom_filter_ranges`].
+
+use std::ops::Range;
+
+use arrow_array::{Array, cast::AsArray};
+use arrow_schema::DataType;
+use lance_core::utils::bloomfilter::sbbf::Sbbf;
+use lance_core::{Error, Result};
+
+use crate::format::pbfile;
+
+/// Schema metadata key holding the index of the global buffer with the bloom filters
+pub const BLOOM_FILTER_META_KEY: &str = "lance:bloom_filters";
+
+/// The default false positive probability of page bloom filters
+pub const DEFAULT_BLOOM_FILTER_FPP: f64 = 0.01;
+
+/// Whether page bloom filters can be written for a column of the given type
+///
+/// Floating point columns are not supported because values that compare equal
+/// (e.g. `0.0` and `-0.0`) can have different bit patterns.
+pub fn supports_bloom_filter(data_type: &DataType) -> bool {
+    data_type.is_integer()
+        || data_type.is_temporal()
+        || matches!(
+            data_type,
+            DataType::Decimal32(_, _)
+                | DataType::Decimal64(_, _)
+                | DataType::Decimal128(_, _)
+                | DataType::Decimal256(_, _)
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Utf8View
+                | DataType::Binary
+                | DataType::LargeBinary
+                | DataType::BinaryView
+                | DataType::FixedSizeBinary(_)
+        )
+}
+
+/// Whether a value of type `value` can be looked up in a filter built for `column`
+fn is_compatible(column: &DataType, value: &DataType) -> bool {
+    let is_string = |data_type: &DataType| {
+        matches!(
+            data_type,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+        )
+    };
+    let is_binary = |data_type: &DataType| {
+        matches!(
+            data_type,
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView
+        )
+    };
+    column == value
+        || (is_string(column) && is_string(value))
+        || (is_binary(column) && is_binary(value))
+}
+
+/// Appends the hash of every value in `array` to `hashes` (`None` for nulls)
+///
+/// Variable width values hash their bytes so that, for example, `Utf8` and
+/// `LargeUtf8` values hash the same.  Fixed width values hash their little
+/// endian representation.
+fn hash_values(array: &dyn Array, hashes: &mut Vec<Option<u64>>) -> Result<()> {
+    hashes.reserve(array.len());
+    match array.data_type() {
+        DataType::Utf8 => hashes.extend(
+            array
+                .as_string::<i32>()
+                .iter()
+                .map(|value| value.map(|value| Sbbf::hash(value.as_bytes()))),
+        ),
+        DataType::LargeUtf8 => hashes.extend(
+            array
+                .as_string::<i64>()
+                .iter()
+                .map(|value| value.map(|value| Sbbf::hash(value.as_bytes()))),
+        ),
+        DataType::Utf8View => hashes.extend(
+            array
+                .as_string_view()
+                .iter()
+                .map(|value| value.map(|value| Sbbf::hash(value.as_bytes()))),
+        ),
+        DataType::Binary => hashes.extend(
+            array
+                .as_binary::<i32>()
+                .iter()
+                .map(|value| value.map(Sbbf::hash)),
+        ),
+        DataType::LargeBinary => hashes.extend(
+            array
+                .as_binary::<i64>()
+                .iter()
+                .map(|value| value.map(Sbbf::hash)),
+        ),
+        DataType::BinaryView => hashes.extend(
+            array
+                .as_binary_view()
+                .iter()
+                .map(|value| value.map(Sbbf::hash)),
+        ),
+        DataType::FixedSizeBinary(_) => hashes.extend(
+            array
+                .as_fixed_size_binary()
+                .iter()
+                .map(|value| value.map(Sbbf::hash)),
+        ),
+        data_type if supports_bloom_filter(data_type) => {
+            let width = data_type.primitive_width().ok_or_else(|| {
+                Error::invalid_input(format!(
+                    "Cannot compute bloom filter hashes for data type {data_type}"
+                ))
+            })?;
+            let data = array.to_data();
+            let values = &data.buffers()[0].as_slice()[data.offset() * width..];
+            hashes.extend((0..array.len()).map(|idx| {
+                array
+                    .is_valid(idx)
+                    .then(|| Sbbf::hash(&values[idx * width..(idx + 1) * width]))
+            }));
+        }
+        data_type => {
+            return Err(Error::invalid_input(format!(
+                "Page bloom filters are not supported for data type {data_type}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Collects the values of one column while it is written and builds a bloom
+/// filter for each of its pages when the file is finished
+pub(crate) struct PageBloomFilterBuilder {
+    field_id: i32,
+    field_name: String,
+    column_index: u32,
+    /// The hash of every row written so far, `None` for nulls
+    hashes: Vec<Option<u64>>,
+    /// (first row, number of rows) of every page written for the column
+    pages: Vec<(u64, u64)>,
+}
+
+impl PageBloomFilterBuilder {
+    pub fn new(field_id: i32, field_name: String, column_index: u32) -> Self {
+        Self {
+            field_id,
+            field_name,
+            column_index,
+            hashes: Vec::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    pub fn field_name(&self) -> &str {
+        &self.field_name
+    }
+
+    pub fn column_index(&self) -> u32 {
+        self.column_index
+    }
+
+    pub fn update(&mut self, array: &dyn Array) -> Result<()> {
+        hash_values(array, &mut self.hashes)
+    }
+
+    pub fn add_page(&mut self, first_row: u64, num_rows: u64) {
+        self.pages.push((first_row, num_rows));
+    }
+
+    pub fn finish(mut self, fpp: f64) -> Result<pbfile::ColumnBloomFilters> {
+        self.pages.sort_unstable();
+        let mut filters = pbfile::ColumnBloomFilters {
+            field_id: self.field_id,
+            page_first_rows: Vec::with_capacity(self.pages.len()),
+            page_num_rows: Vec::with_capacity(self.pages.len()),
+            page_filters: Vec::with_capacity(self.pages.len()),
+        };
+        for (first_row, num_rows) in self.pages {
+            let start = (first_row as usize).min(self.hashes.len());
+            let end = ((first_row + num_rows) as usize).min(self.hashes.len());
+            let mut page_hashes = self.hashes[start..end]
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>();
+            page_hashes.sort_unstable();
+            page_hashes.dedup();
+
+            // A page without any values gets an empty filter, it can never match
+            let filter = if page_hashes.is_empty() {
+                Vec::new()
+            } else {
+                let mut sbbf = Sbbf::with_ndv_fpp(page_hashes.len() as u64, fpp)
+                    .map_err(|e| Error::invalid_input(e.to_string()))?;
+                for hash in page_hashes {
+                    sbbf.insert_hash(hash);
+                }
+                sbbf.to_bytes()
+            };
+            filters.page_first_rows.push(first_row);
+            filters.page_num_rows.push(num_rows);
+            filters.page_filters.push(filter);
+        }
+        Ok(filters)
+    }
+}
+
+/// Returns the row ranges of the pages whose filter might contain `value`
+///
+/// `column_type` is the type of the column the filters were built for and `value`
+/// must be a single element array.  Adjacent ranges are merged.
+pub(crate) fn matching_page_ranges(
+    filters: &pbfile::ColumnBloomFilters,
+    column_type: &DataType,
+    value: &dyn Array,
+) -> Result<Vec<Range<u64>>> {
+    if value.len() != 1 {
+        return Err(Error::invalid_input(format!(
+            "Expected a single value to probe the bloom filters with but got {}",
+            value.len()
+        )));
+    }
+    if !is_compatible(column_type, value.data_type()) {
+        return Err(Error::invalid_input(format!(
+            "Cannot probe the bloom filters of a {} column with a {} value",
+            column_type,
+            value.data_type()
+        )));
+    }
+    let mut hashes = Vec::with_capacity(1);
+    hash_values(value, &mut hashes)?;
+    // `column = NULL` never matches anything
+    let Some(hash) = hashes[0] else {
+        return Ok(Vec::new());
+    };
+
+    let mut ranges: Vec<Range<u64>> = Vec::new();
+    let pages = filters
+        .page_first_rows
+        .iter()
+        .zip(&filters.page_num_rows)
+        .zip(&filters.page_filters);
+    for ((first_row, num_rows), filter) in pages {
+        if filter.is_empty() {
+            continue;
+        }
+        let sbbf = Sbbf::new(filter).map_err(|e| Error::invalid_input(e.to_string()))?;
+        if !sbbf.check_hash(hash) {
+            continue;
+        }
+        let range = *first_row..first_row + num_rows;
+        match ranges.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => ranges.push(range),
+        }
+    }
+    Ok(ranges)
+}
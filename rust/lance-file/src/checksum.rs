@@ -0,0 +1,345 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Checksums of the data buffers of a file
+//!
+//! When [`crate::writer::FileWriterOptions::write_checksums`] is set the writer computes
+//! a checksum of every buffer it writes (page buffers, column buffers, out-of-line
+//! buffers and global buffers other than the schema).  The checksums are stored in a
+//! single global buffer (as a [`pbfile::Checksums`] message) and the index of that
+//! buffer is recorded in the schema metadata under [`CHECKSUMS_META_KEY`].
+//!
+//! Checksums are computed over the bytes as they are stored (e.g. after encryption) so
+//! they can be verified without any keys.  Readers verify every buffer they load when
+//! [`crate::reader::FileReaderOptions::verify_checksums`] is set and [`verify_file`]
+//! checks an entire file.  The file metadata (column metadata, schema and footer) is not
+//! covered by the checksums, corruption there is detected when the metadata is decoded.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::{FutureExt, TryFutureExt, future::BoxFuture};
+use lance_core::{Error, Result};
+use lance_encoding::EncodingsIo;
+use lance_io::scheduler::FileScheduler;
+use object_store::path::Path;
+use prost::Message;
+use twox_hash::XxHash3_64;
+
+use crate::format::pbfile;
+use crate::reader::{CachedFileMetadata, FileReader};
+
+/// Schema metadata key holding the index of the global buffer with the checksums
+pub const CHECKSUMS_META_KEY: &str = "lance:checksums";
+
+/// The only checksum algorithm written today
+pub const CHECKSUM_ALGORITHM: &str = "xxh3-64";
+
+/// Limits how much data [`verify_file`] reads at once
+const VERIFY_BATCH_BYTES: u64 = 64 * 1024 * 1024;
+
+pub(crate) fn checksum(data: &[u8]) -> u64 {
+    XxHash3_64::oneshot(data)
+}
+
+/// Collects the checksums of the buffers written to a file
+#[derive(Debug, Default)]
+pub(crate) struct ChecksumsBuilder {
+    checksums: pbfile::Checksums,
+}
+
+impl ChecksumsBuilder {
+    /// Records the checksum of `data`, written at `position`
+    pub fn add_buffer(&mut self, position: u64, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        self.checksums.buffer_offsets.push(position);
+        self.checksums.buffer_sizes.push(data.len() as u64);
+        self.checksums.buffer_checksums.push(checksum(data));
+    }
+
+    pub fn finish(self) -> pbfile::Checksums {
+        pbfile::Checksums {
+            algorithm: CHECKSUM_ALGORITHM.to_string(),
+            ..self.checksums
+        }
+    }
+}
+
+/// A buffer of a file and its expected checksum
+#[derive(Debug, Clone)]
+struct ChecksummedBuffer {
+    range: Range<u64>,
+    checksum: u64,
+}
+
+impl ChecksummedBuffer {
+    fn verify(&self, path: &Path, data: &[u8]) -> Result<()> {
+        let actual = checksum(data);
+        if actual != self.checksum {
+            return Err(Error::corrupt_file(
+                path.clone(),
+                format!(
+                    "Checksum mismatch for the buffer at {}..{} (expected {:016x}, got {:016x})",
+                    self.range.start, self.range.end, self.checksum, actual
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Reads and validates the checksums of a file, returns `None` if the file has none
+async fn read_checksums(
+    metadata: &CachedFileMetadata,
+    read_buffer: impl AsyncFnOnce(Range<u64>) -> Result<Bytes>,
+    path: &Path,
+) -> Result<Option<Vec<ChecksummedBuffer>>> {
+    let Some(buffer_index) = metadata.file_schema.metadata.get(CHECKSUMS_META_KEY) else {
+        return Ok(None);
+    };
+    let buffer = buffer_index
+        .parse::<usize>()
+        .ok()
+        .and_then(|buffer_index| metadata.file_buffers.get(buffer_index))
+        .ok_or_else(|| {
+            Error::corrupt_file(
+                path.clone(),
+                format!("Invalid checksum buffer index {}", buffer_index),
+            )
+        })?;
+    let bytes = read_buffer(buffer.position..buffer.position + buffer.size).await?;
+    let checksums = pbfile::Checksums::decode(bytes)?;
+    if checksums.algorithm != CHECKSUM_ALGORITHM {
+        return Err(Error::not_supported(format!(
+            "Unsupported checksum algorithm `{}`",
+            checksums.algorithm
+        )));
+    }
+    let num_buffers = checksums.buffer_offsets.len();
+    if checksums.buffer_sizes.len() != num_buffers
+        || checksums.buffer_checksums.len() != num_buffers
+    {
+        return Err(Error::corrupt_file(
+            path.clone(),
+            "The checksum buffer is malformed",
+        ));
+    }
+    let mut buffers = checksums
+        .buffer_offsets
+        .iter()
+        .zip(&checksums.buffer_sizes)
+        .zip(&checksums.buffer_checksums)
+        .map(|((offset, size), checksum)| ChecksummedBuffer {
+            range: *offset..offset + size,
+            checksum: *checksum,
+        })
+        .collect::<Vec<_>>();
+    buffers.sort_by_key(|buffer| buffer.range.start);
+    Ok(Some(buffers))
+}
+
+/// The checksums of a file, used to verify the buffers a reader loads
+#[derive(Debug)]
+pub(crate) struct ChecksumVerification {
+    path: Path,
+    /// The checksummed buffers, sorted by position
+    buffers: Arc<[ChecksummedBuffer]>,
+    /// The indices of the buffers that have already been verified
+    verified: Arc<Mutex<HashSet<usize>>>,
+}
+
+impl ChecksumVerification {
+    /// Loads the checksums of a file, fails if the file does not have checksums
+    pub async fn try_new(
+        metadata: &CachedFileMetadata,
+        io: &Arc<dyn EncodingsIo>,
+        path: &Path,
+    ) -> Result<Self> {
+        let buffers = read_checksums(
+            metadata,
+            async |range| io.submit_single(range, 0).await,
+            path,
+        )
+        .await?
+        .ok_or_else(|| {
+            Error::invalid_input(format!(
+                "Cannot verify the checksums of {} because it was written without checksums",
+                path
+            ))
+        })?;
+        Ok(Self {
+            path: path.clone(),
+            buffers: Arc::from(buffers),
+            verified: Arc::default(),
+        })
+    }
+
+    /// Wraps `io` so that every checksummed buffer is verified the first time it is read
+    pub fn wrap_io(&self, io: Arc<dyn EncodingsIo>) -> Arc<dyn EncodingsIo> {
+        Arc::new(VerifyingIo {
+            inner: io,
+            path: self.path.clone(),
+            buffers: self.buffers.clone(),
+            verified: self.verified.clone(),
+        })
+    }
+}
+
+/// An [`EncodingsIo`] that verifies the checksums of the buffers it reads
+///
+/// Requests usually cover only part of a buffer (e.g. a few mini-block chunks) so the
+/// first read that touches a buffer also reads (and verifies) the entire buffer.  Later
+/// reads of the same buffer are passed through unchanged.
+#[derive(Debug)]
+struct VerifyingIo {
+    inner: Arc<dyn EncodingsIo>,
+    path: Path,
+    buffers: Arc<[ChecksummedBuffer]>,
+    verified: Arc<Mutex<HashSet<usize>>>,
+}
+
+impl VerifyingIo {
+    /// The indices of the buffers overlapping `ranges` that have not been verified yet
+    fn unverified_buffers(&self, ranges: &[Range<u64>]) -> Vec<usize> {
+        let verified = self.verified.lock().unwrap();
+        let mut unverified = Vec::new();
+        for range in ranges {
+            let first = self
+                .buffers
+                .partition_point(|buffer| buffer.range.end <= range.start);
+            unverified.extend(
+                (first..self.buffers.len())
+                    .take_while(|idx| self.buffers[*idx].range.start < range.end)
+                    .filter(|idx| !verified.contains(idx)),
+            );
+        }
+        unverified.sort_unstable();
+        unverified.dedup();
+        unverified
+    }
+
+    fn rewrap(&self, inner: Arc<dyn EncodingsIo>) -> Arc<dyn EncodingsIo> {
+        Arc::new(Self {
+            inner,
+            path: self.path.clone(),
+            buffers: self.buffers.clone(),
+            verified: self.verified.clone(),
+        })
+    }
+}
+
+impl EncodingsIo for VerifyingIo {
+    fn submit_request(
+        &self,
+        ranges: Vec<Range<u64>>,
+        priority: u64,
+    ) -> BoxFuture<'static, Result<Vec<Bytes>>> {
+        let unverified = self.unverified_buffers(&ranges);
+        if unverified.is_empty() {
+            return self.inner.submit_request(ranges, priority);
+        }
+        let num_requested = ranges.len();
+        let mut request = ranges;
+        request.extend(
+            unverified
+                .iter()
+                .map(|idx| self.buffers[*idx].range.clone()),
+        );
+        let path = self.path.clone();
+        let buffers = self.buffers.clone();
+        let verified = self.verified.clone();
+        self.inner
+            .submit_request(request, priority)
+            .and_then(move |mut data| async move {
+                for (idx, bytes) in unverified.iter().zip(&data[num_requested..]) {
+                    buffers[*idx].verify(&path, bytes)?;
+                }
+                verified.lock().unwrap().extend(unverified);
+                data.truncate(num_requested);
+                Ok(data)
+            })
+            .boxed()
+    }
+
+    fn prefetch(&self, ranges: Vec<Range<u64>>) {
+        self.inner.prefetch(ranges);
+    }
+
+    fn with_bypass_backpressure(&self) -> Option<Arc<dyn EncodingsIo>> {
+        self.inner
+            .with_bypass_backpressure()
+            .map(|inner| self.rewrap(inner))
+    }
+
+    fn with_io_stats(
+        &self,
+        stats: Arc<dyn lance_core::utils::io_stats::IoStatsRecorder>,
+    ) -> Option<Arc<dyn EncodingsIo>> {
+        self.inner
+            .with_io_stats(stats)
+            .map(|inner| self.rewrap(inner))
+    }
+}
+
+/// Summary of a successful [`verify_file`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifiedFile {
+    /// The number of buffers that were verified
+    pub num_buffers: u64,
+    /// The total size of the verified buffers in bytes
+    pub num_bytes: u64,
+}
+
+/// Reads every checksummed buffer of a file and verifies its checksum
+///
+/// Returns an error if the file was written without checksums or if any buffer does
+/// not match its checksum.  The file metadata is decoded as part of this so corrupt
+/// metadata is reported as well.
+pub async fn verify_file(scheduler: &FileScheduler) -> Result<VerifiedFile> {
+    let metadata = FileReader::read_all_metadata(scheduler).await?;
+    let path = scheduler.reader().path().clone();
+    let buffers = read_checksums(
+        &metadata,
+        async |range| scheduler.submit_single(range, 0).await,
+        &path,
+    )
+    .await?
+    .ok_or_else(|| {
+        Error::invalid_input(format!(
+            "Cannot verify the checksums of {} because it was written without checksums",
+            path
+        ))
+    })?;
+
+    let mut batch_start = 0;
+    while batch_start < buffers.len() {
+        let mut batch_end = batch_start;
+        let mut batch_bytes = 0;
+        while batch_end < buffers.len()
+            && (batch_end == batch_start || batch_bytes < VERIFY_BATCH_BYTES)
+        {
+            let range = &buffers[batch_end].range;
+            batch_bytes += range.end - range.start;
+            batch_end += 1;
+        }
+        let batch = &buffers[batch_start..batch_end];
+        let data = scheduler
+            .submit_request(batch.iter().map(|buffer| buffer.range.clone()).collect(), 0)
+            .await?;
+        for (buffer, bytes) in batch.iter().zip(&data) {
+            buffer.verify(&path, bytes)?;
+        }
+        batch_start = batch_end;
+    }
+    Ok(VerifiedFile {
+        num_buffers: buffers.len() as u64,
+        num_bytes: buffers
+            .iter()
+            .map(|buffer| buffer.range.end - buffer.range.start)
+            .sum(),
+    })
+}
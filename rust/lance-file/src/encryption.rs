@@ -0,0 +1,484 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Column-level encryption
+//!
+//! When a column is listed in [`crate::writer::FileWriterOptions::encrypted_columns`]
+//! the writer encrypts the page buffers, column buffers and page encoding
+//! descriptions of that column with AES-256 in counter mode.  Every encrypted
+//! column gets its own random nonce.  The nonces and the ids of the keys (never
+//! the keys themselves) are stored in a single global buffer (as a
+//! [`pbfile::EncryptedColumns`] message) and the index of that buffer is recorded
+//! in the schema metadata under [`ENCRYPTION_META_KEY`].
+//!
+//! The keystream of a buffer is positioned at the absolute file offset of the
+//! buffer so that any byte range can be decrypted without reading the rest of the
+//! buffer.  This keeps random access (e.g. takes) working on encrypted columns.
+//!
+//! Readers are given keys through [`crate::reader::FileReaderOptions::encryption_keys`].
+//! Columns whose key was not provided cannot be read but all other columns of the
+//! file remain readable.
+//!
+//! Counter mode does not authenticate the data.  A wrong key is detected (each
+//! column records a key check value) but tampering with encrypted bytes is not.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::sync::Arc;
+
+use aes::Aes256;
+use aes::cipher::{BlockEncrypt, KeyInit, KeyIvInit, StreamCipher, StreamCipherSeek};
+use bytes::Bytes;
+use futures::{FutureExt, TryFutureExt, future::BoxFuture};
+use lance_core::datatypes::Schema;
+use lance_core::{Error, Result};
+use lance_encoding::EncodingsIo;
+use lance_encoding::decoder::{ColumnInfo, PageEncoding, PageInfo};
+use lance_encoding::format::pb21 as pbenc21;
+
+use crate::format::pbfile;
+use crate::reader::{CachedFileMetadata, FileReader};
+
+/// Schema metadata key holding the index of the global buffer with the encryption metadata
+pub const ENCRYPTION_META_KEY: &str = "lance:encryption";
+
+/// The size, in bytes, of an encryption key (AES-256)
+pub const ENCRYPTION_KEY_LEN: usize = 32;
+
+const NONCE_LEN: usize = 16;
+
+type Aes256Ctr = ctr::Ctr128BE<Aes256>;
+
+/// A key used to encrypt (or decrypt) columns
+///
+/// The id is recorded in the file so readers know which key to provide.  The key
+/// itself is never written.
+#[derive(Clone)]
+pub struct EncryptionKey {
+    id: String,
+    key: [u8; ENCRYPTION_KEY_LEN],
+}
+
+impl EncryptionKey {
+    pub fn new(id: impl Into<String>, key: [u8; ENCRYPTION_KEY_LEN]) -> Self {
+        Self { id: id.into(), key }
+    }
+
+    /// Creates a key from a slice which must be [`ENCRYPTION_KEY_LEN`] bytes long
+    pub fn try_new(id: impl Into<String>, key: &[u8]) -> Result<Self> {
+        let key = key.try_into().map_err(|_| {
+            Error::invalid_input(format!(
+                "Encryption keys must be {} bytes long but got {} bytes",
+                ENCRYPTION_KEY_LEN,
+                key.len()
+            ))
+        })?;
+        Ok(Self::new(id, key))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// A block of zeros encrypted with the key, used to detect wrong keys
+    fn key_check(&self) -> Vec<u8> {
+        let mut block = [0_u8; 16].into();
+        Aes256::new(&self.key.into()).encrypt_block(&mut block);
+        block.to_vec()
+    }
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("id", &self.id)
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+/// The keystream of a single encrypted column
+///
+/// Counter mode is symmetric so this both encrypts and decrypts.
+#[derive(Debug)]
+pub(crate) struct ColumnCipher {
+    key: [u8; ENCRYPTION_KEY_LEN],
+    nonce: [u8; NONCE_LEN],
+}
+
+impl ColumnCipher {
+    fn try_new(key: &EncryptionKey, nonce: &[u8]) -> Result<Self> {
+        let nonce = nonce.try_into().map_err(|_| {
+            Error::invalid_input(format!(
+                "Invalid encryption nonce, expected {} bytes but got {}",
+                NONCE_LEN,
+                nonce.len()
+            ))
+        })?;
+        Ok(Self {
+            key: key.key,
+            nonce,
+        })
+    }
+
+    fn apply(&self, nonce: [u8; NONCE_LEN], position: u64, data: &mut [u8]) {
+        let mut cipher = Aes256Ctr::new(&self.key.into(), &nonce.into());
+        cipher.seek(position);
+        cipher.apply_keystream(data);
+    }
+
+    /// Applies the keystream to `data` which starts at `offset` in the file
+    pub fn apply_to_buffer(&self, offset: u64, data: &mut [u8]) {
+        self.apply(self.nonce, offset, data);
+    }
+
+    /// Applies the keystream to the encoding description of the `page_idx`-th page
+    pub fn apply_to_description(&self, page_idx: u64, data: &mut [u8]) {
+        let mut nonce = self.nonce;
+        nonce[0] ^= 0x80;
+        self.apply(nonce, page_idx << 32, data);
+    }
+}
+
+/// Encrypts the columns of one field while it is written
+pub(crate) struct ColumnEncryptor {
+    cipher: ColumnCipher,
+    encryption: pbfile::ColumnEncryption,
+    /// The number of pages written so far for each column of the field
+    num_pages: HashMap<u32, u64>,
+}
+
+impl ColumnEncryptor {
+    pub fn new(field_id: i32, column_indices: Vec<u32>, key: &EncryptionKey) -> Self {
+        let nonce = rand::random::<[u8; NONCE_LEN]>();
+        Self {
+            cipher: ColumnCipher {
+                key: key.key,
+                nonce,
+            },
+            encryption: pbfile::ColumnEncryption {
+                field_id,
+                column_indices,
+                key_id: key.id.clone(),
+                nonce: nonce.to_vec(),
+                key_check: key.key_check(),
+            },
+            num_pages: HashMap::new(),
+        }
+    }
+
+    pub fn encrypts_column(&self, column_index: u32) -> bool {
+        self.encryption.column_indices.contains(&column_index)
+    }
+
+    /// Encrypts a buffer that will be written at `offset`
+    pub fn encrypt_buffer(&self, offset: u64, data: &[u8]) -> Vec<u8> {
+        let mut data = data.to_vec();
+        self.cipher.apply_to_buffer(offset, &mut data);
+        data
+    }
+
+    /// Encrypts the description of the next page of `column_index`
+    pub fn encrypt_description(&mut self, column_index: u32, mut description: Vec<u8>) -> Vec<u8> {
+        let page_idx = self.num_pages.entry(column_index).or_default();
+        self.cipher
+            .apply_to_description(*page_idx, &mut description);
+        *page_idx += 1;
+        description
+    }
+
+    pub fn finish(self) -> pbfile::ColumnEncryption {
+        self.encryption
+    }
+}
+
+/// Page infos for the pages of an encrypted column, used until the column is decrypted
+///
+/// The page descriptions cannot be decoded without the key so they are replaced
+/// with an empty layout.
+pub(crate) fn encrypted_page_infos(column: &pbfile::ColumnMetadata) -> Vec<PageInfo> {
+    column
+        .pages
+        .iter()
+        .map(|page| PageInfo {
+            num_rows: page.length,
+            priority: page.priority,
+            encoding: PageEncoding::Structural(pbenc21::PageLayout::default()),
+            buffer_offsets_and_sizes: Arc::from(
+                page.buffer_offsets
+                    .iter()
+                    .copied()
+                    .zip(page.buffer_sizes.iter().copied())
+                    .collect::<Vec<_>>(),
+            ),
+        })
+        .collect()
+}
+
+fn decrypt_page_encoding(
+    cipher: &ColumnCipher,
+    page_idx: usize,
+    encoding: Option<&pbfile::Encoding>,
+) -> Result<PageEncoding> {
+    let Some(pbfile::encoding::Location::Direct(direct)) =
+        encoding.and_then(|encoding| encoding.location.as_ref())
+    else {
+        return Err(Error::invalid_input(
+            "Encrypted pages must have a direct encoding".to_string(),
+        ));
+    };
+    let mut description = direct.encoding.clone();
+    cipher.apply_to_description(page_idx as u64, &mut description);
+    let decrypted = pbfile::Encoding {
+        location: Some(pbfile::encoding::Location::Direct(pbfile::DirectEncoding {
+            encoding: description,
+        })),
+    };
+    Ok(PageEncoding::Structural(FileReader::fetch_encoding::<
+        pbenc21::PageLayout,
+    >(&decrypted)))
+}
+
+/// The encrypted columns of a file, decrypted with the keys given to a reader
+#[derive(Debug)]
+pub(crate) struct FileDecryption {
+    /// The column infos of all columns with the page descriptions of the columns
+    /// that could be decrypted replaced by their decrypted version
+    column_infos: Vec<Arc<ColumnInfo>>,
+    /// (field name, key id) of the columns whose key was not provided
+    missing_keys: BTreeMap<u32, (String, String)>,
+    /// The encrypted buffers that can be decrypted, sorted by position
+    buffers: Arc<[(Range<u64>, Arc<ColumnCipher>)]>,
+}
+
+impl FileDecryption {
+    /// Returns `None` if the file does not contain any encrypted columns
+    pub fn try_new(metadata: &CachedFileMetadata, keys: &[EncryptionKey]) -> Result<Option<Self>> {
+        if metadata.encrypted_columns.is_empty() {
+            return Ok(None);
+        }
+        let mut column_infos = metadata.column_infos.clone();
+        let mut missing_keys = BTreeMap::new();
+        let mut buffers = Vec::new();
+        for encryption in &metadata.encrypted_columns {
+            let Some(key) = keys.iter().find(|key| key.id == encryption.key_id) else {
+                let field_name = field_name(&metadata.file_schema, encryption.field_id);
+                for column_index in &encryption.column_indices {
+                    missing_keys.insert(
+                        *column_index,
+                        (field_name.clone(), encryption.key_id.clone()),
+                    );
+                }
+                continue;
+            };
+            if key.key_check() != encryption.key_check {
+                return Err(Error::invalid_input(format!(
+                    "The key provided for key id `{}` is not the key column `{}` was encrypted with",
+                    encryption.key_id,
+                    field_name(&metadata.file_schema, encryption.field_id)
+                )));
+            }
+            let cipher = Arc::new(ColumnCipher::try_new(key, &encryption.nonce)?);
+            for column_index in &encryption.column_indices {
+                let column = metadata
+                    .column_metadatas
+                    .get(*column_index as usize)
+                    .ok_or_else(|| {
+                        Error::invalid_input(format!(
+                            "Encrypted column index {} is out of bounds",
+                            column_index
+                        ))
+                    })?;
+                let column_info = &column_infos[*column_index as usize];
+                let page_infos = column_info
+                    .page_infos
+                    .iter()
+                    .zip(&column.pages)
+                    .enumerate()
+                    .map(|(page_idx, (page_info, page))| {
+                        buffers.extend(
+                            page_info
+                                .buffer_offsets_and_sizes
+                                .iter()
+                                .map(|(offset, size)| (*offset..offset + size, cipher.clone())),
+                        );
+                        Ok(PageInfo {
+                            num_rows: page_info.num_rows,
+                            priority: page_info.priority,
+                            encoding: decrypt_page_encoding(
+                                &cipher,
+                                page_idx,
+                                page.encoding.as_ref(),
+                            )?,
+                            buffer_offsets_and_sizes: page_info.buffer_offsets_and_sizes.clone(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                buffers.extend(
+                    column_info
+                        .buffer_offsets_and_sizes
+                        .iter()
+                        .map(|(offset, size)| (*offset..offset + size, cipher.clone())),
+                );
+                column_infos[*column_index as usize] = Arc::new(ColumnInfo {
+                    page_infos: Arc::from(page_infos),
+                    ..column_info.as_ref().clone()
+                });
+            }
+        }
+        buffers.retain(|(range, _)| !range.is_empty());
+        buffers.sort_by_key(|(range, _)| range.start);
+        Ok(Some(Self {
+            column_infos,
+            missing_keys,
+            buffers: Arc::from(buffers),
+        }))
+    }
+
+    pub fn column_infos(&self) -> &[Arc<ColumnInfo>] {
+        &self.column_infos
+    }
+
+    /// Makes sure the key of every column in `column_indices` was provided
+    pub fn check_columns(&self, column_indices: &[u32]) -> Result<()> {
+        for column_index in column_indices {
+            if let Some((field_name, key_id)) = self.missing_keys.get(column_index) {
+                return Err(Error::invalid_input(format!(
+                    "Column `{}` is encrypted with the key `{}` which was not provided",
+                    field_name, key_id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Wraps `io` so that reads of encrypted buffers are decrypted
+    pub fn wrap_io(&self, io: Arc<dyn EncodingsIo>) -> Arc<dyn EncodingsIo> {
+        Arc::new(DecryptingIo {
+            inner: io,
+            buffers: self.buffers.clone(),
+        })
+    }
+}
+
+fn field_name(schema: &Schema, field_id: i32) -> String {
+    schema
+        .field_by_id(field_id)
+        .map(|field| field.name.clone())
+        .unwrap_or_else(|| format!("<field id {}>", field_id))
+}
+
+/// An [`EncodingsIo`] that decrypts the encrypted parts of the ranges it reads
+#[derive(Debug)]
+struct DecryptingIo {
+    inner: Arc<dyn EncodingsIo>,
+    buffers: Arc<[(Range<u64>, Arc<ColumnCipher>)]>,
+}
+
+impl DecryptingIo {
+    fn decrypt(
+        buffers: &[(Range<u64>, Arc<ColumnCipher>)],
+        range: &Range<u64>,
+        data: Bytes,
+    ) -> Bytes {
+        let first = buffers.partition_point(|(buffer, _)| buffer.end <= range.start);
+        let overlapping = buffers[first..]
+            .iter()
+            .take_while(|(buffer, _)| buffer.start < range.end)
+            .collect::<Vec<_>>();
+        if overlapping.is_empty() {
+            return data;
+        }
+        let mut data = data.to_vec();
+        for (buffer, cipher) in overlapping {
+            let start = buffer.start.max(range.start);
+            let end = buffer.end.min(range.end);
+            cipher.apply_to_buffer(
+                start,
+                &mut data[(start - range.start) as usize..(end - range.start) as usize],
+            );
+        }
+        Bytes::from(data)
+    }
+}
+
+impl EncodingsIo for DecryptingIo {
+    fn submit_request(
+        &self,
+        ranges: Vec<Range<u64>>,
+        priority: u64,
+    ) -> BoxFuture<'static, Result<Vec<Bytes>>> {
+        let buffers = self.buffers.clone();
+        self.inner
+            .submit_request(ranges.clone(), priority)
+            .map_ok(move |data| {
+                ranges
+                    .iter()
+                    .zip(data)
+                    .map(|(range, data)| Self::decrypt(&buffers, range, data))
+                    .collect()
+            })
+            .boxed()
+    }
+
+    fn prefetch(&self, ranges: Vec<Range<u64>>) {
+        self.inner.prefetch(ranges);
+    }
+
+    fn with_bypass_backpressure(&self) -> Option<Arc<dyn EncodingsIo>> {
+        self.inner.with_bypass_backpressure().map(|inner| {
+            Arc::new(Self {
+                inner,
+                buffers: self.buffers.clone(),
+            }) as Arc<dyn EncodingsIo>
+        })
+    }
+
+    fn with_io_stats(
+        &self,
+        stats: Arc<dyn lance_core::utils::io_stats::IoStatsRecorder>,
+    ) -> Option<Arc<dyn EncodingsIo>> {
+        self.inner.with_io_stats(stats).map(|inner| {
+            Arc::new(Self {
+                inner,
+                buffers: self.buffers.clone(),
+            }) as Arc<dyn EncodingsIo>
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystream_is_positional() {
+        let key = EncryptionKey::new("k", [7; ENCRYPTION_KEY_LEN]);
+        let encryptor = ColumnEncryptor::new(0, vec![0], &key);
+        let data = (0..1000).map(|i| i as u8).collect::<Vec<_>>();
+        let encrypted = encryptor.encrypt_buffer(4096, &data);
+        assert_ne!(encrypted, data);
+
+        // Any sub-range can be decrypted on its own
+        let cipher = ColumnCipher::try_new(&key, &encryptor.encryption.nonce).unwrap();
+        let mut part = encrypted[17..333].to_vec();
+        cipher.apply_to_buffer(4096 + 17, &mut part);
+        assert_eq!(part, data[17..333]);
+
+        let buffers: Arc<[_]> = Arc::from(vec![(4096..5096, Arc::new(cipher))]);
+        let mut padded = vec![1_u8; 10];
+        padded.extend_from_slice(&encrypted[..500]);
+        let decrypted = DecryptingIo::decrypt(&buffers, &(4086..4596), Bytes::from(padded));
+        assert_eq!(&decrypted[..10], &[1; 10]);
+        assert_eq!(&decrypted[10..], &data[..500]);
+    }
+
+    #[test]
+    fn test_key_debug_is_redacted() {
+        let key = EncryptionKey::try_new("my-key", &[42; ENCRYPTION_KEY_LEN]).unwrap();
+        let debug = format!("{:?}", key);
+        assert!(debug.contains("my-key"));
+        assert!(!debug.contains("42"));
+        assert!(EncryptionKey::try_new("short", &[0; 16]).is_err());
+    }
+}
@@ -0,0 +1,519 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Inspection of the physical layout of a file
+//!
+//! [`FileInspection`] reports, for every column of a file, the layout and compression of each
+//! page together with the size of the buffers that were written.  This is meant to diagnose
+//! format regressions (e.g. a column that stopped being compressed) and bloated columns without
+//! having to decode the footer by hand.
+//!
+//! The inspection only uses the file metadata (see [`crate::reader::FileReader::inspect`]), no
+//! data pages are read.
+
+use std::fmt;
+
+use arrow_schema::DataType;
+use bytes::Bytes;
+use lance_arrow::DataTypeExt;
+use lance_core::datatypes::Field;
+use lance_encoding::format::pb as pbenc;
+use lance_encoding::format::pb21 as pbenc21;
+use lance_encoding::version::LanceFileVersion;
+use prost::Message;
+
+use crate::format::pbfile;
+use crate::reader::CachedFileMetadata;
+
+/// The layout of a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageLayoutKind {
+    /// The values are compressed in small chunks (2.1+)
+    MiniBlock,
+    /// The values are compressed individually and zipped with the repetition / definition
+    /// levels (2.1+)
+    FullZip,
+    /// All (visible) values are the same value or null (2.1+)
+    Constant,
+    /// The values are stored out of line and the page only holds their descriptions (2.1+)
+    Blob,
+    /// The page uses the 2.0 encodings
+    Legacy,
+    /// The page description is encrypted and cannot be inspected without the key
+    Encrypted,
+    /// The page description could not be decoded
+    Unknown,
+}
+
+impl fmt::Display for PageLayoutKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::MiniBlock => "mini-block",
+            Self::FullZip => "full-zip",
+            Self::Constant => "constant",
+            Self::Blob => "blob",
+            Self::Legacy => "legacy",
+            Self::Encrypted => "encrypted",
+            Self::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The physical layout of a single page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageInspection {
+    /// The number of rows in the page
+    pub num_rows: u64,
+    /// The read priority of the page (the first row of the page in most cases)
+    pub priority: u64,
+    /// The layout of the page
+    pub layout: PageLayoutKind,
+    /// A description of the compression applied to the values of the page
+    ///
+    /// For example, `general(zstd, inline_bitpacking(32))`.  This is `None` if the layout
+    /// has no value compression (e.g. constant pages) or if the page could not be decoded.
+    pub compression: Option<String>,
+    /// The number of items (values, including those in lists) in the page, if known
+    pub num_items: Option<u64>,
+    /// The size in bytes of each buffer of the page
+    pub buffer_sizes: Vec<u64>,
+}
+
+impl PageInspection {
+    /// The total size of the buffers of the page
+    pub fn size_bytes(&self) -> u64 {
+        self.buffer_sizes.iter().sum()
+    }
+}
+
+/// The physical layout of a single column
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnInspection {
+    /// The index of the column in the file
+    pub column_index: u32,
+    /// The path of the field stored in the column (e.g. `parent.child`)
+    ///
+    /// This is `None` if the columns could not be matched with the schema.
+    pub field_path: Option<String>,
+    /// The data type of the field stored in the column
+    pub data_type: Option<DataType>,
+    /// The pages of the column
+    pub pages: Vec<PageInspection>,
+    /// The size in bytes of each column-level buffer (e.g. a 2.0 dictionary)
+    pub column_buffer_sizes: Vec<u64>,
+    /// An estimate of the size of the column's values before encoding
+    ///
+    /// This is only available for fixed-width columns that are not nested in a list and
+    /// does not include validity.
+    pub uncompressed_bytes: Option<u64>,
+}
+
+impl ColumnInspection {
+    /// The number of bytes the column occupies on disk (page and column buffers)
+    pub fn encoded_bytes(&self) -> u64 {
+        self.pages.iter().map(|page| page.size_bytes()).sum::<u64>()
+            + self.column_buffer_sizes.iter().sum::<u64>()
+    }
+
+    /// The ratio of the estimated uncompressed size to the encoded size
+    ///
+    /// Values above 1 mean the column was compressed.  This is `None` if the uncompressed
+    /// size is unknown or the column is empty.
+    pub fn compression_ratio(&self) -> Option<f64> {
+        let uncompressed_bytes = self.uncompressed_bytes?;
+        let encoded_bytes = self.encoded_bytes();
+        if encoded_bytes == 0 {
+            None
+        } else {
+            Some(uncompressed_bytes as f64 / encoded_bytes as f64)
+        }
+    }
+}
+
+/// A report of the physical layout of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileInspection {
+    pub version: LanceFileVersion,
+    pub num_rows: u64,
+    pub file_size_bytes: u64,
+    /// The number of bytes in the data page section of the file
+    pub num_data_bytes: u64,
+    /// The number of bytes in the column metadata
+    pub num_column_metadata_bytes: u64,
+    /// The number of bytes in global buffers (including the schema)
+    pub num_global_buffer_bytes: u64,
+    /// The number of bytes in the CMO and GBO tables
+    pub num_footer_bytes: u64,
+    /// The columns of the file, in column order
+    pub columns: Vec<ColumnInspection>,
+}
+
+impl FileInspection {
+    /// Inspect a file from its metadata
+    pub fn new(metadata: &CachedFileMetadata) -> Self {
+        let version = metadata.version();
+        let mut leaves = Vec::with_capacity(metadata.column_metadatas.len());
+        collect_column_fields(
+            metadata.file_schema.fields.iter(),
+            version >= LanceFileVersion::V2_1,
+            "",
+            false,
+            &mut leaves,
+        );
+        // If the schema does not line up with the columns we still report the columns
+        if leaves.len() != metadata.column_metadatas.len() {
+            leaves.clear();
+        }
+
+        let columns = metadata
+            .column_metadatas
+            .iter()
+            .enumerate()
+            .map(|(column_index, column_metadata)| {
+                let is_encrypted = metadata
+                    .encrypted_columns
+                    .iter()
+                    .any(|encryption| encryption.column_indices.contains(&(column_index as u32)));
+                let leaf = leaves.get(column_index);
+                inspect_column(
+                    column_index as u32,
+                    column_metadata,
+                    leaf,
+                    version,
+                    is_encrypted,
+                )
+            })
+            .collect();
+
+        Self {
+            version,
+            num_rows: metadata.num_rows,
+            file_size_bytes: metadata.file_size_bytes,
+            num_data_bytes: metadata.num_data_bytes,
+            num_column_metadata_bytes: metadata.num_column_metadata_bytes,
+            num_global_buffer_bytes: metadata.num_global_buffer_bytes,
+            num_footer_bytes: metadata.num_footer_bytes,
+            columns,
+        }
+    }
+}
+
+impl fmt::Display for FileInspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "version: {}", self.version)?;
+        writeln!(f, "num_rows: {}", self.num_rows)?;
+        writeln!(f, "file_size_bytes: {}", self.file_size_bytes)?;
+        writeln!(f, "num_data_bytes: {}", self.num_data_bytes)?;
+        writeln!(
+            f,
+            "num_column_metadata_bytes: {}",
+            self.num_column_metadata_bytes
+        )?;
+        writeln!(
+            f,
+            "num_global_buffer_bytes: {}",
+            self.num_global_buffer_bytes
+        )?;
+        writeln!(f, "num_footer_bytes: {}", self.num_footer_bytes)?;
+        writeln!(f, "columns:")?;
+        for column in &self.columns {
+            write!(
+                f,
+                "  [{}] {}",
+                column.column_index,
+                column.field_path.as_deref().unwrap_or("?")
+            )?;
+            if let Some(data_type) = &column.data_type {
+                write!(f, ": {}", data_type)?;
+            }
+            write!(
+                f,
+                " pages={} encoded_bytes={}",
+                column.pages.len(),
+                column.encoded_bytes()
+            )?;
+            if let Some(ratio) = column.compression_ratio() {
+                write!(f, " compression_ratio={:.2}", ratio)?;
+            }
+            writeln!(f)?;
+            for page in &column.pages {
+                write!(
+                    f,
+                    "    rows={} layout={} bytes={:?}",
+                    page.num_rows, page.layout, page.buffer_sizes
+                )?;
+                if let Some(compression) = &page.compression {
+                    write!(f, " compression={}", compression)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct ColumnField<'a> {
+    path: String,
+    field: &'a Field,
+    in_list: bool,
+}
+
+// Walks the schema in column order, this must match the order in which the encoders
+// assign column indices
+fn collect_column_fields<'a>(
+    fields: impl Iterator<Item = &'a Field>,
+    is_structural: bool,
+    parent_path: &str,
+    in_list: bool,
+    leaves: &mut Vec<ColumnField<'a>>,
+) {
+    for field in fields {
+        let path = if parent_path.is_empty() {
+            field.name.clone()
+        } else {
+            format!("{}.{}", parent_path, field.name)
+        };
+        let is_leaf = field.children.is_empty() || field.is_packed_struct() || field.is_blob();
+        // In 2.0 every field has a column, in 2.1+ only leaf fields do
+        if !is_structural || is_leaf {
+            leaves.push(ColumnField {
+                path: path.clone(),
+                field,
+                in_list,
+            });
+        }
+        if !is_structural || !is_leaf {
+            let in_list = in_list
+                || matches!(
+                    field.data_type(),
+                    DataType::List(_)
+                        | DataType::LargeList(_)
+                        | DataType::FixedSizeList(_, _)
+                        | DataType::Map(_, _)
+                );
+            collect_column_fields(field.children.iter(), is_structural, &path, in_list, leaves);
+        }
+    }
+}
+
+fn inspect_column(
+    column_index: u32,
+    column_metadata: &pbfile::ColumnMetadata,
+    leaf: Option<&ColumnField<'_>>,
+    version: LanceFileVersion,
+    is_encrypted: bool,
+) -> ColumnInspection {
+    let pages = column_metadata
+        .pages
+        .iter()
+        .map(|page| inspect_page(page, version, is_encrypted))
+        .collect::<Vec<_>>();
+
+    let data_type = leaf.map(|leaf| leaf.field.data_type());
+    let uncompressed_bytes = leaf.and_then(|leaf| {
+        if leaf.in_list {
+            return None;
+        }
+        let num_rows = pages.iter().map(|page| page.num_rows).sum::<u64>();
+        match leaf.field.data_type() {
+            DataType::Boolean => Some(num_rows.div_ceil(8)),
+            DataType::Struct(_) => None,
+            data_type => data_type
+                .byte_width_opt()
+                .map(|width| width as u64 * num_rows),
+        }
+    });
+
+    ColumnInspection {
+        column_index,
+        field_path: leaf.map(|leaf| leaf.path.clone()),
+        data_type,
+        pages,
+        column_buffer_sizes: column_metadata.buffer_sizes.clone(),
+        uncompressed_bytes,
+    }
+}
+
+fn inspect_page(
+    page: &pbfile::column_metadata::Page,
+    version: LanceFileVersion,
+    is_encrypted: bool,
+) -> PageInspection {
+    let (layout, compression, num_items) = if is_encrypted {
+        (PageLayoutKind::Encrypted, None, None)
+    } else if version < LanceFileVersion::V2_1 {
+        let layout = if decode_page_encoding::<pbenc::ArrayEncoding>(page).is_some() {
+            PageLayoutKind::Legacy
+        } else {
+            PageLayoutKind::Unknown
+        };
+        (layout, None, None)
+    } else {
+        match decode_page_encoding::<pbenc21::PageLayout>(page) {
+            Some(page_layout) => describe_page_layout(&page_layout),
+            None => (PageLayoutKind::Unknown, None, None),
+        }
+    };
+    PageInspection {
+        num_rows: page.length,
+        priority: page.priority,
+        layout,
+        compression,
+        num_items,
+        buffer_sizes: page.buffer_sizes.clone(),
+    }
+}
+
+fn decode_page_encoding<M: Default + prost::Name>(
+    page: &pbfile::column_metadata::Page,
+) -> Option<M> {
+    match page.encoding.as_ref()?.location.as_ref()? {
+        pbfile::encoding::Location::Direct(direct) => {
+            let encoding_any =
+                prost_types::Any::decode(Bytes::from(direct.encoding.clone())).ok()?;
+            encoding_any.to_msg::<M>().ok()
+        }
+        _ => None,
+    }
+}
+
+fn describe_page_layout(
+    page_layout: &pbenc21::PageLayout,
+) -> (PageLayoutKind, Option<String>, Option<u64>) {
+    use pbenc21::page_layout::Layout;
+    match &page_layout.layout {
+        Some(Layout::MiniBlockLayout(mini_block)) => {
+            let compression = mini_block.value_compression.as_ref().map(|values| {
+                let values = describe_compression(values);
+                match &mini_block.dictionary {
+                    Some(items) => {
+                        format!("dictionary({}, {})", values, describe_compression(items))
+                    }
+                    None => values,
+                }
+            });
+            (
+                PageLayoutKind::MiniBlock,
+                compression,
+                Some(mini_block.num_items),
+            )
+        }
+        Some(Layout::FullZipLayout(full_zip)) => (
+            PageLayoutKind::FullZip,
+            full_zip
+                .value_compression
+                .as_ref()
+                .map(describe_compression),
+            Some(full_zip.num_items as u64),
+        ),
+        Some(Layout::ConstantLayout(_)) => (PageLayoutKind::Constant, None, None),
+        Some(Layout::BlobLayout(blob)) => {
+            let (_, compression, num_items) = blob
+                .inner_layout
+                .as_deref()
+                .map(describe_page_layout)
+                .unwrap_or((PageLayoutKind::Unknown, None, None));
+            (PageLayoutKind::Blob, compression, num_items)
+        }
+        None => (PageLayoutKind::Unknown, None, None),
+    }
+}
+
+fn describe_buffer_compression(compression: &pbenc21::BufferCompression) -> &'static str {
+    match pbenc21::CompressionScheme::try_from(compression.scheme) {
+        Ok(pbenc21::CompressionScheme::CompressionAlgorithmLz4) => "lz4",
+        Ok(pbenc21::CompressionScheme::CompressionAlgorithmZstd) => "zstd",
+        _ => "unknown",
+    }
+}
+
+fn describe_child(encoding: Option<&pbenc21::CompressiveEncoding>) -> String {
+    encoding
+        .map(describe_compression)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Describes a compressive encoding tree in a compact, human readable form
+///
+/// Each encoding is rendered as `name(args)` where the arguments are the bit widths and
+/// general compression schemes of the encoding followed by its child encodings, for
+/// example `general(zstd, inline_bitpacking(32))`.
+pub fn describe_compression(encoding: &pbenc21::CompressiveEncoding) -> String {
+    use pbenc21::compressive_encoding::Compression;
+    let with_buffer = |name: String, buffer: Option<&pbenc21::BufferCompression>| match buffer {
+        Some(buffer) => format!("{}, {})", name, describe_buffer_compression(buffer)),
+        None => format!("{})", name),
+    };
+    match &encoding.compression {
+        Some(Compression::Flat(flat)) => {
+            with_buffer(format!("flat({}", flat.bits_per_value), flat.data.as_ref())
+        }
+        Some(Compression::Variable(variable)) => with_buffer(
+            format!("variable({}", describe_child(variable.offsets.as_deref())),
+            variable.values.as_ref(),
+        ),
+        Some(Compression::Constant(_)) => "constant".to_string(),
+        Some(Compression::OutOfLineBitpacking(bitpacking)) => format!(
+            "out_of_line_bitpacking({}, {})",
+            bitpacking.uncompressed_bits_per_value,
+            describe_child(bitpacking.values.as_deref())
+        ),
+        Some(Compression::InlineBitpacking(bitpacking)) => with_buffer(
+            format!(
+                "inline_bitpacking({}",
+                bitpacking.uncompressed_bits_per_value
+            ),
+            bitpacking.values.as_ref(),
+        ),
+        Some(Compression::Fsst(fsst)) => {
+            format!("fsst({})", describe_child(fsst.values.as_deref()))
+        }
+        Some(Compression::Dictionary(dictionary)) => format!(
+            "dictionary({}, {})",
+            describe_child(dictionary.indices.as_deref()),
+            describe_child(dictionary.items.as_deref())
+        ),
+        Some(Compression::Rle(rle)) => format!(
+            "rle({}, {})",
+            describe_child(rle.values.as_deref()),
+            describe_child(rle.run_lengths.as_deref())
+        ),
+        Some(Compression::ByteStreamSplit(bss)) => format!(
+            "byte_stream_split({})",
+            describe_child(bss.values.as_deref())
+        ),
+        Some(Compression::General(general)) => format!(
+            "general({}, {})",
+            general
+                .compression
+                .as_ref()
+                .map(describe_buffer_compression)
+                .unwrap_or("unknown"),
+            describe_child(general.values.as_deref())
+        ),
+        Some(Compression::FixedSizeList(fsl)) => format!(
+            "fixed_size_list({}, {})",
+            fsl.items_per_value,
+            describe_child(fsl.values.as_deref())
+        ),
+        Some(Compression::PackedStruct(packed)) => format!(
+            "packed_struct({})",
+            describe_child(packed.values.as_deref())
+        ),
+        Some(Compression::VariablePackedStruct(packed)) => format!(
+            "variable_packed_struct({})",
+            packed
+                .fields
+                .iter()
+                .map(|field| describe_child(field.value.as_ref()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(Compression::Delta(delta)) => format!(
+            "delta({}, {})",
+            delta.uncompressed_bits_per_value, delta.order
+        ),
+        None => "unknown".to_string(),
+    }
+}
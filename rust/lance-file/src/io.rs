@@ -48,6 +48,10 @@ impl EncodingsIo for LanceEncodingsIo {
         }))
     }
 
+    fn prefetch(&self, ranges: Vec<std::ops::Range<u64>>) {
+        self.scheduler.prefetch(ranges);
+    }
+
     fn submit_request(
         &self,
         ranges: Vec<std::ops::Range<u64>>,
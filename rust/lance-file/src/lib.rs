@@ -1,9 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
+pub mod bloom_filter;
+pub mod checksum;
 pub mod datatypes;
+pub mod encryption;
 pub mod format;
+pub mod inspect;
 pub(crate) mod io;
+pub mod page_statistics;
 pub mod previous;
 pub mod reader;
 pub mod testing;
@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Page-level statistics
+//!
+//! When a column is listed in [`crate::writer::FileWriterOptions::page_statistics_columns`]
+//! the writer records the minimum, maximum and null count of every page of that column.
+//! The statistics for all columns are stored in a single global buffer (as a
+//! [`pbfile::PageStatistics`] message) and the index of that buffer is recorded in the
+//! schema metadata under [`PAGE_STATISTICS_META_KEY`].
+//!
+//! Readers evaluate simple predicates against the statistics so that pages which cannot
+//! contain a matching row are never decoded, see
+//! [`crate::reader::FileReader::page_statistics_ranges`].  This complements fragment
+//! level pruning (zone maps) with pruning inside of a file.
+
+use std::collections::VecDeque;
+use std::ops::{Bound, Range};
+
+use arrow_array::ArrayRef;
+use arrow_schema::DataType;
+use lance_arrow_scalar::ArrowScalar;
+use lance_arrow_stats::StatisticsAccumulator;
+use lance_core::{Error, Result};
+
+use crate::format::pbfile;
+
+/// Schema metadata key holding the index of the global buffer with the page statistics
+pub const PAGE_STATISTICS_META_KEY: &str = "lance:page_statistics";
+
+/// Whether page statistics can be written for a column of the given type
+///
+/// Floating point columns are not supported because the ordering used for the
+/// statistics (a total order) does not agree with SQL comparisons for `NaN` and
+/// `-0.0`.
+pub fn supports_page_statistics(data_type: &DataType) -> bool {
+    data_type.is_integer()
+        || data_type.is_temporal()
+        || matches!(
+            data_type,
+            DataType::Boolean
+                | DataType::Decimal32(_, _)
+                | DataType::Decimal64(_, _)
+                | DataType::Decimal128(_, _)
+                | DataType::Decimal256(_, _)
+                | DataType::Utf8
+                | DataType::LargeUtf8
+                | DataType::Binary
+                | DataType::LargeBinary
+                | DataType::FixedSizeBinary(_)
+        )
+}
+
+/// A predicate on a single column that can be evaluated against page statistics
+#[derive(Debug, Clone)]
+pub enum PagePredicate {
+    /// The value is not null and lies within the bounds
+    Range {
+        lower: Bound<ArrowScalar>,
+        upper: Bound<ArrowScalar>,
+    },
+    /// The value is null
+    IsNull,
+    /// The value is not null
+    IsNotNull,
+}
+
+impl PagePredicate {
+    /// `column = value`
+    pub fn eq(value: ArrowScalar) -> Self {
+        Self::Range {
+            lower: Bound::Included(value.clone()),
+            upper: Bound::Included(value),
+        }
+    }
+
+    fn bounds(&self) -> impl Iterator<Item = &ArrowScalar> {
+        let (lower, upper) = match self {
+            Self::Range { lower, upper } => (bound_value(lower), bound_value(upper)),
+            Self::IsNull | Self::IsNotNull => (None, None),
+        };
+        lower.into_iter().chain(upper)
+    }
+
+    /// Whether a page with the given statistics might contain a matching row
+    fn might_match(
+        &self,
+        num_rows: u64,
+        null_count: u64,
+        min: Option<&ArrowScalar>,
+        max: Option<&ArrowScalar>,
+    ) -> bool {
+        match self {
+            Self::IsNull => null_count > 0,
+            Self::IsNotNull => null_count < num_rows,
+            Self::Range { lower, upper } => {
+                // A page without values (all null) never matches a comparison
+                let (Some(min), Some(max)) = (min, max) else {
+                    return false;
+                };
+                let above_lower = match lower {
+                    Bound::Included(lower) => max >= lower,
+                    Bound::Excluded(lower) => max > lower,
+                    Bound::Unbounded => true,
+                };
+                let below_upper = match upper {
+                    Bound::Included(upper) => min <= upper,
+                    Bound::Excluded(upper) => min < upper,
+                    Bound::Unbounded => true,
+                };
+                above_lower && below_upper
+            }
+        }
+    }
+}
+
+fn bound_value(bound: &Bound<ArrowScalar>) -> Option<&ArrowScalar> {
+    match bound {
+        Bound::Included(value) | Bound::Excluded(value) => Some(value),
+        Bound::Unbounded => None,
+    }
+}
+
+fn encode_scalar(scalar: Option<ArrowScalar>) -> Result<Vec<u8>> {
+    Ok(scalar
+        .map(|scalar| scalar.encode())
+        .transpose()?
+        .unwrap_or_default())
+}
+
+fn decode_scalar(bytes: &[u8]) -> Result<Option<ArrowScalar>> {
+    if bytes.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(ArrowScalar::decode(bytes)?))
+    }
+}
+
+/// Collects the values of one column while it is written and computes the
+/// statistics of each page as soon as the page is written
+pub(crate) struct PageStatisticsBuilder {
+    field_name: String,
+    column_index: u32,
+    data_type: DataType,
+    /// Arrays (and the row number of their first row) that are not yet fully
+    /// covered by a written page
+    pending: VecDeque<(u64, ArrayRef)>,
+    statistics: pbfile::ColumnPageStatistics,
+}
+
+impl PageStatisticsBuilder {
+    pub fn new(field_id: i32, field_name: String, column_index: u32, data_type: DataType) -> Self {
+        Self {
+            field_name,
+            column_index,
+            data_type,
+            pending: VecDeque::new(),
+            statistics: pbfile::ColumnPageStatistics {
+                field_id,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn field_name(&self) -> &str {
+        &self.field_name
+    }
+
+    pub fn column_index(&self) -> u32 {
+        self.column_index
+    }
+
+    pub fn update(&mut self, first_row: u64, array: ArrayRef) {
+        if !array.is_empty() {
+            self.pending.push_back((first_row, array));
+        }
+    }
+
+    /// Computes the statistics of a page covering `num_rows` rows starting at `first_row`
+    ///
+    /// Pages of a column are written in row order so any pending array that ends
+    /// within the page is no longer needed afterwards.
+    pub fn add_page(&mut self, first_row: u64, num_rows: u64) -> Result<()> {
+        let end_row = first_row + num_rows;
+        let mut accumulator = StatisticsAccumulator::new(&self.data_type);
+        for (array_start, array) in &self.pending {
+            let array_end = array_start + array.len() as u64;
+            let start = first_row.max(*array_start);
+            let end = end_row.min(array_end);
+            if start < end {
+                accumulator
+                    .update(&array.slice((start - array_start) as usize, (end - start) as usize))?;
+            }
+        }
+        while let Some((array_start, array)) = self.pending.front() {
+            if array_start + array.len() as u64 > end_row {
+                break;
+            }
+            self.pending.pop_front();
+        }
+
+        let page_statistics = accumulator.finish();
+        let statistics = &mut self.statistics;
+        statistics.page_first_rows.push(first_row);
+        statistics.page_num_rows.push(num_rows);
+        statistics.page_null_counts.push(page_statistics.null_count);
+        statistics
+            .page_min_values
+            .push(encode_scalar(page_statistics.min)?);
+        statistics
+            .page_max_values
+            .push(encode_scalar(page_statistics.max)?);
+        Ok(())
+    }
+
+    pub fn finish(self) -> pbfile::ColumnPageStatistics {
+        self.statistics
+    }
+}
+
+/// Returns the row ranges of the pages whose statistics might satisfy `predicate`
+///
+/// `column_type` is the type of the column the statistics were collected for.
+/// Adjacent ranges are merged.
+pub(crate) fn matching_page_ranges(
+    statistics: &pbfile::ColumnPageStatistics,
+    column_type: &DataType,
+    predicate: &PagePredicate,
+) -> Result<Vec<Range<u64>>> {
+    if let Some(value) = predicate
+        .bounds()
+        .find(|value| value.data_type() != column_type)
+    {
+        return Err(Error::invalid_input(format!(
+            "Cannot compare the page statistics of a {} column with a {} value",
+            column_type,
+            value.data_type()
+        )));
+    }
+    if predicate.bounds().any(|value| value.is_null()) {
+        // Comparisons with NULL never match
+        return Ok(Vec::new());
+    }
+
+    let mut ranges: Vec<Range<u64>> = Vec::new();
+    for page_idx in 0..statistics.page_first_rows.len() {
+        let first_row = statistics.page_first_rows[page_idx];
+        let num_rows = statistics.page_num_rows[page_idx];
+        let min = decode_scalar(&statistics.page_min_values[page_idx])?;
+        let max = decode_scalar(&statistics.page_max_values[page_idx])?;
+        if !predicate.might_match(
+            num_rows,
+            statistics.page_null_counts[page_idx],
+            min.as_ref(),
+            max.as_ref(),
+        ) {
+            continue;
+        }
+        let range = first_row..first_row + num_rows;
+        match ranges.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => ranges.push(range),
+        }
+    }
+    Ok(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::Int64Array;
+
+    use super::*;
+
+    #[test]
+    fn test_pages_spanning_arrays() {
+        let mut builder = PageStatisticsBuilder::new(0, "x".to_string(), 0, DataType::Int64);
+        builder.update(0, Arc::new(Int64Array::from_iter_values(0..10)));
+        builder.update(
+            10,
+            Arc::new(Int64Array::from(vec![Some(100), None, Some(-5)])),
+        );
+        builder.update(13, Arc::new(Int64Array::from_iter_values(20..30)));
+        // The first page ends in the middle of the first array, the second page covers
+        // the rest of it, all of the second array and part of the third one
+        builder.add_page(0, 5).unwrap();
+        builder.add_page(5, 10).unwrap();
+        assert_eq!(builder.pending.len(), 1);
+        builder.add_page(15, 8).unwrap();
+        assert!(builder.pending.is_empty());
+
+        let statistics = builder.finish();
+        assert_eq!(statistics.page_null_counts, vec![0, 1, 0]);
+        let values = |values: &[Vec<u8>]| {
+            values
+                .iter()
+                .map(|value| decode_scalar(value).unwrap().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            values(&statistics.page_min_values),
+            vec![0_i64.into(), (-5_i64).into(), 22_i64.into()]
+        );
+        assert_eq!(
+            values(&statistics.page_max_values),
+            vec![4_i64.into(), 100_i64.into(), 29_i64.into()]
+        );
+
+        let ranges =
+            |predicate| matching_page_ranges(&statistics, &DataType::Int64, &predicate).unwrap();
+        assert_eq!(ranges(PagePredicate::eq(3_i64.into())), vec![0..15]);
+        assert_eq!(ranges(PagePredicate::eq(21_i64.into())), vec![5..15]);
+        assert_eq!(ranges(PagePredicate::eq(200_i64.into())), vec![]);
+        assert_eq!(ranges(PagePredicate::eq(25_i64.into())), vec![5..23]);
+        assert_eq!(ranges(PagePredicate::IsNull), vec![5..15]);
+        assert_eq!(
+            ranges(PagePredicate::eq(
+                ArrowScalar::new_null(&DataType::Int64).unwrap()
+            )),
+            vec![]
+        );
+    }
+}
@@ -9,11 +9,16 @@ use std::{
     sync::Arc,
 };
 
-use arrow_array::RecordBatchReader;
-use arrow_schema::Schema as ArrowSchema;
+use arrow_array::{Array, BooleanArray, RecordBatch, RecordBatchIterator, RecordBatchReader};
+use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use arrow_select::{
+    concat::concat_batches,
+    filter::{SlicesIterator, filter_record_batch, prep_null_mask_filter},
+};
 use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
 use bytes::{Bytes, BytesMut};
-use futures::{Stream, StreamExt, stream::BoxStream};
+use futures::{FutureExt, Stream, StreamExt, TryStreamExt, stream::BoxStream};
+use lance_arrow::RecordBatchExt;
 use lance_core::deepsize::{Context, DeepSizeOf};
 use lance_encoding::{
     EncodingsIo,
@@ -32,7 +37,7 @@ use prost::{Message, Name};
 use lance_core::{
     Error, Result,
     cache::LanceCache,
-    datatypes::{Field, Schema},
+    datatypes::{Field, LogicalType, Schema},
 };
 use lance_encoding::format::pb as pbenc;
 use lance_encoding::format::pb21 as pbenc21;
@@ -43,9 +48,14 @@ use lance_io::{
 };
 
 use crate::{
+    bloom_filter::{self, BLOOM_FILTER_META_KEY},
+    checksum::ChecksumVerification,
     datatypes::{Fields, FieldsWithMeta},
+    encryption::{self, ENCRYPTION_META_KEY, EncryptionKey, FileDecryption},
     format::{MAGIC, MAJOR_VERSION, MINOR_VERSION, pb, pbfile},
+    inspect::FileInspection,
     io::LanceEncodingsIo,
+    page_statistics::{self, PAGE_STATISTICS_META_KEY, PagePredicate},
     writer::PAGE_BUFFER_ALIGNMENT,
 };
 
@@ -118,6 +128,12 @@ pub struct CachedFileMetadata {
     /// not fetched through `read_global_buffer`. Buffers that fall outside the
     /// window (large files) are absent here and fall back to a dedicated read.
     pub retained_global_buffers: BTreeMap<u32, Bytes>,
+    /// The encrypted fields of the file (see [`crate::encryption`])
+    ///
+    /// The page descriptions of the columns of these fields are encrypted so the
+    /// page infos in `column_infos` have a placeholder encoding until a reader
+    /// with the key decrypts them.
+    pub encrypted_columns: Vec<pbfile::ColumnEncryption>,
 }
 
 impl CachedFileMetadata {
@@ -186,11 +202,18 @@ impl DeepSizeOf for CachedFileMetadata {
             .map(|buf| buf.len())
             .sum();
 
+        let encrypted_columns_size: usize = self
+            .encrypted_columns
+            .iter()
+            .map(|encryption| encryption.encoded_len() * 4)
+            .sum();
+
         schema_size
             + buffers_size
             + column_metadatas_size
             + column_infos_size
             + retained_buffers_size
+            + encrypted_columns_size
     }
 }
 
@@ -370,6 +393,9 @@ impl ReaderProjection {
     ///
     /// The syntax for column names is the same as [`lance_core::datatypes::Schema::project`]
     ///
+    /// Nested fields can be selected with a path (e.g. `location.x`) and only the columns
+    /// of the selected children are read.  Packed structs are always decoded in full.
+    ///
     /// If the schema provided is not the schema of the entire file then
     /// the projection will be invalid and the read will fail.
     pub fn from_column_names(
@@ -400,6 +426,207 @@ impl ReaderProjection {
             column_indices,
         })
     }
+
+    /// Reads the given top-level string / binary columns as dictionary arrays
+    ///
+    /// Pages that were dictionary encoded when the file was written (see the
+    /// `lance-encoding:dict-*` settings) are returned without materializing the
+    /// dictionary, which preserves the memory savings of dictionary encoding for
+    /// downstream consumers.  Other pages are dictionary encoded after decoding.
+    ///
+    /// This is only supported for 2.1+ files.
+    pub fn with_dictionary_columns(mut self, key_type: DataType, columns: &[&str]) -> Result<Self> {
+        if !key_type.is_dictionary_key_type() {
+            return Err(Error::invalid_input(format!(
+                "{} is not a valid dictionary key type",
+                key_type
+            )));
+        }
+        let schema = Arc::make_mut(&mut self.schema);
+        for column in columns {
+            let field = schema
+                .fields
+                .iter_mut()
+                .find(|field| field.name == *column)
+                .ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "Column `{}` is not a top-level column of the projection",
+                        column
+                    ))
+                })?;
+            let value_type = field.data_type();
+            if !matches!(
+                value_type,
+                DataType::Utf8 | DataType::LargeUtf8 | DataType::Binary | DataType::LargeBinary
+            ) {
+                return Err(Error::invalid_input(format!(
+                    "Column `{}` of type {} cannot be read as a dictionary",
+                    column, value_type
+                )));
+            }
+            field.logical_type = LogicalType::try_from(&DataType::Dictionary(
+                Box::new(key_type.clone()),
+                Box::new(value_type),
+            ))?;
+        }
+        Ok(self)
+    }
+
+    /// Reads the given top-level columns as run-end encoded arrays
+    ///
+    /// Consecutive equal values (including nulls) are returned as a single run which
+    /// keeps heavily repeated columns compact in memory.  Columns that were written as
+    /// run-end encoded arrays are returned as such without this.
+    ///
+    /// This is only supported for 2.1+ files.
+    pub fn with_run_end_encoded_columns(
+        mut self,
+        run_end_type: DataType,
+        columns: &[&str],
+    ) -> Result<Self> {
+        if !matches!(
+            run_end_type,
+            DataType::Int16 | DataType::Int32 | DataType::Int64
+        ) {
+            return Err(Error::invalid_input(format!(
+                "{} is not a valid run end type",
+                run_end_type
+            )));
+        }
+        let schema = Arc::make_mut(&mut self.schema);
+        for column in columns {
+            let field = schema
+                .fields
+                .iter_mut()
+                .find(|field| field.name == *column)
+                .ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "Column `{}` is not a top-level column of the projection",
+                        column
+                    ))
+                })?;
+            let value_type = field.data_type();
+            if !field.children.is_empty()
+                || field.is_blob()
+                || matches!(
+                    value_type,
+                    DataType::Dictionary(_, _) | DataType::RunEndEncoded(_, _)
+                )
+            {
+                return Err(Error::invalid_input(format!(
+                    "Column `{}` of type {} cannot be read as a run-end encoded array",
+                    column, value_type
+                )));
+            }
+            field.logical_type = LogicalType::try_from(&DataType::RunEndEncoded(
+                Arc::new(ArrowField::new("run_ends", run_end_type.clone(), false)),
+                Arc::new(ArrowField::new("values", value_type, true)),
+            ))?;
+        }
+        Ok(self)
+    }
+
+    /// Replaces any packed struct that selects only some of its children with the
+    /// complete packed struct from the file schema
+    ///
+    /// All children of a packed struct are stored in a single column so a packed
+    /// struct can only be decoded in full.  Returns `None` if the projection does not
+    /// need to change.  Other structs are decoded child by child and are left as is.
+    fn with_full_packed_structs(&self, file_schema: &Schema) -> Option<Self> {
+        fn full_packed_struct(field: &Field, file_schema: &Schema) -> Option<Field> {
+            // Blob columns are decoded into whatever shape the projection asks for
+            if field.is_blob() {
+                return None;
+            }
+            if field.is_packed_struct() {
+                let file_field = file_schema.field_by_id(field.id)?;
+                return (file_field.children.len() != field.children.len())
+                    .then(|| file_field.clone());
+            }
+            let mut widened: Option<Field> = None;
+            for (child_idx, child) in field.children.iter().enumerate() {
+                if let Some(child) = full_packed_struct(child, file_schema) {
+                    widened.get_or_insert_with(|| field.clone()).children[child_idx] = child;
+                }
+            }
+            widened
+        }
+
+        let mut schema: Option<Schema> = None;
+        for (field_idx, field) in self.schema.fields.iter().enumerate() {
+            if let Some(field) = full_packed_struct(field, file_schema) {
+                schema
+                    .get_or_insert_with(|| self.schema.as_ref().clone())
+                    .fields[field_idx] = field;
+            }
+        }
+        schema.map(|schema| Self {
+            schema: Arc::new(schema),
+            column_indices: self.column_indices.clone(),
+        })
+    }
+
+    /// The number of column indices a field (and its children) occupies in a projection
+    fn num_column_indices(file_version: LanceFileVersion, field: &Field) -> usize {
+        let is_structural = file_version >= LanceFileVersion::V2_1;
+        if is_structural && (field.is_blob() || field.is_packed_struct()) {
+            return 1;
+        }
+        let own = usize::from(!is_structural || field.children.is_empty());
+        own + field
+            .children
+            .iter()
+            .map(|child| Self::num_column_indices(file_version, child))
+            .sum::<usize>()
+    }
+
+    /// Splits the projection into one projection with the given top-level columns
+    /// and one projection with the remaining columns
+    fn partition(&self, file_version: LanceFileVersion, columns: &[&str]) -> Result<(Self, Self)> {
+        if let Some(column) = columns
+            .iter()
+            .find(|column| !self.schema.fields.iter().any(|f| f.name == **column))
+        {
+            return Err(Error::invalid_input(format!(
+                "Column `{}` is not a top-level column of the projection",
+                column
+            )));
+        }
+        let num_required = self
+            .schema
+            .fields
+            .iter()
+            .map(|field| Self::num_column_indices(file_version, field))
+            .sum::<usize>();
+        if num_required != self.column_indices.len() {
+            return Err(Error::invalid_input(format!(
+                "The projection has {} column indices but its schema requires {}",
+                self.column_indices.len(),
+                num_required
+            )));
+        }
+        let mut selected = (Vec::new(), Vec::new());
+        let mut remaining = (Vec::new(), Vec::new());
+        let mut column_indices = self.column_indices.iter().copied();
+        for field in &self.schema.fields {
+            let target = if columns.contains(&field.name.as_str()) {
+                &mut selected
+            } else {
+                &mut remaining
+            };
+            target.0.push(field.clone());
+            let num_indices = Self::num_column_indices(file_version, field);
+            target.1.extend(column_indices.by_ref().take(num_indices));
+        }
+        let to_projection = |(fields, column_indices): (Vec<Field>, Vec<u32>)| Self {
+            schema: Arc::new(Schema {
+                fields,
+                metadata: self.schema.metadata.clone(),
+            }),
+            column_indices,
+        };
+        Ok((to_projection(selected), to_projection(remaining)))
+    }
 }
 
 /// File Reader Options that can control reading behaviors, such as whether to enable caching on repetition indices
@@ -417,6 +644,17 @@ pub struct FileReaderOptions {
     /// to provide a default for all scans, or at the scanner level (via
     /// `Scanner::batch_size_bytes`) to override per scan.
     pub batch_size_bytes: Option<u64>,
+    /// Keys for the encrypted columns of the file
+    ///
+    /// Keys are matched to columns by their id.  Columns whose key is not given
+    /// cannot be read but the rest of the file can.
+    pub encryption_keys: Vec<EncryptionKey>,
+    /// If true, verify the checksum of every buffer the reader loads
+    ///
+    /// Opening the reader fails if the file was written without checksums.  Each
+    /// buffer is read in full (and verified) the first time any part of it is read,
+    /// see [`crate::checksum`].
+    pub verify_checksums: bool,
 }
 
 impl Default for FileReaderOptions {
@@ -425,10 +663,18 @@ impl Default for FileReaderOptions {
             decoder_config: DecoderConfig::default(),
             read_chunk_size: DEFAULT_READ_CHUNK_SIZE,
             batch_size_bytes: None,
+            encryption_keys: Vec::new(),
+            verify_checksums: false,
         }
     }
 }
 
+/// A predicate evaluated against batches of filter columns
+///
+/// Returns a mask with one entry per row of the batch.  Rows where the mask is
+/// false or null are discarded.  See [`FileReader::read_stream_late_materialized`].
+pub type BatchPredicate = Arc<dyn Fn(&RecordBatch) -> Result<BooleanArray> + Send + Sync>;
+
 #[derive(Debug, Clone)]
 pub struct FileReader {
     scheduler: Arc<dyn EncodingsIo>,
@@ -439,6 +685,8 @@ pub struct FileReader {
     decoder_plugins: Arc<DecoderPlugins>,
     cache: Arc<LanceCache>,
     options: FileReaderOptions,
+    decryption: Option<Arc<FileDecryption>>,
+    checksums: Option<Arc<ChecksumVerification>>,
 }
 #[derive(Debug)]
 struct Footer {
@@ -459,6 +707,34 @@ const FOOTER_LEN: usize = 40;
 
 impl FileReader {
     pub fn with_scheduler(&self, scheduler: Arc<dyn EncodingsIo>) -> Self {
+        let scheduler = Self::wrap_io(
+            scheduler,
+            self.checksums.as_deref(),
+            self.decryption.as_deref(),
+        );
+        self.with_wrapped_scheduler(scheduler)
+    }
+
+    // Checksums are computed over the stored bytes so they must be verified before
+    // the data is decrypted
+    fn wrap_io(
+        scheduler: Arc<dyn EncodingsIo>,
+        checksums: Option<&ChecksumVerification>,
+        decryption: Option<&FileDecryption>,
+    ) -> Arc<dyn EncodingsIo> {
+        let scheduler = match checksums {
+            Some(checksums) => checksums.wrap_io(scheduler),
+            None => scheduler,
+        };
+        match decryption {
+            Some(decryption) => decryption.wrap_io(scheduler),
+            None => scheduler,
+        }
+    }
+
+    // Like `with_scheduler` but `scheduler` already verifies checksums and decrypts
+    // encrypted columns
+    fn with_wrapped_scheduler(&self, scheduler: Arc<dyn EncodingsIo>) -> Self {
         Self {
             scheduler,
             base_projection: self.base_projection.clone(),
@@ -467,6 +743,8 @@ impl FileReader {
             metadata: self.metadata.clone(),
             options: self.options.clone(),
             num_rows: self.num_rows,
+            decryption: self.decryption.clone(),
+            checksums: self.checksums.clone(),
         }
     }
 
@@ -482,11 +760,26 @@ impl FileReader {
         stats: Arc<dyn lance_core::utils::io_stats::IoStatsRecorder>,
     ) -> Self {
         match self.scheduler.with_io_stats(stats) {
-            Some(scheduler) => self.with_scheduler(scheduler),
+            Some(scheduler) => self.with_wrapped_scheduler(scheduler),
             None => self.clone(),
         }
     }
 
+    /// Returns a clone of this reader that records the decode metrics of its reads
+    /// (pages decoded, decoded bytes and decode time per column) into `metrics`
+    ///
+    /// Like [`Self::with_io_stats`] all cached metadata is shared with `self`.  Use
+    /// [`lance_encoding::metrics::DecodeMetrics`] to collect the metrics.  Only files
+    /// with version 2.1 or later report decode metrics.
+    pub fn with_decode_metrics(
+        &self,
+        metrics: Arc<dyn lance_encoding::metrics::DecodeMetricsRecorder>,
+    ) -> Self {
+        let mut reader = self.clone();
+        reader.options.decoder_config.decode_metrics = Some(metrics);
+        reader
+    }
+
     pub fn num_rows(&self) -> u64 {
         self.num_rows
     }
@@ -519,6 +812,54 @@ impl FileReader {
         }
     }
 
+    /// Report the layout, compression and buffer sizes of every column of the file
+    ///
+    /// See [`crate::inspect`]
+    pub fn inspect(&self) -> FileInspection {
+        FileInspection::new(&self.metadata)
+    }
+
+    /// Hint that the given rows will be read soon
+    ///
+    /// The pages (and column metadata buffers) of the projected columns that overlap
+    /// `ranges` are read in the background, in row order, so that a following read
+    /// of those rows does not have to wait on storage.  See [`EncodingsIo::prefetch`].
+    ///
+    /// If `projection` is `None` the base projection of the reader is used.
+    pub fn prefetch(&self, ranges: &[Range<u64>], projection: Option<&ReaderProjection>) {
+        let projection = projection.unwrap_or(&self.base_projection);
+        // (first row, byte range) of everything the read will need
+        let mut to_prefetch = Vec::new();
+        for column_idx in &projection.column_indices {
+            let Some(column) = self.metadata.column_metadatas.get(*column_idx as usize) else {
+                continue;
+            };
+            let buffers = column.buffer_offsets.iter().zip(&column.buffer_sizes);
+            to_prefetch.extend(buffers.map(|(offset, size)| (0, *offset..*offset + *size)));
+
+            for (page_idx, page) in column.pages.iter().enumerate() {
+                let page_end = column
+                    .pages
+                    .get(page_idx + 1)
+                    .map(|next| next.priority)
+                    .unwrap_or(self.num_rows);
+                if !ranges
+                    .iter()
+                    .any(|range| range.start < page_end && page.priority < range.end)
+                {
+                    continue;
+                }
+                let buffers = page.buffer_offsets.iter().zip(&page.buffer_sizes);
+                to_prefetch.extend(
+                    buffers.map(|(offset, size)| (page.priority, *offset..*offset + *size)),
+                );
+            }
+        }
+        to_prefetch.sort_by_key(|(first_row, range)| (*first_row, range.start));
+        self.scheduler
+            .prefetch(to_prefetch.into_iter().map(|(_, range)| range).collect());
+    }
+
     pub async fn read_global_buffer(&self, index: u32) -> Result<Bytes> {
         let buffer_desc = self.metadata.file_buffers.get(index as usize).ok_or_else(||Error::invalid_input(format!("request for global buffer at index {} but there were only {} global buffers in the file", index, self.metadata.file_buffers.len())))?;
 
@@ -537,6 +878,82 @@ impl FileReader {
             .await
     }
 
+    /// Returns the row ranges whose pages might contain rows where `column` equals `value`
+    ///
+    /// This consults the page bloom filters written for the column (see
+    /// [`crate::writer::FileWriterOptions::bloom_filter_columns`]).  Rows outside of
+    /// the returned ranges cannot match so their pages never need to be decoded.  The
+    /// ranges can be read with [`ReadBatchParams::Ranges`].
+    ///
+    /// `value` must be a single element array.  Returns `None` if the file has no bloom
+    /// filters for the column, in which case any row might match.
+    pub async fn bloom_filter_ranges(
+        &self,
+        column: &str,
+        value: &dyn Array,
+    ) -> Result<Option<Vec<Range<u64>>>> {
+        let schema = &self.metadata.file_schema;
+        let Some(buffer_index) = schema.metadata.get(BLOOM_FILTER_META_KEY) else {
+            return Ok(None);
+        };
+        let Some(field) = schema.field(column) else {
+            return Ok(None);
+        };
+        let buffer_index = buffer_index.parse::<u32>().map_err(|e| {
+            Error::invalid_input(format!(
+                "Invalid bloom filter buffer index {}: {}",
+                buffer_index, e
+            ))
+        })?;
+        let bloom_filters =
+            pbfile::BloomFilters::decode(self.read_global_buffer(buffer_index).await?)?;
+        bloom_filters
+            .columns
+            .iter()
+            .find(|filters| filters.field_id == field.id)
+            .map(|filters| bloom_filter::matching_page_ranges(filters, &field.data_type(), value))
+            .transpose()
+    }
+
+    /// Returns the row ranges whose pages might contain rows matching `predicate` on `column`
+    ///
+    /// This consults the page statistics written for the column (see
+    /// [`crate::writer::FileWriterOptions::page_statistics_columns`]).  Rows outside of
+    /// the returned ranges cannot match so their pages never need to be decoded.  The
+    /// ranges can be read with [`ReadBatchParams::Ranges`].
+    ///
+    /// Returns `None` if the file has no statistics for the column, in which case any
+    /// row might match.
+    pub async fn page_statistics_ranges(
+        &self,
+        column: &str,
+        predicate: &PagePredicate,
+    ) -> Result<Option<Vec<Range<u64>>>> {
+        let schema = &self.metadata.file_schema;
+        let Some(buffer_index) = schema.metadata.get(PAGE_STATISTICS_META_KEY) else {
+            return Ok(None);
+        };
+        let Some(field) = schema.field(column) else {
+            return Ok(None);
+        };
+        let buffer_index = buffer_index.parse::<u32>().map_err(|e| {
+            Error::invalid_input(format!(
+                "Invalid page statistics buffer index {}: {}",
+                buffer_index, e
+            ))
+        })?;
+        let page_statistics =
+            pbfile::PageStatistics::decode(self.read_global_buffer(buffer_index).await?)?;
+        page_statistics
+            .columns
+            .iter()
+            .find(|statistics| statistics.field_id == field.id)
+            .map(|statistics| {
+                page_statistics::matching_page_ranges(statistics, &field.data_type(), predicate)
+            })
+            .transpose()
+    }
+
     async fn read_tail(scheduler: &FileScheduler) -> Result<(Bytes, u64)> {
         let file_size = scheduler.reader().size().await? as u64;
         let begin = if file_size < scheduler.reader().block_size() as u64 {
@@ -750,7 +1167,14 @@ impl FileReader {
         let num_data_bytes = footer.column_meta_start - num_global_buffer_bytes;
         let num_column_metadata_bytes = footer.global_buff_offsets_start - footer.column_meta_start;
 
-        let column_infos = Self::meta_to_col_infos(column_metadatas.as_slice(), file_version);
+        let encrypted_columns =
+            Self::read_encrypted_columns(&schema, &gbo_table, &tail_bytes, tail_offset, scheduler)
+                .await?;
+        let column_infos = Self::meta_to_col_infos(
+            column_metadatas.as_slice(),
+            file_version,
+            &encrypted_columns,
+        );
 
         // The tail read above already pulled in any global buffer that lives within
         // the captured window. Copy those user buffers (index >= 1; the schema at 0
@@ -790,10 +1214,44 @@ impl FileReader {
             minor_version: footer.minor_version,
             file_size_bytes: file_len,
             retained_global_buffers,
+            encrypted_columns,
         })
     }
 
-    fn fetch_encoding<M: Default + Name + Sized>(encoding: &pbfile::Encoding) -> M {
+    // The encrypted columns need to be known before the page descriptions are
+    // decoded so, unlike other global buffers, this one is read at open
+    async fn read_encrypted_columns(
+        schema: &Schema,
+        gbo_table: &[BufferDescriptor],
+        tail_bytes: &Bytes,
+        tail_offset: u64,
+        scheduler: &FileScheduler,
+    ) -> Result<Vec<pbfile::ColumnEncryption>> {
+        let Some(buffer_index) = schema.metadata.get(ENCRYPTION_META_KEY) else {
+            return Ok(Vec::new());
+        };
+        let buffer = buffer_index
+            .parse::<usize>()
+            .ok()
+            .and_then(|buffer_index| gbo_table.get(buffer_index))
+            .ok_or_else(|| {
+                Error::invalid_input(format!(
+                    "Invalid encryption metadata buffer index {}",
+                    buffer_index
+                ))
+            })?;
+        let bytes = if buffer.position >= tail_offset {
+            let start = (buffer.position - tail_offset) as usize;
+            tail_bytes.slice(start..start + buffer.size as usize)
+        } else {
+            scheduler
+                .submit_single(buffer.position..buffer.position + buffer.size, 0)
+                .await?
+        };
+        Ok(pbfile::EncryptedColumns::decode(bytes)?.columns)
+    }
+
+    pub(crate) fn fetch_encoding<M: Default + Name + Sized>(encoding: &pbfile::Encoding) -> M {
         match &encoding.location {
             Some(pbfile::encoding::Location::Indirect(_)) => todo!(),
             Some(pbfile::encoding::Location::Direct(encoding)) => {
@@ -809,50 +1267,20 @@ impl FileReader {
     fn meta_to_col_infos(
         column_metadatas: &[pbfile::ColumnMetadata],
         file_version: LanceFileVersion,
+        encrypted_columns: &[pbfile::ColumnEncryption],
     ) -> Vec<Arc<ColumnInfo>> {
         column_metadatas
             .iter()
             .enumerate()
             .map(|(col_idx, col_meta)| {
-                let page_infos = col_meta
-                    .pages
+                let is_encrypted = encrypted_columns
                     .iter()
-                    .map(|page| {
-                        let num_rows = page.length;
-                        let encoding = match file_version {
-                            LanceFileVersion::V2_0 => {
-                                PageEncoding::Legacy(Self::fetch_encoding::<pbenc::ArrayEncoding>(
-                                    page.encoding.as_ref().unwrap(),
-                                ))
-                            }
-                            _ => PageEncoding::Structural(Self::fetch_encoding::<
-                                pbenc21::PageLayout,
-                            >(
-                                page.encoding.as_ref().unwrap()
-                            )),
-                        };
-                        let buffer_offsets_and_sizes = Arc::from(
-                            page.buffer_offsets
-                                .iter()
-                                .zip(page.buffer_sizes.iter())
-                                .map(|(offset, size)| {
-                                    // Starting with version 2.1 we can assert that page buffers are aligned
-                                    assert!(
-                                        file_version < LanceFileVersion::V2_1
-                                            || offset % PAGE_BUFFER_ALIGNMENT as u64 == 0
-                                    );
-                                    (*offset, *size)
-                                })
-                                .collect::<Vec<_>>(),
-                        );
-                        PageInfo {
-                            buffer_offsets_and_sizes,
-                            encoding,
-                            num_rows,
-                            priority: page.priority,
-                        }
-                    })
-                    .collect::<Vec<_>>();
+                    .any(|encryption| encryption.column_indices.contains(&(col_idx as u32)));
+                let page_infos = if is_encrypted {
+                    encryption::encrypted_page_infos(col_meta)
+                } else {
+                    Self::page_infos(col_meta, file_version)
+                };
                 let buffer_offsets_and_sizes = Arc::from(
                     col_meta
                         .buffer_offsets
@@ -871,6 +1299,49 @@ impl FileReader {
             .collect::<Vec<_>>()
     }
 
+    fn page_infos(
+        col_meta: &pbfile::ColumnMetadata,
+        file_version: LanceFileVersion,
+    ) -> Vec<PageInfo> {
+        col_meta
+            .pages
+            .iter()
+            .map(|page| {
+                let num_rows = page.length;
+                let encoding = match file_version {
+                    LanceFileVersion::V2_0 => {
+                        PageEncoding::Legacy(Self::fetch_encoding::<pbenc::ArrayEncoding>(
+                            page.encoding.as_ref().unwrap(),
+                        ))
+                    }
+                    _ => PageEncoding::Structural(Self::fetch_encoding::<pbenc21::PageLayout>(
+                        page.encoding.as_ref().unwrap(),
+                    )),
+                };
+                let buffer_offsets_and_sizes = Arc::from(
+                    page.buffer_offsets
+                        .iter()
+                        .zip(page.buffer_sizes.iter())
+                        .map(|(offset, size)| {
+                            // Starting with version 2.1 we can assert that page buffers are aligned
+                            assert!(
+                                file_version < LanceFileVersion::V2_1
+                                    || offset % PAGE_BUFFER_ALIGNMENT as u64 == 0
+                            );
+                            (*offset, *size)
+                        })
+                        .collect::<Vec<_>>(),
+                );
+                PageInfo {
+                    buffer_offsets_and_sizes,
+                    encoding,
+                    num_rows,
+                    priority: page.priority,
+                }
+            })
+            .collect::<Vec<_>>()
+    }
+
     fn validate_projection(
         projection: &ReaderProjection,
         metadata: &CachedFileMetadata,
@@ -952,6 +1423,16 @@ impl FileReader {
             Self::validate_projection(base_projection, &file_metadata)?;
         }
         let num_rows = file_metadata.num_rows;
+        let decryption =
+            FileDecryption::try_new(&file_metadata, &options.encryption_keys)?.map(Arc::new);
+        let checksums = if options.verify_checksums {
+            Some(Arc::new(
+                ChecksumVerification::try_new(&file_metadata, &scheduler, &path).await?,
+            ))
+        } else {
+            None
+        };
+        let scheduler = Self::wrap_io(scheduler, checksums.as_deref(), decryption.as_deref());
         Ok(Self {
             scheduler,
             base_projection: base_projection.unwrap_or(ReaderProjection::from_whole_schema(
@@ -963,6 +1444,8 @@ impl FileReader {
             decoder_plugins,
             cache,
             options,
+            decryption,
+            checksums,
         })
     }
 
@@ -981,9 +1464,15 @@ impl FileReader {
     // registry will need to figure out.
     fn collect_columns_from_projection(
         &self,
-        _projection: &ReaderProjection,
+        projection: &ReaderProjection,
     ) -> Result<Vec<Arc<ColumnInfo>>> {
-        Ok(self.metadata.column_infos.clone())
+        match &self.decryption {
+            Some(decryption) => {
+                decryption.check_columns(&projection.column_indices)?;
+                Ok(decryption.column_infos().to_vec())
+            }
+            None => Ok(self.metadata.column_infos.clone()),
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1221,6 +1710,7 @@ impl FileReader {
     ) -> Result<Pin<Box<dyn Stream<Item = ReadBatchTask> + Send>>> {
         let projection = projection.unwrap_or_else(|| self.base_projection.clone());
         Self::validate_projection(&projection, &self.metadata)?;
+        let (projection, output_schema) = self.decode_projection(projection);
         let verify_bound = |params: &ReadBatchParams, bound: u64, inclusive: bool| {
             if bound > self.num_rows || bound == self.num_rows && inclusive {
                 Err(Error::invalid_input(format!(
@@ -1231,7 +1721,7 @@ impl FileReader {
                 Ok(())
             }
         };
-        match &params {
+        let tasks = match &params {
             ReadBatchParams::Indices(indices) => {
                 for idx in indices {
                     match idx {
@@ -1284,6 +1774,36 @@ impl FileReader {
                 self.read_range(0..self.num_rows, batch_size, projection, filter)
                     .await
             }
+        }?;
+        Ok(match output_schema {
+            Some(output_schema) => tasks
+                .map(move |task| {
+                    let output_schema = output_schema.clone();
+                    ReadBatchTask {
+                        task: task
+                            .task
+                            .map(move |batch| Ok(batch?.project_by_schema(&output_schema)?))
+                            .boxed(),
+                        num_rows: task.num_rows,
+                    }
+                })
+                .boxed(),
+            None => tasks,
+        })
+    }
+
+    // The projection to decode and, if it differs from `projection`, the schema the
+    // decoded batches need to be projected to
+    fn decode_projection(
+        &self,
+        projection: ReaderProjection,
+    ) -> (ReaderProjection, Option<Arc<ArrowSchema>>) {
+        match projection.with_full_packed_structs(&self.metadata.file_schema) {
+            Some(decode_projection) => (
+                decode_projection,
+                Some(Arc::new(ArrowSchema::from(projection.schema.as_ref()))),
+            ),
+            None => (projection, None),
         }
     }
 
@@ -1476,6 +1996,7 @@ impl FileReader {
     ) -> Result<Box<dyn RecordBatchReader + Send + 'static>> {
         let projection = projection.unwrap_or_else(|| self.base_projection.clone());
         Self::validate_projection(&projection, &self.metadata)?;
+        let (projection, output_schema) = self.decode_projection(projection);
         let verify_bound = |params: &ReadBatchParams, bound: u64, inclusive: bool| {
             if bound > self.num_rows || bound == self.num_rows && inclusive {
                 Err(Error::invalid_input(format!(
@@ -1486,7 +2007,7 @@ impl FileReader {
                 Ok(())
             }
         };
-        match &params {
+        let reader = match &params {
             ReadBatchParams::Indices(indices) => {
                 for idx in indices {
                     match idx {
@@ -1534,7 +2055,17 @@ impl FileReader {
             ReadBatchParams::RangeFull => {
                 self.read_range_blocking(0..self.num_rows, batch_size, projection, filter)
             }
-        }
+        }?;
+        Ok(match output_schema {
+            Some(output_schema) => Box::new(RecordBatchIterator::new(
+                reader.map({
+                    let output_schema = output_schema.clone();
+                    move |batch| batch?.project_by_schema(&output_schema)
+                }),
+                output_schema,
+            )),
+            None => reader,
+        })
     }
 
     /// Reads data from the file as a stream of record batches
@@ -1565,11 +2096,193 @@ impl FileReader {
         .await
     }
 
+    /// Reads data from the file, decoding the remaining columns only for rows that
+    /// pass a predicate on the filter columns
+    ///
+    /// The filter columns (which must be top-level columns of `projection`) are read
+    /// first.  Each batch of filter columns is evaluated with `predicate` and the other
+    /// columns of the projection are then read and decoded for the matching rows only.
+    /// For selective filters on wide rows (e.g. embeddings or large strings) this avoids
+    /// most of the decode work of the heavy columns.
+    ///
+    /// Batches are emitted in the order of the projection schema.  A batch contains the
+    /// matching rows of up to `batch_size` rows of the filter columns, batches without
+    /// any matching rows are skipped.  The other arguments are the same as for
+    /// [`Self::read_stream_projected`].
+    pub async fn read_stream_late_materialized(
+        &self,
+        params: ReadBatchParams,
+        batch_size: u32,
+        batch_readahead: u32,
+        projection: ReaderProjection,
+        filter_columns: &[&str],
+        predicate: BatchPredicate,
+    ) -> Result<Pin<Box<dyn RecordBatchStream>>> {
+        if filter_columns.is_empty() {
+            return Err(Error::invalid_input(
+                "Late materialization requires at least one filter column",
+            ));
+        }
+        Self::validate_projection(&projection, &self.metadata)?;
+        let arrow_schema = Arc::new(ArrowSchema::from(projection.schema.as_ref()));
+        let (filter_projection, remaining_projection) =
+            projection.partition(self.metadata.version(), filter_columns)?;
+        let row_ranges = RequestedRowRanges::new(&params, self.num_rows);
+        let tasks_stream = self
+            .read_tasks(
+                params,
+                batch_size,
+                Some(filter_projection),
+                FilterExpression::no_filter(),
+            )
+            .await?;
+
+        let reader = self.clone();
+        let output_schema = arrow_schema.clone();
+        let mut first_row = 0;
+        let batch_stream = tasks_stream
+            .map(move |task| {
+                let batch_rows = first_row..first_row + task.num_rows as u64;
+                first_row = batch_rows.end;
+                let reader = reader.clone();
+                let row_ranges = row_ranges.clone();
+                let predicate = predicate.clone();
+                let remaining_projection = remaining_projection.clone();
+                let arrow_schema = output_schema.clone();
+                async move {
+                    let filter_batch = task.task.await?;
+                    let mask = predicate(&filter_batch)?;
+                    if mask.len() != filter_batch.num_rows() {
+                        return Err(Error::invalid_input(format!(
+                            "The late materialization predicate returned {} values for a batch of {} rows",
+                            mask.len(),
+                            filter_batch.num_rows()
+                        )));
+                    }
+                    let mask = if mask.null_count() > 0 {
+                        prep_null_mask_filter(&mask)
+                    } else {
+                        mask
+                    };
+                    let filter_batch = filter_record_batch(&filter_batch, &mask)?;
+                    if filter_batch.num_rows() == 0 {
+                        return Ok(None);
+                    }
+                    if remaining_projection.column_indices.is_empty() {
+                        return Ok(Some(filter_batch));
+                    }
+
+                    let mut selected_ranges: Vec<Range<u64>> = Vec::new();
+                    for slice in SlicesIterator::new(&mask) {
+                        let start = batch_rows.start + slice.0 as u64;
+                        let end = batch_rows.start + slice.1 as u64;
+                        row_ranges.map(start..end, &mut selected_ranges);
+                    }
+                    let remaining_batches = reader
+                        .read_tasks(
+                            ReadBatchParams::Ranges(selected_ranges.into()),
+                            filter_batch.num_rows() as u32,
+                            Some(remaining_projection),
+                            FilterExpression::no_filter(),
+                        )
+                        .await?
+                        .then(|task| task.task)
+                        .try_collect::<Vec<_>>()
+                        .await?;
+                    let remaining_batch = match remaining_batches.len() {
+                        1 => remaining_batches.into_iter().next().unwrap(),
+                        _ => concat_batches(&remaining_batches[0].schema(), &remaining_batches)?,
+                    };
+
+                    let columns = arrow_schema
+                        .fields()
+                        .iter()
+                        .map(|field| {
+                            filter_batch
+                                .column_by_name(field.name())
+                                .or_else(|| remaining_batch.column_by_name(field.name()))
+                                .cloned()
+                                .ok_or_else(|| {
+                                    Error::internal(format!(
+                                        "Column `{}` missing from late materialized batch",
+                                        field.name()
+                                    ))
+                                })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(Some(RecordBatch::try_new(arrow_schema, columns)?))
+                }
+            })
+            .buffered(batch_readahead as usize)
+            .try_filter_map(|batch| std::future::ready(Ok(batch)))
+            .boxed();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            arrow_schema,
+            batch_stream,
+        )))
+    }
+
     pub fn schema(&self) -> &Arc<Schema> {
         &self.metadata.file_schema
     }
 }
 
+/// The rows of the file requested by a [`ReadBatchParams`], in the order they are read
+///
+/// Used to map row positions in the stream of batches back to row offsets in the file.
+#[derive(Debug, Clone)]
+struct RequestedRowRanges {
+    ranges: Arc<[Range<u64>]>,
+    /// The position (in the stream) of the first row of each range
+    positions: Arc<[u64]>,
+}
+
+impl RequestedRowRanges {
+    fn new(params: &ReadBatchParams, num_rows: u64) -> Self {
+        let ranges: Arc<[Range<u64>]> = match params {
+            ReadBatchParams::Range(range) => vec![range.start as u64..range.end as u64].into(),
+            ReadBatchParams::Ranges(ranges) => ranges.clone(),
+            ReadBatchParams::RangeFull => vec![0..num_rows].into(),
+            ReadBatchParams::RangeTo(range) => vec![0..range.end as u64].into(),
+            ReadBatchParams::RangeFrom(range) => vec![range.start as u64..num_rows].into(),
+            ReadBatchParams::Indices(indices) => indices
+                .values()
+                .iter()
+                .map(|idx| *idx as u64..*idx as u64 + 1)
+                .collect(),
+        };
+        let positions = ranges
+            .iter()
+            .scan(0, |position, range| {
+                let start = *position;
+                *position += range.end - range.start;
+                Some(start)
+            })
+            .collect();
+        Self { ranges, positions }
+    }
+
+    /// Appends the file rows of the stream positions `positions` to `out`, merging
+    /// adjacent ranges
+    fn map(&self, positions: Range<u64>, out: &mut Vec<Range<u64>>) {
+        let mut range_idx = self.positions.partition_point(|p| *p <= positions.start) - 1;
+        let mut position = positions.start;
+        while position < positions.end {
+            let range = &self.ranges[range_idx];
+            let range_position = self.positions[range_idx];
+            let end = positions.end.min(range_position + range.end - range.start);
+            let rows =
+                range.start + (position - range_position)..range.start + (end - range_position);
+            match out.last_mut() {
+                Some(last) if last.end == rows.start => last.end = rows.end,
+                _ => out.push(rows),
+            }
+            position = end;
+            range_idx += 1;
+        }
+    }
+}
+
 /// Inspects a page and returns a String describing the page's encoding
 pub fn describe_encoding(page: &pbfile::column_metadata::Page) -> String {
     if let Some(encoding) = &page.encoding {
@@ -1582,9 +2295,12 @@ pub fn describe_encoding(page: &pbfile::column_metadata::Page) -> String {
                     )
                 }
                 pbfile::encoding::Location::Direct(direct) => {
+                    // Encrypted page descriptions (see `crate::encryption`) cannot be decoded
                     let encoding_any =
-                        prost_types::Any::decode(Bytes::from(direct.encoding.clone()))
-                            .expect("failed to deserialize encoding as protobuf");
+                        match prost_types::Any::decode(Bytes::from(direct.encoding.clone())) {
+                            Ok(encoding_any) => encoding_any,
+                            Err(err) => return format!("Unsupported(decode_err={})", err),
+                        };
                     if encoding_any.type_url == "/lance.encodings.ArrayEncoding" {
                         let encoding = encoding_any.to_msg::<pbenc::ArrayEncoding>();
                         match encoding {
@@ -1657,7 +2373,7 @@ impl EncodedBatchReaderExt for EncodedBatch {
             footer.minor_version as u32,
         )?;
 
-        let page_table = FileReader::meta_to_col_infos(&column_metadatas, file_version);
+        let page_table = FileReader::meta_to_col_infos(&column_metadatas, file_version, &[]);
 
         Ok(Self {
             data: bytes,
@@ -1706,7 +2422,7 @@ impl EncodedBatchReaderExt for EncodedBatch {
         let column_metadatas =
             FileReader::read_all_column_metadata(column_metadata_bytes, &footer)?;
 
-        let page_table = FileReader::meta_to_col_infos(&column_metadatas, file_version);
+        let page_table = FileReader::meta_to_col_infos(&column_metadatas, file_version, &[]);
 
         Ok(Self {
             data: bytes,
@@ -1726,10 +2442,11 @@ mod tests {
     use std::{collections::BTreeMap, pin::Pin, sync::Arc};
 
     use arrow_array::{
-        RecordBatch, UInt32Array,
+        BooleanArray, RecordBatch, RecordBatchIterator, RecordBatchReader, UInt32Array,
         types::{Float64Type, Int32Type},
     };
     use arrow_schema::{DataType, Field, Fields, Schema as ArrowSchema};
+    use arrow_select::filter::filter_record_batch;
     use bytes::Bytes;
     use futures::{StreamExt, prelude::stream::TryStreamExt};
     use lance_arrow::RecordBatchExt;
@@ -1740,12 +2457,14 @@ mod tests {
         encoder::{EncodedBatch, EncodingOptions, default_encoding_strategy, encode_batch},
         version::LanceFileVersion,
     };
-    use lance_io::{stream::RecordBatchStream, utils::CachedFileSize};
+    use lance_io::{ReadBatchParams, stream::RecordBatchStream, utils::CachedFileSize};
     use log::debug;
     use rstest::rstest;
     use tokio::sync::mpsc;
 
-    use crate::reader::{EncodedBatchReaderExt, FileReader, FileReaderOptions, ReaderProjection};
+    use crate::reader::{
+        BatchPredicate, EncodedBatchReaderExt, FileReader, FileReaderOptions, ReaderProjection,
+    };
     use crate::testing::{FsFixture, WrittenFile, test_cache, write_lance_file};
     use crate::writer::{EncodedBatchWriteExt, FileWriter, FileWriterOptions};
     use lance_encoding::decoder::DecoderConfig;
@@ -1889,6 +2608,7 @@ mod tests {
             keep_original_array: true,
             buffer_alignment: 64,
             version,
+            page_row_alignment: 1,
         };
 
         let encoding_strategy = default_encoding_strategy(version);
@@ -2110,20 +2830,73 @@ mod tests {
         );
     }
 
+    #[rstest]
     #[test_log::test(tokio::test)]
-    async fn test_compressing_buffer() {
+    async fn test_partial_struct_projection(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1, LanceFileVersion::V2_2)]
+        version: LanceFileVersion,
+    ) {
         let fs = FsFixture::default();
-
-        let written_file = create_some_file(&fs, LanceFileVersion::V2_0).await;
+        let inner_type = DataType::Struct(Fields::from(vec![
+            Field::new("c", DataType::Utf8, true),
+            Field::new("d", DataType::Float64, true),
+            Field::new(
+                "e",
+                DataType::List(Arc::new(Field::new("item", DataType::Int32, true))),
+                true,
+            ),
+        ]));
+        let outer_type = DataType::Struct(Fields::from(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", inner_type, true),
+            Field::new("f", DataType::Utf8, true),
+        ]));
+        let packed_type = DataType::Struct(Fields::from(vec![
+            Field::new("x", DataType::Int32, true),
+            Field::new("y", DataType::Float64, true),
+        ]));
+        let mut reader = gen_batch()
+            .col("id", array::step::<Int32Type>())
+            .col("s", array::rand_type(&outer_type));
+        if version >= LanceFileVersion::V2_1 {
+            reader = reader.col("p", array::rand_type(&packed_type));
+        }
+        let mut reader = reader.into_reader_rows(RowCount::from(500), BatchCount::from(4));
+        let schema = reader.schema();
+        let batches = reader.by_ref().collect::<Vec<_>>();
+        let schema = if version >= LanceFileVersion::V2_1 {
+            let mut fields = schema.fields().to_vec();
+            fields[2] = Arc::new(
+                fields[2]
+                    .as_ref()
+                    .clone()
+                    .with_metadata([("packed".to_string(), "true".to_string())].into()),
+            );
+            Arc::new(ArrowSchema::new(fields))
+        } else {
+            schema
+        };
+        let batches = batches
+            .into_iter()
+            .map(|batch| batch.unwrap().with_schema(schema.clone()).unwrap())
+            .collect::<Vec<_>>();
+        let reader = RecordBatchIterator::new(batches.into_iter().map(Ok), schema);
+        let written_file = write_lance_file(
+            reader,
+            &fs,
+            FileWriterOptions {
+                format_version: Some(version),
+                ..Default::default()
+            },
+        )
+        .await;
         let file_scheduler = fs
             .scheduler
             .open_file(&fs.tmp_path, &CachedFileSize::unknown())
             .await
             .unwrap();
-
-        // We can specify the projection as part of the read operation via read_stream_projected
         let file_reader = FileReader::try_open(
-            file_scheduler.clone(),
+            file_scheduler,
             None,
             Arc::<DecoderPlugins>::default(),
             &test_cache(),
@@ -2132,9 +2905,129 @@ mod tests {
         .await
         .unwrap();
 
-        let mut projection = written_file.schema.project(&["score"]).unwrap();
-        for field in projection.fields.iter_mut() {
-            field
+        for columns in [
+            vec!["s.a"],
+            vec!["s.b.d"],
+            vec!["id", "s.b.c"],
+            vec!["s.f", "s.b.e"],
+            vec!["s.b"],
+            vec!["s.a", "s.b.d", "s.f"],
+            vec!["p.x"],
+            vec!["id", "p.y"],
+        ] {
+            if columns[columns.len() - 1].starts_with("p.") && version < LanceFileVersion::V2_1 {
+                continue;
+            }
+            let projection =
+                ReaderProjection::from_column_names(version, &written_file.schema, &columns)
+                    .unwrap();
+            let batch_stream = file_reader
+                .read_stream_projected(
+                    ReadBatchParams::RangeFull,
+                    1024,
+                    16,
+                    projection.clone(),
+                    FilterExpression::no_filter(),
+                )
+                .await
+                .unwrap();
+            let projection_arrow = ArrowSchema::from(projection.schema.as_ref());
+            verify_expected(
+                &written_file.data,
+                batch_stream,
+                1024,
+                Some(Box::new(move |batch: &RecordBatch| {
+                    batch.project_by_schema(&projection_arrow).unwrap()
+                })),
+            )
+            .await;
+        }
+
+        // Sibling children are not read at all
+        let bytes_read = async |columns: &[&str]| {
+            let stats = lance_io::scheduler::IoStats::new();
+            let projection =
+                ReaderProjection::from_column_names(version, &written_file.schema, columns)
+                    .unwrap();
+            file_reader
+                .with_io_stats(stats.recorder())
+                .read_stream_projected(
+                    ReadBatchParams::RangeFull,
+                    1024,
+                    16,
+                    projection,
+                    FilterExpression::no_filter(),
+                )
+                .await
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            stats.snapshot().bytes_read
+        };
+        assert!(bytes_read(&["s.b.d"]).await < bytes_read(&["s"]).await / 2);
+
+        // Packed struct children can also be taken
+        if version >= LanceFileVersion::V2_1 {
+            let projection =
+                ReaderProjection::from_column_names(version, &written_file.schema, &["p.y"])
+                    .unwrap();
+            let indices = UInt32Array::from(vec![1, 700, 1999]);
+            let file_reader = file_reader.clone();
+            let (taken, projection_arrow) = tokio::task::spawn_blocking(move || {
+                let projection_arrow = ArrowSchema::from(projection.schema.as_ref());
+                let taken = file_reader
+                    .read_stream_projected_blocking(
+                        ReadBatchParams::Indices(indices),
+                        1024,
+                        Some(projection),
+                        FilterExpression::no_filter(),
+                    )
+                    .unwrap()
+                    .collect::<ArrowResult<Vec<_>>>()
+                    .unwrap();
+                (taken, projection_arrow)
+            })
+            .await
+            .unwrap();
+            let expected = arrow_select::concat::concat_batches(
+                &written_file.data[0].schema(),
+                &written_file.data,
+            )
+            .unwrap()
+            .take(&UInt32Array::from(vec![1, 700, 1999]))
+            .unwrap()
+            .project_by_schema(&projection_arrow)
+            .unwrap();
+            assert_eq!(taken, vec![expected]);
+        }
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn test_compressing_buffer() {
+        let fs = FsFixture::default();
+
+        let written_file = create_some_file(&fs, LanceFileVersion::V2_0).await;
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+
+        // We can specify the projection as part of the read operation via read_stream_projected
+        let file_reader = FileReader::try_open(
+            file_scheduler.clone(),
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let mut projection = written_file.schema.project(&["score"]).unwrap();
+        for field in projection.fields.iter_mut() {
+            field
                 .metadata
                 .insert("lance:compression".to_string(), "zstd".to_string());
         }
@@ -2203,6 +3096,219 @@ mod tests {
         assert_eq!(batches[0].num_rows(), total_rows);
     }
 
+    #[tokio::test]
+    async fn test_inspect() {
+        use crate::inspect::PageLayoutKind;
+
+        let fs = FsFixture::default();
+        let ids = Arc::new(arrow_array::Int64Array::from_iter_values(0..10_000));
+        let flags = Arc::new(BooleanArray::from_iter(
+            (0..10_000).map(|i| Some(i % 3 == 0)),
+        ));
+        let names = Arc::new(arrow_array::StringArray::from_iter_values(
+            (0..10_000).map(|i| format!("name-{}", i % 7)),
+        ));
+        let location = Arc::new(arrow_array::StructArray::new(
+            Fields::from(vec![
+                Field::new("x", DataType::Int32, false),
+                Field::new("y", DataType::Int32, false),
+            ]),
+            vec![
+                Arc::new(arrow_array::Int32Array::from_iter_values(0..10_000)),
+                Arc::new(arrow_array::Int32Array::from_iter_values(
+                    std::iter::repeat_n(7, 10_000),
+                )),
+            ],
+            None,
+        ));
+        let batch = RecordBatch::try_from_iter(vec![
+            ("id", ids as arrow_array::ArrayRef),
+            ("flag", flags),
+            ("name", names),
+            ("location", location),
+        ])
+        .unwrap();
+        let schema = batch.schema();
+        write_lance_file(
+            RecordBatchIterator::new(vec![Ok(batch)], schema),
+            &fs,
+            FileWriterOptions {
+                format_version: Some(LanceFileVersion::V2_1),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let inspection = file_reader.inspect();
+        assert_eq!(inspection.version, LanceFileVersion::V2_1);
+        assert_eq!(inspection.num_rows, 10_000);
+        let paths = inspection
+            .columns
+            .iter()
+            .map(|column| column.field_path.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec!["id", "flag", "name", "location.x", "location.y"]
+        );
+
+        let statistics = file_reader.file_statistics();
+        for (column, stats) in inspection.columns.iter().zip(statistics.columns.iter()) {
+            assert_eq!(column.pages.len(), stats.num_pages);
+            assert_eq!(column.encoded_bytes(), stats.size_bytes);
+            assert_eq!(
+                column.pages.iter().map(|page| page.num_rows).sum::<u64>(),
+                10_000
+            );
+        }
+
+        // Sequential ids are bitpacked and so are much smaller than 8 bytes per value
+        let id = &inspection.columns[0];
+        assert_eq!(id.data_type, Some(DataType::Int64));
+        assert_eq!(id.uncompressed_bytes, Some(80_000));
+        assert!(id.compression_ratio().unwrap() > 2.0);
+        for page in &id.pages {
+            assert_eq!(page.layout, PageLayoutKind::MiniBlock);
+            assert!(page.compression.is_some());
+        }
+        assert_eq!(inspection.columns[1].uncompressed_bytes, Some(1250));
+        // Variable width columns have no uncompressed estimate
+        assert_eq!(inspection.columns[2].uncompressed_bytes, None);
+        assert_eq!(inspection.columns[2].compression_ratio(), None);
+        assert!(
+            inspection.columns[2].pages[0]
+                .compression
+                .as_ref()
+                .unwrap()
+                .starts_with("dictionary(")
+        );
+        // A column with a single value is run-length encoded
+        let y = &inspection.columns[4];
+        assert!(y.pages[0].compression.as_ref().unwrap().starts_with("rle("));
+        assert!(y.compression_ratio().unwrap() > 100.0);
+
+        let display = inspection.to_string();
+        assert!(display.contains("location.x"));
+        assert!(display.contains("mini-block"));
+    }
+
+    #[tokio::test]
+    async fn test_decode_metrics() {
+        let fs = FsFixture::default();
+        let WrittenFile { data, .. } = create_some_file(&fs, LanceFileVersion::V2_1).await;
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let metrics = lance_encoding::metrics::DecodeMetrics::new();
+        let batch_stream = file_reader
+            .with_decode_metrics(metrics.recorder())
+            .read_stream(
+                lance_io::ReadBatchParams::RangeFull,
+                1024,
+                16,
+                FilterExpression::no_filter(),
+            )
+            .await
+            .unwrap();
+        verify_expected(&data, batch_stream, 1024, None).await;
+
+        let stats = metrics.snapshot();
+        let column_metadatas = &file_reader.metadata().column_metadatas;
+        assert_eq!(stats.columns.len(), column_metadatas.len());
+        for (column_index, column) in &stats.columns {
+            let num_pages = column_metadatas[*column_index as usize].pages.len() as u64;
+            assert!(column.pages_loaded >= num_pages);
+            assert!(column.decode_tasks >= column.pages_loaded);
+            assert!(column.bytes_decoded > 0);
+        }
+        assert!(stats.total().decode_time > std::time::Duration::ZERO);
+
+        // Reads through the original reader are not recorded
+        file_reader
+            .read_stream(
+                lance_io::ReadBatchParams::RangeFull,
+                1024,
+                16,
+                FilterExpression::no_filter(),
+            )
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(metrics.snapshot(), stats);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_prefetch(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1)] version: LanceFileVersion,
+    ) {
+        let fs = FsFixture::default();
+        let WrittenFile { data, .. } = create_some_file(&fs, version).await;
+        let total_rows = data.iter().map(|batch| batch.num_rows()).sum::<usize>() as u64;
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        file_reader.prefetch(&[0..total_rows], None);
+        let iops_after_prefetch = fs.scheduler.stats().iops;
+
+        let batch_stream = file_reader
+            .read_stream(
+                lance_io::ReadBatchParams::RangeFull,
+                1024,
+                16,
+                FilterExpression::no_filter(),
+            )
+            .await
+            .unwrap();
+        verify_expected(&data, batch_stream, 1024, None).await;
+
+        // All of the data was served from the prefetched ranges
+        assert_eq!(fs.scheduler.stats().iops, iops_after_prefetch);
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_blocking_take(
@@ -2248,6 +3354,385 @@ mod tests {
         assert_eq!(batches[0].num_columns(), 1);
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_read_as_dictionary(
+        #[values(LanceFileVersion::V2_1, LanceFileVersion::V2_2)] version: LanceFileVersion,
+    ) {
+        use arrow_array::{Array, StringArray, cast::AsArray};
+
+        let fs = FsFixture::default();
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("category", DataType::Utf8, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        // `category` has few unique values and is dictionary encoded by the writer,
+        // `name` is unique and is not
+        let num_rows = 10_000;
+        let categories = StringArray::from_iter(
+            (0..num_rows).map(|i| (i % 7 != 0).then(|| format!("category-{}", i % 5))),
+        );
+        let names = StringArray::from_iter_values((0..num_rows).map(|i| format!("name-{i}")));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(categories.clone()), Arc::new(names.clone())],
+        )
+        .unwrap();
+        let reader = arrow_array::RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+        let written_file = write_lance_file(
+            reader,
+            &fs,
+            FileWriterOptions {
+                format_version: Some(version),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let projection = ReaderProjection::from_column_names(
+            version,
+            &written_file.schema,
+            &["category", "name"],
+        )
+        .unwrap()
+        .with_dictionary_columns(DataType::Int32, &["category", "name"])
+        .unwrap();
+        let batches = file_reader
+            .read_stream_projected(
+                lance_io::ReadBatchParams::RangeFull,
+                num_rows as u32,
+                16,
+                projection,
+                FilterExpression::no_filter(),
+            )
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let batch = arrow_select::concat::concat_batches(&batches[0].schema(), &batches).unwrap();
+
+        let dict_type = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        for (column, expected) in [("category", &categories), ("name", &names)] {
+            let array = batch.column_by_name(column).unwrap();
+            assert_eq!(array.data_type(), &dict_type);
+            let values = arrow_cast::cast(array, &DataType::Utf8).unwrap();
+            assert_eq!(values.as_string::<i32>(), expected);
+        }
+        // Low cardinality columns come back with small dictionaries
+        let category = batch.column_by_name("category").unwrap();
+        assert!(category.as_dictionary::<Int32Type>().values().len() < 100);
+
+        // Only string / binary columns can be read as dictionaries
+        let projection =
+            ReaderProjection::from_column_names(version, &written_file.schema, &["category"])
+                .unwrap();
+        assert!(
+            projection
+                .clone()
+                .with_dictionary_columns(DataType::Utf8, &["category"])
+                .is_err()
+        );
+        assert!(
+            projection
+                .with_dictionary_columns(DataType::Int32, &["missing"])
+                .is_err()
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_run_end_encoded(
+        #[values(LanceFileVersion::V2_1, LanceFileVersion::V2_2)] version: LanceFileVersion,
+    ) {
+        use arrow_array::{Array, Int32Array, Int64Array, RunArray, StringArray, cast::AsArray};
+
+        let fs = FsFixture::default();
+        let status_type = DataType::RunEndEncoded(
+            Arc::new(Field::new("run_ends", DataType::Int32, false)),
+            Arc::new(Field::new("values", DataType::Utf8, true)),
+        );
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("status", status_type.clone(), true),
+            Field::new("level", DataType::Int64, true),
+        ]));
+        // 20 runs of 1000 rows each
+        let num_rows = 20_000;
+        let run_ends = Int32Array::from_iter_values((1..=20).map(|run| run * 1000));
+        let statuses =
+            StringArray::from_iter((0..20).map(|run| {
+                (run % 4 != 3).then(|| format!("status-{}-{}", run % 2, "x".repeat(100)))
+            }));
+        let status = RunArray::<Int32Type>::try_new(&run_ends, &statuses).unwrap();
+        let levels =
+            Int64Array::from_iter((0..num_rows).map(|i| (i % 3000 >= 1000).then_some(i / 1000)));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(status.clone()), Arc::new(levels.clone())],
+        )
+        .unwrap();
+        let reader = RecordBatchIterator::new(vec![Ok(batch.clone())], schema.clone());
+        let written_file = write_lance_file(
+            reader,
+            &fs,
+            FileWriterOptions {
+                format_version: Some(version),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(
+            written_file.schema.field("status").unwrap().data_type(),
+            status_type
+        );
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+        // The runs are not expanded on disk (that would take more than 2MB)
+        let status_bytes = file_reader.metadata().column_metadatas[0]
+            .pages
+            .iter()
+            .flat_map(|page| page.buffer_sizes.iter())
+            .sum::<u64>();
+        assert!(status_bytes < 64 * 1024, "{}", status_bytes);
+
+        let read = |projection: ReaderProjection| {
+            let file_reader = file_reader.clone();
+            async move {
+                let batches = file_reader
+                    .read_stream_projected(
+                        lance_io::ReadBatchParams::RangeFull,
+                        num_rows as u32,
+                        16,
+                        projection,
+                        FilterExpression::no_filter(),
+                    )
+                    .await
+                    .unwrap()
+                    .try_collect::<Vec<_>>()
+                    .await
+                    .unwrap();
+                arrow_select::concat::concat_batches(&batches[0].schema(), &batches).unwrap()
+            }
+        };
+
+        // Run-end encoded columns are read back as written
+        let projection = ReaderProjection::from_whole_schema(&written_file.schema, version);
+        assert_eq!(read(projection.clone()).await, batch);
+
+        // Other columns can be read as runs
+        let level_batch = read(
+            projection
+                .clone()
+                .with_run_end_encoded_columns(DataType::Int64, &["level"])
+                .unwrap(),
+        )
+        .await;
+        let level = level_batch.column_by_name("level").unwrap();
+        let level = level.as_run::<arrow_array::types::Int64Type>();
+        assert_eq!(level.values().len(), 20);
+        assert_eq!(
+            arrow_cast::cast(level, &DataType::Int64).unwrap().as_ref(),
+            &levels as &dyn Array
+        );
+
+        assert!(
+            projection
+                .clone()
+                .with_run_end_encoded_columns(DataType::UInt32, &["level"])
+                .is_err()
+        );
+        assert!(
+            projection
+                .with_run_end_encoded_columns(DataType::Int32, &["status"])
+                .is_err()
+        );
+
+        // Run-end encoded columns need 2.1+ files
+        let fs = FsFixture::default();
+        let err = FileWriter::try_new(
+            fs.object_store.create(&fs.tmp_path).await.unwrap(),
+            Schema::try_from(schema.as_ref()).unwrap(),
+            FileWriterOptions {
+                format_version: Some(LanceFileVersion::V2_0),
+                ..Default::default()
+            },
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("2.1"), "{}", err);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_read_late_materialized(
+        #[values(LanceFileVersion::V2_0, LanceFileVersion::V2_1)] version: LanceFileVersion,
+    ) {
+        use arrow_array::{Int32Array, StringArray, StructArray, cast::AsArray};
+
+        let fs = FsFixture::default();
+        let nested_fields = Fields::from(vec![
+            Field::new("x", DataType::Int32, true),
+            Field::new("y", DataType::Utf8, true),
+        ]);
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("text", DataType::Utf8, true),
+            Field::new("id", DataType::Int32, true),
+            Field::new("nested", DataType::Struct(nested_fields.clone()), true),
+        ]));
+        let num_rows = 10_000;
+        let ids = Int32Array::from_iter((0..num_rows).map(|i| (i % 13 != 0).then_some(i)));
+        let text = StringArray::from_iter_values((0..num_rows).map(|i| format!("text-{i}")));
+        let nested = StructArray::new(
+            nested_fields,
+            vec![
+                Arc::new(Int32Array::from_iter_values((0..num_rows).map(|i| i * 2))),
+                Arc::new(StringArray::from_iter_values(
+                    (0..num_rows).map(|i| format!("y-{i}")),
+                )),
+            ],
+            None,
+        );
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(text), Arc::new(ids), Arc::new(nested)],
+        )
+        .unwrap();
+        let reader = arrow_array::RecordBatchIterator::new(vec![Ok(batch.clone())], schema);
+        let written_file = write_lance_file(
+            reader,
+            &fs,
+            FileWriterOptions {
+                format_version: Some(version),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &test_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+        let projection = ReaderProjection::from_whole_schema(&written_file.schema, version);
+
+        let mask = |batch: &RecordBatch| {
+            let ids = batch
+                .column_by_name("id")
+                .unwrap()
+                .as_primitive::<Int32Type>();
+            ids.iter()
+                .map(|id| id.map(|id| id % 10 == 3))
+                .collect::<BooleanArray>()
+        };
+        let predicate: BatchPredicate = Arc::new(move |batch: &RecordBatch| Ok(mask(batch)));
+        let read = |params: ReadBatchParams, predicate: BatchPredicate| {
+            let file_reader = file_reader.clone();
+            let projection = projection.clone();
+            async move {
+                let batches = file_reader
+                    .read_stream_late_materialized(params, 1000, 4, projection, &["id"], predicate)
+                    .await
+                    .unwrap()
+                    .try_collect::<Vec<_>>()
+                    .await
+                    .unwrap();
+                assert!(batches.iter().all(|batch| batch.num_rows() > 0));
+                batches
+            }
+        };
+
+        for params in [
+            ReadBatchParams::RangeFull,
+            ReadBatchParams::Range(1234..8765),
+            ReadBatchParams::Ranges(vec![0..100, 2999..3050, 9000..10_000].into()),
+            ReadBatchParams::Indices(UInt32Array::from_iter_values(
+                (0..num_rows as u32).step_by(7),
+            )),
+        ] {
+            let expected = file_reader
+                .read_stream_projected(
+                    params.clone(),
+                    1000,
+                    4,
+                    projection.clone(),
+                    FilterExpression::no_filter(),
+                )
+                .await
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            let expected =
+                arrow_select::concat::concat_batches(&expected[0].schema(), &expected).unwrap();
+            let expected = filter_record_batch(&expected, &mask(&expected)).unwrap();
+
+            let batches = read(params, predicate.clone()).await;
+            let actual =
+                arrow_select::concat::concat_batches(&expected.schema(), &batches).unwrap();
+            assert_eq!(actual, expected);
+        }
+
+        // No batches are emitted when nothing matches
+        let batches = read(
+            ReadBatchParams::RangeFull,
+            Arc::new(|batch: &RecordBatch| Ok(BooleanArray::from(vec![false; batch.num_rows()]))),
+        )
+        .await;
+        assert!(batches.is_empty());
+
+        // Filter columns must be part of the projection
+        assert!(
+            file_reader
+                .read_stream_late_materialized(
+                    ReadBatchParams::RangeFull,
+                    1000,
+                    4,
+                    projection.clone(),
+                    &["missing"],
+                    predicate.clone(),
+                )
+                .await
+                .is_err()
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_drop_in_progress() {
         let fs = FsFixture::default();
@@ -6,15 +6,21 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
-use arrow_array::RecordBatch;
+use arrow_array::{RecordBatch, RecordBatchReader};
+use arrow_schema::DataType;
 
 use arrow_data::ArrayData;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
-use futures::StreamExt;
 use futures::stream::FuturesOrdered;
+use futures::{Stream, StreamExt};
 use lance_core::datatypes::{Field, Schema as LanceSchema};
+use lance_core::error::LanceOptionExt;
 use lance_core::utils::bit::pad_bytes;
 use lance_core::{Error, Result};
+use lance_encoding::constants::{
+    LARGE_VALUE_THRESHOLD_META_KEY, MINICHUNK_SIZE_META_KEY, PAGE_ROW_ALIGNMENT_META_KEY,
+    PAGE_SIZE_META_KEY,
+};
 use lance_encoding::decoder::PageEncoding;
 use lance_encoding::encoder::{
     BatchEncoder, EncodeTask, EncodedBatch, EncodedPage, EncodingOptions, FieldEncoder,
@@ -30,13 +36,22 @@ use prost::Message;
 use prost_types::Any;
 use tokio::io::AsyncWrite;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
 use tracing::instrument;
 
+use crate::bloom_filter::{
+    BLOOM_FILTER_META_KEY, DEFAULT_BLOOM_FILTER_FPP, PageBloomFilterBuilder, supports_bloom_filter,
+};
+use crate::checksum::{CHECKSUMS_META_KEY, ChecksumsBuilder};
 use crate::datatypes::FieldsWithMeta;
+use crate::encryption::{ColumnEncryptor, ENCRYPTION_META_KEY, EncryptionKey};
 use crate::format::MAGIC;
 use crate::format::pb;
 use crate::format::pbfile;
 use crate::format::pbfile::DirectEncoding;
+use crate::page_statistics::{
+    PAGE_STATISTICS_META_KEY, PageStatisticsBuilder, supports_page_statistics,
+};
 
 /// Pages buffers are aligned to 64 bytes
 pub(crate) const PAGE_BUFFER_ALIGNMENT: usize = 64;
@@ -58,6 +73,43 @@ pub struct FileWriteSummary {
     pub size_bytes: u64,
 }
 
+/// Settings for [`FileWriter::write_stream`] and [`FileWriter::write_reader`]
+#[derive(Debug, Clone)]
+pub struct StreamWriteOptions {
+    /// The maximum size (in bytes) of the input batches that are read ahead of the writer
+    ///
+    /// The input is read in the background while the writer encodes and writes earlier
+    /// batches.  Once this many bytes are waiting to be written the input is not polled
+    /// again until the writer catches up.  A batch larger than this is still read but
+    /// nothing else is read ahead of it.  Defaults to 64MiB.
+    pub max_buffered_bytes: u64,
+}
+
+impl Default for StreamWriteOptions {
+    fn default() -> Self {
+        Self {
+            max_buffered_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// How much of an input has been written, see [`FileWriter::write_stream`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteProgress {
+    /// The number of input batches that have been written
+    pub batches_written: u64,
+    /// The number of input rows that have been written
+    pub rows_written: u64,
+    /// The number of bytes written to the file so far
+    ///
+    /// Some of the rows may still be buffered in the writer and only reach the file
+    /// once more data arrives or the writer is finished.
+    pub bytes_written: u64,
+}
+
+// A batch read ahead of the writer, holding its share of the read-ahead budget
+type BufferedBatch = Result<(RecordBatch, OwnedSemaphorePermit)>;
+
 #[derive(Debug, Clone, Default)]
 pub struct FileWriterOptions {
     /// How many bytes to use for buffering column data
@@ -106,6 +158,105 @@ pub struct FileWriterOptions {
     /// versions may have more efficient encodings.  However, newer format versions will
     /// require more up-to-date readers to read the data.
     pub format_version: Option<LanceFileVersion>,
+    /// The names of top-level columns that should get page-level bloom filters
+    ///
+    /// For each listed column the writer builds a bloom filter over the values of
+    /// every page and stores the filters in a global buffer.  Readers can then use
+    /// the filters to skip pages when evaluating equality predicates.  Only columns
+    /// of the types accepted by [`crate::bloom_filter::supports_bloom_filter`] can
+    /// be listed.
+    pub bloom_filter_columns: Vec<String>,
+    /// The false positive probability of the page-level bloom filters
+    ///
+    /// Defaults to [`crate::bloom_filter::DEFAULT_BLOOM_FILTER_FPP`]
+    pub bloom_filter_fpp: Option<f64>,
+    /// The names of top-level columns that should get page-level statistics
+    ///
+    /// For each listed column the writer records the minimum, maximum and null count
+    /// of every page and stores them in a global buffer.  Readers can then use the
+    /// statistics to skip pages that cannot satisfy a predicate.  Only columns of the
+    /// types accepted by [`crate::page_statistics::supports_page_statistics`] can be
+    /// listed.
+    ///
+    /// The writer holds on to the arrays of these columns until their pages have
+    /// been written (see [`Self::keep_original_array`]).
+    pub page_statistics_columns: Vec<String>,
+    /// The top-level columns that should be encrypted, and the key to encrypt each with
+    ///
+    /// The pages of these columns (including the pages of any nested children) are
+    /// encrypted with the key and the id of the key is recorded in the file, see
+    /// [`crate::encryption`].  Readers need the key to read these columns but can read
+    /// the other columns without any key.
+    ///
+    /// Encrypted columns cannot also have page bloom filters or statistics since
+    /// those would reveal the values.
+    pub encrypted_columns: HashMap<String, EncryptionKey>,
+    /// Pages (except the last page of each column) hold a multiple of this many rows
+    ///
+    /// Setting this to the batch size used by readers avoids batches that straddle
+    /// pages.  This is best-effort, see [`EncodingOptions::page_row_alignment`].  Only
+    /// supported for 2.1+ files.
+    pub page_row_alignment: Option<u64>,
+    /// Page layout settings for individual top-level columns
+    ///
+    /// The defaults (e.g. splitting [`Self::data_cache_bytes`] evenly across columns)
+    /// are a poor fit when columns have very different widths, such as a few 4-byte
+    /// scalars next to a large embedding column.  The settings are recorded in the field
+    /// metadata of the column, see [`ColumnPageOptions::to_field_metadata`].  Only
+    /// supported for 2.1+ files.
+    pub column_page_options: HashMap<String, ColumnPageOptions>,
+    /// If true, record a checksum of every data buffer written to the file
+    ///
+    /// Readers can then detect corruption, see [`crate::checksum`].
+    pub write_checksums: bool,
+}
+
+/// Page layout settings for a single column, see [`FileWriterOptions::column_page_options`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnPageOptions {
+    /// How many bytes of (unencoded) data to buffer before writing a page of the column
+    ///
+    /// Overrides the share of [`FileWriterOptions::data_cache_bytes`] for this column.
+    pub page_size_bytes: Option<u64>,
+    /// The maximum size of a mini-block (in bytes) for string and binary data
+    pub minichunk_size: Option<i64>,
+    /// Overrides [`FileWriterOptions::page_row_alignment`] for this column
+    pub row_alignment: Option<u64>,
+    /// Binary and string values larger than this (in bytes) are stored outside of the pages
+    ///
+    /// Such values are split into chunks of at most [`FileWriterOptions::max_page_bytes`]
+    /// and the pages only record where each value is stored.  This keeps a few very large
+    /// values from inflating the pages of a column without making it a blob column.
+    pub large_value_threshold: Option<u64>,
+}
+
+impl ColumnPageOptions {
+    /// Convert the settings into `lance-encoding:*` field metadata entries
+    pub fn to_field_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        if let Some(page_size_bytes) = self.page_size_bytes {
+            metadata.insert(PAGE_SIZE_META_KEY.to_string(), page_size_bytes.to_string());
+        }
+        if let Some(minichunk_size) = self.minichunk_size {
+            metadata.insert(
+                MINICHUNK_SIZE_META_KEY.to_string(),
+                minichunk_size.to_string(),
+            );
+        }
+        if let Some(row_alignment) = self.row_alignment {
+            metadata.insert(
+                PAGE_ROW_ALIGNMENT_META_KEY.to_string(),
+                row_alignment.to_string(),
+            );
+        }
+        if let Some(large_value_threshold) = self.large_value_threshold {
+            metadata.insert(
+                LARGE_VALUE_THRESHOLD_META_KEY.to_string(),
+                large_value_threshold.to_string(),
+            );
+        }
+        metadata
+    }
 }
 
 // Total in-memory budget for buffering serialized page metadata before flushing
@@ -225,6 +376,10 @@ pub struct FileWriter {
     schema_metadata: HashMap<String, String>,
     options: FileWriterOptions,
     page_spill: Option<PageSpillState>,
+    bloom_filters: Vec<PageBloomFilterBuilder>,
+    page_statistics: Vec<PageStatisticsBuilder>,
+    encryptors: Vec<ColumnEncryptor>,
+    checksums: Option<ChecksumsBuilder>,
 }
 
 fn initial_column_metadata() -> pbfile::ColumnMetadata {
@@ -281,6 +436,10 @@ impl FileWriter {
             global_buffers: Vec::new(),
             schema_metadata: HashMap::new(),
             page_spill: None,
+            bloom_filters: Vec::new(),
+            page_statistics: Vec::new(),
+            encryptors: Vec::new(),
+            checksums: options.write_checksums.then(ChecksumsBuilder::default),
             options,
         }
     }
@@ -322,6 +481,19 @@ impl FileWriter {
         Ok(())
     }
 
+    /// Writes a (padded) data buffer, recording its checksum if requested
+    async fn write_buffer(&mut self, buf: &[u8]) -> Result<()> {
+        self.record_checksum(buf).await?;
+        Self::do_write_buffer(&mut self.writer, buf).await
+    }
+
+    async fn record_checksum(&mut self, buf: &[u8]) -> Result<()> {
+        if let Some(checksums) = &mut self.checksums {
+            checksums.add_buffer(self.writer.tell().await? as u64, buf);
+        }
+        Ok(())
+    }
+
     /// Returns the format version that will be used when writing the file
     pub fn version(&self) -> LanceFileVersion {
         self.options.format_version.unwrap_or_default()
@@ -331,15 +503,29 @@ impl FileWriter {
         let buffers = encoded_page.data;
         let mut buffer_offsets = Vec::with_capacity(buffers.len());
         let mut buffer_sizes = Vec::with_capacity(buffers.len());
+        let encryptor_idx = self
+            .encryptors
+            .iter()
+            .position(|encryptor| encryptor.encrypts_column(encoded_page.column_idx));
         for buffer in buffers {
-            buffer_offsets.push(self.writer.tell().await? as u64);
+            let position = self.writer.tell().await? as u64;
+            buffer_offsets.push(position);
             buffer_sizes.push(buffer.len() as u64);
-            Self::do_write_buffer(&mut self.writer, &buffer).await?;
+            if let Some(encryptor_idx) = encryptor_idx {
+                let encrypted = self.encryptors[encryptor_idx].encrypt_buffer(position, &buffer);
+                self.write_buffer(&encrypted).await?;
+            } else {
+                self.write_buffer(&buffer).await?;
+            }
         }
-        let encoded_encoding = match encoded_page.description {
+        let mut encoded_encoding = match encoded_page.description {
             PageEncoding::Legacy(array_encoding) => Any::from_msg(&array_encoding)?.encode_to_vec(),
             PageEncoding::Structural(page_layout) => Any::from_msg(&page_layout)?.encode_to_vec(),
         };
+        if let Some(encryptor_idx) = encryptor_idx {
+            encoded_encoding = self.encryptors[encryptor_idx]
+                .encrypt_description(encoded_page.column_idx, encoded_encoding);
+        }
         let page = pbfile::column_metadata::Page {
             buffer_offsets,
             buffer_sizes,
@@ -352,6 +538,16 @@ impl FileWriter {
             priority: encoded_page.row_number,
         };
         let col_idx = encoded_page.column_idx as usize;
+        for bloom_filter in &mut self.bloom_filters {
+            if bloom_filter.column_index() == encoded_page.column_idx {
+                bloom_filter.add_page(encoded_page.row_number, encoded_page.num_rows);
+            }
+        }
+        for page_statistics in &mut self.page_statistics {
+            if page_statistics.column_index() == encoded_page.column_idx {
+                page_statistics.add_page(encoded_page.row_number, encoded_page.num_rows)?;
+            }
+        }
         if matches!(&self.page_spill, Some(PageSpillState::Pending(..))) {
             let Some(PageSpillState::Pending(store, path)) = self.page_spill.take() else {
                 unreachable!()
@@ -401,6 +597,123 @@ impl FileWriter {
         Ok(())
     }
 
+    /// Write every batch of `stream` to the file
+    ///
+    /// The stream is polled in a background task so that reading the input overlaps
+    /// with encoding and writing, while at most [`StreamWriteOptions::max_buffered_bytes`]
+    /// of input is held in memory.  `on_progress` is called after each batch is written.
+    ///
+    /// Like [`Self::write_batch`] this does not finish the file.  The input is dropped
+    /// if an error occurs.
+    pub async fn write_stream<S>(
+        &mut self,
+        stream: S,
+        options: StreamWriteOptions,
+        on_progress: impl FnMut(&WriteProgress) + Send,
+    ) -> Result<WriteProgress>
+    where
+        S: Stream<Item = Result<RecordBatch>> + Send + 'static,
+    {
+        let (budget, capacity) = Self::read_ahead_budget(&options);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let producer_budget = budget.clone();
+        tokio::spawn(async move {
+            let mut stream = std::pin::pin!(stream);
+            while let Some(batch) = stream.next().await {
+                let batch = match batch {
+                    Ok(batch) => {
+                        let permits = Self::batch_permits(&batch, capacity);
+                        let Ok(permit) = producer_budget.clone().acquire_many_owned(permits).await
+                        else {
+                            // The writer has stopped
+                            return;
+                        };
+                        Ok((batch, permit))
+                    }
+                    Err(err) => Err(err),
+                };
+                if tx.send(batch).is_err() {
+                    return;
+                }
+            }
+        });
+        self.write_buffered_batches(rx, budget, on_progress).await
+    }
+
+    /// Write every batch of `reader` to the file
+    ///
+    /// This is [`Self::write_stream`] for a synchronous reader, which is iterated on a
+    /// blocking thread.
+    pub async fn write_reader<R>(
+        &mut self,
+        reader: R,
+        options: StreamWriteOptions,
+        on_progress: impl FnMut(&WriteProgress) + Send,
+    ) -> Result<WriteProgress>
+    where
+        R: RecordBatchReader + Send + 'static,
+    {
+        let (budget, capacity) = Self::read_ahead_budget(&options);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let producer_budget = budget.clone();
+        let handle = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || {
+            for batch in reader {
+                let batch = match batch {
+                    Ok(batch) => {
+                        let permits = Self::batch_permits(&batch, capacity);
+                        let Ok(permit) =
+                            handle.block_on(producer_budget.clone().acquire_many_owned(permits))
+                        else {
+                            return;
+                        };
+                        Ok((batch, permit))
+                    }
+                    Err(err) => Err(err.into()),
+                };
+                if tx.send(batch).is_err() {
+                    return;
+                }
+            }
+        });
+        self.write_buffered_batches(rx, budget, on_progress).await
+    }
+
+    // The read-ahead budget has one permit per byte
+    fn read_ahead_budget(options: &StreamWriteOptions) -> (Arc<Semaphore>, u32) {
+        let capacity = options.max_buffered_bytes.clamp(1, u32::MAX as u64) as u32;
+        (Arc::new(Semaphore::new(capacity as usize)), capacity)
+    }
+
+    // Batches larger than the budget take the entire budget
+    fn batch_permits(batch: &RecordBatch, capacity: u32) -> u32 {
+        batch.get_array_memory_size().clamp(1, capacity as usize) as u32
+    }
+
+    async fn write_buffered_batches(
+        &mut self,
+        mut batches: mpsc::UnboundedReceiver<BufferedBatch>,
+        budget: Arc<Semaphore>,
+        mut on_progress: impl FnMut(&WriteProgress) + Send,
+    ) -> Result<WriteProgress> {
+        let mut progress = WriteProgress::default();
+        let result = async {
+            while let Some(batch) = batches.recv().await {
+                let (batch, _permit) = batch?;
+                self.write_batch(&batch).await?;
+                progress.batches_written += 1;
+                progress.rows_written += batch.num_rows() as u64;
+                progress.bytes_written = self.tell().await?;
+                on_progress(&progress);
+            }
+            Ok(progress)
+        }
+        .await;
+        // Wakes up the producer if it is waiting on the budget
+        budget.close();
+        result
+    }
+
     fn verify_field_nullability(arr: &ArrayData, field: &Field) -> Result<()> {
         if !field.nullable && arr.null_count() > 0 {
             return Err(Error::invalid_input(format!(
@@ -449,6 +762,7 @@ impl FileWriter {
         });
 
         schema.validate()?;
+        self.apply_column_page_options(&mut schema)?;
 
         let keep_original_array = self.options.keep_original_array.unwrap_or(false);
         let encoding_strategy = self.options.encoding_strategy.clone().unwrap_or_else(|| {
@@ -462,6 +776,7 @@ impl FileWriter {
             keep_original_array,
             buffer_alignment: PAGE_BUFFER_ALIGNMENT as u64,
             version: self.version(),
+            page_row_alignment: self.options.page_row_alignment.unwrap_or(1),
         };
         let encoder =
             BatchEncoder::try_new(&schema, encoding_strategy.as_ref(), &encoding_options)?;
@@ -470,12 +785,210 @@ impl FileWriter {
         self.column_writers = encoder.field_encoders;
         self.column_metadata = vec![initial_column_metadata(); self.num_columns as usize];
         self.field_id_to_column_indices = encoder.field_id_to_column_index;
+        self.bloom_filters = self.make_bloom_filter_builders(&schema)?;
+        self.page_statistics = self.make_page_statistics_builders(&schema)?;
+        self.encryptors = self.make_column_encryptors(&schema)?;
         self.schema_metadata
             .extend(std::mem::take(&mut schema.metadata));
         self.schema = Some(schema);
         Ok(())
     }
 
+    /// Validates the page layout options and records the per-column settings in the
+    /// field metadata, where the encoders pick them up
+    fn apply_column_page_options(&self, schema: &mut LanceSchema) -> Result<()> {
+        let options = &self.options;
+        if options.page_row_alignment.is_none() && options.column_page_options.is_empty() {
+            return Ok(());
+        }
+        if self.version().resolve() < LanceFileVersion::V2_1 {
+            return Err(Error::invalid_input(format!(
+                "Page layout options require file version 2.1 or later (got {})",
+                self.version()
+            )));
+        }
+        if options.page_row_alignment == Some(0) {
+            return Err(Error::invalid_input(
+                "The page row alignment must be positive",
+            ));
+        }
+        for (name, column_options) in &options.column_page_options {
+            if column_options.page_size_bytes == Some(0)
+                || column_options.row_alignment == Some(0)
+                || column_options.large_value_threshold == Some(0)
+                || column_options.minichunk_size.is_some_and(|size| size <= 0)
+            {
+                return Err(Error::invalid_input(format!(
+                    "Invalid page options for column `{}`, sizes and alignments must be positive",
+                    name
+                )));
+            }
+            let field = schema
+                .fields
+                .iter_mut()
+                .find(|field| &field.name == name)
+                .ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "Cannot set page options for column `{}` because it is not a top-level column of the schema",
+                        name
+                    ))
+                })?;
+            field.metadata.extend(column_options.to_field_metadata());
+        }
+        Ok(())
+    }
+
+    /// Finds the top-level columns named in `names` that page-level metadata (bloom
+    /// filters or statistics, described by `kind`) should be collected for
+    fn find_page_metadata_columns<'a>(
+        &self,
+        schema: &'a LanceSchema,
+        names: &[String],
+        kind: &str,
+        is_supported: fn(&DataType) -> bool,
+    ) -> Result<Vec<(&'a Field, u32)>> {
+        // Legacy (2.0) pages do not record the rows they cover
+        if !names.is_empty() && self.version().resolve() < LanceFileVersion::V2_1 {
+            return Err(Error::invalid_input(format!(
+                "Page {} require file version 2.1 or later (got {})",
+                kind,
+                self.version()
+            )));
+        }
+        names
+            .iter()
+            .map(|name| {
+                let field = schema
+                    .fields
+                    .iter()
+                    .find(|field| &field.name == name)
+                    .ok_or_else(|| {
+                        Error::invalid_input(format!(
+                            "Cannot write {} for column `{}` because it is not a top-level column of the schema",
+                            kind, name
+                        ))
+                    })?;
+                if !is_supported(&field.data_type()) {
+                    return Err(Error::invalid_input(format!(
+                        "Cannot write {} for column `{}` with data type {}",
+                        kind,
+                        name,
+                        field.data_type()
+                    )));
+                }
+                let (_, column_index) = self
+                    .field_id_to_column_indices
+                    .iter()
+                    .find(|(field_id, _)| *field_id as i32 == field.id)
+                    .copied()
+                    .expect_ok()?;
+                Ok((field, column_index))
+            })
+            .collect()
+    }
+
+    fn make_bloom_filter_builders(
+        &self,
+        schema: &LanceSchema,
+    ) -> Result<Vec<PageBloomFilterBuilder>> {
+        Ok(self
+            .find_page_metadata_columns(
+                schema,
+                &self.options.bloom_filter_columns,
+                "bloom filters",
+                supports_bloom_filter,
+            )?
+            .into_iter()
+            .map(|(field, column_index)| {
+                PageBloomFilterBuilder::new(field.id, field.name.clone(), column_index)
+            })
+            .collect())
+    }
+
+    fn make_page_statistics_builders(
+        &self,
+        schema: &LanceSchema,
+    ) -> Result<Vec<PageStatisticsBuilder>> {
+        Ok(self
+            .find_page_metadata_columns(
+                schema,
+                &self.options.page_statistics_columns,
+                "statistics",
+                supports_page_statistics,
+            )?
+            .into_iter()
+            .map(|(field, column_index)| {
+                PageStatisticsBuilder::new(
+                    field.id,
+                    field.name.clone(),
+                    column_index,
+                    field.data_type(),
+                )
+            })
+            .collect())
+    }
+
+    fn make_column_encryptors(&self, schema: &LanceSchema) -> Result<Vec<ColumnEncryptor>> {
+        if self.options.encrypted_columns.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.version().resolve() < LanceFileVersion::V2_1 {
+            return Err(Error::invalid_input(format!(
+                "Column encryption requires file version 2.1 or later (got {})",
+                self.version()
+            )));
+        }
+        for name in self.options.encrypted_columns.keys() {
+            if self.options.bloom_filter_columns.contains(name)
+                || self.options.page_statistics_columns.contains(name)
+            {
+                return Err(Error::invalid_input(format!(
+                    "Column `{}` cannot be encrypted because it has page bloom filters or statistics",
+                    name
+                )));
+            }
+            if !schema.fields.iter().any(|field| &field.name == name) {
+                return Err(Error::invalid_input(format!(
+                    "Cannot encrypt column `{}` because it is not a top-level column of the schema",
+                    name
+                )));
+            }
+        }
+        schema
+            .fields
+            .iter()
+            .filter_map(|field| {
+                let key = self.options.encrypted_columns.get(&field.name)?;
+                Some(self.make_column_encryptor(field, key))
+            })
+            .collect()
+    }
+
+    fn make_column_encryptor(&self, field: &Field, key: &EncryptionKey) -> Result<ColumnEncryptor> {
+        fn collect_field_ids(field: &Field, field_ids: &mut Vec<i32>) {
+            field_ids.push(field.id);
+            for child in &field.children {
+                collect_field_ids(child, field_ids);
+            }
+        }
+        // Blob values are written as out-of-line buffers which are not part of any page
+        if field.is_blob() || field.is_blob_v2() {
+            return Err(Error::invalid_input(format!(
+                "Cannot encrypt blob column `{}`",
+                field.name
+            )));
+        }
+        let mut field_ids = Vec::new();
+        collect_field_ids(field, &mut field_ids);
+        let column_indices = self
+            .field_id_to_column_indices
+            .iter()
+            .filter(|(field_id, _)| field_ids.contains(&(*field_id as i32)))
+            .map(|(_, column_index)| *column_index)
+            .collect();
+        Ok(ColumnEncryptor::new(field.id, column_indices, key))
+    }
+
     fn ensure_initialized(&mut self, batch: &RecordBatch) -> Result<&LanceSchema> {
         if self.schema.is_none() {
             let schema = LanceSchema::try_from(batch.schema().as_ref())?;
@@ -547,9 +1060,21 @@ impl FileWriter {
         let mut external_buffers =
             OutOfLineBuffers::new(self.tell().await?, PAGE_BUFFER_ALIGNMENT as u64);
         let encoding_tasks = self.encode_batch(batch, &mut external_buffers)?;
+        for bloom_filter in &mut self.bloom_filters {
+            let array = batch
+                .column_by_name(bloom_filter.field_name())
+                .expect_ok()?;
+            bloom_filter.update(array.as_ref())?;
+        }
+        for page_statistics in &mut self.page_statistics {
+            let array = batch
+                .column_by_name(page_statistics.field_name())
+                .expect_ok()?;
+            page_statistics.update(self.rows_written, array.clone());
+        }
         // Next, write external buffers
         for external_buffer in external_buffers.take_buffers() {
-            Self::do_write_buffer(&mut self.writer, &external_buffer).await?;
+            self.write_buffer(&external_buffer).await?;
         }
 
         let encoding_tasks = encoding_tasks
@@ -690,11 +1215,78 @@ impl FileWriter {
     pub async fn add_global_buffer(&mut self, buffer: Bytes) -> Result<u32> {
         let position = self.writer.tell().await? as u64;
         let len = buffer.len() as u64;
-        Self::do_write_buffer(&mut self.writer, &buffer).await?;
+        self.write_buffer(&buffer).await?;
         self.global_buffers.push((position, len));
         Ok(self.global_buffers.len() as u32)
     }
 
+    async fn write_bloom_filters(&mut self) -> Result<()> {
+        if self.bloom_filters.is_empty() {
+            return Ok(());
+        }
+        let fpp = self
+            .options
+            .bloom_filter_fpp
+            .unwrap_or(DEFAULT_BLOOM_FILTER_FPP);
+        let bloom_filters = pbfile::BloomFilters {
+            columns: std::mem::take(&mut self.bloom_filters)
+                .into_iter()
+                .map(|bloom_filter| bloom_filter.finish(fpp))
+                .collect::<Result<Vec<_>>>()?,
+        };
+        let index = self
+            .add_global_buffer(Bytes::from(bloom_filters.encode_to_vec()))
+            .await?;
+        self.add_schema_metadata(BLOOM_FILTER_META_KEY, index.to_string());
+        Ok(())
+    }
+
+    async fn write_page_statistics(&mut self) -> Result<()> {
+        if self.page_statistics.is_empty() {
+            return Ok(());
+        }
+        let page_statistics = pbfile::PageStatistics {
+            columns: std::mem::take(&mut self.page_statistics)
+                .into_iter()
+                .map(PageStatisticsBuilder::finish)
+                .collect(),
+        };
+        let index = self
+            .add_global_buffer(Bytes::from(page_statistics.encode_to_vec()))
+            .await?;
+        self.add_schema_metadata(PAGE_STATISTICS_META_KEY, index.to_string());
+        Ok(())
+    }
+
+    async fn write_checksums(&mut self) -> Result<()> {
+        // Taken before the buffer is added so it does not checksum itself
+        let Some(checksums) = self.checksums.take() else {
+            return Ok(());
+        };
+        let index = self
+            .add_global_buffer(Bytes::from(checksums.finish().encode_to_vec()))
+            .await?;
+        self.add_schema_metadata(CHECKSUMS_META_KEY, index.to_string());
+        Ok(())
+    }
+
+    async fn write_encrypted_columns(&mut self) -> Result<()> {
+        if self.encryptors.is_empty() {
+            return Ok(());
+        }
+        let encrypted_columns = pbfile::EncryptedColumns {
+            columns: std::mem::take(&mut self.encryptors)
+                .into_iter()
+                .map(ColumnEncryptor::finish)
+                .collect(),
+        };
+        let index = self
+            .add_global_buffer(Bytes::from(encrypted_columns.encode_to_vec()))
+            .await?;
+        self.add_schema_metadata(ENCRYPTION_META_KEY, index.to_string());
+        Ok(())
+    }
+
     async fn finish_writers(&mut self) -> Result<()> {
         let mut col_idx = 0;
         for mut writer in std::mem::take(&mut self.column_writers) {
@@ -702,6 +1294,7 @@ impl FileWriter {
                 OutOfLineBuffers::new(self.tell().await?, PAGE_BUFFER_ALIGNMENT as u64);
             let columns = writer.finish(&mut external_buffers).await?;
             for buffer in external_buffers.take_buffers() {
+                self.record_checksum(&buffer).await?;
                 self.writer.write_all(&buffer).await?;
             }
             debug_assert_eq!(
@@ -716,16 +1309,28 @@ impl FileWriter {
                 for page in column.final_pages {
                     self.write_page(page).await?;
                 }
-                let column_metadata = &mut self.column_metadata[col_idx];
+                let encryptor_idx = self
+                    .encryptors
+                    .iter()
+                    .position(|encryptor| encryptor.encrypts_column(col_idx as u32));
                 let mut buffer_pos = self.writer.tell().await? as u64;
                 for buffer in column.column_buffers {
-                    column_metadata.buffer_offsets.push(buffer_pos);
+                    self.column_metadata[col_idx]
+                        .buffer_offsets
+                        .push(buffer_pos);
                     let mut size = 0;
-                    Self::do_write_buffer(&mut self.writer, &buffer).await?;
+                    if let Some(encryptor_idx) = encryptor_idx {
+                        let encrypted =
+                            self.encryptors[encryptor_idx].encrypt_buffer(buffer_pos, &buffer);
+                        self.write_buffer(&encrypted).await?;
+                    } else {
+                        self.write_buffer(&buffer).await?;
+                    }
                     size += buffer.len() as u64;
                     buffer_pos += size;
-                    column_metadata.buffer_sizes.push(size);
+                    self.column_metadata[col_idx].buffer_sizes.push(size);
                 }
+                let column_metadata = &mut self.column_metadata[col_idx];
                 let encoded_encoding = Any::from_msg(&column.encoding)?.encode_to_vec();
                 column_metadata.encoding = Some(pbfile::Encoding {
                     location: Some(pbfile::encoding::Location::Direct(pbfile::DirectEncoding {
@@ -775,7 +1380,7 @@ impl FileWriter {
             .map(|writer| writer.flush(&mut external_buffers))
             .collect::<Result<Vec<_>>>()?;
         for external_buffer in external_buffers.take_buffers() {
-            Self::do_write_buffer(&mut self.writer, &external_buffer).await?;
+            self.write_buffer(&external_buffer).await?;
         }
         let encoding_tasks = encoding_tasks
             .into_iter()
@@ -787,6 +1392,13 @@ impl FileWriter {
             self.finish_writers().await?;
         }
 
+        // 2. write the page bloom filters, statistics, encryption metadata and checksums
+        //    (if any were requested)
+        self.write_bloom_filters().await?;
+        self.write_page_statistics().await?;
+        self.write_encrypted_columns().await?;
+        self.write_checksums().await?;
+
         // 3. write global buffers (we write the schema here)
         let global_buffer_offsets = self.write_global_buffers().await?;
         let num_global_buffers = global_buffer_offsets.len() as u32;
@@ -975,20 +1587,29 @@ impl EncodedBatchWriteExt for EncodedBatch {
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::ops::Bound;
     use std::sync::Arc;
 
-    use crate::reader::{FileReader, FileReaderOptions, describe_encoding};
+    use crate::encryption::{ENCRYPTION_KEY_LEN, EncryptionKey};
+    use crate::page_statistics::PagePredicate;
+    use crate::reader::{FileReader, FileReaderOptions, ReaderProjection, describe_encoding};
     use crate::testing::FsFixture;
-    use crate::writer::{ENV_LANCE_FILE_WRITER_MAX_PAGE_BYTES, FileWriter, FileWriterOptions};
+    use crate::writer::{
+        ColumnPageOptions, ENV_LANCE_FILE_WRITER_MAX_PAGE_BYTES, FileWriter, FileWriterOptions,
+        StreamWriteOptions,
+    };
     use arrow_array::builder::{Float32Builder, Int32Builder};
-    use arrow_array::{Int32Array, RecordBatch, UInt64Array};
+    use arrow_array::{BinaryArray, Int32Array, RecordBatch, UInt64Array};
     use arrow_array::{RecordBatchReader, StringArray, types::Float64Type};
     use arrow_schema::{DataType, Field, Field as ArrowField, Schema, Schema as ArrowSchema};
+    use futures::{StreamExt, TryStreamExt};
+    use lance_arrow_scalar::ArrowScalar;
     use lance_core::cache::LanceCache;
     use lance_core::datatypes::Schema as LanceSchema;
     use lance_core::utils::tempfile::TempObjFile;
     use lance_datagen::{BatchCount, RowCount, array, gen_batch};
     use lance_encoding::compression_config::{CompressionFieldParams, CompressionParams};
+    use lance_encoding::constants::PAGE_ROW_ALIGNMENT_META_KEY;
     use lance_encoding::decoder::DecoderPlugins;
     use lance_encoding::version::LanceFileVersion;
     use lance_io::object_store::ObjectStore;
@@ -1019,6 +1640,119 @@ mod tests {
         // Tests asserting the contents of the written file are in reader.rs
     }
 
+    #[tokio::test]
+    async fn test_write_stream() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let reader = gen_batch()
+            .col("id", array::step::<arrow_array::types::Int32Type>())
+            .col("score", array::rand::<Float64Type>())
+            .into_reader_rows(RowCount::from(1000), BatchCount::from(20));
+        let arrow_schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        let lance_schema = LanceSchema::try_from(arrow_schema.as_ref()).unwrap();
+
+        // Only one batch fits in the read-ahead budget so the stream is never polled
+        // more than one batch ahead of the writer
+        let polled = Arc::new(AtomicU64::new(0));
+        let stream_polled = polled.clone();
+        let stream = futures::stream::iter(batches.clone()).map(move |batch| {
+            stream_polled.fetch_add(1, Ordering::SeqCst);
+            Ok(batch)
+        });
+        let fs = FsFixture::default();
+        let mut writer = FileWriter::try_new(
+            fs.object_store.create(&fs.tmp_path).await.unwrap(),
+            lance_schema.clone(),
+            FileWriterOptions::default(),
+        )
+        .unwrap();
+        let mut num_callbacks = 0;
+        let progress = writer
+            .write_stream(
+                stream,
+                StreamWriteOptions {
+                    max_buffered_bytes: 1024,
+                },
+                |progress| {
+                    num_callbacks += 1;
+                    assert_eq!(progress.rows_written, progress.batches_written * 1000);
+                    assert!(polled.load(Ordering::SeqCst) <= progress.batches_written + 1);
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(num_callbacks, 20);
+        assert_eq!(progress.batches_written, 20);
+        assert_eq!(progress.rows_written, 20_000);
+        assert_eq!(writer.finish().await.unwrap().num_rows, 20_000);
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &LanceCache::no_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+        let data = file_reader
+            .read_stream(
+                lance_io::ReadBatchParams::RangeFull,
+                1000,
+                16,
+                lance_encoding::decoder::FilterExpression::no_filter(),
+            )
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(data, batches);
+
+        // Synchronous readers work the same way
+        let tmp_path = TempObjFile::default();
+        let mut writer = FileWriter::try_new(
+            fs.object_store.create(&tmp_path).await.unwrap(),
+            lance_schema.clone(),
+            FileWriterOptions::default(),
+        )
+        .unwrap();
+        let reader = arrow_array::RecordBatchIterator::new(
+            batches.clone().into_iter().map(Ok),
+            arrow_schema.clone(),
+        );
+        let progress = writer
+            .write_reader(reader, StreamWriteOptions::default(), |_| {})
+            .await
+            .unwrap();
+        assert_eq!(progress.rows_written, 20_000);
+        writer.finish().await.unwrap();
+
+        // Errors from the input are returned and stop the write
+        let mut writer = FileWriter::try_new(
+            fs.object_store.create(&tmp_path).await.unwrap(),
+            lance_schema,
+            FileWriterOptions::default(),
+        )
+        .unwrap();
+        let stream = futures::stream::iter(vec![
+            Ok(batches[0].clone()),
+            Err(lance_core::Error::io("input failed")),
+            Ok(batches[1].clone()),
+        ]);
+        let err = writer
+            .write_stream(stream, StreamWriteOptions::default(), |_| {})
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("input failed"), "{}", err);
+    }
+
     #[tokio::test]
     async fn test_write_empty() {
         let tmp_path = TempObjFile::default();
@@ -1116,6 +1850,233 @@ mod tests {
         assert_eq!(total_page_num, 8)
     }
 
+    #[tokio::test]
+    async fn test_page_layout_options() {
+        let arrow_schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("text", DataType::Utf8, true),
+        ]));
+        let lance_schema = LanceSchema::try_from(arrow_schema.as_ref()).unwrap();
+        let batches = (0..7)
+            .map(|batch_idx| {
+                let ids = Int32Array::from_iter_values(batch_idx * 1000..(batch_idx + 1) * 1000);
+                let text = StringArray::from_iter(
+                    ids.values()
+                        .iter()
+                        .map(|id| (id % 3 != 0).then(|| format!("text-{id}"))),
+                );
+                RecordBatch::try_new(arrow_schema.clone(), vec![Arc::new(ids), Arc::new(text)])
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let options = FileWriterOptions {
+            // Flush a page as soon as the rows line up
+            data_cache_bytes: Some(1),
+            page_row_alignment: Some(1024),
+            column_page_options: HashMap::from([(
+                "text".to_string(),
+                ColumnPageOptions {
+                    page_size_bytes: Some(64 * 1024),
+                    row_alignment: Some(768),
+                    ..Default::default()
+                },
+            )]),
+            format_version: Some(LanceFileVersion::V2_1),
+            ..Default::default()
+        };
+        let fs = FsFixture::default();
+        let mut writer = FileWriter::try_new(
+            fs.object_store.create(&fs.tmp_path).await.unwrap(),
+            lance_schema.clone(),
+            options,
+        )
+        .unwrap();
+        for batch in &batches {
+            writer.write_batch(batch).await.unwrap();
+        }
+        writer.finish().await.unwrap();
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &LanceCache::no_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+        let page_rows = |column: usize| {
+            file_reader.metadata().column_metadatas[column]
+                .pages
+                .iter()
+                .map(|page| page.length)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(page_rows(0), vec![1024, 1024, 1024, 1024, 1024, 1024, 856]);
+        let text_pages = page_rows(1);
+        assert!(text_pages.len() > 1 && text_pages.len() < 7);
+        assert!(
+            text_pages[..text_pages.len() - 1]
+                .iter()
+                .all(|rows| rows % 768 == 0)
+        );
+        assert_eq!(text_pages.iter().sum::<u64>(), 7000);
+
+        let text_field = &file_reader.schema().fields[1];
+        assert_eq!(
+            text_field.metadata.get(PAGE_ROW_ALIGNMENT_META_KEY),
+            Some(&"768".to_string())
+        );
+        let data = file_reader
+            .read_stream(
+                lance_io::ReadBatchParams::RangeFull,
+                1024,
+                16,
+                lance_encoding::decoder::FilterExpression::no_filter(),
+            )
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let data = arrow_select::concat::concat_batches(&data[0].schema(), &data).unwrap();
+        let expected = arrow_select::concat::concat_batches(&arrow_schema, &batches).unwrap();
+        assert_eq!(data.columns(), expected.columns());
+
+        // Page layout options are validated when the writer is created
+        let try_new = |options: FileWriterOptions| {
+            let lance_schema = lance_schema.clone();
+            let fs = &fs;
+            async move {
+                FileWriter::try_new(
+                    fs.object_store.create(&fs.tmp_path).await.unwrap(),
+                    lance_schema,
+                    options,
+                )
+            }
+        };
+        for options in [
+            FileWriterOptions {
+                page_row_alignment: Some(1024),
+                format_version: Some(LanceFileVersion::V2_0),
+                ..Default::default()
+            },
+            FileWriterOptions {
+                page_row_alignment: Some(0),
+                ..Default::default()
+            },
+            FileWriterOptions {
+                column_page_options: HashMap::from([(
+                    "missing".to_string(),
+                    ColumnPageOptions::default(),
+                )]),
+                ..Default::default()
+            },
+            FileWriterOptions {
+                column_page_options: HashMap::from([(
+                    "id".to_string(),
+                    ColumnPageOptions {
+                        page_size_bytes: Some(0),
+                        ..Default::default()
+                    },
+                )]),
+                ..Default::default()
+            },
+        ] {
+            assert!(try_new(options).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_large_value_threshold() {
+        let arrow_schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("data", DataType::Binary, true),
+        ]));
+        let lance_schema = LanceSchema::try_from(arrow_schema.as_ref()).unwrap();
+        let large_value = (0..3 * 1024 * 1024)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<_>>();
+        let batches = (0..4)
+            .map(|batch_idx| {
+                let ids = Int32Array::from_iter_values(batch_idx * 100..(batch_idx + 1) * 100);
+                let data = BinaryArray::from_iter(ids.values().iter().map(|id| match id {
+                    150 | 151 => Some(large_value.as_slice()),
+                    id if id % 7 == 0 => None,
+                    _ => Some(b"small".as_slice()),
+                }));
+                RecordBatch::try_new(arrow_schema.clone(), vec![Arc::new(ids), Arc::new(data)])
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let options = FileWriterOptions {
+            max_page_bytes: Some(1024 * 1024),
+            column_page_options: HashMap::from([(
+                "data".to_string(),
+                ColumnPageOptions {
+                    large_value_threshold: Some(64 * 1024),
+                    ..Default::default()
+                },
+            )]),
+            format_version: Some(LanceFileVersion::V2_1),
+            ..Default::default()
+        };
+        let fs = FsFixture::default();
+        let mut writer = FileWriter::try_new(
+            fs.object_store.create(&fs.tmp_path).await.unwrap(),
+            lance_schema,
+            options,
+        )
+        .unwrap();
+        for batch in &batches {
+            writer.write_batch(batch).await.unwrap();
+        }
+        writer.finish().await.unwrap();
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &LanceCache::no_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        // The large values are not part of any page
+        for page in &file_reader.metadata().column_metadatas[1].pages {
+            assert!(page.buffer_sizes.iter().sum::<u64>() < 64 * 1024);
+        }
+
+        let data = file_reader
+            .read_stream(
+                lance_io::ReadBatchParams::RangeFull,
+                64,
+                16,
+                lance_encoding::decoder::FilterExpression::no_filter(),
+            )
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let data = arrow_select::concat::concat_batches(&data[0].schema(), &data).unwrap();
+        let expected = arrow_select::concat::concat_batches(&arrow_schema, &batches).unwrap();
+        assert_eq!(data.columns(), expected.columns());
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn test_max_page_bytes_env_var() {
         let arrow_field = Field::new("data", DataType::UInt64, false);
@@ -1253,6 +2214,7 @@ mod tests {
                 compression_level: None,
                 bss: Some(lance_encoding::compression_config::BssMode::Off), // Explicitly disable BSS to ensure RLE is used
                 minichunk_size: None,
+                delta: None,
             },
         );
 
@@ -1731,4 +2693,559 @@ mod tests {
                 .await;
         assert_eq!(baseline, spilled);
     }
+
+    #[tokio::test]
+    async fn test_page_bloom_filters() {
+        let fs = FsFixture::default();
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, true),
+            ArrowField::new("name", DataType::Utf8, true),
+        ]));
+        let lance_schema = LanceSchema::try_from(schema.as_ref()).unwrap();
+
+        let options = FileWriterOptions {
+            // Flush a page for every batch
+            data_cache_bytes: Some(1),
+            format_version: Some(LanceFileVersion::V2_1),
+            bloom_filter_columns: vec!["id".to_string(), "name".to_string()],
+            ..Default::default()
+        };
+        let writer = fs.object_store.create(&fs.tmp_path).await.unwrap();
+        let mut file_writer = FileWriter::try_new(writer, lance_schema, options).unwrap();
+        for page in 0..4 {
+            let ids = Int32Array::from_iter_values(page * 100..(page + 1) * 100);
+            let names = StringArray::from_iter_values(
+                (page * 100..(page + 1) * 100).map(|id| format!("name-{id}")),
+            );
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(ids), Arc::new(names)]).unwrap();
+            file_writer.write_batch(&batch).await.unwrap();
+        }
+        // A page with only nulls can never match
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::new_null(100)),
+                Arc::new(StringArray::new_null(100)),
+            ],
+        )
+        .unwrap();
+        file_writer.write_batch(&batch).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &LanceCache::no_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let ranges = file_reader
+            .bloom_filter_ranges("id", &Int32Array::from(vec![250]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ranges, vec![200..300]);
+
+        let ranges = file_reader
+            .bloom_filter_ranges("name", &StringArray::from(vec!["name-42"]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ranges, vec![0..100]);
+
+        // Values that were never written match no page (barring false positives)
+        let ranges = file_reader
+            .bloom_filter_ranges("id", &Int32Array::from(vec![100_000]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(ranges.is_empty());
+        let ranges = file_reader
+            .bloom_filter_ranges("id", &Int32Array::from(vec![None]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(ranges.is_empty());
+
+        // Mismatched types are rejected
+        assert!(
+            file_reader
+                .bloom_filter_ranges("id", &StringArray::from(vec!["1"]))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_page_statistics() {
+        let fs = FsFixture::default();
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, true),
+            ArrowField::new("name", DataType::Utf8, true),
+        ]));
+        let lance_schema = LanceSchema::try_from(schema.as_ref()).unwrap();
+
+        let options = FileWriterOptions {
+            // Flush a page for every batch
+            data_cache_bytes: Some(1),
+            format_version: Some(LanceFileVersion::V2_1),
+            page_statistics_columns: vec!["id".to_string(), "name".to_string()],
+            ..Default::default()
+        };
+        let writer = fs.object_store.create(&fs.tmp_path).await.unwrap();
+        let mut file_writer = FileWriter::try_new(writer, lance_schema, options).unwrap();
+        for page in 0..4 {
+            let ids = Int32Array::from_iter_values(page * 100..(page + 1) * 100);
+            let names = StringArray::from_iter_values(
+                (page * 100..(page + 1) * 100).map(|id| format!("name-{id:03}")),
+            );
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(ids), Arc::new(names)]).unwrap();
+            file_writer.write_batch(&batch).await.unwrap();
+        }
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::new_null(100)),
+                Arc::new(StringArray::new_null(100)),
+            ],
+        )
+        .unwrap();
+        file_writer.write_batch(&batch).await.unwrap();
+        file_writer.finish().await.unwrap();
+
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let file_reader = FileReader::try_open(
+            file_scheduler,
+            None,
+            Arc::<DecoderPlugins>::default(),
+            &LanceCache::no_cache(),
+            FileReaderOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let ranges = |column: &'static str, predicate: PagePredicate| {
+            let file_reader = &file_reader;
+            async move {
+                file_reader
+                    .page_statistics_ranges(column, &predicate)
+                    .await
+                    .unwrap()
+                    .unwrap()
+            }
+        };
+        assert_eq!(
+            ranges("id", PagePredicate::eq(ArrowScalar::from(250))).await,
+            vec![200..300]
+        );
+        assert_eq!(
+            ranges(
+                "id",
+                PagePredicate::Range {
+                    lower: Bound::Excluded(ArrowScalar::from(99)),
+                    upper: Bound::Unbounded,
+                }
+            )
+            .await,
+            vec![100..400]
+        );
+        assert_eq!(
+            ranges(
+                "name",
+                PagePredicate::Range {
+                    lower: Bound::Unbounded,
+                    upper: Bound::Included(ArrowScalar::from("name-150")),
+                }
+            )
+            .await,
+            vec![0..200]
+        );
+        assert!(
+            ranges("id", PagePredicate::eq(ArrowScalar::from(1000)))
+                .await
+                .is_empty()
+        );
+        assert_eq!(ranges("id", PagePredicate::IsNull).await, vec![400..500]);
+        assert_eq!(ranges("id", PagePredicate::IsNotNull).await, vec![0..400]);
+
+        // Mismatched types are rejected
+        assert!(
+            file_reader
+                .page_statistics_ranges("id", &PagePredicate::eq(ArrowScalar::from(1_i64)))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_page_metadata_columns_validation() {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, true),
+            ArrowField::new("score", DataType::Float32, true),
+        ]));
+        let lance_schema = LanceSchema::try_from(schema.as_ref()).unwrap();
+        let obj_store = Arc::new(ObjectStore::local());
+
+        for (columns, version) in [
+            (vec!["missing"], LanceFileVersion::V2_1),
+            (vec!["score"], LanceFileVersion::V2_1),
+            (vec!["id"], LanceFileVersion::V2_0),
+        ] {
+            let columns = columns.into_iter().map(String::from).collect::<Vec<_>>();
+            for options in [
+                FileWriterOptions {
+                    format_version: Some(version),
+                    bloom_filter_columns: columns.clone(),
+                    ..Default::default()
+                },
+                FileWriterOptions {
+                    format_version: Some(version),
+                    page_statistics_columns: columns.clone(),
+                    ..Default::default()
+                },
+            ] {
+                let tmp_path = TempObjFile::default();
+                let writer = obj_store.create(&tmp_path).await.unwrap();
+                assert!(FileWriter::try_new(writer, lance_schema.clone(), options).is_err());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_columns() {
+        use arrow_array::{StructArray, UInt32Array};
+        use arrow_schema::Fields;
+        use arrow_select::concat::concat_batches;
+        use arrow_select::take::take_record_batch;
+        use futures::TryStreamExt;
+        use lance_encoding::decoder::FilterExpression;
+        use lance_io::ReadBatchParams;
+
+        let fs = FsFixture::default();
+        let card_fields = Fields::from(vec![
+            ArrowField::new("number", DataType::Utf8, true),
+            ArrowField::new("cvv", DataType::Int32, true),
+        ]);
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, true),
+            ArrowField::new("secret", DataType::Utf8, true),
+            ArrowField::new("card", DataType::Struct(card_fields.clone()), true),
+        ]));
+        let lance_schema = LanceSchema::try_from(schema.as_ref()).unwrap();
+        let secret_key = EncryptionKey::new("secret-key", [1; ENCRYPTION_KEY_LEN]);
+        let card_key = EncryptionKey::new("card-key", [2; ENCRYPTION_KEY_LEN]);
+
+        let options = FileWriterOptions {
+            // Flush a page for every batch
+            data_cache_bytes: Some(1),
+            format_version: Some(LanceFileVersion::V2_1),
+            encrypted_columns: HashMap::from([
+                ("secret".to_string(), secret_key.clone()),
+                ("card".to_string(), card_key.clone()),
+            ]),
+            ..Default::default()
+        };
+        let writer = fs.object_store.create(&fs.tmp_path).await.unwrap();
+        let mut file_writer = FileWriter::try_new(writer, lance_schema, options).unwrap();
+        let mut batches = Vec::new();
+        for page in 0..4 {
+            let rows = page * 100..(page + 1) * 100;
+            let card = StructArray::new(
+                card_fields.clone(),
+                vec![
+                    Arc::new(StringArray::from_iter_values(
+                        rows.clone().map(|id| format!("4111-{id:04}")),
+                    )),
+                    Arc::new(Int32Array::from_iter_values(rows.clone())),
+                ],
+                None,
+            );
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from_iter_values(rows.clone())),
+                    Arc::new(StringArray::from_iter(
+                        rows.map(|id| (id % 7 != 0).then(|| format!("classified-{id}"))),
+                    )),
+                    Arc::new(card),
+                ],
+            )
+            .unwrap();
+            file_writer.write_batch(&batch).await.unwrap();
+            batches.push(batch);
+        }
+        file_writer.finish().await.unwrap();
+        let expected = concat_batches(&schema, &batches).unwrap();
+
+        // Neither the values nor the page descriptions are stored in the clear
+        let file_bytes = fs.object_store.read_one_all(&fs.tmp_path).await.unwrap();
+        let contains = |needle: &[u8]| file_bytes.windows(needle.len()).any(|w| w == needle);
+        assert!(!contains(b"classified-"));
+        assert!(!contains(b"4111-"));
+
+        let open = |keys: Vec<EncryptionKey>| {
+            let fs = &fs;
+            async move {
+                let file_scheduler = fs
+                    .scheduler
+                    .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+                    .await
+                    .unwrap();
+                FileReader::try_open(
+                    file_scheduler,
+                    None,
+                    Arc::<DecoderPlugins>::default(),
+                    &LanceCache::no_cache(),
+                    FileReaderOptions {
+                        encryption_keys: keys,
+                        ..Default::default()
+                    },
+                )
+                .await
+            }
+        };
+        let read = |file_reader: &FileReader, params: ReadBatchParams, columns: &[&str]| {
+            let projection = ReaderProjection::from_column_names(
+                file_reader.metadata().version(),
+                &file_reader.metadata().file_schema,
+                columns,
+            )
+            .unwrap();
+            let file_reader = file_reader.clone();
+            async move {
+                let batches = file_reader
+                    .read_stream_projected(
+                        params,
+                        1024,
+                        16,
+                        projection,
+                        FilterExpression::no_filter(),
+                    )
+                    .await?
+                    .try_collect::<Vec<_>>()
+                    .await?;
+                Ok::<_, lance_core::Error>(concat_batches(&batches[0].schema(), &batches).unwrap())
+            }
+        };
+
+        // With all keys every column can be read, including random access
+        let file_reader = open(vec![card_key.clone(), secret_key.clone()])
+            .await
+            .unwrap();
+        let all_columns = ["id", "secret", "card"];
+        let batch = read(&file_reader, ReadBatchParams::RangeFull, &all_columns)
+            .await
+            .unwrap();
+        assert_eq!(batch, expected);
+        let indices = vec![3, 150, 151, 399];
+        let batch = read(
+            &file_reader,
+            ReadBatchParams::Indices(UInt32Array::from(indices.clone())),
+            &all_columns,
+        )
+        .await
+        .unwrap();
+        let expected_taken = take_record_batch(&expected, &UInt32Array::from(indices)).unwrap();
+        assert_eq!(batch, expected_taken);
+
+        // Without a key the other columns can still be read
+        let file_reader = open(vec![secret_key.clone()]).await.unwrap();
+        let batch = read(&file_reader, ReadBatchParams::RangeFull, &["id", "secret"])
+            .await
+            .unwrap();
+        assert_eq!(batch, expected.project(&[0, 1]).unwrap());
+        let err = read(&file_reader, ReadBatchParams::RangeFull, &["id", "card"])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("card-key"), "{}", err);
+        let file_reader = open(Vec::new()).await.unwrap();
+        let batch = read(&file_reader, ReadBatchParams::RangeFull, &["id"])
+            .await
+            .unwrap();
+        assert_eq!(batch, expected.project(&[0]).unwrap());
+
+        // A wrong key is detected
+        let err = open(vec![EncryptionKey::new(
+            "card-key",
+            [3; ENCRYPTION_KEY_LEN],
+        )])
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("card-key"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_checksums() {
+        use crate::checksum::verify_file;
+        use arrow_select::concat::concat_batches;
+        use lance_encoding::decoder::FilterExpression;
+        use lance_io::ReadBatchParams;
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, true),
+            ArrowField::new("secret", DataType::Utf8, true),
+        ]));
+        let lance_schema = LanceSchema::try_from(schema.as_ref()).unwrap();
+        let key = EncryptionKey::new("key", [1; ENCRYPTION_KEY_LEN]);
+        let batches = (0..4)
+            .map(|page| {
+                let rows = page * 100..(page + 1) * 100;
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from_iter_values(rows.clone())),
+                        Arc::new(StringArray::from_iter_values(
+                            rows.map(|id| format!("secret-{id}")),
+                        )),
+                    ],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let expected = concat_batches(&schema, &batches).unwrap();
+
+        let write = |fs: FsFixture, write_checksums: bool| {
+            let lance_schema = lance_schema.clone();
+            let batches = batches.clone();
+            let key = key.clone();
+            async move {
+                let options = FileWriterOptions {
+                    // Flush a page for every batch
+                    data_cache_bytes: Some(1),
+                    format_version: Some(LanceFileVersion::V2_1),
+                    encrypted_columns: HashMap::from([("secret".to_string(), key)]),
+                    write_checksums,
+                    ..Default::default()
+                };
+                let writer = fs.object_store.create(&fs.tmp_path).await.unwrap();
+                let mut file_writer = FileWriter::try_new(writer, lance_schema, options).unwrap();
+                for batch in &batches {
+                    file_writer.write_batch(batch).await.unwrap();
+                }
+                file_writer.finish().await.unwrap();
+                fs
+            }
+        };
+        let read = async |fs: &FsFixture| {
+            let file_scheduler = fs
+                .scheduler
+                .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+                .await?;
+            let file_reader = FileReader::try_open(
+                file_scheduler,
+                None,
+                Arc::<DecoderPlugins>::default(),
+                &LanceCache::no_cache(),
+                FileReaderOptions {
+                    encryption_keys: vec![key.clone()],
+                    verify_checksums: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+            let batches = file_reader
+                .read_stream(
+                    ReadBatchParams::RangeFull,
+                    1024,
+                    16,
+                    FilterExpression::no_filter(),
+                )
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?;
+            Ok::<_, lance_core::Error>(concat_batches(&batches[0].schema(), &batches).unwrap())
+        };
+        let verify = async |fs: &FsFixture| {
+            let file_scheduler = fs
+                .scheduler
+                .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+                .await?;
+            verify_file(&file_scheduler).await
+        };
+
+        let fs = write(FsFixture::default(), true).await;
+        assert_eq!(read(&fs).await.unwrap(), expected);
+        let verified = verify(&fs).await.unwrap();
+        // At least one buffer per page
+        assert!(verified.num_buffers >= 8, "{:?}", verified);
+
+        // Corrupt a single byte of the first page of the id column
+        let file_scheduler = fs
+            .scheduler
+            .open_file(&fs.tmp_path, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+        let metadata = FileReader::read_all_metadata(&file_scheduler)
+            .await
+            .unwrap();
+        let position = metadata.column_metadatas[0].pages[0].buffer_offsets[0] as usize;
+        let mut file_bytes = fs
+            .object_store
+            .read_one_all(&fs.tmp_path)
+            .await
+            .unwrap()
+            .to_vec();
+        file_bytes[position] ^= 0xFF;
+        fs.object_store
+            .put(&fs.tmp_path, &file_bytes)
+            .await
+            .unwrap();
+        let err = read(&fs).await.unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"), "{}", err);
+        let err = verify(&fs).await.unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"), "{}", err);
+
+        // Files without checksums cannot be verified
+        let fs = write(FsFixture::default(), false).await;
+        let err = read(&fs).await.unwrap_err();
+        assert!(err.to_string().contains("without checksums"), "{}", err);
+        let err = verify(&fs).await.unwrap_err();
+        assert!(err.to_string().contains("without checksums"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_columns_validation() {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "id",
+            DataType::Int32,
+            true,
+        )]));
+        let lance_schema = LanceSchema::try_from(schema.as_ref()).unwrap();
+        let obj_store = Arc::new(ObjectStore::local());
+        let key = EncryptionKey::new("key", [0; ENCRYPTION_KEY_LEN]);
+
+        for (column, version, bloom_filter) in [
+            ("missing", LanceFileVersion::V2_1, false),
+            ("id", LanceFileVersion::V2_0, false),
+            ("id", LanceFileVersion::V2_1, true),
+        ] {
+            let options = FileWriterOptions {
+                format_version: Some(version),
+                encrypted_columns: HashMap::from([(column.to_string(), key.clone())]),
+                bloom_filter_columns: if bloom_filter {
+                    vec![column.to_string()]
+                } else {
+                    Vec::new()
+                },
+                ..Default::default()
+            };
+            let tmp_path = TempObjFile::default();
+            let writer = obj_store.create(&tmp_path).await.unwrap();
+            assert!(FileWriter::try_new(writer, lance_schema.clone(), options).is_err());
+        }
+    }
 }
@@ -1,6 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion::common::{Column, Result as DFResult, ScalarValue};
+use datafusion::functions::core::expr_fn::get_field;
+use datafusion::logical_expr::{Expr, lit};
 use geo_traits::{
     CoordTrait, GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait, LineTrait,
     MultiLineStringTrait, MultiPointTrait, MultiPolygonTrait, PointTrait, PolygonTrait, RectTrait,
@@ -9,13 +14,37 @@ use geo_traits::{
     UnimplementedPoint, UnimplementedPolygon, UnimplementedTriangle,
 };
 use geo_types::Coord;
-use geoarrow_array::array::RectArray;
+use geoarrow_array::array::{RectArray, from_arrow_array};
 use geoarrow_array::builder::RectBuilder;
 use geoarrow_array::{GeoArrowArray, GeoArrowArrayAccessor, downcast_geoarrow_array};
 use geoarrow_schema::{BoxType, Dimension};
 use lance_core::error::ArrowResult;
 use serde::{Deserialize, Serialize};
 
+/// Field metadata key, set on a geometry field, naming the column that stores the
+/// bounding box of each geometry
+///
+/// The bounding box column must be a struct with `xmin`, `ymin`, `xmax` and `ymax`
+/// children that are all `Float32` or all `Float64` (the layout of a GeoParquet bbox
+/// covering column).  See [`add_bbox_filters`].
+pub const BBOX_COLUMN_META_KEY: &str = "lance-geo:bbox-column";
+
+const BBOX_FIELDS: [&str; 4] = ["xmin", "ymin", "xmax", "ymax"];
+
+/// Spatial predicates that can only be true if the bounding boxes of the two
+/// geometries intersect
+const BBOX_PREDICATES: [&str; 9] = [
+    "st_intersects",
+    "st_contains",
+    "st_within",
+    "st_covers",
+    "st_coveredby",
+    "st_touches",
+    "st_crosses",
+    "st_overlaps",
+    "st_equals",
+];
+
 /// Inspired by <https://github.com/geoarrow/geoarrow-rs>
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct BoundingBox {
@@ -331,3 +360,119 @@ fn impl_total_bounds<'a>(arr: &'a impl GeoArrowArrayAccessor<'a>) -> ArrowResult
 
     Ok(bbox)
 }
+
+/// The type of the bounds of a bounding box column, if `field` is a valid bounding
+/// box column
+fn bbox_bounds_type(field: &Field) -> Option<DataType> {
+    let DataType::Struct(children) = field.data_type() else {
+        return None;
+    };
+    let mut bounds_type = None;
+    for name in BBOX_FIELDS {
+        let (_, child) = children.find(name)?;
+        if !matches!(child.data_type(), DataType::Float32 | DataType::Float64)
+            || bounds_type.is_some_and(|bounds_type| &bounds_type != child.data_type())
+        {
+            return None;
+        }
+        bounds_type = Some(child.data_type().clone());
+    }
+    bounds_type
+}
+
+/// A filter on a bounding box column that matches the rows whose bounding box
+/// intersects `bbox`
+///
+/// `bounds_type` is the type of the bounds of the column, `Float32` bounds are rounded
+/// outwards so that no intersecting row is filtered out.  Returns `None` if the bounds
+/// type is not supported or `bbox` is empty.
+pub fn bbox_intersects_filter(
+    bbox_column: &str,
+    bbox: &BoundingBox,
+    bounds_type: &DataType,
+) -> Option<Expr> {
+    let bounds = [bbox.minx(), bbox.miny(), bbox.maxx(), bbox.maxy()];
+    if bounds.iter().any(|bound| !bound.is_finite()) {
+        return None;
+    }
+    let bound = |value: f64, round_up: bool| -> Option<ScalarValue> {
+        match bounds_type {
+            DataType::Float64 => Some(ScalarValue::Float64(Some(value))),
+            DataType::Float32 => {
+                let rounded = value as f32;
+                let rounded = if round_up && (rounded as f64) < value {
+                    rounded.next_up()
+                } else if !round_up && (rounded as f64) > value {
+                    rounded.next_down()
+                } else {
+                    rounded
+                };
+                Some(ScalarValue::Float32(Some(rounded)))
+            }
+            _ => None,
+        }
+    };
+    let column = || Expr::Column(Column::new_unqualified(bbox_column));
+    let [xmin, ymin, xmax, ymax] = BBOX_FIELDS.map(|name| get_field(column(), name));
+    Some(
+        xmin.lt_eq(lit(bound(bbox.maxx(), true)?))
+            .and(xmax.gt_eq(lit(bound(bbox.minx(), false)?)))
+            .and(ymin.lt_eq(lit(bound(bbox.maxy(), true)?)))
+            .and(ymax.gt_eq(lit(bound(bbox.miny(), false)?))),
+    )
+}
+
+/// The bounding box of a geometry literal
+fn literal_bounds(value: &ScalarValue, metadata: Option<&Field>) -> Option<BoundingBox> {
+    let array = value.to_array().ok()?;
+    let field = metadata?;
+    let geo_array = from_arrow_array(array.as_ref(), field).ok()?;
+    total_bounds(geo_array.as_ref()).ok()
+}
+
+/// Adds bounding box filters to the spatial predicates of `filter`
+///
+/// Every spatial predicate between a geometry column that has a bounding box column
+/// (see [`BBOX_COLUMN_META_KEY`]) and a geometry literal, e.g.
+/// `st_intersects(geom, <literal>)`, is replaced with `<bbox filter> AND <predicate>`.
+/// The bounding box filter only compares the bounds with constants so it is cheap to
+/// evaluate and can be used to skip fragments with statistics.  The predicates imply
+/// the bounding box filter so the result of the filter does not change.
+pub fn add_bbox_filters(filter: Expr, schema: &Schema) -> DFResult<Expr> {
+    filter
+        .transform_up(|expr| {
+            let Expr::ScalarFunction(func) = &expr else {
+                return Ok(Transformed::no(expr));
+            };
+            if !BBOX_PREDICATES.contains(&func.name()) || func.args.len() != 2 {
+                return Ok(Transformed::no(expr));
+            }
+            let ((Expr::Column(column), Expr::Literal(value, metadata))
+            | (Expr::Literal(value, metadata), Expr::Column(column))) =
+                (&func.args[0], &func.args[1])
+            else {
+                return Ok(Transformed::no(expr));
+            };
+            let Some((bbox_column, bounds_type)) = schema
+                .field_with_name(&column.name)
+                .ok()
+                .and_then(|field| field.metadata().get(BBOX_COLUMN_META_KEY))
+                .and_then(|bbox_column| {
+                    let bbox_field = schema.field_with_name(bbox_column).ok()?;
+                    Some((bbox_column, bbox_bounds_type(bbox_field)?))
+                })
+            else {
+                return Ok(Transformed::no(expr));
+            };
+            let literal_field = metadata.as_ref().map(|metadata| {
+                Field::new("_geo", value.data_type(), true).with_metadata(metadata.to_hashmap())
+            });
+            let Some(bbox_filter) = literal_bounds(value, literal_field.as_ref())
+                .and_then(|bbox| bbox_intersects_filter(bbox_column, &bbox, &bounds_type))
+            else {
+                return Ok(Transformed::no(expr));
+            };
+            Ok(Transformed::yes(bbox_filter.and(expr)))
+        })
+        .data()
+}
@@ -0,0 +1,336 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Discrete global grid cells of geometries.
+//!
+//! `st_geohash` and `st_h3cell` map a geometry to the geohash or H3 cell that
+//! contains the center of its bounding box. The cells are plain strings and
+//! integers, so a cell column added with a SQL expression can be indexed with
+//! a BTree or bitmap index and used for approximate spatial filtering and for
+//! aggregating by cell. Geohash cells nest by prefix, so a coarser cell is
+//! matched with `starts_with(cell, prefix)`. H3 cells are coarsened with
+//! `h3_cell_to_parent`.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, StringBuilder, UInt64Array, UInt64Builder,
+};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, FieldRef, Int64Type, UInt64Type};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    Volatility,
+};
+use datafusion::scalar::ScalarValue;
+use geo_types::Coord;
+use h3o::{CellIndex, LatLng, Resolution};
+use rstar::Envelope;
+
+use crate::crs::{WGS84, field_crs};
+use crate::join::geometry_bounds;
+
+/// The longest geohash, about 37mm x 19mm.
+const MAX_GEOHASH_PRECISION: i64 = 12;
+
+/// All cell functions.
+pub fn cell_udfs() -> Vec<ScalarUDF> {
+    vec![
+        ScalarUDF::new_from_impl(GeometryCell::new(CellGrid::Geohash)),
+        ScalarUDF::new_from_impl(GeometryCell::new(CellGrid::H3)),
+        ScalarUDF::new_from_impl(H3Cell::new(H3CellFunction::ToParent)),
+        ScalarUDF::new_from_impl(H3Cell::new(H3CellFunction::ToString)),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CellGrid {
+    /// `st_geohash(geom, precision)`, the geohash string of `precision`
+    /// characters.
+    Geohash,
+    /// `st_h3cell(geom, resolution)`, the H3 cell index at `resolution`.
+    H3,
+}
+
+/// A function from a geometry and a grid level to the cell of the geometry.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct GeometryCell {
+    grid: CellGrid,
+    signature: Signature,
+}
+
+impl GeometryCell {
+    fn new(grid: CellGrid) -> Self {
+        Self {
+            grid,
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+
+    fn cell_type(&self) -> DataType {
+        match self.grid {
+            CellGrid::Geohash => DataType::Utf8,
+            CellGrid::H3 => DataType::UInt64,
+        }
+    }
+}
+
+impl ScalarUDFImpl for GeometryCell {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        match self.grid {
+            CellGrid::Geohash => "st_geohash",
+            CellGrid::H3 => "st_h3cell",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        match arg_types {
+            [geometry, level] if level.is_integer() || level.is_null() => {
+                Ok(vec![geometry.clone(), DataType::Int64])
+            }
+            _ => Err(DataFusionError::Plan(format!(
+                "{} expects a geometry and an integer grid level, got {arg_types:?}",
+                self.name()
+            ))),
+        }
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.cell_type())
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        let geometry = &args.arg_fields[0];
+        if !is_geometry(geometry) {
+            return Err(DataFusionError::Plan(format!(
+                "{} expects a geometry, got {}",
+                self.name(),
+                geometry.data_type()
+            )));
+        }
+        // Cells are defined on longitude / latitude
+        if let Some(crs) = field_crs(geometry)
+            && crs != WGS84
+        {
+            return Err(DataFusionError::Plan(format!(
+                "{} requires geometries in {WGS84}, got {crs}, use st_transform to reproject them",
+                self.name()
+            )));
+        }
+        Ok(Arc::new(Field::new(self.name(), self.cell_type(), true)))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let all_scalars = args
+            .args
+            .iter()
+            .all(|arg| matches!(arg, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(&args.args)?;
+        let bounds = geometry_bounds(&arrays[0], &args.arg_fields[0])?;
+        let levels = cast(&arrays[1], &DataType::Int64)?;
+        let centers =
+            bounds
+                .iter()
+                .zip(levels.as_primitive::<Int64Type>())
+                .map(|(bounds, level)| {
+                    let bounds = bounds.as_ref()?;
+                    let center = bounds.center();
+                    Some((
+                        Coord {
+                            x: center[0],
+                            y: center[1],
+                        },
+                        level?,
+                    ))
+                });
+
+        let result: ArrayRef = match self.grid {
+            CellGrid::Geohash => {
+                let mut builder = StringBuilder::with_capacity(bounds.len(), 0);
+                for center in centers {
+                    match center {
+                        Some((center, precision)) => {
+                            builder.append_value(geohash(center, precision)?)
+                        }
+                        None => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            CellGrid::H3 => {
+                let mut builder = UInt64Builder::with_capacity(bounds.len());
+                for center in centers {
+                    match center {
+                        Some((center, resolution)) => {
+                            let resolution = resolution_arg(self.name(), resolution)?;
+                            let cell = LatLng::new(center.y, center.x)
+                                .map_err(|e| DataFusionError::Execution(e.to_string()))?
+                                .to_cell(resolution);
+                            builder.append_value(cell.into());
+                        }
+                        None => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+        };
+        if all_scalars {
+            Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                &result, 0,
+            )?))
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+/// Whether a field holds geometries, as WKB or as a GeoArrow type.
+fn is_geometry(field: &Field) -> bool {
+    #[cfg(feature = "geo")]
+    if geoarrow_schema::GeoArrowType::try_from(field).is_ok() {
+        return true;
+    }
+    matches!(
+        field.data_type(),
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView | DataType::Null
+    )
+}
+
+fn geohash(center: Coord, precision: i64) -> Result<String> {
+    if !(1..=MAX_GEOHASH_PRECISION).contains(&precision) {
+        return Err(DataFusionError::Execution(format!(
+            "st_geohash precision must be between 1 and {MAX_GEOHASH_PRECISION}, got {precision}"
+        )));
+    }
+    geohash::encode(center, precision as usize)
+        .map_err(|e| DataFusionError::Execution(format!("st_geohash: {e}")))
+}
+
+fn resolution_arg(function: &str, resolution: i64) -> Result<Resolution> {
+    u8::try_from(resolution)
+        .ok()
+        .and_then(|resolution| Resolution::try_from(resolution).ok())
+        .ok_or_else(|| {
+            DataFusionError::Execution(format!(
+                "{function} resolution must be between 0 and 15, got {resolution}"
+            ))
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum H3CellFunction {
+    /// `h3_cell_to_parent(cell, resolution)`, the ancestor of a cell at a
+    /// coarser resolution.
+    ToParent,
+    /// `h3_cell_to_string(cell)`, the hexadecimal form of a cell index.
+    ToString,
+}
+
+/// A function of H3 cell indices.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct H3Cell {
+    function: H3CellFunction,
+    signature: Signature,
+}
+
+impl H3Cell {
+    fn new(function: H3CellFunction) -> Self {
+        let signature = match function {
+            H3CellFunction::ToParent => Signature::exact(
+                vec![DataType::UInt64, DataType::Int64],
+                Volatility::Immutable,
+            ),
+            H3CellFunction::ToString => {
+                Signature::exact(vec![DataType::UInt64], Volatility::Immutable)
+            }
+        };
+        Self {
+            function,
+            signature,
+        }
+    }
+}
+
+impl ScalarUDFImpl for H3Cell {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        match self.function {
+            H3CellFunction::ToParent => "h3_cell_to_parent",
+            H3CellFunction::ToString => "h3_cell_to_string",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(match self.function {
+            H3CellFunction::ToParent => DataType::UInt64,
+            H3CellFunction::ToString => DataType::Utf8,
+        })
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let all_scalars = args
+            .args
+            .iter()
+            .all(|arg| matches!(arg, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(&args.args)?;
+        let cells = arrays[0].as_primitive::<UInt64Type>();
+        let cells = cells.iter().map(|cell| cell.map(cell_index).transpose());
+
+        let result: ArrayRef = match self.function {
+            H3CellFunction::ToParent => {
+                let resolutions = arrays[1].as_primitive::<Int64Type>();
+                let parents = cells
+                    .zip(resolutions)
+                    .map(|(cell, resolution)| {
+                        let (Some(cell), Some(resolution)) = (cell?, resolution) else {
+                            return Ok(None);
+                        };
+                        let resolution = resolution_arg(self.name(), resolution)?;
+                        // A cell has no parent at a finer resolution
+                        Ok(cell.parent(resolution).map(u64::from))
+                    })
+                    .collect::<Result<UInt64Array>>()?;
+                Arc::new(parents)
+            }
+            H3CellFunction::ToString => {
+                let mut builder = StringBuilder::with_capacity(arrays[0].len(), 0);
+                for cell in cells {
+                    match cell? {
+                        Some(cell) => builder.append_value(cell.to_string()),
+                        None => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+        };
+        if all_scalars {
+            Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                &result, 0,
+            )?))
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+fn cell_index(cell: u64) -> Result<CellIndex> {
+    CellIndex::try_from(cell)
+        .map_err(|e| DataFusionError::Execution(format!("invalid H3 cell index {cell}: {e}")))
+}
@@ -0,0 +1,315 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Coordinate reference systems of geometry columns.
+//!
+//! The CRS of a geometry column is recorded the way GeoArrow records it: as the
+//! `crs` key of the JSON extension metadata of the field. Lance preserves field
+//! metadata, so the CRS travels with the column through writes and scans.
+
+use std::any::Any;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, BinaryBuilder};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    Volatility,
+};
+use datafusion::scalar::ScalarValue;
+use geo::{Coord, Geometry, MapCoords};
+use serde_json::{Map, Value};
+
+use crate::fallback::{parse_wkb, to_geometry, write_wkb};
+
+const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+const EXTENSION_METADATA_KEY: &str = "ARROW:extension:metadata";
+const WKB_EXTENSION_NAME: &str = "geoarrow.wkb";
+
+/// WGS 84 longitude / latitude.
+pub const WGS84: &str = "EPSG:4326";
+/// Spherical (web) mercator.
+pub const WEB_MERCATOR: &str = "EPSG:3857";
+
+/// The radius of the sphere used by web mercator.
+const EARTH_RADIUS: f64 = 6_378_137.0;
+/// The latitude at which web mercator maps the world to a square.
+const MAX_MERCATOR_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// The CRS recorded on a geometry field, normalized with [`normalize_crs`].
+///
+/// A CRS given as PROJJSON is identified by its `id`; PROJJSON without an `id`
+/// is returned as its JSON text.
+pub fn field_crs(field: &Field) -> Option<String> {
+    let metadata = field.metadata().get(EXTENSION_METADATA_KEY)?;
+    let metadata = serde_json::from_str::<Value>(metadata).ok()?;
+    match metadata.get("crs")? {
+        Value::String(crs) => Some(normalize_crs(crs)),
+        Value::Object(projjson) => match projjson.get("id") {
+            Some(Value::Object(id)) => {
+                let authority = id.get("authority")?.as_str()?;
+                let code = match id.get("code")? {
+                    Value::String(code) => code.clone(),
+                    code => code.to_string(),
+                };
+                Some(normalize_crs(&format!("{authority}:{code}")))
+            }
+            _ => Some(Value::Object(projjson.clone()).to_string()),
+        },
+        _ => None,
+    }
+}
+
+/// Record `crs` on a geometry field.
+///
+/// A binary field without a GeoArrow extension type is marked as `geoarrow.wkb`.
+pub fn with_crs(field: Field, crs: &str) -> Field {
+    let mut metadata = field.metadata().clone();
+    if !metadata.contains_key(EXTENSION_NAME_KEY)
+        && matches!(
+            field.data_type(),
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView
+        )
+    {
+        metadata.insert(
+            EXTENSION_NAME_KEY.to_string(),
+            WKB_EXTENSION_NAME.to_string(),
+        );
+    }
+    let mut extension_metadata = metadata
+        .get(EXTENSION_METADATA_KEY)
+        .and_then(|m| serde_json::from_str::<Map<String, Value>>(m).ok())
+        .unwrap_or_default();
+    extension_metadata.insert("crs".to_string(), Value::String(normalize_crs(crs)));
+    // The CRS is now an authority:code identifier
+    extension_metadata.remove("crs_type");
+    metadata.insert(
+        EXTENSION_METADATA_KEY.to_string(),
+        Value::Object(extension_metadata).to_string(),
+    );
+    field.with_metadata(metadata)
+}
+
+/// Normalize a CRS identifier, so `epsg:4326`, `4326` and `OGC:CRS84` all
+/// become `EPSG:4326`.
+pub fn normalize_crs(crs: &str) -> String {
+    let crs = crs.trim().to_uppercase();
+    match crs.as_str() {
+        "OGC:CRS84" | "CRS84" | "WGS84" => WGS84.to_string(),
+        srid if !srid.is_empty() && srid.chars().all(|c| c.is_ascii_digit()) => {
+            format!("EPSG:{srid}")
+        }
+        _ => crs,
+    }
+}
+
+/// The common CRS of the geometry arguments of a spatial function.
+///
+/// Geometries without a recorded CRS are assumed to be in the CRS of the other
+/// arguments. Comparing geometries in different CRS is an error, since the
+/// result would silently be wrong.
+pub fn common_crs<'a>(
+    function: &str,
+    fields: impl IntoIterator<Item = &'a Field>,
+) -> Result<Option<String>> {
+    let mut common: Option<String> = None;
+    for crs in fields.into_iter().filter_map(field_crs) {
+        match &common {
+            Some(common) if *common != crs => {
+                return Err(DataFusionError::Plan(format!(
+                    "{function} cannot compare geometries in different coordinate reference \
+                     systems ({common} and {crs}), use st_transform to reproject one of them"
+                )));
+            }
+            Some(_) => {}
+            None => common = Some(crs),
+        }
+    }
+    Ok(common)
+}
+
+/// Reproject a geometry from one CRS to another.
+///
+/// Only transforms between [`WGS84`] and [`WEB_MERCATOR`] are supported.
+pub fn transform(geometry: &Geometry, from: &str, to: &str) -> Result<Geometry> {
+    let transform = coord_transform(from, to)?;
+    Ok(geometry.map_coords(transform))
+}
+
+/// Check that a transform between two CRS is supported.
+pub fn check_transform(from: &str, to: &str) -> Result<()> {
+    coord_transform(from, to).map(|_| ())
+}
+
+fn coord_transform(from: &str, to: &str) -> Result<fn(Coord) -> Coord> {
+    let (from, to) = (normalize_crs(from), normalize_crs(to));
+    match (from.as_str(), to.as_str()) {
+        (from, to) if from == to => Ok(|coord| coord),
+        (WGS84, WEB_MERCATOR) => Ok(wgs84_to_web_mercator),
+        (WEB_MERCATOR, WGS84) => Ok(web_mercator_to_wgs84),
+        (from, to) => Err(DataFusionError::NotImplemented(format!(
+            "st_transform from {from} to {to} is not supported, only transforms between \
+             {WGS84} and {WEB_MERCATOR} are available"
+        ))),
+    }
+}
+
+fn wgs84_to_web_mercator(coord: Coord) -> Coord {
+    let latitude = coord.y.clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE);
+    Coord {
+        x: coord.x.to_radians() * EARTH_RADIUS,
+        y: (PI / 4.0 + latitude.to_radians() / 2.0).tan().ln() * EARTH_RADIUS,
+    }
+}
+
+fn web_mercator_to_wgs84(coord: Coord) -> Coord {
+    Coord {
+        x: (coord.x / EARTH_RADIUS).to_degrees(),
+        y: (2.0 * (coord.y / EARTH_RADIUS).exp().atan() - PI / 2.0).to_degrees(),
+    }
+}
+
+/// The `st_transform` function.
+///
+/// `st_transform(geom, to_crs)` reprojects WKB geometries from the CRS recorded
+/// on their column, and `st_transform(geom, from_crs, to_crs)` from an explicit
+/// CRS. The CRS arguments must be constants, either an SRID such as `3857` or an
+/// identifier such as `'EPSG:3857'`, and the target CRS is recorded on the
+/// result.
+pub fn st_transform_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(StTransform {
+        signature: Signature::user_defined(Volatility::Immutable),
+    })
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct StTransform {
+    signature: Signature,
+}
+
+impl StTransform {
+    /// The source and target CRS of a call.
+    fn source_and_target(
+        &self,
+        geometry: &Field,
+        crs_arguments: &[Option<&ScalarValue>],
+    ) -> Result<(String, String)> {
+        match crs_arguments {
+            [to] => {
+                let from = field_crs(geometry).ok_or_else(|| {
+                    DataFusionError::Plan(
+                        "st_transform needs the CRS of the geometries, record it on the column \
+                         or pass it with st_transform(geom, from_crs, to_crs)"
+                            .to_string(),
+                    )
+                })?;
+                Ok((from, crs_argument(*to)?))
+            }
+            [from, to] => Ok((crs_argument(*from)?, crs_argument(*to)?)),
+            _ => Err(DataFusionError::Plan(
+                "st_transform expects 2 or 3 arguments".to_string(),
+            )),
+        }
+    }
+}
+
+/// A constant CRS argument.
+fn crs_argument(value: Option<&ScalarValue>) -> Result<String> {
+    let crs = match value {
+        Some(
+            ScalarValue::Utf8(Some(crs))
+            | ScalarValue::LargeUtf8(Some(crs))
+            | ScalarValue::Utf8View(Some(crs)),
+        ) => crs.clone(),
+        Some(value) if value.data_type().is_integer() && !value.is_null() => value.to_string(),
+        _ => {
+            return Err(DataFusionError::Plan(
+                "st_transform requires constant CRS arguments".to_string(),
+            ));
+        }
+    };
+    Ok(normalize_crs(&crs))
+}
+
+impl ScalarUDFImpl for StTransform {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "st_transform"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let valid = matches!(arg_types.len(), 2 | 3)
+            && matches!(
+                arg_types[0],
+                DataType::Binary | DataType::LargeBinary | DataType::BinaryView
+            )
+            && arg_types[1..].iter().all(|data_type| {
+                data_type.is_integer()
+                    || matches!(
+                        data_type,
+                        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+                    )
+            });
+        if !valid {
+            return Err(DataFusionError::Plan(format!(
+                "st_transform expects a WKB geometry followed by one or two CRS, got {arg_types:?}"
+            )));
+        }
+        Ok(arg_types.to_vec())
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        let (from, to) =
+            self.source_and_target(&args.arg_fields[0], &args.scalar_arguments[1..])?;
+        check_transform(&from, &to)?;
+        Ok(Arc::new(with_crs(
+            Field::new(self.name(), DataType::Binary, true),
+            &to,
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let crs_arguments = args.args[1..]
+            .iter()
+            .map(|arg| match arg {
+                ColumnarValue::Scalar(value) => Some(value),
+                ColumnarValue::Array(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let (from, to) = self.source_and_target(&args.arg_fields[0], &crs_arguments)?;
+
+        let geometries = args.args[0].to_array(args.number_rows)?;
+        let geometries = cast(&geometries, &DataType::Binary)?;
+        let mut builder = BinaryBuilder::with_capacity(geometries.len(), 0);
+        for value in geometries.as_binary::<i32>() {
+            match value {
+                Some(bytes) => {
+                    let geometry = to_geometry(&parse_wkb(bytes)?);
+                    builder.append_value(write_wkb(&transform(&geometry, &from, &to)?)?);
+                }
+                None => builder.append_null(),
+            }
+        }
+        let result: ArrayRef = Arc::new(builder.finish());
+        match &args.args[0] {
+            ColumnarValue::Scalar(_) => Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                &result, 0,
+            )?)),
+            ColumnarValue::Array(_) => Ok(ColumnarValue::Array(result)),
+        }
+    }
+}
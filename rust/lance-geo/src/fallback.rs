@@ -0,0 +1,409 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Spatial SQL functions that are available without the `geo` feature.
+//!
+//! The `geo` feature registers the full GeoArrow based function library from
+//! geodatafusion. Without it, the functions here provide a core set of ST_*
+//! predicates, measurements and accessors that operate on geometries stored as
+//! WKB in binary columns, so that spatial SQL still resolves and evaluates.
+//!
+//! Functions of several geometries check that the geometries share a
+//! coordinate reference system, see [`crate::crs`].
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, BinaryBuilder, BooleanBuilder, Float64Builder, StringBuilder,
+};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, FieldRef, Float64Type};
+use datafusion::common::types::{NativeType, logical_float64, logical_string};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::{
+    Coercion, ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl,
+    Signature, TypeSignatureClass, Volatility,
+};
+use datafusion::prelude::SessionContext;
+use datafusion::scalar::ScalarValue;
+use geo::relate::IntersectionMatrix;
+use geo::{Area, Distance, Euclidean, Geometry, Length, Relate};
+use geo_traits::to_geo::ToGeoGeometry;
+use geo_traits::{CoordTrait, GeometryTrait, GeometryType, PointTrait};
+use wkb::reader::{Wkb, read_wkb};
+use wkt::{ToWkt, TryFromWkt};
+
+use crate::crs::{common_crs, st_transform_udf};
+
+type Predicate = fn(&IntersectionMatrix) -> bool;
+
+/// Spatial relationship predicates, evaluated with the DE-9IM relate matrix.
+const PREDICATES: [(&str, Predicate); 10] = [
+    ("st_intersects", |m| m.is_intersects()),
+    ("st_disjoint", |m| m.is_disjoint()),
+    ("st_contains", |m| m.is_contains()),
+    ("st_within", |m| m.is_within()),
+    ("st_covers", |m| m.is_covers()),
+    ("st_coveredby", |m| m.is_coveredby()),
+    ("st_touches", |m| m.is_touches()),
+    ("st_crosses", |m| m.is_crosses()),
+    ("st_overlaps", |m| m.is_overlaps()),
+    ("st_equals", |m| m.is_equal_topo()),
+];
+
+/// Register the fallback spatial functions to a datafusion context.
+pub fn register_functions(ctx: &SessionContext) {
+    for udf in fallback_udfs() {
+        ctx.register_udf(udf);
+    }
+}
+
+/// All fallback spatial functions.
+pub fn fallback_udfs() -> Vec<ScalarUDF> {
+    let mut udfs = PREDICATES
+        .iter()
+        .map(|(name, predicate)| {
+            let predicate = *predicate;
+            SpatialUdf::new(
+                name,
+                vec![geometry(), geometry()],
+                DataType::Boolean,
+                move |args| binary_predicate(args, predicate),
+            )
+        })
+        .collect::<Vec<_>>();
+    udfs.extend([
+        SpatialUdf::new(
+            "st_distance",
+            vec![geometry(), geometry()],
+            DataType::Float64,
+            distance,
+        ),
+        SpatialUdf::new("st_area", vec![geometry()], DataType::Float64, |args| {
+            measure(args, |geom| geom.unsigned_area())
+        }),
+        SpatialUdf::new("st_length", vec![geometry()], DataType::Float64, |args| {
+            measure(args, length)
+        }),
+        SpatialUdf::new("st_x", vec![geometry()], DataType::Float64, |args| {
+            point_coordinate(args, |x, _| x)
+        }),
+        SpatialUdf::new("st_y", vec![geometry()], DataType::Float64, |args| {
+            point_coordinate(args, |_, y| y)
+        }),
+        SpatialUdf::new(
+            "st_geometrytype",
+            vec![geometry()],
+            DataType::Utf8,
+            geometry_type,
+        ),
+        SpatialUdf::new("st_astext", vec![geometry()], DataType::Utf8, as_text),
+        SpatialUdf::new(
+            "st_geomfromtext",
+            vec![Coercion::new_exact(TypeSignatureClass::Native(
+                logical_string(),
+            ))],
+            DataType::Binary,
+            geom_from_text,
+        ),
+        SpatialUdf::new(
+            "st_point",
+            vec![coordinate(), coordinate()],
+            DataType::Binary,
+            point,
+        ),
+    ]);
+    udfs.into_iter()
+        .map(ScalarUDF::new_from_impl)
+        .chain([st_transform_udf()])
+        .collect()
+}
+
+/// A WKB geometry argument, accepting any binary type.
+fn geometry() -> Coercion {
+    Coercion::new_exact(TypeSignatureClass::Binary)
+}
+
+/// A coordinate argument, accepting any numeric type.
+fn coordinate() -> Coercion {
+    Coercion::new_implicit(
+        TypeSignatureClass::Native(logical_float64()),
+        vec![TypeSignatureClass::Numeric],
+        NativeType::Float64,
+    )
+}
+
+type SpatialFn = dyn Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync;
+
+/// A scalar UDF evaluated one array at a time. Scalar arguments are expanded
+/// to arrays and the result is collapsed back to a scalar when all arguments
+/// are scalars.
+struct SpatialUdf {
+    name: String,
+    signature: Signature,
+    return_type: DataType,
+    func: Arc<SpatialFn>,
+}
+
+impl SpatialUdf {
+    fn new(
+        name: &str,
+        args: Vec<Coercion>,
+        return_type: DataType,
+        func: impl Fn(&[ArrayRef]) -> Result<ArrayRef> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            signature: Signature::coercible(args, Volatility::Immutable),
+            return_type,
+            func: Arc::new(func),
+        }
+    }
+}
+
+impl std::fmt::Debug for SpatialUdf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpatialUdf")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+impl PartialEq for SpatialUdf {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.signature == other.signature
+            && self.return_type == other.return_type
+    }
+}
+
+impl Eq for SpatialUdf {}
+
+impl std::hash::Hash for SpatialUdf {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.signature.hash(state);
+        self.return_type.hash(state);
+    }
+}
+
+impl ScalarUDFImpl for SpatialUdf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.return_type.clone())
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        common_crs(&self.name, args.arg_fields.iter().map(|f| f.as_ref()))?;
+        Ok(Arc::new(Field::new(
+            &self.name,
+            self.return_type.clone(),
+            true,
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let all_scalars = args
+            .args
+            .iter()
+            .all(|arg| matches!(arg, ColumnarValue::Scalar(_)));
+        let arrays = ColumnarValue::values_to_arrays(&args.args)?;
+        let result = (self.func)(&arrays)?;
+        if all_scalars {
+            Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                &result, 0,
+            )?))
+        } else {
+            Ok(ColumnarValue::Array(result))
+        }
+    }
+}
+
+/// Iterate the WKB values of a binary array.
+fn for_each_wkb(array: &ArrayRef, mut f: impl FnMut(Option<&Wkb<'_>>) -> Result<()>) -> Result<()> {
+    let array = cast(array, &DataType::Binary)?;
+    for value in array.as_binary::<i32>() {
+        match value {
+            Some(bytes) => f(Some(&parse_wkb(bytes)?))?,
+            None => f(None)?,
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_wkb(bytes: &[u8]) -> Result<Wkb<'_>> {
+    read_wkb(bytes).map_err(|e| DataFusionError::Execution(format!("Invalid WKB geometry: {e}")))
+}
+
+/// Convert to a geo geometry. geo has no empty points, so empty geometries
+/// are represented as an empty collection.
+pub(crate) fn to_geometry(wkb: &Wkb<'_>) -> Geometry {
+    wkb.try_to_geometry()
+        .unwrap_or_else(|| Geometry::GeometryCollection(Default::default()))
+}
+
+fn binary_predicate(args: &[ArrayRef], predicate: Predicate) -> Result<ArrayRef> {
+    let mut left = Vec::with_capacity(args[0].len());
+    for_each_wkb(&args[0], |wkb| {
+        left.push(wkb.map(to_geometry));
+        Ok(())
+    })?;
+    let mut builder = BooleanBuilder::with_capacity(left.len());
+    let mut left = left.into_iter();
+    for_each_wkb(&args[1], |right| {
+        match (left.next().flatten(), right) {
+            (Some(left), Some(right)) => {
+                builder.append_value(predicate(&left.relate(&to_geometry(right))))
+            }
+            _ => builder.append_null(),
+        }
+        Ok(())
+    })?;
+    Ok(Arc::new(builder.finish()))
+}
+
+fn distance(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let mut left = Vec::with_capacity(args[0].len());
+    for_each_wkb(&args[0], |wkb| {
+        left.push(wkb.and_then(|wkb| wkb.try_to_geometry()));
+        Ok(())
+    })?;
+    let mut builder = Float64Builder::with_capacity(left.len());
+    let mut left = left.into_iter();
+    for_each_wkb(&args[1], |right| {
+        // The distance to an empty geometry is undefined
+        match (
+            left.next().flatten(),
+            right.and_then(|r| r.try_to_geometry()),
+        ) {
+            (Some(left), Some(right)) => builder.append_value(Euclidean.distance(&left, &right)),
+            _ => builder.append_null(),
+        }
+        Ok(())
+    })?;
+    Ok(Arc::new(builder.finish()))
+}
+
+fn measure(args: &[ArrayRef], f: impl Fn(&Geometry) -> f64) -> Result<ArrayRef> {
+    let mut builder = Float64Builder::with_capacity(args[0].len());
+    for_each_wkb(&args[0], |wkb| {
+        builder.append_option(wkb.map(|wkb| f(&to_geometry(wkb))));
+        Ok(())
+    })?;
+    Ok(Arc::new(builder.finish()))
+}
+
+/// The length of linear geometries, areal geometries have no length.
+fn length(geom: &Geometry) -> f64 {
+    match geom {
+        Geometry::Line(line) => Euclidean.length(line),
+        Geometry::LineString(line_string) => Euclidean.length(line_string),
+        Geometry::MultiLineString(multi_line_string) => Euclidean.length(multi_line_string),
+        Geometry::GeometryCollection(collection) => collection.iter().map(length).sum(),
+        _ => 0.0,
+    }
+}
+
+fn point_coordinate(args: &[ArrayRef], f: fn(f64, f64) -> f64) -> Result<ArrayRef> {
+    let mut builder = Float64Builder::with_capacity(args[0].len());
+    for_each_wkb(&args[0], |wkb| {
+        match wkb.map(|wkb| wkb.as_type()) {
+            None => builder.append_null(),
+            // Empty points have no coordinates
+            Some(GeometryType::Point(point)) => {
+                builder.append_option(point.coord().map(|c| f(c.x(), c.y())))
+            }
+            Some(_) => {
+                return Err(DataFusionError::Execution(
+                    "ST_X and ST_Y require a point geometry".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    })?;
+    Ok(Arc::new(builder.finish()))
+}
+
+fn geometry_type(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let mut builder = StringBuilder::with_capacity(args[0].len(), args[0].len() * 10);
+    for_each_wkb(&args[0], |wkb| {
+        builder.append_option(wkb.map(|wkb| match wkb.as_type() {
+            GeometryType::Point(_) => "ST_Point",
+            GeometryType::LineString(_) => "ST_LineString",
+            GeometryType::Polygon(_) => "ST_Polygon",
+            GeometryType::MultiPoint(_) => "ST_MultiPoint",
+            GeometryType::MultiLineString(_) => "ST_MultiLineString",
+            GeometryType::MultiPolygon(_) => "ST_MultiPolygon",
+            GeometryType::GeometryCollection(_) => "ST_GeometryCollection",
+            GeometryType::Rect(_) => "ST_Rect",
+            GeometryType::Triangle(_) => "ST_Triangle",
+            GeometryType::Line(_) => "ST_Line",
+        }));
+        Ok(())
+    })?;
+    Ok(Arc::new(builder.finish()))
+}
+
+fn as_text(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let mut builder = StringBuilder::with_capacity(args[0].len(), 0);
+    for_each_wkb(&args[0], |wkb| {
+        builder.append_option(wkb.map(|wkb| to_geometry(wkb).wkt_string()));
+        Ok(())
+    })?;
+    Ok(Arc::new(builder.finish()))
+}
+
+fn geom_from_text(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let array = cast(&args[0], &DataType::Utf8)?;
+    let mut builder = BinaryBuilder::with_capacity(array.len(), 0);
+    for value in array.as_string::<i32>() {
+        match value {
+            Some(text) => {
+                let geom = Geometry::<f64>::try_from_wkt_str(text).map_err(|e| {
+                    DataFusionError::Execution(format!("Invalid WKT geometry '{text}': {e}"))
+                })?;
+                builder.append_value(write_wkb(&geom)?);
+            }
+            None => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+fn point(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let xs = cast(&args[0], &DataType::Float64)?;
+    let ys = cast(&args[1], &DataType::Float64)?;
+    let mut builder = BinaryBuilder::with_capacity(xs.len(), xs.len() * 21);
+    for (x, y) in xs
+        .as_primitive::<Float64Type>()
+        .iter()
+        .zip(ys.as_primitive::<Float64Type>())
+    {
+        match (x, y) {
+            (Some(x), Some(y)) => builder.append_value(write_wkb(&geo::Point::new(x, y))?),
+            _ => builder.append_null(),
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+pub(crate) fn write_wkb(geom: &impl GeometryTrait<T = f64>) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    wkb::writer::write_geometry(&mut buf, geom, &Default::default())
+        .map_err(|e| DataFusionError::Execution(format!("Failed to write WKB geometry: {e}")))?;
+    Ok(buf)
+}
@@ -0,0 +1,502 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Spatial join planning.
+//!
+//! DataFusion plans a join whose only condition is a spatial predicate, such as
+//! `ON st_contains(zones.geom, points.geom)`, as a nested loop join that evaluates
+//! the predicate for every pair of rows. [`SpatialJoinRule`] replaces those joins
+//! with a [`SpatialJoinExec`], an index nested loop join that indexes the bounding
+//! boxes of the build side in an R-tree and only evaluates the join filter for
+//! pairs whose bounding boxes intersect.
+
+use std::any::Any;
+use std::fmt::Formatter;
+use std::sync::{Arc, Mutex};
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, RecordBatch, RecordBatchOptions, UInt32Builder, UInt64Builder,
+};
+use datafusion::arrow::compute::{cast, concat_batches, filter, take};
+use datafusion::arrow::datatypes::{DataType, Field, FieldRef, SchemaRef};
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{JoinSide, JoinType};
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::{
+    EquivalenceProperties, Partitioning, PhysicalExpr, ScalarFunctionExpr, split_conjunction,
+};
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::joins::NestedLoopJoinExec;
+use datafusion::physical_plan::joins::utils::{ColumnIndex, JoinFilter, build_join_schema};
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, ExecutionPlanProperties,
+    PlanProperties, SendableRecordBatchStream, collect,
+};
+use futures::future::{BoxFuture, Shared};
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use geo::BoundingRect;
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::{AABB, RTree};
+
+use crate::fallback::{parse_wkb, to_geometry};
+
+/// Spatial predicates that can only be true when the bounding boxes of both
+/// geometries intersect.
+const SPATIAL_JOIN_PREDICATES: [&str; 9] = [
+    "st_intersects",
+    "st_contains",
+    "st_within",
+    "st_covers",
+    "st_coveredby",
+    "st_touches",
+    "st_crosses",
+    "st_overlaps",
+    "st_equals",
+];
+
+/// Physical optimizer rule that rewrites inner nested loop joins on a spatial
+/// predicate into a [`SpatialJoinExec`].
+///
+/// The rule fires when one conjunct of the join filter is a spatial predicate
+/// whose arguments are a geometry column from each side of the join. The whole
+/// join filter is still evaluated on every candidate pair.
+#[derive(Debug, Default)]
+pub struct SpatialJoinRule;
+
+impl PhysicalOptimizerRule for SpatialJoinRule {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(plan
+            .transform_up(|plan| {
+                let Some(join) = plan.as_any().downcast_ref::<NestedLoopJoinExec>() else {
+                    return Ok(Transformed::no(plan));
+                };
+                match try_rewrite(join)? {
+                    Some(rewritten) => Ok(Transformed::yes(rewritten)),
+                    None => Ok(Transformed::no(plan)),
+                }
+            })?
+            .data)
+    }
+
+    fn name(&self) -> &str {
+        "spatial_join"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+fn try_rewrite(join: &NestedLoopJoinExec) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if *join.join_type() != JoinType::Inner {
+        return Ok(None);
+    }
+    let Some(join_filter) = join.filter() else {
+        return Ok(None);
+    };
+    let Some((left_geometry, right_geometry)) = find_spatial_predicate(join_filter) else {
+        return Ok(None);
+    };
+    let left_schema = join.left().schema();
+    let right_schema = join.right().schema();
+    if !supports_bounds(left_schema.field(left_geometry))
+        || !supports_bounds(right_schema.field(right_geometry))
+    {
+        return Ok(None);
+    }
+
+    let projection = join.projection().as_ref().map(|p| p.to_vec());
+    Ok(Some(Arc::new(SpatialJoinExec::try_new(
+        join.left().clone(),
+        join.right().clone(),
+        join_filter.clone(),
+        left_geometry,
+        right_geometry,
+        projection,
+    )?)))
+}
+
+/// Find a spatial predicate in the join filter comparing a left column with a
+/// right column, returning the indices of the geometry columns in the left and
+/// right inputs.
+fn find_spatial_predicate(join_filter: &JoinFilter) -> Option<(usize, usize)> {
+    let column_index = |expr: &Arc<dyn PhysicalExpr>| -> Option<&ColumnIndex> {
+        let column = expr.as_any().downcast_ref::<Column>()?;
+        join_filter.column_indices().get(column.index())
+    };
+    split_conjunction(join_filter.expression())
+        .into_iter()
+        .find_map(|expr| {
+            let func = expr.as_any().downcast_ref::<ScalarFunctionExpr>()?;
+            if !SPATIAL_JOIN_PREDICATES.contains(&func.name().to_lowercase().as_str()) {
+                return None;
+            }
+            let [first, second] = func.args() else {
+                return None;
+            };
+            let (first, second) = (column_index(first)?, column_index(second)?);
+            match (first.side, second.side) {
+                (JoinSide::Left, JoinSide::Right) => Some((first.index, second.index)),
+                (JoinSide::Right, JoinSide::Left) => Some((second.index, first.index)),
+                _ => None,
+            }
+        })
+}
+
+type Envelope = GeomWithData<Rectangle<[f64; 2]>, usize>;
+
+/// The indexed build side of a spatial join.
+#[derive(Debug)]
+struct BuildSide {
+    batch: RecordBatch,
+    index: RTree<Envelope>,
+}
+
+type SharedBuildSide =
+    Shared<BoxFuture<'static, std::result::Result<Arc<BuildSide>, Arc<DataFusionError>>>>;
+
+/// Inner join on a spatial predicate.
+///
+/// The left input is collected and the bounding box of each geometry is indexed
+/// in an R-tree. Each batch of the right input probes the index with the
+/// bounding boxes of its geometries, and the join filter is evaluated on the
+/// candidate pairs.
+#[derive(Debug)]
+pub struct SpatialJoinExec {
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    left_geometry: usize,
+    probe: Arc<Probe>,
+    properties: Arc<PlanProperties>,
+    build_side: Mutex<Option<SharedBuildSide>>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+/// Joins batches of the right input with the build side.
+#[derive(Debug)]
+struct Probe {
+    join_filter: JoinFilter,
+    geometry: usize,
+    geometry_field: FieldRef,
+    projection: Option<Vec<usize>>,
+    schema: SchemaRef,
+}
+
+impl SpatialJoinExec {
+    /// Create a spatial join.
+    ///
+    /// `left_geometry` and `right_geometry` are the indices of the geometry
+    /// columns, in the schemas of `left` and `right`, that the spatial predicate
+    /// in `join_filter` compares.
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        join_filter: JoinFilter,
+        left_geometry: usize,
+        right_geometry: usize,
+        projection: Option<Vec<usize>>,
+    ) -> Result<Self> {
+        let (join_schema, _) = build_join_schema(&left.schema(), &right.schema(), &JoinType::Inner);
+        let schema = match &projection {
+            Some(projection) => Arc::new(join_schema.project(projection)?),
+            None => Arc::new(join_schema),
+        };
+        let properties = Arc::new(PlanProperties::new(
+            EquivalenceProperties::new(schema.clone()),
+            Partitioning::UnknownPartitioning(right.output_partitioning().partition_count()),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        ));
+        let probe = Arc::new(Probe {
+            join_filter,
+            geometry: right_geometry,
+            geometry_field: right.schema().fields()[right_geometry].clone(),
+            projection,
+            schema,
+        });
+        Ok(Self {
+            left,
+            right,
+            left_geometry,
+            probe,
+            properties,
+            build_side: Mutex::new(None),
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    pub fn join_filter(&self) -> &JoinFilter {
+        &self.probe.join_filter
+    }
+
+    /// Collect and index the left input once, sharing it between partitions.
+    fn build_side(&self, context: Arc<TaskContext>) -> SharedBuildSide {
+        let mut build_side = self.build_side.lock().unwrap();
+        build_side
+            .get_or_insert_with(|| {
+                let left = self.left.clone();
+                let left_geometry = self.left_geometry;
+                async move {
+                    let schema = left.schema();
+                    let batches = collect(left, context).await?;
+                    let batch = concat_batches(&schema, &batches)?;
+                    let bounds =
+                        geometry_bounds(batch.column(left_geometry), schema.field(left_geometry))?;
+                    let envelopes = bounds
+                        .into_iter()
+                        .enumerate()
+                        .filter_map(|(row, bounds)| {
+                            bounds.map(|b| GeomWithData::new(Rectangle::from_aabb(b), row))
+                        })
+                        .collect();
+                    Ok(Arc::new(BuildSide {
+                        batch,
+                        index: RTree::bulk_load(envelopes),
+                    }))
+                }
+                .map(|result: Result<_>| result.map_err(Arc::new))
+                .boxed()
+                .shared()
+            })
+            .clone()
+    }
+}
+
+impl Probe {
+    fn probe(&self, build_side: &BuildSide, right: &RecordBatch) -> Result<RecordBatch> {
+        let bounds = geometry_bounds(right.column(self.geometry), &self.geometry_field)?;
+        let mut left_indices = UInt64Builder::new();
+        let mut right_indices = UInt32Builder::new();
+        for (row, bounds) in bounds.into_iter().enumerate() {
+            let Some(bounds) = bounds else {
+                continue;
+            };
+            for candidate in build_side.index.locate_in_envelope_intersecting(&bounds) {
+                left_indices.append_value(candidate.data as u64);
+                right_indices.append_value(row as u32);
+            }
+        }
+        let left_indices = left_indices.finish();
+        let right_indices = right_indices.finish();
+
+        let take_columns = |column_indices: &mut dyn Iterator<Item = &ColumnIndex>,
+                            left_indices: &dyn Array,
+                            right_indices: &dyn Array|
+         -> Result<Vec<ArrayRef>> {
+            column_indices
+                .map(|column| match column.side {
+                    JoinSide::Left => Ok(take(
+                        build_side.batch.column(column.index),
+                        left_indices,
+                        None,
+                    )?),
+                    JoinSide::Right => Ok(take(right.column(column.index), right_indices, None)?),
+                    JoinSide::None => Err(DataFusionError::Internal(
+                        "Spatial join filter column must come from one side of the join"
+                            .to_string(),
+                    )),
+                })
+                .collect()
+        };
+
+        // Evaluate the join filter on the candidate pairs
+        let filter_batch = RecordBatch::try_new_with_options(
+            self.join_filter.schema().clone(),
+            take_columns(
+                &mut self.join_filter.column_indices().iter(),
+                &left_indices,
+                &right_indices,
+            )?,
+            &RecordBatchOptions::new().with_row_count(Some(left_indices.len())),
+        )?;
+        let mask = self
+            .join_filter
+            .expression()
+            .evaluate(&filter_batch)?
+            .into_array(filter_batch.num_rows())?;
+        let left_indices = filter(&left_indices, mask.as_boolean())?;
+        let right_indices = filter(&right_indices, mask.as_boolean())?;
+
+        let (_, join_columns) = build_join_schema(
+            &build_side.batch.schema(),
+            &right.schema(),
+            &JoinType::Inner,
+        );
+        let columns = match &self.projection {
+            Some(projection) => take_columns(
+                &mut projection.iter().map(|i| &join_columns[*i]),
+                &left_indices,
+                &right_indices,
+            )?,
+            None => take_columns(&mut join_columns.iter(), &left_indices, &right_indices)?,
+        };
+        Ok(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &RecordBatchOptions::new().with_row_count(Some(left_indices.len())),
+        )?)
+    }
+}
+
+impl DisplayAs for SpatialJoinExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "SpatialJoinExec: filter={}", self.probe.join_filter)
+            }
+            DisplayFormatType::TreeRender => write!(f, "filter={}", self.probe.join_filter),
+        }
+    }
+}
+
+impl ExecutionPlan for SpatialJoinExec {
+    fn name(&self) -> &str {
+        "SpatialJoinExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.probe.schema.clone()
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.properties
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![
+            Distribution::SinglePartition,
+            Distribution::UnspecifiedDistribution,
+        ]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.left, &self.right]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let [left, right] = children.try_into().map_err(|_| {
+            DataFusionError::Internal("SpatialJoinExec requires exactly two children".to_string())
+        })?;
+        Ok(Arc::new(Self::try_new(
+            left,
+            right,
+            self.probe.join_filter.clone(),
+            self.left_geometry,
+            self.probe.geometry,
+            self.probe.projection.clone(),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let build_side = self.build_side(context.clone());
+        let right = self.right.execute(partition, context)?;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let probe = self.probe.clone();
+        let stream = futures::stream::once(async move {
+            let build_side = build_side.await.map_err(DataFusionError::Shared)?;
+            Ok::<_, DataFusionError>(right.map(move |batch| {
+                let _timer = baseline_metrics.elapsed_compute().timer();
+                let batch = probe.probe(&build_side, &batch?)?;
+                baseline_metrics.record_output(batch.num_rows());
+                Ok(batch)
+            }))
+        })
+        .try_flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.probe.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+/// Whether [`geometry_bounds`] supports geometries stored in this field.
+fn supports_bounds(field: &Field) -> bool {
+    #[cfg(feature = "geo")]
+    if geoarrow_schema::GeoArrowType::try_from(field).is_ok() {
+        return true;
+    }
+    matches!(
+        field.data_type(),
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView
+    )
+}
+
+/// The bounding box of each geometry. Null and empty geometries have no bounds.
+pub(crate) fn geometry_bounds(
+    array: &ArrayRef,
+    field: &Field,
+) -> Result<Vec<Option<AABB<[f64; 2]>>>> {
+    #[cfg(feature = "geo")]
+    if geoarrow_schema::GeoArrowType::try_from(field).is_ok() {
+        return geoarrow_bounds(array, field);
+    }
+    let _ = field;
+    let array = cast(array, &DataType::Binary)?;
+    array
+        .as_binary::<i32>()
+        .iter()
+        .map(|value| {
+            let Some(value) = value else {
+                return Ok(None);
+            };
+            Ok(to_geometry(&parse_wkb(value)?)
+                .bounding_rect()
+                .map(|rect| AABB::from_corners(rect.min().x_y().into(), rect.max().x_y().into())))
+        })
+        .collect()
+}
+
+#[cfg(feature = "geo")]
+fn geoarrow_bounds(array: &ArrayRef, field: &Field) -> Result<Vec<Option<AABB<[f64; 2]>>>> {
+    use geo_traits::{CoordTrait, RectTrait};
+    use geoarrow_array::GeoArrowArrayAccessor;
+
+    let array = geoarrow_array::array::from_arrow_array(array, field)
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    let rects = crate::bbox::bounding_box(array.as_ref())?;
+    rects
+        .iter()
+        .map(|rect| {
+            let Some(rect) = rect
+                .transpose()
+                .map_err(|e| DataFusionError::External(Box::new(e)))?
+            else {
+                return Ok(None);
+            };
+            let (min, max) = (rect.min(), rect.max());
+            // Empty geometries have an inverted bounding box
+            if min.x() > max.x() || min.y() > max.y() {
+                return Ok(None);
+            }
+            Ok(Some(AABB::from_corners(
+                [min.x(), min.y()],
+                [max.x(), max.y()],
+            )))
+        })
+        .collect()
+}
@@ -5,10 +5,21 @@ use datafusion::prelude::SessionContext;
 
 #[cfg(feature = "geo")]
 pub mod bbox;
+pub mod cell;
+pub mod crs;
+pub mod fallback;
+pub mod join;
 
 pub fn register_functions(ctx: &SessionContext) {
     #[cfg(feature = "geo")]
-    geodatafusion::register(ctx);
+    {
+        geodatafusion::register(ctx);
+        // geodatafusion has no reprojection function
+        ctx.register_udf(crs::st_transform_udf());
+    }
     #[cfg(not(feature = "geo"))]
-    let _ = ctx;
+    fallback::register_functions(ctx);
+    for udf in cell::cell_udfs() {
+        ctx.register_udf(udf);
+    }
 }
@@ -17,6 +17,8 @@ use arrow_array::{
     types::{Float32Type, UInt64Type},
 };
 use arrow_schema::{DataType, SchemaRef};
+use half::bf16;
+use lance_arrow::FloatArray;
 use lance_core::deepsize::DeepSizeOf;
 use lance_core::{Error, ROW_ID, Result};
 use lance_file::previous::reader::FileReader as PreviousFileReader;
@@ -454,10 +456,79 @@ impl<T: ArrowPrimitiveType> DistCalculator for FlatDistanceCal<'_, T> {
     }
 }
 
+/// Distance calculator over bfloat16 vectors.
+///
+/// Queries against bfloat16 columns are given in f32, so they are converted to bfloat16 once.
+pub struct FlatBFloat16DistanceCal<'a> {
+    vectors: &'a [bf16],
+    query: Cow<'a, [bf16]>,
+    dimension: usize,
+    distance_fn: fn(&[bf16], &[bf16]) -> f32,
+}
+
+impl<'a> FlatBFloat16DistanceCal<'a> {
+    fn new(vectors: &'a FixedSizeListArray, query: ArrayRef, distance_type: DistanceType) -> Self {
+        let query = match query.data_type() {
+            DataType::Float32 => query
+                .as_primitive::<Float32Type>()
+                .values()
+                .iter()
+                .map(|v| bf16::from_f32(*v))
+                .collect(),
+            _ => query.as_fixed_size_binary().as_slice().to_vec(),
+        };
+        Self {
+            vectors: vectors.values().as_fixed_size_binary().as_slice(),
+            query: Cow::Owned(query),
+            dimension: vectors.value_length() as usize,
+            distance_fn: distance_type.func(),
+        }
+    }
+
+    fn new_from_id(vectors: &'a FixedSizeListArray, id: u32, distance_type: DistanceType) -> Self {
+        let dimension = vectors.value_length() as usize;
+        let vectors = vectors.values().as_fixed_size_binary().as_slice();
+        let id = id as usize;
+        Self {
+            vectors,
+            query: Cow::Borrowed(&vectors[dimension * id..dimension * (id + 1)]),
+            dimension,
+            distance_fn: distance_type.func(),
+        }
+    }
+
+    #[inline]
+    fn get_vector(&self, id: u32) -> &[bf16] {
+        &self.vectors[self.dimension * id as usize..self.dimension * (id + 1) as usize]
+    }
+}
+
+impl DistCalculator for FlatBFloat16DistanceCal<'_> {
+    #[inline]
+    fn distance(&self, id: u32) -> f32 {
+        (self.distance_fn)(self.query.as_ref(), self.get_vector(id))
+    }
+
+    fn distance_all(&self, _k_hint: usize) -> Vec<f32> {
+        let query = self.query.as_ref();
+        self.vectors
+            .chunks_exact(self.dimension)
+            .map(|vector| (self.distance_fn)(query, vector))
+            .collect()
+    }
+
+    #[inline]
+    fn prefetch(&self, id: u32) {
+        let vector = self.get_vector(id);
+        do_prefetch(vector.as_ptr_range())
+    }
+}
+
 pub enum FlatFloatDistanceCalc<'a> {
     Float16(FlatDistanceCal<'a, Float16Type>),
     Float32(FlatDistanceCal<'a, Float32Type>),
     Float64(FlatDistanceCal<'a, Float64Type>),
+    BFloat16(FlatBFloat16DistanceCal<'a>),
 }
 
 impl<'a> FlatFloatDistanceCalc<'a> {
@@ -478,6 +549,9 @@ impl<'a> FlatFloatDistanceCalc<'a> {
                 query,
                 distance_type,
             )),
+            DataType::FixedSizeBinary(2) => {
+                Self::BFloat16(FlatBFloat16DistanceCal::new(vectors, query, distance_type))
+            }
             dt => panic!("flat float storage does not support data type {dt}"),
         }
     }
@@ -499,6 +573,11 @@ impl<'a> FlatFloatDistanceCalc<'a> {
                 id,
                 distance_type,
             )),
+            DataType::FixedSizeBinary(2) => Self::BFloat16(FlatBFloat16DistanceCal::new_from_id(
+                vectors,
+                id,
+                distance_type,
+            )),
             dt => panic!("flat float storage does not support data type {dt}"),
         }
     }
@@ -510,6 +589,7 @@ impl DistCalculator for FlatFloatDistanceCalc<'_> {
             Self::Float16(calc) => calc.distance(id),
             Self::Float32(calc) => calc.distance(id),
             Self::Float64(calc) => calc.distance(id),
+            Self::BFloat16(calc) => calc.distance(id),
         }
     }
 
@@ -518,6 +598,7 @@ impl DistCalculator for FlatFloatDistanceCalc<'_> {
             Self::Float16(calc) => calc.distance_all(k_hint),
             Self::Float32(calc) => calc.distance_all(k_hint),
             Self::Float64(calc) => calc.distance_all(k_hint),
+            Self::BFloat16(calc) => calc.distance_all(k_hint),
         }
     }
 
@@ -526,6 +607,7 @@ impl DistCalculator for FlatFloatDistanceCalc<'_> {
             Self::Float16(calc) => calc.prefetch(id),
             Self::Float32(calc) => calc.prefetch(id),
             Self::Float64(calc) => calc.prefetch(id),
+            Self::BFloat16(calc) => calc.prefetch(id),
         }
     }
 }
@@ -534,9 +616,10 @@ impl DistCalculator for FlatFloatDistanceCalc<'_> {
 mod tests {
     use super::*;
 
-    use arrow_array::{Float16Array, Float64Array};
+    use arrow_array::{Float16Array, Float32Array, Float64Array};
     use half::f16;
     use lance_arrow::FixedSizeListArrayExt;
+    use lance_arrow::bfloat16::BFloat16Array;
 
     fn make_f16_storage() -> FlatFloatStorage {
         let values = Float16Array::from(vec![
@@ -571,6 +654,23 @@ mod tests {
         assert!((distances[1] - 25.0).abs() < 1e-4);
     }
 
+    #[test]
+    fn test_flat_float_storage_distance_bf16() {
+        let values = BFloat16Array::from_iter_values([1.0, 2.0, 4.0, 6.0].map(bf16::from_f32));
+        let vectors = FixedSizeListArray::try_new_from_values(values.into_inner(), 2).unwrap();
+        let storage = FlatFloatStorage::new(vectors, DistanceType::L2);
+        // Queries against bfloat16 vectors are given in f32.
+        let query: ArrayRef = Arc::new(Float32Array::from(vec![1.0, 2.0]));
+
+        let calc = storage.dist_calculator(query, 0.0);
+        let distances = calc.distance_all(2);
+
+        assert_eq!(distances.len(), 2);
+        assert_eq!(distances[0], 0.0);
+        assert!((distances[1] - 25.0).abs() < 1e-4);
+        assert!((storage.dist_calculator_from_id(1).distance(0) - 25.0).abs() < 1e-4);
+    }
+
     #[test]
     fn test_flat_float_storage_distance_f64() {
         let storage = make_f64_storage();
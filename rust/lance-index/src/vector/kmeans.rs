@@ -1384,15 +1384,17 @@ pub fn compute_partitions_arrow_array(
             centroids.value_length(),
             distance_type,
         )),
-        (DataType::Float32, DataType::Int8) => Ok(compute_partitions_with_dists::<
-            Float32Type,
-            KMeansAlgoFloat<Float32Type>,
-        >(
-            centroids.values().as_primitive(),
-            vectors.convert_to_floating_point()?.values().as_primitive(),
-            centroids.value_length(),
-            distance_type,
-        )),
+        (DataType::Float32, DataType::Int8 | DataType::FixedSizeBinary(2)) => {
+            Ok(compute_partitions_with_dists::<
+                Float32Type,
+                KMeansAlgoFloat<Float32Type>,
+            >(
+                centroids.values().as_primitive(),
+                vectors.convert_to_floating_point()?.values().as_primitive(),
+                centroids.value_length(),
+                distance_type,
+            ))
+        }
         (DataType::Float64, DataType::Float64) => Ok(compute_partitions_with_dists::<
             Float64Type,
             KMeansAlgoFloat<Float64Type>,
@@ -116,7 +116,6 @@ pub(crate) fn compute_residual(
             vectors.value_length(),
         )));
     }
-    // TODO: Bf16 is not supported yet.
     match (centroids.value_type(), vectors.value_type()) {
         (DataType::Float16, DataType::Float16) => {
             do_compute_residual::<Float16Type>(centroids, vectors, distance_type, partitions)
@@ -127,12 +126,14 @@ pub(crate) fn compute_residual(
         (DataType::Float64, DataType::Float64) => {
             do_compute_residual::<Float64Type>(centroids, vectors, distance_type, partitions)
         }
-        (DataType::Float32, DataType::Int8) => do_compute_residual::<Float32Type>(
-            centroids,
-            &vectors.convert_to_floating_point()?,
-            distance_type,
-            partitions,
-        ),
+        (DataType::Float32, DataType::Int8 | DataType::FixedSizeBinary(2)) => {
+            do_compute_residual::<Float32Type>(
+                centroids,
+                &vectors.convert_to_floating_point()?,
+                distance_type,
+                partitions,
+            )
+        }
         _ => Err(Error::index(format!(
             "Compute residual vector: centroids and vector type mismatch: centroid: {}, vector: {}",
             centroids.value_type(),
@@ -154,6 +154,10 @@ impl Quantization for ScalarQuantizer {
             DataType::Float64 => {
                 quantizer.update_bounds::<Float64Type>(fsl)?;
             }
+            // bfloat16 vectors are quantized in f32 space.
+            DataType::FixedSizeBinary(2) => {
+                quantizer.update_bounds::<Float32Type>(&fsl.convert_to_floating_point()?)?;
+            }
             _ => {
                 return Err(Error::index(format!(
                     "SQ builder: unsupported data type: {}",
@@ -181,6 +185,10 @@ impl Quantization for ScalarQuantizer {
             DataType::Float64 => {
                 self.update_bounds::<Float64Type>(fsl)?;
             }
+            // bfloat16 vectors are quantized in f32 space.
+            DataType::FixedSizeBinary(2) => {
+                self.update_bounds::<Float32Type>(&fsl.convert_to_floating_point()?)?;
+            }
             value_type => {
                 return Err(Error::invalid_input(format!(
                     "unsupported data type {} for scalar quantizer",
@@ -204,6 +212,9 @@ impl Quantization for ScalarQuantizer {
             DataType::Float16 => self.transform::<Float16Type>(vectors),
             DataType::Float32 => self.transform::<Float32Type>(vectors),
             DataType::Float64 => self.transform::<Float64Type>(vectors),
+            DataType::FixedSizeBinary(2) => self.transform::<Float32Type>(
+                &vectors.as_fixed_size_list().convert_to_floating_point()?,
+            ),
             value_type => Err(Error::invalid_input(format!(
                 "unsupported data type {} for scalar quantizer",
                 value_type
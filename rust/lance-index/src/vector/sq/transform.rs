@@ -16,7 +16,7 @@ use tracing::instrument;
 
 use crate::vector::transform::Transformer;
 
-use lance_arrow::RecordBatchExt;
+use lance_arrow::{FixedSizeListArrayExt, RecordBatchExt};
 use lance_core::{Error, Result};
 
 use super::ScalarQuantizer;
@@ -63,6 +63,9 @@ impl Transformer for SQTransformer {
             DataType::Float16 => self.quantizer.transform::<Float16Type>(input)?,
             DataType::Float32 => self.quantizer.transform::<Float32Type>(input)?,
             DataType::Float64 => self.quantizer.transform::<Float64Type>(input)?,
+            DataType::FixedSizeBinary(2) => self
+                .quantizer
+                .transform::<Float32Type>(&fsl.convert_to_floating_point()?)?,
             _ => {
                 return Err(Error::index(format!(
                     "unsupported data type: {}",
@@ -12,7 +12,8 @@ use arrow_array::UInt64Array;
 use arrow_array::types::{Float16Type, Float32Type, Float64Type};
 use arrow_array::{Array, ArrowPrimitiveType, RecordBatch, UInt32Array, cast::AsArray};
 use arrow_schema::{DataType, Field, Schema};
-use lance_arrow::RecordBatchExt;
+use lance_arrow::bfloat16::BFloat16Type;
+use lance_arrow::{FloatArray, RecordBatchExt};
 use num_traits::Float;
 
 use lance_core::{Error, ROW_ID, ROW_ID_FIELD, Result};
@@ -134,6 +135,12 @@ impl Transformer for KeepFiniteVectors {
                     DataType::Float32 => is_all_finite::<Float32Type>(&data),
                     // f32 vectors are computed in f32 space, so they have the same limit as f64.
                     DataType::Float64 => is_all_finite::<Float64Type>(&data),
+                    DataType::FixedSizeBinary(2) => {
+                        data.null_count() == 0
+                            && FloatArray::<BFloat16Type>::as_slice(data.as_fixed_size_binary())
+                                .iter()
+                                .all(|v| v.is_finite())
+                    }
                     DataType::UInt8 => data.null_count() == 0,
                     DataType::Int8 => data.null_count() == 0,
                     _ => false,
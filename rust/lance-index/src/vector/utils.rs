@@ -5,7 +5,8 @@ use arrow::compute::cast;
 use arrow_array::types::{Float16Type, Float32Type, Float64Type};
 use arrow_array::{Array, ArrayRef, BooleanArray, FixedSizeListArray, cast::AsArray};
 use arrow_schema::{DataType, Field};
-use lance_arrow::FixedSizeListArrayExt;
+use lance_arrow::bfloat16::BFloat16Type;
+use lance_arrow::{FixedSizeListArrayExt, FloatArray};
 use lance_core::{Error, Result};
 use lance_io::encodings::plain::bytes_to_array;
 use lance_linalg::distance::DistanceType;
@@ -261,6 +262,13 @@ pub fn is_finite(fsl: &FixedSizeListArray) -> BooleanArray {
                     let v = v.as_primitive::<Float64Type>();
                     Array::null_count(v) == 0 && v.values().iter().all(|v| v.is_finite())
                 }
+                DataType::FixedSizeBinary(2) => {
+                    let v = v.as_fixed_size_binary();
+                    Array::null_count(v) == 0
+                        && FloatArray::<BFloat16Type>::as_slice(v)
+                            .iter()
+                            .all(|v| v.is_finite())
+                }
                 _ => Array::null_count(&v) == 0,
             },
             None => false,
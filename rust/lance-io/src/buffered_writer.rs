@@ -0,0 +1,341 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::FutureExt;
+use object_store::path::Path;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
+use tokio::task::JoinHandle;
+use tokio_util::sync::PollSemaphore;
+
+use lance_core::{Error, Result};
+
+use crate::object_writer::WriteResult;
+use crate::traits::Writer;
+
+/// Max size of the chunks handed to the background task.
+const MAX_CHUNK_SIZE: usize = 1024 * 1024;
+
+enum Message {
+    /// A chunk of data, and the part of the budget it holds until it is written.
+    Data(Bytes, OwnedSemaphorePermit),
+    /// All data has been sent, the writer should be shut down.
+    Finish,
+}
+
+/// A writer that flushes to another writer in a background task.
+///
+/// Writes are copied into chunks that are written to the inner writer by a
+/// background task, so producers are not stalled while the inner writer waits on
+/// the object store.  At most `max_buffer_size` bytes are buffered at a time, and
+/// writes wait for the background task once the buffer is full.
+///
+/// Flushing waits until all buffered data has been written to the inner writer.
+/// If the writer is dropped before it is shut down, the inner writer is dropped
+/// as well, which aborts any multipart upload in progress.
+pub struct BufferedWriter {
+    path: Path,
+    chunk: Vec<u8>,
+    chunk_size: usize,
+    cursor: usize,
+    max_buffer_size: u32,
+    budget: PollSemaphore,
+    /// Budget acquired for the current chunk, or for the whole buffer when flushing
+    permit: Option<OwnedSemaphorePermit>,
+    tx: Option<mpsc::UnboundedSender<Message>>,
+    /// The background task, until it has exited
+    task: Option<JoinHandle<Result<WriteResult>>>,
+    result: Option<WriteResult>,
+}
+
+impl BufferedWriter {
+    /// Wrap `inner`, buffering at most `max_buffer_size` bytes.
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn new(inner: Box<dyn Writer>, path: Path, max_buffer_size: usize) -> Self {
+        let max_buffer_size = max_buffer_size.clamp(1, u32::MAX as usize);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(Self::flush_loop(inner, rx));
+        Self {
+            path,
+            chunk: Vec::new(),
+            chunk_size: max_buffer_size.min(MAX_CHUNK_SIZE),
+            cursor: 0,
+            max_buffer_size: max_buffer_size as u32,
+            budget: PollSemaphore::new(Arc::new(Semaphore::new(max_buffer_size))),
+            permit: None,
+            tx: Some(tx),
+            task: Some(task),
+            result: None,
+        }
+    }
+
+    async fn flush_loop(
+        mut inner: Box<dyn Writer>,
+        mut rx: mpsc::UnboundedReceiver<Message>,
+    ) -> Result<WriteResult> {
+        while let Some(message) = rx.recv().await {
+            match message {
+                Message::Data(data, _permit) => inner.write_all(&data).await?,
+                Message::Finish => return Writer::shutdown(&mut inner).await,
+            }
+        }
+        Err(Error::internal(
+            "buffered writer was dropped before it was shut down",
+        ))
+    }
+
+    fn closed_err(&self) -> io::Error {
+        io::Error::other(format!(
+            "cannot write to BufferedWriter for {} after shutdown",
+            self.path
+        ))
+    }
+
+    /// Wait for the background task to exit and return its result.
+    fn poll_task(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<WriteResult>> {
+        let Some(task) = &mut self.task else {
+            return Poll::Ready(Err(self.closed_err()));
+        };
+        let result = ready!(task.poll_unpin(cx));
+        self.task = None;
+        self.tx = None;
+        Poll::Ready(match result {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(err)) => Err(io::Error::other(err)),
+            Err(err) => Err(io::Error::other(err)),
+        })
+    }
+
+    /// Wait for the background task to exit after it stopped receiving data.
+    fn poll_task_error(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let result = ready!(self.poll_task(cx));
+        Poll::Ready(Err(result.err().unwrap_or_else(|| self.closed_err())))
+    }
+
+    /// Acquire `permits` bytes of the budget.
+    fn poll_acquire(&mut self, cx: &mut Context<'_>, permits: u32) -> Poll<()> {
+        if self.permit.is_none() {
+            // The semaphore is never closed
+            let permit = ready!(self.budget.poll_acquire_many(cx, permits)).unwrap();
+            self.permit = Some(permit);
+        }
+        Poll::Ready(())
+    }
+
+    /// Hand the current chunk to the background task, waiting for room in the buffer.
+    fn poll_send_chunk(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.chunk.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+        let Some(tx) = &self.tx else {
+            return Poll::Ready(Err(self.closed_err()));
+        };
+        if tx.is_closed() {
+            return self.poll_task_error(cx);
+        }
+        ready!(self.poll_acquire(cx, self.chunk.len() as u32));
+
+        let data = Bytes::from(std::mem::take(&mut self.chunk));
+        let permit = self.permit.take().unwrap();
+        if self
+            .tx
+            .as_ref()
+            .unwrap()
+            .send(Message::Data(data, permit))
+            .is_err()
+        {
+            return self.poll_task_error(cx);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for BufferedWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.chunk.len() >= self.chunk_size {
+            ready!(self.poll_send_chunk(cx))?;
+        }
+        if self.tx.is_none() {
+            return Poll::Ready(Err(self.closed_err()));
+        }
+
+        let chunk_size = self.chunk_size;
+        if self.chunk.capacity() == 0 {
+            self.chunk.reserve_exact(chunk_size);
+        }
+        let bytes_to_write = (chunk_size - self.chunk.len()).min(buf.len());
+        self.chunk.extend_from_slice(&buf[..bytes_to_write]);
+        self.cursor += bytes_to_write;
+        Poll::Ready(Ok(bytes_to_write))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_send_chunk(cx))?;
+        if self.tx.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+        // The whole budget is only available once the background task has written
+        // every chunk that was sent.
+        let max_buffer_size = self.max_buffer_size;
+        ready!(self.poll_acquire(cx, max_buffer_size));
+        self.permit = None;
+        if self.tx.as_ref().is_some_and(|tx| tx.is_closed()) {
+            return self.poll_task_error(cx);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.result.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        if self.tx.is_some() {
+            ready!(self.poll_send_chunk(cx))?;
+            // If the background task already exited, its result is returned below
+            if let Some(tx) = self.tx.take() {
+                let _ = tx.send(Message::Finish);
+            }
+        }
+        let mut result = ready!(self.poll_task(cx))?;
+        result.size = self.cursor;
+        self.result = Some(result);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[async_trait]
+impl Writer for BufferedWriter {
+    async fn tell(&mut self) -> Result<usize> {
+        Ok(self.cursor)
+    }
+
+    async fn shutdown(&mut self) -> Result<WriteResult> {
+        AsyncWriteExt::shutdown(self).await.map_err(|e| {
+            Error::io(format!(
+                "failed to shutdown buffered writer for {}: {}",
+                self.path, e
+            ))
+        })?;
+        Ok(self.result.clone().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::object_store::ObjectStore;
+
+    /// A writer that counts the bytes written to it, and only accepts a write
+    /// when a permit is added to its gate.
+    struct SlowWriter {
+        inner: Box<dyn Writer>,
+        written: Arc<AtomicUsize>,
+        gate: PollSemaphore,
+    }
+
+    impl AsyncWrite for SlowWriter {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            ready!(self.gate.poll_acquire(cx)).unwrap().forget();
+            let n = ready!(Pin::new(&mut self.inner).poll_write(cx, buf))?;
+            self.written.fetch_add(n, Ordering::SeqCst);
+            Poll::Ready(Ok(n))
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+
+    #[async_trait]
+    impl Writer for SlowWriter {
+        async fn tell(&mut self) -> Result<usize> {
+            self.inner.tell().await
+        }
+
+        async fn shutdown(&mut self) -> Result<WriteResult> {
+            Writer::shutdown(&mut self.inner).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_buffered_writer() {
+        let store = ObjectStore::memory();
+        let path = Path::from("buffered");
+        let written = Arc::new(AtomicUsize::new(0));
+        let gate = Arc::new(Semaphore::new(0));
+        let inner = SlowWriter {
+            inner: store.create(&path).await.unwrap(),
+            written: written.clone(),
+            gate: PollSemaphore::new(gate.clone()),
+        };
+        let mut writer = BufferedWriter::new(Box::new(inner), path.clone(), 4096);
+        assert_eq!(writer.chunk_size, 4096);
+
+        // The producer is not blocked until the buffer is full
+        writer.write_all(&[1; 4096]).await.unwrap();
+        writer.write_all(&[2; 4096]).await.unwrap();
+        assert_eq!(writer.tell().await.unwrap(), 8192);
+        let blocked = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            writer.write_all(&[3; 1]),
+        )
+        .await;
+        assert!(
+            blocked.is_err(),
+            "write should wait for the background flush"
+        );
+        assert_eq!(written.load(Ordering::SeqCst), 0);
+
+        // Once the inner writer makes progress the producer is unblocked
+        gate.add_permits(100);
+        writer.write_all(&[3; 1]).await.unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(written.load(Ordering::SeqCst), 8193);
+
+        let result = Writer::shutdown(&mut writer).await.unwrap();
+        assert_eq!(result.size, 8193);
+        let data = store.read_one_all(&path).await.unwrap();
+        assert_eq!(data.len(), 8193);
+        assert_eq!(&data[..4096], &[1; 4096]);
+        assert_eq!(&data[4096..8192], &[2; 4096]);
+        assert_eq!(data[8192], 3);
+
+        assert!(writer.write_all(&[4]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_buffered_writer_dropped() {
+        let store = ObjectStore::memory();
+        let path = Path::from("dropped");
+        let inner = store.create(&path).await.unwrap();
+        let mut writer = BufferedWriter::new(inner, path.clone(), 4096);
+        writer.write_all(&[1; 10_000]).await.unwrap();
+        writer.flush().await.unwrap();
+        drop(writer);
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!store.exists(&path).await.unwrap());
+    }
+}
@@ -11,6 +11,7 @@ use arrow_array::{PrimitiveArray, UInt32Array};
 
 use lance_core::{Error, Result};
 
+pub mod buffered_writer;
 pub mod encodings;
 pub mod ffi;
 pub mod local;
@@ -10,7 +10,7 @@ use crate::local::read_exact_at;
 #[cfg(unix)]
 use std::os::unix::fs::FileExt;
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use futures::{
     FutureExt,
     future::{BoxFuture, Shared},
@@ -24,7 +24,7 @@ use tokio::sync::OnceCell;
 use tracing::instrument;
 
 use crate::{
-    object_store::DEFAULT_CLOUD_IO_PARALLELISM,
+    object_store::{DEFAULT_CLOUD_IO_PARALLELISM, is_retryable, tracing::with_attempt},
     traits::{ByteStream, Reader},
 };
 
@@ -57,6 +57,94 @@ impl StaticGetRange for Arc<GetRequest> {
     }
 }
 
+impl GetRequest {
+    /// Request the rest of an object whose download failed, `range` is the part
+    /// that has not been received yet.
+    ///
+    /// The request is conditional on the `e_tag` of the first response, so the
+    /// download fails instead of mixing two versions of the object.
+    fn resume(
+        self: &Arc<Self>,
+        range: Range<u64>,
+        e_tag: Option<String>,
+    ) -> BoxFuture<'static, OSResult<GetResult>> {
+        let request = self.clone();
+        Box::pin(async move {
+            do_with_retry(|| {
+                let options = GetOptions {
+                    range: Some(range.clone().into()),
+                    if_match: e_tag.clone().or_else(|| request.options.if_match.clone()),
+                    ..request.options.clone()
+                };
+                let request = request.clone();
+                Box::pin(async move { request.object_store.get_opts(&request.path, options).await })
+            })
+            .await
+        })
+    }
+}
+
+/// The state of a download that can be resumed
+struct ResumableGet {
+    request: Arc<GetRequest>,
+    stream: ByteStream,
+    /// The part of the object that has not been received yet
+    remaining: Range<u64>,
+    e_tag: Option<String>,
+    retries: usize,
+}
+
+/// Stream the body of `result`.
+///
+/// If the body fails mid-stream with a retryable error, up to `retries` new
+/// requests are made for the part of the object that has not been received, so a
+/// dropped connection does not restart the whole download.
+fn resumable_stream(request: Arc<GetRequest>, result: GetResult, retries: usize) -> ByteStream {
+    let state = ResumableGet {
+        request,
+        remaining: result.range.clone(),
+        e_tag: result.meta.e_tag.clone(),
+        stream: result.into_stream(),
+        retries,
+    };
+    stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        loop {
+            match state.stream.next().await {
+                Some(Ok(bytes)) => {
+                    state.remaining.start += bytes.len() as u64;
+                    return Some((Ok(bytes), Some(state)));
+                }
+                Some(Err(err))
+                    if state.retries > 0
+                        && is_retryable(&err)
+                        && !state.remaining.is_empty() =>
+                {
+                    state.retries -= 1;
+                    log::debug!(
+                        "Resuming download of {} at offset {} (remaining retries: {}).  Error details: {:?}",
+                        state.request.path,
+                        state.remaining.start,
+                        state.retries,
+                        err
+                    );
+                    match state
+                        .request
+                        .resume(state.remaining.clone(), state.e_tag.clone())
+                        .await
+                    {
+                        Ok(result) => state.stream = result.into_stream(),
+                        Err(err) => return Some((Err(err), None)),
+                    }
+                }
+                Some(Err(err)) => return Some((Err(err), None)),
+                None => return None,
+            }
+        }
+    })
+    .boxed()
+}
+
 /// Object Reader
 ///
 /// Object Store + Base Path
@@ -99,64 +187,67 @@ impl CloudObjectReader {
     }
 }
 
-// Retries for the initial request are handled by object store, but
-// there are no retries for failures that occur during the streaming
-// of the response body. Thus we add an outer retry loop here.
+// Retries for the initial request are handled by object store, but some
+// transient errors (e.g. failures while decoding the response) are not retried
+// there. Thus we add an outer retry loop here, which gives up on fatal errors.
 async fn do_with_retry<'a, O>(f: impl Fn() -> BoxFuture<'a, OSResult<O>> + Clone) -> OSResult<O> {
     let mut retries = 3;
+    let mut attempt = 1;
     loop {
         let f = f.clone();
-        match f().await {
+        match with_attempt(attempt, f()).await {
             Ok(val) => return Ok(val),
             Err(err) => {
-                if retries == 0 {
+                if retries == 0 || !is_retryable(&err) {
                     return Err(err);
                 }
                 retries -= 1;
+                attempt += 1;
             }
         }
     }
 }
 
-// We have a separate retry loop here.  This is because object_store does not
-// attempt retries on downloads that fail during streaming of the response body.
+// object_store does not attempt retries on downloads that fail during streaming
+// of the response body.
 //
 // However, this failure is pretty common (e.g. timeout) and we want to retry in these
-// situations.  In addition, we provide additional logging information in these
-// failures cases.
-async fn do_get_with_outer_retry(
+// situations.  The download is resumed from the failed offset, and we provide
+// additional logging information in these failures cases.
+async fn do_get_with_resume(
     download_retry_count: usize,
     get_request: Arc<GetRequest>,
     desc: impl Fn() -> String,
 ) -> OSResult<Bytes> {
-    let mut retries = download_retry_count;
-    loop {
-        let get_request_clone = get_request.clone();
-        let get_result = do_with_retry(move || get_request_clone.get_range()).await?;
-        match get_result.bytes().await {
-            Ok(bytes) => return Ok(bytes),
+    let get_request_clone = get_request.clone();
+    let get_result = do_with_retry(move || get_request_clone.get_range()).await?;
+    let size = (get_result.range.end - get_result.range.start) as usize;
+    let mut stream = resumable_stream(get_request.clone(), get_result, download_retry_count);
+
+    let mut chunks = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(chunk) => chunks.push(chunk),
             Err(err) => {
-                if retries == 0 {
-                    log::warn!(
-                        "Failed to download {} from {} after {} attempts.  This may indicate that cloud storage is overloaded or your timeout settings are too restrictive.  Error details: {:?}",
-                        desc(),
-                        get_request.path(),
-                        download_retry_count,
-                        err
-                    );
-                    return Err(err);
-                }
-                log::debug!(
-                    "Retrying {} from {} (remaining retries: {}).  Error details: {:?}",
+                log::warn!(
+                    "Failed to download {} from {} after {} attempts.  This may indicate that cloud storage is overloaded or your timeout settings are too restrictive.  Error details: {:?}",
                     desc(),
                     get_request.path(),
-                    retries,
+                    download_retry_count,
                     err
                 );
-                retries -= 1;
+                return Err(err);
             }
         }
     }
+    if chunks.len() == 1 {
+        return Ok(chunks.pop().unwrap());
+    }
+    let mut bytes = BytesMut::with_capacity(size);
+    for chunk in chunks {
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes.freeze())
 }
 
 impl Reader for CloudObjectReader {
@@ -221,7 +312,7 @@ impl Reader for CloudObjectReader {
             options: GetOptions::default(),
         });
         Box::pin(async move {
-            do_get_with_outer_retry(self.download_retry_count, get_request, || {
+            do_get_with_resume(self.download_retry_count, get_request, || {
                 "read_all".to_string()
             })
             .await
@@ -237,7 +328,11 @@ impl Reader for CloudObjectReader {
         Box::pin(async move {
             let get_request_clone = get_request.clone();
             let get_result = do_with_retry(move || get_request_clone.get_range()).await?;
-            Ok(get_result.into_stream())
+            Ok(resumable_stream(
+                get_request,
+                get_result,
+                self.download_retry_count,
+            ))
         })
     }
 
@@ -259,7 +354,11 @@ impl Reader for CloudObjectReader {
         Box::pin(async move {
             let get_request_clone = get_request.clone();
             let get_result = do_with_retry(move || get_request_clone.get_range()).await?;
-            Ok(get_result.into_stream())
+            Ok(resumable_stream(
+                get_request,
+                get_result,
+                self.download_retry_count,
+            ))
         })
     }
 }
@@ -461,3 +560,122 @@ impl DeepSizeOf for SmallReader {
         size
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use object_store::{GetRange, GetResultPayload, memory::InMemory};
+
+    use super::*;
+    use crate::testing::MockObjectStore;
+
+    /// A store whose first download drops the connection after `fail_after` bytes
+    fn flaky_store(
+        data: Bytes,
+        fail_after: usize,
+        requests: Arc<Mutex<Vec<GetOptions>>>,
+    ) -> Arc<dyn ObjectStore> {
+        let base_store = Arc::new(InMemory::new());
+        let mut store = MockObjectStore::default();
+        store.expect_get_opts().returning(move |location, options| {
+            let base_store = base_store.clone();
+            let data = data.clone();
+            let location = location.clone();
+            let first = {
+                let mut requests = requests.lock().unwrap();
+                requests.push(options.clone());
+                requests.len() == 1
+            };
+            async move {
+                if first {
+                    base_store.put(&location, data.into()).await?;
+                }
+                let result = base_store.get_opts(&location, options).await?;
+                if !first {
+                    return Ok(result);
+                }
+                let body = result.bytes().await?;
+                let meta = base_store.head(&location).await?;
+                let stream = stream::iter(vec![
+                    Ok(body.slice(..fail_after)),
+                    Err(object_store::Error::Generic {
+                        store: "flaky",
+                        source: "connection reset by peer".into(),
+                    }),
+                ]);
+                Ok(GetResult {
+                    payload: GetResultPayload::Stream(stream.boxed()),
+                    range: 0..meta.size,
+                    meta,
+                    attributes: Default::default(),
+                })
+            }
+            .boxed()
+        });
+        Arc::new(store)
+    }
+
+    #[tokio::test]
+    async fn test_resume_download() {
+        let data = Bytes::from((0..100u8).collect::<Vec<_>>());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let store = flaky_store(data.clone(), 40, requests.clone());
+        let reader = CloudObjectReader::new(store, Path::from("data"), 64, Some(100), 3).unwrap();
+
+        assert_eq!(reader.get_all().await.unwrap(), data);
+
+        // The second request only asks for the bytes that were not received, and
+        // only if the object did not change in the meantime
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].range, Some(GetRange::Bounded(40..100)));
+        assert!(requests[1].if_match.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_resume_stream() {
+        let data = Bytes::from((0..100u8).collect::<Vec<_>>());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let store = flaky_store(data.clone(), 40, requests.clone());
+        let reader = CloudObjectReader::new(store, Path::from("data"), 64, Some(100), 3).unwrap();
+
+        let chunks = reader
+            .get_stream()
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<OSResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(chunks.concat(), data.to_vec());
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // Without retries the error is returned
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let store = flaky_store(data.clone(), 40, requests.clone());
+        let reader = CloudObjectReader::new(store, Path::from("data"), 64, Some(100), 0).unwrap();
+        assert!(reader.get_all().await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fatal_errors_are_not_retried() {
+        let mut store = MockObjectStore::default();
+        store.expect_get_opts().times(1).returning(|location, _| {
+            let path = location.to_string();
+            async move {
+                Err(object_store::Error::NotFound {
+                    path,
+                    source: "missing".into(),
+                })
+            }
+            .boxed()
+        });
+        let reader =
+            CloudObjectReader::new(Arc::new(store), Path::from("data"), 64, None, 3).unwrap();
+        let err = reader.get_all().await.unwrap_err();
+        assert!(matches!(err, object_store::Error::NotFound { .. }), "{err}");
+    }
+}
@@ -19,34 +19,44 @@ use lance_core::deepsize::DeepSizeOf;
 use lance_core::error::LanceOptionExt;
 use lance_core::utils::parse::str_is_truthy;
 use list_retry::ListRetryStream;
+use metrics::IoMetricsRecorder;
 use object_store::DynObjectStore;
 use object_store::ObjectStoreExt as OSObjectStoreExt;
 #[cfg(feature = "aws")]
 use object_store::aws::AwsCredentialProvider;
-#[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+#[cfg(any(feature = "aws", feature = "azure", feature = "gcp", feature = "http"))]
 use object_store::{ClientOptions, HeaderMap, HeaderValue};
 use object_store::{ObjectMeta, ObjectStore as OSObjectStore, path::Path};
 use providers::local::FileStoreProvider;
 use providers::memory::MemoryStoreProvider;
+use retry::DEFAULT_LIST_RETRY_COUNT;
 use tokio::io::AsyncWriteExt;
 use url::Url;
 
 use super::local::LocalObjectReader;
 #[cfg(target_os = "linux")]
 use crate::uring::{UringCurrentThreadReader, UringReader};
+pub mod disk_cache;
 #[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
 pub(crate) mod dynamic_credentials;
 #[cfg(any(feature = "oss", feature = "huggingface", feature = "tos"))]
 pub(crate) mod dynamic_opendal;
+pub mod encryption;
+pub mod fault_injection;
 mod list_retry;
+pub mod metrics;
 pub mod providers;
+pub mod rate_limit;
+pub mod retry;
 pub mod storage_options;
 #[cfg(test)]
 pub(crate) mod test_utils;
 pub mod throttle;
-mod tracing;
+pub(crate) mod tracing;
+use crate::buffered_writer::BufferedWriter;
 use crate::object_reader::SmallReader;
-use crate::object_writer::{LocalWriter, WriteResult};
+use crate::object_writer::{LocalWriter, UploadOptions, WriteResult};
+use crate::scheduler::{CoalesceOptions, IoBudget};
 use crate::traits::{WriteExt, Writer};
 use crate::utils::tracking_store::{IOTracker, IoStats};
 use crate::{object_reader::CloudObjectReader, object_writer::ObjectWriter, traits::Reader};
@@ -69,6 +79,8 @@ const DEFAULT_LOCAL_BLOCK_SIZE: usize = 4 * 1024; // 4KB block size
     feature = "tencent",
     feature = "huggingface",
     feature = "tos",
+    feature = "http",
+    feature = "hdfs",
 ))]
 const DEFAULT_CLOUD_BLOCK_SIZE: usize = 64 * 1024; // 64KB block size
 
@@ -80,7 +92,11 @@ pub static DEFAULT_MAX_IOP_SIZE: std::sync::LazyLock<u64> = std::sync::LazyLock:
 
 pub const DEFAULT_DOWNLOAD_RETRY_COUNT: usize = 3;
 
-pub use providers::{ObjectStoreProvider, ObjectStoreRegistry};
+pub use providers::{
+    ObjectStoreProvider, ObjectStoreRegistry, register_object_store_provider,
+    registered_object_store_provider, unregister_object_store_provider,
+};
+pub use retry::{BackoffBase, RetryPolicy, is_retryable};
 pub use storage_options::{
     EXPIRES_AT_MILLIS_KEY, LanceNamespaceStorageOptionsProvider, REFRESH_OFFSET_MILLIS_KEY,
     StorageOptionsAccessor, StorageOptionsProvider,
@@ -144,6 +160,14 @@ pub struct ObjectStore {
     io_parallelism: usize,
     /// Number of times to retry a failed download
     download_retry_count: usize,
+    /// Number of times to resume a failed list
+    list_retry_count: usize,
+    /// I/O budget shared by the schedulers reading from this store
+    io_budget: Option<Arc<IoBudget>>,
+    /// Part size and concurrency of multipart uploads
+    upload_options: UploadOptions,
+    /// Thresholds used by the scheduler to merge and split reads
+    coalesce_options: CoalesceOptions,
     /// IO tracker for monitoring read/write operations
     io_tracker: IOTracker,
     /// The datastore prefix that uniquely identifies this object store. It encodes information
@@ -224,6 +248,20 @@ pub struct ObjectStoreParams {
     /// 50GB.
     pub use_constant_size_upload_parts: bool,
     pub list_is_lexically_ordered: Option<bool>,
+    /// Retry and timeout policy for requests to the object store.
+    ///
+    /// If not set, the policy is read from the storage options. See
+    /// [`RetryPolicy::from_storage_options`].
+    pub retry_policy: Option<RetryPolicy>,
+    /// Receives metrics of every request made by the object store.
+    ///
+    /// See [`metrics::IoMetricsRecorder`].
+    pub io_metrics: Option<Arc<dyn IoMetricsRecorder>>,
+    /// An I/O budget shared with other object stores.
+    ///
+    /// All schedulers reading from the object store draw from this budget. See
+    /// [`IoBudget`].
+    pub io_budget: Option<Arc<IoBudget>>,
 }
 
 impl Default for ObjectStoreParams {
@@ -239,6 +277,9 @@ impl Default for ObjectStoreParams {
             storage_options_accessor: None,
             use_constant_size_upload_parts: false,
             list_is_lexically_ordered: None,
+            retry_policy: None,
+            io_metrics: None,
+            io_budget: None,
         }
     }
 }
@@ -257,6 +298,17 @@ impl ObjectStoreParams {
             .as_ref()
             .and_then(|a| a.initial_storage_options())
     }
+
+    /// Get the retry policy for the store
+    ///
+    /// Returns [`Self::retry_policy`] if set, otherwise the policy configured
+    /// in `storage_options`.
+    pub fn resolve_retry_policy(&self, storage_options: &StorageOptions) -> Result<RetryPolicy> {
+        match &self.retry_policy {
+            Some(policy) => Ok(policy.clone()),
+            None => RetryPolicy::from_storage_options(storage_options),
+        }
+    }
 }
 
 // We implement hash for caching
@@ -282,6 +334,13 @@ impl std::hash::Hash for ObjectStoreParams {
         }
         self.use_constant_size_upload_parts.hash(state);
         self.list_is_lexically_ordered.hash(state);
+        self.retry_policy.hash(state);
+        if let Some(io_metrics) = &self.io_metrics {
+            Arc::as_ptr(io_metrics).hash(state);
+        }
+        if let Some(io_budget) = &self.io_budget {
+            Arc::as_ptr(io_budget).hash(state);
+        }
     }
 }
 
@@ -319,6 +378,10 @@ impl PartialEq for ObjectStoreParams {
                     .map(|a| a.accessor_id())
             && self.use_constant_size_upload_parts == other.use_constant_size_upload_parts
             && self.list_is_lexically_ordered == other.list_is_lexically_ordered
+            && self.retry_policy == other.retry_policy
+            && self.io_metrics.as_ref().map(Arc::as_ptr)
+                == other.io_metrics.as_ref().map(Arc::as_ptr)
+            && self.io_budget.as_ref().map(Arc::as_ptr) == other.io_budget.as_ref().map(Arc::as_ptr)
     }
 }
 
@@ -469,17 +532,20 @@ impl ObjectStore {
             let io_tracker = IOTracker::default();
             let tracked_store = io_tracker.wrap("", inner);
 
+            let storage_options =
+                StorageOptions::new(params.storage_options().cloned().unwrap_or_default());
+            let retry_policy = params.resolve_retry_policy(&storage_options)?;
             let store = Self {
-                inner: tracked_store,
-                scheme: path.scheme().to_string(),
                 block_size: params.block_size.unwrap_or(64 * 1024),
-                max_iop_size: *DEFAULT_MAX_IOP_SIZE,
                 use_constant_size_upload_parts: params.use_constant_size_upload_parts,
                 list_is_lexically_ordered: params.list_is_lexically_ordered.unwrap_or_default(),
-                io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
-                download_retry_count: DEFAULT_DOWNLOAD_RETRY_COUNT,
+                download_retry_count: retry_policy.download_retry_count,
+                list_retry_count: retry_policy.list_retry_count,
+                io_budget: params.io_budget.clone(),
+                upload_options: UploadOptions::from_storage_options(&storage_options)?,
+                coalesce_options: CoalesceOptions::from_storage_options(&storage_options)?,
                 io_tracker,
-                store_prefix,
+                ..Self::with_defaults(tracked_store, path.scheme(), store_prefix)
             };
             let path = Path::parse(path.path())?;
             return Ok((Arc::new(store), path));
@@ -592,6 +658,21 @@ impl ObjectStore {
         &self.io_tracker
     }
 
+    /// Part size and concurrency of multipart uploads to this store
+    pub fn upload_options(&self) -> &UploadOptions {
+        &self.upload_options
+    }
+
+    /// Thresholds used to merge and split the reads from this store
+    pub fn coalesce_options(&self) -> &CoalesceOptions {
+        &self.coalesce_options
+    }
+
+    /// The I/O budget shared by the schedulers reading from this store, if any
+    pub fn io_budget(&self) -> Option<&Arc<IoBudget>> {
+        self.io_budget.as_ref()
+    }
+
     /// Get a snapshot of current IO statistics without resetting counters
     ///
     /// Returns the current IO statistics without modifying the internal state.
@@ -738,7 +819,20 @@ impl ObjectStore {
     }
 
     /// Create a new file.
+    ///
+    /// If the `write_buffer_size` storage option is set, the data is buffered and
+    /// written to the store in the background.
     pub async fn create(&self, path: &Path) -> Result<Box<dyn Writer>> {
+        let writer = self.create_unbuffered(path).await?;
+        Ok(match self.upload_options.write_buffer_size {
+            Some(write_buffer_size) => {
+                Box::new(BufferedWriter::new(writer, path.clone(), write_buffer_size))
+            }
+            None => writer,
+        })
+    }
+
+    async fn create_unbuffered(&self, path: &Path) -> Result<Box<dyn Writer>> {
         match self.scheme.as_str() {
             "file" => {
                 let local_path = super::local::to_local_path(path);
@@ -846,7 +940,10 @@ impl ObjectStore {
         &self,
         path: Option<Path>,
     ) -> Pin<Box<dyn Stream<Item = Result<ObjectMeta>> + Send>> {
-        Box::pin(ListRetryStream::new(self.inner.clone(), path, 5).map(|m| m.map_err(|e| e.into())))
+        Box::pin(
+            ListRetryStream::new(self.inner.clone(), path, self.list_retry_count)
+                .map(|m| m.map_err(|e| e.into())),
+        )
     }
 
     /// Read all files (start from base directory) recursively
@@ -984,6 +1081,23 @@ impl StorageOptions {
         })
     }
 
+    /// Parse the value of an option, matching the key case-insensitively.
+    ///
+    /// Returns `None` if the option is not set and an error if it cannot be parsed.
+    pub(crate) fn parse<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| {
+                value.parse::<T>().map_err(|_| {
+                    Error::invalid_input(format!(
+                        "Invalid value for storage option '{key}': '{value}'"
+                    ))
+                })
+            })
+            .transpose()
+    }
+
     /// Number of times to retry a download that fails
     pub fn download_retry_count(&self) -> usize {
         self.0
@@ -1022,8 +1136,19 @@ impl StorageOptions {
     /// `x-ms-version: 2023-11-03`.
     ///
     /// Returns an error if any `headers.*` key has an invalid header name or value.
-    #[cfg(any(feature = "aws", feature = "azure", feature = "gcp"))]
+    #[cfg(any(feature = "aws", feature = "azure", feature = "gcp", feature = "http"))]
     pub fn client_options(&self) -> Result<ClientOptions> {
+        let headers = self.default_headers()?;
+        let mut client_options = ClientOptions::default();
+        if !headers.is_empty() {
+            client_options = client_options.with_default_headers(headers);
+        }
+        Ok(client_options)
+    }
+
+    /// The HTTP headers set with `headers.*` keys, see [`Self::client_options`].
+    #[cfg(any(feature = "aws", feature = "azure", feature = "gcp", feature = "http"))]
+    pub(crate) fn default_headers(&self) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         for (key, value) in &self.0 {
             if let Some(header_name) = key.strip_prefix("headers.") {
@@ -1038,11 +1163,7 @@ impl StorageOptions {
                 headers.insert(name, val);
             }
         }
-        let mut client_options = ClientOptions::default();
-        if !headers.is_empty() {
-            client_options = client_options.with_default_headers(headers);
-        }
-        Ok(client_options)
+        Ok(headers)
     }
 
     /// Get the expiration time in milliseconds since epoch, if present
@@ -1063,6 +1184,36 @@ static DEFAULT_OBJECT_STORE_REGISTRY: std::sync::LazyLock<ObjectStoreRegistry> =
     std::sync::LazyLock::new(ObjectStoreRegistry::default);
 
 impl ObjectStore {
+    /// A store for `inner` with default settings.
+    ///
+    /// Providers override the fields that depend on the kind of store with
+    /// struct update syntax.  Settings that apply to every store, such as the IO
+    /// budget and the upload and coalesce options, are left at their defaults
+    /// here and set by the [`ObjectStoreRegistry`] once the provider returns.
+    pub(crate) fn with_defaults(
+        inner: Arc<DynObjectStore>,
+        scheme: impl Into<String>,
+        store_prefix: String,
+    ) -> Self {
+        let scheme = scheme.into();
+        Self {
+            inner,
+            block_size: infer_block_size(&scheme),
+            scheme,
+            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
+            use_constant_size_upload_parts: false,
+            list_is_lexically_ordered: false,
+            io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
+            download_retry_count: DEFAULT_DOWNLOAD_RETRY_COUNT,
+            list_retry_count: DEFAULT_LIST_RETRY_COUNT,
+            io_budget: None,
+            upload_options: UploadOptions::default(),
+            coalesce_options: CoalesceOptions::default(),
+            io_tracker: IOTracker::default(),
+            store_prefix,
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         store: Arc<DynObjectStore>,
@@ -1100,16 +1251,13 @@ impl ObjectStore {
         let tracked_store = io_tracker.wrap("", store);
 
         Self {
-            inner: tracked_store,
-            scheme: scheme.into(),
             block_size,
-            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
             use_constant_size_upload_parts,
             list_is_lexically_ordered,
             io_parallelism,
             download_retry_count,
             io_tracker,
-            store_prefix,
+            ..Self::with_defaults(tracked_store, scheme, store_prefix)
         }
     }
 }
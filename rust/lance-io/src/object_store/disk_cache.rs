@@ -0,0 +1,750 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Read-through local disk cache for remote object stores.
+//!
+//! Repeated scans and index probes against a remote dataset tend to request the
+//! same byte ranges over and over.  [`DiskCachedStore`] keeps those ranges on a
+//! local disk (ideally an SSD) so that subsequent reads are served without a
+//! round trip to the object store.
+//!
+//! Only ranged reads of immutable files (data files, deletion files and index
+//! files) are cached.  Lance never modifies these files in place, so a cached
+//! range can never become stale.  Files are recognized by their position below
+//! the dataset root (`data/<file>`, `_deletions/<file>` and
+//! `_indices/<uuid>/<file>`), so a dataset stored under a directory that happens
+//! to be named `data` doesn't get its manifests cached.  Everything else is
+//! passed through unchanged.
+//!
+//! The cache is bounded by a maximum size in bytes.  When it is full, entries are
+//! evicted (approximately least recently used) and their files are deleted.  Cache
+//! files survive process restarts and are re-indexed when the cache is reopened.
+//!
+//! The cache is enabled per store through storage options:
+//!
+//! | Storage Option Key              | Env Var                         | Default |
+//! |---------------------------------|---------------------------------|---------|
+//! | `lance_disk_cache_dir`          | `LANCE_DISK_CACHE_DIR`          | (none)  |
+//! | `lance_disk_cache_max_bytes`    | `LANCE_DISK_CACHE_MAX_BYTES`    | 10 GiB  |
+//! | `lance_disk_cache_max_range_bytes` | `LANCE_DISK_CACHE_MAX_RANGE_BYTES` | 16 MiB |
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::{StreamExt, stream};
+use object_store::path::Path;
+use object_store::{
+    Attributes, CopyOptions, GetOptions, GetRange, GetResult, GetResultPayload, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, ObjectStoreExt, PutMultipartOptions, PutOptions,
+    PutPayload, PutResult, RenameOptions, Result as OSResult,
+};
+
+use super::metrics::IoMetricsRecorder;
+
+pub const DISK_CACHE_DIR_KEY: &str = "lance_disk_cache_dir";
+pub const DISK_CACHE_MAX_BYTES_KEY: &str = "lance_disk_cache_max_bytes";
+pub const DISK_CACHE_MAX_RANGE_BYTES_KEY: &str = "lance_disk_cache_max_range_bytes";
+
+/// The cache name reported to [`IoMetricsRecorder::record_cache_access`].
+pub const DISK_CACHE_METRICS_NAME: &str = "disk";
+
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+const DEFAULT_MAX_RANGE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Size of the fixed header stored in front of every cache file: object size,
+/// last modified time (milliseconds since epoch), start and end of the cached
+/// range and the length of the object key, all little endian.  The object key
+/// follows the fixed header, then the cached bytes.
+///
+/// File names are hashes, so the key and range are checked on every read to
+/// make sure the file holds the requested bytes.
+const HEADER_SIZE: usize = 36;
+
+/// Directories of the dataset root that only contain immutable files, with the
+/// number of path components from the directory down to a file.
+const IMMUTABLE_DIRS: &[(&str, usize)] = &[("data", 1), ("_deletions", 1), ("_indices", 2)];
+
+/// Configuration for the local disk cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskCacheConfig {
+    /// Directory the cached ranges are stored in.
+    pub directory: PathBuf,
+    /// Maximum total size of the cached ranges, in bytes.
+    pub max_bytes: u64,
+    /// Ranged reads larger than this are not cached.
+    pub max_range_bytes: u64,
+}
+
+impl DiskCacheConfig {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_range_bytes: DEFAULT_MAX_RANGE_BYTES,
+        }
+    }
+
+    pub fn with_max_bytes(self, max_bytes: u64) -> Self {
+        Self { max_bytes, ..self }
+    }
+
+    pub fn with_max_range_bytes(self, max_range_bytes: u64) -> Self {
+        Self {
+            max_range_bytes,
+            ..self
+        }
+    }
+
+    /// Build the configuration from storage options, falling back to environment
+    /// variables.  Returns `None` if no cache directory is configured.
+    pub fn from_storage_options(
+        storage_options: Option<&HashMap<String, String>>,
+    ) -> lance_core::Result<Option<Self>> {
+        let resolve = |key: &str| {
+            storage_options
+                .and_then(|opts| opts.get(key).cloned())
+                .or_else(|| std::env::var(key.to_ascii_uppercase()).ok())
+        };
+        let resolve_u64 = |key: &str, default: u64| -> lance_core::Result<u64> {
+            match resolve(key) {
+                Some(val) => val.parse::<u64>().map_err(|_| {
+                    lance_core::Error::invalid_input(format!(
+                        "Invalid value for storage option '{key}': '{val}'"
+                    ))
+                }),
+                None => Ok(default),
+            }
+        };
+
+        let Some(directory) = resolve(DISK_CACHE_DIR_KEY).filter(|dir| !dir.is_empty()) else {
+            return Ok(None);
+        };
+        Ok(Some(
+            Self::new(directory)
+                .with_max_bytes(resolve_u64(DISK_CACHE_MAX_BYTES_KEY, DEFAULT_MAX_BYTES)?)
+                .with_max_range_bytes(resolve_u64(
+                    DISK_CACHE_MAX_RANGE_BYTES_KEY,
+                    DEFAULT_MAX_RANGE_BYTES,
+                )?),
+        ))
+    }
+}
+
+/// Statistics of a [`DiskCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub num_entries: u64,
+    pub size_bytes: u64,
+}
+
+/// A size-bounded directory of cached byte ranges.
+///
+/// Caches are shared by directory, so every store configured with the same
+/// directory accounts against the same size limit.
+pub struct DiskCache {
+    config: DiskCacheConfig,
+    /// File name -> file size
+    index: moka::sync::Cache<String, u64>,
+    hits: std::sync::atomic::AtomicU64,
+    misses: std::sync::atomic::AtomicU64,
+}
+
+impl Debug for DiskCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiskCache")
+            .field("config", &self.config)
+            .field("num_entries", &self.index.entry_count())
+            .finish()
+    }
+}
+
+static DISK_CACHES: LazyLock<Mutex<HashMap<PathBuf, Arc<DiskCache>>>> =
+    LazyLock::new(Default::default);
+
+impl DiskCache {
+    /// Open the cache for the configured directory, re-indexing any files left
+    /// by a previous process.
+    ///
+    /// If a cache is already open for the directory it is returned instead.
+    pub fn open(config: DiskCacheConfig) -> lance_core::Result<Arc<Self>> {
+        let mut caches = DISK_CACHES.lock().unwrap();
+        if let Some(cache) = caches.get(&config.directory) {
+            return Ok(cache.clone());
+        }
+
+        std::fs::create_dir_all(&config.directory)?;
+        let directory = config.directory.clone();
+        let index = moka::sync::Cache::builder()
+            .max_capacity(config.max_bytes)
+            .weigher(|_, size: &u64| (*size).try_into().unwrap_or(u32::MAX))
+            .eviction_listener(move |name: Arc<String>, _, cause| {
+                if cause != moka::notification::RemovalCause::Replaced {
+                    let _ = std::fs::remove_file(directory.join(name.as_str()));
+                }
+            })
+            .build();
+        for entry in std::fs::read_dir(&config.directory)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.ends_with(".tmp") {
+                let _ = std::fs::remove_file(entry.path());
+            } else {
+                index.insert(name, metadata.len());
+            }
+        }
+
+        let cache = Arc::new(Self {
+            config,
+            index,
+            hits: Default::default(),
+            misses: Default::default(),
+        });
+        caches.insert(cache.config.directory.clone(), cache.clone());
+        Ok(cache)
+    }
+
+    pub fn config(&self) -> &DiskCacheConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> DiskCacheStats {
+        self.index.run_pending_tasks();
+        DiskCacheStats {
+            hits: self.hits.load(std::sync::atomic::Ordering::Relaxed),
+            misses: self.misses.load(std::sync::atomic::Ordering::Relaxed),
+            num_entries: self.index.entry_count(),
+            size_bytes: self.index.weighted_size(),
+        }
+    }
+
+    fn file_name(object_key: &str, range: &Range<u64>) -> String {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        object_key.hash(&mut hasher);
+        format!(
+            "{:016x}-{}-{}",
+            hasher.finish(),
+            range.start,
+            range.end - range.start
+        )
+    }
+
+    /// Check that a cache file holds `range` of the object and return the object
+    /// size, last modified time and the offset of the cached bytes.
+    fn decode_header(
+        data: &[u8],
+        object_key: &str,
+        range: &Range<u64>,
+    ) -> Option<(u64, i64, usize)> {
+        if data.len() < HEADER_SIZE {
+            return None;
+        }
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        let key_len = u32::from_le_bytes(data[32..36].try_into().unwrap()) as usize;
+        let data_start = HEADER_SIZE + key_len;
+        let matches = u64_at(16) == range.start
+            && u64_at(24) == range.end
+            && data.get(HEADER_SIZE..data_start) == Some(object_key.as_bytes())
+            && (data.len() - data_start) as u64 == range.end - range.start;
+        matches.then_some((u64_at(0), u64_at(8) as i64, data_start))
+    }
+
+    async fn get(
+        &self,
+        name: &str,
+        object_key: &str,
+        range: &Range<u64>,
+    ) -> Option<(ObjectMeta, Bytes)> {
+        if self.index.get(name).is_none() {
+            self.misses
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            return None;
+        }
+        let data = tokio::fs::read(self.config.directory.join(name)).await;
+        match data
+            .ok()
+            .and_then(|data| Some((Self::decode_header(&data, object_key, range)?, data)))
+        {
+            Some(((size, modified, data_start), data)) => {
+                self.hits.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                let meta = ObjectMeta {
+                    location: Path::default(),
+                    last_modified: DateTime::<Utc>::from_timestamp_millis(modified)
+                        .unwrap_or_default(),
+                    size,
+                    e_tag: None,
+                    version: None,
+                };
+                Some((meta, Bytes::from(data).slice(data_start..)))
+            }
+            None => {
+                // The file was removed or truncated behind our back, or it holds
+                // another range whose name hashes the same.  The entry is
+                // replaced once the requested range is read.
+                self.index.invalidate(name);
+                self.misses
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    async fn put(
+        &self,
+        name: String,
+        object_key: String,
+        range: Range<u64>,
+        meta: &ObjectMeta,
+        data: Bytes,
+    ) {
+        let path = self.config.directory.join(&name);
+        let tmp_path = self.config.directory.join(format!("{name}.tmp"));
+        let size = meta.size;
+        let last_modified = meta.last_modified;
+        let result = tokio::task::spawn_blocking(move || {
+            let mut file = std::fs::File::create(&tmp_path)?;
+            file.write_all(&size.to_le_bytes())?;
+            file.write_all(&last_modified.timestamp_millis().to_le_bytes())?;
+            file.write_all(&range.start.to_le_bytes())?;
+            file.write_all(&range.end.to_le_bytes())?;
+            file.write_all(&(object_key.len() as u32).to_le_bytes())?;
+            file.write_all(object_key.as_bytes())?;
+            file.write_all(&data)?;
+            std::fs::rename(&tmp_path, &path)?;
+            Ok::<_, std::io::Error>((HEADER_SIZE + object_key.len() + data.len()) as u64)
+        })
+        .await;
+        match result {
+            Ok(Ok(file_size)) => self.index.insert(name, file_size),
+            Ok(Err(err)) => log::warn!("Failed to write disk cache entry {name}: {err}"),
+            Err(err) => log::warn!("Failed to write disk cache entry {name}: {err}"),
+        }
+    }
+}
+
+/// An [`ObjectStore`] wrapper that caches ranged reads of immutable files in a
+/// [`DiskCache`].
+pub struct DiskCachedStore {
+    target: Arc<dyn ObjectStore>,
+    store_prefix: String,
+    cache: Arc<DiskCache>,
+    metrics: Option<Arc<dyn IoMetricsRecorder>>,
+}
+
+impl Debug for DiskCachedStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiskCachedStore")
+            .field("target", &self.target)
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
+impl Display for DiskCachedStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DiskCachedStore({})", self.target)
+    }
+}
+
+impl DiskCachedStore {
+    /// Wrap `target`.  The store prefix identifies the bucket / account so that
+    /// stores sharing a cache directory do not collide.
+    pub fn new(
+        target: Arc<dyn ObjectStore>,
+        store_prefix: impl Into<String>,
+        cache: Arc<DiskCache>,
+    ) -> Self {
+        Self {
+            target,
+            store_prefix: store_prefix.into(),
+            cache,
+            metrics: None,
+        }
+    }
+
+    /// Report cache hits and misses to `metrics`.
+    pub fn with_metrics(self, metrics: Arc<dyn IoMetricsRecorder>) -> Self {
+        Self {
+            metrics: Some(metrics),
+            ..self
+        }
+    }
+
+    pub fn cache(&self) -> &Arc<DiskCache> {
+        &self.cache
+    }
+
+    /// Identifies the object in the cache files, across the stores sharing them.
+    fn object_key(&self, location: &Path) -> String {
+        format!("{}/{}", self.store_prefix, location)
+    }
+
+    async fn lookup(&self, location: &Path, range: &Range<u64>) -> Option<(ObjectMeta, Bytes)> {
+        let object_key = self.object_key(location);
+        let name = DiskCache::file_name(&object_key, range);
+        let entry = self.cache.get(&name, &object_key, range).await;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_cache_access(DISK_CACHE_METRICS_NAME, entry.is_some());
+        }
+        entry
+    }
+
+    async fn store(&self, location: &Path, range: &Range<u64>, meta: &ObjectMeta, data: Bytes) {
+        let object_key = self.object_key(location);
+        let name = DiskCache::file_name(&object_key, range);
+        self.cache
+            .put(name, object_key, range.clone(), meta, data)
+            .await;
+    }
+
+    fn is_cacheable(&self, location: &Path, range: &Range<u64>) -> bool {
+        range.end > range.start
+            && range.end - range.start <= self.cache.config.max_range_bytes
+            && is_immutable(location)
+    }
+
+    fn cacheable_range(&self, location: &Path, options: &GetOptions) -> Option<Range<u64>> {
+        let plain = options.if_match.is_none()
+            && options.if_none_match.is_none()
+            && options.if_modified_since.is_none()
+            && options.if_unmodified_since.is_none()
+            && options.version.is_none()
+            && !options.head;
+        match &options.range {
+            Some(GetRange::Bounded(range)) if plain && self.is_cacheable(location, range) => {
+                Some(range.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether the file is one Lance never modifies: a file directly inside the
+/// `data` or `_deletions` directory of a dataset, or inside an index directory.
+///
+/// Only the directory right above the file counts, so a dataset stored under a
+/// `.../data/...` prefix doesn't make its manifests look immutable.
+/// Manifests are excluded outright since `_latest.manifest` is rewritten.
+fn is_immutable(location: &Path) -> bool {
+    let parts = location.parts().collect::<Vec<_>>();
+    let Some(file_name) = parts.last() else {
+        return false;
+    };
+    if file_name.as_ref().ends_with(".manifest") {
+        return false;
+    }
+    IMMUTABLE_DIRS
+        .iter()
+        .any(|(dir, depth)| parts.len() > *depth && parts[parts.len() - 1 - depth].as_ref() == *dir)
+}
+
+#[async_trait]
+impl ObjectStore for DiskCachedStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> OSResult<PutResult> {
+        self.target.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> OSResult<Box<dyn MultipartUpload>> {
+        self.target.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
+        let Some(range) = self.cacheable_range(location, &options) else {
+            return self.target.get_opts(location, options).await;
+        };
+        if let Some((mut meta, data)) = self.lookup(location, &range).await {
+            meta.location = location.clone();
+            return Ok(GetResult {
+                payload: GetResultPayload::Stream(stream::once(async move { Ok(data) }).boxed()),
+                meta,
+                range,
+                attributes: Attributes::default(),
+            });
+        }
+
+        let result = self.target.get_opts(location, options).await?;
+        let meta = result.meta.clone();
+        let attributes = result.attributes.clone();
+        let requested = range;
+        let range = result.range.clone();
+        let data = result.bytes().await?;
+        // Ranges past the end of the object are cut short, only cache complete ranges
+        if range == requested {
+            self.store(location, &range, &meta, data.clone()).await;
+        }
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream::once(async move { Ok(data) }).boxed()),
+            meta,
+            range,
+            attributes,
+        })
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> OSResult<Vec<Bytes>> {
+        let mut results = vec![None; ranges.len()];
+        let mut missing = Vec::new();
+        for (i, range) in ranges.iter().enumerate() {
+            if self.is_cacheable(location, range)
+                && let Some((_, data)) = self.lookup(location, range).await
+            {
+                results[i] = Some(data);
+                continue;
+            }
+            missing.push(i);
+        }
+        if !missing.is_empty() {
+            let missing_ranges = missing
+                .iter()
+                .map(|i| ranges[*i].clone())
+                .collect::<Vec<_>>();
+            let fetched = self.target.get_ranges(location, &missing_ranges).await?;
+            // The object size is only needed for cache hits served through get_opts,
+            // so look it up lazily and only if something will be cached.
+            let mut meta = None;
+            for (i, data) in missing.into_iter().zip(fetched) {
+                let range = &ranges[i];
+                if self.is_cacheable(location, range)
+                    && data.len() as u64 == range.end - range.start
+                {
+                    if meta.is_none() {
+                        meta = Some(self.target.head(location).await?);
+                    }
+                    self.store(location, range, meta.as_ref().unwrap(), data.clone())
+                        .await;
+                }
+                results[i] = Some(data);
+            }
+        }
+        Ok(results.into_iter().map(Option::unwrap).collect())
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, OSResult<Path>>,
+    ) -> BoxStream<'static, OSResult<Path>> {
+        self.target.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.target.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.target.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
+        self.target.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, opts: CopyOptions) -> OSResult<()> {
+        self.target.copy_opts(from, to, opts).await
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, opts: RenameOptions) -> OSResult<()> {
+        self.target.rename_opts(from, to, opts).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use object_store::memory::InMemory;
+
+    use crate::object_store::WrappingObjectStore;
+    use crate::utils::tracking_store::IOTracker;
+
+    fn make_store(dir: &std::path::Path, max_bytes: u64) -> (DiskCachedStore, IOTracker) {
+        let tracker = IOTracker::default();
+        let target = tracker.wrap("", Arc::new(InMemory::new()));
+        let cache = DiskCache::open(DiskCacheConfig::new(dir).with_max_bytes(max_bytes)).unwrap();
+        (DiskCachedStore::new(target, "memory$test", cache), tracker)
+    }
+
+    #[tokio::test]
+    async fn test_cached_range_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, tracker) = make_store(dir.path(), 1024 * 1024);
+        let data_path = Path::from("table/data/file.lance");
+        let manifest_path = Path::from("table/_versions/1.manifest");
+        let data = Bytes::from((0..=255u8).cycle().take(4096).collect::<Vec<_>>());
+        store.put(&data_path, data.clone().into()).await.unwrap();
+        store
+            .put(&manifest_path, data.clone().into())
+            .await
+            .unwrap();
+
+        let before = tracker.incremental_stats().read_iops;
+        assert_eq!(before, 0);
+        for _ in 0..3 {
+            let bytes = store.get_range(&data_path, 100..612).await.unwrap();
+            assert_eq!(bytes, data.slice(100..612));
+            let bytes = store.get_range(&manifest_path, 100..612).await.unwrap();
+            assert_eq!(bytes, data.slice(100..612));
+        }
+        // The data file is read once, the manifest is never cached
+        assert_eq!(tracker.incremental_stats().read_iops, 4);
+
+        let result = store
+            .get_opts(&data_path, GetOptions::new().with_range(Some(100..612)))
+            .await
+            .unwrap();
+        assert_eq!(result.meta.size, 4096);
+        assert_eq!(result.meta.location, data_path);
+        assert_eq!(result.range, 100..612);
+
+        let ranges = store
+            .get_ranges(&data_path, &[100..612, 0..10])
+            .await
+            .unwrap();
+        assert_eq!(ranges[0], data.slice(100..612));
+        assert_eq!(ranges[1], data.slice(0..10));
+        let stats = store.cache().stats();
+        assert_eq!(stats.num_entries, 2);
+        assert!(stats.hits >= 3);
+    }
+
+    #[tokio::test]
+    async fn test_eviction_removes_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, _) = make_store(dir.path(), 4096);
+        let path = Path::from("data/file.lance");
+        let data = Bytes::from(vec![7u8; 16 * 1024]);
+        store.put(&path, data.into()).await.unwrap();
+
+        for i in 0..16 {
+            store
+                .get_range(&path, i * 1024..(i + 1) * 1024)
+                .await
+                .unwrap();
+        }
+        let stats = store.cache().stats();
+        assert!(stats.size_bytes <= 4096, "{stats:?}");
+        let num_files = std::fs::read_dir(dir.path()).unwrap().count() as u64;
+        assert_eq!(num_files, stats.num_entries);
+    }
+
+    #[test]
+    fn test_immutable_paths() {
+        for path in [
+            "table/data/file.lance",
+            "data/file.lance",
+            "table/_deletions/0-1-2.arrow",
+            "table/_indices/1234-abcd/index.idx",
+            // The dataset root is under a directory named `data`
+            "bucket/data/table/data/file.lance",
+        ] {
+            assert!(is_immutable(&Path::from(path)), "{path}");
+        }
+        for path in [
+            "bucket/data/table/_versions/1.manifest",
+            "bucket/data/table/_latest.manifest",
+            "bucket/data/_latest.manifest",
+            "bucket/data/table/_transactions/1-abc.txn",
+            "table/data/nested/file.lance",
+            "bucket/_indices/table/_versions/2.manifest",
+        ] {
+            assert!(!is_immutable(&Path::from(path)), "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entries_are_verified_on_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::open(DiskCacheConfig::new(dir.path())).unwrap();
+        let meta = ObjectMeta {
+            location: Path::from("data/a.lance"),
+            last_modified: Utc::now(),
+            size: 100,
+            e_tag: None,
+            version: None,
+        };
+        let range = 0..4;
+        let name = DiskCache::file_name("store/data/a.lance", &range);
+        cache
+            .put(
+                name.clone(),
+                "store/data/a.lance".to_string(),
+                range.clone(),
+                &meta,
+                Bytes::from_static(b"abcd"),
+            )
+            .await;
+
+        let (read_meta, data) = cache
+            .get(&name, "store/data/a.lance", &range)
+            .await
+            .unwrap();
+        assert_eq!(data, Bytes::from_static(b"abcd"));
+        assert_eq!(read_meta.size, 100);
+        // A different object or range stored under the same name is not returned
+        assert!(
+            cache
+                .get(&name, "store/data/b.lance", &range)
+                .await
+                .is_none()
+        );
+        assert!(
+            cache
+                .get(&name, "store/data/a.lance", &(0..3))
+                .await
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_config_from_storage_options() {
+        assert_eq!(DiskCacheConfig::from_storage_options(None).unwrap(), None);
+        let options = HashMap::from([
+            (
+                DISK_CACHE_DIR_KEY.to_string(),
+                "/tmp/lance-cache".to_string(),
+            ),
+            (DISK_CACHE_MAX_BYTES_KEY.to_string(), "1024".to_string()),
+        ]);
+        let config = DiskCacheConfig::from_storage_options(Some(&options))
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.directory, PathBuf::from("/tmp/lance-cache"));
+        assert_eq!(config.max_bytes, 1024);
+        assert_eq!(config.max_range_bytes, DEFAULT_MAX_RANGE_BYTES);
+
+        let options = HashMap::from([
+            (
+                DISK_CACHE_DIR_KEY.to_string(),
+                "/tmp/lance-cache".to_string(),
+            ),
+            (DISK_CACHE_MAX_BYTES_KEY.to_string(), "lots".to_string()),
+        ]);
+        assert!(DiskCacheConfig::from_storage_options(Some(&options)).is_err());
+    }
+}
@@ -1,14 +1,23 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use futures::stream::{self, BoxStream};
 use lance_core::error::{Error, Result};
-use object_store::{CredentialProvider, Result as ObjectStoreResult};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, CredentialProvider, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore as OSObjectStore, PutMultipartOptions, PutOptions, PutPayload,
+    PutResult, RenameOptions, Result as ObjectStoreResult,
+};
 
 use crate::object_store::{StorageOptionsAccessor, StorageOptionsProvider};
 
@@ -141,6 +150,261 @@ where
     }
 }
 
+/// Wraps an object store whose credentials come from a [`StorageOptionsAccessor`]
+/// and retries requests that are rejected because the credentials expired.
+///
+/// The accessor refreshes credentials ahead of their advertised expiration, but
+/// tokens can still lapse early (e.g. clock skew or a revoked session).  When a
+/// request fails with an authentication error, the cached credentials are
+/// invalidated and the request is retried once with fresh credentials.
+///
+/// Listings are only retried if the first page is rejected, and deletes are
+/// retried for the paths that were not deleted yet.
+#[derive(Debug)]
+pub struct CredentialRefreshStore {
+    target: Arc<dyn OSObjectStore>,
+    accessor: Arc<StorageOptionsAccessor>,
+}
+
+impl CredentialRefreshStore {
+    /// Wrap `target` if the accessor has a provider to refresh credentials from.
+    pub fn wrap(
+        target: Arc<dyn OSObjectStore>,
+        accessor: Option<Arc<StorageOptionsAccessor>>,
+    ) -> Arc<dyn OSObjectStore> {
+        match accessor.filter(|accessor| accessor.has_provider()) {
+            Some(accessor) => Arc::new(Self { target, accessor }),
+            None => target,
+        }
+    }
+
+    async fn with_refresh<T, F, Fut>(&self, request: F) -> ObjectStoreResult<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = ObjectStoreResult<T>>,
+    {
+        match request().await {
+            Err(err) if is_expired_credential_error(&err) => {
+                log::debug!(
+                    "Request failed with an authentication error, refreshing credentials: {err}"
+                );
+                self.accessor.invalidate().await;
+                request().await
+            }
+            result => result,
+        }
+    }
+
+    /// Like [`Self::with_refresh`] for a listing
+    ///
+    /// Only an error on the first item is retried, retrying after items were
+    /// returned would return them twice.
+    fn list_with_refresh<F>(&self, request: F) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>>
+    where
+        F: Fn() -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> + Send + 'static,
+    {
+        let accessor = self.accessor.clone();
+        stream::once(async move {
+            let mut items = request();
+            match items.next().await {
+                Some(Err(err)) if is_expired_credential_error(&err) => {
+                    log::debug!(
+                        "Listing failed with an authentication error, refreshing credentials: {err}"
+                    );
+                    accessor.invalidate().await;
+                    request()
+                }
+                Some(first) => stream::once(async move { first }).chain(items).boxed(),
+                None => stream::empty().boxed(),
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
+/// The max number of paths to delete before checking whether credentials must
+/// be refreshed, matches the S3 bulk delete limit.
+const DELETE_CHUNK_SIZE: usize = 1000;
+
+/// Delete `paths`, retrying the paths that were not deleted once if any of the
+/// deletes was rejected because the credentials expired.
+async fn delete_with_refresh(
+    target: Arc<dyn OSObjectStore>,
+    accessor: Arc<StorageOptionsAccessor>,
+    paths: Vec<Path>,
+) -> Vec<ObjectStoreResult<Path>> {
+    let delete = |paths: Vec<Path>| {
+        target
+            .delete_stream(stream::iter(paths.into_iter().map(Ok)).boxed())
+            .collect::<Vec<_>>()
+    };
+    let results = delete(paths.clone()).await;
+    let Some(err) = results.iter().find_map(|result| {
+        result
+            .as_ref()
+            .err()
+            .filter(|err| is_expired_credential_error(err))
+    }) else {
+        return results;
+    };
+    log::debug!("Delete failed with an authentication error, refreshing credentials: {err}");
+    accessor.invalidate().await;
+
+    let deleted = results
+        .into_iter()
+        .filter_map(ObjectStoreResult::ok)
+        .collect::<HashSet<_>>();
+    let remaining = paths
+        .into_iter()
+        .filter(|path| !deleted.contains(path))
+        .collect();
+    let mut results = deleted.into_iter().map(Ok).collect::<Vec<_>>();
+    results.extend(delete(remaining).await);
+    results
+}
+
+/// Whether the error indicates that the credentials of the request expired.
+///
+/// Other permission errors, e.g. a policy that denies access to the object, are
+/// not retried since fresh credentials would be rejected as well.
+fn is_expired_credential_error(err: &object_store::Error) -> bool {
+    match err {
+        object_store::Error::Unauthenticated { .. } => true,
+        // S3 rejects expired session tokens with a 400 `ExpiredToken` error,
+        // other stores with a 403
+        object_store::Error::PermissionDenied { source, .. }
+        | object_store::Error::Generic { source, .. } => {
+            is_expired_token_message(&source.to_string())
+        }
+        _ => false,
+    }
+}
+
+fn is_expired_token_message(message: &str) -> bool {
+    const EXPIRED_TOKEN_MESSAGES: [&str; 4] = [
+        "expiredtoken",
+        "tokenexpired",
+        "token has expired",
+        "token is expired",
+    ];
+    let message = message.to_lowercase();
+    EXPIRED_TOKEN_MESSAGES
+        .iter()
+        .any(|expired| message.contains(expired))
+}
+
+impl fmt::Display for CredentialRefreshStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CredentialRefresh({})", self.target)
+    }
+}
+
+#[async_trait]
+#[deny(clippy::missing_trait_methods)]
+impl OSObjectStore for CredentialRefreshStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> ObjectStoreResult<PutResult> {
+        self.with_refresh(|| {
+            self.target
+                .put_opts(location, payload.clone(), opts.clone())
+        })
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+        self.with_refresh(|| self.target.put_multipart_opts(location, opts.clone()))
+            .await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> ObjectStoreResult<GetResult> {
+        self.with_refresh(|| self.target.get_opts(location, options.clone()))
+            .await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> ObjectStoreResult<Vec<Bytes>> {
+        self.with_refresh(|| self.target.get_ranges(location, ranges))
+            .await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, ObjectStoreResult<Path>>,
+    ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+        let target = self.target.clone();
+        let accessor = self.accessor.clone();
+        locations
+            .ready_chunks(DELETE_CHUNK_SIZE)
+            .then(move |locations| {
+                let target = target.clone();
+                let accessor = accessor.clone();
+                async move {
+                    let mut results = Vec::new();
+                    let mut paths = Vec::with_capacity(locations.len());
+                    for location in locations {
+                        match location {
+                            Ok(path) => paths.push(path),
+                            Err(err) => results.push(Err(err)),
+                        }
+                    }
+                    results.extend(delete_with_refresh(target, accessor, paths).await);
+                    results
+                }
+            })
+            .flat_map(stream::iter)
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let target = self.target.clone();
+        let prefix = prefix.cloned();
+        self.list_with_refresh(move || target.list(prefix.as_ref()))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+        let target = self.target.clone();
+        let prefix = prefix.cloned();
+        let offset = offset.clone();
+        self.list_with_refresh(move || target.list_with_offset(prefix.as_ref(), &offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> ObjectStoreResult<ListResult> {
+        self.with_refresh(|| self.target.list_with_delimiter(prefix))
+            .await
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, opts: CopyOptions) -> ObjectStoreResult<()> {
+        self.with_refresh(|| self.target.copy_opts(from, to, opts.clone()))
+            .await
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        opts: RenameOptions,
+    ) -> ObjectStoreResult<()> {
+        self.with_refresh(|| self.target.rename_opts(from, to, opts.clone()))
+            .await
+    }
+}
+
 fn missing_dynamic_credential(kind: &str) -> Error {
     Error::invalid_input(format!(
         "Missing required {kind} credential fields in dynamic storage options"
@@ -408,4 +672,247 @@ mod tests {
 
         assert_eq!(credentials.bearer, "gcp-token");
     }
+
+    // Rejects requests until the accessor vends the `fresh` token
+    #[derive(Debug)]
+    struct TokenCheckingStore {
+        accessor: Arc<StorageOptionsAccessor>,
+        target: Arc<object_store::memory::InMemory>,
+    }
+
+    impl fmt::Display for TokenCheckingStore {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "TokenCheckingStore")
+        }
+    }
+
+    impl TokenCheckingStore {
+        fn new(accessor: Arc<StorageOptionsAccessor>) -> Self {
+            Self {
+                accessor,
+                target: Arc::new(object_store::memory::InMemory::new()),
+            }
+        }
+
+        async fn check(&self, location: &Path) -> ObjectStoreResult<()> {
+            check_token(&self.accessor, location).await
+        }
+    }
+
+    async fn check_token(
+        accessor: &StorageOptionsAccessor,
+        location: &Path,
+    ) -> ObjectStoreResult<()> {
+        let options = accessor.get_storage_options().await.unwrap();
+        if options.get("token").map(String::as_str) == Some("fresh") {
+            Ok(())
+        } else {
+            Err(object_store::Error::Unauthenticated {
+                path: location.to_string(),
+                source: "token expired".into(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl OSObjectStore for TokenCheckingStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> ObjectStoreResult<PutResult> {
+            self.check(location).await?;
+            self.target.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOptions,
+        ) -> ObjectStoreResult<Box<dyn MultipartUpload>> {
+            self.target.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            options: GetOptions,
+        ) -> ObjectStoreResult<GetResult> {
+            self.check(location).await?;
+            self.target.get_opts(location, options).await
+        }
+
+        fn delete_stream(
+            &self,
+            locations: BoxStream<'static, ObjectStoreResult<Path>>,
+        ) -> BoxStream<'static, ObjectStoreResult<Path>> {
+            use object_store::ObjectStoreExt;
+
+            let accessor = self.accessor.clone();
+            let target = self.target.clone();
+            locations
+                .then(move |location| {
+                    let accessor = accessor.clone();
+                    let target = target.clone();
+                    async move {
+                        let location = location?;
+                        check_token(&accessor, &location).await?;
+                        target.delete(&location).await?;
+                        Ok(location)
+                    }
+                })
+                .boxed()
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, ObjectStoreResult<ObjectMeta>> {
+            let accessor = self.accessor.clone();
+            let items = self.target.list(prefix);
+            stream::once(async move {
+                match check_token(&accessor, &Path::default()).await {
+                    Ok(()) => items,
+                    Err(err) => stream::iter([Err(err)]).boxed(),
+                }
+            })
+            .flatten()
+            .boxed()
+        }
+
+        async fn list_with_delimiter(
+            &self,
+            prefix: Option<&Path>,
+        ) -> ObjectStoreResult<ListResult> {
+            self.target.list_with_delimiter(prefix).await
+        }
+
+        async fn copy_opts(
+            &self,
+            from: &Path,
+            to: &Path,
+            opts: CopyOptions,
+        ) -> ObjectStoreResult<()> {
+            self.target.copy_opts(from, to, opts).await
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RotatingProvider {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl StorageOptionsProvider for RotatingProvider {
+        async fn fetch_storage_options(&self) -> Result<Option<HashMap<String, String>>> {
+            let calls = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let token = if calls == 0 { "stale" } else { "fresh" };
+            // No expiration, the token lapses without the provider saying so
+            Ok(Some(HashMap::from([(
+                "token".to_string(),
+                token.to_string(),
+            )])))
+        }
+
+        fn provider_id(&self) -> String {
+            "RotatingProvider".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_credential_refresh_store() {
+        use object_store::ObjectStoreExt;
+
+        let provider = Arc::new(RotatingProvider::default());
+        let accessor = Arc::new(StorageOptionsAccessor::with_provider(provider.clone()));
+        let inner = Arc::new(TokenCheckingStore::new(accessor.clone()));
+
+        // Without a provider the store is not wrapped
+        let static_accessor = Arc::new(StorageOptionsAccessor::with_static_options(HashMap::new()));
+        let store = CredentialRefreshStore::wrap(inner.clone(), Some(static_accessor));
+        assert_eq!(store.to_string(), "TokenCheckingStore");
+
+        // The first request is rejected with the stale token, then retried
+        let store = CredentialRefreshStore::wrap(inner, Some(accessor));
+        let path = Path::from("data");
+        store.put(&path, "hello".into()).await.unwrap();
+        assert_eq!(
+            store.get(&path).await.unwrap().bytes().await.unwrap(),
+            Bytes::from_static(b"hello")
+        );
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Other errors are not retried
+        let err = store.get(&Path::from("missing")).await.unwrap_err();
+        assert!(matches!(err, object_store::Error::NotFound { .. }));
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_credential_refresh_store_streams() {
+        use futures::TryStreamExt;
+        use object_store::ObjectStoreExt;
+
+        let new_store = || {
+            let provider = Arc::new(RotatingProvider::default());
+            let accessor = Arc::new(StorageOptionsAccessor::with_provider(provider.clone()));
+            let inner = Arc::new(TokenCheckingStore::new(accessor.clone()));
+            (
+                CredentialRefreshStore::wrap(inner.clone(), Some(accessor)),
+                inner,
+                provider,
+            )
+        };
+        let paths = [Path::from("a"), Path::from("b")];
+
+        // Listings are retried when the first page is rejected
+        let (store, inner, provider) = new_store();
+        for path in &paths {
+            inner.target.put(path, "hello".into()).await.unwrap();
+        }
+        let listed = store.list(None).try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Deletes are retried for the paths that were rejected
+        let (store, inner, provider) = new_store();
+        for path in &paths {
+            inner.target.put(path, "hello".into()).await.unwrap();
+        }
+        let locations = stream::iter(paths.clone().map(Ok)).boxed();
+        let mut deleted = store
+            .delete_stream(locations)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        deleted.sort();
+        assert_eq!(deleted, paths);
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(inner.target.list(None).next().await.is_none());
+    }
+
+    #[test]
+    fn test_is_expired_credential_error() {
+        let unauthenticated = object_store::Error::Unauthenticated {
+            path: "a".to_string(),
+            source: "invalid token".into(),
+        };
+        assert!(is_expired_credential_error(&unauthenticated));
+
+        let expired = object_store::Error::PermissionDenied {
+            path: "a".to_string(),
+            source: "The provided token has expired".into(),
+        };
+        assert!(is_expired_credential_error(&expired));
+
+        let denied = object_store::Error::PermissionDenied {
+            path: "a".to_string(),
+            source: "Access Denied".into(),
+        };
+        assert!(!is_expired_credential_error(&denied));
+
+        let generic = object_store::Error::Generic {
+            store: "S3",
+            source: "ExpiredToken: The provided token has expired".into(),
+        };
+        assert!(is_expired_credential_error(&generic));
+    }
 }
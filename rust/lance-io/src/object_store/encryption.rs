@@ -0,0 +1,1039 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Client-side encryption for object stores.
+//!
+//! [`EncryptedObjectStore`] encrypts objects before they are written and decrypts
+//! them when they are read, so the object store only ever sees ciphertext.  The
+//! rest of Lance is unaware of the encryption: sizes reported by `head` and `list`
+//! and the ranges of ranged reads are all in terms of the plaintext.
+//!
+//! Objects use envelope encryption.  The data is encrypted with AES-256-GCM under a
+//! data key, and the data key is itself encrypted by a [`KeyProvider`] (for example
+//! a KMS).  The encrypted data key and the id of the key that encrypted it are
+//! stored in the header of every object, so objects stay readable after the key
+//! provider rotates to a new key.
+//!
+//! A store generates a single data key, but every object is encrypted with its own
+//! key derived from the data key and a random object id (HKDF-SHA256), so nonces
+//! never repeat across objects and chunks of one object cannot be spliced into
+//! another.  Every chunk is also authenticated together with a digest of the
+//! header, so the header cannot be altered either.  The object path is not
+//! authenticated, objects can be copied and renamed.
+//!
+//! An encrypted object has the layout
+//!
+//! ```text
+//! | header (HEADER_SIZE bytes) | chunk 0 | chunk 1 | ... | final chunk |
+//! ```
+//!
+//! The plaintext is split into chunks of [`CHUNK_SIZE`] bytes that are encrypted
+//! separately, so a ranged read only fetches and decrypts the chunks it overlaps.
+//! The final chunk holds the remainder of the plaintext (possibly nothing) and is
+//! authenticated as final, which detects truncated objects.  Since the overhead is
+//! fixed, the plaintext size can be computed from the stored size alone.
+//!
+//! The store is installed with [`EncryptionWrapper`] through
+//! [`ObjectStoreParams::object_store_wrapper`](super::ObjectStoreParams::object_store_wrapper).
+//! Local datasets must use the `file-object-store` scheme, since the `file` scheme
+//! reads and writes local files without going through the object store.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, stream};
+use lance_core::{Error, Result};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetRange, GetResult, GetResultPayload, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as OSResult, UploadPart,
+};
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::digest::{SHA256, SHA256_OUTPUT_LEN, digest};
+use ring::hkdf::{self, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use tokio::sync::OnceCell;
+
+use super::WrappingObjectStore;
+
+/// Size of the header stored in front of every encrypted object.
+pub const HEADER_SIZE: u64 = 1024;
+/// Size of the plaintext chunks that are encrypted separately.
+pub const CHUNK_SIZE: u64 = 64 * 1024;
+/// Size of the data keys, in bytes.
+pub const DATA_KEY_SIZE: usize = 32;
+
+const MAGIC: &[u8; 4] = b"LNCE";
+const FORMAT_VERSION: u8 = 1;
+const TAG_SIZE: u64 = 16;
+const OBJECT_ID_SIZE: usize = 32;
+/// HKDF info of the keys of individual objects
+const OBJECT_KEY_INFO: &[u8] = b"lance object key";
+/// Offset of the key id and the encrypted data key within the header. Preceded by
+/// the magic, the version, 3 reserved bytes, the object id and the two lengths.
+const KEY_INFO_OFFSET: usize = 4 + 4 + OBJECT_ID_SIZE + 2 + 2;
+/// Max # of unwrapped data keys (and object headers) kept in memory.
+const KEY_CACHE_SIZE: u64 = 1024;
+
+const STORE_NAME: &str = "EncryptedObjectStore";
+
+/// A data key generated by a [`KeyProvider`]
+pub struct DataKey {
+    /// The AES-256 key objects are encrypted with
+    pub key: [u8; DATA_KEY_SIZE],
+    /// The id of the key that encrypted `encrypted_key`
+    pub key_id: String,
+    /// `key`, encrypted by the key provider
+    pub encrypted_key: Vec<u8>,
+}
+
+impl Debug for DataKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataKey")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Creates and decrypts the data keys of an [`EncryptedObjectStore`]
+///
+/// This is typically backed by a KMS.  The key id and encrypted key returned by
+/// [`Self::generate_data_key`] are stored in plain text in the header of every
+/// object and passed back to [`Self::decrypt_data_key`] when the object is read.
+#[async_trait]
+pub trait KeyProvider: Debug + Send + Sync {
+    /// Generate a new data key
+    async fn generate_data_key(&self) -> Result<DataKey>;
+
+    /// Decrypt a data key previously returned by [`Self::generate_data_key`]
+    async fn decrypt_data_key(
+        &self,
+        key_id: &str,
+        encrypted_key: &[u8],
+    ) -> Result<[u8; DATA_KEY_SIZE]>;
+}
+
+/// A [`KeyProvider`] that encrypts data keys with master keys held in memory
+///
+/// New data keys are encrypted with the current master key.  Previous master keys
+/// can be added with [`Self::with_previous_key`] so that objects written before a
+/// key rotation stay readable.
+pub struct LocalKeyProvider {
+    current_key_id: String,
+    master_keys: HashMap<String, LessSafeKey>,
+    rng: SystemRandom,
+}
+
+impl Debug for LocalKeyProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalKeyProvider")
+            .field("current_key_id", &self.current_key_id)
+            .field("key_ids", &self.master_keys.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl LocalKeyProvider {
+    pub fn new(key_id: impl Into<String>, master_key: [u8; DATA_KEY_SIZE]) -> Self {
+        let current_key_id = key_id.into();
+        Self {
+            master_keys: HashMap::from([(current_key_id.clone(), aes_key(&master_key))]),
+            current_key_id,
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Also decrypt data keys that were encrypted with `master_key`
+    pub fn with_previous_key(
+        mut self,
+        key_id: impl Into<String>,
+        master_key: [u8; DATA_KEY_SIZE],
+    ) -> Self {
+        self.master_keys
+            .entry(key_id.into())
+            .or_insert_with(|| aes_key(&master_key));
+        self
+    }
+}
+
+#[async_trait]
+impl KeyProvider for LocalKeyProvider {
+    async fn generate_data_key(&self) -> Result<DataKey> {
+        let mut key = [0; DATA_KEY_SIZE];
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut key)
+            .and_then(|_| self.rng.fill(&mut nonce))
+            .map_err(|_| Error::internal("failed to generate a random data key"))?;
+
+        // The encrypted key is the nonce followed by the sealed data key
+        let mut encrypted_key = key.to_vec();
+        self.master_keys[&self.current_key_id]
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(self.current_key_id.as_bytes()),
+                &mut encrypted_key,
+            )
+            .map_err(|_| Error::internal("failed to encrypt data key"))?;
+        encrypted_key.splice(0..0, nonce);
+
+        Ok(DataKey {
+            key,
+            key_id: self.current_key_id.clone(),
+            encrypted_key,
+        })
+    }
+
+    async fn decrypt_data_key(
+        &self,
+        key_id: &str,
+        encrypted_key: &[u8],
+    ) -> Result<[u8; DATA_KEY_SIZE]> {
+        let master_key = self
+            .master_keys
+            .get(key_id)
+            .ok_or_else(|| Error::invalid_input(format!("unknown master key id '{key_id}'")))?;
+        if encrypted_key.len() < NONCE_LEN {
+            return Err(Error::invalid_input("encrypted data key is too short"));
+        }
+        let (nonce, sealed) = encrypted_key.split_at(NONCE_LEN);
+        let mut sealed = sealed.to_vec();
+        let key = master_key
+            .open_in_place(
+                Nonce::try_assume_unique_for_key(nonce).unwrap(),
+                Aad::from(key_id.as_bytes()),
+                &mut sealed,
+            )
+            .map_err(|_| {
+                Error::invalid_input(format!(
+                    "failed to decrypt data key with master key '{key_id}'"
+                ))
+            })?;
+        key.try_into()
+            .map_err(|_| Error::invalid_input("decrypted data key has the wrong size"))
+    }
+}
+
+/// Wraps object stores with an [`EncryptedObjectStore`]
+#[derive(Debug)]
+pub struct EncryptionWrapper {
+    key_provider: Arc<dyn KeyProvider>,
+}
+
+impl EncryptionWrapper {
+    pub fn new(key_provider: Arc<dyn KeyProvider>) -> Self {
+        Self { key_provider }
+    }
+}
+
+impl WrappingObjectStore for EncryptionWrapper {
+    fn wrap(&self, _store_prefix: &str, original: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        Arc::new(EncryptedObjectStore::new(
+            original,
+            self.key_provider.clone(),
+        ))
+    }
+}
+
+/// The data key new objects are encrypted with
+struct WriteKey {
+    data_key: Arc<hkdf::Salt>,
+    key_id: String,
+    encrypted_key: Vec<u8>,
+}
+
+/// What is needed to decrypt the chunks of an object
+struct ObjectHeader {
+    cipher: LessSafeKey,
+    digest: [u8; SHA256_OUTPUT_LEN],
+}
+
+impl ObjectHeader {
+    /// Decrypt `data`, which holds the chunks of an object of `size` plaintext bytes
+    /// starting at chunk `first`.  Returns `None` if any chunk fails authentication.
+    fn decrypt(&self, size: u64, first: u64, data: &[u8]) -> Option<Vec<u8>> {
+        let final_chunk = size / CHUNK_SIZE;
+        let mut plaintext = Vec::with_capacity(data.len());
+        let mut remaining = data;
+        let mut index = first;
+        while !remaining.is_empty() && index <= final_chunk {
+            let chunk_size = if index == final_chunk {
+                size - index * CHUNK_SIZE
+            } else {
+                CHUNK_SIZE
+            };
+            let stored_size = ((chunk_size + TAG_SIZE) as usize).min(remaining.len());
+            let (chunk, rest) = remaining.split_at(stored_size);
+            let start = plaintext.len();
+            plaintext.extend_from_slice(chunk);
+            let opened = self
+                .cipher
+                .open_in_place(
+                    chunk_nonce(index),
+                    chunk_aad(&self.digest, index == final_chunk),
+                    &mut plaintext[start..],
+                )
+                .ok()?
+                .len();
+            plaintext.truncate(start + opened);
+            remaining = rest;
+            index += 1;
+        }
+        Some(plaintext)
+    }
+}
+
+/// Encrypts the chunks of one object
+struct ChunkEncryptor {
+    header: Vec<u8>,
+    object: Arc<ObjectHeader>,
+    next_chunk: u64,
+}
+
+impl ChunkEncryptor {
+    fn new(key: &WriteKey, object_id: [u8; OBJECT_ID_SIZE]) -> Self {
+        let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&[FORMAT_VERSION, 0, 0, 0]);
+        header.extend_from_slice(&object_id);
+        header.extend_from_slice(&(key.key_id.len() as u16).to_le_bytes());
+        header.extend_from_slice(&(key.encrypted_key.len() as u16).to_le_bytes());
+        header.extend_from_slice(key.key_id.as_bytes());
+        header.extend_from_slice(&key.encrypted_key);
+        header.resize(HEADER_SIZE as usize, 0);
+        let object = Arc::new(ObjectHeader {
+            cipher: object_key(&key.data_key, &object_id),
+            digest: header_digest(&header),
+        });
+        Self {
+            header,
+            object,
+            next_chunk: 0,
+        }
+    }
+
+    fn header(&self) -> Vec<u8> {
+        self.header.clone()
+    }
+
+    fn object_header(&self) -> Arc<ObjectHeader> {
+        self.object.clone()
+    }
+
+    /// Encrypt the next chunk and append it to `out`
+    fn seal(&mut self, chunk: &[u8], is_final: bool, out: &mut Vec<u8>) -> OSResult<()> {
+        let start = out.len();
+        out.extend_from_slice(chunk);
+        let tag = self
+            .object
+            .cipher
+            .seal_in_place_separate_tag(
+                chunk_nonce(self.next_chunk),
+                chunk_aad(&self.object.digest, is_final),
+                &mut out[start..],
+            )
+            .map_err(|_| encryption_error("failed to encrypt chunk"))?;
+        out.extend_from_slice(tag.as_ref());
+        self.next_chunk += 1;
+        Ok(())
+    }
+
+    /// Encrypt the full chunks at the start of `data` and append them to `out`.
+    /// Returns the # of bytes encrypted.
+    fn seal_full_chunks(&mut self, data: &[u8], out: &mut Vec<u8>) -> OSResult<usize> {
+        let num_bytes = data.len() / CHUNK_SIZE as usize * CHUNK_SIZE as usize;
+        for chunk in data[..num_bytes].chunks(CHUNK_SIZE as usize) {
+            self.seal(chunk, false, out)?;
+        }
+        Ok(num_bytes)
+    }
+}
+
+/// An [`ObjectStore`] that encrypts the objects of the wrapped store
+///
+/// See the [module documentation](self) for the format of encrypted objects.
+pub struct EncryptedObjectStore {
+    target: Arc<dyn ObjectStore>,
+    key_provider: Arc<dyn KeyProvider>,
+    /// Generated on the first write and used for every object written by this store
+    write_key: OnceCell<Arc<WriteKey>>,
+    /// Decrypted data keys, by key id and encrypted key
+    data_keys: moka::sync::Cache<(String, Vec<u8>), Arc<hkdf::Salt>>,
+    /// Headers of recently accessed objects, so that ranged reads of them only
+    /// need a single request
+    headers: moka::sync::Cache<Path, Arc<ObjectHeader>>,
+    rng: SystemRandom,
+}
+
+impl Debug for EncryptedObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedObjectStore")
+            .field("target", &self.target)
+            .field("key_provider", &self.key_provider)
+            .finish()
+    }
+}
+
+impl Display for EncryptedObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncryptedObjectStore({})", self.target)
+    }
+}
+
+impl EncryptedObjectStore {
+    pub fn new(target: Arc<dyn ObjectStore>, key_provider: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            target,
+            key_provider,
+            write_key: OnceCell::new(),
+            data_keys: moka::sync::Cache::new(KEY_CACHE_SIZE),
+            headers: moka::sync::Cache::new(KEY_CACHE_SIZE),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    async fn write_key(&self) -> OSResult<Arc<WriteKey>> {
+        self.write_key
+            .get_or_try_init(|| async {
+                let data_key = self
+                    .key_provider
+                    .generate_data_key()
+                    .await
+                    .map_err(|err| encryption_error(err.to_string()))?;
+                if KEY_INFO_OFFSET + data_key.key_id.len() + data_key.encrypted_key.len()
+                    > HEADER_SIZE as usize
+                {
+                    return Err(encryption_error(format!(
+                        "the key id and encrypted data key of key '{}' do not fit in the {HEADER_SIZE} byte object header",
+                        data_key.key_id
+                    )));
+                }
+                Ok(Arc::new(WriteKey {
+                    data_key: Arc::new(hkdf::Salt::new(HKDF_SHA256, &data_key.key)),
+                    key_id: data_key.key_id,
+                    encrypted_key: data_key.encrypted_key,
+                }))
+            })
+            .await
+            .cloned()
+    }
+
+    async fn encryptor(&self) -> OSResult<ChunkEncryptor> {
+        let key = self.write_key().await?;
+        let mut object_id = [0; OBJECT_ID_SIZE];
+        self.rng
+            .fill(&mut object_id)
+            .map_err(|_| encryption_error("failed to generate a random object id"))?;
+        Ok(ChunkEncryptor::new(&key, object_id))
+    }
+
+    async fn data_key(&self, key_id: &str, encrypted_key: &[u8]) -> OSResult<Arc<hkdf::Salt>> {
+        let cache_key = (key_id.to_string(), encrypted_key.to_vec());
+        if let Some(data_key) = self.data_keys.get(&cache_key) {
+            return Ok(data_key);
+        }
+        let key = self
+            .key_provider
+            .decrypt_data_key(key_id, encrypted_key)
+            .await
+            .map_err(|err| encryption_error(err.to_string()))?;
+        let data_key = Arc::new(hkdf::Salt::new(HKDF_SHA256, &key));
+        self.data_keys.insert(cache_key, data_key.clone());
+        Ok(data_key)
+    }
+
+    /// Read and parse the header of `location`, returning it along with the
+    /// metadata of the stored object
+    async fn read_header(
+        &self,
+        location: &Path,
+        options: &GetOptions,
+    ) -> OSResult<(Arc<ObjectHeader>, ObjectMeta)> {
+        let mut options = options.clone();
+        options.range = Some(GetRange::Bounded(0..HEADER_SIZE));
+        let result = self.target.get_opts(location, options).await?;
+        let meta = result.meta.clone();
+        let data = result.bytes().await?;
+        if data.len() < HEADER_SIZE as usize || &data[..4] != MAGIC {
+            return Err(not_encrypted_error(location));
+        }
+        if data[4] != FORMAT_VERSION {
+            return Err(encryption_error(format!(
+                "{location} was encrypted with unsupported format version {}",
+                data[4]
+            )));
+        }
+        let object_id: [u8; OBJECT_ID_SIZE] = data[8..8 + OBJECT_ID_SIZE].try_into().unwrap();
+        let lengths = &data[8 + OBJECT_ID_SIZE..KEY_INFO_OFFSET];
+        let key_id_len = u16::from_le_bytes([lengths[0], lengths[1]]) as usize;
+        let encrypted_key_len = u16::from_le_bytes([lengths[2], lengths[3]]) as usize;
+        let key_id_end = KEY_INFO_OFFSET + key_id_len;
+        let encrypted_key_end = key_id_end + encrypted_key_len;
+        if encrypted_key_end > data.len() {
+            return Err(encryption_error(format!("{location} has a corrupt header")));
+        }
+        let key_id = std::str::from_utf8(&data[KEY_INFO_OFFSET..key_id_end])
+            .map_err(|_| encryption_error(format!("{location} has a corrupt header")))?;
+        let data_key = self
+            .data_key(key_id, &data[key_id_end..encrypted_key_end])
+            .await?;
+
+        let header = Arc::new(ObjectHeader {
+            cipher: object_key(&data_key, &object_id),
+            digest: header_digest(&data[..HEADER_SIZE as usize]),
+        });
+        self.headers.insert(location.clone(), header.clone());
+        Ok((header, meta))
+    }
+
+    /// Read the plaintext `range` of `location`
+    ///
+    /// The range is clamped to the size of the object.  Returns `None` if the data
+    /// could not be decrypted with `header`.
+    async fn read_range(
+        &self,
+        location: &Path,
+        header: &ObjectHeader,
+        range: Range<u64>,
+        mut options: GetOptions,
+    ) -> OSResult<Option<GetResult>> {
+        let first = range.start / CHUNK_SIZE;
+        let last = range.end.saturating_sub(1).max(range.start) / CHUNK_SIZE;
+        options.range = Some(GetRange::Bounded(
+            chunk_offset(first)..chunk_offset(last + 1),
+        ));
+        let result = self.target.get_opts(location, options).await?;
+        let mut meta = result.meta.clone();
+        let attributes = result.attributes.clone();
+        let size = plaintext_size(location, meta.size)?;
+        let range = range.start..range.end.min(size);
+        if range.start > range.end || (range.is_empty() && size > 0) {
+            return Err(encryption_error(format!(
+                "wanted range starting at {}, but {location} was only {size} bytes long",
+                range.start
+            )));
+        }
+
+        let data = result.bytes().await?;
+        let Some(plaintext) = header.decrypt(size, first, &data) else {
+            return Ok(None);
+        };
+        let offset = first * CHUNK_SIZE;
+        let data = Bytes::from(plaintext)
+            .slice((range.start - offset) as usize..(range.end - offset) as usize);
+        meta.size = size;
+        Ok(Some(GetResult {
+            payload: GetResultPayload::Stream(stream::once(async move { Ok(data) }).boxed()),
+            meta,
+            range,
+            attributes,
+        }))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for EncryptedObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> OSResult<PutResult> {
+        let data = Bytes::from(payload);
+        let mut encryptor = self.encryptor().await?;
+        let mut out = Vec::with_capacity(encrypted_size(data.len() as u64) as usize);
+        out.extend(encryptor.header());
+        let num_bytes = encryptor.seal_full_chunks(&data, &mut out)?;
+        encryptor.seal(&data[num_bytes..], true, &mut out)?;
+
+        let result = self.target.put_opts(location, out.into(), opts).await?;
+        self.headers
+            .insert(location.clone(), encryptor.object_header());
+        Ok(result)
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> OSResult<Box<dyn MultipartUpload>> {
+        let encryptor = self.encryptor().await?;
+        let target = self.target.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(EncryptedMultipartUpload {
+            target,
+            out: encryptor.header(),
+            encryptor,
+            pending: Vec::new(),
+            location: location.clone(),
+            headers: self.headers.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
+        if options.head {
+            let mut result = self.target.get_opts(location, options).await?;
+            result.meta.size = plaintext_size(location, result.meta.size)?;
+            result.range = 0..result.meta.size;
+            return Ok(result);
+        }
+
+        if let Some(GetRange::Bounded(range)) = &options.range
+            && !range.is_empty()
+            && let Some(header) = self.headers.get(location)
+        {
+            let result = self
+                .read_range(location, &header, range.clone(), options.clone())
+                .await?;
+            if let Some(result) = result {
+                return Ok(result);
+            }
+            // The object was overwritten since its header was cached
+            self.headers.invalidate(location);
+        }
+
+        let (header, meta) = self.read_header(location, &options).await?;
+        let size = plaintext_size(location, meta.size)?;
+        let range = match &options.range {
+            Some(range) => range
+                .as_range(size)
+                .map_err(|err| encryption_error(err.to_string()))?,
+            None => 0..size,
+        };
+        self.read_range(location, &header, range, options)
+            .await?
+            .ok_or_else(|| encryption_error(format!("failed to decrypt {location}")))
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, OSResult<Path>>,
+    ) -> BoxStream<'static, OSResult<Path>> {
+        let headers = self.headers.clone();
+        self.target
+            .delete_stream(locations)
+            .map(move |location| {
+                if let Ok(location) = &location {
+                    headers.invalidate(location);
+                }
+                location
+            })
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.target.list(prefix).map(decrypt_meta).boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.target
+            .list_with_offset(prefix, offset)
+            .map(decrypt_meta)
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
+        let mut result = self.target.list_with_delimiter(prefix).await?;
+        for meta in &mut result.objects {
+            meta.size = plaintext_size(&meta.location, meta.size)?;
+        }
+        Ok(result)
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, opts: CopyOptions) -> OSResult<()> {
+        self.headers.invalidate(to);
+        self.target.copy_opts(from, to, opts).await
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, opts: RenameOptions) -> OSResult<()> {
+        self.headers.invalidate(from);
+        self.headers.invalidate(to);
+        self.target.rename_opts(from, to, opts).await
+    }
+}
+
+/// A [`MultipartUpload`] that encrypts the parts it uploads
+///
+/// Plaintext that does not fill a whole chunk is held back until the next part (or
+/// until the upload completes), so parts whose size is a multiple of [`CHUNK_SIZE`]
+/// keep their boundaries.
+struct EncryptedMultipartUpload {
+    target: Box<dyn MultipartUpload>,
+    encryptor: ChunkEncryptor,
+    /// Encrypted data that has not been uploaded yet (initially the header)
+    out: Vec<u8>,
+    /// Plaintext that does not fill a whole chunk yet
+    pending: Vec<u8>,
+    location: Path,
+    headers: moka::sync::Cache<Path, Arc<ObjectHeader>>,
+}
+
+impl Debug for EncryptedMultipartUpload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedMultipartUpload")
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for EncryptedMultipartUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        for bytes in &data {
+            self.pending.extend_from_slice(bytes);
+        }
+        let mut out = std::mem::take(&mut self.out);
+        match self.encryptor.seal_full_chunks(&self.pending, &mut out) {
+            Ok(num_bytes) => {
+                self.pending.drain(..num_bytes);
+            }
+            Err(err) => return Box::pin(async move { Err(err) }),
+        }
+        if out.is_empty() {
+            return Box::pin(async { Ok(()) });
+        }
+        self.target.put_part(out.into())
+    }
+
+    async fn complete(&mut self) -> OSResult<PutResult> {
+        let mut out = std::mem::take(&mut self.out);
+        let pending = std::mem::take(&mut self.pending);
+        self.encryptor.seal(&pending, true, &mut out)?;
+        self.target.put_part(out.into()).await?;
+        let result = self.target.complete().await?;
+        self.headers
+            .insert(self.location.clone(), self.encryptor.object_header());
+        Ok(result)
+    }
+
+    async fn abort(&mut self) -> OSResult<()> {
+        self.target.abort().await
+    }
+}
+
+fn aes_key(key: &[u8; DATA_KEY_SIZE]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).unwrap())
+}
+
+/// The key of the object with `object_id`
+fn object_key(data_key: &hkdf::Salt, object_id: &[u8; OBJECT_ID_SIZE]) -> LessSafeKey {
+    let prk = data_key.extract(object_id);
+    let key = prk.expand(&[OBJECT_KEY_INFO], &AES_256_GCM).unwrap();
+    LessSafeKey::new(UnboundKey::from(key))
+}
+
+fn header_digest(header: &[u8]) -> [u8; SHA256_OUTPUT_LEN] {
+    digest(&SHA256, header).as_ref().try_into().unwrap()
+}
+
+/// Every object has its own key, so the chunk index is a unique nonce
+fn chunk_nonce(index: u64) -> Nonce {
+    let mut nonce = [0; NONCE_LEN];
+    nonce[NONCE_LEN - 8..].copy_from_slice(&index.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+fn chunk_aad(
+    header_digest: &[u8; SHA256_OUTPUT_LEN],
+    is_final: bool,
+) -> Aad<[u8; SHA256_OUTPUT_LEN + 1]> {
+    let mut aad = [0; SHA256_OUTPUT_LEN + 1];
+    aad[..SHA256_OUTPUT_LEN].copy_from_slice(header_digest);
+    aad[SHA256_OUTPUT_LEN] = is_final as u8;
+    Aad::from(aad)
+}
+
+/// Offset of the chunk `index` within the stored object
+fn chunk_offset(index: u64) -> u64 {
+    HEADER_SIZE + index * (CHUNK_SIZE + TAG_SIZE)
+}
+
+/// The stored size of an object of `size` plaintext bytes
+fn encrypted_size(size: u64) -> u64 {
+    HEADER_SIZE + size + (size / CHUNK_SIZE + 1) * TAG_SIZE
+}
+
+/// The plaintext size of an object that is stored with `size` bytes
+fn plaintext_size(location: &Path, size: u64) -> OSResult<u64> {
+    if size < HEADER_SIZE + TAG_SIZE {
+        return Err(not_encrypted_error(location));
+    }
+    let body = size - HEADER_SIZE;
+    let full_chunks = (body - TAG_SIZE) / (CHUNK_SIZE + TAG_SIZE);
+    let final_chunk = body - full_chunks * (CHUNK_SIZE + TAG_SIZE) - TAG_SIZE;
+    if final_chunk >= CHUNK_SIZE {
+        return Err(not_encrypted_error(location));
+    }
+    Ok(full_chunks * CHUNK_SIZE + final_chunk)
+}
+
+fn decrypt_meta(meta: OSResult<ObjectMeta>) -> OSResult<ObjectMeta> {
+    let mut meta = meta?;
+    meta.size = plaintext_size(&meta.location, meta.size)?;
+    Ok(meta)
+}
+
+fn not_encrypted_error(location: &Path) -> object_store::Error {
+    encryption_error(format!("{location} is not an encrypted object"))
+}
+
+fn encryption_error(message: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE_NAME,
+        source: message.into().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use object_store::memory::InMemory;
+    use object_store::{ObjectStoreExt, PutMode};
+
+    use crate::utils::tracking_store::IOTracker;
+
+    fn make_store() -> (Arc<InMemory>, EncryptedObjectStore, IOTracker) {
+        let inner = Arc::new(InMemory::new());
+        let tracker = IOTracker::default();
+        let target = tracker.wrap("", inner.clone());
+        let key_provider = Arc::new(LocalKeyProvider::new("key-1", [7; DATA_KEY_SIZE]));
+        (
+            inner,
+            EncryptedObjectStore::new(target, key_provider),
+            tracker,
+        )
+    }
+
+    fn some_data(len: usize) -> Bytes {
+        Bytes::from((0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_sizes() {
+        for size in [
+            0,
+            1,
+            CHUNK_SIZE - 1,
+            CHUNK_SIZE,
+            CHUNK_SIZE + 1,
+            5 * CHUNK_SIZE,
+        ] {
+            let location = Path::from("file");
+            assert_eq!(
+                plaintext_size(&location, encrypted_size(size)).unwrap(),
+                size
+            );
+        }
+        assert!(plaintext_size(&Path::from("file"), 10).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let (inner, store, _) = make_store();
+        for size in [0, 100, CHUNK_SIZE as usize, 3 * CHUNK_SIZE as usize + 17] {
+            let location = Path::from(format!("data/{size}.lance"));
+            let data = some_data(size);
+            store.put(&location, data.clone().into()).await.unwrap();
+
+            // Nothing is stored in plain text
+            let stored = inner.get(&location).await.unwrap().bytes().await.unwrap();
+            assert_eq!(stored.len() as u64, encrypted_size(size as u64));
+            if size > 0 {
+                assert!(
+                    !stored
+                        .windows(size.min(64))
+                        .any(|w| w == &data[..size.min(64)])
+                );
+            }
+
+            let read = store.get(&location).await.unwrap();
+            assert_eq!(read.meta.size, size as u64);
+            assert_eq!(read.bytes().await.unwrap(), data);
+            assert_eq!(store.head(&location).await.unwrap().size, size as u64);
+        }
+
+        let listed = store
+            .list(Some(&Path::from("data")))
+            .map(|meta| meta.unwrap().size)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(listed.len(), 4);
+        assert!(listed.contains(&(3 * CHUNK_SIZE + 17)));
+    }
+
+    #[tokio::test]
+    async fn test_object_keys() {
+        let (inner, store, _) = make_store();
+        let data = some_data(100);
+        let (a, b) = (Path::from("a"), Path::from("b"));
+        store.put(&a, data.clone().into()).await.unwrap();
+        store.put(&b, data.clone().into()).await.unwrap();
+
+        // Objects are encrypted with different keys
+        let stored_a = inner.get(&a).await.unwrap().bytes().await.unwrap();
+        let stored_b = inner.get(&b).await.unwrap().bytes().await.unwrap();
+        assert_ne!(
+            stored_a.slice(HEADER_SIZE as usize..),
+            stored_b.slice(HEADER_SIZE as usize..)
+        );
+
+        // Chunks cannot be moved to another object
+        let mut spliced = stored_b[..HEADER_SIZE as usize].to_vec();
+        spliced.extend_from_slice(&stored_a[HEADER_SIZE as usize..]);
+        inner.put(&b, spliced.into()).await.unwrap();
+        store.headers.invalidate(&b);
+        assert!(store.get(&b).await.is_err());
+
+        // The header is authenticated
+        let mut tampered = stored_a.to_vec();
+        tampered[5] = 1;
+        inner.put(&a, tampered.into()).await.unwrap();
+        store.headers.invalidate(&a);
+        assert!(store.get(&a).await.is_err());
+
+        // Objects can be renamed
+        inner.put(&a, stored_a.into()).await.unwrap();
+        store.rename(&a, &b).await.unwrap();
+        assert_eq!(store.get(&b).await.unwrap().bytes().await.unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn test_ranged_reads() {
+        let (_, store, tracker) = make_store();
+        let location = Path::from("data/file.lance");
+        let data = some_data(3 * CHUNK_SIZE as usize + 100);
+        store.put(&location, data.clone().into()).await.unwrap();
+        tracker.incremental_stats();
+
+        let ranges = [
+            0..10,
+            CHUNK_SIZE - 5..CHUNK_SIZE + 5,
+            100..2 * CHUNK_SIZE + 50,
+            3 * CHUNK_SIZE..3 * CHUNK_SIZE + 100,
+        ];
+        for range in ranges.clone() {
+            let bytes = store.get_range(&location, range.clone()).await.unwrap();
+            assert_eq!(bytes, data.slice(range.start as usize..range.end as usize));
+        }
+        // The header was cached when the object was written
+        assert_eq!(tracker.incremental_stats().read_iops, ranges.len() as u64);
+
+        // A fresh store has to read the header first
+        let fresh = EncryptedObjectStore::new(
+            store.target.clone(),
+            Arc::new(LocalKeyProvider::new("key-1", [7; DATA_KEY_SIZE])),
+        );
+        let result = fresh
+            .get_opts(
+                &location,
+                GetOptions::new().with_range(Some(GetRange::Suffix(10))),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.range, data.len() as u64 - 10..data.len() as u64);
+        assert_eq!(result.bytes().await.unwrap(), data.slice(data.len() - 10..));
+
+        assert!(store.get_range(&location, 0..0).await.is_err());
+        assert!(
+            store
+                .get_range(&location, data.len() as u64..data.len() as u64 + 1)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overwrite() {
+        let (_, store, _) = make_store();
+        let other = EncryptedObjectStore::new(
+            store.target.clone(),
+            Arc::new(LocalKeyProvider::new("key-1", [7; DATA_KEY_SIZE])),
+        );
+        let location = Path::from("_latest.manifest");
+        store.put(&location, some_data(100).into()).await.unwrap();
+        // Written by another store, so the cached header of `store` is stale
+        let data = some_data(200);
+        other.put(&location, data.clone().into()).await.unwrap();
+
+        let bytes = store.get_range(&location, 10..20).await.unwrap();
+        assert_eq!(bytes, data.slice(10..20));
+    }
+
+    #[tokio::test]
+    async fn test_multipart_upload() {
+        let (_, store, _) = make_store();
+        let location = Path::from("data/file.lance");
+        let data = some_data(4 * CHUNK_SIZE as usize + 123);
+
+        let mut upload = store.put_multipart(&location).await.unwrap();
+        for part in [
+            data.slice(..CHUNK_SIZE as usize),
+            data.slice(CHUNK_SIZE as usize..CHUNK_SIZE as usize + 10),
+            data.slice(CHUNK_SIZE as usize + 10..),
+        ] {
+            upload.put_part(part.into()).await.unwrap();
+        }
+        upload.complete().await.unwrap();
+
+        let read = store.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(read, data);
+    }
+
+    #[tokio::test]
+    async fn test_conditional_put() {
+        let (_, store, _) = make_store();
+        let location = Path::from("_versions/1.manifest");
+        store
+            .put_opts(&location, some_data(10).into(), PutMode::Create.into())
+            .await
+            .unwrap();
+        let err = store
+            .put_opts(&location, some_data(10).into(), PutMode::Create.into())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, object_store::Error::AlreadyExists { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_key_rotation() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let location = Path::from("data/file.lance");
+        let data = some_data(1000);
+        let old = EncryptedObjectStore::new(
+            inner.clone(),
+            Arc::new(LocalKeyProvider::new("key-1", [1; DATA_KEY_SIZE])),
+        );
+        old.put(&location, data.clone().into()).await.unwrap();
+
+        let rotated = EncryptedObjectStore::new(
+            inner.clone(),
+            Arc::new(
+                LocalKeyProvider::new("key-2", [2; DATA_KEY_SIZE])
+                    .with_previous_key("key-1", [1; DATA_KEY_SIZE]),
+            ),
+        );
+        let read = rotated.get(&location).await.unwrap().bytes().await.unwrap();
+        assert_eq!(read, data);
+
+        let wrong_key = EncryptedObjectStore::new(
+            inner.clone(),
+            Arc::new(LocalKeyProvider::new("key-1", [3; DATA_KEY_SIZE])),
+        );
+        assert!(wrong_key.get(&location).await.is_err());
+
+        // Plain objects are rejected
+        let plain = Path::from("data/plain.lance");
+        inner.put(&plain, some_data(2000).into()).await.unwrap();
+        assert!(rotated.get(&plain).await.is_err());
+    }
+}
@@ -0,0 +1,723 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Latency and failure injection for object store requests.
+//!
+//! [`FaultInjectingStore`] delays and fails requests to the wrapped store, which is
+//! useful for testing how the scanner and the commit path cope with a slow or
+//! unreliable store.  There are two kinds of faults:
+//!
+//! - Random faults: every request is delayed by a fixed latency plus a random
+//!   jitter, and fails with a transient error with a given probability.  The
+//!   randomness comes from a seeded generator, so the same seed and the same
+//!   sequence of requests inject the same faults.
+//! - [`FaultRule`]s, which fail specific requests, e.g. "the second write to a
+//!   path containing `_versions/`".  Rules are fully deterministic.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use lance_io::object_store::fault_injection::*;
+//!
+//! let config = FaultInjectionConfig::default()
+//!     .with_latency(Duration::from_millis(50), Duration::from_millis(20))
+//!     .with_rule(FaultRule::new(FaultOperation::Write, FaultKind::Conflict).with_path("_versions/"));
+//! let params = ObjectStoreParams {
+//!     object_store_wrapper: Some(Arc::new(FaultInjectionWrapper::new(config))),
+//!     ..Default::default()
+//! };
+//! ```
+
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, stream};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions, Result as OSResult,
+    UploadPart,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::WrappingObjectStore;
+
+const STORE_NAME: &str = "FaultInjectingStore";
+
+/// The kinds of requests faults can be injected into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultOperation {
+    /// Gets, ranged gets and heads
+    Read,
+    /// Puts, multipart uploads (and their parts), copies and renames
+    Write,
+    Delete,
+    List,
+}
+
+/// The error a fault produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    /// A generic error, which is retried (see [`is_retryable`](super::is_retryable))
+    Transient,
+    /// A permission error, which is not retried
+    Permanent,
+    /// The error a conditional write gets when the object already exists, as if a
+    /// concurrent writer got there first
+    Conflict,
+}
+
+impl FaultKind {
+    fn error(&self, operation: FaultOperation, path: &Path) -> object_store::Error {
+        let message = format!("injected {self:?} fault in {operation:?} of {path}");
+        match self {
+            Self::Transient => object_store::Error::Generic {
+                store: STORE_NAME,
+                source: message.into(),
+            },
+            Self::Permanent => object_store::Error::PermissionDenied {
+                path: path.to_string(),
+                source: message.into(),
+            },
+            Self::Conflict => object_store::Error::AlreadyExists {
+                path: path.to_string(),
+                source: message.into(),
+            },
+        }
+    }
+}
+
+/// Fails the requests of one operation that match a path
+#[derive(Debug, Clone)]
+pub struct FaultRule {
+    operation: FaultOperation,
+    kind: FaultKind,
+    path_contains: Option<String>,
+    skip: u64,
+    times: Option<u64>,
+}
+
+impl FaultRule {
+    /// Fail every request of `operation` with `kind`
+    pub fn new(operation: FaultOperation, kind: FaultKind) -> Self {
+        Self {
+            operation,
+            kind,
+            path_contains: None,
+            skip: 0,
+            times: None,
+        }
+    }
+
+    /// Only match requests whose path contains `path_contains`
+    pub fn with_path(self, path_contains: impl Into<String>) -> Self {
+        Self {
+            path_contains: Some(path_contains.into()),
+            ..self
+        }
+    }
+
+    /// Let the first `skip` matching requests through
+    pub fn with_skip(self, skip: u64) -> Self {
+        Self { skip, ..self }
+    }
+
+    /// Only fail `times` requests, after the skipped ones
+    pub fn with_times(self, times: u64) -> Self {
+        Self {
+            times: Some(times),
+            ..self
+        }
+    }
+
+    fn matches(&self, operation: FaultOperation, path: &Path) -> bool {
+        self.operation == operation
+            && self
+                .path_contains
+                .as_ref()
+                .is_none_or(|part| path.as_ref().contains(part.as_str()))
+    }
+}
+
+/// Configuration of a [`FaultInjectingStore`]
+#[derive(Debug, Clone)]
+pub struct FaultInjectionConfig {
+    /// Seed of the generator behind the jitter and the random failures.
+    pub seed: u64,
+    /// Latency added to every request.
+    pub latency: Duration,
+    /// Max random latency added on top of `latency`.
+    pub jitter: Duration,
+    /// Probability that a request fails with a [`FaultKind::Transient`] error.
+    pub failure_probability: f64,
+    /// Operations the random failures apply to.  Empty means all operations.
+    pub failure_operations: Vec<FaultOperation>,
+    /// Requests that fail deterministically.  The first matching rule applies.
+    pub rules: Vec<FaultRule>,
+}
+
+impl Default for FaultInjectionConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+            failure_probability: 0.0,
+            failure_operations: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl FaultInjectionConfig {
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    pub fn with_latency(self, latency: Duration, jitter: Duration) -> Self {
+        Self {
+            latency,
+            jitter,
+            ..self
+        }
+    }
+
+    /// Fail requests of `operations` (all operations if empty) with `probability`
+    pub fn with_failure_probability(
+        self,
+        probability: f64,
+        operations: Vec<FaultOperation>,
+    ) -> Self {
+        Self {
+            failure_probability: probability,
+            failure_operations: operations,
+            ..self
+        }
+    }
+
+    pub fn with_rule(mut self, rule: FaultRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+}
+
+/// Counts of the faults injected by a [`FaultInjectingStore`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultInjectionStats {
+    pub requests: u64,
+    pub failures: u64,
+}
+
+struct FaultState {
+    rng: StdRng,
+    /// # of requests matched by each rule
+    rule_matches: Vec<u64>,
+}
+
+/// The faults shared by every store wrapped by a [`FaultInjectionWrapper`]
+struct FaultInjector {
+    config: FaultInjectionConfig,
+    state: Mutex<FaultState>,
+    requests: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl FaultInjector {
+    fn new(config: FaultInjectionConfig) -> Self {
+        Self {
+            state: Mutex::new(FaultState {
+                rng: StdRng::seed_from_u64(config.seed),
+                rule_matches: vec![0; config.rules.len()],
+            }),
+            config,
+            requests: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+        }
+    }
+
+    /// Decide the delay and outcome of a request
+    fn draw(&self, operation: FaultOperation, path: &Path) -> (Duration, Option<FaultKind>) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.state.lock().unwrap();
+        let mut delay = self.config.latency;
+        if !self.config.jitter.is_zero() {
+            delay += self.config.jitter.mul_f64(state.rng.random::<f64>());
+        }
+
+        let mut fault = None;
+        for (rule, matches) in self.config.rules.iter().zip(state.rule_matches.iter_mut()) {
+            if !rule.matches(operation, path) {
+                continue;
+            }
+            *matches += 1;
+            let failed = *matches - 1;
+            if failed >= rule.skip && rule.times.is_none_or(|times| failed < rule.skip + times) {
+                fault = Some(rule.kind);
+                break;
+            }
+        }
+        if fault.is_none()
+            && self.config.failure_probability > 0.0
+            && (self.config.failure_operations.is_empty()
+                || self.config.failure_operations.contains(&operation))
+            && state
+                .rng
+                .random_bool(self.config.failure_probability.min(1.0))
+        {
+            fault = Some(FaultKind::Transient);
+        }
+        if fault.is_some() {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        (delay, fault)
+    }
+
+    /// Delay the request, then fail it if a fault was drawn
+    async fn inject(&self, operation: FaultOperation, path: &Path) -> OSResult<()> {
+        let (delay, fault) = self.draw(operation, path);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        match fault {
+            Some(kind) => Err(kind.error(operation, path)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Wraps object stores with a [`FaultInjectingStore`]
+///
+/// All stores wrapped by the same wrapper share the random generator, the rule
+/// counters and the stats.
+#[derive(Clone)]
+pub struct FaultInjectionWrapper {
+    injector: Arc<FaultInjector>,
+}
+
+impl Debug for FaultInjectionWrapper {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FaultInjectionWrapper")
+            .field("config", &self.injector.config)
+            .finish()
+    }
+}
+
+impl FaultInjectionWrapper {
+    pub fn new(config: FaultInjectionConfig) -> Self {
+        Self {
+            injector: Arc::new(FaultInjector::new(config)),
+        }
+    }
+
+    pub fn stats(&self) -> FaultInjectionStats {
+        FaultInjectionStats {
+            requests: self.injector.requests.load(Ordering::Relaxed),
+            failures: self.injector.failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl WrappingObjectStore for FaultInjectionWrapper {
+    fn wrap(&self, _store_prefix: &str, original: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        Arc::new(FaultInjectingStore {
+            target: original,
+            injector: self.injector.clone(),
+        })
+    }
+}
+
+/// An [`ObjectStore`] that injects latency and failures into requests
+///
+/// Faults are injected before the request is sent, so a failed request has no
+/// effect on the wrapped store.
+pub struct FaultInjectingStore {
+    target: Arc<dyn ObjectStore>,
+    injector: Arc<FaultInjector>,
+}
+
+impl Debug for FaultInjectingStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FaultInjectingStore")
+            .field("target", &self.target)
+            .field("config", &self.injector.config)
+            .finish()
+    }
+}
+
+impl Display for FaultInjectingStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FaultInjectingStore({})", self.target)
+    }
+}
+
+impl FaultInjectingStore {
+    pub fn new(target: Arc<dyn ObjectStore>, config: FaultInjectionConfig) -> Self {
+        Self {
+            target,
+            injector: Arc::new(FaultInjector::new(config)),
+        }
+    }
+
+    pub fn stats(&self) -> FaultInjectionStats {
+        FaultInjectionStats {
+            requests: self.injector.requests.load(Ordering::Relaxed),
+            failures: self.injector.failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for FaultInjectingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> OSResult<PutResult> {
+        self.injector
+            .inject(FaultOperation::Write, location)
+            .await?;
+        self.target.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> OSResult<Box<dyn MultipartUpload>> {
+        self.injector
+            .inject(FaultOperation::Write, location)
+            .await?;
+        let target = self.target.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(FaultInjectingMultipartUpload {
+            target,
+            injector: self.injector.clone(),
+            location: location.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
+        self.injector.inject(FaultOperation::Read, location).await?;
+        self.target.get_opts(location, options).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, OSResult<Path>>,
+    ) -> BoxStream<'static, OSResult<Path>> {
+        let injector = self.injector.clone();
+        let target = self.target.clone();
+        // Paths that fail are reported without being passed on to the target
+        locations
+            .then(move |location| {
+                let injector = injector.clone();
+                async move {
+                    let location = location?;
+                    injector
+                        .inject(FaultOperation::Delete, &location)
+                        .await
+                        .map(|_| location)
+                }
+            })
+            .flat_map(move |location| match location {
+                Ok(location) => target.delete_stream(stream::once(async { Ok(location) }).boxed()),
+                Err(err) => stream::once(async { Err(err) }).boxed(),
+            })
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        let injector = self.injector.clone();
+        let path = prefix.cloned().unwrap_or_default();
+        let list = self.target.list(prefix);
+        stream::once(async move {
+            match injector.inject(FaultOperation::List, &path).await {
+                Ok(()) => list,
+                Err(err) => stream::once(async { Err(err) }).boxed(),
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        let injector = self.injector.clone();
+        let path = prefix.cloned().unwrap_or_default();
+        let list = self.target.list_with_offset(prefix, offset);
+        stream::once(async move {
+            match injector.inject(FaultOperation::List, &path).await {
+                Ok(()) => list,
+                Err(err) => stream::once(async { Err(err) }).boxed(),
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
+        let path = prefix.cloned().unwrap_or_default();
+        self.injector.inject(FaultOperation::List, &path).await?;
+        self.target.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, opts: CopyOptions) -> OSResult<()> {
+        self.injector.inject(FaultOperation::Write, to).await?;
+        self.target.copy_opts(from, to, opts).await
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, opts: RenameOptions) -> OSResult<()> {
+        self.injector.inject(FaultOperation::Write, to).await?;
+        self.target.rename_opts(from, to, opts).await
+    }
+}
+
+/// A [`MultipartUpload`] that injects faults into its parts and its completion
+struct FaultInjectingMultipartUpload {
+    target: Box<dyn MultipartUpload>,
+    injector: Arc<FaultInjector>,
+    location: Path,
+}
+
+impl Debug for FaultInjectingMultipartUpload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FaultInjectingMultipartUpload")
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for FaultInjectingMultipartUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        // Faults are drawn in part order, a failed part is never sent
+        let (delay, fault) = self.injector.draw(FaultOperation::Write, &self.location);
+        if let Some(kind) = fault {
+            let err = kind.error(FaultOperation::Write, &self.location);
+            return async move {
+                tokio::time::sleep(delay).await;
+                Err(err)
+            }
+            .boxed();
+        }
+        let fut = self.target.put_part(data);
+        async move {
+            tokio::time::sleep(delay).await;
+            fut.await
+        }
+        .boxed()
+    }
+
+    async fn complete(&mut self) -> OSResult<PutResult> {
+        self.injector
+            .inject(FaultOperation::Write, &self.location)
+            .await?;
+        self.target.complete().await
+    }
+
+    async fn abort(&mut self) -> OSResult<()> {
+        self.target.abort().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::Bytes;
+    use object_store::memory::InMemory;
+    use object_store::{ObjectStoreExt, PutMode};
+
+    use crate::object_store::{
+        ObjectStore as LanceObjectStore, ObjectStoreParams, ObjectStoreRegistry, is_retryable,
+    };
+
+    fn make_store(config: FaultInjectionConfig) -> FaultInjectingStore {
+        FaultInjectingStore::new(Arc::new(InMemory::new()), config)
+    }
+
+    #[tokio::test]
+    async fn test_rules() {
+        let store = make_store(
+            FaultInjectionConfig::default()
+                .with_rule(
+                    FaultRule::new(FaultOperation::Write, FaultKind::Conflict)
+                        .with_path("_versions/")
+                        .with_skip(1)
+                        .with_times(1),
+                )
+                .with_rule(FaultRule::new(FaultOperation::Read, FaultKind::Permanent)),
+        );
+        let data = || Bytes::from_static(b"data").into();
+
+        store
+            .put(&Path::from("ds/_versions/1.manifest"), data())
+            .await
+            .unwrap();
+        let err = store
+            .put_opts(
+                &Path::from("ds/_versions/2.manifest"),
+                data(),
+                PutMode::Create.into(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, object_store::Error::AlreadyExists { .. }));
+        // The failed write did not reach the store, and the rule only fired once
+        assert!(
+            store
+                .target
+                .head(&Path::from("ds/_versions/2.manifest"))
+                .await
+                .is_err()
+        );
+        store
+            .put(&Path::from("ds/_versions/2.manifest"), data())
+            .await
+            .unwrap();
+        store
+            .put(&Path::from("ds/data/a.lance"), data())
+            .await
+            .unwrap();
+
+        let err = store.get(&Path::from("ds/data/a.lance")).await.unwrap_err();
+        assert!(!is_retryable(&err));
+        assert_eq!(
+            store.stats(),
+            FaultInjectionStats {
+                requests: 5,
+                failures: 2,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_random_failures_are_deterministic() {
+        let run = |seed| async move {
+            let store = make_store(
+                FaultInjectionConfig::default()
+                    .with_seed(seed)
+                    .with_failure_probability(0.3, vec![FaultOperation::Read]),
+            );
+            let path = Path::from("file");
+            store
+                .put(&path, Bytes::from_static(b"data").into())
+                .await
+                .unwrap();
+            let mut outcomes = Vec::new();
+            for _ in 0..100 {
+                let result = store.head(&path).await;
+                if let Err(err) = &result {
+                    assert!(is_retryable(err));
+                }
+                outcomes.push(result.is_ok());
+            }
+            outcomes
+        };
+        let outcomes = run(42).await;
+        assert_eq!(outcomes, run(42).await);
+        assert_ne!(outcomes, run(7).await);
+        let failures = outcomes.iter().filter(|ok| !**ok).count();
+        assert!((10..60).contains(&failures), "{failures}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_latency() {
+        let store = make_store(
+            FaultInjectionConfig::default()
+                .with_latency(Duration::from_millis(100), Duration::from_millis(50)),
+        );
+        let path = Path::from("file");
+        let start = tokio::time::Instant::now();
+        store
+            .put(&path, Bytes::from_static(b"data").into())
+            .await
+            .unwrap();
+        store.get(&path).await.unwrap();
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(200), "{elapsed:?}");
+        assert!(elapsed <= Duration::from_millis(300), "{elapsed:?}");
+    }
+
+    #[tokio::test]
+    async fn test_multipart_and_delete_faults() {
+        let store = make_store(
+            FaultInjectionConfig::default()
+                .with_rule(
+                    FaultRule::new(FaultOperation::Write, FaultKind::Transient)
+                        .with_skip(2)
+                        .with_times(1),
+                )
+                .with_rule(
+                    FaultRule::new(FaultOperation::Delete, FaultKind::Transient).with_path("b"),
+                ),
+        );
+        let path = Path::from("a");
+        let mut upload = store.put_multipart(&path).await.unwrap();
+        upload
+            .put_part(Bytes::from_static(b"one").into())
+            .await
+            .unwrap();
+        // The second part is the third write
+        assert!(
+            upload
+                .put_part(Bytes::from_static(b"two").into())
+                .await
+                .is_err()
+        );
+        upload.abort().await.unwrap();
+
+        store
+            .put(&path, Bytes::from_static(b"a").into())
+            .await
+            .unwrap();
+        store
+            .put(&Path::from("b"), Bytes::from_static(b"b").into())
+            .await
+            .unwrap();
+        let deleted = store
+            .delete_stream(stream::iter([Ok(Path::from("a")), Ok(Path::from("b"))]).boxed())
+            .collect::<Vec<_>>()
+            .await;
+        assert!(deleted[0].is_ok());
+        assert!(deleted[1].is_err());
+        assert!(store.target.head(&Path::from("b")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reads_retry_transient_faults() {
+        let wrapper = Arc::new(FaultInjectionWrapper::new(
+            FaultInjectionConfig::default().with_rule(
+                FaultRule::new(FaultOperation::Read, FaultKind::Transient)
+                    .with_path("data/")
+                    .with_times(2),
+            ),
+        ));
+        let params = ObjectStoreParams {
+            object_store_wrapper: Some(wrapper.clone()),
+            ..Default::default()
+        };
+        let (store, base) = LanceObjectStore::from_uri_and_params(
+            Arc::new(ObjectStoreRegistry::default()),
+            "memory:///ds",
+            &params,
+        )
+        .await
+        .unwrap();
+        let path = base.clone().join("data").join("a.lance");
+        store.put(&path, b"some data").await.unwrap();
+
+        let reader = store.open(&path).await.unwrap();
+        let bytes = reader.get_range(5..9).await.unwrap();
+        assert_eq!(bytes.as_ref(), b"data");
+        assert_eq!(wrapper.stats().failures, 2);
+    }
+}
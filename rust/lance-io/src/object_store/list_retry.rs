@@ -9,6 +9,8 @@ use object_store::{ObjectMeta, ObjectStore, path::Path};
 use rand::Rng;
 use tokio::time::Sleep;
 
+use super::retry::is_retryable;
+
 const DEFAULT_BASE_RETRY_DELAY: Duration = Duration::from_millis(100);
 const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(5);
 
@@ -70,16 +72,6 @@ impl ListRetryStream {
         }
     }
 
-    fn is_retryable(error: &object_store::Error) -> bool {
-        !matches!(
-            error,
-            object_store::Error::NotFound { .. }
-                | object_store::Error::InvalidPath { .. }
-                | object_store::Error::NotSupported { .. }
-                | object_store::Error::NotImplemented { .. }
-        )
-    }
-
     fn retry_delay(&self) -> Duration {
         let exponent = self.current_retries.saturating_sub(1).min(16) as u32;
         let base_ms = self.base_retry_delay.as_millis().max(1);
@@ -132,7 +124,7 @@ impl Stream for ListRetryStream {
                     // If the stream is done, return None
                     return Poll::Ready(None);
                 }
-                Poll::Ready(Some(Err(error))) if Self::is_retryable(&error) => {
+                Poll::Ready(Some(Err(error))) if is_retryable(&error) => {
                     if this.current_retries < this.max_retries {
                         this.current_retries += 1;
                         this.retry_sleep = Some(Box::pin(tokio::time::sleep(this.retry_delay())));
@@ -0,0 +1,643 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Pluggable IO metrics for object stores.
+//!
+//! [`IoMetricsRecorder`] receives one event per object store request (with the
+//! operation, number of bytes, latency and outcome) as well as cache hits and
+//! misses.  Applications implement it to forward metrics into Prometheus,
+//! OpenTelemetry, or any other metrics system.  [`InMemoryIoMetrics`] is a simple
+//! recorder that aggregates counters and latency histograms in memory.
+//!
+//! A recorder is attached to a store with [`ObjectStoreParams::io_metrics`].
+//! Since stores are created per set of params, giving each dataset (or each
+//! scan) its own params and recorder attributes IO to that dataset or scan.
+//!
+//! [`ObjectStoreParams::io_metrics`]: super::ObjectStoreParams::io_metrics
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as OSResult, UploadPart,
+};
+
+/// The kind of object store request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IoOperation {
+    /// A read of a whole object or a range of it (`get`, `get_range`, `get_ranges`)
+    Read,
+    /// A metadata read (`head`)
+    Head,
+    /// A single put, or one part of a multipart upload
+    Write,
+    /// A list request
+    List,
+    Delete,
+    /// A copy or rename
+    Copy,
+}
+
+impl IoOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Head => "head",
+            Self::Write => "write",
+            Self::List => "list",
+            Self::Delete => "delete",
+            Self::Copy => "copy",
+        }
+    }
+}
+
+impl Display for IoOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single completed object store request.
+#[derive(Debug, Clone)]
+pub struct IoRequestEvent<'a> {
+    /// The prefix that identifies the object store (e.g. `s3$bucket`)
+    pub store_prefix: &'a str,
+    pub operation: IoOperation,
+    pub path: &'a Path,
+    /// Bytes read or written.  Zero for metadata operations.
+    pub bytes: u64,
+    /// Time from issuing the request until it completed.  For streaming reads
+    /// this includes reading the response body.
+    pub latency: Duration,
+    /// Whether the request succeeded
+    pub success: bool,
+}
+
+/// Receives IO metrics from object stores.
+///
+/// Implementations must be cheap and non-blocking, since they are called inline
+/// on every request.
+pub trait IoMetricsRecorder: Debug + Send + Sync {
+    /// Record a completed request.
+    fn record_request(&self, event: &IoRequestEvent<'_>);
+
+    /// Record a lookup in a cache, such as the local disk cache.
+    fn record_cache_access(&self, cache: &str, hit: bool) {
+        let _ = (cache, hit);
+    }
+}
+
+/// Upper bounds, in microseconds, of the buckets of a [`LatencyHistogram`].
+/// The last bucket is unbounded.
+pub const LATENCY_BUCKETS_MICROS: [u64; 16] = [
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000, 500_000,
+    1_000_000, 2_500_000, 5_000_000, 10_000_000,
+];
+
+/// A fixed bucket histogram of request latencies.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Count per bucket.  Bucket `i` counts latencies up to
+    /// `LATENCY_BUCKETS_MICROS[i]`, the last bucket counts everything above.
+    pub counts: [u64; LATENCY_BUCKETS_MICROS.len() + 1],
+    pub total: Duration,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: Duration) {
+        let micros = latency.as_micros() as u64;
+        let bucket = LATENCY_BUCKETS_MICROS.partition_point(|bound| *bound < micros);
+        self.counts[bucket] += 1;
+        self.total += latency;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Approximate quantile (`0.0..=1.0`), reported as the upper bound of the
+    /// bucket containing it.  Returns `None` if the histogram is empty or the
+    /// quantile falls in the unbounded bucket.
+    pub fn quantile(&self, quantile: f64) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((count as f64) * quantile.clamp(0.0, 1.0)).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bucket, bucket_count) in self.counts.iter().enumerate() {
+            seen += bucket_count;
+            if seen >= rank {
+                return LATENCY_BUCKETS_MICROS
+                    .get(bucket)
+                    .map(|micros| Duration::from_micros(*micros));
+            }
+        }
+        None
+    }
+}
+
+/// Aggregated metrics of one [`IoOperation`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationMetrics {
+    pub requests: u64,
+    pub errors: u64,
+    pub bytes: u64,
+    pub latency: LatencyHistogram,
+}
+
+/// Aggregated metrics of one cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// A snapshot of the metrics collected by [`InMemoryIoMetrics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IoMetricsSnapshot {
+    pub operations: HashMap<IoOperation, OperationMetrics>,
+    pub caches: HashMap<String, CacheMetrics>,
+}
+
+impl IoMetricsSnapshot {
+    pub fn operation(&self, operation: IoOperation) -> OperationMetrics {
+        self.operations.get(&operation).cloned().unwrap_or_default()
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.operation(IoOperation::Read).bytes
+    }
+
+    pub fn bytes_written(&self) -> u64 {
+        self.operation(IoOperation::Write).bytes
+    }
+}
+
+/// An [`IoMetricsRecorder`] that aggregates metrics in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryIoMetrics {
+    metrics: Mutex<IoMetricsSnapshot>,
+}
+
+impl InMemoryIoMetrics {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Get a snapshot of the metrics collected so far.
+    pub fn snapshot(&self) -> IoMetricsSnapshot {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    /// Get the metrics collected since the last call and reset them.
+    pub fn take(&self) -> IoMetricsSnapshot {
+        std::mem::take(&mut *self.metrics.lock().unwrap())
+    }
+}
+
+impl IoMetricsRecorder for InMemoryIoMetrics {
+    fn record_request(&self, event: &IoRequestEvent<'_>) {
+        let mut metrics = self.metrics.lock().unwrap();
+        let operation = metrics.operations.entry(event.operation).or_default();
+        operation.requests += 1;
+        if !event.success {
+            operation.errors += 1;
+        }
+        operation.bytes += event.bytes;
+        operation.latency.record(event.latency);
+    }
+
+    fn record_cache_access(&self, cache: &str, hit: bool) {
+        let mut metrics = self.metrics.lock().unwrap();
+        let cache = metrics.caches.entry(cache.to_string()).or_default();
+        if hit {
+            cache.hits += 1;
+        } else {
+            cache.misses += 1;
+        }
+    }
+}
+
+/// An [`ObjectStore`] wrapper that reports every request to an
+/// [`IoMetricsRecorder`].
+pub struct IoMetricsStore {
+    target: Arc<dyn ObjectStore>,
+    store_prefix: Arc<str>,
+    recorder: Arc<dyn IoMetricsRecorder>,
+}
+
+impl Debug for IoMetricsStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IoMetricsStore")
+            .field("target", &self.target)
+            .field("recorder", &self.recorder)
+            .finish()
+    }
+}
+
+impl Display for IoMetricsStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IoMetricsStore({})", self.target)
+    }
+}
+
+impl IoMetricsStore {
+    pub fn new(
+        target: Arc<dyn ObjectStore>,
+        store_prefix: &str,
+        recorder: Arc<dyn IoMetricsRecorder>,
+    ) -> Self {
+        Self {
+            target,
+            store_prefix: store_prefix.into(),
+            recorder,
+        }
+    }
+
+    fn record(
+        &self,
+        operation: IoOperation,
+        path: &Path,
+        bytes: u64,
+        start: Instant,
+        success: bool,
+    ) {
+        record(
+            self.recorder.as_ref(),
+            &self.store_prefix,
+            operation,
+            path,
+            bytes,
+            start,
+            success,
+        );
+    }
+
+    /// Wrap a stream of list results, recording the request when it finishes.
+    fn record_list(
+        &self,
+        prefix: Option<&Path>,
+        stream: BoxStream<'static, OSResult<ObjectMeta>>,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        let recorder = self.recorder.clone();
+        let store_prefix = self.store_prefix.clone();
+        let prefix = prefix.cloned().unwrap_or_default();
+        let mut guard = RequestGuard {
+            recorder,
+            store_prefix,
+            operation: IoOperation::List,
+            path: prefix,
+            bytes: 0,
+            start: Instant::now(),
+            success: true,
+        };
+        // The guard is moved into the closure, so the request is recorded when
+        // the stream is dropped.
+        stream
+            .inspect(move |item| guard.observe(item.is_ok(), 0))
+            .boxed()
+    }
+}
+
+fn record(
+    recorder: &dyn IoMetricsRecorder,
+    store_prefix: &str,
+    operation: IoOperation,
+    path: &Path,
+    bytes: u64,
+    start: Instant,
+    success: bool,
+) {
+    recorder.record_request(&IoRequestEvent {
+        store_prefix,
+        operation,
+        path,
+        bytes,
+        latency: start.elapsed(),
+        success,
+    });
+}
+
+/// Records a streaming request once the stream is dropped.
+struct RequestGuard {
+    recorder: Arc<dyn IoMetricsRecorder>,
+    store_prefix: Arc<str>,
+    operation: IoOperation,
+    path: Path,
+    bytes: u64,
+    start: Instant,
+    success: bool,
+}
+
+impl RequestGuard {
+    fn observe(&mut self, success: bool, bytes: u64) {
+        self.success &= success;
+        self.bytes += bytes;
+    }
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        record(
+            self.recorder.as_ref(),
+            &self.store_prefix,
+            self.operation,
+            &self.path,
+            self.bytes,
+            self.start,
+            self.success,
+        );
+    }
+}
+
+#[async_trait]
+#[deny(clippy::missing_trait_methods)]
+impl ObjectStore for IoMetricsStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> OSResult<PutResult> {
+        let start = Instant::now();
+        let bytes = payload.content_length() as u64;
+        let result = self.target.put_opts(location, payload, opts).await;
+        self.record(IoOperation::Write, location, bytes, start, result.is_ok());
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> OSResult<Box<dyn MultipartUpload>> {
+        let target = self.target.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(IoMetricsMultipartUpload {
+            target,
+            store_prefix: self.store_prefix.clone(),
+            path: location.clone(),
+            recorder: self.recorder.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
+        let start = Instant::now();
+        let operation = if options.head {
+            IoOperation::Head
+        } else {
+            IoOperation::Read
+        };
+        let mut result = match self.target.get_opts(location, options).await {
+            Ok(result) => result,
+            Err(err) => {
+                self.record(operation, location, 0, start, false);
+                return Err(err);
+            }
+        };
+        match result.payload {
+            GetResultPayload::Stream(stream) if operation == IoOperation::Read => {
+                // Record once the body has been consumed, to capture the full
+                // latency and the bytes actually read.
+                let mut guard = RequestGuard {
+                    recorder: self.recorder.clone(),
+                    store_prefix: self.store_prefix.clone(),
+                    operation,
+                    path: location.clone(),
+                    bytes: 0,
+                    start,
+                    success: true,
+                };
+                result.payload = GetResultPayload::Stream(
+                    stream
+                        .inspect(move |chunk| match chunk {
+                            Ok(data) => guard.observe(true, data.len() as u64),
+                            Err(_) => guard.observe(false, 0),
+                        })
+                        .boxed(),
+                );
+            }
+            _ => {
+                let bytes = match operation {
+                    IoOperation::Read => result.range.end - result.range.start,
+                    _ => 0,
+                };
+                self.record(operation, location, bytes, start, true);
+            }
+        }
+        Ok(result)
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> OSResult<Vec<Bytes>> {
+        let start = Instant::now();
+        let result = self.target.get_ranges(location, ranges).await;
+        let bytes = result
+            .as_ref()
+            .map(|data| data.iter().map(|b| b.len() as u64).sum())
+            .unwrap_or(0);
+        self.record(IoOperation::Read, location, bytes, start, result.is_ok());
+        result
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, OSResult<Path>>,
+    ) -> BoxStream<'static, OSResult<Path>> {
+        let recorder = self.recorder.clone();
+        let store_prefix = self.store_prefix.clone();
+        let start = Instant::now();
+        self.target
+            .delete_stream(locations)
+            .inspect(move |result| {
+                let (path, success) = match result {
+                    Ok(path) => (path.clone(), true),
+                    Err(_) => (Path::default(), false),
+                };
+                record(
+                    recorder.as_ref(),
+                    &store_prefix,
+                    IoOperation::Delete,
+                    &path,
+                    0,
+                    start,
+                    success,
+                );
+            })
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.record_list(prefix, self.target.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.record_list(prefix, self.target.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
+        let start = Instant::now();
+        let result = self.target.list_with_delimiter(prefix).await;
+        self.record(
+            IoOperation::List,
+            &prefix.cloned().unwrap_or_default(),
+            0,
+            start,
+            result.is_ok(),
+        );
+        result
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, opts: CopyOptions) -> OSResult<()> {
+        let start = Instant::now();
+        let result = self.target.copy_opts(from, to, opts).await;
+        self.record(IoOperation::Copy, from, 0, start, result.is_ok());
+        result
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, opts: RenameOptions) -> OSResult<()> {
+        let start = Instant::now();
+        let result = self.target.rename_opts(from, to, opts).await;
+        self.record(IoOperation::Copy, from, 0, start, result.is_ok());
+        result
+    }
+}
+
+#[derive(Debug)]
+struct IoMetricsMultipartUpload {
+    target: Box<dyn MultipartUpload>,
+    store_prefix: Arc<str>,
+    path: Path,
+    recorder: Arc<dyn IoMetricsRecorder>,
+}
+
+#[async_trait]
+impl MultipartUpload for IoMetricsMultipartUpload {
+    fn put_part(&mut self, payload: PutPayload) -> UploadPart {
+        let bytes = payload.content_length() as u64;
+        let upload = self.target.put_part(payload);
+        let recorder = self.recorder.clone();
+        let store_prefix = self.store_prefix.clone();
+        let path = self.path.clone();
+        let start = Instant::now();
+        Box::pin(async move {
+            let result = upload.await;
+            record(
+                recorder.as_ref(),
+                &store_prefix,
+                IoOperation::Write,
+                &path,
+                bytes,
+                start,
+                result.is_ok(),
+            );
+            result
+        })
+    }
+
+    async fn complete(&mut self) -> OSResult<PutResult> {
+        self.target.complete().await
+    }
+
+    async fn abort(&mut self) -> OSResult<()> {
+        self.target.abort().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use object_store::memory::InMemory;
+    use object_store::{ObjectStoreExt, PutPayload};
+
+    use super::*;
+    use crate::object_store::{
+        ObjectStore as LanceObjectStore, ObjectStoreParams, ObjectStoreRegistry,
+    };
+
+    #[tokio::test]
+    async fn test_metrics_store() {
+        let metrics = InMemoryIoMetrics::new();
+        let store = IoMetricsStore::new(Arc::new(InMemory::new()), "memory", metrics.clone());
+
+        let path = Path::from("data/a.lance");
+        store
+            .put(&path, PutPayload::from_static(b"hello world"))
+            .await
+            .unwrap();
+        let data = store.get_range(&path, 0..5).await.unwrap();
+        assert_eq!(data.as_ref(), b"hello");
+        let data = store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(data.len(), 11);
+        store.head(&path).await.unwrap();
+        let listed = store.list(None).try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        store
+            .get(&Path::from("data/missing.lance"))
+            .await
+            .unwrap_err();
+
+        let snapshot = metrics.take();
+        assert_eq!(snapshot.bytes_written(), 11);
+        let reads = snapshot.operation(IoOperation::Read);
+        assert_eq!(reads.requests, 3);
+        assert_eq!(reads.errors, 1);
+        assert_eq!(reads.bytes, 16);
+        assert_eq!(reads.latency.count(), 3);
+        assert_eq!(snapshot.operation(IoOperation::Head).requests, 1);
+        assert_eq!(snapshot.operation(IoOperation::List).requests, 1);
+
+        assert_eq!(metrics.snapshot(), IoMetricsSnapshot::default());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_from_params() {
+        let metrics = InMemoryIoMetrics::new();
+        let params = ObjectStoreParams {
+            io_metrics: Some(metrics.clone()),
+            ..Default::default()
+        };
+        let registry = Arc::new(ObjectStoreRegistry::default());
+        let (store, base) =
+            LanceObjectStore::from_uri_and_params(registry, "memory:///ds", &params)
+                .await
+                .unwrap();
+        let path = base.clone().join("data").join("a.lance");
+        store.put(&path, b"0123456789").await.unwrap();
+        let data = store.read_one_range(&path, 2..6).await.unwrap();
+        assert_eq!(data.as_ref(), b"2345");
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.bytes_written(), 10);
+        assert_eq!(snapshot.bytes_read(), 4);
+    }
+
+    #[test]
+    fn test_latency_histogram() {
+        let mut histogram = LatencyHistogram::default();
+        assert_eq!(histogram.quantile(0.5), None);
+        for millis in [1, 2, 3, 40] {
+            histogram.record(Duration::from_millis(millis));
+        }
+        histogram.record(Duration::from_secs(60));
+        assert_eq!(histogram.count(), 5);
+        assert_eq!(histogram.quantile(0.2), Some(Duration::from_millis(1)));
+        assert_eq!(histogram.quantile(0.4), Some(Duration::from_micros(2_500)));
+        assert_eq!(histogram.quantile(0.5), Some(Duration::from_millis(5)));
+        assert_eq!(histogram.quantile(0.8), Some(Duration::from_millis(50)));
+        assert_eq!(histogram.quantile(1.0), None);
+    }
+}
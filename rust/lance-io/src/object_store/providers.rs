@@ -4,7 +4,7 @@
 use std::{
     collections::HashMap,
     sync::{
-        Arc, RwLock, Weak,
+        Arc, LazyLock, RwLock, Weak,
         atomic::{AtomicU64, Ordering},
     },
 };
@@ -13,9 +13,14 @@ use object_store::path::Path;
 use url::Url;
 
 use crate::object_store::WrappingObjectStore;
+use crate::object_store::disk_cache::{DiskCache, DiskCacheConfig, DiskCachedStore};
+use crate::object_store::metrics::IoMetricsStore;
+use crate::object_store::rate_limit::{RateLimitConfig, RateLimitedStore, RateLimiter};
 use crate::object_store::uri_to_url;
 
-use super::{ObjectStore, ObjectStoreParams, tracing::ObjectStoreTracingExt};
+use super::{ObjectStore, ObjectStoreParams, StorageOptions, tracing::ObjectStoreTracingExt};
+use crate::object_writer::UploadOptions;
+use crate::scheduler::CoalesceOptions;
 use lance_core::error::{Error, LanceOptionExt, Result};
 
 #[cfg(feature = "aws")]
@@ -26,6 +31,10 @@ pub mod azure;
 pub mod gcp;
 #[cfg(feature = "goosefs")]
 pub mod goosefs;
+#[cfg(feature = "hdfs")]
+pub mod hdfs;
+#[cfg(feature = "http")]
+pub mod http;
 #[cfg(feature = "huggingface")]
 pub mod huggingface;
 pub mod local;
@@ -73,6 +82,63 @@ pub trait ObjectStoreProvider: std::fmt::Debug + Sync + Send {
     ) -> Result<String> {
         Ok(format!("{}${}", url.scheme(), url.authority()))
     }
+
+    /// Whether the stores created by this provider support conditional puts.
+    ///
+    /// Datasets on stores that support conditional puts are committed with them,
+    /// which is safe with concurrent writers.  This is only consulted for providers
+    /// registered with [`register_object_store_provider`], the built-in schemes
+    /// already know how to commit.
+    fn supports_conditional_put(&self) -> bool {
+        false
+    }
+}
+
+type ProviderMap = HashMap<String, Arc<dyn ObjectStoreProvider>>;
+
+// Providers registered for the whole process, see `register_object_store_provider`.
+static REGISTERED_PROVIDERS: LazyLock<RwLock<ProviderMap>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Register an object store provider for the whole process.
+///
+/// Registered providers are available to every registry created with
+/// [`ObjectStoreRegistry::default()`], including the ones that Lance creates
+/// internally (e.g. when opening a dataset without a session, or in the namespace
+/// implementations).  This lets downstream crates add support for a custom URL
+/// scheme without having to thread a registry through every API.
+///
+/// Providers added to a registry with [`ObjectStoreRegistry::insert`], as well as
+/// the built-in providers of the registry, take precedence over registered ones.
+///
+/// Returns the provider previously registered for the scheme, if any.
+pub fn register_object_store_provider(
+    scheme: &str,
+    provider: Arc<dyn ObjectStoreProvider>,
+) -> Option<Arc<dyn ObjectStoreProvider>> {
+    REGISTERED_PROVIDERS
+        .write()
+        .expect("registered providers lock poisoned")
+        .insert(scheme.into(), provider)
+}
+
+/// Remove a provider registered with [`register_object_store_provider`].
+///
+/// Stores that were already created by the provider remain usable.
+pub fn unregister_object_store_provider(scheme: &str) -> Option<Arc<dyn ObjectStoreProvider>> {
+    REGISTERED_PROVIDERS
+        .write()
+        .expect("registered providers lock poisoned")
+        .remove(scheme)
+}
+
+/// Get the provider registered for a scheme with [`register_object_store_provider`].
+pub fn registered_object_store_provider(scheme: &str) -> Option<Arc<dyn ObjectStoreProvider>> {
+    REGISTERED_PROVIDERS
+        .read()
+        .expect("registered providers lock poisoned")
+        .get(scheme)
+        .cloned()
 }
 
 /// Statistics for the object store registry cache.
@@ -101,6 +167,9 @@ pub struct ObjectStoreRegistryStats {
 /// - `gs`: A Google Cloud Storage object store.
 /// - `tos`: A Volcengine TOS object store.
 ///
+/// Providers registered for the whole process with [`register_object_store_provider`]
+/// are also available.
+///
 /// Use [`Self::empty()`] to create an empty registry, with no providers registered.
 ///
 /// The registry also caches object stores that are currently in use. It holds
@@ -109,7 +178,9 @@ pub struct ObjectStoreRegistryStats {
 /// call to either [`Self::active_stores()`] or [`Self::get_store()`].
 #[derive(Debug)]
 pub struct ObjectStoreRegistry {
-    providers: RwLock<HashMap<String, Arc<dyn ObjectStoreProvider>>>,
+    providers: RwLock<ProviderMap>,
+    // Whether to fall back to the providers registered for the whole process.
+    use_registered_providers: bool,
     // Cache of object stores currently in use. We use a weak reference so the
     // cache itself doesn't keep them alive if no object store is actually using
     // it.
@@ -127,6 +198,7 @@ impl ObjectStoreRegistry {
     pub fn empty() -> Self {
         Self {
             providers: RwLock::new(HashMap::new()),
+            use_registered_providers: false,
             active_stores: RwLock::new(HashMap::new()),
             hits: AtomicU64::new(0),
             misses: AtomicU64::new(0),
@@ -135,11 +207,16 @@ impl ObjectStoreRegistry {
 
     /// Get the object store provider for a given scheme.
     pub fn get_provider(&self, scheme: &str) -> Option<Arc<dyn ObjectStoreProvider>> {
-        self.providers
+        let provider = self
+            .providers
             .read()
             .expect("ObjectStoreRegistry lock poisoned")
             .get(scheme)
-            .cloned()
+            .cloned();
+        match provider {
+            None if self.use_registered_providers => registered_object_store_provider(scheme),
+            provider => provider,
+        }
     }
 
     /// Get a list of all active object stores.
@@ -194,8 +271,18 @@ impl ObjectStoreRegistry {
     fn scheme_not_found_error(&self, scheme: &str) -> Error {
         let mut message = format!("No object store provider found for scheme: '{}'", scheme);
         if let Ok(providers) = self.providers.read() {
-            let valid_schemes = providers.keys().cloned().collect::<Vec<_>>().join(", ");
-            message.push_str(&format!("\nValid schemes: {}", valid_schemes));
+            let mut valid_schemes = providers.keys().cloned().collect::<Vec<_>>();
+            if self.use_registered_providers
+                && let Ok(registered) = REGISTERED_PROVIDERS.read()
+            {
+                valid_schemes.extend(
+                    registered
+                        .keys()
+                        .filter(|scheme| !providers.contains_key(*scheme))
+                        .cloned(),
+                );
+            }
+            message.push_str(&format!("\nValid schemes: {}", valid_schemes.join(", ")));
         }
         Error::invalid_input(message)
     }
@@ -251,9 +338,38 @@ impl ObjectStoreRegistry {
         self.misses.fetch_add(1, Ordering::Relaxed);
 
         let mut store = provider.new_store(base_path, params).await?;
+        store.io_budget = params.io_budget.clone();
+        let storage_options =
+            StorageOptions::new(params.storage_options().cloned().unwrap_or_default());
+        store.upload_options = UploadOptions::from_storage_options(&storage_options)?;
+        store.coalesce_options = CoalesceOptions::from_storage_options(&storage_options)?;
 
         store.inner = store.inner.traced();
 
+        if let Some(config) = RateLimitConfig::from_storage_options(params.storage_options())? {
+            let limiter = RateLimiter::shared(&cache_path, config);
+            store.inner = Arc::new(RateLimitedStore::new(store.inner, limiter));
+        }
+
+        if let Some(io_metrics) = &params.io_metrics {
+            store.inner = Arc::new(IoMetricsStore::new(
+                store.inner,
+                &cache_path,
+                io_metrics.clone(),
+            ));
+        }
+
+        if !store.is_local()
+            && let Some(config) = DiskCacheConfig::from_storage_options(params.storage_options())?
+        {
+            let cache = DiskCache::open(config)?;
+            let mut cached_store = DiskCachedStore::new(store.inner, &cache_path, cache);
+            if let Some(io_metrics) = &params.io_metrics {
+                cached_store = cached_store.with_metrics(io_metrics.clone());
+            }
+            store.inner = Arc::new(cached_store);
+        }
+
         if let Some(wrapper) = &params.object_store_wrapper {
             store.inner = wrapper.wrap(&cache_path, store.inner);
         }
@@ -335,12 +451,21 @@ impl Default for ObjectStoreRegistry {
         providers.insert("oss".into(), Arc::new(oss::OssStoreProvider));
         #[cfg(feature = "tencent")]
         providers.insert("cos".into(), Arc::new(tencent::TencentStoreProvider));
+        #[cfg(feature = "hdfs")]
+        providers.insert("hdfs".into(), Arc::new(hdfs::HdfsStoreProvider));
+        #[cfg(feature = "http")]
+        {
+            let http = Arc::new(http::HttpStoreProvider);
+            providers.insert("http".into(), http.clone());
+            providers.insert("https".into(), http);
+        }
         #[cfg(feature = "huggingface")]
         providers.insert("hf".into(), Arc::new(huggingface::HuggingfaceStoreProvider));
         #[cfg(feature = "tos")]
         providers.insert("tos".into(), Arc::new(tos::TosStoreProvider));
         Self {
             providers: RwLock::new(providers),
+            use_registered_providers: true,
             active_stores: RwLock::new(HashMap::new()),
             hits: AtomicU64::new(0),
             misses: AtomicU64::new(0),
@@ -438,6 +563,65 @@ mod tests {
         );
     }
 
+    #[derive(Debug)]
+    struct CustomProvider;
+
+    #[async_trait::async_trait]
+    impl ObjectStoreProvider for CustomProvider {
+        async fn new_store(
+            &self,
+            _base_path: Url,
+            params: &ObjectStoreParams,
+        ) -> Result<ObjectStore> {
+            memory::MemoryStoreProvider
+                .new_store(Url::parse("memory:///").unwrap(), params)
+                .await
+        }
+
+        fn supports_conditional_put(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registered_provider() {
+        let scheme = "lance-test-registered";
+        let url = Url::parse(&format!("{scheme}://bucket/path")).unwrap();
+        assert!(register_object_store_provider(scheme, Arc::new(CustomProvider)).is_none());
+
+        // Default registries fall back to the registered providers
+        let registry = ObjectStoreRegistry::default();
+        let store = registry
+            .get_store(url.clone(), &ObjectStoreParams::default())
+            .await
+            .unwrap();
+        assert_eq!(store.scheme(), "memory");
+        assert_eq!(
+            registry
+                .calculate_object_store_prefix(url.as_str(), None)
+                .unwrap(),
+            format!("{scheme}$bucket")
+        );
+        assert!(
+            registered_object_store_provider(scheme)
+                .unwrap()
+                .supports_conditional_put()
+        );
+
+        // Empty registries only use their own providers
+        let empty = ObjectStoreRegistry::empty();
+        assert!(empty.get_provider(scheme).is_none());
+
+        assert!(unregister_object_store_provider(scheme).is_some());
+        let err = registry
+            .calculate_object_store_prefix(url.as_str(), None)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("No object store provider"),
+            "{err}"
+        );
+    }
+
     #[tokio::test]
     async fn test_stats_hit_miss_tracking() {
         use crate::object_store::StorageOptionsAccessor;
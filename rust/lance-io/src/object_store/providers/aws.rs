@@ -16,8 +16,7 @@ use opendal::{Operator, services::S3};
 use aws_config::default_provider::credentials::DefaultCredentialsChain;
 use aws_credential_types::provider::ProvideCredentials;
 use object_store::{
-    ClientOptions, CredentialProvider, Result as ObjectStoreResult, RetryConfig,
-    StaticCredentialProvider,
+    ClientOptions, CredentialProvider, Result as ObjectStoreResult, StaticCredentialProvider,
     aws::{
         AmazonS3Builder, AmazonS3ConfigKey, AwsCredential as ObjectStoreAwsCredential,
         AwsCredentialProvider,
@@ -27,12 +26,15 @@ use tokio::sync::RwLock;
 use url::Url;
 
 use crate::object_store::{
-    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, DEFAULT_MAX_IOP_SIZE, ObjectStore,
-    ObjectStoreParams, ObjectStoreProvider, StorageOptions, StorageOptionsAccessor,
-    dynamic_credentials::{NamespaceCredentialsProvider, build_dynamic_credential_provider},
+    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, RetryPolicy, StorageOptions, StorageOptionsAccessor,
+    dynamic_credentials::{
+        CredentialRefreshStore, NamespaceCredentialsProvider, build_dynamic_credential_provider,
+    },
     throttle::{AimdThrottleConfig, AimdThrottledStore},
 };
 use lance_core::error::{Error, Result};
+use lance_core::utils::parse::str_is_truthy;
 
 #[derive(Default, Debug)]
 pub struct AwsStoreProvider;
@@ -43,28 +45,22 @@ impl AwsStoreProvider {
         base_path: &mut Url,
         params: &ObjectStoreParams,
         storage_options: &StorageOptions,
+        retry_policy: &RetryPolicy,
         is_s3_express: bool,
     ) -> Result<Arc<dyn OSObjectStore>> {
-        // Use a low retry count since the AIMD throttle layer handles
-        // throttle recovery with its own retry loop.
-        let retry_config = RetryConfig {
-            backoff: Default::default(),
-            max_retries: storage_options.client_max_retries(),
-            retry_timeout: Duration::from_secs(storage_options.client_retry_timeout()),
-        };
-
         let mut s3_storage_options = storage_options.as_s3_options();
         let region = resolve_s3_region(base_path, &s3_storage_options).await?;
 
         // Get accessor from params
         let accessor = params.get_accessor();
 
-        let (aws_creds, region) = build_aws_credential(
+        let (aws_creds, region) = build_aws_credential_with_profile(
             params.s3_credentials_refresh_offset,
             params.aws_credentials.clone(),
             Some(&s3_storage_options),
             region,
             accessor,
+            storage_options.aws_profile(),
         )
         .await?;
 
@@ -78,15 +74,17 @@ impl AwsStoreProvider {
         base_path.set_query(None);
 
         // we can't use parse_url_opts here because we need to manually set the credentials provider
-        let mut builder =
-            AmazonS3Builder::new().with_client_options(storage_options.client_options()?);
+        let mut builder = AmazonS3Builder::new()
+            .with_client_options(retry_policy.apply_timeouts(storage_options.client_options()?));
         for (key, value) in s3_storage_options {
             builder = builder.with_config(key, value);
         }
         builder = builder
             .with_url(base_path.as_ref())
             .with_credentials(aws_creds)
-            .with_retry(retry_config)
+            // Use a low retry count since the AIMD throttle layer handles
+            // throttle recovery with its own retry loop.
+            .with_retry(retry_policy.retry_config())
             .with_region(region);
 
         Ok(Arc::new(builder.build()?) as Arc<dyn OSObjectStore>)
@@ -134,7 +132,7 @@ impl ObjectStoreProvider for AwsStoreProvider {
         let mut storage_options =
             StorageOptions::new(params.storage_options().cloned().unwrap_or_default());
         storage_options.with_env_s3();
-        let download_retry_count = storage_options.download_retry_count();
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
 
         let use_opendal = storage_options
             .0
@@ -157,8 +155,16 @@ impl ObjectStoreProvider for AwsStoreProvider {
                 .await?
         } else {
             // Use default Amazon S3 implementation
-            self.build_amazon_s3_store(&mut base_path, params, &storage_options, is_s3_express)
-                .await?
+            let inner = self
+                .build_amazon_s3_store(
+                    &mut base_path,
+                    params,
+                    &storage_options,
+                    &retry_policy,
+                    is_s3_express,
+                )
+                .await?;
+            CredentialRefreshStore::wrap(inner, params.get_accessor())
         };
         let throttle_config = AimdThrottleConfig::from_storage_options(params.storage_options())?;
         let inner = if throttle_config.is_disabled() {
@@ -168,17 +174,17 @@ impl ObjectStoreProvider for AwsStoreProvider {
         };
 
         Ok(ObjectStore {
-            inner,
-            scheme: String::from(base_path.scheme()),
             block_size,
-            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
             use_constant_size_upload_parts,
             list_is_lexically_ordered: !is_s3_express,
             io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
-            download_retry_count,
-            io_tracker: Default::default(),
-            store_prefix: self
-                .calculate_object_store_prefix(&base_path, params.storage_options())?,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                inner,
+                String::from(base_path.scheme()),
+                self.calculate_object_store_prefix(&base_path, params.storage_options())?,
+            )
         })
     }
 }
@@ -251,13 +257,42 @@ pub async fn build_aws_credential(
     region: Option<String>,
     storage_options_accessor: Option<Arc<StorageOptionsAccessor>>,
 ) -> Result<(AwsCredentialProvider, String)> {
+    build_aws_credential_with_profile(
+        credentials_refresh_offset,
+        credentials,
+        storage_options,
+        region,
+        storage_options_accessor,
+        None,
+    )
+    .await
+}
+
+/// Build AWS credentials, like [`build_aws_credential`], for a named profile.
+///
+/// If `profile` is set, the default credential provider chain and the region
+/// lookup use that profile from the shared AWS config files instead of the
+/// `AWS_PROFILE` environment variable.
+pub async fn build_aws_credential_with_profile(
+    credentials_refresh_offset: Duration,
+    credentials: Option<AwsCredentialProvider>,
+    storage_options: Option<&HashMap<AmazonS3ConfigKey, String>>,
+    region: Option<String>,
+    storage_options_accessor: Option<Arc<StorageOptionsAccessor>>,
+    profile: Option<&str>,
+) -> Result<(AwsCredentialProvider, String)> {
+    use aws_config::default_provider::region::DefaultRegionChain;
     use aws_config::meta::region::RegionProviderChain;
     const DEFAULT_REGION: &str = "us-west-2";
 
     let region = if let Some(region) = region {
         region
     } else {
-        RegionProviderChain::default_provider()
+        let mut region_chain = DefaultRegionChain::builder();
+        if let Some(profile) = profile {
+            region_chain = region_chain.profile_name(profile);
+        }
+        RegionProviderChain::first_try(region_chain.build())
             .or_else(DEFAULT_REGION)
             .region()
             .await
@@ -293,7 +328,11 @@ pub async fn build_aws_credential(
     } else if let Some(creds) = storage_options_credentials {
         Ok((Arc::new(creds), region))
     } else {
-        let credentials_provider = DefaultCredentialsChain::builder().build().await;
+        let mut credentials_chain = DefaultCredentialsChain::builder();
+        if let Some(profile) = profile {
+            credentials_chain = credentials_chain.profile_name(profile);
+        }
+        let credentials_provider = credentials_chain.build().await;
 
         Ok((
             Arc::new(AwsCredentialAdapter::new(
@@ -423,14 +462,71 @@ impl StorageOptions {
     }
 
     /// Subset of options relevant for s3 storage
+    ///
+    /// In addition to the keys understood by [`AmazonS3ConfigKey`], this accepts:
+    ///
+    /// - `aws_requester_pays` (or `requester_pays`): request payer for requester
+    ///   pays buckets, an alias of `aws_request_payer`.
+    /// - `aws_force_path_style` (or `force_path_style`): use path-style addressing,
+    ///   the inverse of `aws_virtual_hosted_style_request`.
+    ///
+    /// If `aws_sse_kms_key_id` is set without `aws_server_side_encryption`, the
+    /// server side encryption defaults to `aws:kms`.
     pub fn as_s3_options(&self) -> HashMap<AmazonS3ConfigKey, String> {
-        self.0
+        let mut options: HashMap<AmazonS3ConfigKey, String> = self
+            .0
             .iter()
             .filter_map(|(key, value)| {
                 let s3_key = AmazonS3ConfigKey::from_str(&key.to_ascii_lowercase()).ok()?;
                 Some((s3_key, value.clone()))
             })
-            .collect()
+            .collect();
+
+        for (key, value) in &self.0 {
+            match key.to_ascii_lowercase().as_str() {
+                "aws_requester_pays" | "requester_pays" => {
+                    options
+                        .entry(AmazonS3ConfigKey::RequestPayer)
+                        .or_insert_with(|| str_is_truthy(value).to_string());
+                }
+                "aws_force_path_style" | "force_path_style" => {
+                    options
+                        .entry(AmazonS3ConfigKey::VirtualHostedStyleRequest)
+                        .or_insert_with(|| (!str_is_truthy(value)).to_string());
+                }
+                "upload_checksum" if str_is_truthy(value) => {
+                    // S3 verifies every uploaded part against its checksum header
+                    // and rejects corrupted parts, which the writer then retries.
+                    options
+                        .entry(AmazonS3ConfigKey::Checksum)
+                        .or_insert_with(|| "sha256".to_string());
+                }
+                _ => {}
+            }
+        }
+
+        // The encryption config keys are not exported by object_store, so
+        // resolve them through their string names.
+        if let (Ok(kms_key), Ok(encryption)) = (
+            AmazonS3ConfigKey::from_str("aws_sse_kms_key_id"),
+            AmazonS3ConfigKey::from_str("aws_server_side_encryption"),
+        ) && options.contains_key(&kms_key)
+        {
+            options
+                .entry(encryption)
+                .or_insert_with(|| "aws:kms".to_string());
+        }
+
+        options
+    }
+
+    /// The AWS profile to resolve credentials and region from, set with the
+    /// `aws_profile` storage option.
+    pub fn aws_profile(&self) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("aws_profile"))
+            .map(|(_, value)| value.as_str())
     }
 }
 
@@ -538,6 +634,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_s3_storage_options() {
+        let options = StorageOptions::new(HashMap::from([
+            ("aws_requester_pays".to_string(), "true".to_string()),
+            ("aws_force_path_style".to_string(), "true".to_string()),
+            (
+                "aws_endpoint".to_string(),
+                "http://localhost:9000".to_string(),
+            ),
+            ("aws_sse_kms_key_id".to_string(), "my-key".to_string()),
+            ("aws_profile".to_string(), "analytics".to_string()),
+            ("upload_checksum".to_string(), "true".to_string()),
+        ]));
+        let s3_options = options.as_s3_options();
+        assert_eq!(s3_options[&AmazonS3ConfigKey::RequestPayer], "true");
+        assert_eq!(s3_options[&AmazonS3ConfigKey::Checksum], "sha256");
+        assert_eq!(
+            s3_options[&AmazonS3ConfigKey::VirtualHostedStyleRequest],
+            "false"
+        );
+        assert_eq!(
+            s3_options[&AmazonS3ConfigKey::Endpoint],
+            "http://localhost:9000"
+        );
+        assert_eq!(
+            s3_options[&AmazonS3ConfigKey::from_str("aws_server_side_encryption").unwrap()],
+            "aws:kms"
+        );
+        assert_eq!(
+            s3_options[&AmazonS3ConfigKey::from_str("aws_sse_kms_key_id").unwrap()],
+            "my-key"
+        );
+        assert_eq!(options.aws_profile(), Some("analytics"));
+
+        // Explicit object_store keys take precedence over the aliases
+        let options = StorageOptions::new(HashMap::from([
+            ("aws_force_path_style".to_string(), "true".to_string()),
+            (
+                "aws_virtual_hosted_style_request".to_string(),
+                "true".to_string(),
+            ),
+            ("aws_sse_kms_key_id".to_string(), "my-key".to_string()),
+            (
+                "aws_server_side_encryption".to_string(),
+                "aws:kms:dsse".to_string(),
+            ),
+        ]));
+        let s3_options = options.as_s3_options();
+        assert_eq!(
+            s3_options[&AmazonS3ConfigKey::VirtualHostedStyleRequest],
+            "true"
+        );
+        assert_eq!(
+            s3_options[&AmazonS3ConfigKey::from_str("aws_server_side_encryption").unwrap()],
+            "aws:kms:dsse"
+        );
+        assert_eq!(options.aws_profile(), None);
+    }
+
     #[test]
     fn test_is_s3_express() {
         let cases = [
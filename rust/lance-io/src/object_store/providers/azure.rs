@@ -5,23 +5,19 @@ use std::{
     collections::HashMap,
     str::FromStr,
     sync::{Arc, LazyLock},
-    time::Duration,
 };
 
 use object_store::ObjectStore as OSObjectStore;
 use object_store_opendal::OpendalStore;
 use opendal::{Operator, services::Azblob, services::Azdls};
 
-use object_store::{
-    RetryConfig,
-    azure::{AzureConfigKey, AzureCredential, MicrosoftAzureBuilder},
-};
+use object_store::azure::{AzureConfigKey, AzureCredential, MicrosoftAzureBuilder};
 use url::Url;
 
 use crate::object_store::{
-    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, DEFAULT_MAX_IOP_SIZE, ObjectStore,
-    ObjectStoreParams, ObjectStoreProvider, StorageOptions, StorageOptionsAccessor,
-    dynamic_credentials::build_dynamic_credential_provider,
+    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, RetryPolicy, StorageOptions, StorageOptionsAccessor,
+    dynamic_credentials::{CredentialRefreshStore, build_dynamic_credential_provider},
     throttle::{AimdThrottleConfig, AimdThrottledStore},
 };
 use lance_core::error::{Error, Result};
@@ -168,20 +164,15 @@ impl AzureBlobStoreProvider {
         &self,
         base_path: &Url,
         storage_options: &StorageOptions,
+        retry_policy: &RetryPolicy,
         accessor: Option<Arc<StorageOptionsAccessor>>,
     ) -> Result<Arc<dyn OSObjectStore>> {
-        // Use a low retry count since the AIMD throttle layer handles
-        // throttle recovery with its own retry loop.
-        let retry_config = RetryConfig {
-            backoff: Default::default(),
-            max_retries: storage_options.client_max_retries(),
-            retry_timeout: Duration::from_secs(storage_options.client_retry_timeout()),
-        };
-
         let mut builder = MicrosoftAzureBuilder::new()
             .with_url(base_path.as_ref())
-            .with_retry(retry_config)
-            .with_client_options(storage_options.client_options()?);
+            // Use a low retry count since the AIMD throttle layer handles
+            // throttle recovery with its own retry loop.
+            .with_retry(retry_policy.retry_config())
+            .with_client_options(retry_policy.apply_timeouts(storage_options.client_options()?));
         for (key, value) in storage_options.as_azure_options() {
             builder = builder.with_config(key, value);
         }
@@ -247,7 +238,7 @@ impl ObjectStoreProvider for AzureBlobStoreProvider {
         let mut storage_options =
             StorageOptions::new(params.storage_options().cloned().unwrap_or_default());
         storage_options.with_env_azure();
-        let download_retry_count = storage_options.download_retry_count();
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
 
         let use_opendal = storage_options
             .0
@@ -263,8 +254,15 @@ impl ObjectStoreProvider for AzureBlobStoreProvider {
             self.build_opendal_azure_store(&base_path, &storage_options)
                 .await?
         } else {
-            self.build_microsoft_azure_store(&base_path, &storage_options, accessor)
-                .await?
+            let inner = self
+                .build_microsoft_azure_store(
+                    &base_path,
+                    &storage_options,
+                    &retry_policy,
+                    accessor.clone(),
+                )
+                .await?;
+            CredentialRefreshStore::wrap(inner, accessor)
         };
         let throttle_config = AimdThrottleConfig::from_storage_options(params.storage_options())?;
         let inner = if throttle_config.is_disabled() {
@@ -274,17 +272,17 @@ impl ObjectStoreProvider for AzureBlobStoreProvider {
         };
 
         Ok(ObjectStore {
-            inner,
-            scheme,
             block_size,
-            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
             use_constant_size_upload_parts: false,
             list_is_lexically_ordered: true,
             io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
-            download_retry_count,
-            io_tracker: Default::default(),
-            store_prefix: self
-                .calculate_object_store_prefix(&base_path, params.storage_options())?,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                inner,
+                scheme,
+                self.calculate_object_store_prefix(&base_path, params.storage_options())?,
+            )
         })
     }
 
@@ -1,26 +1,141 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
-use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use object_store::ObjectStore as OSObjectStore;
 use object_store_opendal::OpendalStore;
 use opendal::{Operator, services::Gcs};
 
 use object_store::{
-    RetryConfig, StaticCredentialProvider,
+    StaticCredentialProvider,
     gcp::{GcpCredential, GoogleCloudStorageBuilder, GoogleConfigKey},
 };
 use url::Url;
 
 use crate::object_store::{
-    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, DEFAULT_MAX_IOP_SIZE, ObjectStore,
-    ObjectStoreParams, ObjectStoreProvider, StorageOptions, StorageOptionsAccessor,
-    dynamic_credentials::build_dynamic_credential_provider,
+    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, RetryPolicy, StorageOptions, StorageOptionsAccessor,
+    dynamic_credentials::{CredentialRefreshStore, build_dynamic_credential_provider},
     throttle::{AimdThrottleConfig, AimdThrottledStore},
 };
 use lance_core::error::{Error, Result};
 
+/// Email of a service account to impersonate.
+pub const GOOGLE_IMPERSONATE_SERVICE_ACCOUNT_KEY: &str = "google_impersonate_service_account";
+/// Comma separated chain of service accounts to delegate the impersonation through.
+pub const GOOGLE_IMPERSONATION_DELEGATES_KEY: &str = "google_impersonation_delegates";
+/// Audience of the workload identity pool provider, e.g.
+/// `//iam.googleapis.com/projects/<number>/locations/global/workloadIdentityPools/<pool>/providers/<provider>`.
+pub const GOOGLE_WORKLOAD_IDENTITY_AUDIENCE_KEY: &str = "google_workload_identity_audience";
+/// File containing the subject token to exchange, e.g. a projected Kubernetes token.
+pub const GOOGLE_WORKLOAD_IDENTITY_TOKEN_FILE_KEY: &str = "google_workload_identity_token_file";
+/// URL returning the subject token to exchange.
+pub const GOOGLE_WORKLOAD_IDENTITY_TOKEN_URL_KEY: &str = "google_workload_identity_token_url";
+/// Type of the subject token, defaults to a JWT.
+pub const GOOGLE_WORKLOAD_IDENTITY_SUBJECT_TOKEN_TYPE_KEY: &str =
+    "google_workload_identity_subject_token_type";
+
+const DEFAULT_SUBJECT_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:jwt";
+const STS_TOKEN_URL: &str = "https://sts.googleapis.com/v1/token";
+
+fn impersonation_url(service_account: &str) -> String {
+    format!(
+        "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/{service_account}:generateAccessToken"
+    )
+}
+
+/// Build the credentials file for workload identity federation or service account
+/// impersonation configured through storage options, if any.
+///
+/// With a workload identity audience, this is an `external_account` credential that
+/// exchanges the subject token for a Google token, optionally impersonating a
+/// service account.  Otherwise, with only an impersonated service account, this is
+/// an `impersonated_service_account` credential whose source is the application
+/// default credentials of the user (`google_application_credentials` or the gcloud
+/// well-known file).
+fn federated_credential(storage_options: &StorageOptions) -> Result<Option<serde_json::Value>> {
+    let get = |key: &str| storage_options.get(key).filter(|v| !v.is_empty());
+    let service_account = get(GOOGLE_IMPERSONATE_SERVICE_ACCOUNT_KEY);
+
+    if let Some(audience) = get(GOOGLE_WORKLOAD_IDENTITY_AUDIENCE_KEY) {
+        let credential_source = match (
+            get(GOOGLE_WORKLOAD_IDENTITY_TOKEN_FILE_KEY),
+            get(GOOGLE_WORKLOAD_IDENTITY_TOKEN_URL_KEY),
+        ) {
+            (Some(file), None) => serde_json::json!({"file": file, "format": {"type": "text"}}),
+            (None, Some(url)) => serde_json::json!({"url": url, "format": {"type": "text"}}),
+            _ => {
+                return Err(Error::invalid_input(format!(
+                    "Workload identity federation requires exactly one of \
+                     '{GOOGLE_WORKLOAD_IDENTITY_TOKEN_FILE_KEY}' or \
+                     '{GOOGLE_WORKLOAD_IDENTITY_TOKEN_URL_KEY}'"
+                )));
+            }
+        };
+        let mut credential = serde_json::json!({
+            "type": "external_account",
+            "audience": audience,
+            "subject_token_type": get(GOOGLE_WORKLOAD_IDENTITY_SUBJECT_TOKEN_TYPE_KEY)
+                .map(String::as_str)
+                .unwrap_or(DEFAULT_SUBJECT_TOKEN_TYPE),
+            "token_url": STS_TOKEN_URL,
+            "credential_source": credential_source,
+        });
+        if let Some(service_account) = service_account {
+            credential["service_account_impersonation_url"] =
+                impersonation_url(service_account).into();
+        }
+        return Ok(Some(credential));
+    }
+
+    let Some(service_account) = service_account else {
+        return Ok(None);
+    };
+    let source_path = match storage_options.get("google_application_credentials") {
+        Some(path) => std::path::PathBuf::from(path),
+        None => std::env::home_dir()
+            .ok_or_else(|| {
+                Error::invalid_input(
+                    "Service account impersonation requires application default credentials",
+                )
+            })?
+            .join(".config/gcloud/application_default_credentials.json"),
+    };
+    let source: serde_json::Value = std::fs::read(&source_path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_slice(&content).map_err(|e| e.to_string()))
+        .map_err(|e| {
+            Error::invalid_input(format!(
+                "Failed to read application default credentials from {}: {e}",
+                source_path.display()
+            ))
+        })?;
+    if source["type"] != "authorized_user" {
+        return Err(Error::invalid_input(format!(
+            "Service account impersonation requires authorized user credentials as the \
+             source, found {} in {}",
+            source["type"],
+            source_path.display()
+        )));
+    }
+    let delegates = get(GOOGLE_IMPERSONATION_DELEGATES_KEY)
+        .map(|delegates| {
+            delegates
+                .split(',')
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    Ok(Some(serde_json::json!({
+        "type": "impersonated_service_account",
+        "service_account_impersonation_url": impersonation_url(service_account),
+        "source_credentials": source,
+        "delegates": delegates,
+    })))
+}
+
 #[derive(Default, Debug)]
 pub struct GcsStoreProvider;
 
@@ -29,6 +144,7 @@ impl GcsStoreProvider {
         &self,
         base_path: &Url,
         storage_options: &StorageOptions,
+        credential: Option<serde_json::Value>,
     ) -> Result<Arc<dyn OSObjectStore>> {
         let bucket = base_path
             .host_str()
@@ -48,6 +164,14 @@ impl GcsStoreProvider {
             config_map.insert("root".to_string(), format!("/{}", prefix));
         }
 
+        if let Some(credential) = credential {
+            use base64::Engine;
+            config_map.insert(
+                "credential".to_string(),
+                base64::engine::general_purpose::STANDARD.encode(credential.to_string()),
+            );
+        }
+
         let operator = Operator::from_iter::<Gcs>(config_map)
             .map_err(|e| Error::invalid_input(format!("Failed to create GCS operator: {:?}", e)))?
             .finish();
@@ -59,20 +183,15 @@ impl GcsStoreProvider {
         &self,
         base_path: &Url,
         storage_options: &StorageOptions,
+        retry_policy: &RetryPolicy,
         accessor: Option<Arc<StorageOptionsAccessor>>,
     ) -> Result<Arc<dyn OSObjectStore>> {
-        // Use a low retry count since the AIMD throttle layer handles
-        // throttle recovery with its own retry loop.
-        let retry_config = RetryConfig {
-            backoff: Default::default(),
-            max_retries: storage_options.client_max_retries(),
-            retry_timeout: Duration::from_secs(storage_options.client_retry_timeout()),
-        };
-
         let mut builder = GoogleCloudStorageBuilder::new()
             .with_url(base_path.as_ref())
-            .with_retry(retry_config)
-            .with_client_options(storage_options.client_options()?);
+            // Use a low retry count since the AIMD throttle layer handles
+            // throttle recovery with its own retry loop.
+            .with_retry(retry_policy.retry_config())
+            .with_client_options(retry_policy.apply_timeouts(storage_options.client_options()?));
         for (key, value) in storage_options.as_gcs_options() {
             builder = builder.with_config(key, value);
         }
@@ -100,7 +219,7 @@ impl ObjectStoreProvider for GcsStoreProvider {
         let mut storage_options =
             StorageOptions::new(params.storage_options().cloned().unwrap_or_default());
         storage_options.with_env_gcs();
-        let download_retry_count = storage_options.download_retry_count();
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
 
         let use_opendal = storage_options
             .0
@@ -109,15 +228,25 @@ impl ObjectStoreProvider for GcsStoreProvider {
             .unwrap_or(false);
 
         let accessor = params.get_accessor();
+        // The native client does not support impersonation or workload identity
+        // federation, so they are always served by OpenDAL.
+        let credential = federated_credential(&storage_options)?;
 
-        let inner = if use_opendal {
+        let inner = if use_opendal || credential.is_some() {
             // OpenDAL GCS intentionally uses static/environment-backed configuration only.
             // Namespace-vended dynamic credentials are supported on the native object_store path.
-            self.build_opendal_gcs_store(&base_path, &storage_options)
+            self.build_opendal_gcs_store(&base_path, &storage_options, credential)
                 .await?
         } else {
-            self.build_google_cloud_store(&base_path, &storage_options, accessor)
-                .await?
+            let inner = self
+                .build_google_cloud_store(
+                    &base_path,
+                    &storage_options,
+                    &retry_policy,
+                    accessor.clone(),
+                )
+                .await?;
+            CredentialRefreshStore::wrap(inner, accessor)
         };
         let throttle_config = AimdThrottleConfig::from_storage_options(params.storage_options())?;
         let inner = if throttle_config.is_disabled() {
@@ -127,17 +256,17 @@ impl ObjectStoreProvider for GcsStoreProvider {
         };
 
         Ok(ObjectStore {
-            inner,
-            scheme: String::from("gs"),
             block_size,
-            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
             use_constant_size_upload_parts: false,
             list_is_lexically_ordered: true,
             io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
-            download_retry_count,
-            io_tracker: Default::default(),
-            store_prefix: self
-                .calculate_object_store_prefix(&base_path, params.storage_options())?,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                inner,
+                String::from("gs"),
+                self.calculate_object_store_prefix(&base_path, params.storage_options())?,
+            )
         })
     }
 }
@@ -240,4 +369,117 @@ mod tests {
 
         assert_eq!(credentials.bearer, "gcp-token");
     }
+
+    fn options(pairs: &[(&str, &str)]) -> StorageOptions {
+        StorageOptions(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_workload_identity_credential() {
+        assert!(federated_credential(&options(&[])).unwrap().is_none());
+
+        let audience = "//iam.googleapis.com/projects/1/locations/global/workloadIdentityPools/p/providers/k8s";
+        let credential = federated_credential(&options(&[
+            (GOOGLE_WORKLOAD_IDENTITY_AUDIENCE_KEY, audience),
+            (GOOGLE_WORKLOAD_IDENTITY_TOKEN_FILE_KEY, "/var/run/token"),
+            (
+                GOOGLE_IMPERSONATE_SERVICE_ACCOUNT_KEY,
+                "sa@project.iam.gserviceaccount.com",
+            ),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(credential["type"], "external_account");
+        assert_eq!(credential["audience"], audience);
+        assert_eq!(credential["subject_token_type"], DEFAULT_SUBJECT_TOKEN_TYPE);
+        assert_eq!(credential["credential_source"]["file"], "/var/run/token");
+        assert_eq!(
+            credential["service_account_impersonation_url"],
+            "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/sa@project.iam.gserviceaccount.com:generateAccessToken"
+        );
+
+        // A subject token source is required
+        let err = federated_credential(&options(&[(
+            GOOGLE_WORKLOAD_IDENTITY_AUDIENCE_KEY,
+            audience,
+        )]))
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(GOOGLE_WORKLOAD_IDENTITY_TOKEN_FILE_KEY)
+        );
+    }
+
+    #[test]
+    fn test_impersonation_credential() {
+        let dir = tempfile::tempdir().unwrap();
+        let adc_path = dir.path().join("adc.json");
+        std::fs::write(
+            &adc_path,
+            r#"{"type": "authorized_user", "client_id": "id", "client_secret": "secret", "refresh_token": "token"}"#,
+        )
+        .unwrap();
+
+        let credential = federated_credential(&options(&[
+            (
+                GOOGLE_IMPERSONATE_SERVICE_ACCOUNT_KEY,
+                "sa@project.iam.gserviceaccount.com",
+            ),
+            (GOOGLE_IMPERSONATION_DELEGATES_KEY, "a@p.iam, b@p.iam"),
+            ("google_application_credentials", adc_path.to_str().unwrap()),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(credential["type"], "impersonated_service_account");
+        assert_eq!(credential["source_credentials"]["client_id"], "id");
+        assert_eq!(
+            credential["delegates"],
+            serde_json::json!(["a@p.iam", "b@p.iam"])
+        );
+
+        // Only user credentials can be used as the source
+        std::fs::write(&adc_path, r#"{"type": "service_account"}"#).unwrap();
+        let err = federated_credential(&options(&[
+            (
+                GOOGLE_IMPERSONATE_SERVICE_ACCOUNT_KEY,
+                "sa@project.iam.gserviceaccount.com",
+            ),
+            ("google_application_credentials", adc_path.to_str().unwrap()),
+        ]))
+        .unwrap_err();
+        assert!(err.to_string().contains("authorized user"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_workload_identity_uses_opendal() {
+        let provider = GcsStoreProvider;
+        let url = Url::parse("gs://test-bucket/path").unwrap();
+        let params = ObjectStoreParams {
+            storage_options_accessor: Some(Arc::new(StorageOptionsAccessor::with_static_options(
+                HashMap::from([
+                    (
+                        GOOGLE_WORKLOAD_IDENTITY_AUDIENCE_KEY.to_string(),
+                        "//iam.googleapis.com/projects/1/locations/global/workloadIdentityPools/p/providers/k8s".to_string(),
+                    ),
+                    (
+                        GOOGLE_WORKLOAD_IDENTITY_TOKEN_FILE_KEY.to_string(),
+                        "/var/run/token".to_string(),
+                    ),
+                ]),
+            ))),
+            ..Default::default()
+        };
+
+        let store = provider.new_store(url, &params).await.unwrap();
+        assert!(
+            store.inner.to_string().contains("Opendal"),
+            "{}",
+            store.inner
+        );
+    }
 }
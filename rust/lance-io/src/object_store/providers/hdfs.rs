@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use object_store_opendal::OpendalStore;
+use opendal::{Operator, services::HdfsNative};
+use url::Url;
+
+use crate::object_store::{
+    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, StorageOptions,
+};
+use lance_core::error::{Error, Result};
+
+/// Prefixes of the storage options that are passed to the HDFS client as Hadoop
+/// configuration, e.g. `dfs.client.use.datanode.hostname`.
+const HADOOP_CONFIG_PREFIXES: [&str; 3] = ["dfs.", "fs.", "hadoop."];
+
+/// HDFS object store provider.
+///
+/// Uses OpenDAL's native HDFS client, which speaks the HDFS RPC protocol directly
+/// and does not need a JVM or libhdfs.
+/// URL format: `hdfs://namenode:port/path`
+///
+/// The name node can be overridden with the `hdfs_name_node` storage option, which
+/// accepts a comma separated list of name nodes for HA clusters.  Storage options
+/// that look like Hadoop configuration (`dfs.*`, `fs.*`, `hadoop.*`) are passed
+/// to the client, and `HADOOP_CONF_DIR` is honored as well.
+///
+/// HDFS has no conditional writes.  Commits go through a rename instead, which
+/// HDFS performs atomically and fails if the destination already exists.
+#[derive(Default, Debug)]
+pub struct HdfsStoreProvider;
+
+impl HdfsStoreProvider {
+    /// Resolve the name node from storage_options or the URL authority.
+    fn resolve_name_node(url: &Url, storage_options: &StorageOptions) -> Result<String> {
+        if let Some(name_node) = storage_options
+            .get("hdfs_name_node")
+            .filter(|v| !v.is_empty())
+        {
+            return Ok(name_node.clone());
+        }
+
+        if url.host_str().is_none_or(|host| host.is_empty()) {
+            return Err(Error::invalid_input(
+                "HDFS URL must contain a name node, e.g. hdfs://namenode:8020/path",
+            ));
+        }
+        Ok(format!("hdfs://{}", url.authority()))
+    }
+
+    /// The storage options that should be passed to the client as Hadoop configuration.
+    fn hadoop_config(storage_options: &StorageOptions) -> HashMap<String, String> {
+        storage_options
+            .0
+            .iter()
+            .filter(|(key, _)| {
+                HADOOP_CONFIG_PREFIXES
+                    .iter()
+                    .any(|prefix| key.starts_with(prefix))
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStoreProvider for HdfsStoreProvider {
+    async fn new_store(&self, base_path: Url, params: &ObjectStoreParams) -> Result<ObjectStore> {
+        let block_size = params.block_size.unwrap_or(DEFAULT_CLOUD_BLOCK_SIZE);
+        let storage_options =
+            StorageOptions::new(params.storage_options().cloned().unwrap_or_default());
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
+
+        let name_node = Self::resolve_name_node(&base_path, &storage_options)?;
+        // The store is shared by every dataset of the cluster, so it is rooted at
+        // `/` and paths are resolved from the URL path.
+        let builder = HdfsNative::default()
+            .name_node(&name_node)
+            .root("/")
+            .options(Self::hadoop_config(&storage_options));
+        let operator = Operator::new(builder)
+            .map_err(|e| Error::invalid_input(format!("Failed to create HDFS operator: {:?}", e)))?
+            .finish();
+
+        Ok(ObjectStore {
+            block_size,
+            use_constant_size_upload_parts: params.use_constant_size_upload_parts,
+            list_is_lexically_ordered: params.list_is_lexically_ordered.unwrap_or(false),
+            io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                Arc::new(OpendalStore::new(operator)),
+                "hdfs".to_string(),
+                self.calculate_object_store_prefix(&base_path, params.storage_options())?,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::path::Path;
+
+    fn options(pairs: &[(&str, &str)]) -> StorageOptions {
+        StorageOptions(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_hdfs_store_path() {
+        let provider = HdfsStoreProvider;
+
+        let url = Url::parse("hdfs://namenode:8020/data/embeddings.lance").unwrap();
+        let path = provider.extract_path(&url).unwrap();
+        assert_eq!(path, Path::from("data/embeddings.lance"));
+        let prefix = provider.calculate_object_store_prefix(&url, None).unwrap();
+        assert_eq!(prefix, "hdfs$namenode:8020");
+    }
+
+    #[test]
+    fn test_resolve_name_node() {
+        let url = Url::parse("hdfs://namenode:8020/data").unwrap();
+        let name_node = HdfsStoreProvider::resolve_name_node(&url, &options(&[])).unwrap();
+        assert_eq!(name_node, "hdfs://namenode:8020");
+
+        let ha = "hdfs://nn1:8020,hdfs://nn2:8020";
+        let name_node =
+            HdfsStoreProvider::resolve_name_node(&url, &options(&[("hdfs_name_node", ha)]))
+                .unwrap();
+        assert_eq!(name_node, ha);
+
+        let url = Url::parse("hdfs:///data").unwrap();
+        assert!(HdfsStoreProvider::resolve_name_node(&url, &options(&[])).is_err());
+    }
+
+    #[test]
+    fn test_hadoop_config() {
+        let config = HdfsStoreProvider::hadoop_config(&options(&[
+            ("dfs.client.use.datanode.hostname", "true"),
+            ("hadoop.security.authentication", "kerberos"),
+            ("hdfs_name_node", "hdfs://nn1:8020"),
+            ("timeout", "30s"),
+        ]));
+        assert_eq!(
+            config,
+            HashMap::from([
+                (
+                    "dfs.client.use.datanode.hostname".to_string(),
+                    "true".to_string()
+                ),
+                (
+                    "hadoop.security.authentication".to_string(),
+                    "kerberos".to_string()
+                ),
+            ])
+        );
+    }
+}
@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Read-only object store for datasets served over plain HTTP(S).
+//!
+//! Datasets published on a static web server or a CDN can be opened with an
+//! `https://` (or `http://`) URL.  Objects are read with ranged `GET` requests
+//! and redirects are followed, so the server only needs to support the `Range`
+//! header.  No bucket credentials are needed, but authentication headers can be
+//! sent with every request:
+//!
+//! | Storage Option Key   | Description                                             |
+//! |----------------------|---------------------------------------------------------|
+//! | `http_bearer_token`  | Sent as `Authorization: Bearer <token>`                 |
+//! | `headers.<name>`     | Sent as the `<name>` header, e.g. `headers.x-api-key`   |
+//!
+//! Static servers cannot list directories, so the latest version of a dataset is
+//! found through the version hint file that is written next to the manifests.
+//! Datasets without a hint can still be opened at a specific version.  Servers
+//! that implement WebDAV can be listed as well.  All writes are rejected.
+
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Range;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use object_store::http::HttpBuilder;
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, HeaderValue, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore as OSObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult,
+    RenameOptions, Result as OSResult,
+};
+use url::Url;
+
+use crate::object_store::{
+    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, StorageOptions,
+};
+use lance_core::error::{Error, Result};
+
+pub const HTTP_BEARER_TOKEN_KEY: &str = "http_bearer_token";
+
+/// Provider of read-only stores for `http` and `https` URLs.
+#[derive(Default, Debug)]
+pub struct HttpStoreProvider;
+
+impl HttpStoreProvider {
+    fn client_options(
+        base_path: &Url,
+        storage_options: &StorageOptions,
+    ) -> Result<object_store::ClientOptions> {
+        let mut headers = storage_options.default_headers()?;
+        if let Some(token) = storage_options.get(HTTP_BEARER_TOKEN_KEY) {
+            let mut value = HeaderValue::from_str(&format!("Bearer {token}")).map_err(|e| {
+                Error::invalid_input(format!(
+                    "Invalid value for storage option '{HTTP_BEARER_TOKEN_KEY}': {e}"
+                ))
+            })?;
+            value.set_sensitive(true);
+            headers.insert(http::header::AUTHORIZATION, value);
+        }
+        let mut client_options = object_store::ClientOptions::default();
+        if !headers.is_empty() {
+            client_options = client_options.with_default_headers(headers);
+        }
+        if base_path.scheme() == "http" {
+            client_options = client_options.with_allow_http(true);
+        }
+        Ok(client_options)
+    }
+}
+
+#[async_trait]
+impl ObjectStoreProvider for HttpStoreProvider {
+    async fn new_store(&self, base_path: Url, params: &ObjectStoreParams) -> Result<ObjectStore> {
+        let block_size = params.block_size.unwrap_or(DEFAULT_CLOUD_BLOCK_SIZE);
+        let storage_options =
+            StorageOptions::new(params.storage_options().cloned().unwrap_or_default());
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
+
+        // Stores are shared by every dataset on the same host, so root the store
+        // at the host and resolve paths relative to it.
+        let mut root = base_path.clone();
+        root.set_path("/");
+        root.set_query(None);
+        root.set_fragment(None);
+
+        let client_options = Self::client_options(&base_path, &storage_options)?;
+        let inner = HttpBuilder::new()
+            .with_url(root.as_str())
+            .with_retry(retry_policy.retry_config())
+            .with_client_options(retry_policy.apply_timeouts(client_options))
+            .build()?;
+
+        Ok(ObjectStore {
+            block_size,
+            use_constant_size_upload_parts: false,
+            // Static servers cannot list, the latest version is found with the
+            // version hint instead.
+            list_is_lexically_ordered: false,
+            io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                Arc::new(ReadOnlyStore::new(Arc::new(inner))),
+                base_path.scheme().to_string(),
+                self.calculate_object_store_prefix(&base_path, params.storage_options())?,
+            )
+        })
+    }
+}
+
+/// Wraps an object store and rejects every request that would modify it.
+pub struct ReadOnlyStore {
+    target: Arc<dyn OSObjectStore>,
+}
+
+impl ReadOnlyStore {
+    pub fn new(target: Arc<dyn OSObjectStore>) -> Self {
+        Self { target }
+    }
+
+    fn read_only_error(operation: &str, location: &Path) -> object_store::Error {
+        object_store::Error::NotSupported {
+            source: format!("cannot {operation} '{location}', the object store is read-only")
+                .into(),
+        }
+    }
+}
+
+impl Debug for ReadOnlyStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadOnlyStore")
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
+impl Display for ReadOnlyStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ReadOnly({})", self.target)
+    }
+}
+
+#[async_trait]
+#[deny(clippy::missing_trait_methods)]
+impl OSObjectStore for ReadOnlyStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        _payload: PutPayload,
+        _opts: PutOptions,
+    ) -> OSResult<PutResult> {
+        Err(Self::read_only_error("write", location))
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        _opts: PutMultipartOptions,
+    ) -> OSResult<Box<dyn MultipartUpload>> {
+        Err(Self::read_only_error("write", location))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
+        self.target.get_opts(location, options).await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> OSResult<Vec<Bytes>> {
+        self.target.get_ranges(location, ranges).await
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, OSResult<Path>>,
+    ) -> BoxStream<'static, OSResult<Path>> {
+        locations
+            .map(|location| Err(Self::read_only_error("delete", &location?)))
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.target.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        self.target.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
+        self.target.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(&self, _from: &Path, to: &Path, _opts: CopyOptions) -> OSResult<()> {
+        Err(Self::read_only_error("copy to", to))
+    }
+
+    async fn rename_opts(&self, from: &Path, _to: &Path, _opts: RenameOptions) -> OSResult<()> {
+        Err(Self::read_only_error("rename", from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::object_store::{ObjectStoreRegistry, StorageOptionsAccessor};
+
+    const CONTENT: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    // A minimal static file server that serves `CONTENT` at `/data/file`,
+    // redirects `/old/*` to `/data/*` and requires a bearer token.
+    async fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    let request = String::from_utf8_lossy(&request).to_lowercase();
+                    let mut lines = request.lines();
+                    let mut parts = lines.next().unwrap().split(' ');
+                    let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+                    let headers = lines
+                        .filter_map(|line| line.split_once(": "))
+                        .collect::<HashMap<_, _>>();
+
+                    let response = if headers.get("authorization") != Some(&"bearer secret") {
+                        "HTTP/1.1 401 Unauthorized\r\ncontent-length: 0\r\n\r\n"
+                            .as_bytes()
+                            .to_vec()
+                    } else if let Some(rest) = path.strip_prefix("/old/") {
+                        format!(
+                            "HTTP/1.1 302 Found\r\nlocation: /data/{rest}\r\ncontent-length: 0\r\n\r\n"
+                        )
+                        .into_bytes()
+                    } else if path != "/data/file" {
+                        "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n"
+                            .as_bytes()
+                            .to_vec()
+                    } else {
+                        let range = headers
+                            .get("range")
+                            .and_then(|r| r.strip_prefix("bytes="))
+                            .and_then(|r| r.split_once('-'))
+                            .map(|(start, end)| {
+                                start.parse::<usize>().unwrap()..end.parse::<usize>().unwrap() + 1
+                            });
+                        let (status, body) = match &range {
+                            Some(range) => ("206 Partial Content", &CONTENT[range.clone()]),
+                            None => ("200 OK", CONTENT),
+                        };
+                        let mut response =
+                            format!("HTTP/1.1 {status}\r\ncontent-length: {}\r\n", body.len());
+                        if let Some(range) = range {
+                            response.push_str(&format!(
+                                "content-range: bytes {}-{}/{}\r\n",
+                                range.start,
+                                range.end - 1,
+                                CONTENT.len()
+                            ));
+                        }
+                        response.push_str("\r\n");
+                        let mut response = response.into_bytes();
+                        if method != "head" {
+                            response.extend_from_slice(body);
+                        }
+                        response
+                    };
+                    socket.write_all(&response).await.unwrap();
+                    socket.shutdown().await.ok();
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    fn params(options: &[(&str, &str)]) -> ObjectStoreParams {
+        ObjectStoreParams {
+            storage_options_accessor: Some(Arc::new(StorageOptionsAccessor::with_static_options(
+                options
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ))),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_store() {
+        let base = serve().await;
+        let registry = Arc::new(ObjectStoreRegistry::default());
+        let params = params(&[(HTTP_BEARER_TOKEN_KEY, "secret")]);
+        let (store, path) =
+            ObjectStore::from_uri_and_params(registry.clone(), &format!("{base}/old"), &params)
+                .await
+                .unwrap();
+        assert!(!store.list_is_lexically_ordered);
+        assert_eq!(path, Path::from("old"));
+
+        // Reads follow the redirect and only fetch the requested range
+        let file = path.clone().join("file");
+        assert_eq!(store.size(&file).await.unwrap(), CONTENT.len() as u64);
+        let reader = store.open(&file).await.unwrap();
+        assert_eq!(
+            reader.get_range(3..8).await.unwrap(),
+            Bytes::from_static(&CONTENT[3..8])
+        );
+        assert_eq!(store.read_one_all(&file).await.unwrap(), CONTENT);
+        assert!(!store.exists(&path.clone().join("missing")).await.unwrap());
+
+        // Writes are rejected without reaching the server
+        let err = store.put(&file, b"new data").await.unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+        let err = store.delete(&file).await.unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+
+        // Without the token the server refuses the request
+        let (store, _) =
+            ObjectStore::from_uri_and_params(registry, &base, &ObjectStoreParams::default())
+                .await
+                .unwrap();
+        assert!(store.size(&Path::from("data/file")).await.is_err());
+    }
+
+    #[test]
+    fn test_invalid_bearer_token() {
+        let url = Url::parse("https://example.com/dataset.lance").unwrap();
+        let options = StorageOptions::new(HashMap::from([(
+            HTTP_BEARER_TOKEN_KEY.to_string(),
+            "bad\ntoken".to_string(),
+        )]));
+        let err = HttpStoreProvider::client_options(&url, &options).unwrap_err();
+        assert!(err.to_string().contains(HTTP_BEARER_TOKEN_KEY), "{err}");
+    }
+}
@@ -13,8 +13,8 @@ use url::Url;
 use crate::object_store::dynamic_opendal::DynamicOpenDalStore;
 use crate::object_store::parse_hf_repo_id;
 use crate::object_store::{
-    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, DEFAULT_MAX_IOP_SIZE, ObjectStore,
-    ObjectStoreParams, ObjectStoreProvider, StorageOptions,
+    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, StorageOptions,
 };
 use lance_core::error::{Error, Result};
 
@@ -178,7 +178,7 @@ impl ObjectStoreProvider for HuggingfaceStoreProvider {
 
         let block_size = params.block_size.unwrap_or(DEFAULT_CLOUD_BLOCK_SIZE);
         let storage_options = StorageOptions(params.storage_options().cloned().unwrap_or_default());
-        let download_retry_count = storage_options.download_retry_count();
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
 
         let mut base_options = build_hf_base_options(&repo_type, &repo_id, &storage_options);
         if !base_options.contains_key("hf_token") && !base_options.contains_key("token") {
@@ -207,17 +207,17 @@ impl ObjectStoreProvider for HuggingfaceStoreProvider {
             };
 
         Ok(ObjectStore {
-            scheme: "hf".to_string(),
-            inner,
             block_size,
-            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
             use_constant_size_upload_parts: params.use_constant_size_upload_parts,
             list_is_lexically_ordered: params.list_is_lexically_ordered.unwrap_or(true),
             io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
-            download_retry_count,
-            io_tracker: Default::default(),
-            store_prefix: self
-                .calculate_object_store_prefix(&base_path, params.storage_options())?,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                inner,
+                "hf".to_string(),
+                self.calculate_object_store_prefix(&base_path, params.storage_options())?,
+            )
         })
     }
 
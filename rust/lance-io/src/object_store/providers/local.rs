@@ -4,8 +4,8 @@
 use std::{collections::HashMap, sync::Arc};
 
 use crate::object_store::{
-    DEFAULT_LOCAL_BLOCK_SIZE, DEFAULT_LOCAL_IO_PARALLELISM, DEFAULT_MAX_IOP_SIZE, ObjectStore,
-    ObjectStoreParams, ObjectStoreProvider, StorageOptions,
+    DEFAULT_LOCAL_BLOCK_SIZE, DEFAULT_LOCAL_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, StorageOptions,
 };
 use lance_core::Error;
 use lance_core::error::Result;
@@ -20,19 +20,19 @@ impl ObjectStoreProvider for FileStoreProvider {
     async fn new_store(&self, base_path: Url, params: &ObjectStoreParams) -> Result<ObjectStore> {
         let block_size = params.block_size.unwrap_or(DEFAULT_LOCAL_BLOCK_SIZE);
         let storage_options = StorageOptions(params.storage_options().cloned().unwrap_or_default());
-        let download_retry_count = storage_options.download_retry_count();
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
         Ok(ObjectStore {
-            inner: Arc::new(LocalFileSystem::new()),
-            scheme: base_path.scheme().to_owned(),
             block_size,
-            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
             use_constant_size_upload_parts: false,
             list_is_lexically_ordered: false,
             io_parallelism: DEFAULT_LOCAL_IO_PARALLELISM,
-            download_retry_count,
-            io_tracker: Default::default(),
-            store_prefix: self
-                .calculate_object_store_prefix(&base_path, params.storage_options())?,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                Arc::new(LocalFileSystem::new()),
+                base_path.scheme().to_owned(),
+                self.calculate_object_store_prefix(&base_path, params.storage_options())?,
+            )
         })
     }
 
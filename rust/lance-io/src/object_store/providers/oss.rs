@@ -11,8 +11,8 @@ use url::Url;
 
 use crate::object_store::dynamic_opendal::DynamicOpenDalStore;
 use crate::object_store::{
-    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, DEFAULT_MAX_IOP_SIZE, ObjectStore,
-    ObjectStoreParams, ObjectStoreProvider, StorageOptions,
+    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, StorageOptions,
 };
 use lance_core::error::{Error, Result};
 
@@ -107,6 +107,7 @@ impl ObjectStoreProvider for OssStoreProvider {
     async fn new_store(&self, base_path: Url, params: &ObjectStoreParams) -> Result<ObjectStore> {
         let block_size = params.block_size.unwrap_or(DEFAULT_CLOUD_BLOCK_SIZE);
         let storage_options = StorageOptions(params.storage_options().cloned().unwrap_or_default());
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
 
         let base_options = Self::base_oss_options(&base_path, &storage_options)?;
         let accessor = params.get_accessor();
@@ -135,16 +136,17 @@ impl ObjectStoreProvider for OssStoreProvider {
         }
 
         Ok(ObjectStore {
-            scheme: "oss".to_string(),
-            inner,
             block_size,
-            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
             use_constant_size_upload_parts: params.use_constant_size_upload_parts,
             list_is_lexically_ordered: params.list_is_lexically_ordered.unwrap_or(true),
             io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
-            download_retry_count: storage_options.download_retry_count(),
-            io_tracker: Default::default(),
-            store_prefix: self.calculate_object_store_prefix(&url, params.storage_options())?,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                inner,
+                "oss".to_string(),
+                self.calculate_object_store_prefix(&url, params.storage_options())?,
+            )
         })
     }
 }
@@ -9,8 +9,8 @@ use opendal::{Operator, services::Cos};
 use url::Url;
 
 use crate::object_store::{
-    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, DEFAULT_MAX_IOP_SIZE, ObjectStore,
-    ObjectStoreParams, ObjectStoreProvider, StorageOptions,
+    DEFAULT_CLOUD_BLOCK_SIZE, DEFAULT_CLOUD_IO_PARALLELISM, ObjectStore, ObjectStoreParams,
+    ObjectStoreProvider, StorageOptions,
 };
 use lance_core::error::{Error, Result};
 
@@ -22,6 +22,7 @@ impl ObjectStoreProvider for TencentStoreProvider {
     async fn new_store(&self, base_path: Url, params: &ObjectStoreParams) -> Result<ObjectStore> {
         let block_size = params.block_size.unwrap_or(DEFAULT_CLOUD_BLOCK_SIZE);
         let storage_options = StorageOptions(params.storage_options().cloned().unwrap_or_default());
+        let retry_policy = params.resolve_retry_policy(&storage_options)?;
 
         let bucket = base_path
             .host_str()
@@ -91,16 +92,17 @@ impl ObjectStoreProvider for TencentStoreProvider {
         }
 
         Ok(ObjectStore {
-            scheme: "cos".to_string(),
-            inner: opendal_store,
             block_size,
-            max_iop_size: *DEFAULT_MAX_IOP_SIZE,
             use_constant_size_upload_parts: params.use_constant_size_upload_parts,
             list_is_lexically_ordered: params.list_is_lexically_ordered.unwrap_or(true),
             io_parallelism: DEFAULT_CLOUD_IO_PARALLELISM,
-            download_retry_count: storage_options.download_retry_count(),
-            io_tracker: Default::default(),
-            store_prefix: self.calculate_object_store_prefix(&url, params.storage_options())?,
+            download_retry_count: retry_policy.download_retry_count,
+            list_retry_count: retry_policy.list_retry_count,
+            ..ObjectStore::with_defaults(
+                opendal_store,
+                "cos".to_string(),
+                self.calculate_object_store_prefix(&url, params.storage_options())?,
+            )
         })
     }
 }
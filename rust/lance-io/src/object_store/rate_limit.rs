@@ -0,0 +1,497 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Fixed rate limits for object store requests.
+//!
+//! [`RateLimitedStore`] caps the number of requests per second and the read and
+//! write throughput of an object store.  Unlike the
+//! [`AimdThrottledStore`](super::throttle::AimdThrottledStore), which backs off when
+//! the store reports throttling, these limits are fixed.  They are meant for keeping
+//! Lance within its share of a bucket that is shared with other workloads.
+//!
+//! Limits are shared by store prefix: every store in the process that points at
+//! the same bucket (with the same limits) draws from the same [`RateLimiter`].
+//! A limiter can also be shared explicitly with [`RateLimitWrapper`].
+//!
+//! The read throughput limit overlaps with the bandwidth limit of an
+//! [`IoBudget`](crate::scheduler::IoBudget), but they apply at different layers.
+//! The budget is charged by the scan scheduler for the bytes it requests, across
+//! every store it is attached to.  The rate limit is charged by the store for the
+//! bytes it actually returns, including reads that do not go through a scheduler
+//! (e.g. manifests).  When both are set the lower limit wins.
+//!
+//! The limits are configured through storage options:
+//!
+//! | Storage Option Key                        | Env Var                                   | Default |
+//! |-------------------------------------------|-------------------------------------------|---------|
+//! | `lance_rate_limit_iops`                   | `LANCE_RATE_LIMIT_IOPS`                   | (none)  |
+//! | `lance_rate_limit_read_bytes_per_second`  | `LANCE_RATE_LIMIT_READ_BYTES_PER_SECOND`  | (none)  |
+//! | `lance_rate_limit_write_bytes_per_second` | `LANCE_RATE_LIMIT_WRITE_BYTES_PER_SECOND` | (none)  |
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::{Arc, LazyLock, Mutex, Weak};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, stream};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetRange, GetResult, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, RenameOptions,
+    Result as OSResult, UploadPart,
+};
+
+use super::WrappingObjectStore;
+use crate::utils::token_bucket::TokenBucket;
+
+pub const RATE_LIMIT_IOPS_KEY: &str = "lance_rate_limit_iops";
+pub const RATE_LIMIT_READ_BYTES_PER_SECOND_KEY: &str = "lance_rate_limit_read_bytes_per_second";
+pub const RATE_LIMIT_WRITE_BYTES_PER_SECOND_KEY: &str = "lance_rate_limit_write_bytes_per_second";
+
+/// Limits applied by a [`RateLimiter`].  Unset limits are not enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RateLimitConfig {
+    /// Max # of requests per second, of any kind.
+    pub max_iops: Option<u64>,
+    /// Max # of bytes read per second.
+    pub max_read_bytes_per_second: Option<u64>,
+    /// Max # of bytes written per second.
+    pub max_write_bytes_per_second: Option<u64>,
+}
+
+impl RateLimitConfig {
+    pub fn with_max_iops(self, max_iops: u64) -> Self {
+        Self {
+            max_iops: Some(max_iops),
+            ..self
+        }
+    }
+
+    pub fn with_max_read_bytes_per_second(self, max_read_bytes_per_second: u64) -> Self {
+        Self {
+            max_read_bytes_per_second: Some(max_read_bytes_per_second),
+            ..self
+        }
+    }
+
+    pub fn with_max_write_bytes_per_second(self, max_write_bytes_per_second: u64) -> Self {
+        Self {
+            max_write_bytes_per_second: Some(max_write_bytes_per_second),
+            ..self
+        }
+    }
+
+    /// Build the configuration from storage options, falling back to environment
+    /// variables.  Returns `None` if no limit is configured.
+    pub fn from_storage_options(
+        storage_options: Option<&HashMap<String, String>>,
+    ) -> lance_core::Result<Option<Self>> {
+        let resolve_u64 = |key: &str| -> lance_core::Result<Option<u64>> {
+            let val = storage_options
+                .and_then(|opts| opts.get(key).cloned())
+                .or_else(|| std::env::var(key.to_ascii_uppercase()).ok());
+            match val {
+                Some(val) => match val.parse::<u64>() {
+                    Ok(limit) if limit > 0 => Ok(Some(limit)),
+                    _ => Err(lance_core::Error::invalid_input(format!(
+                        "Invalid value for storage option '{key}': '{val}', must be a positive integer"
+                    ))),
+                },
+                None => Ok(None),
+            }
+        };
+
+        let config = Self {
+            max_iops: resolve_u64(RATE_LIMIT_IOPS_KEY)?,
+            max_read_bytes_per_second: resolve_u64(RATE_LIMIT_READ_BYTES_PER_SECOND_KEY)?,
+            max_write_bytes_per_second: resolve_u64(RATE_LIMIT_WRITE_BYTES_PER_SECOND_KEY)?,
+        };
+        Ok((config != Self::default()).then_some(config))
+    }
+}
+
+/// Token buckets enforcing a [`RateLimitConfig`]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    iops: Option<TokenBucket>,
+    read_bytes: Option<TokenBucket>,
+    write_bytes: Option<TokenBucket>,
+}
+
+impl Debug for RateLimiter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Limiters in use, by store prefix and config
+type LimiterRegistry = HashMap<(String, RateLimitConfig), Weak<RateLimiter>>;
+
+static RATE_LIMITERS: LazyLock<Mutex<LimiterRegistry>> = LazyLock::new(Default::default);
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            iops: config.max_iops.map(TokenBucket::with_rate),
+            read_bytes: config.max_read_bytes_per_second.map(TokenBucket::with_rate),
+            write_bytes: config
+                .max_write_bytes_per_second
+                .map(TokenBucket::with_rate),
+        })
+    }
+
+    /// The limiter of the stores with `store_prefix` and `config`
+    ///
+    /// Returns the limiter already in use by such a store, if there is one.
+    pub fn shared(store_prefix: &str, config: RateLimitConfig) -> Arc<Self> {
+        let mut limiters = RATE_LIMITERS.lock().unwrap();
+        limiters.retain(|_, limiter| limiter.strong_count() > 0);
+        let key = (store_prefix.to_string(), config);
+        if let Some(limiter) = limiters.get(&key).and_then(Weak::upgrade) {
+            return limiter;
+        }
+        let limiter = Self::new(config);
+        limiters.insert(key, Arc::downgrade(&limiter));
+        limiter
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Wait until a request that reads `read_bytes` and writes `write_bytes` may
+    /// be made.
+    pub async fn acquire(&self, read_bytes: u64, write_bytes: u64) {
+        let wait = [
+            (&self.iops, 1),
+            (&self.read_bytes, read_bytes),
+            (&self.write_bytes, write_bytes),
+        ]
+        .into_iter()
+        .filter_map(|(bucket, amount)| {
+            bucket
+                .as_ref()
+                .filter(|_| amount > 0)
+                .map(|bucket| bucket.reserve(amount as f64))
+        })
+        .max()
+        .unwrap_or_default();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Wait until `read_bytes` more bytes may be read, without counting a request
+    async fn acquire_read_bytes(&self, read_bytes: u64) {
+        if let Some(bucket) = &self.read_bytes {
+            bucket.acquire(read_bytes as f64).await;
+        }
+    }
+}
+
+/// Wraps object stores with a [`RateLimitedStore`] that shares one [`RateLimiter`]
+#[derive(Debug, Clone)]
+pub struct RateLimitWrapper {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitWrapper {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl WrappingObjectStore for RateLimitWrapper {
+    fn wrap(&self, _store_prefix: &str, original: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+        Arc::new(RateLimitedStore::new(original, self.limiter.clone()))
+    }
+}
+
+/// An [`ObjectStore`] that waits on a [`RateLimiter`] before every request
+///
+/// Reads of a known range are charged before the request.  Reads of an unknown
+/// size (e.g. whole objects) are charged once the response arrives, before it is
+/// returned.  Every part of a multipart upload counts as a request.
+pub struct RateLimitedStore {
+    target: Arc<dyn ObjectStore>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl Debug for RateLimitedStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimitedStore")
+            .field("target", &self.target)
+            .field("limiter", &self.limiter)
+            .finish()
+    }
+}
+
+impl Display for RateLimitedStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RateLimitedStore({})", self.target)
+    }
+}
+
+impl RateLimitedStore {
+    pub fn new(target: Arc<dyn ObjectStore>, limiter: Arc<RateLimiter>) -> Self {
+        Self { target, limiter }
+    }
+
+    pub fn limiter(&self) -> &Arc<RateLimiter> {
+        &self.limiter
+    }
+}
+
+#[async_trait]
+impl ObjectStore for RateLimitedStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> OSResult<PutResult> {
+        self.limiter
+            .acquire(0, payload.content_length() as u64)
+            .await;
+        self.target.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> OSResult<Box<dyn MultipartUpload>> {
+        self.limiter.acquire(0, 0).await;
+        let target = self.target.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(RateLimitedMultipartUpload {
+            target,
+            limiter: self.limiter.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
+        let known_size = match &options.range {
+            _ if options.head => Some(0),
+            Some(GetRange::Bounded(range)) => Some(range.end.saturating_sub(range.start)),
+            _ => None,
+        };
+        self.limiter.acquire(known_size.unwrap_or(0), 0).await;
+        let result = self.target.get_opts(location, options).await?;
+        if known_size.is_none() {
+            self.limiter
+                .acquire_read_bytes(result.range.end - result.range.start)
+                .await;
+        }
+        Ok(result)
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, OSResult<Path>>,
+    ) -> BoxStream<'static, OSResult<Path>> {
+        let limiter = self.limiter.clone();
+        let locations = locations
+            .then(move |location| {
+                let limiter = limiter.clone();
+                async move {
+                    limiter.acquire(0, 0).await;
+                    location
+                }
+            })
+            .boxed();
+        self.target.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        let limiter = self.limiter.clone();
+        let list = self.target.list(prefix);
+        stream::once(async move {
+            limiter.acquire(0, 0).await;
+            list
+        })
+        .flatten()
+        .boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, OSResult<ObjectMeta>> {
+        let limiter = self.limiter.clone();
+        let list = self.target.list_with_offset(prefix, offset);
+        stream::once(async move {
+            limiter.acquire(0, 0).await;
+            list
+        })
+        .flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> OSResult<ListResult> {
+        self.limiter.acquire(0, 0).await;
+        self.target.list_with_delimiter(prefix).await
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, opts: CopyOptions) -> OSResult<()> {
+        self.limiter.acquire(0, 0).await;
+        self.target.copy_opts(from, to, opts).await
+    }
+
+    async fn rename_opts(&self, from: &Path, to: &Path, opts: RenameOptions) -> OSResult<()> {
+        self.limiter.acquire(0, 0).await;
+        self.target.rename_opts(from, to, opts).await
+    }
+}
+
+/// A [`MultipartUpload`] that waits on a [`RateLimiter`] before every part
+struct RateLimitedMultipartUpload {
+    target: Box<dyn MultipartUpload>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl Debug for RateLimitedMultipartUpload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimitedMultipartUpload").finish()
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for RateLimitedMultipartUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let limiter = self.limiter.clone();
+        let num_bytes = data.content_length() as u64;
+        // Call put_part synchronously to preserve part ordering, the part is only
+        // sent once the returned future is polled.
+        let fut = self.target.put_part(data);
+        async move {
+            limiter.acquire(0, num_bytes).await;
+            fut.await
+        }
+        .boxed()
+    }
+
+    async fn complete(&mut self) -> OSResult<PutResult> {
+        self.limiter.acquire(0, 0).await;
+        self.target.complete().await
+    }
+
+    async fn abort(&mut self) -> OSResult<()> {
+        self.target.abort().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    use bytes::Bytes;
+    use object_store::ObjectStoreExt;
+    use object_store::memory::InMemory;
+
+    fn make_store(config: RateLimitConfig) -> RateLimitedStore {
+        RateLimitedStore::new(Arc::new(InMemory::new()), RateLimiter::new(config))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_iops_limit() {
+        let store = make_store(RateLimitConfig::default().with_max_iops(10));
+        let path = Path::from("file");
+        store
+            .put(&path, Bytes::from_static(b"data").into())
+            .await
+            .unwrap();
+
+        let start = tokio::time::Instant::now();
+        // The first second's worth of requests (one of which was the put) are
+        // served from the bucket, the rest at 10 per second
+        for _ in 0..29 {
+            store.head(&path).await.unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(1990), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(2100), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throughput_limits() {
+        let store = make_store(
+            RateLimitConfig::default()
+                .with_max_read_bytes_per_second(1000)
+                .with_max_write_bytes_per_second(500),
+        );
+        let path = Path::from("file");
+        let data = Bytes::from(vec![1u8; 2000]);
+
+        let start = tokio::time::Instant::now();
+        // 500 bytes are available immediately, the rest takes 3 seconds
+        store.put(&path, data.into()).await.unwrap();
+        assert_eq!(start.elapsed().as_secs(), 3);
+
+        let start = tokio::time::Instant::now();
+        store.get_range(&path, 0..1000).await.unwrap();
+        assert_eq!(start.elapsed().as_secs(), 0);
+        // Whole object reads are charged after the response
+        store.get(&path).await.unwrap();
+        assert_eq!(start.elapsed().as_secs(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_multipart_upload() {
+        let store = make_store(RateLimitConfig::default().with_max_write_bytes_per_second(100));
+        let path = Path::from("file");
+        let start = tokio::time::Instant::now();
+        let mut upload = store.put_multipart(&path).await.unwrap();
+        for _ in 0..3 {
+            upload
+                .put_part(Bytes::from(vec![0u8; 100]).into())
+                .await
+                .unwrap();
+        }
+        upload.complete().await.unwrap();
+        assert_eq!(start.elapsed().as_secs(), 2);
+        assert_eq!(store.head(&path).await.unwrap().size, 300);
+    }
+
+    #[test]
+    fn test_shared_limiters() {
+        let config = RateLimitConfig::default().with_max_iops(100);
+        let a = RateLimiter::shared("s3$bucket", config);
+        let b = RateLimiter::shared("s3$bucket", config);
+        assert!(Arc::ptr_eq(&a, &b));
+        let c = RateLimiter::shared("s3$other", config);
+        assert!(!Arc::ptr_eq(&a, &c));
+        let d = RateLimiter::shared("s3$bucket", config.with_max_iops(50));
+        assert!(!Arc::ptr_eq(&a, &d));
+    }
+
+    #[test]
+    fn test_config_from_storage_options() {
+        assert_eq!(RateLimitConfig::from_storage_options(None).unwrap(), None);
+        let options = HashMap::from([
+            (RATE_LIMIT_IOPS_KEY.to_string(), "100".to_string()),
+            (
+                RATE_LIMIT_READ_BYTES_PER_SECOND_KEY.to_string(),
+                "1048576".to_string(),
+            ),
+        ]);
+        let config = RateLimitConfig::from_storage_options(Some(&options))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            config,
+            RateLimitConfig::default()
+                .with_max_iops(100)
+                .with_max_read_bytes_per_second(1048576)
+        );
+
+        let options = HashMap::from([(RATE_LIMIT_IOPS_KEY.to_string(), "0".to_string())]);
+        assert!(RateLimitConfig::from_storage_options(Some(&options)).is_err());
+    }
+}
@@ -0,0 +1,398 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Retry and timeout policy for object store requests.
+//!
+//! There are two layers of retries when talking to an object store:
+//!
+//! - The HTTP client of the cloud stores retries individual requests (reads,
+//!   writes, and list pages) with exponential backoff.
+//! - Lance retries whole downloads and list streams on errors the client does
+//!   not retry, such as failures while decoding the response body.
+//!
+//! [`RetryPolicy`] configures both layers, along with the per-request and
+//! connect timeouts.  It can be set directly on
+//! [`ObjectStoreParams::retry_policy`](super::ObjectStoreParams::retry_policy)
+//! or through storage options.  The timeouts are set with the `request_timeout`
+//! and `connect_timeout` client options, which take precedence over the policy.
+//!
+//! | Storage Option Key             | Default | Description                                              |
+//! |--------------------------------|---------|----------------------------------------------------------|
+//! | `client_max_retries`           | 3       | Max retries of a single request                           |
+//! | `client_retry_timeout`         | 180     | Seconds after which a request is no longer retried        |
+//! | `client_retry_init_backoff_ms` | 100     | Backoff before the first retry, in milliseconds           |
+//! | `client_retry_max_backoff_ms`  | 15000   | Max backoff between retries, in milliseconds              |
+//! | `client_retry_backoff_base`    | 2.0     | Multiplier applied to the backoff after each retry        |
+//! | `download_retry_count`         | 3       | Retries of a download that fails after the client retries |
+//! | `list_retry_count`             | 5       | Retries of a list that fails after the client retries     |
+//!
+//! Lance only retries errors that [`is_retryable`] classifies as transient, and a
+//! download that fails while streaming the response body is resumed from the
+//! failed offset instead of being restarted.
+
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use lance_core::{Error, ErrorCode, Result};
+
+use super::{DEFAULT_DOWNLOAD_RETRY_COUNT, StorageOptions};
+
+pub const CLIENT_RETRY_INIT_BACKOFF_MS_KEY: &str = "client_retry_init_backoff_ms";
+pub const CLIENT_RETRY_MAX_BACKOFF_MS_KEY: &str = "client_retry_max_backoff_ms";
+pub const CLIENT_RETRY_BACKOFF_BASE_KEY: &str = "client_retry_backoff_base";
+pub const LIST_RETRY_COUNT_KEY: &str = "list_retry_count";
+
+pub const DEFAULT_LIST_RETRY_COUNT: usize = 5;
+
+/// The multiplier applied to the backoff after each retry.
+///
+/// It is a finite number of at least 1.0, checked on construction, so that
+/// [`RetryPolicy`] can be compared and hashed as part of the store parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackoffBase(f64);
+
+impl BackoffBase {
+    pub fn try_new(base: f64) -> Result<Self> {
+        if !base.is_finite() || base < 1.0 {
+            return Err(Error::invalid_input(format!(
+                "Backoff base must be a number of at least 1.0, got {base}"
+            )));
+        }
+        Ok(Self(base))
+    }
+
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Default for BackoffBase {
+    fn default() -> Self {
+        Self(2.0)
+    }
+}
+
+impl Eq for BackoffBase {}
+
+impl Hash for BackoffBase {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+/// Retry and timeout settings applied to every request of an object store.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    /// Max number of times the client retries a single request.
+    pub max_retries: usize,
+    /// Elapsed time after which the client stops retrying a request.
+    pub retry_timeout: Duration,
+    /// Backoff before the first retry.
+    pub init_backoff: Duration,
+    /// Upper bound of the backoff between retries.
+    pub max_backoff: Duration,
+    /// Multiplier applied to the backoff after each retry.
+    pub backoff_base: BackoffBase,
+    /// Timeout of a single request.  `None` keeps the client default.
+    pub request_timeout: Option<Duration>,
+    /// Timeout for establishing a connection.  `None` keeps the client default.
+    pub connect_timeout: Option<Duration>,
+    /// Number of times Lance retries a download that fails after the client
+    /// has given up.
+    pub download_retry_count: usize,
+    /// Number of times Lance resumes a list that fails after the client has
+    /// given up.
+    pub list_retry_count: usize,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            retry_timeout: Duration::from_secs(180),
+            init_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(15),
+            backoff_base: BackoffBase::default(),
+            request_timeout: None,
+            connect_timeout: None,
+            download_retry_count: DEFAULT_DOWNLOAD_RETRY_COUNT,
+            list_retry_count: DEFAULT_LIST_RETRY_COUNT,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn with_max_retries(self, max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            ..self
+        }
+    }
+
+    pub fn with_retry_timeout(self, retry_timeout: Duration) -> Self {
+        Self {
+            retry_timeout,
+            ..self
+        }
+    }
+
+    /// Set the exponential backoff between retries of a request.
+    pub fn with_backoff(
+        self,
+        init_backoff: Duration,
+        max_backoff: Duration,
+        base: BackoffBase,
+    ) -> Self {
+        Self {
+            init_backoff,
+            max_backoff,
+            backoff_base: base,
+            ..self
+        }
+    }
+
+    pub fn with_request_timeout(self, request_timeout: Duration) -> Self {
+        Self {
+            request_timeout: Some(request_timeout),
+            ..self
+        }
+    }
+
+    pub fn with_connect_timeout(self, connect_timeout: Duration) -> Self {
+        Self {
+            connect_timeout: Some(connect_timeout),
+            ..self
+        }
+    }
+
+    pub fn with_download_retry_count(self, download_retry_count: usize) -> Self {
+        Self {
+            download_retry_count,
+            ..self
+        }
+    }
+
+    pub fn with_list_retry_count(self, list_retry_count: usize) -> Self {
+        Self {
+            list_retry_count,
+            ..self
+        }
+    }
+
+    /// Build the retry policy from storage options.
+    ///
+    /// Options that are not set keep their default.  Returns an error if one of
+    /// the backoff or list retry options cannot be parsed.
+    pub fn from_storage_options(storage_options: &StorageOptions) -> Result<Self> {
+        let default = Self::default();
+        let backoff_base = match storage_options.parse::<f64>(CLIENT_RETRY_BACKOFF_BASE_KEY)? {
+            Some(base) => BackoffBase::try_new(base).map_err(|_| {
+                Error::invalid_input(format!(
+                    "Invalid value for storage option '{CLIENT_RETRY_BACKOFF_BASE_KEY}': \
+                     '{base}', must be at least 1.0"
+                ))
+            })?,
+            None => default.backoff_base,
+        };
+        Ok(Self {
+            max_retries: storage_options.client_max_retries(),
+            retry_timeout: Duration::from_secs(storage_options.client_retry_timeout()),
+            init_backoff: storage_options
+                .parse::<u64>(CLIENT_RETRY_INIT_BACKOFF_MS_KEY)?
+                .map(Duration::from_millis)
+                .unwrap_or(default.init_backoff),
+            max_backoff: storage_options
+                .parse::<u64>(CLIENT_RETRY_MAX_BACKOFF_MS_KEY)?
+                .map(Duration::from_millis)
+                .unwrap_or(default.max_backoff),
+            backoff_base,
+            request_timeout: None,
+            connect_timeout: None,
+            download_retry_count: storage_options.download_retry_count(),
+            list_retry_count: storage_options
+                .parse::<usize>(LIST_RETRY_COUNT_KEY)?
+                .unwrap_or(default.list_retry_count),
+        })
+    }
+
+    /// The [`RetryConfig`](object_store::RetryConfig) for the object store client.
+    #[cfg(any(feature = "aws", feature = "azure", feature = "gcp", feature = "http"))]
+    pub fn retry_config(&self) -> object_store::RetryConfig {
+        object_store::RetryConfig {
+            backoff: object_store::BackoffConfig {
+                init_backoff: self.init_backoff,
+                max_backoff: self.max_backoff,
+                base: self.backoff_base.get(),
+            },
+            max_retries: self.max_retries,
+            retry_timeout: self.retry_timeout,
+        }
+    }
+
+    /// Apply the request and connect timeouts to the client options.
+    #[cfg(any(feature = "aws", feature = "azure", feature = "gcp", feature = "http"))]
+    pub fn apply_timeouts(
+        &self,
+        options: object_store::ClientOptions,
+    ) -> object_store::ClientOptions {
+        let options = match self.request_timeout {
+            Some(timeout) => options.with_timeout(timeout),
+            None => options,
+        };
+        match self.connect_timeout {
+            Some(timeout) => options.with_connect_timeout(timeout),
+            None => options,
+        }
+    }
+}
+
+/// Whether a failed object store request may succeed if it is retried.
+///
+/// Errors about the request itself, such as a missing object, a failed
+/// precondition, an invalid path or missing permissions, are fatal.  Everything
+/// else, such as timeouts, dropped connections and server errors, is considered
+/// transient.
+///
+/// This is the object store half of [`ErrorCode::is_retryable`], which applies
+/// the same classification to object store errors wrapped in a Lance error.
+pub fn is_retryable(error: &object_store::Error) -> bool {
+    ErrorCode::from_object_store(error).is_retryable()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::object_store::{
+        ObjectStore, ObjectStoreParams, ObjectStoreRegistry, StorageOptionsAccessor,
+    };
+
+    fn options(pairs: &[(&str, &str)]) -> StorageOptions {
+        StorageOptions(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn test_from_storage_options() {
+        let policy = RetryPolicy::from_storage_options(&options(&[])).unwrap();
+        assert_eq!(policy, RetryPolicy::default());
+
+        let policy = RetryPolicy::from_storage_options(&options(&[
+            ("client_max_retries", "7"),
+            ("client_retry_timeout", "30"),
+            ("client_retry_init_backoff_ms", "50"),
+            ("client_retry_max_backoff_ms", "2000"),
+            ("client_retry_backoff_base", "3"),
+            ("download_retry_count", "4"),
+            ("list_retry_count", "0"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            policy,
+            RetryPolicy::default()
+                .with_max_retries(7)
+                .with_retry_timeout(Duration::from_secs(30))
+                .with_backoff(
+                    Duration::from_millis(50),
+                    Duration::from_secs(2),
+                    BackoffBase::try_new(3.0).unwrap(),
+                )
+                .with_download_retry_count(4)
+                .with_list_retry_count(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_on_store() {
+        let registry = Arc::new(ObjectStoreRegistry::default());
+        let params = ObjectStoreParams {
+            storage_options_accessor: Some(Arc::new(StorageOptionsAccessor::with_static_options(
+                HashMap::from([("list_retry_count".to_string(), "2".to_string())]),
+            ))),
+            ..Default::default()
+        };
+        let (store, _) = ObjectStore::from_uri_and_params(registry.clone(), "memory:///", &params)
+            .await
+            .unwrap();
+        assert_eq!(store.list_retry_count, 2);
+        assert_eq!(store.download_retry_count, DEFAULT_DOWNLOAD_RETRY_COUNT);
+
+        // An explicit policy takes precedence over the storage options
+        let params = ObjectStoreParams {
+            retry_policy: Some(RetryPolicy::default().with_download_retry_count(7)),
+            ..params
+        };
+        let (store, _) = ObjectStore::from_uri_and_params(registry, "memory:///", &params)
+            .await
+            .unwrap();
+        assert_eq!(store.list_retry_count, DEFAULT_LIST_RETRY_COUNT);
+        assert_eq!(store.download_retry_count, 7);
+
+        // Stores passed in through the params read the storage options too
+        #[allow(deprecated)]
+        let params = ObjectStoreParams {
+            object_store: Some((
+                Arc::new(object_store::memory::InMemory::new()),
+                url::Url::parse("memory:///").unwrap(),
+            )),
+            retry_policy: None,
+            ..params
+        };
+        let (store, _) = ObjectStore::from_uri_and_params(
+            Arc::new(ObjectStoreRegistry::default()),
+            "memory:///",
+            &params,
+        )
+        .await
+        .unwrap();
+        assert_eq!(store.list_retry_count, 2);
+    }
+
+    #[test]
+    fn test_backoff_base() {
+        assert_eq!(BackoffBase::try_new(1.5).unwrap().get(), 1.5);
+        for base in [0.5, f64::NAN, f64::INFINITY] {
+            assert!(BackoffBase::try_new(base).is_err(), "{base}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_storage_options() {
+        for (key, value) in [
+            ("client_retry_backoff_base", "0.5"),
+            ("client_retry_init_backoff_ms", "1.5"),
+            ("list_retry_count", "many"),
+        ] {
+            let err = RetryPolicy::from_storage_options(&options(&[(key, value)])).unwrap_err();
+            assert!(err.to_string().contains(key), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let path = "a/b".to_string();
+        let source = || "boom".into();
+        assert!(is_retryable(&object_store::Error::Generic {
+            store: "test",
+            source: source(),
+        }));
+        assert!(!is_retryable(&object_store::Error::NotFound {
+            path: path.clone(),
+            source: source(),
+        }));
+        assert!(!is_retryable(&object_store::Error::Precondition {
+            path: path.clone(),
+            source: source(),
+        }));
+        assert!(!is_retryable(&object_store::Error::PermissionDenied {
+            path,
+            source: source(),
+        }));
+    }
+}
@@ -43,8 +43,12 @@ const DEFAULT_REFRESH_OFFSET_MILLIS: u64 = 60_000;
 ///
 /// # Current Use Cases
 ///
-/// - **Temporary Credentials**: Fetch short-lived AWS temporary credentials that expire
-///   after a set time period, with automatic refresh before expiration
+/// - **Temporary Credentials**: Fetch short-lived credentials (AWS STS credentials,
+///   Azure SAS or bearer tokens, GCS access tokens) that expire after a set time
+///   period, with automatic refresh before expiration.  If a refresh fails while the
+///   cached credentials are still valid, they keep being used until they expire.  If
+///   a request is rejected because the credentials lapsed early, they are fetched
+///   again and the request is retried once.
 ///
 /// # Future Possible Use Cases
 ///
@@ -196,6 +200,13 @@ impl fmt::Debug for StorageOptionsAccessor {
     }
 }
 
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::from_secs(0))
+        .as_millis() as u64
+}
+
 #[derive(Debug, Clone)]
 struct CachedStorageOptions {
     options: HashMap<String, String>,
@@ -387,12 +398,33 @@ impl StorageOptionsAccessor {
             provider.provider_id()
         );
 
-        let storage_options_map = provider.fetch_storage_options().await.map_err(|e| {
-            Error::io_source(Box::new(std::io::Error::other(format!(
-                "Failed to fetch storage options: {}",
-                e
-            ))))
-        })?;
+        let storage_options_map = match provider.fetch_storage_options().await {
+            Ok(storage_options_map) => storage_options_map,
+            // Options are refreshed ahead of their expiration.  If the refresh fails
+            // while the cached options are still valid, keep using them so that a
+            // transient provider failure doesn't fail in-flight requests.
+            Err(e) => match &*cache {
+                Some(cached_opts)
+                    if cached_opts
+                        .expires_at_millis
+                        .is_some_and(|expires_at| now_millis() < expires_at) =>
+                {
+                    log::warn!(
+                        "Failed to refresh storage options from provider: {}, \
+                         using cached options until they expire: {}",
+                        provider.provider_id(),
+                        e
+                    );
+                    return Ok(Some(super::StorageOptions(cached_opts.options.clone())));
+                }
+                _ => {
+                    return Err(Error::io_source(Box::new(std::io::Error::other(format!(
+                        "Failed to fetch storage options: {}",
+                        e
+                    )))));
+                }
+            },
+        };
 
         let Some(options) = storage_options_map else {
             // Provider returned None, fall back to initial options or use defaults
@@ -414,10 +446,7 @@ impl StorageOptionsAccessor {
             .and_then(|s| s.parse::<u64>().ok());
 
         if let Some(expires_at) = expires_at_millis {
-            let now_ms = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or(Duration::from_secs(0))
-                .as_millis() as u64;
+            let now_ms = now_millis();
             let expires_in_secs = (expires_at.saturating_sub(now_ms)) / 1000;
             log::debug!(
                 "Successfully refreshed storage options from provider: {}, options expire in {} seconds",
@@ -444,10 +473,7 @@ impl StorageOptionsAccessor {
             None => true,
             Some(cached_opts) => {
                 if let Some(expires_at_millis) = cached_opts.expires_at_millis {
-                    let now_ms = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or(Duration::from_secs(0))
-                        .as_millis() as u64;
+                    let now_ms = now_millis();
 
                     // Refresh if we're within the refresh offset of expiration
                     let refresh_offset_millis = self.refresh_offset.as_millis() as u64;
@@ -460,6 +486,21 @@ impl StorageOptionsAccessor {
         }
     }
 
+    /// Mark the cached options as expired, so that the next access fetches
+    /// fresh options from the provider.
+    ///
+    /// This is used when a request is rejected because the credentials expired
+    /// or were revoked earlier than the provider said they would.  Does nothing
+    /// if there is no provider.
+    pub async fn invalidate(&self) {
+        if self.provider.is_none() {
+            return;
+        }
+        if let Some(cached_opts) = self.cache.write().await.as_mut() {
+            cached_opts.expires_at_millis = Some(0);
+        }
+    }
+
     /// Get the initial storage options without refresh
     ///
     /// Returns the initial options that were provided when creating the accessor.
@@ -748,4 +789,72 @@ mod tests {
         accessor.get_storage_options().await.unwrap();
         assert_eq!(mock_provider.get_call_count().await, 1);
     }
+
+    #[derive(Debug)]
+    struct FailingStorageOptionsProvider;
+
+    #[async_trait]
+    impl StorageOptionsProvider for FailingStorageOptionsProvider {
+        async fn fetch_storage_options(&self) -> Result<Option<HashMap<String, String>>> {
+            Err(Error::internal("provider unavailable"))
+        }
+
+        fn provider_id(&self) -> String {
+            "FailingStorageOptionsProvider".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_failure_uses_valid_cache() {
+        MockClock::set_system_time(Duration::from_secs(100_000));
+
+        let now_ms = MockClock::system_time().as_millis() as u64;
+        let initial = HashMap::from([
+            ("aws_access_key_id".to_string(), "INITIAL_KEY".to_string()),
+            (
+                EXPIRES_AT_MILLIS_KEY.to_string(),
+                (now_ms + 600_000).to_string(),
+            ),
+            (REFRESH_OFFSET_MILLIS_KEY.to_string(), "300000".to_string()),
+        ]);
+        let accessor = StorageOptionsAccessor::with_initial_and_provider(
+            initial,
+            Arc::new(FailingStorageOptionsProvider),
+        );
+
+        // Within the refresh offset the refresh fails, but the options are still valid
+        MockClock::set_system_time(Duration::from_secs(100_000 + 360));
+        let result = accessor.get_storage_options().await.unwrap();
+        assert_eq!(result.0.get("aws_access_key_id").unwrap(), "INITIAL_KEY");
+
+        // Once they have expired the failure is returned
+        MockClock::set_system_time(Duration::from_secs(100_000 + 660));
+        assert!(accessor.get_storage_options().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate() {
+        MockClock::set_system_time(Duration::from_secs(100_000));
+
+        let mock_provider = Arc::new(MockStorageOptionsProvider::new(Some(600_000)));
+        let accessor = StorageOptionsAccessor::with_provider(mock_provider.clone());
+        let result = accessor.get_storage_options().await.unwrap();
+        assert_eq!(result.0.get("aws_access_key_id").unwrap(), "AKID_1");
+
+        accessor.invalidate().await;
+        let result = accessor.get_storage_options().await.unwrap();
+        assert_eq!(result.0.get("aws_access_key_id").unwrap(), "AKID_2");
+        assert_eq!(mock_provider.get_call_count().await, 2);
+
+        // Static options are never invalidated
+        let accessor = StorageOptionsAccessor::with_static_options(HashMap::from([(
+            "key".to_string(),
+            "value".to_string(),
+        )]));
+        accessor.invalidate().await;
+        assert_eq!(
+            accessor.get_storage_options().await.unwrap().0["key"],
+            "value"
+        );
+    }
 }
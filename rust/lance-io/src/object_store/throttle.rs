@@ -40,9 +40,10 @@ use object_store::{
     UploadPart,
 };
 use rand::Rng;
-use tokio::sync::Mutex;
 use tracing::{debug, warn};
 
+use crate::utils::token_bucket::TokenBucket;
+
 /// Check whether an `object_store::Error` represents a throttle response
 /// (HTTP 429 / 503) from a cloud object store.
 ///
@@ -316,17 +317,10 @@ impl AimdThrottleConfig {
     }
 }
 
-struct TokenBucketState {
-    tokens: f64,
-    last_refill: tokio::time::Instant,
-    rate: f64,
-}
-
 /// Per-category throttle state: an AIMD controller paired with a token bucket.
 struct OperationThrottle {
     controller: AimdController,
-    bucket: Mutex<TokenBucketState>,
-    burst_capacity: f64,
+    bucket: TokenBucket,
     max_retries: usize,
     min_backoff_ms: u64,
     max_backoff_ms: u64,
@@ -344,12 +338,7 @@ impl OperationThrottle {
         let controller = AimdController::new(aimd_config)?;
         Ok(Self {
             controller,
-            bucket: Mutex::new(TokenBucketState {
-                tokens: burst_capacity,
-                last_refill: tokio::time::Instant::now(),
-                rate: initial_rate,
-            }),
-            burst_capacity,
+            bucket: TokenBucket::new(initial_rate, burst_capacity),
             max_retries,
             min_backoff_ms,
             max_backoff_ms,
@@ -357,43 +346,12 @@ impl OperationThrottle {
     }
 
     /// Acquire a token from the bucket, sleeping if none are available.
-    ///
-    /// Each caller reserves a token immediately (allowing `tokens` to go
-    /// negative) so that concurrent waiters queue behind each other instead
-    /// of all waking at the same instant (thundering herd).
     async fn acquire_token(&self) {
-        let sleep_duration = {
-            let mut bucket = self.bucket.lock().await;
-            let now = tokio::time::Instant::now();
-            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
-            bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(self.burst_capacity);
-            bucket.last_refill = now;
-
-            // Reserve a token (may go negative to queue behind other waiters)
-            bucket.tokens -= 1.0;
-
-            if bucket.tokens >= 0.0 {
-                // Had a token available, no need to sleep
-                return;
-            }
-
-            // Sleep proportional to our position in the queue
-            std::time::Duration::from_secs_f64(-bucket.tokens / bucket.rate)
-        };
-
-        tokio::time::sleep(sleep_duration).await;
-    }
-
-    /// Update the bucket's fill rate from the controller.
-    async fn update_bucket_rate(&self, new_rate: f64) {
-        let mut bucket = self.bucket.lock().await;
-        bucket.rate = new_rate;
+        self.bucket.acquire(1.0).await;
     }
 
     /// Classify a result and feed it back to the AIMD controller without
-    /// acquiring a token. Uses `try_lock` for the bucket update so that if the
-    /// bucket lock is contended the rate update is deferred to the next
-    /// `throttled()` call.
+    /// acquiring a token.
     fn observe_outcome<T>(&self, result: &OSResult<T>) {
         let outcome = match result {
             Ok(_) => RequestOutcome::Success,
@@ -420,9 +378,7 @@ impl OperationThrottle {
                 "AIMD throttle: rate reduced due to throttle errors"
             );
         }
-        if let Ok(mut bucket) = self.bucket.try_lock() {
-            bucket.rate = new_rate;
-        }
+        self.bucket.set_rate(new_rate);
     }
 
     /// Execute an operation with throttling: acquire token, run, classify result.
@@ -461,7 +417,7 @@ impl OperationThrottle {
                     "AIMD throttle: rate reduced due to throttle errors"
                 );
             }
-            self.update_bucket_rate(new_rate).await;
+            self.bucket.set_rate(new_rate);
 
             match &result {
                 Err(err) if is_throttle_error(err) && attempt < self.max_retries => {
@@ -489,7 +445,7 @@ impl Debug for OperationThrottle {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("OperationThrottle")
             .field("controller", &self.controller)
-            .field("burst_capacity", &self.burst_capacity)
+            .field("bucket", &self.bucket)
             .finish()
     }
 }
@@ -2,6 +2,11 @@
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
 //! Wrappers around object_store that apply tracing
+//!
+//! Every request gets a `debug` span named after the operation, with the path, the
+//! number of bytes and the attempt number of the request.  The spans are children
+//! of the span that was current when the request was made, so they show up under
+//! the scan or commit that issued them when exported with `tracing-opentelemetry`.
 
 use std::ops::Range;
 use std::sync::Arc;
@@ -18,6 +23,22 @@ use object_store::{
 };
 use tracing::{Instrument, Span, instrument};
 
+tokio::task_local! {
+    static ATTEMPT: u32;
+}
+
+/// Run `fut`, recording `attempt` as the attempt number of the requests it makes.
+///
+/// Used by the retry loops that sit above the object store, requests made outside
+/// of a retry loop are recorded as the first attempt.
+pub async fn with_attempt<F: std::future::Future>(attempt: u32, fut: F) -> F::Output {
+    ATTEMPT.scope(attempt, fut).await
+}
+
+fn current_attempt() -> u32 {
+    ATTEMPT.try_with(|attempt| *attempt).unwrap_or(1)
+}
+
 #[derive(Debug)]
 pub struct TracedMultipartUpload {
     write_span: Span,
@@ -61,7 +82,7 @@ impl std::fmt::Display for TracedObjectStore {
 #[async_trait::async_trait]
 #[deny(clippy::missing_trait_methods)]
 impl object_store::ObjectStore for TracedObjectStore {
-    #[instrument(level = "debug", skip(self, bytes, location, opts), fields(path = location.as_ref(), size = bytes.content_length()))]
+    #[instrument(level = "debug", skip(self, bytes, location, opts), fields(path = location.as_ref(), size = bytes.content_length(), attempt = current_attempt()))]
     async fn put_opts(
         &self,
         location: &Path,
@@ -71,7 +92,7 @@ impl object_store::ObjectStore for TracedObjectStore {
         self.target.put_opts(location, bytes, opts).await
     }
 
-    #[instrument(level = "debug", skip(self, location, opts), fields(path = location.as_ref(), size = tracing::field::Empty))]
+    #[instrument(level = "debug", skip(self, location, opts), fields(path = location.as_ref(), size = tracing::field::Empty, attempt = current_attempt()))]
     async fn put_multipart_opts(
         &self,
         location: &Path,
@@ -85,7 +106,7 @@ impl object_store::ObjectStore for TracedObjectStore {
         }))
     }
 
-    #[instrument(level = "debug", skip(self, options, location), fields(path = location.as_ref(), size = tracing::field::Empty))]
+    #[instrument(level = "debug", skip(self, options, location), fields(path = location.as_ref(), size = tracing::field::Empty, attempt = current_attempt()))]
     async fn get_opts(&self, location: &Path, options: GetOptions) -> OSResult<GetResult> {
         let res = self.target.get_opts(location, options).await?;
 
@@ -95,7 +116,7 @@ impl object_store::ObjectStore for TracedObjectStore {
         Ok(res)
     }
 
-    #[instrument(level = "debug", skip(self, location), fields(path = location.as_ref(), size = ranges.iter().map(|r| r.end - r.start).sum::<u64>()))]
+    #[instrument(level = "debug", skip(self, location), fields(path = location.as_ref(), size = ranges.iter().map(|r| r.end - r.start).sum::<u64>(), attempt = current_attempt()))]
     async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> OSResult<Vec<Bytes>> {
         self.target.get_ranges(location, ranges).await
     }
@@ -190,6 +211,7 @@ mod tests {
                     expect::field("path")
                         .with_value(&"a/b.bin")
                         .and(expect::field("size").with_value(&data.len()))
+                        .and(expect::field("attempt").with_value(&1u32))
                         .only(),
                 ),
             )
@@ -217,9 +239,13 @@ mod tests {
         let span = expect::span().named("get_opts");
         let (sub, handle) = subscriber::mock()
             .new_span(
-                // size = Empty at span creation, so only path is visited.
-                span.clone()
-                    .with_fields(expect::field("path").with_value(&"a/b.bin").only()),
+                // size = Empty at span creation, so only path and attempt are visited.
+                span.clone().with_fields(
+                    expect::field("path")
+                        .with_value(&"a/b.bin")
+                        .and(expect::field("attempt").with_value(&1u32))
+                        .only(),
+                ),
             )
             .enter(span.clone())
             .record(span.clone(), expect::field("size").with_value(&size))
@@ -247,8 +273,12 @@ mod tests {
         let span = expect::span().named("get_opts");
         let (sub, handle) = subscriber::mock()
             .new_span(
-                span.clone()
-                    .with_fields(expect::field("path").with_value(&"a/b.bin").only()),
+                span.clone().with_fields(
+                    expect::field("path")
+                        .with_value(&"a/b.bin")
+                        .and(expect::field("attempt").with_value(&1u32))
+                        .only(),
+                ),
             )
             .enter(span.clone())
             .record(span.clone(), expect::field("size").with_value(&size))
@@ -307,8 +337,12 @@ mod tests {
         let span = expect::span().named("get_opts");
         let (sub, handle) = subscriber::mock()
             .new_span(
-                span.clone()
-                    .with_fields(expect::field("path").with_value(&"a/b.bin").only()),
+                span.clone().with_fields(
+                    expect::field("path")
+                        .with_value(&"a/b.bin")
+                        .and(expect::field("attempt").with_value(&1u32))
+                        .only(),
+                ),
             )
             .enter(span.clone())
             .record(span.clone(), expect::field("size").with_value(&size))
@@ -384,8 +418,13 @@ mod tests {
         // events are not in the queue so they are silently ignored.
         let (sub, handle) = subscriber::mock()
             .new_span(
-                // size = Empty at span creation, so only path is visited.
-                put_mp_span.with_fields(expect::field("path").with_value(&"a/b.bin").only()),
+                // size = Empty at span creation, so only path and attempt are visited.
+                put_mp_span.with_fields(
+                    expect::field("path")
+                        .with_value(&"a/b.bin")
+                        .and(expect::field("attempt").with_value(&1u32))
+                        .only(),
+                ),
             )
             .run_with_handle();
 
@@ -398,4 +437,29 @@ mod tests {
 
         handle.assert_finished();
     }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_retry_records_attempt() {
+        let path = Path::from("a/b.bin");
+        let store = make_store();
+        store.put(&path, payload(b"hello world")).await.unwrap();
+
+        let span = expect::span().named("get_opts");
+        let (sub, handle) = subscriber::mock()
+            .new_span(
+                span.clone().with_fields(
+                    expect::field("path")
+                        .with_value(&"a/b.bin")
+                        .and(expect::field("attempt").with_value(&3u32))
+                        .only(),
+                ),
+            )
+            .run_with_handle();
+
+        let _guard = tracing::subscriber::set_default(sub);
+        with_attempt(3, store.get(&path)).await.unwrap();
+        drop(_guard);
+
+        handle.assert_finished();
+    }
 }
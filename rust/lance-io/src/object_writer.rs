@@ -6,7 +6,7 @@ use std::pin::Pin;
 use std::sync::{Arc, OnceLock};
 use std::task::Poll;
 
-use crate::object_store::ObjectStore as LanceObjectStore;
+use crate::object_store::{ObjectStore as LanceObjectStore, StorageOptions};
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::FutureExt;
@@ -79,6 +79,116 @@ fn initial_upload_size() -> usize {
     })
 }
 
+pub const UPLOAD_PART_SIZE_KEY: &str = "upload_part_size";
+pub const UPLOAD_CONCURRENCY_KEY: &str = "upload_concurrency";
+pub const UPLOAD_MAX_PART_RETRIES_KEY: &str = "upload_max_part_retries";
+pub const WRITE_BUFFER_SIZE_KEY: &str = "write_buffer_size";
+
+/// Settings for multipart uploads of large objects.
+///
+/// The defaults come from the `LANCE_INITIAL_UPLOAD_SIZE`, `LANCE_UPLOAD_CONCURRENCY`
+/// and `LANCE_CONN_RESET_RETRIES` environment variables and can be overridden per
+/// object store with the `upload_part_size`, `upload_concurrency`,
+/// `upload_max_part_retries` and `write_buffer_size` storage options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UploadOptions {
+    /// Size of the parts of a multipart upload, between 5MB and 5GB.
+    ///
+    /// Unless the store requires constant size parts, the part size grows every 100
+    /// parts so that objects larger than 10,000 parts can still be uploaded.
+    pub part_size: usize,
+    /// Max number of parts uploaded concurrently by a single writer.
+    pub max_concurrency: usize,
+    /// Max number of times the parts of an upload are retried after a transient
+    /// error or a checksum mismatch.
+    pub max_part_retries: u16,
+    /// If set, writers copy the data into a buffer of at most this many bytes that
+    /// is flushed to the store in the background.  See [`BufferedWriter`].
+    ///
+    /// [`BufferedWriter`]: crate::buffered_writer::BufferedWriter
+    pub write_buffer_size: Option<usize>,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            part_size: initial_upload_size(),
+            max_concurrency: max_upload_parallelism(),
+            max_part_retries: max_conn_reset_retries(),
+            write_buffer_size: None,
+        }
+    }
+}
+
+impl UploadOptions {
+    /// Set the part size, clamped to the valid range of 5MB to 5GB.
+    pub fn with_part_size(self, part_size: usize) -> Self {
+        Self {
+            part_size: clamp_initial_upload_size(part_size).0,
+            ..self
+        }
+    }
+
+    pub fn with_max_concurrency(self, max_concurrency: usize) -> Self {
+        Self {
+            max_concurrency: max_concurrency.max(1),
+            ..self
+        }
+    }
+
+    pub fn with_max_part_retries(self, max_part_retries: u16) -> Self {
+        Self {
+            max_part_retries,
+            ..self
+        }
+    }
+
+    pub fn with_write_buffer_size(self, write_buffer_size: usize) -> Self {
+        Self {
+            write_buffer_size: Some(write_buffer_size.max(1)),
+            ..self
+        }
+    }
+
+    /// Build the upload options from storage options, options that are not set
+    /// keep their default.
+    pub fn from_storage_options(storage_options: &StorageOptions) -> Result<Self> {
+        let mut options = Self::default();
+        if let Some(part_size) = storage_options.parse::<usize>(UPLOAD_PART_SIZE_KEY)? {
+            let (clamped, was_clamped) = clamp_initial_upload_size(part_size);
+            if was_clamped {
+                return Err(Error::invalid_input(format!(
+                    "Invalid value for storage option '{UPLOAD_PART_SIZE_KEY}': '{part_size}', \
+                     must be between {INITIAL_UPLOAD_STEP} and {MAX_UPLOAD_PART_SIZE} bytes"
+                )));
+            }
+            options.part_size = clamped;
+        }
+        if let Some(max_concurrency) = storage_options.parse::<usize>(UPLOAD_CONCURRENCY_KEY)? {
+            options = options.with_max_concurrency(max_concurrency);
+        }
+        if let Some(max_part_retries) = storage_options.parse::<u16>(UPLOAD_MAX_PART_RETRIES_KEY)? {
+            options.max_part_retries = max_part_retries;
+        }
+        if let Some(write_buffer_size) = storage_options.parse::<usize>(WRITE_BUFFER_SIZE_KEY)? {
+            options = options.with_write_buffer_size(write_buffer_size);
+        }
+        Ok(options)
+    }
+
+    /// The capacity of the buffer for the part after `part_idx`.
+    fn part_size_for(&self, part_idx: u16, constant_upload_size: bool) -> usize {
+        if constant_upload_size {
+            // The store does not support variable part sizes, so use the initial size.
+            self.part_size
+        } else {
+            // Increase the upload size every 100 parts. This gives maximum part size of 2.5TB.
+            self.part_size
+                .max(((part_idx / 100) as usize + 1) * INITIAL_UPLOAD_STEP)
+        }
+    }
+}
+
 /// Writer to an object in an object store.
 ///
 /// If the object is small enough, the writer will upload the object in a single
@@ -94,6 +204,7 @@ pub struct ObjectWriter {
     buffer: Vec<u8>,
     // TODO: use constant size to support R2
     use_constant_size_upload_parts: bool,
+    upload_options: UploadOptions,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -170,26 +281,27 @@ impl UploadState {
 
 impl ObjectWriter {
     pub async fn new(object_store: &LanceObjectStore, path: &Path) -> Result<Self> {
+        let upload_options = *object_store.upload_options();
         Ok(Self {
             state: UploadState::Started(object_store.inner.clone()),
             cursor: 0,
             path: Arc::new(path.clone()),
             connection_resets: 0,
-            buffer: Vec::with_capacity(initial_upload_size()),
+            buffer: Vec::with_capacity(upload_options.part_size),
             use_constant_size_upload_parts: object_store.use_constant_size_upload_parts,
+            upload_options,
         })
     }
 
     /// Returns the contents of `buffer` as a `Bytes` object and resets `buffer`.
     /// The new capacity of `buffer` is determined by the current part index.
-    fn next_part_buffer(buffer: &mut Vec<u8>, part_idx: u16, constant_upload_size: bool) -> Bytes {
-        let new_capacity = if constant_upload_size {
-            // The store does not support variable part sizes, so use the initial size.
-            initial_upload_size()
-        } else {
-            // Increase the upload size every 100 parts. This gives maximum part size of 2.5TB.
-            initial_upload_size().max(((part_idx / 100) as usize + 1) * INITIAL_UPLOAD_STEP)
-        };
+    fn next_part_buffer(
+        buffer: &mut Vec<u8>,
+        part_idx: u16,
+        constant_upload_size: bool,
+        upload_options: &UploadOptions,
+    ) -> Bytes {
+        let new_capacity = upload_options.part_size_for(part_idx, constant_upload_size);
         let new_buffer = Vec::with_capacity(new_capacity);
         let part = std::mem::replace(buffer, new_buffer);
         Bytes::from(part)
@@ -235,6 +347,7 @@ impl ObjectWriter {
                             &mut mut_self.buffer,
                             0,
                             mut_self.use_constant_size_upload_parts,
+                            &mut_self.upload_options,
                         );
                         futures.spawn(Self::put_part(upload.as_mut(), data, 0, None));
 
@@ -255,8 +368,9 @@ impl ObjectWriter {
                             Ok(Ok(())) => {}
                             Err(err) => return Err(std::io::Error::other(err)),
                             Ok(Err(err)) if should_retry_upload_put(&err.source) => {
-                                if mut_self.connection_resets < max_conn_reset_retries() {
-                                    // Retry, but only up to max_conn_reset_retries of them.
+                                let max_retries = mut_self.upload_options.max_part_retries;
+                                if mut_self.connection_resets < max_retries {
+                                    // Retry, but only up to max_part_retries of them.
                                     mut_self.connection_resets += 1;
 
                                     // Resubmit with random jitter
@@ -276,7 +390,7 @@ impl ObjectWriter {
                                         Box::new(ConnectionResetError {
                                             message: format!(
                                                 "Hit max retries ({}) for retryable upload error",
-                                                max_conn_reset_retries()
+                                                max_retries
                                             ),
                                             source: Box::new(err.source),
                                         }),
@@ -344,7 +458,13 @@ fn should_retry_upload_put(source: &OSError) -> bool {
     };
 
     let message = source.to_string().to_ascii_lowercase();
-    message.contains("connection reset by peer") || message.contains("requesttimeout")
+    message.contains("connection reset by peer")
+        || message.contains("requesttimeout")
+        // The part was corrupted in transit and rejected by the store's checksum
+        // verification, e.g. S3 with `aws_checksum_algorithm` set.
+        || message.contains("baddigest")
+        || message.contains("invaliddigest")
+        || message.contains("xamzcontentsha256mismatch")
 }
 
 #[derive(Debug)]
@@ -394,12 +514,12 @@ impl AsyncWrite for ObjectWriter {
                     futures,
                     ..
                 } => {
-                    // TODO: Make max concurrency configurable from storage options.
-                    if futures.len() < max_upload_parallelism() {
+                    if futures.len() < mut_self.upload_options.max_concurrency {
                         let data = Self::next_part_buffer(
                             &mut mut_self.buffer,
                             *part_idx,
                             mut_self.use_constant_size_upload_parts,
+                            &mut_self.upload_options,
                         );
                         futures.spawn(
                             Self::put_part(upload.as_mut(), data, *part_idx, None)
@@ -468,7 +588,9 @@ impl AsyncWrite for ObjectWriter {
                     part_idx,
                 } => {
                     // Flush final batch
-                    if !mut_self.buffer.is_empty() && futures.len() < max_upload_parallelism() {
+                    if !mut_self.buffer.is_empty()
+                        && futures.len() < mut_self.upload_options.max_concurrency
+                    {
                         // We can just use `take` since we don't need the buffer anymore.
                         let data = Bytes::from(std::mem::take(&mut mut_self.buffer));
                         futures.spawn(
@@ -726,9 +848,12 @@ fn get_inode(_metadata: &std::fs::Metadata) -> u64 {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use tokio::io::AsyncWriteExt;
 
     use super::*;
+    use crate::object_store::{ObjectStoreParams, ObjectStoreRegistry, StorageOptionsAccessor};
 
     #[tokio::test]
     async fn test_write() {
@@ -768,6 +893,83 @@ mod tests {
         assert_eq!(res.size, buf.len() * 5);
     }
 
+    #[tokio::test]
+    async fn test_upload_options() {
+        let options = StorageOptions::new(HashMap::from([
+            (
+                UPLOAD_PART_SIZE_KEY.to_string(),
+                (8 * 1024 * 1024).to_string(),
+            ),
+            (UPLOAD_CONCURRENCY_KEY.to_string(), "1".to_string()),
+            (UPLOAD_MAX_PART_RETRIES_KEY.to_string(), "2".to_string()),
+            (WRITE_BUFFER_SIZE_KEY.to_string(), "1048576".to_string()),
+        ]));
+        let upload_options = UploadOptions::from_storage_options(&options).unwrap();
+        assert_eq!(
+            upload_options,
+            UploadOptions::default()
+                .with_part_size(8 * 1024 * 1024)
+                .with_max_concurrency(1)
+                .with_max_part_retries(2)
+                .with_write_buffer_size(1024 * 1024)
+        );
+        assert_eq!(upload_options.part_size_for(0, false), 8 * 1024 * 1024);
+        assert_eq!(upload_options.part_size_for(300, false), 20 * 1024 * 1024);
+        assert_eq!(upload_options.part_size_for(300, true), 8 * 1024 * 1024);
+
+        for (key, value) in [
+            (UPLOAD_PART_SIZE_KEY, "1024"),
+            (UPLOAD_CONCURRENCY_KEY, "many"),
+        ] {
+            let options =
+                StorageOptions::new(HashMap::from([(key.to_string(), value.to_string())]));
+            let err = UploadOptions::from_storage_options(&options).unwrap_err();
+            assert!(err.to_string().contains(key), "{err}");
+        }
+
+        // The options are picked up by stores created through the registry
+        let params = ObjectStoreParams {
+            storage_options_accessor: Some(Arc::new(StorageOptionsAccessor::with_static_options(
+                options.0,
+            ))),
+            ..Default::default()
+        };
+        let (store, _) = LanceObjectStore::from_uri_and_params(
+            Arc::new(ObjectStoreRegistry::default()),
+            "memory:///",
+            &params,
+        )
+        .await
+        .unwrap();
+        assert_eq!(*store.upload_options(), upload_options);
+
+        let mut object_writer = ObjectWriter::new(&store, &Path::from("/foo"))
+            .await
+            .unwrap();
+        let buf = vec![1; INITIAL_UPLOAD_STEP];
+        for _ in 0..3 {
+            object_writer.write_all(buf.as_slice()).await.unwrap();
+        }
+        let res = Writer::shutdown(&mut object_writer).await.unwrap();
+        assert_eq!(res.size, buf.len() * 3);
+        assert_eq!(
+            store.size(&Path::from("/foo")).await.unwrap(),
+            buf.len() as u64 * 3
+        );
+
+        // Writers created by the store flush through a write buffer
+        let mut writer = store.create(&Path::from("/bar")).await.unwrap();
+        for _ in 0..3 {
+            writer.write_all(buf.as_slice()).await.unwrap();
+        }
+        let res = Writer::shutdown(&mut writer).await.unwrap();
+        assert_eq!(res.size, buf.len() * 3);
+        assert_eq!(
+            store.size(&Path::from("/bar")).await.unwrap(),
+            buf.len() as u64 * 3
+        );
+    }
+
     #[tokio::test]
     async fn test_abort_write() {
         let store = LanceObjectStore::memory();
@@ -880,6 +1082,16 @@ mod tests {
         };
         assert!(should_retry_upload_put(&connection_reset));
 
+        let bad_digest = OSError::Generic {
+            store: "S3",
+            source: Box::new(io::Error::other(
+                "Server returned non-2xx status code: 400 Bad Request: \
+                 <Error><Code>BadDigest</Code><Message>The SHA256 you specified did not match \
+                 the calculated checksum.</Message></Error>",
+            )),
+        };
+        assert!(should_retry_upload_put(&bad_digest));
+
         let not_retryable = OSError::Generic {
             store: "S3",
             source: Box::new(io::Error::other("access denied")),
@@ -14,22 +14,31 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::sync::Notify;
+use tracing::{Instrument, Span};
 
 use lance_core::utils::io_stats::IoStatsRecorder;
 use lance_core::utils::parse::str_is_truthy;
 use lance_core::{Error, Result};
 
-use crate::object_store::ObjectStore;
+use crate::object_store::{ObjectStore, StorageOptions};
 use crate::traits::Reader;
 use crate::utils::CachedFileSize;
 
+mod budget;
 mod lite;
+mod prefetch;
+
+pub use budget::{IoBudget, IoBudgetConfig, IoBudgetPermit};
+use prefetch::PrefetchBuffer;
 
 // Don't log backpressure warnings until at least this many seconds have passed
 const BACKPRESSURE_MIN: u64 = 5;
 // Don't log backpressure warnings more than once / minute
 const BACKPRESSURE_DEBOUNCE: u64 = 60;
 
+pub const READ_COALESCE_MAX_GAP_KEY: &str = "read_coalesce_max_gap";
+pub const READ_MAX_REQUEST_SIZE_KEY: &str = "read_max_request_size";
+
 // Global counter of how many IOPS we have issued
 static IOPS_COUNTER: AtomicU64 = AtomicU64::new(0);
 // Global counter of how many bytes were read by the scheduler
@@ -348,19 +357,82 @@ impl<F: FnOnce(Response) + Send> DataSink for MutableBatch<F> {
     }
 }
 
+/// The class of an I/O request
+///
+/// Queued requests of a more urgent class are always issued before requests of a
+/// less urgent class, regardless of their numeric priority.  This allows interactive
+/// queries to jump ahead of background work (e.g. compaction or statistics scans)
+/// that shares the same scheduler.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IoPriorityClass {
+    /// Latency sensitive requests, e.g. user-facing queries
+    Interactive,
+    /// The default class
+    #[default]
+    Normal,
+    /// Throughput oriented requests that can wait, e.g. compaction
+    Background,
+}
+
+/// Controls the order in which queued requests are issued, ahead of the numeric priority
+///
+/// Requests are ordered by class first.  Within a class, requests with a deadline are
+/// issued before requests without one, earliest deadline first.  The deadline is only a
+/// scheduling hint, requests that miss their deadline are still run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct IoUrgency {
+    pub class: IoPriorityClass,
+    pub deadline: Option<Instant>,
+}
+
+impl IoUrgency {
+    pub fn new(class: IoPriorityClass) -> Self {
+        Self {
+            class,
+            deadline: None,
+        }
+    }
+
+    pub fn with_deadline(self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Compare two urgencies, `Greater` means `self` should be issued first
+    fn cmp_urgency(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .class
+            .cmp(&self.class)
+            .then_with(|| match (self.deadline, other.deadline) {
+                (Some(ours), Some(theirs)) => theirs.cmp(&ours),
+                (Some(_), None) => std::cmp::Ordering::Greater,
+                (None, Some(_)) => std::cmp::Ordering::Less,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+    }
+}
+
 struct IoTask {
     reader: Arc<dyn Reader>,
     to_read: Range<u64>,
     when_done: Box<dyn FnOnce(Result<Bytes>) + Send>,
     priority: u128,
+    urgency: IoUrgency,
     bypass_backpressure: bool,
+    budget: Option<Arc<IoBudget>>,
+    /// The span of the request, the read is traced under it
+    span: Span,
 }
 
 impl Eq for IoTask {}
 
 impl PartialEq for IoTask {
     fn eq(&self, other: &Self) -> bool {
-        self.bypass_backpressure == other.bypass_backpressure && self.priority == other.priority
+        self.bypass_backpressure == other.bypass_backpressure
+            && self.urgency == other.urgency
+            && self.priority == other.priority
     }
 }
 
@@ -373,9 +445,11 @@ impl PartialOrd for IoTask {
 impl Ord for IoTask {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         // Bypass tasks are always delivered before normal tasks.
-        // Within the same bypass class, this is a min-heap on priority.
+        // Within the same bypass class, more urgent tasks come first and
+        // then this is a min-heap on priority.
         self.bypass_backpressure
             .cmp(&other.bypass_backpressure)
+            .then_with(|| self.urgency.cmp_urgency(&other.urgency))
             .then(other.priority.cmp(&self.priority))
     }
 }
@@ -396,6 +470,10 @@ impl IoTask {
         let bytes = if self.to_read.start == self.to_read.end {
             Ok(Bytes::new())
         } else {
+            let _permit = match &self.budget {
+                Some(budget) => Some(budget.acquire(num_bytes).await),
+                None => None,
+            };
             let bytes_fut = self
                 .reader
                 .get_range(self.to_read.start as usize..self.to_read.end as usize);
@@ -430,7 +508,8 @@ async fn run_io_loop(tasks: Arc<IoQueue>) {
         let next_task = tasks.pop().await;
         match next_task {
             Some(task) => {
-                tokio::spawn(task.run());
+                let span = task.span.clone();
+                tokio::spawn(task.run().instrument(span));
             }
             None => {
                 // The sender has been dropped, we are done
@@ -579,6 +658,9 @@ pub struct ScanScheduler {
     object_store: Arc<ObjectStore>,
     io_queue: IoQueueType,
     stats: IoStats,
+    budget: Option<Arc<IoBudget>>,
+    coalesce_options: CoalesceOptions,
+    prefetch: PrefetchBuffer,
 }
 
 impl Debug for ScanScheduler {
@@ -596,6 +678,68 @@ struct Response {
     num_bytes: u64,
 }
 
+/// Thresholds used to turn the ranges of a request into I/O operations.
+///
+/// Ranges that are close together are merged into a single read, and reads that are
+/// too large are split into several reads that are issued in parallel.  The best
+/// values depend on the storage: a local NVMe drive favors small reads while a cloud
+/// object store favors fewer, larger ones.
+///
+/// Unset thresholds fall back to the block size and max IOP size of the object store.
+/// They can be set per object store with the `read_coalesce_max_gap` and
+/// `read_max_request_size` storage options, or per scheduler in [`SchedulerConfig`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CoalesceOptions {
+    /// Ranges separated by at most this many bytes are merged into a single read.
+    pub max_gap: Option<u64>,
+    /// Merged reads larger than this many bytes are split into smaller reads.
+    pub max_request_size: Option<u64>,
+}
+
+impl CoalesceOptions {
+    pub fn with_max_gap(self, max_gap: u64) -> Self {
+        Self {
+            max_gap: Some(max_gap),
+            ..self
+        }
+    }
+
+    /// Set the max size of a single read, must be greater than zero.
+    pub fn with_max_request_size(self, max_request_size: u64) -> Self {
+        Self {
+            max_request_size: Some(max_request_size.max(1)),
+            ..self
+        }
+    }
+
+    /// Build the coalescing thresholds from storage options, options that are not
+    /// set are left unset.
+    pub fn from_storage_options(storage_options: &StorageOptions) -> Result<Self> {
+        let mut options = Self::default();
+        if let Some(max_gap) = storage_options.parse::<u64>(READ_COALESCE_MAX_GAP_KEY)? {
+            options.max_gap = Some(max_gap);
+        }
+        if let Some(max_request_size) = storage_options.parse::<u64>(READ_MAX_REQUEST_SIZE_KEY)? {
+            if max_request_size == 0 {
+                return Err(Error::invalid_input(format!(
+                    "Invalid value for storage option '{READ_MAX_REQUEST_SIZE_KEY}': '0', \
+                     must be greater than zero"
+                )));
+            }
+            options.max_request_size = Some(max_request_size);
+        }
+        Ok(options)
+    }
+
+    /// Fill the thresholds that are not set from `other`.
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            max_gap: self.max_gap.or(other.max_gap),
+            max_request_size: self.max_request_size.or(other.max_request_size),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SchedulerConfig {
     /// the # of bytes that can be buffered but not yet requested.
@@ -608,6 +752,12 @@ pub struct SchedulerConfig {
     /// - `Some(false)` forces the standard scheduler.
     /// - `None` defers to the object store's preference (see [`ObjectStore::prefers_lite_scheduler`]).
     pub use_lite_scheduler: Option<bool>,
+    /// Overrides the coalescing thresholds of the object store for the files
+    /// opened by this scheduler.
+    pub coalesce_options: CoalesceOptions,
+    /// The max # of bytes that can be held by data prefetched with
+    /// [`FileScheduler::prefetch`].  Prefetching is disabled when this is 0.
+    pub prefetch_buffer_size_bytes: u64,
 }
 
 impl SchedulerConfig {
@@ -617,6 +767,8 @@ impl SchedulerConfig {
             use_lite_scheduler: std::env::var("LANCE_USE_LITE_SCHEDULER")
                 .ok()
                 .map(|v| str_is_truthy(v.trim())),
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: io_buffer_size_bytes,
         }
     }
 
@@ -625,6 +777,8 @@ impl SchedulerConfig {
         Self {
             io_buffer_size_bytes: 256 * 1024 * 1024,
             use_lite_scheduler: None,
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 256 * 1024 * 1024,
         }
     }
 
@@ -640,6 +794,20 @@ impl SchedulerConfig {
             ..self
         }
     }
+
+    pub fn with_coalesce_options(self, coalesce_options: CoalesceOptions) -> Self {
+        Self {
+            coalesce_options,
+            ..self
+        }
+    }
+
+    pub fn with_prefetch_buffer_size(self, prefetch_buffer_size_bytes: u64) -> Self {
+        Self {
+            prefetch_buffer_size_bytes,
+            ..self
+        }
+    }
 }
 
 impl ScanScheduler {
@@ -672,10 +840,15 @@ impl ScanScheduler {
             tokio::task::spawn(async move { run_io_loop(io_queue_clone).await });
             IoQueueType::Standard(io_queue)
         };
+        let budget = object_store.io_budget().cloned();
+        let coalesce_options = config.coalesce_options.or(*object_store.coalesce_options());
         Arc::new(Self {
             object_store,
             io_queue,
             stats: IoStats::new(),
+            budget,
+            coalesce_options,
+            prefetch: PrefetchBuffer::new(config.prefetch_buffer_size_bytes),
         })
     }
 
@@ -706,8 +879,14 @@ impl ScanScheduler {
             .object_store
             .open_with_size(path, file_size_bytes as usize)
             .await?;
-        let block_size = self.object_store.block_size() as u64;
-        let max_iop_size = self.object_store.max_iop_size();
+        let block_size = self
+            .coalesce_options
+            .max_gap
+            .unwrap_or(self.object_store.block_size() as u64);
+        let max_iop_size = self
+            .coalesce_options
+            .max_request_size
+            .unwrap_or_else(|| self.object_store.max_iop_size());
         Ok(FileScheduler {
             reader: reader.into(),
             block_size,
@@ -715,6 +894,7 @@ impl ScanScheduler {
             base_priority,
             max_iop_size,
             bypass_backpressure: false,
+            urgency: IoUrgency::default(),
             extra_stats: None,
         })
     }
@@ -730,12 +910,14 @@ impl ScanScheduler {
         self.open_file_with_priority(path, 0, file_size_bytes).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn do_submit_request(
         &self,
         reader: Arc<dyn Reader>,
         request: Vec<Range<u64>>,
         tx: oneshot::Sender<Response>,
         priority: u128,
+        urgency: IoUrgency,
         io_queue: &Arc<IoQueue>,
         bypass_backpressure: bool,
     ) {
@@ -762,7 +944,10 @@ impl ScanScheduler {
                 reader: reader.clone(),
                 to_read: iop,
                 priority,
+                urgency,
                 bypass_backpressure,
+                budget: self.budget.clone(),
+                span: Span::current(),
                 when_done: Box::new(move |data| {
                     io_queue_clone.on_iop_complete();
                     let mut dest = dest.lock().unwrap();
@@ -783,12 +968,21 @@ impl ScanScheduler {
         reader: Arc<dyn Reader>,
         request: Vec<Range<u64>>,
         priority: u128,
+        urgency: IoUrgency,
         io_queue: &Arc<IoQueue>,
         bypass_backpressure: bool,
     ) -> impl Future<Output = Result<Vec<Bytes>>> + Send + use<> {
         let (tx, rx) = oneshot::channel::<Response>();
 
-        self.do_submit_request(reader, request, tx, priority, io_queue, bypass_backpressure);
+        self.do_submit_request(
+            reader,
+            request,
+            tx,
+            priority,
+            urgency,
+            io_queue,
+            bypass_backpressure,
+        );
 
         let io_queue_clone = io_queue.clone();
 
@@ -806,6 +1000,7 @@ impl ScanScheduler {
         reader: Arc<dyn Reader>,
         request: Vec<Range<u64>>,
         priority: u128,
+        urgency: IoUrgency,
         io_queue: &Arc<lite::IoQueue>,
         bypass_backpressure: bool,
     ) -> impl Future<Output = Result<Vec<Bytes>>> + Send + use<> {
@@ -815,13 +1010,22 @@ impl ScanScheduler {
             .map(|task| {
                 let reader = reader.clone();
                 let queue = io_queue.clone();
-                let run_fn = Box::new(move || {
-                    reader
+                let budget = self.budget.clone();
+                let run_fn = Box::new(move || match budget {
+                    Some(budget) => async move {
+                        let _permit = budget.acquire(task.end - task.start).await;
+                        reader
+                            .get_range(task.start as usize..task.end as usize)
+                            .await
+                            .map_err(Error::from)
+                    }
+                    .boxed(),
+                    None => reader
                         .get_range(task.start as usize..task.end as usize)
                         .map_err(Error::from)
-                        .boxed()
+                        .boxed(),
                 });
-                queue.submit(task, priority, run_fn, bypass_backpressure)
+                queue.submit(task, priority, urgency, run_fn, bypass_backpressure)
             })
             .collect::<Result<Vec<_>>>();
         match maybe_tasks {
@@ -843,6 +1047,27 @@ impl ScanScheduler {
         request: Vec<Range<u64>>,
         priority: u128,
         bypass_backpressure: bool,
+    ) -> impl Future<Output = Result<Vec<Bytes>>> + Send + use<> {
+        self.submit_request_with_urgency(
+            reader,
+            request,
+            priority,
+            IoUrgency::default(),
+            bypass_backpressure,
+        )
+    }
+
+    /// Submit a request with a priority class and optional deadline
+    ///
+    /// Queued requests are ordered by `urgency` before `priority`.  See [`IoUrgency`]
+    /// for details.
+    pub fn submit_request_with_urgency(
+        &self,
+        reader: Arc<dyn Reader>,
+        request: Vec<Range<u64>>,
+        priority: u128,
+        urgency: IoUrgency,
+        bypass_backpressure: bool,
     ) -> impl Future<Output = Result<Vec<Bytes>>> + Send + use<> {
         match &self.io_queue {
             IoQueueType::Standard(io_queue) => {
@@ -850,13 +1075,21 @@ impl ScanScheduler {
                     reader,
                     request,
                     priority,
+                    urgency,
+                    io_queue,
+                    bypass_backpressure,
+                ))
+            }
+            IoQueueType::Lite(io_queue) => {
+                futures::future::Either::Right(self.submit_request_lite(
+                    reader,
+                    request,
+                    priority,
+                    urgency,
                     io_queue,
                     bypass_backpressure,
                 ))
             }
-            IoQueueType::Lite(io_queue) => futures::future::Either::Right(
-                self.submit_request_lite(reader, request, priority, io_queue, bypass_backpressure),
-            ),
         }
     }
 
@@ -899,6 +1132,7 @@ pub struct FileScheduler {
     base_priority: u64,
     max_iop_size: u64,
     bypass_backpressure: bool,
+    urgency: IoUrgency,
     /// Optional secondary statistics sink.  When set, every request submitted
     /// through this handle is also recorded here, in addition to the
     /// scheduler's global totals.  Used to measure per-scope I/O.
@@ -927,10 +1161,112 @@ impl FileScheduler {
     /// Each request has a backpressure ID which controls which backpressure throttle
     /// is applied to the request.  Requests made to the same backpressure throttle
     /// will be throttled together.
+    ///
+    /// Ranges that lie within a range passed to [`Self::prefetch`] are served from
+    /// the prefetched data.
     pub fn submit_request(
         &self,
         request: Vec<Range<u64>>,
         priority: u64,
+    ) -> impl Future<Output = Result<Vec<Bytes>>> + Send + use<> {
+        let prefetch = &self.root.prefetch;
+        let prefetched = if prefetch.is_enabled() {
+            let path = self.reader.path();
+            request
+                .iter()
+                .map(|range| prefetch.get(path, range))
+                .collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+        if prefetched.iter().all(Option::is_none) {
+            return self.submit_uncached(request, priority).left_future();
+        }
+
+        let misses = request
+            .iter()
+            .zip(&prefetched)
+            .filter(|(_, hit)| hit.is_none())
+            .map(|(range, _)| range.clone())
+            .collect::<Vec<_>>();
+        let misses_fut = if misses.is_empty() {
+            std::future::ready(Ok(Vec::new())).boxed()
+        } else {
+            self.submit_uncached(misses, priority).boxed()
+        };
+        let this = self.clone();
+        async move {
+            let mut misses = misses_fut.await?.into_iter();
+            let mut bytes = Vec::with_capacity(request.len());
+            for (range, hit) in request.into_iter().zip(prefetched) {
+                match hit {
+                    Some(hit) => match hit.await {
+                        Some(data) => bytes.push(data),
+                        // The background read failed, read the range again to surface the error
+                        None => bytes.extend(this.submit_uncached(vec![range], priority).await?),
+                    },
+                    None => bytes.push(misses.next().unwrap()),
+                }
+            }
+            Ok(bytes)
+        }
+        .right_future()
+    }
+
+    /// Start reading `ranges` in the background so that later requests for them
+    /// (or for parts of them) don't have to wait on the object store.
+    ///
+    /// This is a hint, intended for scans that know which data they will read next.
+    /// The reads are issued with [`IoPriorityClass::Background`] and the data is held
+    /// until it has been requested through [`Self::submit_request`], up to the
+    /// prefetch buffer size of the [`SchedulerConfig`].  Ranges that don't fit in the
+    /// buffer are not prefetched.  Ranges should be passed in the order they will be
+    /// read.
+    ///
+    /// When the object store has a disk cache, the prefetched data is also written to it.
+    pub fn prefetch(&self, ranges: Vec<Range<u64>>) {
+        if !self.root.prefetch.is_enabled() {
+            return;
+        }
+        let background = self.with_priority_class(IoPriorityClass::Background);
+        let path = self.reader.path();
+
+        let mut merged = Vec::<Range<u64>>::with_capacity(ranges.len());
+        for range in ranges.into_iter().filter(|r| !r.is_empty()) {
+            match merged.last_mut() {
+                Some(last)
+                    if range.start >= last.start
+                        && is_close_together(last, &range, self.block_size) =>
+                {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
+            }
+        }
+
+        for range in merged {
+            let fits = self.root.prefetch.insert_with(path, range.clone(), || {
+                // Prefetches of the same file are issued in file order
+                let data = background
+                    .submit_uncached(vec![range.clone()], range.start)
+                    .map(|bytes| bytes.ok().and_then(|mut bytes| bytes.pop()))
+                    .boxed()
+                    .shared();
+                // Drive the read even if nothing requests the data, so that its
+                // bytes are released from the I/O buffer once it completes
+                tokio::spawn(data.clone());
+                data
+            });
+            if !fits {
+                break;
+            }
+        }
+    }
+
+    fn submit_uncached(
+        &self,
+        request: Vec<Range<u64>>,
+        priority: u64,
     ) -> impl Future<Output = Result<Vec<Bytes>>> + Send + use<> {
         // The final priority is a combination of the row offset and the file number
         let priority = ((self.base_priority as u128) << 64) + priority as u128;
@@ -977,10 +1313,11 @@ impl FileScheduler {
             extra_stats.record_request(&updated_requests);
         }
 
-        let bytes_vec_fut = self.root.submit_request(
+        let bytes_vec_fut = self.root.submit_request_with_urgency(
             self.reader.clone(),
             updated_requests.clone(),
             priority,
+            self.urgency,
             self.bypass_backpressure,
         );
 
@@ -1041,10 +1378,35 @@ impl FileScheduler {
             max_iop_size: self.max_iop_size,
             base_priority: priority,
             bypass_backpressure: self.bypass_backpressure,
+            urgency: self.urgency,
             extra_stats: self.extra_stats.clone(),
         }
     }
 
+    /// Returns a copy of this scheduler that submits all requests with the given class.
+    ///
+    /// Queued requests of a more urgent class are issued before any request of a less
+    /// urgent class that shares the same [`ScanScheduler`].
+    pub fn with_priority_class(&self, class: IoPriorityClass) -> Self {
+        Self {
+            urgency: IoUrgency {
+                class,
+                ..self.urgency
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of this scheduler that submits all requests with the given deadline.
+    ///
+    /// Within a priority class, requests with an earlier deadline are issued first.
+    pub fn with_deadline(&self, deadline: Instant) -> Self {
+        Self {
+            urgency: self.urgency.with_deadline(deadline),
+            ..self.clone()
+        }
+    }
+
     /// Returns a copy of this scheduler that additionally records the I/O it
     /// performs into `stats`, on top of the scheduler's global statistics.
     ///
@@ -1099,7 +1461,10 @@ impl FileScheduler {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::VecDeque, time::Duration};
+    use std::{
+        collections::{HashMap, VecDeque},
+        time::Duration,
+    };
 
     use futures::poll;
     use lance_core::utils::tempfile::TempObjFile;
@@ -1110,7 +1475,10 @@ mod tests {
     use url::Url;
 
     use crate::{
-        object_store::{DEFAULT_DOWNLOAD_RETRY_COUNT, DEFAULT_MAX_IOP_SIZE},
+        object_store::{
+            DEFAULT_DOWNLOAD_RETRY_COUNT, DEFAULT_MAX_IOP_SIZE, ObjectStoreParams,
+            ObjectStoreRegistry,
+        },
         testing::MockObjectStore,
     };
 
@@ -1125,7 +1493,10 @@ mod tests {
             to_read: 0..1,
             when_done: Box::new(|_| {}),
             priority,
+            urgency: IoUrgency::default(),
             bypass_backpressure,
+            budget: None,
+            span: Span::none(),
         }
     }
 
@@ -1146,6 +1517,40 @@ mod tests {
         assert_eq!(order, vec![(5, true), (20, true), (1, false), (10, false)]);
     }
 
+    #[test]
+    fn test_iotask_urgency_ordering() {
+        // Bypass still wins, then the class, then the deadline, then the priority.
+        let now = Instant::now();
+        let later = now + Duration::from_secs(5);
+        let with_urgency = |priority, bypass, class, deadline: Option<Instant>| IoTask {
+            urgency: IoUrgency { class, deadline },
+            ..make_task(priority, bypass)
+        };
+        let mut heap = BinaryHeap::new();
+        heap.push(with_urgency(1, false, IoPriorityClass::Background, None));
+        heap.push(with_urgency(2, false, IoPriorityClass::Normal, None));
+        heap.push(with_urgency(3, false, IoPriorityClass::Interactive, None));
+        heap.push(with_urgency(
+            4,
+            false,
+            IoPriorityClass::Interactive,
+            Some(later),
+        ));
+        heap.push(with_urgency(
+            5,
+            false,
+            IoPriorityClass::Interactive,
+            Some(now),
+        ));
+        heap.push(with_urgency(6, true, IoPriorityClass::Background, None));
+
+        let order: Vec<u128> = std::iter::from_fn(|| heap.pop())
+            .map(|t| t.priority)
+            .collect();
+
+        assert_eq!(order, vec![6, 5, 4, 3, 2, 1]);
+    }
+
     #[tokio::test]
     async fn test_full_seq_read() {
         let tmp_file = TempObjFile::default();
@@ -1277,6 +1682,96 @@ mod tests {
         assert_eq!(11, scheduler.stats().iops);
     }
 
+    #[tokio::test]
+    async fn test_coalesce_options() {
+        let tmp_file = TempObjFile::default();
+
+        let obj_store = Arc::new(ObjectStore::local());
+        let some_data = vec![7; 4 * 1024 * 1024];
+        obj_store.put(&tmp_file, &some_data).await.unwrap();
+
+        let options = StorageOptions::new(HashMap::from([
+            (READ_COALESCE_MAX_GAP_KEY.to_string(), "0".to_string()),
+            (READ_MAX_REQUEST_SIZE_KEY.to_string(), "1048576".to_string()),
+        ]));
+        let coalesce_options = CoalesceOptions::from_storage_options(&options).unwrap();
+        assert_eq!(
+            coalesce_options,
+            CoalesceOptions::default()
+                .with_max_gap(0)
+                .with_max_request_size(1024 * 1024)
+        );
+
+        let config = SchedulerConfig::default_for_testing().with_coalesce_options(coalesce_options);
+        let scheduler = ScanScheduler::new(obj_store, config);
+        let file_scheduler = scheduler
+            .open_file(&tmp_file, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+
+        // Ranges with a gap between them are no longer coalesced
+        let req =
+            file_scheduler.submit_request(vec![50_000..51_000, 52_000..53_000, 54_000..55_000], 0);
+        req.await.unwrap();
+        assert_eq!(3, scheduler.stats().iops);
+
+        // Adjacent ranges are still merged, and then split by the max request size
+        let req =
+            file_scheduler.submit_request(vec![0..1024 * 1024, 1024 * 1024..4 * 1024 * 1024], 0);
+        let bytes = req.await.unwrap();
+        assert_eq!(bytes[1].len(), 3 * 1024 * 1024);
+        assert_eq!(7, scheduler.stats().iops);
+
+        let options = StorageOptions::new(HashMap::from([(
+            READ_MAX_REQUEST_SIZE_KEY.to_string(),
+            "0".to_string(),
+        )]));
+        assert!(CoalesceOptions::from_storage_options(&options).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch() {
+        let tmp_file = TempObjFile::default();
+
+        let obj_store = Arc::new(ObjectStore::local());
+        let some_data = (0..200_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        obj_store.put(&tmp_file, &some_data).await.unwrap();
+
+        let config = SchedulerConfig::default_for_testing().with_prefetch_buffer_size(2500);
+        let scheduler = ScanScheduler::new(obj_store, config);
+        let file_scheduler = scheduler
+            .open_file(&tmp_file, &CachedFileSize::unknown())
+            .await
+            .unwrap();
+
+        // Adjacent ranges are prefetched with a single read, the last range does not
+        // fit in the buffer
+        file_scheduler.prefetch(vec![0..1000, 1000..2000, 100_000..101_000]);
+        assert_eq!(1, scheduler.stats().iops);
+        assert_eq!(2000, scheduler.prefetch.num_bytes());
+
+        let bytes = file_scheduler
+            .submit_request(vec![10..20, 500..1500, 100_000..100_010], 0)
+            .await
+            .unwrap();
+        assert_eq!(2, scheduler.stats().iops);
+        assert_eq!(bytes[0], some_data[10..20]);
+        assert_eq!(bytes[1], some_data[500..1500]);
+        assert_eq!(bytes[2], some_data[100_000..100_010]);
+
+        // Once all of the prefetched data has been requested it is released
+        let bytes = file_scheduler
+            .submit_request(vec![0..2000], 0)
+            .await
+            .unwrap();
+        assert_eq!(bytes[0], some_data[0..2000]);
+        assert_eq!(2, scheduler.stats().iops);
+        assert_eq!(0, scheduler.prefetch.num_bytes());
+
+        file_scheduler.submit_single(0..10, 0).await.unwrap();
+        assert_eq!(3, scheduler.stats().iops);
+    }
+
     #[tokio::test]
     async fn test_io_stats_sink() {
         let tmp_file = TempObjFile::default();
@@ -1369,6 +1864,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 1024 * 1024,
             use_lite_scheduler: None,
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
 
         let scan_scheduler = ScanScheduler::new(obj_store, config);
@@ -1460,6 +1957,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 10,
             use_lite_scheduler: None,
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
 
         let scan_scheduler = ScanScheduler::new(obj_store.clone(), config);
@@ -1535,6 +2034,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 10,
             use_lite_scheduler: None,
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
 
         let scan_scheduler = ScanScheduler::new(obj_store, config);
@@ -1622,6 +2123,55 @@ mod tests {
         assert_eq!(fut3.await.unwrap()[0].len(), 100);
     }
 
+    #[tokio::test]
+    async fn test_shared_io_budget() {
+        let budget = IoBudget::new(IoBudgetConfig::default().with_max_concurrent_iops(1));
+        let params = ObjectStoreParams {
+            io_budget: Some(budget.clone()),
+            ..Default::default()
+        };
+        let registry = Arc::new(ObjectStoreRegistry::default());
+        let (store, _) = ObjectStore::from_uri_and_params(registry, "memory:///", &params)
+            .await
+            .unwrap();
+        let path = Path::parse("foo").unwrap();
+        store.put(&path, &[0; 100]).await.unwrap();
+
+        for use_lite_scheduler in [false, true] {
+            let config = SchedulerConfig {
+                use_lite_scheduler: Some(use_lite_scheduler),
+                ..SchedulerConfig::default_for_testing()
+            };
+            // Two schedulers, e.g. for two different datasets, draw from the same budget
+            let mut reads = Vec::new();
+            let permit = budget.acquire(0).await;
+            for _ in 0..2 {
+                let scheduler = ScanScheduler::new(store.clone(), config);
+                let file = scheduler
+                    .open_file(&path, &CachedFileSize::new(100))
+                    .await
+                    .unwrap();
+                reads.push(tokio::spawn(async move {
+                    let _scheduler = scheduler;
+                    file.submit_single(0..10, 0).await
+                }));
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert!(reads.iter().all(|read| !read.is_finished()));
+
+            drop(permit);
+            for read in reads {
+                let bytes = timeout(Duration::from_secs(10), read)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(bytes.len(), 10);
+            }
+            assert_eq!(budget.available_iops(), Some(1));
+        }
+    }
+
     #[tokio::test]
     async fn test_object_store_selects_scheduler() {
         // A memory:// store should use the standard scheduler when config is None
@@ -1630,6 +2180,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 256 * 1024 * 1024,
             use_lite_scheduler: None,
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
         let scheduler = ScanScheduler::new(memory_store.clone(), config);
         assert!(!scheduler.uses_lite_scheduler());
@@ -1650,6 +2202,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 256 * 1024 * 1024,
             use_lite_scheduler: None,
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
         let scheduler = ScanScheduler::new(uring_store.clone(), config);
         assert!(scheduler.uses_lite_scheduler());
@@ -1658,6 +2212,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 256 * 1024 * 1024,
             use_lite_scheduler: Some(false),
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
         let scheduler = ScanScheduler::new(uring_store, config);
         assert!(!scheduler.uses_lite_scheduler());
@@ -1666,6 +2222,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 256 * 1024 * 1024,
             use_lite_scheduler: Some(true),
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
         let scheduler = ScanScheduler::new(memory_store, config);
         assert!(scheduler.uses_lite_scheduler());
@@ -1687,6 +2245,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 1,
             use_lite_scheduler: None,
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
         let scan_scheduler = ScanScheduler::new(obj_store.clone(), config);
         let file_scheduler = scan_scheduler
@@ -1712,6 +2272,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 0,
             use_lite_scheduler: Some(false),
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
         let scheduler = ScanScheduler::new(obj_store, config);
 
@@ -1788,6 +2350,8 @@ mod tests {
         let config = SchedulerConfig {
             io_buffer_size_bytes: 10,
             use_lite_scheduler: Some(false),
+            coalesce_options: CoalesceOptions::default(),
+            prefetch_buffer_size_bytes: 0,
         };
         let scan_scheduler = ScanScheduler::new(obj_store, config);
         let file_scheduler = scan_scheduler
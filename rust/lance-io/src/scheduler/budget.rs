@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! A process-wide I/O budget that can be shared between schedulers.
+//!
+//! Every [`ScanScheduler`](super::ScanScheduler) limits its own concurrency and
+//! buffered bytes.  However, a service that opens many datasets will create many
+//! schedulers and the total I/O they issue is unbounded.  An [`IoBudget`] caps the
+//! number of concurrent IOPS and the read bandwidth across all schedulers that
+//! share it, regardless of the dataset or object store they read from.
+//!
+//! The budget is attached to object stores through
+//! [`ObjectStoreParams::io_budget`](crate::object_store::ObjectStoreParams::io_budget)
+//! and every scheduler created for such a store will draw from it.
+//!
+//! To cap the traffic to a particular bucket instead, including reads that do not
+//! go through a scheduler, see
+//! [`RateLimitedStore`](crate::object_store::rate_limit::RateLimitedStore).
+
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::utils::token_bucket::TokenBucket;
+
+/// Limits for an [`IoBudget`], `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct IoBudgetConfig {
+    /// The max number of IOPS that can be in flight at once
+    pub max_concurrent_iops: Option<usize>,
+    /// The max number of bytes that can be read per second
+    pub max_bytes_per_second: Option<u64>,
+}
+
+impl IoBudgetConfig {
+    pub fn with_max_concurrent_iops(self, max_concurrent_iops: usize) -> Self {
+        Self {
+            max_concurrent_iops: Some(max_concurrent_iops),
+            ..self
+        }
+    }
+
+    pub fn with_max_bytes_per_second(self, max_bytes_per_second: u64) -> Self {
+        Self {
+            max_bytes_per_second: Some(max_bytes_per_second),
+            ..self
+        }
+    }
+}
+
+/// A budget of IOPS and bandwidth shared by all schedulers it is attached to
+#[derive(Debug)]
+pub struct IoBudget {
+    config: IoBudgetConfig,
+    iops: Option<Arc<Semaphore>>,
+    bandwidth: Option<TokenBucket>,
+}
+
+/// Held while an IOP is in flight, releases the IOP back to the budget when dropped
+#[derive(Debug)]
+pub struct IoBudgetPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl IoBudget {
+    pub fn new(config: IoBudgetConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            iops: config
+                .max_concurrent_iops
+                .map(|max_iops| Arc::new(Semaphore::new(max_iops.max(1)))),
+            bandwidth: config.max_bytes_per_second.map(TokenBucket::with_rate),
+        })
+    }
+
+    pub fn config(&self) -> &IoBudgetConfig {
+        &self.config
+    }
+
+    /// The number of IOPS that can be started without waiting, if IOPS are limited
+    pub fn available_iops(&self) -> Option<usize> {
+        self.iops.as_ref().map(|iops| iops.available_permits())
+    }
+
+    /// Wait until an IOP of `num_bytes` fits in the budget
+    ///
+    /// The returned permit should be held until the IOP completes.
+    pub async fn acquire(&self, num_bytes: u64) -> IoBudgetPermit {
+        let permit = match &self.iops {
+            // The semaphore is never closed
+            Some(iops) => Some(iops.clone().acquire_owned().await.unwrap()),
+            None => None,
+        };
+        if let Some(bandwidth) = &self.bandwidth {
+            bandwidth.acquire(num_bytes as f64).await;
+        }
+        IoBudgetPermit { _permit: permit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_iops_limit() {
+        let budget = IoBudget::new(IoBudgetConfig::default().with_max_concurrent_iops(2));
+        let first = budget.acquire(10).await;
+        let _second = budget.acquire(10).await;
+        assert_eq!(budget.available_iops(), Some(0));
+
+        let third = tokio::time::timeout(Duration::from_millis(50), budget.acquire(10)).await;
+        assert!(third.is_err());
+
+        drop(first);
+        assert_eq!(budget.available_iops(), Some(1));
+        let _third = budget.acquire(10).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bandwidth_limit() {
+        let budget = IoBudget::new(IoBudgetConfig::default().with_max_bytes_per_second(1000));
+        assert_eq!(budget.available_iops(), None);
+
+        // The first second of bandwidth is available immediately
+        let start = tokio::time::Instant::now();
+        budget.acquire(1000).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        // After that, requests are paced by the rate
+        budget.acquire(500).await;
+        assert!(start.elapsed() >= Duration::from_millis(450));
+    }
+}
@@ -33,7 +33,7 @@ use std::{
 use bytes::Bytes;
 use lance_core::{Error, Result};
 
-use super::{BACKPRESSURE_DEBOUNCE, BACKPRESSURE_MIN};
+use super::{BACKPRESSURE_DEBOUNCE, BACKPRESSURE_MIN, IoUrgency};
 
 type RunFn = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = Result<Bytes>> + Send>> + Send>;
 
@@ -355,18 +355,20 @@ impl BackpressureThrottle for SimpleBackpressureThrottle {
 struct TaskEntry {
     task_id: u64,
     priority: u128,
+    urgency: IoUrgency,
     reserved: bool,
 }
 
 impl Ord for TaskEntry {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // Prefer reserved tasks over unreserved tasks and then highest priority tasks over lowest
-        // priority tasks.
+        // Prefer reserved tasks over unreserved tasks, then more urgent tasks over less urgent
+        // tasks, and then highest priority tasks over lowest priority tasks.
         //
         // This is a max-heap so we sort by reserved in normal order (true > false) and priority
         // in reverse order (lowest priority first)
         self.reserved
             .cmp(&other.reserved)
+            .then_with(|| self.urgency.cmp_urgency(&other.urgency))
             .then(other.priority.cmp(&self.priority))
     }
 }
@@ -379,7 +381,7 @@ impl PartialOrd for TaskEntry {
 
 impl PartialEq for TaskEntry {
     fn eq(&self, other: &Self) -> bool {
-        self.priority == other.priority
+        self.priority == other.priority && self.urgency == other.urgency
     }
 }
 
@@ -450,7 +452,12 @@ impl IoQueue {
         }
     }
 
-    fn push(&self, mut task: IoTask, mut state: MutexGuard<IoQueueState>) -> Result<()> {
+    fn push(
+        &self,
+        mut task: IoTask,
+        urgency: IoUrgency,
+        mut state: MutexGuard<IoQueueState>,
+    ) -> Result<()> {
         let task_id = task.id;
         let maybe_reservation = if task.bypass_backpressure {
             Some(state.backpressure_throttle.force_acquire(task.priority))
@@ -469,6 +476,7 @@ impl IoQueue {
         state.pending_tasks.push(TaskEntry {
             task_id,
             priority: task.priority,
+            urgency,
             reserved: task.is_reserved(),
         });
         state.tasks.insert(task_id, task);
@@ -479,6 +487,7 @@ impl IoQueue {
         self: Arc<Self>,
         range: Range<u64>,
         priority: u128,
+        urgency: IoUrgency,
         run_fn: RunFn,
         bypass_backpressure: bool,
     ) -> Result<TaskHandle> {
@@ -501,7 +510,7 @@ impl IoQueue {
                 run_fn,
             },
         };
-        self.push(task, state)?;
+        self.push(task, urgency, state)?;
         Ok(TaskHandle {
             task_id,
             queue: self,
@@ -587,6 +596,7 @@ impl Future for TaskHandle {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scheduler::IoPriorityClass;
     use tokio::sync::oneshot;
 
     #[tokio::test]
@@ -616,6 +626,7 @@ mod tests {
             .submit(
                 0..10,
                 0,
+                IoUrgency::default(),
                 make_run_fn(0, blocker_rx, start_order.clone()),
                 false,
             )
@@ -629,6 +640,7 @@ mod tests {
             .submit(
                 0..10,
                 30,
+                IoUrgency::default(),
                 make_run_fn(30, rx_30, start_order.clone()),
                 false,
             )
@@ -640,6 +652,7 @@ mod tests {
             .submit(
                 0..10,
                 10,
+                IoUrgency::default(),
                 make_run_fn(10, rx_10, start_order.clone()),
                 false,
             )
@@ -651,6 +664,7 @@ mod tests {
             .submit(
                 0..10,
                 50,
+                IoUrgency::default(),
                 make_run_fn(50, rx_50, start_order.clone()),
                 false,
             )
@@ -662,6 +676,7 @@ mod tests {
             .submit(
                 0..10,
                 20,
+                IoUrgency::default(),
                 make_run_fn(20, rx_20, start_order.clone()),
                 false,
             )
@@ -696,6 +711,68 @@ mod tests {
         assert_eq!(*start_order.lock().unwrap(), vec![0, 10, 20, 30, 50]);
     }
 
+    #[tokio::test]
+    async fn test_urgency_ordering() {
+        let queue = Arc::new(IoQueue::new(128, 10));
+        let start_order: Arc<Mutex<Vec<u128>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let make_run_fn =
+            |prio: u128, rx: oneshot::Receiver<Bytes>, order: Arc<Mutex<Vec<u128>>>| -> RunFn {
+                Box::new(move || {
+                    order.lock().unwrap().push(prio);
+                    Box::pin(async move { Ok(rx.await.unwrap()) })
+                })
+            };
+
+        let (blocker_tx, blocker_rx) = oneshot::channel();
+        let blocker = queue
+            .clone()
+            .submit(
+                0..10,
+                0,
+                IoUrgency::default(),
+                make_run_fn(0, blocker_rx, start_order.clone()),
+                false,
+            )
+            .unwrap();
+
+        // A background task with a better priority is queued before an interactive task
+        let (background_tx, background_rx) = oneshot::channel();
+        let background = queue
+            .clone()
+            .submit(
+                0..10,
+                1,
+                IoUrgency::new(IoPriorityClass::Background),
+                make_run_fn(1, background_rx, start_order.clone()),
+                false,
+            )
+            .unwrap();
+        let (interactive_tx, interactive_rx) = oneshot::channel();
+        let interactive = queue
+            .clone()
+            .submit(
+                0..10,
+                2,
+                IoUrgency::new(IoPriorityClass::Interactive),
+                make_run_fn(2, interactive_rx, start_order.clone()),
+                false,
+            )
+            .unwrap();
+        assert_eq!(*start_order.lock().unwrap(), vec![0]);
+
+        // The interactive task is started first.  The background task then starts as well
+        // since its priority is lower than any in-flight priority.
+        blocker_tx.send(Bytes::from_static(b"x")).unwrap();
+        blocker.await.unwrap();
+        assert_eq!(*start_order.lock().unwrap(), vec![0, 2, 1]);
+
+        interactive_tx.send(Bytes::from_static(b"x")).unwrap();
+        background_tx.send(Bytes::from_static(b"x")).unwrap();
+        interactive.await.unwrap();
+        background.await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_zero_buffer_bypasses_backpressure() {
         // Budget = 0 sets no_backpressure = true, so all tasks start immediately
@@ -714,17 +791,35 @@ mod tests {
         let (tx0, rx0) = oneshot::channel();
         let h0 = queue
             .clone()
-            .submit(0..10, 0, make_run_fn(0, rx0, start_order.clone()), false)
+            .submit(
+                0..10,
+                0,
+                IoUrgency::default(),
+                make_run_fn(0, rx0, start_order.clone()),
+                false,
+            )
             .unwrap();
         let (tx1, rx1) = oneshot::channel();
         let h1 = queue
             .clone()
-            .submit(0..10, 1, make_run_fn(1, rx1, start_order.clone()), false)
+            .submit(
+                0..10,
+                1,
+                IoUrgency::default(),
+                make_run_fn(1, rx1, start_order.clone()),
+                false,
+            )
             .unwrap();
         let (tx2, rx2) = oneshot::channel();
         let h2 = queue
             .clone()
-            .submit(0..10, 2, make_run_fn(2, rx2, start_order.clone()), false)
+            .submit(
+                0..10,
+                2,
+                IoUrgency::default(),
+                make_run_fn(2, rx2, start_order.clone()),
+                false,
+            )
             .unwrap();
 
         // All three tasks start immediately — no backpressure budget check when max_bytes=0.
@@ -760,6 +855,7 @@ mod tests {
             .submit(
                 0..10,
                 0,
+                IoUrgency::default(),
                 make_run_fn(0, blocker_rx, start_order.clone()),
                 false,
             )
@@ -772,6 +868,7 @@ mod tests {
             .submit(
                 0..10,
                 1,
+                IoUrgency::default(),
                 make_run_fn(1, normal_rx, start_order.clone()),
                 false,
             )
@@ -784,6 +881,7 @@ mod tests {
             .submit(
                 0..10,
                 2,
+                IoUrgency::default(),
                 make_run_fn(2, bypass_rx, start_order.clone()),
                 true,
             )
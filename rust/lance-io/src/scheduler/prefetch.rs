@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Data read ahead of the decoder for sequential scans.
+//!
+//! A sequential scan knows which byte ranges it will need long before the decoder
+//! asks for them.  [`FileScheduler::prefetch`](super::FileScheduler::prefetch) reads
+//! those ranges in the background and keeps them in a [`PrefetchBuffer`], bounded by
+//! a byte budget.  Requests that fall inside a prefetched range are then served from
+//! the buffer, waiting on the background read if it has not finished yet, instead of
+//! going to the object store.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Mutex;
+
+use bytes::Bytes;
+use futures::FutureExt;
+use futures::future::{BoxFuture, Shared};
+use object_store::path::Path;
+
+/// The result of a background read, `None` if the read failed
+pub type PrefetchFuture = Shared<BoxFuture<'static, Option<Bytes>>>;
+
+struct PrefetchEntry {
+    range: Range<u64>,
+    data: PrefetchFuture,
+    /// Bytes of the range that have not been requested yet
+    remaining: u64,
+}
+
+#[derive(Default)]
+struct PrefetchState {
+    entries: HashMap<Path, Vec<PrefetchEntry>>,
+    bytes: u64,
+}
+
+/// Prefetched ranges of the files opened by a [`ScanScheduler`](super::ScanScheduler)
+///
+/// An entry is dropped once every byte of it has been requested, which frees its
+/// part of the budget for the next prefetch.
+pub struct PrefetchBuffer {
+    capacity: u64,
+    state: Mutex<PrefetchState>,
+}
+
+impl PrefetchBuffer {
+    pub fn new(capacity: u64) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(PrefetchState::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Prefetch `range` of `path` with the read started by `read`
+    ///
+    /// Returns false, without starting the read, if the range does not fit in the
+    /// remaining budget.  Ranges that are already prefetched are skipped.
+    pub fn insert_with(
+        &self,
+        path: &Path,
+        range: Range<u64>,
+        read: impl FnOnce() -> PrefetchFuture,
+    ) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let num_bytes = range.end - range.start;
+        if state
+            .entries
+            .get(path)
+            .is_some_and(|entries| entries.iter().any(|e| contains(&e.range, &range)))
+        {
+            return true;
+        }
+        if state.bytes + num_bytes > self.capacity {
+            return false;
+        }
+        state.bytes += num_bytes;
+        state
+            .entries
+            .entry(path.clone())
+            .or_default()
+            .push(PrefetchEntry {
+                range,
+                data: read(),
+                remaining: num_bytes,
+            });
+        true
+    }
+
+    /// Take the prefetched data for `range` of `path`, if it lies within a prefetched range
+    pub fn get(
+        &self,
+        path: &Path,
+        range: &Range<u64>,
+    ) -> Option<BoxFuture<'static, Option<Bytes>>> {
+        if range.is_empty() {
+            return None;
+        }
+        let mut state = self.state.lock().unwrap();
+        let entries = state.entries.get_mut(path)?;
+        let idx = entries.iter().position(|e| contains(&e.range, range))?;
+        let entry = &mut entries[idx];
+        let data = entry.data.clone();
+        let start = (range.start - entry.range.start) as usize;
+        let end = (range.end - entry.range.start) as usize;
+
+        entry.remaining = entry.remaining.saturating_sub(range.end - range.start);
+        if entry.remaining == 0 {
+            let entry = entries.swap_remove(idx);
+            if entries.is_empty() {
+                state.entries.remove(path);
+            }
+            state.bytes -= entry.range.end - entry.range.start;
+        }
+        Some(
+            data.map(move |bytes| bytes.map(|bytes| bytes.slice(start..end)))
+                .boxed(),
+        )
+    }
+
+    #[cfg(test)]
+    pub fn num_bytes(&self) -> u64 {
+        self.state.lock().unwrap().bytes
+    }
+}
+
+fn contains(outer: &Range<u64>, inner: &Range<u64>) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
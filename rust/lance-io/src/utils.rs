@@ -22,6 +22,7 @@ use crate::{
 };
 use lance_core::{Error, Result};
 
+pub(crate) mod token_bucket;
 pub mod tracking_store;
 
 /// Read a binary array from a [Reader].
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! The token bucket shared by the I/O budget, the fixed rate limits and the AIMD
+//! throttle.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// A token bucket that refills at `rate` tokens per second, up to `capacity`
+///
+/// Tokens are reserved immediately, so the bucket may go into debt and later
+/// callers queue behind earlier ones instead of all waking at the same instant.
+/// A reservation larger than the capacity simply waits longer.
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    state: Mutex<TokenBucketState>,
+}
+
+#[derive(Debug)]
+struct TokenBucketState {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucketState {
+    /// Add the tokens accrued at the current rate since the last refill
+    fn refill(&mut self, capacity: f64) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(capacity);
+        self.last_refill = now;
+    }
+}
+
+impl TokenBucket {
+    /// A bucket that starts full and holds `capacity` tokens
+    pub fn new(rate: f64, capacity: f64) -> Self {
+        debug_assert!(rate > 0.0, "token bucket rate must be positive");
+        Self {
+            capacity,
+            state: Mutex::new(TokenBucketState {
+                rate,
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// A bucket that holds up to one second of tokens
+    pub fn with_rate(rate: u64) -> Self {
+        let rate = rate.max(1) as f64;
+        Self::new(rate, rate)
+    }
+
+    /// Reserve `amount` tokens, returning how long the caller must wait before
+    /// using them.
+    pub fn reserve(&self, amount: f64) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        state.refill(self.capacity);
+        state.tokens -= amount;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / state.rate)
+        }
+    }
+
+    /// Reserve `amount` tokens and wait until they may be used
+    pub async fn acquire(&self, amount: f64) {
+        let wait = self.reserve(amount);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Change the refill rate, tokens already in the bucket are kept
+    ///
+    /// The time elapsed so far is credited at the old rate.
+    pub fn set_rate(&self, rate: f64) {
+        debug_assert!(rate > 0.0, "token bucket rate must be positive");
+        let mut state = self.state.lock().unwrap();
+        state.refill(self.capacity);
+        state.rate = rate;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reserve() {
+        let bucket = TokenBucket::new(10.0, 5.0);
+        assert_eq!(bucket.reserve(5.0), Duration::ZERO);
+        // In debt by two tokens, which takes 200ms to pay off
+        assert_eq!(bucket.reserve(2.0), Duration::from_millis(200));
+
+        // Refills never exceed the capacity
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(bucket.reserve(5.0), Duration::ZERO);
+        assert!(!bucket.reserve(1.0).is_zero());
+
+        bucket.set_rate(1.0);
+        assert_eq!(bucket.reserve(1.0), Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_set_rate_refills_at_old_rate() {
+        let bucket = TokenBucket::new(10.0, 10.0);
+        assert_eq!(bucket.reserve(10.0), Duration::ZERO);
+
+        // Half a second at 10 tokens/s refills 5 tokens before the decrease
+        tokio::time::advance(Duration::from_millis(500)).await;
+        bucket.set_rate(2.0);
+        assert_eq!(bucket.reserve(5.0), Duration::ZERO);
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert_eq!(bucket.reserve(2.0), Duration::from_millis(500));
+    }
+}
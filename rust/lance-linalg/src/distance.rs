@@ -15,6 +15,8 @@ use arrow_array::cast::AsArray;
 use arrow_array::types::{Float16Type, Float32Type, Float64Type, UInt8Type};
 use arrow_array::{Array, ArrowPrimitiveType, FixedSizeListArray, Float32Array, ListArray};
 use arrow_schema::{ArrowError, DataType};
+use half::bf16;
+use lance_arrow::FloatArray;
 
 pub mod cosine;
 pub mod cosine_u8;
@@ -123,7 +125,11 @@ pub fn multivec_distance(
     // check the query vectors type first
     // because we don't want to check the vectors type for each vector
     match query.data_type() {
-        DataType::Float16 | DataType::Float32 | DataType::Float64 | DataType::UInt8 => {}
+        DataType::Float16
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::FixedSizeBinary(2)
+        | DataType::UInt8 => {}
         _ => {
             return Err(ArrowError::InvalidArgumentError(
                 "query must be a float array or binary array".to_string(),
@@ -178,6 +184,11 @@ pub fn multivec_distance(
                             dim,
                             distance_type,
                         ),
+                        DataType::FixedSizeBinary(2) => {
+                            let query = query.as_fixed_size_binary().as_slice();
+                            let vectors = multivector.values().as_fixed_size_binary().as_slice();
+                            multivec_distance_slices::<bf16>(query, vectors, dim, distance_type)
+                        }
                         _ => unreachable!("missed to check query type"),
                     },
                 };
@@ -198,14 +209,24 @@ fn multivec_distance_impl<T: ArrowPrimitiveType>(
 where
     T::Native: L2 + Cosine + Dot,
 {
-    let query = query.as_primitive::<T>().values();
+    multivec_distance_slices(
+        query.as_primitive::<T>().values(),
+        multivector.values().as_primitive::<T>().values(),
+        dim,
+        distance_type,
+    )
+}
+
+fn multivec_distance_slices<T: L2 + Cosine + Dot>(
+    query: &[T],
+    vectors: &[T],
+    dim: usize,
+    distance_type: DistanceType,
+) -> f32 {
     query
         .chunks_exact(dim)
         .map(|q| {
-            multivector
-                .values()
-                .as_primitive::<T>()
-                .values()
+            vectors
                 .chunks_exact(dim)
                 .map(|v| 1.0 - distance_type.func()(q, v))
                 .max_by(|a, b| a.total_cmp(b))
@@ -245,4 +266,35 @@ mod tests {
         assert!(dists[0].is_nan());
         assert_eq!(dists[1], -4.0);
     }
+
+    #[test]
+    fn test_arrow_batch_func_bf16() {
+        use lance_arrow::FixedSizeListArrayExt;
+        use lance_arrow::bfloat16::BFloat16Array;
+
+        let dim = 4;
+        let query = [1.0_f32, 2.0, 3.0, 4.0];
+        let vectors = (0..32).map(|v| v as f32 - 8.0).collect::<Vec<_>>();
+        let to_bf16 = |values: &[f32]| {
+            BFloat16Array::from_iter_values(values.iter().copied().map(bf16::from_f32)).into_inner()
+        };
+
+        let f32_vectors =
+            FixedSizeListArray::try_new_from_values(Float32Array::from(vectors.clone()), dim)
+                .unwrap();
+        let bf16_vectors = FixedSizeListArray::try_new_from_values(to_bf16(&vectors), dim).unwrap();
+        for distance_type in [DistanceType::L2, DistanceType::Cosine, DistanceType::Dot] {
+            let expected =
+                distance_type.arrow_batch_func()(&Float32Array::from(query.to_vec()), &f32_vectors)
+                    .unwrap();
+            let actual = distance_type.arrow_batch_func()(&to_bf16(&query), &bf16_vectors).unwrap();
+            assert_eq!(expected.len(), actual.len());
+            for (expected, actual) in expected.values().iter().zip(actual.values()) {
+                assert!(
+                    (expected - actual).abs() <= 1e-3 * expected.abs().max(1.0),
+                    "{distance_type}: {expected} != {actual}"
+                );
+            }
+        }
+    }
 }
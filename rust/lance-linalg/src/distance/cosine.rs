@@ -16,6 +16,7 @@ use arrow_array::{
 };
 use arrow_schema::DataType;
 use half::{bf16, f16};
+use lance_arrow::bfloat16::BFloat16Type;
 use lance_arrow::{ArrowFloatType, FixedSizeListArrayExt, FloatArray};
 use lance_core::utils::cpu::SIMD_SUPPORT;
 #[cfg(feature = "fp16kernels")]
@@ -420,8 +421,18 @@ pub fn cosine_distance_arrow_batch(
 ) -> Result<Arc<Float32Array>> {
     match *from.data_type() {
         DataType::Float16 => do_cosine_distance_arrow_batch::<Float16Type>(from.as_primitive(), to),
+        // f32 queries against bf16 vectors are computed in f32 space.
+        DataType::Float32 if to.value_type() == DataType::FixedSizeBinary(2) => {
+            do_cosine_distance_arrow_batch::<Float32Type>(
+                from.as_primitive(),
+                &to.convert_to_floating_point()?,
+            )
+        }
         DataType::Float32 => do_cosine_distance_arrow_batch::<Float32Type>(from.as_primitive(), to),
         DataType::Float64 => do_cosine_distance_arrow_batch::<Float64Type>(from.as_primitive(), to),
+        DataType::FixedSizeBinary(2) => {
+            do_cosine_distance_arrow_batch::<BFloat16Type>(from.as_fixed_size_binary(), to)
+        }
         DataType::Int8 => do_cosine_distance_arrow_batch::<Float32Type>(
             &from
                 .as_primitive::<Int8Type>()
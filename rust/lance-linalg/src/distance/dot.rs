@@ -12,6 +12,7 @@ use arrow_array::types::{Float16Type, Float64Type, Int8Type};
 use arrow_array::{Array, FixedSizeListArray, Float32Array, cast::AsArray, types::Float32Type};
 use arrow_schema::DataType;
 use half::{bf16, f16};
+use lance_arrow::bfloat16::BFloat16Type;
 use lance_arrow::{ArrowFloatType, FixedSizeListArrayExt, FloatArray};
 use lance_core::assume_eq;
 use lance_core::utils::cpu::SIMD_SUPPORT;
@@ -288,9 +289,10 @@ pub fn dot_distance_batch<'a, T: Dot>(
     Box::new(to.chunks_exact(dimension).map(|v| dot_distance(from, v)))
 }
 
-fn do_dot_distance_arrow_batch<T: ArrowFloatType>(
+fn do_dot_arrow_batch<T: ArrowFloatType>(
     from: &T::ArrayType,
     to: &FixedSizeListArray,
+    negative: bool,
 ) -> Result<Arc<Float32Array>>
 where
     T::Native: Dot,
@@ -309,10 +311,10 @@ where
                 to.value_type()
             )))?;
 
-    let dists = to_values
-        .as_slice()
-        .chunks_exact(dimension)
-        .map(|v| dot_distance(from.as_slice(), v));
+    let dists = to_values.as_slice().chunks_exact(dimension).map(|v| {
+        let dot = T::Native::dot(from.as_slice(), v);
+        if negative { 1.0 - dot } else { dot }
+    });
 
     Ok(Arc::new(Float32Array::new(
         dists.collect(),
@@ -335,21 +337,52 @@ where
 pub fn dot_distance_arrow_batch(
     from: &dyn Array,
     to: &FixedSizeListArray,
+) -> Result<Arc<Float32Array>> {
+    dot_arrow_batch_impl(from, to, true)
+}
+
+/// Compute the dot product between a vector and a batch of vectors.
+///
+/// Null buffer of `to` is propagated to the returned array.
+///
+/// # Panics
+///
+/// Panics if the length of `from` is not equal to the dimension (value length) of `to`.
+pub fn dot_arrow_batch(from: &dyn Array, to: &FixedSizeListArray) -> Result<Arc<Float32Array>> {
+    dot_arrow_batch_impl(from, to, false)
+}
+
+fn dot_arrow_batch_impl(
+    from: &dyn Array,
+    to: &FixedSizeListArray,
+    negative: bool,
 ) -> Result<Arc<Float32Array>> {
     let dimension = to.value_length() as usize;
     debug_assert_eq!(from.len(), dimension);
 
     match *from.data_type() {
-        DataType::Float16 => do_dot_distance_arrow_batch::<Float16Type>(from.as_primitive(), to),
-        DataType::Float32 => do_dot_distance_arrow_batch::<Float32Type>(from.as_primitive(), to),
-        DataType::Float64 => do_dot_distance_arrow_batch::<Float64Type>(from.as_primitive(), to),
-        DataType::Int8 => do_dot_distance_arrow_batch::<Float32Type>(
+        DataType::Float16 => do_dot_arrow_batch::<Float16Type>(from.as_primitive(), to, negative),
+        // f32 queries against bf16 vectors are computed in f32 space.
+        DataType::Float32 if to.value_type() == DataType::FixedSizeBinary(2) => {
+            do_dot_arrow_batch::<Float32Type>(
+                from.as_primitive(),
+                &to.convert_to_floating_point()?,
+                negative,
+            )
+        }
+        DataType::Float32 => do_dot_arrow_batch::<Float32Type>(from.as_primitive(), to, negative),
+        DataType::Float64 => do_dot_arrow_batch::<Float64Type>(from.as_primitive(), to, negative),
+        DataType::FixedSizeBinary(2) => {
+            do_dot_arrow_batch::<BFloat16Type>(from.as_fixed_size_binary(), to, negative)
+        }
+        DataType::Int8 => do_dot_arrow_batch::<Float32Type>(
             &from
                 .as_primitive::<Int8Type>()
                 .into_iter()
                 .map(|x| x.unwrap() as f32)
                 .collect(),
             &to.convert_to_floating_point()?,
+            negative,
         ),
         _ => Err(Error::InvalidArgumentError(format!(
             "Unsupported data type: {:?}",
@@ -399,6 +432,21 @@ mod tests {
         assert_eq!(f64::dot(&x, &y), dot(&x, &y));
     }
 
+    #[test]
+    fn test_dot_arrow_batch() {
+        let from = Float32Array::from(vec![1.0, 2.0]);
+        let to = FixedSizeListArray::try_new_from_values(
+            Float32Array::from(vec![3.0, 4.0, 0.5, -1.0]),
+            2,
+        )
+        .unwrap();
+
+        let dots = dot_arrow_batch(&from, &to).unwrap();
+        assert_eq!(dots.values(), &[11.0, -1.5]);
+        let distances = dot_distance_arrow_batch(&from, &to).unwrap();
+        assert_eq!(distances.values(), &[-10.0, 2.5]);
+    }
+
     /// Reference implementation of dot product.
     fn dot_scalar_ref(x: &[f64], y: &[f64]) -> f32 {
         x.iter().zip(y.iter()).map(|(&x, &y)| x * y).sum::<f64>() as f32
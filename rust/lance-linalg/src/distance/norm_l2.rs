@@ -8,6 +8,7 @@ use arrow_array::cast::AsArray;
 use arrow_array::types::{Float16Type, Float32Type, Float64Type};
 use arrow_schema::DataType;
 use half::{bf16, f16};
+use lance_arrow::FloatArray;
 #[allow(unused_imports)]
 use lance_core::utils::cpu::SIMD_SUPPORT;
 #[cfg(feature = "fp16kernels")]
@@ -238,6 +239,13 @@ pub fn norm_squared_fsl(fsl: &FixedSizeListArray) -> Vec<f32> {
             .chunks_exact(dim)
             .map(|v| v.iter().map(|v| v * v).sum::<f64>() as f32)
             .collect::<Vec<_>>(),
+        DataType::FixedSizeBinary(2) => fsl
+            .values()
+            .as_fixed_size_binary()
+            .as_slice()
+            .chunks_exact(dim)
+            .map(|v| v.iter().map(|v| v.to_f32().powi(2)).sum::<f32>())
+            .collect::<Vec<_>>(),
         _ => {
             unimplemented!("Unsupported data type: {}", fsl.value_type())
         }
@@ -16,6 +16,9 @@ use arrow_array::{
     },
 };
 use arrow_schema::{ArrowError, DataType};
+use half::bf16;
+use lance_arrow::FloatArray;
+use lance_arrow::bfloat16::BFloat16Array;
 use num_traits::AsPrimitive;
 use num_traits::{Float, Num, bounds::Bounded};
 
@@ -197,12 +200,40 @@ where
     )
 }
 
+/// bfloat16 vectors are normalized in f32 space to avoid losing precision in the norm.
+fn do_normalize_fsl_bf16(fsl: &FixedSizeListArray) -> Result<FixedSizeListArray> {
+    let dim = fsl.value_length() as usize;
+    let norm_arr = BFloat16Array::from_iter_values(
+        fsl.values()
+            .as_fixed_size_binary()
+            .as_slice()
+            .chunks(dim)
+            .flat_map(|chunk| {
+                let chunk = chunk.iter().map(|v| v.to_f32()).collect::<Vec<_>>();
+                normalize(&chunk).0.map(bf16::from_f32).collect::<Vec<_>>()
+            }),
+    );
+
+    let field = match fsl.data_type() {
+        DataType::FixedSizeList(field, _) => field.clone(),
+        _ => unreachable!("FixedSizeListArray must have FixedSizeList data type"),
+    };
+
+    FixedSizeListArray::try_new(
+        field,
+        fsl.value_length(),
+        Arc::new(norm_arr.into_inner()),
+        fsl.nulls().cloned(),
+    )
+}
+
 /// L2 normalize a [FixedSizeListArray] (of vectors).
 pub fn normalize_fsl(fsl: &FixedSizeListArray) -> Result<FixedSizeListArray> {
     match fsl.value_type() {
         DataType::Float16 => do_normalize_fsl::<Float16Type>(fsl),
         DataType::Float32 => do_normalize_fsl::<Float32Type>(fsl),
         DataType::Float64 => do_normalize_fsl::<Float64Type>(fsl),
+        DataType::FixedSizeBinary(2) => do_normalize_fsl_bf16(fsl),
         _ => Err(ArrowError::SchemaError(format!(
             "Normalize only supports float array, got: {}",
             fsl.value_type()
@@ -257,6 +288,7 @@ pub fn normalize_fsl_owned(fsl: FixedSizeListArray) -> Result<FixedSizeListArray
         DataType::Float16 => do_normalize_fsl_inplace::<Float16Type>(fsl),
         DataType::Float32 => do_normalize_fsl_inplace::<Float32Type>(fsl),
         DataType::Float64 => do_normalize_fsl_inplace::<Float64Type>(fsl),
+        DataType::FixedSizeBinary(2) => do_normalize_fsl_bf16(&fsl),
         _ => Err(ArrowError::SchemaError(format!(
             "Normalize only supports float array, got: {}",
             fsl.value_type()
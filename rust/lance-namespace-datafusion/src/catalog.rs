@@ -4,15 +4,150 @@
 use std::any::Any;
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
 use datafusion::catalog::{CatalogProvider, CatalogProviderList, SchemaProvider};
 use datafusion::error::Result;
+use futures::{StreamExt, stream};
 
 #[allow(unused_imports)]
 use crate::SessionBuilder;
+use crate::error::to_datafusion_error;
 use crate::namespace_level::NamespaceLevel;
-use crate::schema::LanceSchemaProvider;
+use crate::schema::{LanceSchemaProvider, SchemaOptions};
+use crate::udtf::block_on;
+
+/// How [`LanceCatalogProviderList`] and [`LanceCatalogProvider`] discover their
+/// children.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CatalogDiscovery {
+    /// List all child namespaces when the provider is created.
+    ///
+    /// The catalogs of a catalog list are loaded several at a time, see
+    /// [`SchemaOptions::discovery_concurrency`], and a catalog that fails to
+    /// load fails the list unless [`SchemaOptions::discovery_errors`] is
+    /// [`DiscoveryErrors::Lenient`].
+    #[default]
+    Eager,
+    /// Resolve children by name on first use, without listing the namespace.
+    ///
+    /// Resolved children are cached for `ttl` (forever if `None`), after which
+    /// they are resolved again and any tables they loaded are reloaded. A name
+    /// is checked against the namespace in the background when it is first
+    /// resolved, and dropped from the cache if the namespace does not exist, so
+    /// lookups of missing names do not grow the cache.
+    Lazy { ttl: Option<Duration> },
+}
+
+/// How eager discovery handles children that fail to load.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiscoveryErrors {
+    /// Fail creating the provider if any child fails to load.
+    #[default]
+    Strict,
+    /// Log a warning and leave out children that fail to load, so one broken
+    /// namespace does not keep the others from being queried.
+    Lenient,
+}
+
+/// The number of children loaded at a time by eager discovery, unless
+/// configured with [`SchemaOptions::discovery_concurrency`].
+pub const DEFAULT_DISCOVERY_CONCURRENCY: usize = 16;
+
+/// A provider cached by a catalog list or catalog.
+#[derive(Debug)]
+struct CachedEntry<T: ?Sized> {
+    provider: Arc<T>,
+    /// When a discovered entry has to be resolved again. Explicitly registered
+    /// entries never expire.
+    expires_at: Option<Instant>,
+    /// Whether the entry was discovered from the namespace, as opposed to
+    /// registered by the user.
+    discovered: bool,
+}
+
+// Not derived, that would require `T: Clone`.
+impl<T: ?Sized> Clone for CachedEntry<T> {
+    fn clone(&self) -> Self {
+        Self {
+            provider: Arc::clone(&self.provider),
+            expires_at: self.expires_at,
+            discovered: self.discovered,
+        }
+    }
+}
+
+impl<T: ?Sized> CachedEntry<T> {
+    fn discovered(provider: Arc<T>, discovery: CatalogDiscovery) -> Self {
+        let expires_at = match discovery {
+            CatalogDiscovery::Lazy { ttl: Some(ttl) } => Some(Instant::now() + ttl),
+            _ => None,
+        };
+        Self {
+            provider,
+            expires_at,
+            discovered: true,
+        }
+    }
+
+    fn registered(provider: Arc<T>) -> Self {
+        Self {
+            provider,
+            expires_at: None,
+            discovered: false,
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| Instant::now() >= expires_at)
+    }
+}
+
+/// Cache a lazily resolved provider for `name`, and drop it again in the
+/// background if `ns_level` turns out not to exist.
+///
+/// DataFusion resolves providers synchronously, so the provider is returned
+/// before the check completes. Without a runtime the entry is only dropped by
+/// a refresh.
+fn insert_lazy<T: ?Sized + Send + Sync + 'static>(
+    cache: &Arc<DashMap<String, CachedEntry<T>>>,
+    name: &str,
+    ns_level: NamespaceLevel,
+    provider: Arc<T>,
+    discovery: CatalogDiscovery,
+) {
+    cache.insert(
+        name.to_string(),
+        CachedEntry::discovered(Arc::clone(&provider), discovery),
+    );
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        let cache = Arc::clone(cache);
+        let name = name.to_string();
+        handle.spawn(async move {
+            if let Ok(false) = ns_level.exists().await {
+                cache.remove_if(&name, |_, entry| Arc::ptr_eq(&entry.provider, &provider));
+            }
+        });
+    }
+}
+
+/// The cache key of the child `name` of `ns_level`, following its aliases and
+/// matching the cached names regardless of case if configured.
+fn resolve_cached_name<T: ?Sized>(
+    cache: &DashMap<String, CachedEntry<T>>,
+    ns_level: &NamespaceLevel,
+    name: &str,
+) -> String {
+    let name = ns_level.unalias(name);
+    if cache.contains_key(name) || !ns_level.has_case_insensitive_names() {
+        return name.to_string();
+    }
+    ns_level
+        .resolve_name(name, cache.iter().map(|entry| entry.key().clone()))
+        .unwrap_or_else(|| name.to_string())
+}
 
 /// A dynamic [`CatalogProviderList`] that maps Lance namespaces to catalogs.
 ///
@@ -20,6 +155,20 @@ use crate::schema::LanceSchemaProvider;
 /// via [`SessionBuilder::with_root`], and each child namespace under this root is
 /// automatically registered as a [`LanceCatalogProvider`].
 ///
+/// By default all child namespaces are listed when the list is created. With
+/// [`CatalogDiscovery::Lazy`] catalogs are resolved by name when first used
+/// instead, so creating a session does not depend on the size of the namespace.
+/// [`Self::refresh`] drops the cached catalogs.
+///
+/// Catalog names follow the aliases of the namespace, see
+/// [`NamespaceLevel::with_alias`]. With
+/// [`NamespaceLevel::with_case_insensitive_names`] they also match the names
+/// of cached catalogs regardless of case, so with lazy discovery only once a
+/// catalog was used under its exact name.
+///
+/// With [`SchemaOptions::write_through`] registered catalogs are created as
+/// child namespaces.
+///
 /// This `CatalogProviderList` is optional when building a DataFusion `SessionContext`.
 /// If not provided, you can still configure catalogs using
 /// [`SessionBuilder::add_catalog`] or set a default catalog via
@@ -27,28 +176,98 @@ use crate::schema::LanceSchemaProvider;
 #[derive(Debug, Clone)]
 pub struct LanceCatalogProviderList {
     /// Root Lance namespace used to resolve catalogs / schemas / tables.
-    #[allow(dead_code)]
     ns_level: NamespaceLevel,
-    /// Catalogs that have been loaded from the root namespace.
+    discovery: CatalogDiscovery,
+    /// Passed to the schemas, see [`LanceSchemaProvider::with_options`].
+    schema_options: SchemaOptions,
+    /// Catalogs that have been loaded from the root namespace or registered.
     ///
-    /// Note: The values in this map may become stale over time, as there is currently
-    /// no mechanism to automatically refresh or invalidate cached catalog providers.
-    catalogs: DashMap<String, Arc<dyn CatalogProvider>>,
+    /// Note: With eager discovery the values in this map may become stale over time
+    /// until [`Self::refresh`] is called.
+    catalogs: Arc<DashMap<String, CachedEntry<dyn CatalogProvider>>>,
 }
 
 impl LanceCatalogProviderList {
     pub async fn try_new(namespace: NamespaceLevel) -> Result<Self> {
-        let catalogs = DashMap::new();
-        for child_namespace in namespace.children().await? {
-            let catalog_name = child_namespace.name().to_string();
-            let catalog_provider = Arc::new(LanceCatalogProvider::try_new(child_namespace).await?);
-            catalogs.insert(catalog_name, catalog_provider as Arc<dyn CatalogProvider>);
-        }
+        Self::try_new_with_discovery(namespace, CatalogDiscovery::Eager).await
+    }
+
+    /// Create a catalog list that discovers catalogs as configured by `discovery`.
+    pub async fn try_new_with_discovery(
+        namespace: NamespaceLevel,
+        discovery: CatalogDiscovery,
+    ) -> Result<Self> {
+        Self::try_new_with_options(namespace, discovery, SchemaOptions::default()).await
+    }
 
-        Ok(Self {
+    /// Create a catalog list that discovers catalogs as configured by `discovery`,
+    /// whose schemas are created with `schema_options`.
+    pub async fn try_new_with_options(
+        namespace: NamespaceLevel,
+        discovery: CatalogDiscovery,
+        schema_options: SchemaOptions,
+    ) -> Result<Self> {
+        let list = Self {
             ns_level: namespace,
-            catalogs,
-        })
+            discovery,
+            schema_options,
+            catalogs: Arc::new(DashMap::new()),
+        };
+        if discovery == CatalogDiscovery::Eager {
+            list.load_catalogs().await?;
+        }
+        Ok(list)
+    }
+
+    /// Load the catalogs of all child namespaces, several at a time since each
+    /// catalog lists its own children.
+    async fn load_catalogs(&self) -> Result<()> {
+        let concurrency = self
+            .schema_options
+            .discovery_concurrency
+            .unwrap_or(DEFAULT_DISCOVERY_CONCURRENCY);
+        let loaded = stream::iter(self.ns_level.children().await?)
+            .map(|child_namespace| async move {
+                let catalog_name = child_namespace.name().to_string();
+                let catalog_provider = LanceCatalogProvider::try_new_with_options(
+                    child_namespace,
+                    self.discovery,
+                    self.schema_options.clone(),
+                )
+                .await;
+                (catalog_name, catalog_provider)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        for (catalog_name, catalog_provider) in loaded {
+            match catalog_provider {
+                Ok(catalog_provider) => {
+                    self.catalogs.insert(
+                        catalog_name,
+                        CachedEntry::discovered(Arc::new(catalog_provider), self.discovery),
+                    );
+                }
+                Err(err) if self.schema_options.discovery_errors == DiscoveryErrors::Lenient => {
+                    log::warn!("Skipping catalog {catalog_name}, it failed to load: {err}");
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop the catalogs discovered from the namespace.
+    ///
+    /// With eager discovery the child namespaces are listed again, with lazy
+    /// discovery catalogs are resolved again on their next use. Catalogs added
+    /// with [`CatalogProviderList::register_catalog`] are kept.
+    pub async fn refresh(&self) -> Result<()> {
+        self.catalogs.retain(|_, entry| !entry.discovered);
+        if self.discovery == CatalogDiscovery::Eager {
+            self.load_catalogs().await?;
+        }
+        Ok(())
     }
 }
 
@@ -59,17 +278,42 @@ impl CatalogProviderList for LanceCatalogProviderList {
 
     /// Adds a new catalog to this catalog list.
     /// If a catalog of the same name existed before, it is replaced in the list and returned.
+    ///
+    /// With [`SchemaOptions::write_through`] the child namespace of the catalog
+    /// and the namespaces of its schemas are created unless they exist. This
+    /// cannot fail, so errors creating them are logged.
     fn register_catalog(
         &self,
         name: String,
         catalog: Arc<dyn CatalogProvider>,
     ) -> Option<Arc<dyn CatalogProvider>> {
-        self.catalogs.insert(name, catalog)
+        if self.schema_options.write_through {
+            let ns_level = self.ns_level.child(&name);
+            let schema_names = catalog.schema_names();
+            let created = block_on("register_catalog", async {
+                ns_level.create_if_not_exists().await?;
+                for schema_name in schema_names {
+                    ns_level.child(&schema_name).create_if_not_exists().await?;
+                }
+                Ok::<_, lance::Error>(())
+            })
+            .and_then(|created| created.map_err(to_datafusion_error));
+            if let Err(err) = created {
+                log::error!("Failed to create the namespace of catalog {name}: {err}");
+            }
+        }
+        self.catalogs
+            .insert(name, CachedEntry::registered(catalog))
+            .map(|entry| entry.provider)
     }
 
+    /// The names of the loaded catalogs.
+    ///
+    /// With lazy discovery this only includes catalogs that have been used.
     fn catalog_names(&self) -> Vec<String> {
         self.catalogs
             .iter()
+            .filter(|entry| !entry.value().is_expired())
             .map(|entry| entry.key().clone())
             .collect::<HashSet<_>>()
             .into_iter()
@@ -77,9 +321,28 @@ impl CatalogProviderList for LanceCatalogProviderList {
     }
 
     fn catalog(&self, name: &str) -> Option<Arc<dyn CatalogProvider>> {
-        self.catalogs
-            .get(name)
-            .map(|entry| Arc::clone(entry.value()))
+        let name = &resolve_cached_name(&self.catalogs, &self.ns_level, name);
+        let cached = self.catalogs.get(name).map(|entry| entry.value().clone());
+        match (cached, self.discovery) {
+            (Some(entry), _) if !entry.is_expired() => Some(entry.provider),
+            (_, CatalogDiscovery::Lazy { .. }) => {
+                let ns_level = self.ns_level.child(name);
+                let provider = Arc::new(LanceCatalogProvider::new_lazy(
+                    ns_level.clone(),
+                    self.discovery,
+                    self.schema_options.clone(),
+                )) as Arc<dyn CatalogProvider>;
+                insert_lazy(
+                    &self.catalogs,
+                    name,
+                    ns_level,
+                    Arc::clone(&provider),
+                    self.discovery,
+                );
+                Some(provider)
+            }
+            (_, CatalogDiscovery::Eager) => None,
+        }
     }
 }
 
@@ -89,29 +352,88 @@ impl CatalogProviderList for LanceCatalogProviderList {
 /// The underlying namespace must be a three-level namespace. It is either explicitly
 /// registered via [`SessionBuilder::add_catalog`], or automatically created as part of
 /// the catalog hierarchy when [`SessionBuilder::with_root`] is used.
-/// Child namespaces are automatically loaded as [`LanceSchemaProvider`] instances.
+/// Child namespaces are loaded as [`LanceSchemaProvider`] instances, either when
+/// the catalog is created or on first use, see [`CatalogDiscovery`].
+/// [`Self::refresh`] drops the cached schemas.
+///
+/// With [`SchemaOptions::write_through`] registered schemas are created as
+/// child namespaces.
 #[derive(Debug, Clone)]
 pub struct LanceCatalogProvider {
-    #[allow(dead_code)]
     ns_level: NamespaceLevel,
-    /// Note: The values in this map may become stale over time, as there is currently
-    /// no mechanism to automatically refresh or invalidate cached schema providers.
-    schemas: DashMap<String, Arc<dyn SchemaProvider>>,
+    discovery: CatalogDiscovery,
+    /// Passed to the schemas, see [`LanceSchemaProvider::with_options`].
+    schema_options: SchemaOptions,
+    /// Note: With eager discovery the values in this map may become stale over time
+    /// until [`Self::refresh`] is called.
+    schemas: Arc<DashMap<String, CachedEntry<dyn SchemaProvider>>>,
 }
 
 impl LanceCatalogProvider {
     pub async fn try_new(namespace: NamespaceLevel) -> Result<Self> {
-        let schemas = DashMap::new();
-        for child_namespace in namespace.children().await? {
+        Self::try_new_with_discovery(namespace, CatalogDiscovery::Eager).await
+    }
+
+    /// Create a catalog that discovers schemas as configured by `discovery`.
+    pub async fn try_new_with_discovery(
+        namespace: NamespaceLevel,
+        discovery: CatalogDiscovery,
+    ) -> Result<Self> {
+        Self::try_new_with_options(namespace, discovery, SchemaOptions::default()).await
+    }
+
+    /// Create a catalog that discovers schemas as configured by `discovery`,
+    /// whose schemas are created with `schema_options`.
+    pub async fn try_new_with_options(
+        namespace: NamespaceLevel,
+        discovery: CatalogDiscovery,
+        schema_options: SchemaOptions,
+    ) -> Result<Self> {
+        let catalog = Self::new_lazy(namespace, discovery, schema_options);
+        if discovery == CatalogDiscovery::Eager {
+            catalog.load_schemas().await?;
+        }
+        Ok(catalog)
+    }
+
+    async fn load_schemas(&self) -> Result<()> {
+        for child_namespace in self.ns_level.children().await? {
             let schema_name = child_namespace.name().to_string();
-            let schema_provider = Arc::new(LanceSchemaProvider::try_new(child_namespace).await?);
-            schemas.insert(schema_name, schema_provider as Arc<dyn SchemaProvider>);
+            let schema_provider = Arc::new(
+                LanceSchemaProvider::new(child_namespace).with_options(self.schema_options.clone()),
+            );
+            self.schemas.insert(
+                schema_name,
+                CachedEntry::discovered(schema_provider, self.discovery),
+            );
         }
+        Ok(())
+    }
 
-        Ok(Self {
+    /// Drop the schemas discovered from the namespace.
+    ///
+    /// With eager discovery the child namespaces are listed again, with lazy
+    /// discovery schemas are resolved again on their next use. Schemas added
+    /// with [`CatalogProvider::register_schema`] are kept.
+    pub async fn refresh(&self) -> Result<()> {
+        self.schemas.retain(|_, entry| !entry.discovered);
+        if self.discovery == CatalogDiscovery::Eager {
+            self.load_schemas().await?;
+        }
+        Ok(())
+    }
+
+    fn new_lazy(
+        namespace: NamespaceLevel,
+        discovery: CatalogDiscovery,
+        schema_options: SchemaOptions,
+    ) -> Self {
+        Self {
             ns_level: namespace,
-            schemas,
-        })
+            discovery,
+            schema_options,
+            schemas: Arc::new(DashMap::new()),
+        }
     }
 }
 
@@ -123,6 +445,7 @@ impl CatalogProvider for LanceCatalogProvider {
     fn schema_names(&self) -> Vec<String> {
         self.schemas
             .iter()
+            .filter(|entry| !entry.value().is_expired())
             .map(|entry| entry.key().clone())
             .collect::<HashSet<_>>()
             .into_iter()
@@ -130,16 +453,47 @@ impl CatalogProvider for LanceCatalogProvider {
     }
 
     fn schema(&self, schema_name: &str) -> Option<Arc<dyn SchemaProvider>> {
-        self.schemas
+        let schema_name = &resolve_cached_name(&self.schemas, &self.ns_level, schema_name);
+        let cached = self
+            .schemas
             .get(schema_name)
-            .map(|entry| Arc::clone(entry.value()))
+            .map(|entry| entry.value().clone());
+        match (cached, self.discovery) {
+            (Some(entry), _) if !entry.is_expired() => Some(entry.provider),
+            (_, CatalogDiscovery::Lazy { .. }) => {
+                let ns_level = self.ns_level.child(schema_name);
+                let provider = Arc::new(
+                    LanceSchemaProvider::new(ns_level.clone())
+                        .with_options(self.schema_options.clone()),
+                ) as Arc<dyn SchemaProvider>;
+                insert_lazy(
+                    &self.schemas,
+                    schema_name,
+                    ns_level,
+                    Arc::clone(&provider),
+                    self.discovery,
+                );
+                Some(provider)
+            }
+            (_, CatalogDiscovery::Eager) => None,
+        }
     }
 
+    /// With [`SchemaOptions::write_through`] the child namespace of the schema
+    /// is created unless it exists.
     fn register_schema(
         &self,
         name: &str,
         schema: Arc<dyn SchemaProvider>,
     ) -> Result<Option<Arc<dyn SchemaProvider>>> {
-        Ok(self.schemas.insert(name.to_string(), schema))
+        if self.schema_options.write_through {
+            let ns_level = self.ns_level.child(name);
+            block_on("register_schema", ns_level.create_if_not_exists())?
+                .map_err(to_datafusion_error)?;
+        }
+        Ok(self
+            .schemas
+            .insert(name.to_string(), CachedEntry::registered(schema))
+            .map(|entry| entry.provider))
     }
 }
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Lance settings of a DataFusion session.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+
+use datafusion::common::config::{ConfigEntry, ConfigExtension, ExtensionOptions};
+use datafusion::common::config_err;
+use datafusion::error::Result;
+
+/// The `lance.*` settings of a session, registered by
+/// [`SessionBuilder`](crate::SessionBuilder).
+///
+/// `lance.storage.<key>` sets the storage option `<key>` used to open the
+/// datasets queried by URL, see [`LanceUrlTableFactory`](crate::LanceUrlTableFactory):
+///
+/// ```sql
+/// SET lance.storage.aws_region = 'us-east-1';
+/// SET lance.storage.aws_access_key_id = '...';
+/// SELECT * FROM 's3://bucket/orders.lance';
+/// ```
+///
+/// Other sessions can register the settings with
+/// [`SessionConfig::with_option_extension`](datafusion::prelude::SessionConfig::with_option_extension).
+#[derive(Debug, Clone, Default)]
+pub struct LanceConfig {
+    storage_options: BTreeMap<String, String>,
+}
+
+/// The key prefix of storage options, below the `lance` prefix.
+const STORAGE_PREFIX: &str = "storage.";
+
+impl LanceConfig {
+    /// The storage options set with `lance.storage.<key>`.
+    pub fn storage_options(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.storage_options.iter()
+    }
+}
+
+impl ConfigExtension for LanceConfig {
+    const PREFIX: &'static str = "lance";
+}
+
+impl ExtensionOptions for LanceConfig {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn cloned(&self) -> Box<dyn ExtensionOptions> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key.strip_prefix(STORAGE_PREFIX) {
+            Some(option) if !option.is_empty() => {
+                self.storage_options
+                    .insert(option.to_string(), value.to_string());
+                Ok(())
+            }
+            _ => config_err!("Unknown Lance setting lance.{key}"),
+        }
+    }
+
+    fn entries(&self) -> Vec<ConfigEntry> {
+        self.storage_options
+            .iter()
+            .map(|(option, value)| ConfigEntry {
+                key: format!("{STORAGE_PREFIX}{option}"),
+                value: Some(value.clone()),
+                description: "Storage option of datasets queried by URL",
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::prelude::SessionConfig;
+
+    use super::*;
+
+    #[test]
+    fn test_storage_options() {
+        let mut config = SessionConfig::new().with_option_extension(LanceConfig::default());
+        let options = config.options_mut();
+        options
+            .set("lance.storage.aws_region", "us-east-1")
+            .unwrap();
+        options.set("lance.storage.allow_http", "true").unwrap();
+        assert!(options.set("lance.storage.", "x").is_err());
+        assert!(options.set("lance.cache_size", "1").is_err());
+
+        let lance = config.options().extensions.get::<LanceConfig>().unwrap();
+        assert_eq!(
+            lance.storage_options().collect::<Vec<_>>(),
+            vec![
+                (&"allow_http".to_string(), &"true".to_string()),
+                (&"aws_region".to_string(), &"us-east-1".to_string()),
+            ]
+        );
+    }
+}
@@ -2,12 +2,22 @@
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
 pub mod catalog;
+pub mod config;
 pub mod error;
 pub mod namespace_level;
 pub mod schema;
 pub mod session_builder;
+pub mod sql;
+pub mod udtf;
+pub mod url_table;
 
-pub use catalog::{LanceCatalogProvider, LanceCatalogProviderList};
+pub use catalog::{
+    CatalogDiscovery, DiscoveryErrors, LanceCatalogProvider, LanceCatalogProviderList,
+};
+pub use config::LanceConfig;
 pub use namespace_level::NamespaceLevel;
-pub use schema::LanceSchemaProvider;
+pub use schema::{LanceSchemaProvider, SchemaOptions, SessionHandle, VERSIONS_TABLE_SUFFIX};
 pub use session_builder::SessionBuilder;
+pub use sql::LanceSqlExt;
+pub use udtf::{LanceFullTextSearchUDTF, LanceVectorSearchUDTF, LanceVersionUDTF};
+pub use url_table::LanceUrlTableFactory;
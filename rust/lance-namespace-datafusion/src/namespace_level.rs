@@ -1,12 +1,21 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 
+use datafusion::arrow::record_batch::RecordBatchReader;
 use lance::dataset::builder::DatasetBuilder;
+use lance::dataset::{WriteMode, WriteParams};
+use lance::session::Session;
 use lance::{Dataset, Result};
+use lance_core::ErrorCode;
 use lance_namespace::LanceNamespace;
-use lance_namespace::models::{ListNamespacesRequest, ListTablesRequest};
+use lance_namespace::models::{
+    CreateNamespaceRequest, DescribeNamespaceRequest, DropTableRequest, ListNamespacesRequest,
+    ListTablesRequest, NamespaceExistsRequest, TableExistsRequest,
+};
 
 const DEFAULT_NAMESPACE_NAME: &str = "lance";
 
@@ -16,6 +25,31 @@ pub struct NamespaceLevel {
     root: Arc<dyn LanceNamespace>,
     /// Full namespace identifier, e.g. [catalog, schema].
     namespace_id: Option<Vec<String>>,
+    /// How child namespaces and tables are listed.
+    listing: ListingOptions,
+    /// The Lance session tables are opened and written with.
+    session: Option<Arc<Session>>,
+    /// How the names of child namespaces and tables are resolved.
+    names: Arc<NameResolution>,
+}
+
+/// How [`NamespaceLevel`] lists child namespaces and tables.
+#[derive(Debug, Clone, Copy, Default)]
+struct ListingOptions {
+    /// The number of names requested per page, the namespace default if `None`.
+    page_size: Option<i32>,
+    /// The number of names after which listing stops.
+    max_results: Option<usize>,
+}
+
+/// How [`NamespaceLevel`] resolves the names of child namespaces and tables.
+#[derive(Debug, Clone, Default)]
+struct NameResolution {
+    /// Whether a name matches a child whose name differs only in ASCII case.
+    case_insensitive: bool,
+    /// The names of child namespaces and tables by the full identifier of
+    /// their alias.
+    aliases: HashMap<Vec<String>, String>,
 }
 
 impl From<Arc<dyn LanceNamespace>> for NamespaceLevel {
@@ -42,6 +76,9 @@ impl NamespaceLevel {
         Self {
             root,
             namespace_id: None,
+            listing: ListingOptions::default(),
+            session: None,
+            names: Arc::default(),
         }
     }
 
@@ -50,9 +87,130 @@ impl NamespaceLevel {
         Self {
             root,
             namespace_id: Some(namespace_id),
+            listing: ListingOptions::default(),
+            session: None,
+            names: Arc::default(),
         }
     }
 
+    /// Request `page_size` names per page when listing child namespaces and
+    /// tables. Child levels inherit the page size.
+    pub fn with_list_page_size(mut self, page_size: i32) -> Self {
+        self.listing.page_size = Some(page_size);
+        self
+    }
+
+    /// Stop listing child namespaces and tables after `max_results` names, so a
+    /// very large namespace cannot exhaust memory. Child levels inherit the limit.
+    pub fn with_max_list_results(mut self, max_results: usize) -> Self {
+        self.listing.max_results = Some(max_results);
+        self
+    }
+
+    /// Open and write tables with the given Lance session, so they share its
+    /// caches and object stores. Child levels inherit the session.
+    pub fn with_session(mut self, session: Arc<Session>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Match the names of child namespaces and tables regardless of ASCII
+    /// case, for clients such as BI tools that upper-case identifiers. An exact
+    /// match takes precedence, and a name that matches several children only
+    /// regardless of case matches none of them. Child levels inherit the setting.
+    pub fn with_case_insensitive_names(mut self, case_insensitive: bool) -> Self {
+        Arc::make_mut(&mut self.names).case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Resolve the child namespace or table with the full identifier `alias`
+    /// to its sibling `name`, e.g. `["retail", "sales", "ORDERS_V2"]` to
+    /// `orders`. With case-insensitive names the alias matches regardless of
+    /// case too. Child levels inherit the aliases.
+    pub fn with_alias(mut self, alias: Vec<String>, name: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.names)
+            .aliases
+            .insert(alias, name.into());
+        self
+    }
+
+    /// Whether names of child namespaces and tables match regardless of case.
+    pub fn has_case_insensitive_names(&self) -> bool {
+        self.names.case_insensitive
+    }
+
+    /// The name of the child namespace or table `name` is an alias of, `name`
+    /// itself if it is no alias.
+    pub fn unalias<'a>(&'a self, name: &'a str) -> &'a str {
+        let id = self.child_id(name.to_string());
+        let aliases = &self.names.aliases;
+        aliases
+            .get(&id)
+            .or_else(|| {
+                if !self.names.case_insensitive {
+                    return None;
+                }
+                aliases
+                    .iter()
+                    .find(|(alias, _)| {
+                        alias.len() == id.len()
+                            && alias
+                                .iter()
+                                .zip(&id)
+                                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+                    })
+                    .map(|(_, name)| name)
+            })
+            .map_or(name, String::as_str)
+    }
+
+    /// The one of the child names `names` that `name` refers to, following
+    /// aliases and matching regardless of case if configured.
+    pub fn resolve_name<I>(&self, name: &str, names: I) -> Option<I::Item>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.match_name(self.unalias(name), names)
+    }
+
+    /// The one of `names` that equals `name`, or with case-insensitive names
+    /// the only one that differs from it in case.
+    fn match_name<I>(&self, name: &str, names: I) -> Option<I::Item>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut case_insensitive_matches = Vec::new();
+        for candidate in names {
+            if candidate.as_ref() == name {
+                return Some(candidate);
+            }
+            if self.names.case_insensitive && candidate.as_ref().eq_ignore_ascii_case(name) {
+                case_insensitive_matches.push(candidate);
+            }
+        }
+        if case_insensitive_matches.len() == 1 {
+            case_insensitive_matches.pop()
+        } else {
+            None
+        }
+    }
+
+    /// The name of the table `table_name` refers to, see [`Self::resolve_name`].
+    /// The tables are only listed if names are matched regardless of case and
+    /// no table has exactly the given name.
+    pub async fn resolve_table_name(&self, table_name: &str) -> Result<String> {
+        let name = self.unalias(table_name);
+        if !self.names.case_insensitive || self.table_exists(name).await? {
+            return Ok(name.to_string());
+        }
+        let tables = self.tables().await?;
+        Ok(self
+            .match_name(name, tables)
+            .unwrap_or_else(|| name.to_string()))
+    }
+
     /// Return the full namespace identifier.
     pub fn id(&self) -> Vec<String> {
         self.namespace_id.clone().unwrap_or_default()
@@ -68,6 +226,17 @@ impl NamespaceLevel {
             })
     }
 
+    /// The child namespace with the given name, without checking that it exists.
+    pub fn child(&self, child_name: &str) -> Self {
+        Self {
+            root: Arc::clone(&self.root),
+            namespace_id: Some(self.child_id(child_name.to_string())),
+            listing: self.listing,
+            session: self.session.clone(),
+            names: Arc::clone(&self.names),
+        }
+    }
+
     fn child_id(&self, child_name: String) -> Vec<String> {
         match &self.namespace_id {
             Some(namespace_id) => {
@@ -79,49 +248,217 @@ impl NamespaceLevel {
         }
     }
 
-    /// List direct child namespaces.
+    /// List direct child namespaces, following page tokens until the namespace
+    /// has no more pages or the configured maximum is reached.
     pub async fn children(&self) -> Result<Vec<Self>> {
-        let root = Arc::clone(&self.root);
-        let namespace_id = self.namespace_id.clone().unwrap_or_default();
-        let request = ListNamespacesRequest {
-            id: Some(namespace_id.clone()),
-            page_token: None,
-            limit: None,
+        let namespace_id = self.id();
+        let namespaces = self
+            .list_pages("namespaces", |page_token, limit| {
+                let request = ListNamespacesRequest {
+                    id: Some(namespace_id.clone()),
+                    page_token,
+                    limit,
+                    ..Default::default()
+                };
+                async move {
+                    let response = self.root.list_namespaces(request).await?;
+                    Ok((response.namespaces, response.page_token))
+                }
+            })
+            .await?;
+
+        Ok(namespaces
+            .iter()
+            .map(|relative_ns_id| self.child(relative_ns_id))
+            .collect())
+    }
+
+    /// Collect the names of a paginated listing.
+    async fn list_pages<F, Fut>(&self, kind: &str, mut list_page: F) -> Result<Vec<String>>
+    where
+        F: FnMut(Option<String>, Option<i32>) -> Fut,
+        Fut: Future<Output = Result<(Vec<String>, Option<String>)>>,
+    {
+        let mut names = Vec::new();
+        let mut page_token = None;
+        loop {
+            let (page, next_page_token) = list_page(page_token, self.listing.page_size).await?;
+            names.extend(page);
+            if let Some(max_results) = self.listing.max_results
+                && names.len() >= max_results
+            {
+                if names.len() > max_results || next_page_token.is_some() {
+                    log::warn!(
+                        "Listing {} of namespace {:?} stopped after {} results",
+                        kind,
+                        self.id(),
+                        max_results
+                    );
+                }
+                names.truncate(max_results);
+                return Ok(names);
+            }
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(names),
+            }
+        }
+    }
+
+    /// Check whether this namespace exists. The root namespace always exists.
+    pub async fn exists(&self) -> Result<bool> {
+        let Some(namespace_id) = self.namespace_id.clone() else {
+            return Ok(true);
+        };
+        let request = NamespaceExistsRequest {
+            id: Some(namespace_id),
             ..Default::default()
         };
+        match self.root.namespace_exists(request).await {
+            Ok(()) => Ok(true),
+            Err(err) if err.code() == ErrorCode::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
 
-        let namespaces = root.list_namespaces(request).await?.namespaces;
+    /// Create this namespace unless it exists. The root namespace always exists.
+    pub async fn create_if_not_exists(&self) -> Result<()> {
+        if self.exists().await? {
+            return Ok(());
+        }
+        let request = CreateNamespaceRequest {
+            id: Some(self.id()),
+            mode: Some("ExistOk".to_string()),
+            ..Default::default()
+        };
+        match self.root.create_namespace(request).await {
+            Ok(_) => Ok(()),
+            Err(err) if err.code() == ErrorCode::AlreadyExists => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
 
-        Ok(namespaces
-            .into_iter()
-            .map(|relative_ns_id| {
-                Self::from_namespace(Arc::clone(&self.root), self.child_id(relative_ns_id))
-            })
-            .collect())
+    /// The properties stored on this namespace, empty if the namespace does not
+    /// support properties.
+    pub async fn properties(&self) -> Result<HashMap<String, String>> {
+        let request = DescribeNamespaceRequest {
+            id: Some(self.id()),
+            ..Default::default()
+        };
+        match self.root.describe_namespace(request).await {
+            Ok(response) => Ok(response.properties.unwrap_or_default()),
+            Err(err) if err.code() == ErrorCode::NotSupported => Ok(HashMap::new()),
+            Err(err) => Err(err),
+        }
     }
 
-    /// List table names under this namespace.
+    /// List table names under this namespace, following page tokens until the
+    /// namespace has no more pages or the configured maximum is reached.
     pub async fn tables(&self) -> Result<Vec<String>> {
-        let root = Arc::clone(&self.root);
-        let namespace_id = self.namespace_id.clone().unwrap_or_default();
-        let request = ListTablesRequest {
-            id: Some(namespace_id),
-            page_token: None,
-            limit: None,
+        let namespace_id = self.id();
+        self.list_pages("tables", |page_token, limit| {
+            let request = ListTablesRequest {
+                id: Some(namespace_id.clone()),
+                page_token,
+                limit,
+                ..Default::default()
+            };
+            async move {
+                let response = self.root.list_tables(request).await?;
+                Ok((response.tables, response.page_token))
+            }
+        })
+        .await
+    }
+
+    /// Check whether a table with the given name exists in this namespace.
+    pub async fn table_exists(&self, table_name: &str) -> Result<bool> {
+        let request = TableExistsRequest {
+            id: Some(self.child_id(table_name.to_string())),
             ..Default::default()
         };
+        match self.root.table_exists(request).await {
+            Ok(()) => Ok(true),
+            Err(err) if err.code() == ErrorCode::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
 
-        root.list_tables(request).await.map(|resp| resp.tables)
+    /// Create a table in this namespace and write the given batches to it.
+    pub async fn create_table(
+        &self,
+        table_name: &str,
+        batches: impl RecordBatchReader + Send + 'static,
+    ) -> Result<Dataset> {
+        Dataset::write_into_namespace(
+            batches,
+            Arc::clone(&self.root),
+            self.child_id(table_name.to_string()),
+            Some(WriteParams {
+                mode: WriteMode::Create,
+                session: self.session.clone(),
+                ..Default::default()
+            }),
+        )
+        .await
+    }
+
+    /// Replace the data of a table in this namespace with the given batches.
+    ///
+    /// The new data is committed as a new version of the table, so readers see
+    /// either the old or the new rows, and the old rows stay readable while the
+    /// batches are written.
+    pub async fn overwrite_table(
+        &self,
+        table_name: &str,
+        batches: impl RecordBatchReader + Send + 'static,
+    ) -> Result<Dataset> {
+        Dataset::write_into_namespace(
+            batches,
+            Arc::clone(&self.root),
+            self.child_id(table_name.to_string()),
+            Some(WriteParams {
+                mode: WriteMode::Overwrite,
+                session: self.session.clone(),
+                ..Default::default()
+            }),
+        )
+        .await
+    }
+
+    /// Drop a table from this namespace, including its data.
+    pub async fn drop_table(&self, table_name: &str) -> Result<()> {
+        let request = DropTableRequest {
+            id: Some(self.child_id(table_name.to_string())),
+            ..Default::default()
+        };
+        self.root.drop_table(request).await?;
+        Ok(())
     }
 
     /// Load a Lance dataset for the given table name in this namespace.
     pub async fn load_dataset(&self, table_name: &str) -> Result<Dataset> {
-        DatasetBuilder::from_namespace(
+        self.dataset_builder(table_name).await?.load().await
+    }
+
+    /// Load the given version of a Lance dataset in this namespace.
+    pub async fn load_dataset_version(&self, table_name: &str, version: u64) -> Result<Dataset> {
+        self.dataset_builder(table_name)
+            .await?
+            .with_version(version)
+            .load()
+            .await
+    }
+
+    async fn dataset_builder(&self, table_name: &str) -> Result<DatasetBuilder> {
+        let builder = DatasetBuilder::from_namespace(
             Arc::clone(&self.root),
             self.child_id(table_name.to_string()),
         )
-        .await?
-        .load()
-        .await
+        .await?;
+        Ok(match &self.session {
+            Some(session) => builder.with_session(Arc::clone(session)),
+            None => builder,
+        })
     }
 }
@@ -2,49 +2,470 @@
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
 use std::any::Any;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use dashmap::DashMap;
-use datafusion::catalog::SchemaProvider;
-use datafusion::datasource::TableProvider;
-use datafusion::error::Result;
+use datafusion::arrow::record_batch::RecordBatchReader;
+use datafusion::catalog::{SchemaProvider, UrlTableFactory};
+use datafusion::common::{exec_err, plan_datafusion_err};
+use datafusion::datasource::{MemTable, TableProvider, ViewTable};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::SessionState;
+use datafusion::execution::context::SessionContext;
+use datafusion::physical_plan::execute_stream;
 
+use crate::catalog::DiscoveryErrors;
 use crate::error::to_datafusion_error;
 use crate::namespace_level::NamespaceLevel;
+use crate::sql::StreamReader;
+use crate::udtf::block_on;
+use crate::url_table::LanceUrlTableFactory;
 use lance::datafusion::LanceTableProvider;
 
 /// A dynamic [`SchemaProvider`] backed directly by a [`NamespaceLevel`].
 ///
 /// Exposes Lance tables in the namespace as [`LanceTableProvider`] instances,
 /// loaded on demand and cached by table name.
+///
+/// Tables are created, replaced and dropped in the namespace with
+/// [`Self::create_table`], [`Self::replace_table`] and [`Self::drop_table`], which
+/// back `CREATE [OR REPLACE] TABLE` and `DROP TABLE` in
+/// [`LanceSqlExt::lance_sql`](crate::LanceSqlExt::lance_sql).
+///
+/// The tables can also be written with `INSERT INTO` and `INSERT OVERWRITE`.
+/// Datasets are loaded through the namespace, so the new version is committed
+/// with the namespace's commit handler. A cached table is reloaded once a
+/// newer version exists, so later queries see the inserted rows.
+///
+/// [`SchemaProvider::table_names`] and [`SchemaProvider::table_exist`] are
+/// synchronous and cannot list the namespace themselves. They answer from the
+/// loaded tables and the last table listing, which is taken by [`Self::refresh`]
+/// and, if a TTL is set with [`Self::with_table_list_ttl`], retaken in the
+/// background once it is older than the TTL.
+///
+/// SQL views are stored as namespace properties named `view.<name>`, whose value
+/// is the query of the view:
+///
+/// ```text
+/// view.big_orders = SELECT * FROM retail.sales.orders WHERE amount > 100
+/// ```
+///
+/// They are read when the schema first looks up a table that is not loaded, and
+/// planned as [`ViewTable`]s in the session set with [`SchemaOptions::session`],
+/// so their queries can refer to any table of the session. Tables take
+/// precedence over views of the same name.
+///
+/// Tables with a version pinned in [`SchemaOptions::pinned_versions`] are
+/// loaded at that version and never reloaded for newer versions, so queries
+/// in the session read the same data however the table changes.
+///
+/// Other tables are checked for a newer version on every lookup. With
+/// [`SchemaOptions::max_staleness`] a table is only checked again once its last
+/// check is older than the window, which saves a metadata request per query at
+/// the cost of seeing versions committed elsewhere up to the window late.
+///
+/// In a session with `datafusion.execution.collect_statistics` enabled, the
+/// default, tables are loaded with their row counts and column sizes so the
+/// optimizer can order joins, see
+/// [`LanceTableProviderBuilder::with_statistics`](lance::datafusion::LanceTableProviderBuilder::with_statistics).
+///
+/// Tables registered with [`SchemaProvider::register_table`], for example by
+/// `SessionContext::register_table`, are written to the namespace.
+///
+/// With [`SchemaOptions::url_tables`], table names that are Lance dataset URLs
+/// are read from the dataset at the URL instead of the namespace.
+///
+/// The versions of a table, including those removed by cleanup that the
+/// version archive keeps, are read from the system table named after the table
+/// with a `$versions` suffix, see
+/// [`Dataset::versions_as_batches`](lance::Dataset::versions_as_batches):
+///
+/// ```text
+/// SELECT version, timestamp_millis FROM "orders$versions" WHERE operation_type = 'Append'
+/// ```
+///
+/// Table names follow the aliases of the namespace and, with
+/// [`NamespaceLevel::with_case_insensitive_names`], match tables whose names
+/// differ only in case.
 #[derive(Debug, Clone)]
 pub struct LanceSchemaProvider {
     ns_level: NamespaceLevel,
-    tables: DashMap<String, Arc<LanceTableProvider>>,
+    tables: DashMap<String, CachedTable>,
+    /// The table names last listed from the namespace.
+    listed_tables: Arc<Mutex<ListedTables>>,
+    /// The queries of the views of the namespace by view name, `None` until
+    /// they are read.
+    views: Arc<Mutex<Option<HashMap<String, String>>>>,
+    options: SchemaOptions,
+}
+
+/// The namespace property prefix of view definitions.
+const VIEW_PROPERTY_PREFIX: &str = "view.";
+
+/// The suffix of the table names that read the versions of a table.
+pub const VERSIONS_TABLE_SUFFIX: &str = "$versions";
+
+/// Options for [`LanceSchemaProvider`]s, passed down from
+/// [`SessionBuilder`](crate::SessionBuilder) through the catalogs.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaOptions {
+    /// How long a table listing is used before it is retaken in the background,
+    /// see [`LanceSchemaProvider::with_table_list_ttl`].
+    pub table_list_ttl: Option<Duration>,
+    /// The session views are planned in. Without a session, views cannot be used.
+    pub session: Option<SessionHandle>,
+    /// Versions tables are read at, by table id, the namespace id followed by
+    /// the table name.
+    pub pinned_versions: HashMap<Vec<String>, u64>,
+    /// How long a loaded table is used before checking for a newer version
+    /// again, `None` to check on every lookup.
+    pub max_staleness: Option<Duration>,
+    /// Whether catalogs and schemas registered in the Lance catalogs are
+    /// created in the namespace, see [`LanceCatalogProvider`](crate::LanceCatalogProvider).
+    pub write_through: bool,
+    /// The number of child namespaces eager discovery loads at a time,
+    /// [`DEFAULT_DISCOVERY_CONCURRENCY`](crate::catalog::DEFAULT_DISCOVERY_CONCURRENCY)
+    /// if `None`.
+    pub discovery_concurrency: Option<usize>,
+    /// How eager discovery handles child namespaces that fail to load.
+    pub discovery_errors: DiscoveryErrors,
+    /// Resolves table names that are Lance dataset URLs, `None` to only
+    /// resolve the tables of the namespace.
+    pub url_tables: Option<LanceUrlTableFactory>,
+}
+
+/// A loaded table and when it was last checked against the latest version.
+#[derive(Debug, Clone)]
+struct CachedTable {
+    provider: Arc<LanceTableProvider>,
+    checked_at: Instant,
+}
+
+/// A handle to the state of the session a schema is registered in.
+///
+/// The handle does not keep the session alive, so it can be stored in the
+/// session's own catalogs.
+#[derive(Clone)]
+pub struct SessionHandle(Arc<dyn Fn() -> Option<SessionState> + Send + Sync>);
+
+impl SessionHandle {
+    /// A handle to the current state of `ctx`.
+    pub fn new(ctx: &SessionContext) -> Self {
+        let state = ctx.state_weak_ref();
+        Self(Arc::new(move || {
+            state.upgrade().map(|state| state.read().clone())
+        }))
+    }
+
+    /// The current state of the session, `None` once the session is dropped.
+    pub(crate) fn state(&self) -> Option<SessionState> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for SessionHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionHandle").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Default)]
+struct ListedTables {
+    names: HashSet<String>,
+    /// When the listing was last started, `None` if the tables were never listed.
+    listed_at: Option<Instant>,
 }
 
 impl LanceSchemaProvider {
     pub async fn try_new(namespace: NamespaceLevel) -> Result<Self> {
-        Ok(Self {
+        Ok(Self::new(namespace))
+    }
+
+    /// Create a schema provider for the namespace, tables are loaded on first use.
+    pub fn new(namespace: NamespaceLevel) -> Self {
+        Self {
             ns_level: namespace,
             tables: DashMap::new(),
-        })
+            listed_tables: Arc::new(Mutex::new(ListedTables::default())),
+            views: Arc::new(Mutex::new(None)),
+            options: SchemaOptions::default(),
+        }
+    }
+
+    /// Configure the provider with the given options.
+    pub fn with_options(mut self, options: SchemaOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// List the tables of the namespace in the background whenever the last
+    /// listing is older than `ttl`, so tables created elsewhere show up in
+    /// [`SchemaProvider::table_names`] without an explicit [`Self::refresh`].
+    pub fn with_table_list_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.options.table_list_ttl = ttl;
+        self
+    }
+
+    /// List the tables of the namespace again and drop all loaded tables and
+    /// views, so they are reloaded on their next use.
+    pub async fn refresh(&self) -> Result<()> {
+        self.listed_tables.lock().unwrap().listed_at = Some(Instant::now());
+        let names = self.ns_level.tables().await.map_err(to_datafusion_error)?;
+        self.listed_tables.lock().unwrap().names = names.into_iter().collect();
+        self.tables.clear();
+        *self.views.lock().unwrap() = None;
+        Ok(())
+    }
+
+    /// Drop the loaded table with the given name, so it is reloaded on its next use.
+    pub fn invalidate(&self, table_name: &str) {
+        self.tables.remove(table_name);
+    }
+
+    /// Retake the table listing in the background if it is older than the TTL.
+    fn maybe_refresh_table_names(&self) {
+        let Some(ttl) = self.options.table_list_ttl else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        {
+            let mut listed = self.listed_tables.lock().unwrap();
+            if listed
+                .listed_at
+                .is_some_and(|listed_at| listed_at.elapsed() < ttl)
+            {
+                return;
+            }
+            // Mark the listing as taken so concurrent calls don't list again.
+            listed.listed_at = Some(Instant::now());
+        }
+        let ns_level = self.ns_level.clone();
+        let listed_tables = Arc::clone(&self.listed_tables);
+        handle.spawn(async move {
+            if let Ok(names) = ns_level.tables().await {
+                listed_tables.lock().unwrap().names = names.into_iter().collect();
+            }
+        });
+    }
+
+    /// Create a table in the namespace and write the given batches to it.
+    ///
+    /// Fails if a table with the same name already exists.
+    pub async fn create_table(
+        &self,
+        table_name: &str,
+        batches: impl RecordBatchReader + Send + 'static,
+    ) -> Result<Arc<dyn TableProvider>> {
+        self.ns_level
+            .create_table(table_name, batches)
+            .await
+            .map_err(to_datafusion_error)?;
+        self.tables.remove(table_name);
+        self.listed_tables
+            .lock()
+            .unwrap()
+            .names
+            .insert(table_name.to_string());
+        self.load_and_cache_table(table_name)
+            .await?
+            .ok_or_else(|| DataFusionError::Internal(format!("Table {table_name} not found")))
+    }
+
+    /// Replace the data of a table in the namespace with the given batches.
+    ///
+    /// The replacement is committed as a new version of the table, the schema of
+    /// the batches may differ from the schema of the table.
+    pub async fn replace_table(
+        &self,
+        table_name: &str,
+        batches: impl RecordBatchReader + Send + 'static,
+    ) -> Result<Arc<dyn TableProvider>> {
+        self.ns_level
+            .overwrite_table(table_name, batches)
+            .await
+            .map_err(to_datafusion_error)?;
+        self.tables.remove(table_name);
+        self.load_and_cache_table(table_name)
+            .await?
+            .ok_or_else(|| DataFusionError::Internal(format!("Table {table_name} not found")))
+    }
+
+    /// Drop a table from the namespace, including its data.
+    pub async fn drop_table(&self, table_name: &str) -> Result<()> {
+        self.tables.remove(table_name);
+        self.listed_tables.lock().unwrap().names.remove(table_name);
+        self.ns_level
+            .drop_table(table_name)
+            .await
+            .map_err(to_datafusion_error)
+    }
+
+    /// Check whether a table exists in the namespace, whether or not it was loaded.
+    pub async fn namespace_table_exists(&self, table_name: &str) -> Result<bool> {
+        self.ns_level
+            .table_exists(table_name)
+            .await
+            .map_err(to_datafusion_error)
+    }
+
+    /// The name `table_name` refers to among the loaded and listed tables, see
+    /// [`NamespaceLevel::resolve_name`]. Names that match none of them are
+    /// resolved against the namespace when the table is loaded.
+    fn resolve_cached_name(&self, table_name: &str) -> String {
+        let name = self.ns_level.unalias(table_name);
+        if self.tables.contains_key(name) || !self.ns_level.has_case_insensitive_names() {
+            return name.to_string();
+        }
+        let mut names = self.listed_tables.lock().unwrap().names.clone();
+        names.extend(self.tables.iter().map(|entry| entry.key().clone()));
+        self.ns_level
+            .resolve_name(name, names)
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// The version the table is pinned to, if any.
+    fn pinned_version(&self, table_name: &str) -> Option<u64> {
+        if self.options.pinned_versions.is_empty() {
+            return None;
+        }
+        let mut table_id = self.ns_level.id();
+        table_id.push(table_name.to_string());
+        self.options.pinned_versions.get(&table_id).copied()
+    }
+
+    /// Load the given version of a table.
+    ///
+    /// Earlier versions never change, so the provider is not cached and is not
+    /// affected by [`Self::invalidate`] or [`Self::refresh`].
+    pub async fn table_at_version(
+        &self,
+        table_name: &str,
+        version: u64,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let dataset = self
+            .ns_level
+            .load_dataset_version(table_name, version)
+            .await
+            .map_err(to_datafusion_error)?;
+        let table_provider = LanceTableProvider::builder(Arc::new(dataset))
+            .with_statistics(self.collect_statistics())
+            .build()
+            .await
+            .map_err(to_datafusion_error)?;
+        Ok(Arc::new(table_provider))
+    }
+
+    /// Whether loaded tables report statistics to the optimizer, following the
+    /// `datafusion.execution.collect_statistics` setting of the session.
+    fn collect_statistics(&self) -> bool {
+        self.options
+            .session
+            .as_ref()
+            .and_then(SessionHandle::state)
+            .is_some_and(|state| state.config().collect_statistics())
+    }
+
+    /// The queries of the views of the namespace, read from its properties on
+    /// first use.
+    async fn views(&self) -> Result<HashMap<String, String>> {
+        if let Some(views) = self.views.lock().unwrap().as_ref() {
+            return Ok(views.clone());
+        }
+        let views = self
+            .ns_level
+            .properties()
+            .await
+            .map_err(to_datafusion_error)?
+            .into_iter()
+            .filter_map(|(key, query)| {
+                key.strip_prefix(VIEW_PROPERTY_PREFIX)
+                    .map(|name| (name.to_string(), query))
+            })
+            .collect::<HashMap<_, _>>();
+        *self.views.lock().unwrap() = Some(views.clone());
+        Ok(views)
+    }
+
+    /// Plan the view with the given name, if there is one.
+    async fn view(&self, name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        let Some(query) = self.views().await?.remove(name) else {
+            return Ok(None);
+        };
+        let state = self
+            .options
+            .session
+            .as_ref()
+            .and_then(SessionHandle::state)
+            .ok_or_else(|| {
+                plan_datafusion_err!(
+                    "View {name} can only be used in a session built with SessionBuilder"
+                )
+            })?;
+        let plan = state.create_logical_plan(&query).await?;
+        Ok(Some(Arc::new(ViewTable::new(plan, Some(query)))))
+    }
+
+    /// The versions of a table as a table, read when the table is looked up so
+    /// that it lists the latest versions.
+    async fn versions_table(&self, table_name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        let table_name = self.resolve_cached_name(table_name);
+        let table_name = self
+            .ns_level
+            .resolve_table_name(&table_name)
+            .await
+            .map_err(to_datafusion_error)?;
+        let dataset = self
+            .ns_level
+            .load_dataset(&table_name)
+            .await
+            .map_err(to_datafusion_error)?;
+        let batches = dataset
+            .versions_as_batches()
+            .await
+            .map_err(to_datafusion_error)?;
+        let schema = batches[0].schema();
+        Ok(Some(Arc::new(MemTable::try_new(schema, vec![batches])?)))
     }
 
     async fn load_and_cache_table(
         &self,
         table_name: &str,
     ) -> Result<Option<Arc<dyn TableProvider>>> {
-        let dataset = self
+        let table_name = &self
             .ns_level
-            .load_dataset(table_name)
+            .resolve_table_name(table_name)
             .await
             .map_err(to_datafusion_error)?;
+        let dataset = match self.pinned_version(table_name) {
+            Some(version) => {
+                self.ns_level
+                    .load_dataset_version(table_name, version)
+                    .await
+            }
+            None => self.ns_level.load_dataset(table_name).await,
+        }
+        .map_err(to_datafusion_error)?;
         let dataset = Arc::new(dataset);
-        let table_provider = Arc::new(LanceTableProvider::new(dataset, false, false));
-        self.tables
-            .insert(table_name.to_string(), Arc::clone(&table_provider));
+        let table_provider = Arc::new(
+            LanceTableProvider::builder(dataset)
+                .with_statistics(self.collect_statistics())
+                .build()
+                .await
+                .map_err(to_datafusion_error)?,
+        );
+        self.tables.insert(
+            table_name.to_string(),
+            CachedTable {
+                provider: Arc::clone(&table_provider),
+                checked_at: Instant::now(),
+            },
+        );
         Ok(Some(table_provider as Arc<dyn TableProvider>))
     }
 }
@@ -55,15 +476,46 @@ impl SchemaProvider for LanceSchemaProvider {
         self
     }
 
+    /// The names of the loaded tables, of the tables in the last listing and of
+    /// the views, once they are read.
     fn table_names(&self) -> Vec<String> {
-        self.tables
-            .iter()
-            .map(|entry| entry.key().clone())
-            .collect()
+        self.maybe_refresh_table_names();
+        let mut names = self.listed_tables.lock().unwrap().names.clone();
+        names.extend(self.tables.iter().map(|entry| entry.key().clone()));
+        if let Some(views) = self.views.lock().unwrap().as_ref() {
+            names.extend(views.keys().cloned());
+        }
+        names.into_iter().collect()
     }
 
     async fn table(&self, table_name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
-        if let Some(existing) = self.tables.get(table_name) {
+        if let Some(url_tables) = &self.options.url_tables
+            && let Some(table) = url_tables.try_new(table_name).await?
+        {
+            return Ok(Some(table));
+        }
+        if let Some(versions_of) = table_name.strip_suffix(VERSIONS_TABLE_SUFFIX) {
+            return self.versions_table(versions_of).await;
+        }
+        let table_name = &self.resolve_cached_name(table_name);
+        // Clone the cached provider out of the map so no shard lock is held
+        // across the awaits below or the removal of a stale entry.
+        let cached = self
+            .tables
+            .get(table_name)
+            .map(|entry| entry.value().clone());
+        if let Some(CachedTable {
+            provider: existing,
+            checked_at,
+        }) = cached
+        {
+            let recently_checked = self
+                .options
+                .max_staleness
+                .is_some_and(|max_staleness| checked_at.elapsed() < max_staleness);
+            if recently_checked || self.pinned_version(table_name).is_some() {
+                return Ok(Some(existing as Arc<dyn TableProvider>));
+            }
             // Reuse cached provider when still fresh; otherwise reload.
             let ds = existing.dataset();
             let latest = ds.latest_version_id().await.map_err(to_datafusion_error)?;
@@ -72,14 +524,77 @@ impl SchemaProvider for LanceSchemaProvider {
                 self.tables.remove(table_name);
                 self.load_and_cache_table(table_name).await
             } else {
-                Ok(Some(Arc::clone(existing.value()) as Arc<dyn TableProvider>))
+                if let Some(mut entry) = self.tables.get_mut(table_name)
+                    && Arc::ptr_eq(&entry.provider, &existing)
+                {
+                    entry.checked_at = Instant::now();
+                }
+                Ok(Some(existing as Arc<dyn TableProvider>))
             }
+        } else if !self
+            .listed_tables
+            .lock()
+            .unwrap()
+            .names
+            .contains(table_name)
+            && let Some(view) = self.view(table_name).await?
+        {
+            Ok(Some(view))
         } else {
             self.load_and_cache_table(table_name).await
         }
     }
 
     fn table_exist(&self, name: &str) -> bool {
+        if let Some(versions_of) = name.strip_suffix(VERSIONS_TABLE_SUFFIX) {
+            return self.table_exist(versions_of);
+        }
+        self.maybe_refresh_table_names();
+        let name = &self.resolve_cached_name(name);
         self.tables.contains_key(name)
+            || self.listed_tables.lock().unwrap().names.contains(name)
+            || self
+                .views
+                .lock()
+                .unwrap()
+                .as_ref()
+                .is_some_and(|views| views.contains_key(name))
+    }
+
+    /// Write the rows of `table` to a new table of the namespace, like
+    /// `CREATE TABLE name AS SELECT * FROM table`, so the registration
+    /// persists. Fails if the namespace already has a table of that name.
+    ///
+    /// DataFusion registers tables synchronously, so the rows are written by
+    /// blocking the calling thread, which requires a multi-threaded Tokio runtime.
+    fn register_table(
+        &self,
+        name: String,
+        table: Arc<dyn TableProvider>,
+    ) -> Result<Option<Arc<dyn TableProvider>>> {
+        block_on("register_table", async {
+            if self.namespace_table_exists(&name).await? {
+                return exec_err!("The table {name} already exists");
+            }
+            let state = self
+                .options
+                .session
+                .as_ref()
+                .and_then(SessionHandle::state)
+                .unwrap_or_else(|| SessionContext::new().state());
+            let plan = table.scan(&state, None, &[], None).await?;
+            let stream = execute_stream(plan, state.task_ctx())?;
+            self.create_table(&name, StreamReader::new(stream)).await?;
+            Ok(None)
+        })?
+    }
+
+    // Dropping the table from the namespace would delete its data, `DROP TABLE`
+    // goes through `LanceSqlExt::lance_sql`.
+
+    fn deregister_table(&self, name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        Err(DataFusionError::NotImplemented(format!(
+            "Cannot deregister table {name} from a Lance namespace, use LanceSqlExt::lance_sql to run DROP TABLE"
+        )))
     }
 }
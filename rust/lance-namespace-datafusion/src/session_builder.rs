@@ -2,13 +2,26 @@
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
 use datafusion::catalog::{CatalogProvider, SchemaProvider};
+use datafusion::common::TableReference;
 use datafusion::error::Result;
+use datafusion::execution::SessionStateBuilder;
 use datafusion::execution::context::{SessionConfig, SessionContext};
+use datafusion::execution::runtime_env::RuntimeEnv;
+use lance::dataset::{DEFAULT_INDEX_CACHE_SIZE, DEFAULT_METADATA_CACHE_SIZE};
+use lance::io::ObjectStoreRegistry;
+use lance::session::Session;
+use lance_geo::join::SpatialJoinRule;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::LanceCatalogProvider;
-use crate::catalog::LanceCatalogProviderList;
+use crate::catalog::{CatalogDiscovery, DiscoveryErrors, LanceCatalogProviderList};
+use crate::config::LanceConfig;
 use crate::namespace_level::NamespaceLevel;
+use crate::schema::{SchemaOptions, SessionHandle};
+use crate::udtf::{LanceFullTextSearchUDTF, LanceVectorSearchUDTF, LanceVersionUDTF};
+use crate::url_table::LanceUrlTableFactory;
 
 /// Builder for configuring a `SessionContext` with Lance namespaces.
 #[derive(Clone, Debug, Default)]
@@ -30,6 +43,30 @@ pub struct SessionBuilder {
     default_schema: Option<String>,
     /// Optional default schema provider.
     default_schema_provider: Option<Arc<dyn SchemaProvider>>,
+    /// How catalogs and schemas are discovered from the namespaces.
+    discovery: CatalogDiscovery,
+    /// How long a schema uses its table listing before listing the tables again.
+    table_list_ttl: Option<Duration>,
+    /// The number of names requested per page when listing namespaces.
+    list_page_size: Option<i32>,
+    /// The number of names after which listing a namespace stops.
+    max_list_results: Option<usize>,
+    /// Tables read at a fixed version, by table name.
+    pinned_versions: Vec<(String, u64)>,
+    /// How long a loaded table is used before checking for a newer version.
+    max_staleness: Option<Duration>,
+    /// Optional DataFusion runtime environment.
+    runtime_env: Option<Arc<RuntimeEnv>>,
+    /// Optional Lance session tables are opened and written with.
+    lance_session: Option<Arc<Session>>,
+    /// The number of child namespaces eager discovery loads at a time.
+    discovery_concurrency: Option<usize>,
+    /// How eager discovery handles child namespaces that fail to load.
+    discovery_errors: DiscoveryErrors,
+    /// Whether registered catalogs and schemas are created in the namespaces.
+    write_through: bool,
+    /// Whether Lance dataset URLs can be queried as tables.
+    url_table: bool,
 }
 
 impl SessionBuilder {
@@ -55,6 +92,130 @@ impl SessionBuilder {
         self
     }
 
+    /// Configure how catalogs and schemas are discovered from the namespaces.
+    ///
+    /// Use [`CatalogDiscovery::Lazy`] to build the session without listing
+    /// the namespaces, which can be slow for namespaces with many children.
+    pub fn with_catalog_discovery(mut self, discovery: CatalogDiscovery) -> Self {
+        self.discovery = discovery;
+        self
+    }
+
+    /// List the tables of each schema again in the background once the last
+    /// listing is older than `ttl`.
+    ///
+    /// Without a TTL a schema only lists its tables when
+    /// [`LanceSchemaProvider::refresh`](crate::LanceSchemaProvider::refresh) is
+    /// called, and `SHOW TABLES` and `information_schema` only include the tables
+    /// listed or used so far.
+    pub fn with_table_list_ttl(mut self, ttl: Duration) -> Self {
+        self.table_list_ttl = Some(ttl);
+        self
+    }
+
+    /// Request `page_size` names per page when listing the child namespaces and
+    /// tables of the namespaces.
+    pub fn with_list_page_size(mut self, page_size: i32) -> Self {
+        self.list_page_size = Some(page_size);
+        self
+    }
+
+    /// Stop listing the child namespaces or tables of a namespace after
+    /// `max_results` names. Names past the limit are not listed, but can still
+    /// be used in queries.
+    pub fn with_max_list_results(mut self, max_results: usize) -> Self {
+        self.max_list_results = Some(max_results);
+        self
+    }
+
+    /// Read the table `table` at `version` instead of its latest version.
+    ///
+    /// The table name is resolved against the default catalog and schema, like
+    /// a table in a query. The pinned version is loaded once and used for the
+    /// lifetime of the session, so repeated queries read the same data even when
+    /// the table is written to elsewhere.
+    pub fn pin_table_version(mut self, table: &str, version: u64) -> Self {
+        self.pinned_versions.push((table.to_string(), version));
+        self
+    }
+
+    /// Check loaded tables for a newer version at most once per `max_staleness`
+    /// instead of on every query.
+    ///
+    /// Versions committed by other writers, and by `INSERT` statements in this
+    /// session, are read up to `max_staleness` late. Statements run with
+    /// [`LanceSqlExt::lance_sql`](crate::LanceSqlExt::lance_sql) are read right
+    /// away.
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.max_staleness = Some(max_staleness);
+        self
+    }
+
+    /// Load up to `concurrency` catalogs at a time with eager discovery,
+    /// [`DEFAULT_DISCOVERY_CONCURRENCY`](crate::catalog::DEFAULT_DISCOVERY_CONCURRENCY)
+    /// by default.
+    pub fn with_discovery_concurrency(mut self, concurrency: usize) -> Self {
+        self.discovery_concurrency = Some(concurrency);
+        self
+    }
+
+    /// Set how eager discovery handles child namespaces that fail to load,
+    /// [`DiscoveryErrors::Strict`] by default.
+    pub fn with_discovery_errors(mut self, discovery_errors: DiscoveryErrors) -> Self {
+        self.discovery_errors = discovery_errors;
+        self
+    }
+
+    /// Provide the DataFusion `RuntimeEnv` of the session, for example to share
+    /// its memory pool and disk manager with other sessions.
+    pub fn with_runtime_env(mut self, runtime_env: Arc<RuntimeEnv>) -> Self {
+        self.runtime_env = Some(runtime_env);
+        self
+    }
+
+    /// Open and write tables with the given Lance session, so all tables of
+    /// the session share its index and metadata caches and its object stores,
+    /// instead of each table building its own.
+    pub fn with_lance_session(mut self, session: Arc<Session>) -> Self {
+        self.lance_session = Some(session);
+        self
+    }
+
+    /// Open and write tables with object stores from `registry`, so stores
+    /// configured once, with their credentials and connection pools, are
+    /// shared by all tables. Replaces a session set with
+    /// [`Self::with_lance_session`].
+    pub fn with_object_store_registry(self, registry: Arc<ObjectStoreRegistry>) -> Self {
+        self.with_lance_session(Arc::new(Session::new(
+            DEFAULT_INDEX_CACHE_SIZE,
+            DEFAULT_METADATA_CACHE_SIZE,
+            registry,
+        )))
+    }
+
+    /// Create the namespaces of catalogs and schemas registered in the Lance
+    /// catalogs, like `ctx.register_catalog` and `catalog.register_schema`, so
+    /// the registrations persist in the catalog service.
+    ///
+    /// Tables registered in namespace schemas are always written to the
+    /// namespace, see [`LanceSchemaProvider`](crate::LanceSchemaProvider).
+    pub fn with_write_through(mut self, write_through: bool) -> Self {
+        self.write_through = write_through;
+        self
+    }
+
+    /// Let queries read Lance datasets by their URL, like
+    /// `SELECT * FROM 's3://bucket/orders.lance?version=12'`, see
+    /// [`LanceUrlTableFactory`].
+    ///
+    /// URLs are resolved by the namespace schemas, so they can be used where
+    /// the default schema is a namespace schema. Object stores are configured
+    /// with `lance.storage.*` settings, see [`LanceConfig`].
+    pub fn enable_url_table(mut self) -> Self {
+        self.url_table = true;
+        self
+    }
+
     /// Provide an explicit `SessionConfig` for the underlying
     /// `SessionContext`.
     pub fn with_config(mut self, config: SessionConfig) -> Self {
@@ -85,6 +246,14 @@ impl SessionBuilder {
     }
 
     /// Build a `SessionContext` with all configured namespaces.
+    ///
+    /// The context has the Lance UDFs, the [`LanceVersionUDTF`] time-travel
+    /// table function, the [`LanceVectorSearchUDTF`] nearest neighbor search
+    /// and the [`LanceFullTextSearchUDTF`] full text search table functions
+    /// registered and plans joins on spatial predicates with
+    /// [`SpatialJoinRule`]. Views stored in the namespaces are planned in the
+    /// context, see [`LanceSchemaProvider`](crate::LanceSchemaProvider). The
+    /// `lance.*` settings of [`LanceConfig`] can be set in the context.
     pub async fn build(self) -> Result<SessionContext> {
         self.check_params_valid()?;
         let config = self.config.unwrap_or_default();
@@ -96,20 +265,100 @@ impl SessionBuilder {
             .default_schema
             .unwrap_or_else(|| options.catalog.default_schema.clone());
 
-        let ctx = SessionContext::new_with_config(
-            config
-                .with_default_catalog_and_schema(default_catalog.as_str(), default_schema.as_str()),
-        );
+        let mut config = config
+            .with_default_catalog_and_schema(default_catalog.as_str(), default_schema.as_str());
+        if config.options().extensions.get::<LanceConfig>().is_none() {
+            config = config.with_option_extension(LanceConfig::default());
+        }
+        let mut state = SessionStateBuilder::new()
+            .with_config(config)
+            .with_default_features()
+            .with_physical_optimizer_rule(Arc::new(SpatialJoinRule));
+        if let Some(runtime_env) = &self.runtime_env {
+            state = state.with_runtime_env(Arc::clone(runtime_env));
+        }
+        let state = state.build();
+        let ctx = SessionContext::new_with_state(state);
+        lance_datafusion::udf::register_functions(&ctx);
+        let mut pinned_versions = HashMap::new();
+        for (table, version) in &self.pinned_versions {
+            let table = TableReference::parse_str(table).resolve(&default_catalog, &default_schema);
+            // Catalogs added by name may be backed by a namespace of another
+            // name, catalogs of the root are its child namespaces.
+            let mut table_id = match self
+                .catalogs
+                .iter()
+                .find(|(name, _)| name.as_str() == table.catalog.as_ref())
+            {
+                Some((_, namespace)) => namespace.id(),
+                None => {
+                    let mut id = self
+                        .root
+                        .as_ref()
+                        .map(NamespaceLevel::id)
+                        .unwrap_or_default();
+                    id.push(table.catalog.to_string());
+                    id
+                }
+            };
+            table_id.push(table.schema.to_string());
+            table_id.push(table.table.to_string());
+            pinned_versions.insert(table_id, *version);
+        }
+        let schema_options = SchemaOptions {
+            table_list_ttl: self.table_list_ttl,
+            session: Some(SessionHandle::new(&ctx)),
+            pinned_versions,
+            max_staleness: self.max_staleness,
+            discovery_concurrency: self.discovery_concurrency,
+            discovery_errors: self.discovery_errors,
+            write_through: self.write_through,
+            url_tables: self.url_table.then(|| {
+                let factory = LanceUrlTableFactory::new().with_session(SessionHandle::new(&ctx));
+                match &self.lance_session {
+                    Some(session) => factory.with_lance_session(Arc::clone(session)),
+                    None => factory,
+                }
+            }),
+        };
+
+        let configure = |mut namespace: NamespaceLevel| {
+            if let Some(session) = &self.lance_session {
+                namespace = namespace.with_session(Arc::clone(session));
+            }
+            if let Some(page_size) = self.list_page_size {
+                namespace = namespace.with_list_page_size(page_size);
+            }
+            if let Some(max_results) = self.max_list_results {
+                namespace = namespace.with_max_list_results(max_results);
+            }
+            namespace
+        };
 
         if let Some(root) = self.root {
-            let catalog_list = Arc::new(LanceCatalogProviderList::try_new(root).await?);
+            let root = configure(root);
+            let catalog_list = Arc::new(
+                LanceCatalogProviderList::try_new_with_options(
+                    root,
+                    self.discovery,
+                    schema_options.clone(),
+                )
+                .await?,
+            );
             ctx.register_catalog_list(catalog_list);
         }
 
         for (catalog_name, namespace) in self.catalogs {
             ctx.register_catalog(
                 catalog_name,
-                Arc::new(LanceCatalogProvider::try_new(namespace).await?),
+                Arc::new(
+                    LanceCatalogProvider::try_new_with_options(
+                        configure(namespace),
+                        self.discovery,
+                        schema_options.clone(),
+                    )
+                    .await?,
+                ),
             );
         }
         if let Some(catalog_provider) = self.default_catalog_provider {
@@ -118,6 +367,31 @@ impl SessionBuilder {
             }
             ctx.register_catalog(default_catalog.as_str(), catalog_provider);
         }
+        let catalog_list = Arc::clone(ctx.state().catalog_list());
+        ctx.register_udtf(
+            "lance_version",
+            Arc::new(LanceVersionUDTF::new(
+                Arc::clone(&catalog_list),
+                &default_catalog,
+                &default_schema,
+            )),
+        );
+        ctx.register_udtf(
+            "vector_search",
+            Arc::new(LanceVectorSearchUDTF::new(
+                Arc::clone(&catalog_list),
+                &default_catalog,
+                &default_schema,
+            )),
+        );
+        ctx.register_udtf(
+            "fts_search",
+            Arc::new(LanceFullTextSearchUDTF::new(
+                catalog_list,
+                &default_catalog,
+                &default_schema,
+            )),
+        );
 
         Ok(ctx)
     }
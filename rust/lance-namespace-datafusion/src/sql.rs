@@ -0,0 +1,925 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! SQL statements that DataFusion parses but does not plan, translated into
+//! Lance operations on the underlying datasets.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_trait::async_trait;
+use datafusion::arrow::array::UInt64Array;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::record_batch::{RecordBatch, RecordBatchReader};
+use datafusion::catalog::SchemaProvider;
+use datafusion::common::{DFSchema, TableReference, plan_datafusion_err, plan_err};
+use datafusion::dataframe::DataFrame;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::execution::context::SessionContext;
+use datafusion::logical_expr::{
+    CreateMemoryTable, DdlStatement, DropTable, EmptyRelation, LogicalPlan,
+};
+use datafusion::sql::parser::{CopyToSource, CopyToStatement, Statement};
+use datafusion::sql::planner::{IdentNormalizer, object_name_to_table_reference};
+use datafusion::sql::sqlparser::ast::{
+    self, Assignment, AssignmentTarget, BinaryOperator, CreateIndex, Delete, Expr as SQLExpr,
+    FromTable, ObjectName, Statement as SQLStatement, TableFactor, TableWithJoins, Update, Value,
+};
+use futures::StreamExt;
+use lance::Dataset;
+use lance::datafusion::LanceTableProvider;
+use lance::dataset::{UpdateBuilder, WriteMode, WriteParams};
+use lance::index::DatasetIndexExt;
+use lance::index::vector::VectorIndexParams;
+use lance_index::scalar::ScalarIndexParams;
+use lance_index::vector::ivf::IvfBuildParams;
+use lance_index::vector::pq::PQBuildParams;
+use lance_index::vector::sq::builder::SQBuildParams;
+use lance_index::{IndexParams, IndexType};
+use lance_linalg::distance::DistanceType;
+
+use crate::error::to_datafusion_error;
+use crate::schema::LanceSchemaProvider;
+
+/// Extends a namespace-backed [`SessionContext`] with SQL statements that map
+/// onto Lance operations.
+///
+/// Currently this covers `CREATE INDEX`:
+///
+/// ```sql
+/// CREATE INDEX emb_idx ON retail.sales.items USING IVF_PQ (embedding)
+///     WITH (num_partitions = 256, num_sub_vectors = 16, distance_type = 'cosine');
+/// CREATE INDEX ON retail.sales.items USING BTREE (item_id);
+/// ```
+///
+/// and `CREATE TABLE` / `DROP TABLE` in schemas backed by a [`LanceSchemaProvider`],
+/// which create and drop the table in the namespace:
+///
+/// ```sql
+/// CREATE TABLE retail.sales.returns (order_id INT, reason VARCHAR);
+/// CREATE TABLE IF NOT EXISTS retail.sales.big_orders AS
+///     SELECT * FROM retail.sales.orders WHERE amount > 100;
+/// DROP TABLE IF EXISTS retail.sales.returns;
+/// ```
+///
+/// and `DELETE` / `UPDATE` on Lance tables, which commit a new version of the
+/// table and return the number of affected rows in a `count` column:
+///
+/// ```sql
+/// DELETE FROM retail.sales.orders WHERE amount < 10;
+/// UPDATE retail.sales.orders SET amount = amount * 2 WHERE customer_id = 1;
+/// ```
+///
+/// and `COPY TO` a Lance dataset, selected with `STORED AS LANCE`, a `format`
+/// option or a `.lance` target. The `mode` option is one of `create` (the
+/// default), `append` or `overwrite`. Other formats are written by DataFusion,
+/// which also accepts the format as a `format` option here:
+///
+/// ```sql
+/// COPY (SELECT * FROM retail.sales.orders WHERE amount > 100)
+///     TO 's3://bucket/exports/big_orders.lance' OPTIONS (mode append);
+/// COPY retail.sales.orders TO '/tmp/orders/' OPTIONS (format csv);
+/// ```
+///
+/// Every other statement is planned and executed by [`SessionContext::sql`].
+#[async_trait]
+pub trait LanceSqlExt {
+    /// Run a single SQL statement, handling Lance-specific statements before
+    /// falling back to DataFusion.
+    async fn lance_sql(&self, sql: &str) -> Result<DataFrame>;
+}
+
+#[async_trait]
+impl LanceSqlExt for SessionContext {
+    async fn lance_sql(&self, sql: &str) -> Result<DataFrame> {
+        let state = self.state();
+        let dialect = state.config().options().sql_parser.dialect;
+        let statement = state.sql_to_statement(sql, &dialect)?;
+
+        match statement {
+            Statement::Statement(stmt) => match *stmt {
+                SQLStatement::CreateIndex(create_index) => {
+                    create_lance_index(self, create_index).await?;
+                    empty_dataframe(self)
+                }
+                SQLStatement::Delete(delete) => {
+                    let count = delete_lance_rows(self, delete).await?;
+                    count_dataframe(self, count)
+                }
+                SQLStatement::Update(update) => {
+                    let count = update_lance_rows(self, update).await?;
+                    count_dataframe(self, count)
+                }
+                stmt @ (SQLStatement::CreateTable(_) | SQLStatement::Drop { .. }) => {
+                    let plan = self
+                        .state()
+                        .statement_to_plan(Statement::Statement(Box::new(stmt)))
+                        .await?;
+                    execute_ddl(self, plan).await
+                }
+                stmt => execute_statement(self, Statement::Statement(Box::new(stmt))).await,
+            },
+            Statement::CopyTo(copy) => match copy_format(&copy).as_deref() {
+                Some("LANCE") => {
+                    let count = copy_to_lance(self, copy).await?;
+                    count_dataframe(self, count)
+                }
+                format => {
+                    let stored_as = format.map(str::to_string);
+                    let options = copy
+                        .options
+                        .into_iter()
+                        .filter(|(key, _)| !key.eq_ignore_ascii_case("format"))
+                        .collect();
+                    let copy = CopyToStatement {
+                        stored_as,
+                        options,
+                        ..copy
+                    };
+                    execute_statement(self, Statement::CopyTo(copy)).await
+                }
+            },
+            statement => execute_statement(self, statement).await,
+        }
+    }
+}
+
+async fn execute_statement(ctx: &SessionContext, statement: Statement) -> Result<DataFrame> {
+    let plan = ctx.state().statement_to_plan(statement).await?;
+    ctx.execute_logical_plan(plan).await
+}
+
+/// Run table DDL against Lance schemas in the namespace, other plans are
+/// executed by DataFusion.
+async fn execute_ddl(ctx: &SessionContext, plan: LogicalPlan) -> Result<DataFrame> {
+    match plan {
+        LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(create)) => {
+            match lance_schema(ctx, &create.name)? {
+                Some(schema) => {
+                    create_lance_table(ctx, as_lance_schema(&schema), create).await?;
+                    empty_dataframe(ctx)
+                }
+                None => {
+                    let plan = LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(create));
+                    ctx.execute_logical_plan(plan).await
+                }
+            }
+        }
+        LogicalPlan::Ddl(DdlStatement::DropTable(drop)) => match lance_schema(ctx, &drop.name)? {
+            Some(schema) => {
+                drop_lance_table(as_lance_schema(&schema), drop).await?;
+                empty_dataframe(ctx)
+            }
+            None => {
+                let plan = LogicalPlan::Ddl(DdlStatement::DropTable(drop));
+                ctx.execute_logical_plan(plan).await
+            }
+        },
+        plan => ctx.execute_logical_plan(plan).await,
+    }
+}
+
+/// The schema the table belongs to, if it is a Lance namespace schema.
+fn lance_schema(
+    ctx: &SessionContext,
+    table_ref: &TableReference,
+) -> Result<Option<Arc<dyn SchemaProvider>>> {
+    let schema = ctx.state().schema_for_ref(table_ref.clone())?;
+    Ok(schema
+        .as_any()
+        .is::<LanceSchemaProvider>()
+        .then_some(schema))
+}
+
+fn as_lance_schema(schema: &Arc<dyn SchemaProvider>) -> &LanceSchemaProvider {
+    schema
+        .as_any()
+        .downcast_ref::<LanceSchemaProvider>()
+        .expect("checked by lance_schema")
+}
+
+/// Create a namespace table for `CREATE TABLE`, writing the rows of the
+/// `AS SELECT` query if there is one.
+///
+/// The rows are streamed into the table. `CREATE OR REPLACE` overwrites the
+/// existing table in a single commit once all rows are written, so a failing
+/// query leaves the table as it was, and the query may read the table itself.
+async fn create_lance_table(
+    ctx: &SessionContext,
+    schema: &LanceSchemaProvider,
+    create: CreateMemoryTable,
+) -> Result<()> {
+    let CreateMemoryTable {
+        name,
+        constraints,
+        input,
+        if_not_exists,
+        or_replace,
+        column_defaults,
+        temporary,
+    } = create;
+    if temporary {
+        return plan_err!("Lance tables cannot be temporary");
+    }
+    if !constraints.is_empty() {
+        return plan_err!("Lance tables do not support constraints");
+    }
+    if !column_defaults.is_empty() {
+        return plan_err!("Lance tables do not support column defaults");
+    }
+
+    let table_name = name.table();
+    let exists = schema.namespace_table_exists(table_name).await?;
+    if exists {
+        if if_not_exists {
+            return Ok(());
+        }
+        if !or_replace {
+            return plan_err!("Table {name} already exists");
+        }
+    }
+
+    let stream = DataFrame::new(ctx.state(), input.as_ref().clone())
+        .execute_stream()
+        .await?;
+    let reader = StreamReader::new(stream);
+    if exists {
+        schema.replace_table(table_name, reader).await?;
+    } else {
+        schema.create_table(table_name, reader).await?;
+    }
+    Ok(())
+}
+
+/// Reads a DataFusion stream as a [`RecordBatchReader`], for the Lance write APIs.
+///
+/// Lance iterates readers on a blocking thread, which blocks on the runtime the
+/// reader was created in for each batch.
+pub(crate) struct StreamReader {
+    stream: SendableRecordBatchStream,
+    runtime: tokio::runtime::Handle,
+    /// The number of rows read so far.
+    rows: Arc<AtomicU64>,
+}
+
+impl StreamReader {
+    pub(crate) fn new(stream: SendableRecordBatchStream) -> Self {
+        Self {
+            stream,
+            runtime: tokio::runtime::Handle::current(),
+            rows: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// A counter of the rows read, which can be checked once the reader is consumed.
+    fn row_counter(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.rows)
+    }
+}
+
+impl Iterator for StreamReader {
+    type Item = std::result::Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.runtime.block_on(self.stream.next())?;
+        if let Ok(batch) = &batch {
+            self.rows
+                .fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+        }
+        Some(batch.map_err(|err| ArrowError::ExternalError(Box::new(err))))
+    }
+}
+
+impl RecordBatchReader for StreamReader {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+}
+
+/// The upper-cased format of a `COPY TO` statement: `STORED AS`, a `format`
+/// option, or `LANCE` for a `.lance` target. `None` leaves the format to
+/// DataFusion, which infers it from the target extension.
+fn copy_format(copy: &CopyToStatement) -> Option<String> {
+    copy.stored_as
+        .as_ref()
+        .map(|stored_as| stored_as.to_uppercase())
+        .or_else(|| {
+            copy.options
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case("format"))
+                .map(|(_, value)| option_string(value).to_uppercase())
+        })
+        .or_else(|| {
+            copy.target
+                .trim_end_matches('/')
+                .ends_with(".lance")
+                .then(|| "LANCE".to_string())
+        })
+}
+
+/// The text of a `COPY TO` option value, which may be quoted or a bare word.
+fn option_string(value: &Value) -> String {
+    match value {
+        Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Write the rows of a `COPY TO` source to a Lance dataset at the target,
+/// returning the number of rows written.
+async fn copy_to_lance(ctx: &SessionContext, copy: CopyToStatement) -> Result<u64> {
+    let CopyToStatement {
+        source,
+        target,
+        partitioned_by,
+        options,
+        ..
+    } = copy;
+    if !partitioned_by.is_empty() {
+        return plan_err!("Lance COPY TO does not support PARTITIONED BY");
+    }
+    let mut mode = WriteMode::Create;
+    for (key, value) in options {
+        match key.to_lowercase().as_str() {
+            "format" => {}
+            "mode" => {
+                mode = match option_string(&value).to_lowercase().as_str() {
+                    "create" => WriteMode::Create,
+                    "append" => WriteMode::Append,
+                    "overwrite" => WriteMode::Overwrite,
+                    other => {
+                        return plan_err!(
+                            "Unsupported Lance COPY TO mode {other}, expected create, append or overwrite"
+                        );
+                    }
+                }
+            }
+            _ => return plan_err!("Unsupported Lance COPY TO option: {key}"),
+        }
+    }
+
+    let source = match source {
+        CopyToSource::Relation(table_name) => ctx.table(table_reference(ctx, table_name)?).await?,
+        CopyToSource::Query(query) => {
+            let plan = ctx
+                .state()
+                .statement_to_plan(Statement::Statement(Box::new(SQLStatement::Query(query))))
+                .await?;
+            DataFrame::new(ctx.state(), plan)
+        }
+    };
+    let reader = StreamReader::new(source.execute_stream().await?);
+    let rows = reader.row_counter();
+    Dataset::write(
+        reader,
+        &target,
+        Some(WriteParams {
+            mode,
+            ..Default::default()
+        }),
+    )
+    .await
+    .map_err(to_datafusion_error)?;
+    Ok(rows.load(Ordering::Relaxed))
+}
+
+/// Drop a namespace table for `DROP TABLE`.
+async fn drop_lance_table(schema: &LanceSchemaProvider, drop: DropTable) -> Result<()> {
+    let table_name = drop.name.table();
+    if !schema.namespace_table_exists(table_name).await? {
+        if drop.if_exists {
+            return Ok(());
+        }
+        return plan_err!("Table {} doesn't exist", drop.name);
+    }
+    schema.drop_table(table_name).await
+}
+
+/// Delete the rows matching the `WHERE` clause of a `DELETE` statement,
+/// returning the number of deleted rows.
+async fn delete_lance_rows(ctx: &SessionContext, delete: Delete) -> Result<u64> {
+    let Delete {
+        tables,
+        from,
+        using,
+        selection,
+        returning,
+        order_by,
+        limit,
+        ..
+    } = delete;
+    if !tables.is_empty() || using.is_some() {
+        return plan_err!("Lance DELETE only supports deleting from a single table");
+    }
+    if returning.is_some() {
+        return plan_err!("Lance DELETE does not support RETURNING");
+    }
+    if !order_by.is_empty() || limit.is_some() {
+        return plan_err!("Lance DELETE does not support ORDER BY or LIMIT");
+    }
+    let (FromTable::WithFromKeyword(from) | FromTable::WithoutKeyword(from)) = from;
+    let [table] = <[TableWithJoins; 1]>::try_from(from)
+        .map_err(|_| plan_datafusion_err!("Lance DELETE only supports a single table"))?;
+
+    let table_ref = table_reference(ctx, dml_table_name(table)?)?;
+    let mut dataset = lance_dataset(ctx, &table_ref).await?;
+    // Without a WHERE clause every row is deleted.
+    let predicate = selection.map_or_else(|| "true".to_string(), |expr| expr.to_string());
+    let result = dataset
+        .delete(&predicate)
+        .await
+        .map_err(to_datafusion_error)?;
+    invalidate_lance_table(ctx, &table_ref)?;
+    Ok(result.num_deleted_rows)
+}
+
+/// Apply the assignments of an `UPDATE` statement to the rows matching its
+/// `WHERE` clause, returning the number of updated rows.
+async fn update_lance_rows(ctx: &SessionContext, update: Update) -> Result<u64> {
+    let Update {
+        table,
+        assignments,
+        from,
+        selection,
+        returning,
+        or,
+        limit,
+        ..
+    } = update;
+    if from.is_some() {
+        return plan_err!("Lance UPDATE does not support FROM");
+    }
+    if returning.is_some() {
+        return plan_err!("Lance UPDATE does not support RETURNING");
+    }
+    if or.is_some() || limit.is_some() {
+        return plan_err!("Lance UPDATE does not support OR or LIMIT");
+    }
+
+    let normalize = ctx
+        .state()
+        .config()
+        .options()
+        .sql_parser
+        .enable_ident_normalization;
+    let normalizer = IdentNormalizer::new(normalize);
+    let table_ref = table_reference(ctx, dml_table_name(table)?)?;
+    let dataset = lance_dataset(ctx, &table_ref).await?;
+
+    let mut builder = UpdateBuilder::new(Arc::new(dataset));
+    if let Some(selection) = selection {
+        builder = builder
+            .update_where(&selection.to_string())
+            .map_err(to_datafusion_error)?;
+    }
+    for Assignment { target, value } in assignments {
+        let column = match target {
+            AssignmentTarget::ColumnName(column) => column,
+            target => {
+                return plan_err!("Lance UPDATE does not support tuple assignments: {target}");
+            }
+        };
+        let column = column
+            .0
+            .last()
+            .and_then(|part| part.as_ident().cloned())
+            .map(|ident| normalizer.normalize(ident))
+            .ok_or_else(|| plan_datafusion_err!("Invalid column in UPDATE: {column}"))?;
+        builder = builder
+            .set(column, &value.to_string())
+            .map_err(to_datafusion_error)?;
+    }
+    let result = builder
+        .build()
+        .map_err(to_datafusion_error)?
+        .execute()
+        .await
+        .map_err(to_datafusion_error)?;
+    invalidate_lance_table(ctx, &table_ref)?;
+    Ok(result.rows_updated)
+}
+
+/// The name of the table a `DELETE` or `UPDATE` statement writes to.
+///
+/// The `WHERE` clause is evaluated by Lance against the table's own columns, so
+/// joins and table aliases are not supported.
+fn dml_table_name(table: TableWithJoins) -> Result<ObjectName> {
+    if !table.joins.is_empty() {
+        return plan_err!("Lance DELETE and UPDATE do not support joins");
+    }
+    match table.relation {
+        TableFactor::Table {
+            name, alias: None, ..
+        } => Ok(name),
+        relation => plan_err!("Lance DELETE and UPDATE require a plain table, got: {relation}"),
+    }
+}
+
+/// The reference to the table with the given name in a statement.
+fn table_reference(ctx: &SessionContext, table_name: ObjectName) -> Result<TableReference> {
+    let normalize = ctx
+        .state()
+        .config()
+        .options()
+        .sql_parser
+        .enable_ident_normalization;
+    object_name_to_table_reference(table_name, normalize)
+}
+
+/// Load the latest version of the Lance table with the given name.
+///
+/// The table must resolve to a [`LanceTableProvider`]. Writers call
+/// [`invalidate_lance_table`] once they committed, so namespace schema providers
+/// load the new version on the next lookup of the table.
+async fn lance_dataset(ctx: &SessionContext, table_ref: &TableReference) -> Result<Dataset> {
+    let provider = ctx.table_provider(table_ref.clone()).await?;
+    let lance_provider = provider
+        .as_any()
+        .downcast_ref::<LanceTableProvider>()
+        .ok_or_else(|| plan_datafusion_err!("Table {table_ref} is not a Lance table"))?;
+
+    let mut dataset = lance_provider.dataset().as_ref().clone();
+    dataset
+        .checkout_latest()
+        .await
+        .map_err(to_datafusion_error)?;
+    Ok(dataset)
+}
+
+/// Drop the table from the cache of its namespace schema after a write, so the
+/// next query reads the written version even within the schema's staleness
+/// window.
+fn invalidate_lance_table(ctx: &SessionContext, table_ref: &TableReference) -> Result<()> {
+    if let Some(schema) = lance_schema(ctx, table_ref)? {
+        as_lance_schema(&schema).invalidate(table_ref.table());
+    }
+    Ok(())
+}
+
+/// DML statements return the number of affected rows, mirroring DataFusion's
+/// own DML handling.
+fn count_dataframe(ctx: &SessionContext, count: u64) -> Result<DataFrame> {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "count",
+        DataType::UInt64,
+        false,
+    )]));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(UInt64Array::from(vec![count]))])?;
+    ctx.read_batch(batch)
+}
+
+/// DDL statements return an empty result, mirroring DataFusion's own DDL handling.
+fn empty_dataframe(ctx: &SessionContext) -> Result<DataFrame> {
+    let plan = LogicalPlan::EmptyRelation(EmptyRelation {
+        produce_one_row: false,
+        schema: Arc::new(DFSchema::empty()),
+    });
+    Ok(DataFrame::new(ctx.state(), plan))
+}
+
+/// Build a Lance index for a `CREATE INDEX` statement.
+///
+/// The index is written to the latest version of the dataset, see [`lance_dataset`].
+async fn create_lance_index(ctx: &SessionContext, create_index: CreateIndex) -> Result<()> {
+    let CreateIndex {
+        name,
+        table_name,
+        using,
+        columns,
+        unique,
+        if_not_exists,
+        with,
+        predicate,
+        ..
+    } = create_index;
+
+    if unique {
+        return plan_err!("Lance does not support UNIQUE indices");
+    }
+    if predicate.is_some() {
+        return plan_err!("Lance does not support partial indices (CREATE INDEX ... WHERE)");
+    }
+
+    let normalize = ctx
+        .state()
+        .config()
+        .options()
+        .sql_parser
+        .enable_ident_normalization;
+    let normalizer = IdentNormalizer::new(normalize);
+
+    let table_ref = table_reference(ctx, table_name)?;
+    let mut dataset = lance_dataset(ctx, &table_ref).await?;
+
+    let columns = columns
+        .into_iter()
+        .map(|column| column_path(column.column.expr, &normalizer))
+        .collect::<Result<Vec<_>>>()?;
+    let index_name = name
+        .and_then(|name| name.0.last().and_then(|part| part.as_ident().cloned()))
+        .map(|ident| normalizer.normalize(ident));
+    let index_type = index_type(using.as_ref())?;
+    let options = parse_with_options(with, &normalizer)?;
+    let params = index_params(index_type, options)?;
+
+    if if_not_exists && index_exists(&dataset, index_name.as_deref(), &columns).await? {
+        return Ok(());
+    }
+
+    let columns = columns.iter().map(String::as_str).collect::<Vec<_>>();
+    dataset
+        .create_index(&columns, index_type, index_name, params.as_ref(), false)
+        .await
+        .map_err(to_datafusion_error)?;
+    invalidate_lance_table(ctx, &table_ref)
+}
+
+/// Whether `CREATE INDEX IF NOT EXISTS` has nothing to do: an index with the
+/// given name exists or, for unnamed indices, an index on the same columns.
+///
+/// The SQL parser requires a name together with `IF NOT EXISTS`, statements
+/// built without one are matched by their columns.
+async fn index_exists(dataset: &Dataset, name: Option<&str>, columns: &[String]) -> Result<bool> {
+    let indices = dataset.load_indices().await.map_err(to_datafusion_error)?;
+    if let Some(name) = name {
+        return Ok(indices.iter().any(|index| index.name == name));
+    }
+    let field_ids = columns
+        .iter()
+        .map(|column| {
+            dataset
+                .schema()
+                .field(column)
+                .map(|field| field.id)
+                .ok_or_else(|| plan_datafusion_err!("Column {column} does not exist"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(indices.iter().any(|index| index.fields == field_ids))
+}
+
+/// Convert an indexed column expression into a Lance field path.
+fn column_path(expr: SQLExpr, normalizer: &IdentNormalizer) -> Result<String> {
+    match expr {
+        SQLExpr::Identifier(ident) => Ok(normalizer.normalize(ident)),
+        SQLExpr::CompoundIdentifier(idents) => Ok(idents
+            .into_iter()
+            .map(|ident| normalizer.normalize(ident))
+            .collect::<Vec<_>>()
+            .join(".")),
+        expr => plan_err!("Lance indices can only be created on columns, got: {expr}"),
+    }
+}
+
+/// Map the `USING` clause onto a Lance index type, defaulting to BTREE.
+fn index_type(using: Option<&ast::IndexType>) -> Result<IndexType> {
+    let name = match using {
+        None | Some(ast::IndexType::BTree) => "BTREE".to_string(),
+        Some(ast::IndexType::Bloom) => "BLOOMFILTER".to_string(),
+        Some(ast::IndexType::Custom(ident)) => ident.value.to_uppercase(),
+        Some(other) => return plan_err!("Unsupported Lance index type: {other}"),
+    };
+    IndexType::try_from(name.as_str()).map_err(to_datafusion_error)
+}
+
+/// A literal value from the `WITH (...)` clause.
+#[derive(Debug, Clone, PartialEq)]
+enum OptionValue {
+    Number(String),
+    String(String),
+    Boolean(bool),
+}
+
+impl OptionValue {
+    fn as_usize(&self, key: &str) -> Result<usize> {
+        match self {
+            Self::Number(n) => n
+                .parse()
+                .map_err(|_| plan_datafusion_err!("Option {key} must be a positive integer")),
+            _ => plan_err!("Option {key} must be a positive integer"),
+        }
+    }
+
+    fn as_str(&self) -> String {
+        match self {
+            Self::Number(s) | Self::String(s) => s.clone(),
+            Self::Boolean(b) => b.to_string(),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Number(n) => {
+                serde_json::from_str(n).unwrap_or_else(|_| serde_json::Value::String(n.clone()))
+            }
+            Self::String(s) => serde_json::Value::String(s.clone()),
+            Self::Boolean(b) => serde_json::Value::Bool(*b),
+        }
+    }
+}
+
+/// Parse `WITH (key = value, ...)` into a map of lower-cased keys to literal values.
+fn parse_with_options(
+    with: Vec<SQLExpr>,
+    normalizer: &IdentNormalizer,
+) -> Result<HashMap<String, OptionValue>> {
+    let mut options = HashMap::with_capacity(with.len());
+    for expr in with {
+        let SQLExpr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq,
+            right,
+        } = expr
+        else {
+            return plan_err!("Index options must be of the form key = value, got: {expr}");
+        };
+        let SQLExpr::Identifier(key) = *left else {
+            return plan_err!("Index option name must be an identifier, got: {left}");
+        };
+        let key = key.value.to_lowercase();
+        let value = match *right {
+            SQLExpr::Value(value) => match value.value {
+                Value::Number(n, _) => OptionValue::Number(n.to_string()),
+                Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => {
+                    OptionValue::String(s)
+                }
+                Value::Boolean(b) => OptionValue::Boolean(b),
+                value => return plan_err!("Unsupported value for index option {key}: {value}"),
+            },
+            SQLExpr::Identifier(ident) => OptionValue::String(normalizer.normalize(ident)),
+            expr => return plan_err!("Unsupported value for index option {key}: {expr}"),
+        };
+        if options.insert(key.clone(), value).is_some() {
+            return plan_err!("Index option {key} is specified more than once");
+        }
+    }
+    Ok(options)
+}
+
+/// Remove an integer option from the `WITH` options.
+fn take_usize(options: &mut HashMap<String, OptionValue>, key: &str) -> Result<Option<usize>> {
+    options
+        .remove(key)
+        .map(|value| value.as_usize(key))
+        .transpose()
+}
+
+/// Translate `WITH` options into Lance index build parameters.
+///
+/// Vector indices accept `num_partitions`, `num_sub_vectors`, `num_bits`,
+/// `max_iterations`, `sample_rate` and `distance_type` (alias `metric_type`).
+/// `max_iterations` and `sample_rate` apply to every training stage; prefix
+/// them with the stage (`ivf_`, `pq_` or `sq_`, e.g. `pq_sample_rate`) to set
+/// them for one stage only. Scalar indices pass all options through as the JSON
+/// training parameters of the index plugin.
+fn index_params(
+    index_type: IndexType,
+    mut options: HashMap<String, OptionValue>,
+) -> Result<Box<dyn IndexParams>> {
+    if index_type.is_scalar() {
+        let mut params =
+            ScalarIndexParams::for_builtin(index_type.try_into().map_err(to_datafusion_error)?);
+        if !options.is_empty() {
+            let json = options
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_json()))
+                .collect::<serde_json::Map<_, _>>();
+            params = params.with_params(&json);
+        }
+        return Ok(Box::new(params));
+    }
+
+    let distance_type = match options
+        .remove("distance_type")
+        .or_else(|| options.remove("metric_type"))
+    {
+        Some(value) => DistanceType::try_from(value.as_str().as_str())?,
+        None => DistanceType::L2,
+    };
+    let mut ivf = IvfBuildParams::default();
+    if let Some(num_partitions) = take_usize(&mut options, "num_partitions")? {
+        ivf.num_partitions = Some(num_partitions);
+    }
+    let max_iters = take_usize(&mut options, "max_iterations")?;
+    let sample_rate = take_usize(&mut options, "sample_rate")?;
+    let num_bits = take_usize(&mut options, "num_bits")?;
+    if let Some(max_iters) = take_usize(&mut options, "ivf_max_iterations")?.or(max_iters) {
+        ivf.max_iters = max_iters;
+    }
+    if let Some(sample_rate) = take_usize(&mut options, "ivf_sample_rate")?.or(sample_rate) {
+        ivf.sample_rate = sample_rate;
+    }
+
+    let params = match index_type {
+        IndexType::IvfFlat => VectorIndexParams::with_ivf_flat_params(distance_type, ivf),
+        IndexType::IvfPq | IndexType::Vector => {
+            let mut pq = PQBuildParams::default();
+            if let Some(num_sub_vectors) = take_usize(&mut options, "num_sub_vectors")? {
+                pq.num_sub_vectors = num_sub_vectors;
+            }
+            if let Some(num_bits) = num_bits {
+                pq.num_bits = num_bits;
+            }
+            if let Some(max_iters) = take_usize(&mut options, "pq_max_iterations")?.or(max_iters) {
+                pq.max_iters = max_iters;
+            }
+            if let Some(sample_rate) = take_usize(&mut options, "pq_sample_rate")?.or(sample_rate) {
+                pq.sample_rate = sample_rate;
+            }
+            VectorIndexParams::with_ivf_pq_params(distance_type, ivf, pq)
+        }
+        IndexType::IvfSq => {
+            let mut sq = SQBuildParams::default();
+            if let Some(num_bits) = num_bits {
+                sq.num_bits = u16::try_from(num_bits)
+                    .map_err(|_| plan_datafusion_err!("Option num_bits is out of range"))?;
+            }
+            if let Some(sample_rate) = take_usize(&mut options, "sq_sample_rate")?.or(sample_rate) {
+                sq.sample_rate = sample_rate;
+            }
+            VectorIndexParams::with_ivf_sq_params(distance_type, ivf, sq)
+        }
+        other => {
+            return Err(DataFusionError::NotImplemented(format!(
+                "CREATE INDEX does not support {other} indices yet"
+            )));
+        }
+    };
+
+    if let Some(key) = options.keys().next() {
+        return plan_err!("Unknown option {key} for {index_type} index");
+    }
+    Ok(Box::new(params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::sql::sqlparser::ast::Ident;
+    use lance::index::vector::StageParams;
+
+    #[test]
+    fn test_index_type_from_using() {
+        assert_eq!(index_type(None).unwrap(), IndexType::BTree);
+        assert_eq!(
+            index_type(Some(&ast::IndexType::BTree)).unwrap(),
+            IndexType::BTree
+        );
+        assert_eq!(
+            index_type(Some(&ast::IndexType::Custom(Ident::new("ivf_pq")))).unwrap(),
+            IndexType::IvfPq
+        );
+        assert!(index_type(Some(&ast::IndexType::GIN)).is_err());
+    }
+
+    #[test]
+    fn test_vector_index_params() {
+        let options = HashMap::from([
+            (
+                "num_partitions".to_string(),
+                OptionValue::Number("4".to_string()),
+            ),
+            (
+                "num_sub_vectors".to_string(),
+                OptionValue::Number("2".to_string()),
+            ),
+            (
+                "distance_type".to_string(),
+                OptionValue::String("cosine".to_string()),
+            ),
+        ]);
+        let params = index_params(IndexType::IvfPq, options).unwrap();
+        let params = params.as_any().downcast_ref::<VectorIndexParams>().unwrap();
+        assert_eq!(params.metric_type, DistanceType::Cosine);
+        assert_eq!(params.stages.len(), 2);
+
+        let options = HashMap::from([("bogus".to_string(), OptionValue::Number("1".to_string()))]);
+        assert!(index_params(IndexType::IvfFlat, options).is_err());
+    }
+
+    #[test]
+    fn test_stage_training_options() {
+        let number = |n: &str| OptionValue::Number(n.to_string());
+        let options = HashMap::from([
+            ("sample_rate".to_string(), number("64")),
+            ("pq_sample_rate".to_string(), number("32")),
+            ("ivf_max_iterations".to_string(), number("10")),
+        ]);
+        let params = index_params(IndexType::IvfPq, options).unwrap();
+        let params = params.as_any().downcast_ref::<VectorIndexParams>().unwrap();
+        let (StageParams::Ivf(ivf), StageParams::PQ(pq)) = (&params.stages[0], &params.stages[1])
+        else {
+            panic!("expected IVF and PQ stages, got {:?}", params.stages);
+        };
+        assert_eq!(ivf.sample_rate, 64);
+        assert_eq!(ivf.max_iters, 10);
+        assert_eq!(pq.sample_rate, 32);
+        assert_eq!(pq.max_iters, PQBuildParams::default().max_iters);
+
+        // Options for stages the index doesn't have are rejected.
+        let options = HashMap::from([("pq_sample_rate".to_string(), number("32"))]);
+        assert!(index_params(IndexType::IvfFlat, options).is_err());
+    }
+}
@@ -0,0 +1,524 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Table functions registered by [`SessionBuilder`](crate::SessionBuilder).
+
+use std::any::Any;
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::catalog::{
+    CatalogProviderList, SchemaProvider, Session, TableFunctionImpl, TableProvider,
+};
+use datafusion::common::{
+    ResolvedTableReference, ScalarValue, TableReference, plan_datafusion_err, plan_err,
+};
+use datafusion::error::Result;
+use datafusion::logical_expr::simplify::SimplifyContext;
+use datafusion::logical_expr::{Expr, TableType};
+use datafusion::optimizer::simplify_expressions::ExprSimplifier;
+use datafusion::physical_expr::PhysicalExpr;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_plan::projection::ProjectionExec;
+use lance::Dataset;
+use lance::datafusion::LanceTableProvider;
+use lance_index::scalar::FullTextSearchQuery;
+use lance_index::scalar::inverted::SCORE_COL;
+use lance_index::vector::DIST_COL;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use crate::error::to_datafusion_error;
+use crate::schema::LanceSchemaProvider;
+
+/// The `lance_version('table', version)` table function, a table of a Lance
+/// namespace schema at an earlier version.
+///
+/// `version` is either a version number or a timestamp, in which case the last
+/// version committed at or before the timestamp is read. The table name is
+/// resolved against the default catalog and schema, like a table in a query.
+///
+/// ```sql
+/// SELECT * FROM lance_version('retail.sales.orders', 3);
+/// SELECT * FROM lance_version('retail.sales.orders', TIMESTAMP '2025-06-01 00:00:00');
+/// ```
+///
+/// DataFusion resolves table functions synchronously, so the dataset is loaded
+/// by blocking the planning thread, which requires a multi-threaded Tokio runtime.
+#[derive(Debug)]
+pub struct LanceVersionUDTF {
+    tables: TableResolver,
+}
+
+impl LanceVersionUDTF {
+    /// Resolve tables in `catalog_list`, unqualified names are looked up in the
+    /// given default catalog and schema.
+    pub fn new(
+        catalog_list: Arc<dyn CatalogProviderList>,
+        default_catalog: &str,
+        default_schema: &str,
+    ) -> Self {
+        Self {
+            tables: TableResolver::new(catalog_list, default_catalog, default_schema),
+        }
+    }
+}
+
+/// Resolves the table name argument of a table function to a Lance namespace
+/// schema.
+#[derive(Debug)]
+struct TableResolver {
+    catalog_list: Arc<dyn CatalogProviderList>,
+    default_catalog: String,
+    default_schema: String,
+}
+
+impl TableResolver {
+    fn new(
+        catalog_list: Arc<dyn CatalogProviderList>,
+        default_catalog: &str,
+        default_schema: &str,
+    ) -> Self {
+        Self {
+            catalog_list,
+            default_catalog: default_catalog.to_string(),
+            default_schema: default_schema.to_string(),
+        }
+    }
+
+    /// The schema of the table named by `table`, a string literal.
+    fn resolve(
+        &self,
+        function: &str,
+        table: &Expr,
+    ) -> Result<(Arc<dyn SchemaProvider>, ResolvedTableReference)> {
+        let table_ref = match table {
+            Expr::Literal(
+                ScalarValue::Utf8(Some(name))
+                | ScalarValue::LargeUtf8(Some(name))
+                | ScalarValue::Utf8View(Some(name)),
+                _,
+            ) => TableReference::parse_str(name),
+            _ => return plan_err!("{function} table name should be a string"),
+        };
+        let table_ref = table_ref.resolve(&self.default_catalog, &self.default_schema);
+        let schema = self
+            .catalog_list
+            .catalog(&table_ref.catalog)
+            .and_then(|catalog| catalog.schema(&table_ref.schema))
+            .ok_or_else(|| {
+                plan_datafusion_err!(
+                    "Schema {}.{} not found",
+                    table_ref.catalog,
+                    table_ref.schema
+                )
+            })?;
+        if !schema.as_any().is::<LanceSchemaProvider>() {
+            return plan_err!("Table {table_ref} is not in a Lance namespace schema");
+        }
+        Ok((schema, table_ref))
+    }
+}
+
+fn as_lance_schema(schema: &Arc<dyn SchemaProvider>) -> &LanceSchemaProvider {
+    schema
+        .as_any()
+        .downcast_ref::<LanceSchemaProvider>()
+        .expect("checked by TableResolver::resolve")
+}
+
+/// The version argument of `lance_version`.
+enum VersionArg {
+    Number(u64),
+    /// Nanoseconds since the epoch, in UTC.
+    AsOf(i64),
+}
+
+impl TableFunctionImpl for LanceVersionUDTF {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        let [table, version] = args else {
+            return plan_err!("lance_version takes a table name and a version or timestamp");
+        };
+        let (schema, table_ref) = self.tables.resolve("lance_version", table)?;
+        let schema = as_lance_schema(&schema);
+        let version = version_arg(version)?;
+
+        block_on("lance_version", async {
+            let version = match version {
+                VersionArg::Number(version) => version,
+                VersionArg::AsOf(timestamp) => {
+                    version_as_of(schema, &table_ref.table, timestamp).await?
+                }
+            };
+            schema.table_at_version(&table_ref.table, version).await
+        })?
+    }
+}
+
+/// The last version of the table committed at or before `timestamp`.
+async fn version_as_of(schema: &LanceSchemaProvider, table: &str, timestamp: i64) -> Result<u64> {
+    let versions = lance_dataset(schema, table)
+        .await?
+        .versions()
+        .await
+        .map_err(to_datafusion_error)?;
+    versions
+        .iter()
+        .filter(|version| {
+            version
+                .timestamp
+                .timestamp_nanos_opt()
+                .is_some_and(|committed| committed <= timestamp)
+        })
+        .map(|version| version.version)
+        .max()
+        .ok_or_else(|| {
+            plan_datafusion_err!("Table {table} has no version at or before {timestamp}")
+        })
+}
+
+fn version_arg(expr: &Expr) -> Result<VersionArg> {
+    let Expr::Literal(value, _) = expr else {
+        return plan_err!("lance_version version should be a literal");
+    };
+    if value.data_type().is_integer() {
+        if let Ok(ScalarValue::UInt64(Some(version))) = value.cast_to(&DataType::UInt64) {
+            return Ok(VersionArg::Number(version));
+        }
+        return plan_err!("lance_version version should be a non-negative integer");
+    }
+    match value.cast_to(&DataType::Timestamp(
+        TimeUnit::Nanosecond,
+        Some("UTC".into()),
+    )) {
+        Ok(ScalarValue::TimestampNanosecond(Some(timestamp), _)) => Ok(VersionArg::AsOf(timestamp)),
+        _ => plan_err!("lance_version version should be a version number or a timestamp"),
+    }
+}
+
+/// The latest dataset of a Lance table in `schema`.
+async fn lance_dataset(schema: &LanceSchemaProvider, table: &str) -> Result<Arc<Dataset>> {
+    let provider = schema
+        .table(table)
+        .await?
+        .ok_or_else(|| plan_datafusion_err!("Table {table} not found"))?;
+    let provider = provider
+        .as_any()
+        .downcast_ref::<LanceTableProvider>()
+        .ok_or_else(|| plan_datafusion_err!("Table {table} is not a Lance table"))?;
+    Ok(provider.dataset())
+}
+
+/// Run `future` to completion from a synchronous DataFusion callback, like a
+/// table function.
+pub(crate) fn block_on<F: Future>(function: &str, future: F) -> Result<F::Output> {
+    let handle = Handle::try_current()
+        .map_err(|_| plan_datafusion_err!("{function} must be called within a Tokio runtime"))?;
+    if handle.runtime_flavor() != RuntimeFlavor::MultiThread {
+        return plan_err!("{function} requires a multi-threaded Tokio runtime");
+    }
+    Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+}
+
+/// The `vector_search('table', query, k [, 'column'])` table function, the `k`
+/// rows of a Lance namespace table nearest to the `query` vector.
+///
+/// The rows have the columns of the table and a `_distance` column. The vector
+/// column may be omitted if the table has a single one. The search uses the
+/// vector index of the column if there is one, and is exhaustive otherwise.
+///
+/// ```sql
+/// SELECT item_id, _distance FROM vector_search('retail.sales.items', [0.1, 0.2, 0.3, 0.4], 10);
+/// SELECT * FROM vector_search('retail.sales.items', [0.1, 0.2, 0.3, 0.4], 10, 'embedding')
+///     WHERE price < 100;
+/// ```
+///
+/// `WHERE` clauses filter the `k` nearest rows, so fewer than `k` rows may be
+/// returned. Like [`LanceVersionUDTF`], this requires a multi-threaded Tokio
+/// runtime.
+#[derive(Debug)]
+pub struct LanceVectorSearchUDTF {
+    tables: TableResolver,
+}
+
+impl LanceVectorSearchUDTF {
+    /// Resolve tables in `catalog_list`, unqualified names are looked up in the
+    /// given default catalog and schema.
+    pub fn new(
+        catalog_list: Arc<dyn CatalogProviderList>,
+        default_catalog: &str,
+        default_schema: &str,
+    ) -> Self {
+        Self {
+            tables: TableResolver::new(catalog_list, default_catalog, default_schema),
+        }
+    }
+}
+
+impl TableFunctionImpl for LanceVectorSearchUDTF {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        let (table, query, k, column) = match args {
+            [table, query, k] => (table, query, k, None),
+            [table, query, k, column] => (table, query, k, Some(column)),
+            _ => {
+                return plan_err!(
+                    "vector_search takes a table name, a query vector, k and optionally a column"
+                );
+            }
+        };
+        let (schema, table_ref) = self.tables.resolve("vector_search", table)?;
+        let schema = as_lance_schema(&schema);
+        let simplifier = ExprSimplifier::new(SimplifyContext::default());
+        let k = match simplifier.simplify(k.clone())? {
+            Expr::Literal(value, _) if value.data_type().is_integer() => {
+                match value.cast_to(&DataType::UInt64) {
+                    Ok(ScalarValue::UInt64(Some(k))) if k > 0 => k as usize,
+                    _ => return plan_err!("vector_search k should be a positive integer"),
+                }
+            }
+            _ => return plan_err!("vector_search k should be a positive integer"),
+        };
+        let column = column
+            .map(|column| string_arg("vector_search column", column))
+            .transpose()?;
+
+        let dataset = block_on("vector_search", lance_dataset(schema, &table_ref.table))??;
+        let (column, element_type) = vector_column(&dataset, column)?;
+        let query = match simplifier.simplify(query.clone())? {
+            Expr::Literal(ScalarValue::List(list), _) if list.len() == 1 => list.value(0),
+            Expr::Literal(ScalarValue::LargeList(list), _) if list.len() == 1 => list.value(0),
+            Expr::Literal(ScalarValue::FixedSizeList(list), _) if list.len() == 1 => list.value(0),
+            _ => return plan_err!("vector_search query should be an array of numbers"),
+        };
+        let query = cast(&query, &element_type)?;
+        Ok(Arc::new(VectorSearchTable::new(dataset, column, query, k)))
+    }
+}
+
+/// The value of a string literal argument.
+fn string_arg(name: &str, expr: &Expr) -> Result<String> {
+    match expr {
+        Expr::Literal(
+            ScalarValue::Utf8(Some(value))
+            | ScalarValue::LargeUtf8(Some(value))
+            | ScalarValue::Utf8View(Some(value)),
+            _,
+        ) => Ok(value.clone()),
+        _ => plan_err!("{name} should be a string"),
+    }
+}
+
+/// The vector column to search and the type of its elements, `column` or the
+/// only vector column of the dataset.
+fn vector_column(dataset: &Dataset, column: Option<String>) -> Result<(String, DataType)> {
+    let schema = Schema::from(dataset.schema());
+    let vector_columns = schema
+        .fields()
+        .iter()
+        .filter_map(|field| match field.data_type() {
+            DataType::FixedSizeList(element, _) if element.data_type().is_floating() => {
+                Some((field.name().clone(), element.data_type().clone()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    match column {
+        Some(column) => vector_columns
+            .into_iter()
+            .find(|(name, _)| *name == column)
+            .ok_or_else(|| plan_datafusion_err!("Column {column} is not a vector column")),
+        None => match <[_; 1]>::try_from(vector_columns) {
+            Ok([vector_column]) => Ok(vector_column),
+            Err(_) => plan_err!(
+                "vector_search needs a column name unless the table has exactly one vector column"
+            ),
+        },
+    }
+}
+
+/// The nearest neighbors of a query vector in a Lance dataset.
+#[derive(Debug)]
+struct VectorSearchTable {
+    dataset: Arc<Dataset>,
+    column: String,
+    query: ArrayRef,
+    k: usize,
+    schema: SchemaRef,
+}
+
+impl VectorSearchTable {
+    fn new(dataset: Arc<Dataset>, column: String, query: ArrayRef, k: usize) -> Self {
+        let mut fields = Schema::from(dataset.schema()).fields().to_vec();
+        fields.push(Arc::new(Field::new(DIST_COL, DataType::Float32, true)));
+        Self {
+            dataset,
+            column,
+            query,
+            k,
+            schema: Arc::new(Schema::new(fields)),
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for VectorSearchTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let mut scan = self.dataset.scan();
+        scan.nearest(&self.column, self.query.as_ref(), self.k)?;
+        let plan = scan.create_plan().await?;
+        project_by_name(&self.schema, projection, plan)
+    }
+}
+
+/// Select the projected columns of `schema` from the output of a search by
+/// name, searches order their output columns their own way.
+fn project_by_name(
+    schema: &SchemaRef,
+    projection: Option<&Vec<usize>>,
+    plan: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let plan_schema = plan.schema();
+    let indices = match projection {
+        Some(projection) => projection.clone(),
+        None => (0..schema.fields().len()).collect(),
+    };
+    let exprs = indices
+        .into_iter()
+        .map(|idx| {
+            let name = schema.field(idx).name();
+            let column = Column::new_with_schema(name, &plan_schema)?;
+            Ok((Arc::new(column) as Arc<dyn PhysicalExpr>, name.clone()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(ProjectionExec::try_new(exprs, plan)?))
+}
+
+/// The `fts_search('table', 'query' [, 'column'])` table function, the rows of
+/// a Lance namespace table matching a full text search query.
+///
+/// The rows have the columns of the table and a `_score` column with their
+/// BM25 score. Without a column, all columns with an inverted index are
+/// searched. The search is answered by the inverted indices rather than by
+/// scanning the text, and a `LIMIT` without an `ORDER BY` is pushed into it.
+///
+/// ```sql
+/// SELECT item_id, _score FROM fts_search('retail.sales.items', 'red shoes')
+///     ORDER BY _score DESC LIMIT 10;
+/// SELECT * FROM fts_search('retail.sales.items', 'red shoes', 'description')
+///     WHERE price < 100;
+/// ```
+///
+/// Like [`LanceVersionUDTF`], this requires a multi-threaded Tokio runtime.
+#[derive(Debug)]
+pub struct LanceFullTextSearchUDTF {
+    tables: TableResolver,
+}
+
+impl LanceFullTextSearchUDTF {
+    /// Resolve tables in `catalog_list`, unqualified names are looked up in the
+    /// given default catalog and schema.
+    pub fn new(
+        catalog_list: Arc<dyn CatalogProviderList>,
+        default_catalog: &str,
+        default_schema: &str,
+    ) -> Self {
+        Self {
+            tables: TableResolver::new(catalog_list, default_catalog, default_schema),
+        }
+    }
+}
+
+impl TableFunctionImpl for LanceFullTextSearchUDTF {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        let (table, query, column) = match args {
+            [table, query] => (table, query, None),
+            [table, query, column] => (table, query, Some(column)),
+            _ => {
+                return plan_err!("fts_search takes a table name, a query and optionally a column");
+            }
+        };
+        let (schema, table_ref) = self.tables.resolve("fts_search", table)?;
+        let schema = as_lance_schema(&schema);
+        let simplifier = ExprSimplifier::new(SimplifyContext::default());
+        let query = string_arg("fts_search query", &simplifier.simplify(query.clone())?)?;
+        let mut query = FullTextSearchQuery::new(query);
+        if let Some(column) = column {
+            let column = string_arg("fts_search column", column)?;
+            query = query.with_column(column).map_err(to_datafusion_error)?;
+        }
+
+        let dataset = block_on("fts_search", lance_dataset(schema, &table_ref.table))??;
+        Ok(Arc::new(FullTextSearchTable::new(dataset, query)))
+    }
+}
+
+/// The rows of a Lance dataset matching a full text search query.
+#[derive(Debug)]
+struct FullTextSearchTable {
+    dataset: Arc<Dataset>,
+    query: FullTextSearchQuery,
+    schema: SchemaRef,
+}
+
+impl FullTextSearchTable {
+    fn new(dataset: Arc<Dataset>, query: FullTextSearchQuery) -> Self {
+        let mut fields = Schema::from(dataset.schema()).fields().to_vec();
+        fields.push(Arc::new(Field::new(SCORE_COL, DataType::Float32, true)));
+        Self {
+            dataset,
+            query,
+            schema: Arc::new(Schema::new(fields)),
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for FullTextSearchTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let query = self.query.clone().limit(limit.map(|limit| limit as i64));
+        let mut scan = self.dataset.scan();
+        scan.full_text_search(query)?;
+        let plan = scan.create_plan().await?;
+        project_by_name(&self.schema, projection, plan)
+    }
+}
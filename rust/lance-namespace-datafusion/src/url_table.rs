@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Lance datasets queried by their URL.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::catalog::{TableProvider, UrlTableFactory};
+use datafusion::common::plan_err;
+use datafusion::error::Result;
+use lance::Error;
+use lance::datafusion::LanceTableProvider;
+use lance::dataset::builder::DatasetBuilder;
+use lance::session::Session;
+
+use crate::config::LanceConfig;
+use crate::error::to_datafusion_error;
+use crate::schema::SessionHandle;
+
+/// A [`UrlTableFactory`] that reads the Lance dataset at a URL, so datasets
+/// outside of the namespaces can be queried by their location:
+///
+/// ```sql
+/// SELECT * FROM 's3://bucket/path/orders.lance';
+/// SELECT * FROM '/data/orders.lance/';
+/// SELECT * FROM '/data/orders.lance?version=12';
+/// SELECT * FROM '/data/orders.lance?tag=nightly';
+/// ```
+///
+/// The URL is a local path or an object store URI whose last path segment
+/// ends with `.lance`, optionally followed by a slash. The `version` and `tag`
+/// query parameters read an earlier version of the dataset, other query
+/// parameters are rejected. Names that are not Lance URLs are left to other
+/// tables, as are URLs where no dataset exists.
+///
+/// Datasets are opened with the storage options set as `lance.storage.<key>`
+/// in the session given with [`Self::with_session`], see [`LanceConfig`], so
+/// object stores can be configured per session instead of with environment
+/// variables.
+///
+/// Namespace sessions resolve URLs with [`SessionBuilder::enable_url_table`],
+/// other sessions can use the factory with DataFusion's
+/// [`DynamicFileCatalog`](datafusion::catalog::DynamicFileCatalog).
+///
+/// [`SessionBuilder::enable_url_table`]: crate::SessionBuilder::enable_url_table
+#[derive(Debug, Clone, Default)]
+pub struct LanceUrlTableFactory {
+    session: Option<SessionHandle>,
+    lance_session: Option<Arc<Session>>,
+}
+
+impl LanceUrlTableFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the storage options from the settings of the given session.
+    pub fn with_session(mut self, session: SessionHandle) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Open datasets with the given Lance session, so they share its caches
+    /// and object stores.
+    pub fn with_lance_session(mut self, session: Arc<Session>) -> Self {
+        self.lance_session = Some(session);
+        self
+    }
+
+    /// The `lance.storage.*` settings of the session.
+    fn storage_options(&self) -> HashMap<String, String> {
+        let Some(state) = self.session.as_ref().and_then(SessionHandle::state) else {
+            return HashMap::new();
+        };
+        state
+            .config()
+            .options()
+            .extensions
+            .get::<LanceConfig>()
+            .map(|config| {
+                config
+                    .storage_options()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// The version of a dataset named by a URL query parameter.
+#[derive(Debug, PartialEq, Eq)]
+enum UrlVersion {
+    Number(u64),
+    Tag(String),
+}
+
+/// A Lance dataset URL split into the dataset URI and the version to read.
+#[derive(Debug, PartialEq, Eq)]
+struct LanceUrl {
+    uri: String,
+    version: Option<UrlVersion>,
+}
+
+impl LanceUrl {
+    /// Parse `url`, `None` if it does not name a Lance dataset.
+    fn parse(url: &str) -> Result<Option<Self>> {
+        let (path, query) = match url.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (url, None),
+        };
+        let path = path.strip_suffix('/').unwrap_or(path);
+        if !path.ends_with(".lance") {
+            return Ok(None);
+        }
+
+        let mut version = None;
+        for param in query.into_iter().flat_map(|query| query.split('&')) {
+            if param.is_empty() {
+                continue;
+            }
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            let parsed = match key {
+                "version" => match value.parse() {
+                    Ok(number) => UrlVersion::Number(number),
+                    Err(_) => return plan_err!("Version {value} of {path} is not a number"),
+                },
+                "tag" if !value.is_empty() => UrlVersion::Tag(value.to_string()),
+                "tag" => return plan_err!("Tag of {path} is empty"),
+                _ => return plan_err!("Unknown query parameter {key} in {url}"),
+            };
+            if version.replace(parsed).is_some() {
+                return plan_err!("{url} names more than one version");
+            }
+        }
+        Ok(Some(Self {
+            uri: path.to_string(),
+            version,
+        }))
+    }
+}
+
+#[async_trait]
+impl UrlTableFactory for LanceUrlTableFactory {
+    async fn try_new(&self, url: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        let Some(url) = LanceUrl::parse(url)? else {
+            return Ok(None);
+        };
+        let builder =
+            DatasetBuilder::from_uri(&url.uri).with_storage_options(self.storage_options());
+        let builder = match &self.lance_session {
+            Some(session) => builder.with_session(Arc::clone(session)),
+            None => builder,
+        };
+        let builder = match &url.version {
+            Some(UrlVersion::Number(version)) => builder.with_version(*version),
+            Some(UrlVersion::Tag(tag)) => builder.with_tag(tag),
+            None => builder,
+        };
+        let dataset = match builder.load().await {
+            Ok(dataset) => dataset,
+            Err(Error::DatasetNotFound { .. }) => return Ok(None),
+            Err(err) => return Err(to_datafusion_error(err)),
+        };
+        let table_provider = LanceTableProvider::builder(Arc::new(dataset))
+            .build()
+            .await
+            .map_err(to_datafusion_error)?;
+        Ok(Some(Arc::new(table_provider)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Option<LanceUrl> {
+        LanceUrl::parse(url).unwrap()
+    }
+
+    #[test]
+    fn test_parse_lance_url() {
+        assert_eq!(parse("orders"), None);
+        assert_eq!(parse("/data/orders.csv"), None);
+        assert_eq!(
+            parse("s3://bucket/orders.lance"),
+            Some(LanceUrl {
+                uri: "s3://bucket/orders.lance".to_string(),
+                version: None,
+            })
+        );
+        assert_eq!(
+            parse("/data/orders.lance/"),
+            Some(LanceUrl {
+                uri: "/data/orders.lance".to_string(),
+                version: None,
+            })
+        );
+        assert_eq!(
+            parse("/data/orders.lance/?version=12"),
+            Some(LanceUrl {
+                uri: "/data/orders.lance".to_string(),
+                version: Some(UrlVersion::Number(12)),
+            })
+        );
+        assert_eq!(
+            parse("gs://bucket/orders.lance?tag=nightly"),
+            Some(LanceUrl {
+                uri: "gs://bucket/orders.lance".to_string(),
+                version: Some(UrlVersion::Tag("nightly".to_string())),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_lance_url() {
+        for url in [
+            "/data/orders.lance?version=latest",
+            "/data/orders.lance?tag=",
+            "/data/orders.lance?version=1&tag=nightly",
+            "/data/orders.lance?branch=dev",
+        ] {
+            assert!(LanceUrl::parse(url).is_err(), "{url}");
+        }
+    }
+}
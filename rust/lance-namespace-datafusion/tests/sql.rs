@@ -2,17 +2,37 @@
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
 use std::sync::Arc;
+use std::time::Duration;
 
-use arrow_array::{Int32Array, Int64Array, RecordBatch, RecordBatchIterator, StringArray};
-use arrow_schema::Schema;
+use arrow_array::{
+    BinaryArray, BooleanArray, FixedSizeListArray, Float32Array, Int32Array, Int64Array,
+    RecordBatch, RecordBatchIterator, StringArray, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema};
+use async_trait::async_trait;
+use datafusion::arrow::compute::concat_batches;
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::catalog::memory::{MemoryCatalogProvider, MemorySchemaProvider};
+use datafusion::catalog::{CatalogProvider, CatalogProviderList, SchemaProvider};
 use datafusion::common::record_batch;
+use datafusion::common::stats::Precision;
+use datafusion::datasource::MemTable;
 use datafusion::error::{DataFusionError, Result as DFResult};
-use datafusion::prelude::SessionContext;
+use datafusion::prelude::{SessionConfig, SessionContext};
 use lance::Dataset;
+use lance::datafusion::LanceTableProvider;
 use lance::dataset::{WriteMode, WriteParams};
+use lance::index::DatasetIndexExt;
+use lance::io::ObjectStoreRegistry;
+use lance::session::Session;
 use lance_namespace::LanceNamespace;
-use lance_namespace::models::CreateNamespaceRequest;
-use lance_namespace_datafusion::{NamespaceLevel, SessionBuilder};
+use lance_namespace::models::{
+    CreateNamespaceRequest, ListNamespacesRequest, ListNamespacesResponse, NamespaceExistsRequest,
+};
+use lance_namespace_datafusion::{
+    CatalogDiscovery, DiscoveryErrors, LanceCatalogProvider, LanceCatalogProviderList,
+    LanceSchemaProvider, LanceSqlExt, NamespaceLevel, SessionBuilder,
+};
 use lance_namespace_impls::DirectoryNamespaceBuilder;
 use tempfile::TempDir;
 
@@ -75,6 +95,97 @@ fn customers_dim_data() -> (Arc<Schema>, RecordBatch) {
     (schema, batch)
 }
 
+fn items_data() -> (Arc<Schema>, RecordBatch) {
+    const DIM: i32 = 8;
+    const NUM_ROWS: i32 = 512;
+    let item_field = Arc::new(Field::new("item", DataType::Float32, true));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("item_id", DataType::Int32, false),
+        Field::new(
+            "embedding",
+            DataType::FixedSizeList(Arc::clone(&item_field), DIM),
+            false,
+        ),
+    ]));
+    let values = Float32Array::from_iter_values((0..NUM_ROWS * DIM).map(|v| (v % 97) as f32));
+    let embeddings = FixedSizeListArray::try_new(item_field, DIM, Arc::new(values), None).unwrap();
+    let batch = RecordBatch::try_new(
+        Arc::clone(&schema),
+        vec![
+            Arc::new(Int32Array::from_iter_values(0..NUM_ROWS)),
+            Arc::new(embeddings),
+        ],
+    )
+    .unwrap();
+
+    (schema, batch)
+}
+
+/// Little-endian WKB for a point.
+fn point_wkb(x: f64, y: f64) -> Vec<u8> {
+    let mut wkb = vec![1, 1, 0, 0, 0];
+    wkb.extend(x.to_le_bytes());
+    wkb.extend(y.to_le_bytes());
+    wkb
+}
+
+/// Little-endian WKB for a polygon with a single ring.
+fn polygon_wkb(ring: &[(f64, f64)]) -> Vec<u8> {
+    let mut wkb = vec![1, 3, 0, 0, 0];
+    wkb.extend(1u32.to_le_bytes());
+    wkb.extend((ring.len() as u32).to_le_bytes());
+    for (x, y) in ring {
+        wkb.extend(x.to_le_bytes());
+        wkb.extend(y.to_le_bytes());
+    }
+    wkb
+}
+
+fn stores_data() -> (Arc<Schema>, RecordBatch) {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("store_id", DataType::Int32, false),
+        Field::new("geom", DataType::Binary, true),
+    ]));
+    let points = [(1.0, 1.0), (3.0, 3.0), (12.0, 12.0), (50.0, 50.0)]
+        .into_iter()
+        .map(|(x, y)| Some(point_wkb(x, y)))
+        .chain([None])
+        .collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(
+        Arc::clone(&schema),
+        vec![
+            Arc::new(Int32Array::from_iter_values(1..=5)),
+            Arc::new(BinaryArray::from_iter(points)),
+        ],
+    )
+    .unwrap();
+
+    (schema, batch)
+}
+
+fn zones_data() -> (Arc<Schema>, RecordBatch) {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("geom", DataType::Binary, false),
+    ]));
+    let square = |min: f64, max: f64| {
+        polygon_wkb(&[(min, min), (max, min), (max, max), (min, max), (min, min)])
+    };
+    let batch = RecordBatch::try_new(
+        Arc::clone(&schema),
+        vec![
+            Arc::new(StringArray::from(vec!["downtown", "uptown"])),
+            Arc::new(BinaryArray::from_iter_values([
+                square(0.0, 5.0),
+                square(10.0, 20.0),
+            ])),
+        ],
+    )
+    .unwrap();
+
+    (schema, batch)
+}
+
 async fn write_table(
     dir: &TempDir,
     file_name: &str,
@@ -101,6 +212,10 @@ async fn write_table(
 }
 
 async fn setup_test_context() -> DFResult<Context> {
+    setup_test_context_with_discovery(CatalogDiscovery::Eager).await
+}
+
+async fn setup_test_context_with_discovery(discovery: CatalogDiscovery) -> DFResult<Context> {
     let root_dir = TempDir::new()?;
     let extra_dir = TempDir::new()?;
 
@@ -122,6 +237,33 @@ async fn setup_test_context() -> DFResult<Context> {
     )
     .await?;
 
+    let (items_schema, items_batch) = items_data();
+    write_table(
+        &root_dir,
+        "retail$sales$items.lance",
+        items_schema,
+        items_batch,
+    )
+    .await?;
+
+    let (stores_schema, stores_batch) = stores_data();
+    write_table(
+        &root_dir,
+        "retail$sales$stores.lance",
+        stores_schema,
+        stores_batch,
+    )
+    .await?;
+
+    let (zones_schema, zones_batch) = zones_data();
+    write_table(
+        &root_dir,
+        "retail$sales$zones.lance",
+        zones_schema,
+        zones_batch,
+    )
+    .await?;
+
     let (orders2_schema, orders2_batch) = orders2_data();
     write_table(
         &root_dir,
@@ -166,6 +308,13 @@ async fn setup_test_context() -> DFResult<Context> {
 
     let mut create_sales = CreateNamespaceRequest::new();
     create_sales.id = Some(vec!["retail".to_string(), "sales".to_string()]);
+    create_sales.properties = Some(
+        [(
+            "view.big_orders".to_string(),
+            "SELECT * FROM retail.sales.orders WHERE amount > 100".to_string(),
+        )]
+        .into(),
+    );
     root_dir_ns
         .create_namespace(create_sales)
         .await
@@ -213,6 +362,7 @@ async fn setup_test_context() -> DFResult<Context> {
     let extra_ns: Arc<dyn LanceNamespace> = Arc::new(extra_dir_ns);
 
     let ctx = SessionBuilder::new()
+        .with_catalog_discovery(discovery)
         .with_root(NamespaceLevel::from_root(Arc::clone(&root_ns)))
         .add_catalog(
             "crm",
@@ -378,3 +528,1171 @@ async fn cte_view_customer_orders() -> DFResult<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn spatial_join_points_in_polygons() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let sql = "SELECT s.store_id, z.name \
+               FROM retail.sales.stores s \
+               JOIN retail.sales.zones z \
+                 ON st_contains(z.geom, s.geom) \
+               ORDER BY s.store_id";
+
+    // The join is planned as a spatial join rather than a nested loop join.
+    let plan = ns
+        .ctx
+        .sql(&format!("EXPLAIN {sql}"))
+        .await?
+        .collect()
+        .await?;
+    let plan = pretty_format_batches(&plan)?.to_string();
+    assert!(plan.contains("SpatialJoinExec"), "{plan}");
+    assert!(!plan.contains("NestedLoopJoinExec"), "{plan}");
+
+    let batches = ns.ctx.sql(sql).await?.collect().await?;
+    let batch = concat_batches(&batches[0].schema(), &batches)?;
+    assert_eq!(
+        col::<Int32Array>(&batch, 0),
+        &Int32Array::from(vec![1, 2, 3])
+    );
+    assert_eq!(
+        col::<StringArray>(&batch, 1),
+        &StringArray::from(vec!["downtown", "downtown", "uptown"])
+    );
+
+    Ok(())
+}
+
+async fn index_names(ctx: &SessionContext, table: &str) -> DFResult<Vec<String>> {
+    let provider = ctx.table_provider(table).await?;
+    let dataset = provider
+        .as_any()
+        .downcast_ref::<LanceTableProvider>()
+        .unwrap()
+        .dataset();
+    let indices = dataset
+        .load_indices()
+        .await
+        .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+    let mut names = indices
+        .iter()
+        .map(|index| index.name.clone())
+        .collect::<Vec<_>>();
+    names.sort();
+    Ok(names)
+}
+
+#[tokio::test]
+async fn create_index_statements() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+
+    ns.ctx
+        .lance_sql("CREATE INDEX order_id_idx ON retail.sales.orders USING BTREE (order_id)")
+        .await?
+        .collect()
+        .await?;
+    ns.ctx
+        .lance_sql(
+            "CREATE INDEX emb_idx ON retail.sales.items USING IVF_PQ (embedding) \
+             WITH (num_partitions = 2, num_sub_vectors = 2, distance_type = 'l2')",
+        )
+        .await?
+        .collect()
+        .await?;
+
+    assert_eq!(
+        index_names(&ns.ctx, "retail.sales.orders").await?,
+        vec!["order_id_idx"]
+    );
+    assert_eq!(
+        index_names(&ns.ctx, "retail.sales.items").await?,
+        vec!["emb_idx"]
+    );
+
+    // Re-creating an existing index fails unless IF NOT EXISTS is given.
+    assert!(
+        ns.ctx
+            .lance_sql("CREATE INDEX order_id_idx ON retail.sales.orders USING BTREE (order_id)")
+            .await
+            .is_err()
+    );
+    ns.ctx
+        .lance_sql(
+            "CREATE INDEX IF NOT EXISTS order_id_idx ON retail.sales.orders USING BTREE (order_id)",
+        )
+        .await?;
+
+    // IF NOT EXISTS still builds indices that don't exist yet.
+    ns.ctx
+        .lance_sql(
+            "CREATE INDEX IF NOT EXISTS customer_idx ON retail.sales.orders USING BTREE (customer_id)",
+        )
+        .await?;
+    assert_eq!(
+        index_names(&ns.ctx, "retail.sales.orders").await?,
+        vec!["customer_idx", "order_id_idx"]
+    );
+
+    // Unknown index options are rejected before any index is built.
+    assert!(
+        ns.ctx
+            .lance_sql(
+                "CREATE INDEX ON retail.sales.items USING IVF_FLAT (embedding) WITH (bogus = 1)"
+            )
+            .await
+            .is_err()
+    );
+
+    // Other statements fall through to DataFusion and see the new version.
+    let batches = ns
+        .ctx
+        .lance_sql("SELECT amount FROM retail.sales.orders WHERE order_id = 102")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(col::<Int32Array>(&batches[0], 0).value(0), 200);
+
+    Ok(())
+}
+
+async fn order_ids(ctx: &SessionContext, table: &str) -> DFResult<Vec<i32>> {
+    let batches = ctx
+        .sql(&format!("SELECT order_id FROM {table} ORDER BY order_id"))
+        .await?
+        .collect()
+        .await?;
+    Ok(batches
+        .iter()
+        .flat_map(|batch| col::<Int32Array>(batch, 0).values().to_vec())
+        .collect())
+}
+
+#[tokio::test]
+async fn insert_into_select() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+
+    // Copy rows across catalogs, the count of written rows is returned.
+    let batches = ns
+        .ctx
+        .sql(
+            "INSERT INTO retail.sales.orders \
+             SELECT order_id, customer_id, amount FROM wholesale.sales2.orders2",
+        )
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(col::<arrow_array::UInt64Array>(&batches[0], 0).value(0), 2);
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103, 201, 202]
+    );
+
+    ns.ctx
+        .sql("INSERT INTO retail.sales.orders VALUES (104, 3, 400)")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103, 104, 201, 202]
+    );
+
+    // Each insert is committed as a new version of the underlying dataset.
+    let uri = ns.root_dir.path().join("retail$sales$orders.lance");
+    let dataset = Dataset::open(uri.to_str().unwrap())
+        .await
+        .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+    assert_eq!(dataset.version().version, 3);
+    assert_eq!(
+        dataset
+            .count_rows(None)
+            .await
+            .map_err(|e| DataFusionError::Execution(e.to_string()))?,
+        6
+    );
+
+    // Mismatched columns are rejected at planning time.
+    assert!(
+        ns.ctx
+            .sql("INSERT INTO retail.sales.orders SELECT name FROM retail.sales.customers")
+            .await
+            .is_err()
+    );
+
+    // INSERT OVERWRITE replaces the rows of the table.
+    ns.ctx
+        .sql("INSERT OVERWRITE retail.sales.orders SELECT * FROM wholesale.sales2.orders2")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![201, 202]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_and_update() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+
+    // The number of affected rows is returned.
+    let batches = ns
+        .ctx
+        .lance_sql("DELETE FROM retail.sales.orders WHERE amount < 200")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(col::<arrow_array::UInt64Array>(&batches[0], 0).value(0), 1);
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![102, 103]
+    );
+
+    let batches = ns
+        .ctx
+        .lance_sql("UPDATE retail.sales.orders SET order_id = order_id + 100 WHERE customer_id = 3")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(col::<arrow_array::UInt64Array>(&batches[0], 0).value(0), 1);
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![102, 203]
+    );
+
+    // Without a WHERE clause every row is affected.
+    ns.ctx
+        .lance_sql("UPDATE retail.sales.orders SET amount = 0")
+        .await?
+        .collect()
+        .await?;
+    let batches = ns
+        .ctx
+        .sql("SELECT SUM(amount) FROM retail.sales.orders")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(col::<arrow_array::Int64Array>(&batches[0], 0).value(0), 0);
+    ns.ctx
+        .lance_sql("DELETE FROM retail.sales.orders")
+        .await?
+        .collect()
+        .await?;
+    assert!(order_ids(&ns.ctx, "retail.sales.orders").await?.is_empty());
+
+    // Each statement is committed as a new version of the underlying dataset.
+    let uri = ns.root_dir.path().join("retail$sales$orders.lance");
+    let dataset = Dataset::open(uri.to_str().unwrap())
+        .await
+        .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+    assert_eq!(dataset.version().version, 5);
+
+    // Joins are rejected.
+    assert!(
+        ns.ctx
+            .lance_sql(
+                "UPDATE retail.sales.orders SET amount = 1 \
+                 FROM retail.sales.customers WHERE orders.customer_id = customers.customer_id",
+            )
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+// Table functions are resolved synchronously, loading the version blocks the
+// planning thread.
+#[tokio::test(flavor = "multi_thread")]
+async fn copy_to() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let export_dir = TempDir::new()?;
+    let lance_path = export_dir.path().join("big_orders.lance");
+    let lance_path = lance_path.to_string_lossy();
+
+    let copy =
+        format!("COPY (SELECT * FROM retail.sales.orders WHERE amount > 100) TO '{lance_path}'");
+    let batches = ns.ctx.lance_sql(&copy).await?.collect().await?;
+    assert_eq!(col::<arrow_array::UInt64Array>(&batches[0], 0).value(0), 2);
+    let dataset = Dataset::open(&lance_path)
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    assert_eq!(dataset.count_rows(None).await.unwrap(), 2);
+
+    // The dataset already exists, rows are only added in append mode.
+    assert!(ns.ctx.lance_sql(&copy).await.is_err());
+    ns.ctx
+        .lance_sql(&format!(
+            "COPY retail.sales.orders TO '{lance_path}' STORED AS LANCE OPTIONS (mode append)"
+        ))
+        .await?
+        .collect()
+        .await?;
+    let dataset = Dataset::open(&lance_path)
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    assert_eq!(dataset.count_rows(None).await.unwrap(), 5);
+
+    // Other formats are written by DataFusion.
+    let csv_path = format!("{}/orders/", export_dir.path().to_string_lossy());
+    ns.ctx
+        .lance_sql(&format!(
+            "COPY retail.sales.orders TO '{csv_path}' OPTIONS (format csv)"
+        ))
+        .await?
+        .collect()
+        .await?;
+    let count = ns
+        .ctx
+        .read_csv(csv_path, Default::default())
+        .await?
+        .count()
+        .await?;
+    assert_eq!(count, 3);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_search() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+
+    // Every 97th item has the embedding of item 0.
+    let batches = ns
+        .ctx
+        .sql(
+            "SELECT item_id, _distance FROM \
+             vector_search('retail.sales.items', [0, 1, 2, 3, 4, 5, 6, 7], 3) \
+             ORDER BY item_id",
+        )
+        .await?
+        .collect()
+        .await?;
+    let batch = concat_batches(&batches[0].schema(), &batches)?;
+    assert_eq!(batch.num_rows(), 3);
+    for item_id in col::<Int32Array>(&batch, 0).values() {
+        assert_eq!(item_id % 97, 0);
+    }
+    assert!(
+        col::<Float32Array>(&batch, 1)
+            .values()
+            .iter()
+            .all(|distance| *distance == 0.0)
+    );
+
+    // Filters apply to the nearest rows.
+    let batches = ns
+        .ctx
+        .sql(
+            "SELECT item_id FROM \
+             vector_search('retail.sales.items', [0, 1, 2, 3, 4, 5, 6, 7], 3, 'embedding') \
+             WHERE item_id > 1000",
+        )
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 0);
+
+    assert!(
+        ns.ctx
+            .sql("SELECT * FROM vector_search('retail.sales.items', [0, 1], 3, 'item_id')")
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fts_search() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    ns.ctx
+        .lance_sql("CREATE INDEX ON retail.sales.customers USING INVERTED (name)")
+        .await?
+        .collect()
+        .await?;
+
+    let batches = ns
+        .ctx
+        .sql(
+            "SELECT customer_id, city, _score FROM \
+             fts_search('retail.sales.customers', 'bob', 'name')",
+        )
+        .await?
+        .collect()
+        .await?;
+    let batch = concat_batches(&batches[0].schema(), &batches)?;
+    assert_eq!(batch.num_rows(), 1);
+    assert_eq!(col::<Int32Array>(&batch, 0).value(0), 2);
+    assert_eq!(col::<StringArray>(&batch, 1).value(0), "SF");
+    assert!(col::<Float32Array>(&batch, 2).value(0) > 0.0);
+
+    // The column may be omitted, indexed columns are searched.
+    let batches = ns
+        .ctx
+        .sql(
+            "SELECT customer_id FROM fts_search('retail.sales.customers', 'carol alice') \
+             ORDER BY customer_id",
+        )
+        .await?
+        .collect()
+        .await?;
+    let ids = batches
+        .iter()
+        .flat_map(|batch| col::<Int32Array>(batch, 0).values().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![1, 3]);
+
+    assert!(
+        ns.ctx
+            .sql("SELECT * FROM fts_search('retail.sales.customers', 'bob', 'missing')")
+            .await?
+            .collect()
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn time_travel() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    ns.ctx
+        .sql("INSERT INTO retail.sales.orders VALUES (104, 3, 400)")
+        .await?
+        .collect()
+        .await?;
+
+    assert_eq!(
+        order_ids(&ns.ctx, "lance_version('retail.sales.orders', 1)").await?,
+        vec![101, 102, 103]
+    );
+    assert_eq!(
+        order_ids(&ns.ctx, "lance_version('retail.sales.orders', 2)").await?,
+        vec![101, 102, 103, 104]
+    );
+    // A timestamp reads the last version committed at or before it.
+    assert_eq!(
+        order_ids(
+            &ns.ctx,
+            "lance_version('retail.sales.orders', '2100-01-01T00:00:00Z')"
+        )
+        .await?,
+        vec![101, 102, 103, 104]
+    );
+    assert!(
+        order_ids(
+            &ns.ctx,
+            "lance_version('retail.sales.orders', '2000-01-01T00:00:00Z')"
+        )
+        .await
+        .is_err()
+    );
+    assert!(
+        order_ids(&ns.ctx, "lance_version('retail.sales.orders', 3)")
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn versions_table() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    ns.ctx
+        .sql("INSERT INTO retail.sales.orders VALUES (104, 3, 400)")
+        .await?
+        .collect()
+        .await?;
+
+    let batches = ns
+        .ctx
+        .sql(
+            "SELECT version, is_live FROM retail.sales.\"orders$versions\" \
+             WHERE operation_type = 'Append' ORDER BY version",
+        )
+        .await?
+        .collect()
+        .await?;
+    let batch = concat_batches(&batches[0].schema(), &batches)?;
+    assert_eq!(col::<UInt64Array>(&batch, 0).values().to_vec(), vec![2]);
+    assert!(col::<BooleanArray>(&batch, 1).value(0));
+
+    let batches = ns
+        .ctx
+        .sql("SELECT count(*) FROM retail.sales.\"orders$versions\"")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(col::<Int64Array>(&batches[0], 0).value(0), 2);
+
+    let schema = ns.ctx.catalog("retail").unwrap().schema("sales").unwrap();
+    assert!(schema.table_exist("orders$versions"));
+    assert!(
+        ns.ctx
+            .sql("SELECT * FROM retail.sales.\"missing$versions\"")
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn namespace_views() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.big_orders").await?,
+        vec![102, 103]
+    );
+
+    // Views are planned on every use, so they see new rows of their tables.
+    ns.ctx
+        .sql("INSERT INTO retail.sales.orders VALUES (104, 3, 400)")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.big_orders").await?,
+        vec![102, 103, 104]
+    );
+
+    let schema = ns.ctx.catalog("retail").unwrap().schema("sales").unwrap();
+    assert!(schema.table_exist("big_orders"));
+    assert!(schema.table_names().contains(&"big_orders".to_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_and_drop_table() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+
+    ns.ctx
+        .lance_sql("CREATE TABLE retail.sales.returns (order_id INT, amount INT)")
+        .await?;
+    ns.ctx
+        .sql("INSERT INTO retail.sales.returns VALUES (101, 100)")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(order_ids(&ns.ctx, "retail.sales.returns").await?, vec![101]);
+
+    // Creating an existing table fails unless IF NOT EXISTS or OR REPLACE is given.
+    assert!(
+        ns.ctx
+            .lance_sql("CREATE TABLE retail.sales.returns (order_id INT)")
+            .await
+            .is_err()
+    );
+    ns.ctx
+        .lance_sql("CREATE TABLE IF NOT EXISTS retail.sales.returns (order_id INT)")
+        .await?;
+    assert_eq!(order_ids(&ns.ctx, "retail.sales.returns").await?, vec![101]);
+
+    // CREATE TABLE AS SELECT writes the rows of the query.
+    ns.ctx
+        .lance_sql(
+            "CREATE TABLE retail.sales.big_orders AS \
+             SELECT * FROM retail.sales.orders WHERE amount > 100",
+        )
+        .await?;
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.big_orders").await?,
+        vec![102, 103]
+    );
+    ns.ctx
+        .lance_sql(
+            "CREATE OR REPLACE TABLE retail.sales.big_orders AS \
+             SELECT * FROM retail.sales.orders WHERE amount > 200",
+        )
+        .await?;
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.big_orders").await?,
+        vec![103]
+    );
+
+    // The table is only replaced once the query has run, so it can read the
+    // table itself and a failing query leaves the table as it was.
+    ns.ctx
+        .lance_sql(
+            "CREATE OR REPLACE TABLE retail.sales.big_orders AS \
+             SELECT * FROM retail.sales.orders WHERE amount > 100",
+        )
+        .await?;
+    ns.ctx
+        .lance_sql(
+            "CREATE OR REPLACE TABLE retail.sales.big_orders AS \
+             SELECT * FROM retail.sales.big_orders WHERE order_id > 102",
+        )
+        .await?;
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.big_orders").await?,
+        vec![103]
+    );
+    assert!(
+        ns.ctx
+            .lance_sql(
+                "CREATE OR REPLACE TABLE retail.sales.big_orders AS \
+                 SELECT CAST(CAST(order_id AS VARCHAR) || 'x' AS INT) AS order_id \
+                 FROM retail.sales.orders",
+            )
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.big_orders").await?,
+        vec![103]
+    );
+
+    // Dropped tables are gone from the namespace.
+    ns.ctx.lance_sql("DROP TABLE retail.sales.returns").await?;
+    assert!(
+        ns.ctx
+            .sql("SELECT * FROM retail.sales.returns")
+            .await
+            .is_err()
+    );
+    assert!(
+        ns.ctx
+            .lance_sql("DROP TABLE retail.sales.returns")
+            .await
+            .is_err()
+    );
+    ns.ctx
+        .lance_sql("DROP TABLE IF EXISTS retail.sales.returns")
+        .await?;
+
+    // DataFusion's own DDL cannot register in-memory tables in a namespace.
+    assert!(
+        ns.ctx
+            .sql("CREATE TABLE retail.sales.mem (x INT)")
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn refresh_table_names() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let schema = ns.ctx.catalog("retail").unwrap().schema("sales").unwrap();
+    let schema = schema
+        .as_any()
+        .downcast_ref::<LanceSchemaProvider>()
+        .unwrap();
+
+    // Tables are only known once they are used or listed.
+    assert!(!schema.table_exist("customers"));
+    schema.refresh().await?;
+    let mut names = schema.table_names();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["customers", "items", "orders", "stores", "zones"]
+    );
+
+    // Tables created through the session are known right away.
+    ns.ctx
+        .lance_sql("CREATE TABLE retail.sales.returns (order_id INT, amount INT)")
+        .await?;
+    assert!(schema.table_exist("returns"));
+    ns.ctx.lance_sql("DROP TABLE retail.sales.returns").await?;
+    assert!(!schema.table_exist("returns"));
+
+    // Invalidated tables are loaded again on their next use.
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+    schema.invalidate("orders");
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+
+    Ok(())
+}
+
+/// Another handle to the root namespace of the test context.
+async fn open_root_namespace(ns: &Context) -> DFResult<Arc<dyn LanceNamespace>> {
+    let namespace =
+        DirectoryNamespaceBuilder::new(ns.root_dir.path().to_string_lossy().to_string())
+            .manifest_enabled(true)
+            .dir_listing_enabled(true)
+            .build()
+            .await
+            .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+    Ok(Arc::new(namespace))
+}
+
+#[tokio::test]
+async fn pinned_table_version() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let pinned_ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(open_root_namespace(&ns).await?))
+        .with_default_catalog("retail", None)
+        .pin_table_version("sales.orders", 1)
+        .build()
+        .await?;
+    ns.ctx
+        .sql("INSERT INTO retail.sales.orders VALUES (104, 3, 400)")
+        .await?
+        .collect()
+        .await?;
+
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103, 104]
+    );
+    assert_eq!(
+        order_ids(&pinned_ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+    // Other tables are read at their latest version.
+    assert!(pinned_ctx.table("retail.sales.customers").await.is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_staleness() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(open_root_namespace(&ns).await?))
+        .with_max_staleness(Duration::from_secs(3600))
+        .build()
+        .await?;
+    assert_eq!(
+        order_ids(&ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+
+    // Within the window the loaded version is used without checking for a newer one.
+    ctx.sql("INSERT INTO retail.sales.orders VALUES (104, 3, 400)")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(
+        order_ids(&ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+
+    // Writes through lance_sql are read right away.
+    ctx.lance_sql("DELETE FROM retail.sales.orders WHERE order_id = 101")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(
+        order_ids(&ctx, "retail.sales.orders").await?,
+        vec![102, 103, 104]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn table_statistics() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let orders = ns.ctx.table_provider("retail.sales.orders").await?;
+    let statistics = orders.statistics().unwrap();
+    assert_eq!(statistics.num_rows, Precision::Exact(3));
+    assert_eq!(statistics.column_statistics.len(), 3);
+
+    // Sessions that don't collect statistics don't read them.
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(open_root_namespace(&ns).await?))
+        .with_config(SessionConfig::new().with_collect_statistics(false))
+        .build()
+        .await?;
+    let orders = ctx.table_provider("retail.sales.orders").await?;
+    assert!(orders.statistics().is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn paginated_table_listing() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let root_ns = open_root_namespace(&ns).await?;
+    let sales = NamespaceLevel::from_root(root_ns)
+        .with_list_page_size(2)
+        .child("retail")
+        .child("sales");
+
+    // All pages are listed.
+    let schema = LanceSchemaProvider::new(sales.clone());
+    schema.refresh().await?;
+    let mut names = schema.table_names();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["customers", "items", "orders", "stores", "zones"]
+    );
+
+    // Listing stops at the maximum, unlisted tables can still be queried.
+    let schema = LanceSchemaProvider::new(sales.with_max_list_results(3));
+    schema.refresh().await?;
+    assert_eq!(schema.table_names().len(), 3);
+    assert!(schema.table("zones").await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn lazy_catalog_discovery() -> DFResult<()> {
+    let ns = setup_test_context_with_discovery(CatalogDiscovery::Lazy { ttl: None }).await?;
+    let catalog_list = ns.ctx.state().catalog_list().clone();
+    let catalog_list = catalog_list
+        .as_any()
+        .downcast_ref::<LanceCatalogProviderList>()
+        .unwrap();
+
+    // Nothing is listed up front, catalogs are resolved when used.
+    assert!(catalog_list.catalog_names().is_empty());
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+    assert_eq!(
+        order_ids(&ns.ctx, "wholesale.sales2.orders2").await?,
+        vec![201, 202]
+    );
+    let mut names = catalog_list.catalog_names();
+    names.sort();
+    assert_eq!(names, vec!["retail", "wholesale"]);
+
+    // Missing namespaces surface when their tables are loaded.
+    assert!(
+        ns.ctx
+            .sql("SELECT * FROM missing.sales.orders")
+            .await
+            .is_err()
+    );
+    // and are dropped from the cache once the background check completes.
+    for _ in 0..100 {
+        if !catalog_list
+            .catalog_names()
+            .contains(&"missing".to_string())
+        {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert!(
+        !catalog_list
+            .catalog_names()
+            .contains(&"missing".to_string())
+    );
+
+    let retail = catalog_list.catalog("retail").unwrap();
+    let retail = retail
+        .as_any()
+        .downcast_ref::<LanceCatalogProvider>()
+        .unwrap();
+    assert_eq!(retail.schema_names(), vec!["sales"]);
+    retail.refresh().await?;
+    assert!(retail.schema_names().is_empty());
+
+    catalog_list.refresh().await?;
+    assert!(catalog_list.catalog_names().is_empty());
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+
+    // A zero TTL resolves catalogs on every use.
+    let ns = setup_test_context_with_discovery(CatalogDiscovery::Lazy {
+        ttl: Some(std::time::Duration::ZERO),
+    })
+    .await?;
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+    assert_eq!(
+        order_ids(&ns.ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn url_tables() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let dir = TempDir::new()?;
+    let (schema, batch) = orders2_data();
+    write_table(&dir, "orders.lance", schema.clone(), batch.clone()).await?;
+    let path = dir.path().join("orders.lance");
+    let path = path.to_string_lossy();
+    Dataset::write(
+        RecordBatchIterator::new(vec![Ok(batch)], schema),
+        &path,
+        Some(WriteParams {
+            mode: WriteMode::Append,
+            ..Default::default()
+        }),
+    )
+    .await
+    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(open_root_namespace(&ns).await?))
+        .with_default_catalog("retail", None)
+        .with_default_schema("sales", None)
+        .enable_url_table()
+        .build()
+        .await?;
+    let count = |sql: String| {
+        let ctx = ctx.clone();
+        async move {
+            let batches = ctx.sql(&sql).await?.collect().await?;
+            DFResult::Ok(col::<Int64Array>(&batches[0], 0).value(0))
+        }
+    };
+    assert_eq!(count(format!("SELECT count(*) FROM '{path}'")).await?, 4);
+    assert_eq!(count(format!("SELECT count(*) FROM '{path}/'")).await?, 4);
+    assert_eq!(
+        count(format!("SELECT count(*) FROM '{path}?version=1'")).await?,
+        2
+    );
+    // Storage options are taken from the session settings.
+    ctx.sql("SET lance.storage.allow_http = 'true'")
+        .await?
+        .collect()
+        .await?;
+    assert_eq!(count(format!("SELECT count(*) FROM '{path}'")).await?, 4);
+    assert!(ctx.sql("SET lance.cache_size = 1").await.is_err());
+
+    // Namespace tables are still resolved.
+    assert_eq!(count("SELECT count(*) FROM orders".to_string()).await?, 3);
+
+    assert!(
+        ctx.sql(&format!("SELECT * FROM '{path}?version=latest'"))
+            .await
+            .is_err()
+    );
+    // URLs are only resolved when enabled.
+    assert!(
+        ns.ctx
+            .sql(&format!("SELECT * FROM '{path}'"))
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn register_table_writes_through() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let (schema, batch) = orders2_data();
+    let table = Arc::new(MemTable::try_new(schema, vec![vec![batch]])?);
+    ns.ctx
+        .register_table("retail.sales.new_orders", table.clone())?;
+    assert!(
+        ns.ctx
+            .register_table("retail.sales.new_orders", table)
+            .is_err()
+    );
+
+    // The table is in the namespace, so other sessions see it.
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(open_root_namespace(&ns).await?))
+        .build()
+        .await?;
+    assert_eq!(
+        order_ids(&ctx, "retail.sales.new_orders").await?,
+        vec![201, 202]
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn register_catalog_writes_through() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let root = open_root_namespace(&ns).await?;
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(root.clone()))
+        .with_write_through(true)
+        .build()
+        .await?;
+
+    let online = Arc::new(MemoryCatalogProvider::new());
+    online.register_schema("web", Arc::new(MemorySchemaProvider::new()))?;
+    ctx.register_catalog("online", online);
+    ctx.catalog("retail")
+        .unwrap()
+        .register_schema("marketing", Arc::new(MemorySchemaProvider::new()))?;
+
+    for id in [
+        vec!["online"],
+        vec!["online", "web"],
+        vec!["retail", "marketing"],
+    ] {
+        let request = NamespaceExistsRequest {
+            id: Some(id.iter().map(|name| name.to_string()).collect()),
+            ..Default::default()
+        };
+        root.namespace_exists(request)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    }
+
+    // Without write-through registrations only live in the session.
+    ns.ctx
+        .catalog("retail")
+        .unwrap()
+        .register_schema("support", Arc::new(MemorySchemaProvider::new()))?;
+    let request = NamespaceExistsRequest {
+        id: Some(vec!["retail".to_string(), "support".to_string()]),
+        ..Default::default()
+    };
+    assert!(root.namespace_exists(request).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn shared_lance_session() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let session = Arc::new(Session::default());
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(open_root_namespace(&ns).await?))
+        .with_lance_session(session.clone())
+        .build()
+        .await?;
+    assert_eq!(
+        order_ids(&ctx, "retail.sales.orders").await?,
+        vec![101, 102, 103]
+    );
+    assert!(session.approx_num_items() > 0);
+
+    let registry = Arc::new(ObjectStoreRegistry::default());
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(open_root_namespace(&ns).await?))
+        .with_object_store_registry(registry.clone())
+        .build()
+        .await?;
+    ctx.sql("SELECT * FROM retail.sales.orders JOIN retail.sales.customers USING (customer_id)")
+        .await?
+        .collect()
+        .await?;
+    let stats = registry.stats();
+    assert!(stats.hits + stats.misses > 0);
+
+    Ok(())
+}
+
+/// A namespace whose listing of one child namespace fails.
+#[derive(Debug)]
+struct BrokenChildNamespace {
+    inner: Arc<dyn LanceNamespace>,
+    broken: Vec<String>,
+}
+
+#[async_trait]
+impl LanceNamespace for BrokenChildNamespace {
+    async fn list_namespaces(
+        &self,
+        request: ListNamespacesRequest,
+    ) -> lance::Result<ListNamespacesResponse> {
+        if request.id.as_ref() == Some(&self.broken) {
+            return Err(lance::Error::io("namespace is unavailable"));
+        }
+        self.inner.list_namespaces(request).await
+    }
+
+    fn namespace_id(&self) -> String {
+        self.inner.namespace_id()
+    }
+}
+
+#[tokio::test]
+async fn concurrent_discovery() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let root = open_root_namespace(&ns).await?;
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(root.clone()))
+        .with_discovery_concurrency(1)
+        .build()
+        .await?;
+    let mut names = ctx.catalog_names();
+    names.sort();
+    assert_eq!(names, vec!["retail", "wholesale"]);
+
+    let broken: Arc<dyn LanceNamespace> = Arc::new(BrokenChildNamespace {
+        inner: root,
+        broken: vec!["wholesale".to_string()],
+    });
+    assert!(
+        SessionBuilder::new()
+            .with_root(NamespaceLevel::from_root(broken.clone()))
+            .build()
+            .await
+            .is_err()
+    );
+
+    // Lenient discovery leaves the broken catalog out.
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(broken))
+        .with_discovery_errors(DiscoveryErrors::Lenient)
+        .build()
+        .await?;
+    let mut names = ctx.catalog_names();
+    names.sort();
+    assert_eq!(names, vec!["retail"]);
+
+    Ok(())
+}
+
+async fn row_count(ctx: &SessionContext, sql: &str) -> DFResult<i64> {
+    let batches = ctx.sql(sql).await?.collect().await?;
+    Ok(col::<Int64Array>(&batches[0], 0).value(0))
+}
+
+#[tokio::test]
+async fn case_insensitive_names_and_aliases() -> DFResult<()> {
+    let ns = setup_test_context().await?;
+    let root = open_root_namespace(&ns).await?;
+    let alias = |id: &[&str]| id.iter().map(|part| part.to_string()).collect::<Vec<_>>();
+    let ctx = SessionBuilder::new()
+        .with_root(
+            NamespaceLevel::from_root(root.clone())
+                .with_case_insensitive_names(true)
+                .with_alias(alias(&["retail", "sales", "purchases"]), "orders"),
+        )
+        .build()
+        .await?;
+
+    let expected = row_count(&ctx, "SELECT COUNT(*) FROM retail.sales.orders").await?;
+    assert_eq!(
+        row_count(&ctx, r#"SELECT COUNT(*) FROM "RETAIL"."SALES"."ORDERS""#).await?,
+        expected
+    );
+    assert_eq!(
+        row_count(&ctx, r#"SELECT COUNT(*) FROM "Retail"."Sales"."Purchases""#).await?,
+        expected
+    );
+
+    // Names stay case sensitive unless configured.
+    let ctx = SessionBuilder::new()
+        .with_root(NamespaceLevel::from_root(root))
+        .build()
+        .await?;
+    assert!(
+        ctx.sql(r#"SELECT * FROM "RETAIL"."SALES"."ORDERS""#)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
@@ -192,6 +192,17 @@ impl ConnectBuilder {
                     .to_string(),
             }
             .into()),
+            #[cfg(feature = "sql")]
+            "sql" => crate::sql::SqlNamespaceBuilder::from_properties(self.properties)?
+                .build()
+                .await
+                .map(|ns| Arc::new(ns) as Arc<dyn LanceNamespace>),
+            #[cfg(not(feature = "sql"))]
+            "sql" => Err(NamespaceError::Unsupported {
+                message: "SQL namespace implementation requires 'sql' feature to be enabled"
+                    .to_string(),
+            }
+            .into()),
             "dir" => {
                 // Create directory implementation (always available)
                 let mut builder = crate::dir::DirectoryNamespaceBuilder::from_properties(
@@ -208,9 +219,10 @@ impl ConnectBuilder {
             }
             _ => Err(NamespaceError::Unsupported {
                 message: format!(
-                    "Implementation '{}' is not available. Supported: dir{}",
+                    "Implementation '{}' is not available. Supported: dir{}{}",
                     self.impl_name,
-                    if cfg!(feature = "rest") { ", rest" } else { "" }
+                    if cfg!(feature = "rest") { ", rest" } else { "" },
+                    if cfg!(feature = "sql") { ", sql" } else { "" }
                 ),
             }
             .into()),
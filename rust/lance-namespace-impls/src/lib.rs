@@ -9,6 +9,7 @@
 //!
 //! - `rest`: REST API-based namespace implementation
 //! - `rest-adapter`: REST server adapter that exposes any namespace via HTTP
+//! - `sql`: PostgreSQL-backed namespace implementation
 //! - `dir-aws`, `dir-azure`, `dir-gcp`, `dir-oss`: Cloud storage backend support for directory namespace (via lance-io)
 //! - `credential-vendor-aws`, `credential-vendor-gcp`, `credential-vendor-azure`: Credential vending for cloud storage
 //!
@@ -16,6 +17,7 @@
 //!
 //! - `DirectoryNamespace`: Directory-based implementation (always available)
 //! - `RestNamespace`: REST API-based implementation (requires `rest` feature)
+//! - `SqlNamespace`: PostgreSQL-based implementation (requires `sql` feature)
 //!
 //! ## Credential Vending
 //!
@@ -82,6 +84,9 @@ pub mod rest;
 #[cfg(feature = "rest-adapter")]
 pub mod rest_adapter;
 
+#[cfg(feature = "sql")]
+pub mod sql;
+
 // Re-export connect builder
 pub use connect::ConnectBuilder;
 pub use context::{DynamicContextProvider, OperationInfo};
@@ -114,5 +119,8 @@ pub use credentials::azure_props;
 #[cfg(feature = "rest")]
 pub use rest::{RestNamespace, RestNamespaceBuilder};
 
+#[cfg(feature = "sql")]
+pub use sql::{SqlNamespace, SqlNamespaceBuilder};
+
 #[cfg(feature = "rest-adapter")]
 pub use rest_adapter::{RestAdapter, RestAdapterConfig, RestAdapterHandle};
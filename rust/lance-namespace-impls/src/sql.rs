@@ -0,0 +1,811 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! SQL database implementation of Lance Namespace
+//!
+//! [`SqlNamespace`] stores the namespace hierarchy, table locations and
+//! properties in two tables of a PostgreSQL database, `lance_namespaces` and
+//! `lance_tables`. The tables are created on first connect if they do not
+//! exist. Table data stays in the table locations; the database only records
+//! where each table lives.
+//!
+//! Every table row carries a version number that is bumped on each change, so
+//! re-registering a table (`overwrite` mode) is a compare-and-swap against the
+//! version read just before: a writer that loses the race retries, and gives up
+//! with a `ConcurrentModification` error after [`MAX_REGISTER_ATTEMPTS`].
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use lance_core::{Error, Result};
+use lance_namespace::LanceNamespace;
+use lance_namespace::error::NamespaceError;
+use lance_namespace::models::{
+    CreateNamespaceRequest, CreateNamespaceResponse, DeclareTableRequest, DeclareTableResponse,
+    DeregisterTableRequest, DeregisterTableResponse, DescribeNamespaceRequest,
+    DescribeNamespaceResponse, DescribeTableRequest, DescribeTableResponse, DropNamespaceRequest,
+    DropNamespaceResponse, ListNamespacesRequest, ListNamespacesResponse, ListTablesRequest,
+    ListTablesResponse, NamespaceExistsRequest, RegisterTableRequest, RegisterTableResponse,
+    TableExistsRequest,
+};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+/// Separator of the levels of a namespace id in the `lance_namespaces` table.
+const DELIMITER: &str = "$";
+
+/// The number of times a conflicting `overwrite` registration is retried.
+pub const MAX_REGISTER_ATTEMPTS: usize = 5;
+
+const CREATE_NAMESPACES_TABLE: &str = "CREATE TABLE IF NOT EXISTS lance_namespaces (
+    id TEXT PRIMARY KEY,
+    parent TEXT REFERENCES lance_namespaces (id),
+    name TEXT NOT NULL,
+    properties TEXT NOT NULL
+)";
+
+const CREATE_TABLES_TABLE: &str = "CREATE TABLE IF NOT EXISTS lance_tables (
+    namespace TEXT NOT NULL REFERENCES lance_namespaces (id),
+    name TEXT NOT NULL,
+    location TEXT NOT NULL,
+    properties TEXT NOT NULL,
+    version BIGINT NOT NULL,
+    PRIMARY KEY (namespace, name)
+)";
+
+const INSERT_ROOT_NAMESPACE: &str = "INSERT INTO lance_namespaces (id, parent, name, properties)
+    VALUES ('', NULL, '', '{}') ON CONFLICT (id) DO NOTHING";
+
+/// Builder for creating a [`SqlNamespace`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lance_namespace_impls::SqlNamespaceBuilder;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let namespace = SqlNamespaceBuilder::new("postgres://lance@localhost/catalog")
+///     .root("s3://bucket/warehouse")
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqlNamespaceBuilder {
+    uri: String,
+    root: Option<String>,
+    max_connections: u32,
+}
+
+impl SqlNamespaceBuilder {
+    const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+
+    /// Create a new builder connecting to the database at `uri`.
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            root: None,
+            max_connections: Self::DEFAULT_MAX_CONNECTIONS,
+        }
+    }
+
+    /// Create a builder from properties.
+    ///
+    /// Supported properties:
+    /// - `uri` (required): the database connection string
+    /// - `root`: the location declared tables are created under
+    /// - `max_connections`: the size of the connection pool (default: 10)
+    pub fn from_properties(properties: HashMap<String, String>) -> Result<Self> {
+        let uri = properties.get("uri").cloned().ok_or_else(|| {
+            lance_core::Error::from(NamespaceError::InvalidInput {
+                message: "Missing required property 'uri' for SQL namespace".to_string(),
+            })
+        })?;
+        let mut builder = Self::new(uri);
+        builder.root = properties.get("root").cloned();
+        if let Some(max_connections) = properties.get("max_connections") {
+            builder.max_connections = max_connections.parse().map_err(|_| {
+                lance_core::Error::from(NamespaceError::InvalidInput {
+                    message: format!("Invalid max_connections '{}'", max_connections),
+                })
+            })?;
+        }
+        Ok(builder)
+    }
+
+    /// Set the location new tables are declared under.
+    ///
+    /// A declared table without an explicit location is placed at
+    /// `{root}/{namespace}${table}.lance`. Without a root, declaring a table
+    /// requires a location.
+    pub fn root(mut self, root: impl Into<String>) -> Self {
+        self.root = Some(root.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Set the maximum number of pooled database connections.
+    pub fn max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Connect to the database and create the namespace tables if needed.
+    pub async fn build(self) -> Result<SqlNamespace> {
+        let pool = PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .connect(&self.uri)
+            .await
+            .map_err(|e| internal_error("connect to the namespace database", e))?;
+        for statement in [
+            CREATE_NAMESPACES_TABLE,
+            CREATE_TABLES_TABLE,
+            INSERT_ROOT_NAMESPACE,
+        ] {
+            sqlx::query(statement)
+                .execute(&pool)
+                .await
+                .map_err(|e| internal_error("create the namespace tables", e))?;
+        }
+        Ok(SqlNamespace {
+            pool,
+            root: self.root,
+        })
+    }
+}
+
+/// A Lance namespace stored in a PostgreSQL database.
+///
+/// See the [module documentation](self) for the storage layout.
+#[derive(Debug, Clone)]
+pub struct SqlNamespace {
+    pool: PgPool,
+    root: Option<String>,
+}
+
+impl SqlNamespace {
+    /// The database key of the namespace `id`, the root namespace is `""`.
+    fn namespace_key(id: &[String]) -> String {
+        id.join(DELIMITER)
+    }
+
+    /// Split a table id into its namespace key and table name.
+    fn table_key(id: Option<Vec<String>>) -> Result<(String, String)> {
+        let mut id = id.unwrap_or_default();
+        let name = id.pop().ok_or_else(|| {
+            Error::from(NamespaceError::InvalidInput {
+                message: "Table id must not be empty".to_string(),
+            })
+        })?;
+        Ok((Self::namespace_key(&id), name))
+    }
+
+    fn table_display(namespace: &str, name: &str) -> String {
+        if namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}{}{}", namespace, DELIMITER, name)
+        }
+    }
+
+    async fn namespace_properties(&self, key: &str) -> Result<Option<HashMap<String, String>>> {
+        let properties: Option<String> =
+            sqlx::query_scalar("SELECT properties FROM lance_namespaces WHERE id = $1")
+                .bind(key)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| internal_error("describe namespace", e))?;
+        properties.as_deref().map(parse_properties).transpose()
+    }
+
+    async fn require_namespace(&self, key: &str) -> Result<()> {
+        if self.namespace_properties(key).await?.is_none() {
+            return Err(NamespaceError::NamespaceNotFound {
+                message: format!("Namespace '{}' not found", key),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Insert a table row, returning false if the table already exists.
+    async fn insert_table(
+        &self,
+        namespace: &str,
+        name: &str,
+        location: &str,
+        properties: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO lance_tables (namespace, name, location, properties, version)
+             VALUES ($1, $2, $3, $4, 1) ON CONFLICT (namespace, name) DO NOTHING",
+        )
+        .bind(namespace)
+        .bind(name)
+        .bind(location)
+        .bind(properties)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) {
+                NamespaceError::NamespaceNotFound {
+                    message: format!("Namespace '{}' not found", namespace),
+                }
+                .into()
+            } else {
+                internal_error("register table", e)
+            }
+        })?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Point an existing table at a new location, retrying if another writer
+    /// changes the table in between.
+    async fn overwrite_table(
+        &self,
+        namespace: &str,
+        name: &str,
+        location: &str,
+        properties: &str,
+    ) -> Result<()> {
+        for _ in 0..MAX_REGISTER_ATTEMPTS {
+            let version: Option<i64> = sqlx::query_scalar(
+                "SELECT version FROM lance_tables WHERE namespace = $1 AND name = $2",
+            )
+            .bind(namespace)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| internal_error("register table", e))?;
+            let Some(version) = version else {
+                if self
+                    .insert_table(namespace, name, location, properties)
+                    .await?
+                {
+                    return Ok(());
+                }
+                continue;
+            };
+            let result = sqlx::query(
+                "UPDATE lance_tables SET location = $3, properties = $4, version = version + 1
+                 WHERE namespace = $1 AND name = $2 AND version = $5",
+            )
+            .bind(namespace)
+            .bind(name)
+            .bind(location)
+            .bind(properties)
+            .bind(version)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| internal_error("register table", e))?;
+            if result.rows_affected() == 1 {
+                return Ok(());
+            }
+        }
+        Err(NamespaceError::ConcurrentModification {
+            message: format!(
+                "Table '{}' was modified concurrently {} times while registering it",
+                Self::table_display(namespace, name),
+                MAX_REGISTER_ATTEMPTS
+            ),
+        }
+        .into())
+    }
+}
+
+#[async_trait]
+impl LanceNamespace for SqlNamespace {
+    async fn list_namespaces(
+        &self,
+        request: ListNamespacesRequest,
+    ) -> Result<ListNamespacesResponse> {
+        let parent = Self::namespace_key(&request.id.unwrap_or_default());
+        self.require_namespace(&parent).await?;
+        let limit = request.limit.filter(|limit| *limit > 0);
+        let namespaces: Vec<String> = sqlx::query_scalar(
+            "SELECT name FROM lance_namespaces
+             WHERE parent = $1 AND ($2::TEXT IS NULL OR name > $2)
+             ORDER BY name LIMIT $3",
+        )
+        .bind(&parent)
+        .bind(request.page_token)
+        .bind(limit.map(i64::from))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| internal_error("list namespaces", e))?;
+        Ok(ListNamespacesResponse {
+            page_token: next_page_token(&namespaces, limit),
+            namespaces,
+        })
+    }
+
+    async fn describe_namespace(
+        &self,
+        request: DescribeNamespaceRequest,
+    ) -> Result<DescribeNamespaceResponse> {
+        let key = Self::namespace_key(&request.id.unwrap_or_default());
+        let properties = self.namespace_properties(&key).await?.ok_or_else(|| {
+            Error::from(NamespaceError::NamespaceNotFound {
+                message: format!("Namespace '{}' not found", key),
+            })
+        })?;
+        Ok(DescribeNamespaceResponse {
+            properties: Some(properties),
+        })
+    }
+
+    async fn create_namespace(
+        &self,
+        request: CreateNamespaceRequest,
+    ) -> Result<CreateNamespaceResponse> {
+        let mut id = request.id.unwrap_or_default();
+        let Some(name) = id.pop() else {
+            return Err(NamespaceError::NamespaceAlreadyExists {
+                message: "Root namespace already exists and cannot be created".to_string(),
+            }
+            .into());
+        };
+        let parent = Self::namespace_key(&id);
+        id.push(name.clone());
+        let key = Self::namespace_key(&id);
+        let properties = request.properties.unwrap_or_default();
+        let serialized = serialize_properties(&properties)?;
+
+        let result = sqlx::query(
+            "INSERT INTO lance_namespaces (id, parent, name, properties)
+             VALUES ($1, $2, $3, $4) ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(&key)
+        .bind(&parent)
+        .bind(&name)
+        .bind(&serialized)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            if is_foreign_key_violation(&e) {
+                NamespaceError::NamespaceNotFound {
+                    message: format!("Parent namespace '{}' not found", parent),
+                }
+                .into()
+            } else {
+                internal_error("create namespace", e)
+            }
+        })?;
+        if result.rows_affected() == 1 {
+            return Ok(CreateNamespaceResponse {
+                properties: Some(properties),
+                ..Default::default()
+            });
+        }
+
+        match request.mode.as_deref() {
+            Some(mode) if mode.eq_ignore_ascii_case("exist_ok") => {
+                let properties = self.namespace_properties(&key).await?.unwrap_or_default();
+                Ok(CreateNamespaceResponse {
+                    properties: Some(properties),
+                    ..Default::default()
+                })
+            }
+            Some(mode) if mode.eq_ignore_ascii_case("overwrite") => {
+                sqlx::query("UPDATE lance_namespaces SET properties = $2 WHERE id = $1")
+                    .bind(&key)
+                    .bind(&serialized)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| internal_error("create namespace", e))?;
+                Ok(CreateNamespaceResponse {
+                    properties: Some(properties),
+                    ..Default::default()
+                })
+            }
+            _ => Err(NamespaceError::NamespaceAlreadyExists {
+                message: format!("Namespace '{}' already exists", key),
+            }
+            .into()),
+        }
+    }
+
+    async fn drop_namespace(&self, request: DropNamespaceRequest) -> Result<DropNamespaceResponse> {
+        let id = request.id.unwrap_or_default();
+        if id.is_empty() {
+            return Err(NamespaceError::InvalidInput {
+                message: "Root namespace cannot be dropped".to_string(),
+            }
+            .into());
+        }
+        if request
+            .behavior
+            .as_deref()
+            .is_some_and(|behavior| behavior.eq_ignore_ascii_case("cascade"))
+        {
+            return Err(NamespaceError::Unsupported {
+                message: "SQL namespace does not support cascading drops".to_string(),
+            }
+            .into());
+        }
+        let key = Self::namespace_key(&id);
+        let properties: Option<String> =
+            sqlx::query_scalar("DELETE FROM lance_namespaces WHERE id = $1 RETURNING properties")
+                .bind(&key)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| {
+                    if is_foreign_key_violation(&e) {
+                        NamespaceError::NamespaceNotEmpty {
+                            message: format!("Namespace '{}' is not empty", key),
+                        }
+                        .into()
+                    } else {
+                        internal_error("drop namespace", e)
+                    }
+                })?;
+        match properties {
+            Some(properties) => Ok(DropNamespaceResponse {
+                properties: Some(parse_properties(&properties)?),
+                ..Default::default()
+            }),
+            None if request
+                .mode
+                .as_deref()
+                .is_some_and(|mode| mode.eq_ignore_ascii_case("skip")) =>
+            {
+                Ok(DropNamespaceResponse::default())
+            }
+            None => Err(NamespaceError::NamespaceNotFound {
+                message: format!("Namespace '{}' not found", key),
+            }
+            .into()),
+        }
+    }
+
+    async fn namespace_exists(&self, request: NamespaceExistsRequest) -> Result<()> {
+        self.require_namespace(&Self::namespace_key(&request.id.unwrap_or_default()))
+            .await
+    }
+
+    async fn list_tables(&self, request: ListTablesRequest) -> Result<ListTablesResponse> {
+        let namespace = Self::namespace_key(&request.id.unwrap_or_default());
+        self.require_namespace(&namespace).await?;
+        let limit = request.limit.filter(|limit| *limit > 0);
+        let tables: Vec<String> = sqlx::query_scalar(
+            "SELECT name FROM lance_tables
+             WHERE namespace = $1 AND ($2::TEXT IS NULL OR name > $2)
+             ORDER BY name LIMIT $3",
+        )
+        .bind(&namespace)
+        .bind(request.page_token)
+        .bind(limit.map(i64::from))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| internal_error("list tables", e))?;
+        Ok(ListTablesResponse {
+            page_token: next_page_token(&tables, limit),
+            tables,
+        })
+    }
+
+    async fn describe_table(&self, request: DescribeTableRequest) -> Result<DescribeTableResponse> {
+        let (namespace, name) = Self::table_key(request.id)?;
+        let row: Option<(String, String)> = sqlx::query_as(
+            "SELECT location, properties FROM lance_tables WHERE namespace = $1 AND name = $2",
+        )
+        .bind(&namespace)
+        .bind(&name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| internal_error("describe table", e))?;
+        let Some((location, properties)) = row else {
+            return Err(table_not_found(&namespace, &name));
+        };
+        Ok(DescribeTableResponse {
+            table: Some(name),
+            namespace: Some(
+                namespace
+                    .split(DELIMITER)
+                    .filter(|level| !level.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            table_uri: Some(location.clone()),
+            location: Some(location),
+            properties: Some(parse_properties(&properties)?),
+            ..Default::default()
+        })
+    }
+
+    async fn table_exists(&self, request: TableExistsRequest) -> Result<()> {
+        let (namespace, name) = Self::table_key(request.id)?;
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM lance_tables WHERE namespace = $1 AND name = $2)",
+        )
+        .bind(&namespace)
+        .bind(&name)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| internal_error("check table existence", e))?;
+        if exists {
+            Ok(())
+        } else {
+            Err(table_not_found(&namespace, &name))
+        }
+    }
+
+    async fn register_table(&self, request: RegisterTableRequest) -> Result<RegisterTableResponse> {
+        let (namespace, name) = Self::table_key(request.id)?;
+        let properties = request.properties.unwrap_or_default();
+        let serialized = serialize_properties(&properties)?;
+        match request.mode.as_deref() {
+            Some(mode) if mode.eq_ignore_ascii_case("overwrite") => {
+                self.overwrite_table(&namespace, &name, &request.location, &serialized)
+                    .await?;
+            }
+            mode if mode.is_none_or(|mode| mode.eq_ignore_ascii_case("create")) => {
+                if !self
+                    .insert_table(&namespace, &name, &request.location, &serialized)
+                    .await?
+                {
+                    return Err(NamespaceError::TableAlreadyExists {
+                        message: format!(
+                            "Table '{}' already exists",
+                            Self::table_display(&namespace, &name)
+                        ),
+                    }
+                    .into());
+                }
+            }
+            Some(mode) => {
+                return Err(NamespaceError::InvalidInput {
+                    message: format!(
+                        "Invalid register mode '{}', expected 'create' or 'overwrite'",
+                        mode
+                    ),
+                }
+                .into());
+            }
+        }
+        Ok(RegisterTableResponse {
+            location: Some(request.location),
+            properties: Some(properties),
+            ..Default::default()
+        })
+    }
+
+    async fn deregister_table(
+        &self,
+        request: DeregisterTableRequest,
+    ) -> Result<DeregisterTableResponse> {
+        let (namespace, name) = Self::table_key(request.id.clone())?;
+        let row: Option<(String, String)> = sqlx::query_as(
+            "DELETE FROM lance_tables WHERE namespace = $1 AND name = $2
+             RETURNING location, properties",
+        )
+        .bind(&namespace)
+        .bind(&name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| internal_error("deregister table", e))?;
+        let Some((location, properties)) = row else {
+            return Err(table_not_found(&namespace, &name));
+        };
+        Ok(DeregisterTableResponse {
+            id: request.id,
+            location: Some(location),
+            properties: Some(parse_properties(&properties)?),
+            ..Default::default()
+        })
+    }
+
+    async fn declare_table(&self, request: DeclareTableRequest) -> Result<DeclareTableResponse> {
+        let (namespace, name) = Self::table_key(request.id)?;
+        let object_id = Self::table_display(&namespace, &name);
+        let location = match (request.location, &self.root) {
+            (Some(location), _) => location,
+            (None, Some(root)) => format!("{}/{}.lance", root, object_id),
+            (None, None) => {
+                return Err(NamespaceError::InvalidInput {
+                    message: format!(
+                        "Table '{}' needs a location, the SQL namespace has no root",
+                        object_id
+                    ),
+                }
+                .into());
+            }
+        };
+        let properties = request.properties.unwrap_or_default();
+        let serialized = serialize_properties(&properties)?;
+        if !self
+            .insert_table(&namespace, &name, &location, &serialized)
+            .await?
+        {
+            return Err(NamespaceError::TableAlreadyExists {
+                message: format!("Table '{}' already exists", object_id),
+            }
+            .into());
+        }
+        Ok(DeclareTableResponse {
+            location: Some(location),
+            properties: Some(properties),
+            ..Default::default()
+        })
+    }
+
+    fn namespace_id(&self) -> String {
+        format!("SqlNamespace {{ root: {:?} }}", self.root)
+    }
+}
+
+/// The page token of a listing: the last name if the page is full.
+fn next_page_token(names: &[String], limit: Option<i32>) -> Option<String> {
+    match limit {
+        Some(limit) if names.len() == limit as usize => names.last().cloned(),
+        _ => None,
+    }
+}
+
+fn serialize_properties(properties: &HashMap<String, String>) -> Result<String> {
+    serde_json::to_string(properties).map_err(|e| {
+        NamespaceError::Internal {
+            message: format!("Failed to serialize properties: {}", e),
+        }
+        .into()
+    })
+}
+
+fn parse_properties(properties: &str) -> Result<HashMap<String, String>> {
+    serde_json::from_str(properties).map_err(|e| {
+        NamespaceError::Internal {
+            message: format!("Failed to parse stored properties: {}", e),
+        }
+        .into()
+    })
+}
+
+fn table_not_found(namespace: &str, name: &str) -> Error {
+    NamespaceError::TableNotFound {
+        message: format!(
+            "Table '{}' not found",
+            SqlNamespace::table_display(namespace, name)
+        ),
+    }
+    .into()
+}
+
+fn is_foreign_key_violation(error: &sqlx::Error) -> bool {
+    error
+        .as_database_error()
+        .is_some_and(|e| e.is_foreign_key_violation())
+}
+
+fn internal_error(operation: &str, error: sqlx::Error) -> Error {
+    NamespaceError::Internal {
+        message: format!("Failed to {}: {}", operation, error),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_key() {
+        let (namespace, name) =
+            SqlNamespace::table_key(Some(vec!["retail".into(), "sales".into(), "orders".into()]))
+                .unwrap();
+        assert_eq!(namespace, "retail$sales");
+        assert_eq!(name, "orders");
+        assert_eq!(
+            SqlNamespace::table_display(&namespace, &name),
+            "retail$sales$orders"
+        );
+
+        let (namespace, name) = SqlNamespace::table_key(Some(vec!["orders".into()])).unwrap();
+        assert_eq!(namespace, "");
+        assert_eq!(SqlNamespace::table_display(&namespace, &name), "orders");
+
+        assert!(SqlNamespace::table_key(None).is_err());
+    }
+
+    #[test]
+    fn test_next_page_token() {
+        let names = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(next_page_token(&names, Some(2)), Some("b".to_string()));
+        assert_eq!(next_page_token(&names, Some(3)), None);
+        assert_eq!(next_page_token(&names, None), None);
+    }
+
+    #[test]
+    fn test_from_properties() {
+        let properties = HashMap::from([
+            ("uri".to_string(), "postgres://localhost/lance".to_string()),
+            ("max_connections".to_string(), "4".to_string()),
+        ]);
+        let builder = SqlNamespaceBuilder::from_properties(properties).unwrap();
+        assert_eq!(builder.uri, "postgres://localhost/lance");
+        assert_eq!(builder.max_connections, 4);
+
+        let err = SqlNamespaceBuilder::from_properties(HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("uri"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a PostgreSQL database in LANCE_TEST_POSTGRES_URL"]
+    async fn test_register_tables() {
+        let uri = std::env::var("LANCE_TEST_POSTGRES_URL").unwrap();
+        let namespace = SqlNamespaceBuilder::new(uri)
+            .root("memory://warehouse")
+            .build()
+            .await
+            .unwrap();
+        let schema = format!("test_{}", rand::random::<u32>());
+
+        namespace
+            .create_namespace(CreateNamespaceRequest {
+                id: Some(vec![schema.clone()]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let declared = namespace
+            .declare_table(DeclareTableRequest {
+                id: Some(vec![schema.clone(), "orders".into()]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            declared.location.as_deref(),
+            Some(format!("memory://warehouse/{}$orders.lance", schema).as_str())
+        );
+
+        let err = namespace
+            .register_table(RegisterTableRequest {
+                id: Some(vec![schema.clone(), "orders".into()]),
+                location: "memory://elsewhere".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        namespace
+            .register_table(RegisterTableRequest {
+                id: Some(vec![schema.clone(), "orders".into()]),
+                location: "memory://elsewhere".into(),
+                mode: Some("overwrite".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let described = namespace
+            .describe_table(DescribeTableRequest {
+                id: Some(vec![schema.clone(), "orders".into()]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(described.location.as_deref(), Some("memory://elsewhere"));
+
+        let err = namespace
+            .drop_namespace(DropNamespaceRequest {
+                id: Some(vec![schema.clone()]),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not empty"));
+
+        namespace
+            .deregister_table(DeregisterTableRequest {
+                id: Some(vec![schema.clone(), "orders".into()]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        namespace
+            .drop_namespace(DropNamespaceRequest {
+                id: Some(vec![schema]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+}
@@ -156,6 +156,35 @@ impl std::fmt::Display for ErrorCode {
     }
 }
 
+impl From<ErrorCode> for lance_core::ErrorCode {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::Unsupported => Self::NotSupported,
+            ErrorCode::NamespaceNotFound
+            | ErrorCode::TableNotFound
+            | ErrorCode::TableIndexNotFound
+            | ErrorCode::TableTagNotFound
+            | ErrorCode::TransactionNotFound
+            | ErrorCode::TableVersionNotFound
+            | ErrorCode::TableColumnNotFound
+            | ErrorCode::TableBranchNotFound => Self::NotFound,
+            ErrorCode::NamespaceAlreadyExists
+            | ErrorCode::TableAlreadyExists
+            | ErrorCode::TableIndexAlreadyExists
+            | ErrorCode::TableTagAlreadyExists
+            | ErrorCode::TableBranchAlreadyExists => Self::AlreadyExists,
+            ErrorCode::NamespaceNotEmpty | ErrorCode::InvalidTableState => Self::Conflict,
+            ErrorCode::InvalidInput | ErrorCode::TableSchemaValidationError => Self::InvalidInput,
+            ErrorCode::ConcurrentModification => Self::RetryableConflict,
+            ErrorCode::PermissionDenied => Self::PermissionDenied,
+            ErrorCode::Unauthenticated => Self::Unauthenticated,
+            ErrorCode::ServiceUnavailable => Self::Unavailable,
+            ErrorCode::Throttling => Self::Throttled,
+            ErrorCode::Internal => Self::Internal,
+        }
+    }
+}
+
 /// Lance Namespace error type.
 ///
 /// This enum provides fine-grained error types for Lance Namespace operations.
@@ -385,11 +414,13 @@ impl NamespaceError {
 /// Converts a NamespaceError into a lance_core::Error.
 ///
 /// The original `NamespaceError` is preserved in the `source` field and can be
-/// extracted via downcasting for programmatic error handling.
+/// extracted via downcasting for programmatic error handling.  Its code is
+/// mapped to a [`lance_core::ErrorCode`] so callers that only see the Lance
+/// error can still tell retryable failures apart.
 impl From<NamespaceError> for lance_core::Error {
     #[track_caller]
     fn from(err: NamespaceError) -> Self {
-        Self::namespace_source(Box::new(err))
+        Self::namespace_with_code(err.code().into(), Box::new(err))
     }
 }
 
@@ -454,6 +485,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_lance_error_code() {
+        let lance_err: lance_core::Error = NamespaceError::TableNotFound {
+            message: "users".to_string(),
+        }
+        .into();
+        assert_eq!(lance_err.code(), lance_core::ErrorCode::NotFound);
+        assert!(!lance_err.is_retryable());
+
+        let lance_err: lance_core::Error = NamespaceError::Throttling {
+            message: "slow down".to_string(),
+        }
+        .into();
+        assert_eq!(lance_err.code(), lance_core::ErrorCode::Throttled);
+        assert!(lance_err.is_retryable());
+    }
+
     #[test]
     fn test_error_display() {
         let err = NamespaceError::TableNotFound {
@@ -58,6 +58,8 @@ fn make_uniform_pb_fragments(n: u64, num_fields: usize) -> Vec<pb::DataFragment>
                 file_minor_version: 0,
                 file_size_bytes: 0,
                 base_id: None,
+                zone_maps: vec![],
+                partition_values: vec![],
             }],
             deletion_file: None,
             row_id_sequence: None,
@@ -134,6 +136,8 @@ fn make_diverse_pb_fragments(
                     file_minor_version: 0,
                     file_size_bytes: 0,
                     base_id: None,
+                    zone_maps: vec![],
+                    partition_values: vec![],
                 }],
                 deletion_file: None,
                 row_id_sequence: None,
@@ -20,8 +20,12 @@ pub const FLAG_TABLE_CONFIG: u64 = 8;
 pub const FLAG_BASE_PATHS: u64 = 16;
 /// Disable writing transaction file under _transaction/, this flag is set when we only want to write inline transaction in manifest
 pub const FLAG_DISABLE_TRANSACTION_FILE: u64 = 32;
+/// The manifest only stores the fragments changed since an earlier manifest
+pub const FLAG_DELTA_MANIFEST: u64 = 64;
+/// The index metadata is stored behind an index directory
+pub const FLAG_INDEX_DIRECTORY: u64 = 128;
 /// The first bit that is unknown as a feature flag
-pub const FLAG_UNKNOWN: u64 = 64;
+pub const FLAG_UNKNOWN: u64 = 256;
 
 /// Set the reader and writer feature flags in the manifest based on the contents of the manifest.
 pub fn apply_feature_flags(
@@ -71,6 +75,18 @@ pub fn apply_feature_flags(
         manifest.writer_feature_flags |= FLAG_BASE_PATHS;
     }
 
+    // Readers need the base manifest to know all fragments of a delta manifest
+    if manifest.is_stored_as_delta() {
+        manifest.reader_feature_flags |= FLAG_DELTA_MANIFEST;
+        manifest.writer_feature_flags |= FLAG_DELTA_MANIFEST;
+    }
+
+    // Readers need to know that the indices are not in the index section
+    if manifest.uses_index_directory() {
+        manifest.reader_feature_flags |= FLAG_INDEX_DIRECTORY;
+        manifest.writer_feature_flags |= FLAG_INDEX_DIRECTORY;
+    }
+
     if disable_transaction_file {
         manifest.writer_feature_flags |= FLAG_DISABLE_TRANSACTION_FILE;
     }
@@ -85,6 +101,52 @@ pub fn can_write_dataset(writer_flags: u64) -> bool {
     writer_flags < FLAG_UNKNOWN
 }
 
+/// The flags of `flags` that this version of Lance does not know.
+pub fn unknown_flags(flags: u64) -> u64 {
+    flags & !(FLAG_UNKNOWN - 1)
+}
+
+/// Checks that this version of Lance can read the dataset of `manifest`.
+///
+/// Fails with [`Error::UnsupportedVersion`] naming the unknown reader flags.
+pub fn check_read_compatibility(manifest: &Manifest) -> Result<()> {
+    check_compatibility(manifest, manifest.reader_feature_flags, "read")
+}
+
+/// Checks that this version of Lance can write to the dataset of `manifest`.
+///
+/// Fails with [`Error::UnsupportedVersion`] naming the unknown writer flags.
+pub fn check_write_compatibility(manifest: &Manifest) -> Result<()> {
+    check_compatibility(manifest, manifest.writer_feature_flags, "written")
+}
+
+fn check_compatibility(manifest: &Manifest, flags: u64, access: &str) -> Result<()> {
+    let unsupported_flags = unknown_flags(flags);
+    if unsupported_flags == 0 {
+        return Ok(());
+    }
+    let current = env!("CARGO_PKG_VERSION");
+    // The flags are unknown because a newer library set them, so the writer
+    // of the manifest is the oldest version we know to support them.
+    let min_library_version = manifest
+        .writer_version
+        .as_ref()
+        .and_then(|writer| writer.lance_lib_version())
+        .filter(|writer| semver::Version::parse(current).is_ok_and(|current| *writer > current))
+        .map(|writer| writer.to_string());
+    let upgrade = match &min_library_version {
+        Some(version) => format!("Please upgrade Lance to {version} or later"),
+        None => "Please upgrade Lance".to_string(),
+    };
+    Err(Error::unsupported_version(
+        format!(
+            "This dataset cannot be {access} by Lance {current}, it uses unknown feature flags {unsupported_flags:#x}. {upgrade}."
+        ),
+        unsupported_flags,
+        min_library_version,
+    ))
+}
+
 pub fn has_deprecated_v2_feature_flag(writer_flags: u64) -> bool {
     writer_flags & FLAG_USE_V2_FORMAT_DEPRECATED != 0
 }
@@ -103,6 +165,8 @@ mod tests {
         assert!(can_read_dataset(super::FLAG_TABLE_CONFIG));
         assert!(can_read_dataset(super::FLAG_BASE_PATHS));
         assert!(can_read_dataset(super::FLAG_DISABLE_TRANSACTION_FILE));
+        assert!(can_read_dataset(super::FLAG_DELTA_MANIFEST));
+        assert!(can_read_dataset(super::FLAG_INDEX_DIRECTORY));
         assert!(can_read_dataset(
             super::FLAG_DELETION_FILES
                 | super::FLAG_STABLE_ROW_IDS
@@ -120,6 +184,8 @@ mod tests {
         assert!(can_write_dataset(super::FLAG_TABLE_CONFIG));
         assert!(can_write_dataset(super::FLAG_BASE_PATHS));
         assert!(can_write_dataset(super::FLAG_DISABLE_TRANSACTION_FILE));
+        assert!(can_write_dataset(super::FLAG_DELTA_MANIFEST));
+        assert!(can_write_dataset(super::FLAG_INDEX_DIRECTORY));
         assert!(can_write_dataset(
             super::FLAG_DELETION_FILES
                 | super::FLAG_STABLE_ROW_IDS
@@ -181,4 +247,58 @@ mod tests {
             0
         );
     }
+
+    #[test]
+    fn test_compatibility_check() {
+        use crate::format::{DataStorageFormat, Manifest, WriterVersion};
+        use arrow_schema::{Field as ArrowField, Schema as ArrowSchema};
+        use lance_core::datatypes::Schema;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "i",
+            arrow_schema::DataType::Int64,
+            false,
+        )]);
+        let mut manifest = Manifest::new(
+            Schema::try_from(&arrow_schema).unwrap(),
+            Arc::new(vec![]),
+            DataStorageFormat::default(),
+            HashMap::new(),
+        );
+        manifest.reader_feature_flags = FLAG_DELETION_FILES;
+        manifest.writer_feature_flags = FLAG_DELETION_FILES | (FLAG_UNKNOWN << 1);
+        manifest.writer_version = Some(WriterVersion {
+            library: "lance".to_string(),
+            version: "999.1.0".to_string(),
+            prerelease: None,
+            build_metadata: None,
+        });
+
+        check_read_compatibility(&manifest).unwrap();
+        let err = check_write_compatibility(&manifest).unwrap_err();
+        let Error::UnsupportedVersion {
+            unsupported_flags,
+            min_library_version,
+            ..
+        } = &err
+        else {
+            panic!("Expected UnsupportedVersion, got {err:?}");
+        };
+        assert_eq!(*unsupported_flags, FLAG_UNKNOWN << 1);
+        assert_eq!(min_library_version.as_deref(), Some("999.1.0"));
+        assert!(err.to_string().contains("999.1.0"), "{err}");
+
+        // Without a newer writer the required version is unknown
+        manifest.writer_version = None;
+        let err = check_write_compatibility(&manifest).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::UnsupportedVersion {
+                min_library_version: None,
+                ..
+            }
+        ));
+    }
 }
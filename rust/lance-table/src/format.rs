@@ -4,20 +4,30 @@
 use arrow_buffer::ToByteSlice;
 use uuid::Uuid;
 
+mod encryption;
 mod fragment;
 mod index;
 mod manifest;
+mod partition;
 mod transaction;
 
 pub use crate::rowids::version::{
     RowDatasetVersionMeta, RowDatasetVersionRun, RowDatasetVersionSequence,
 };
+pub use encryption::{ColumnKey, ENCRYPTION_SPEC_KEY, EncryptionScheme, EncryptionSpec};
 pub use fragment::*;
-pub use index::{IndexFile, IndexMetadata, index_metadata_codec, list_index_files_with_sizes};
+pub use index::{
+    IndexDirectoryEntry, IndexFile, IndexMetadata, index_metadata_codec,
+    list_index_files_with_sizes,
+};
 
 pub use manifest::{
-    BasePath, DETACHED_VERSION_MASK, DataStorageFormat, Manifest, ManifestSummary,
-    SelfDescribingFileReader, WriterVersion, is_detached_version,
+    BasePath, DELTA_MANIFEST_MAX_DEPTH_KEY, DETACHED_VERSION_MASK, DataStorageFormat, DeltaBase,
+    EXTERNAL_INDEX_SECTION_KEY, Manifest, ManifestSummary, SelfDescribingFileReader, WriterVersion,
+    is_detached_version,
+};
+pub use partition::{
+    PARTITION_SPEC_KEY, PartitionField, PartitionSpec, PartitionTransform, PartitionValue,
 };
 pub use transaction::Transaction;
 
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Encryption of the columns of a dataset.
+//!
+//! An [`EncryptionSpec`] stored in the manifest config lists the encrypted
+//! columns along with their data keys.  The data keys are stored encrypted by a
+//! key provider (typically a KMS) together with the id of the provider key, so
+//! readers need access to the provider to decrypt them.  Writers encrypt these
+//! columns in every data file they write, see `lance_file::encryption`.
+
+use std::collections::{HashMap, HashSet};
+
+use lance_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// The manifest config key of the [`EncryptionSpec`] of a dataset.
+pub const ENCRYPTION_SPEC_KEY: &str = "lance.encryption";
+
+/// How the columns of a dataset are encrypted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptionScheme {
+    /// Each column is encrypted in the data files with its own AES-256 key in
+    /// counter mode.
+    #[default]
+    ColumnAes256Ctr,
+}
+
+/// The data key of an encrypted column, see [`EncryptionSpec`].
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnKey {
+    /// The id of the encrypted top-level field.
+    pub field_id: i32,
+    /// The id the data files record for the data key.
+    pub id: String,
+    /// The id of the provider key that encrypted the data key, e.g. a KMS key
+    /// ARN.
+    pub key_id: String,
+    /// The data key, encrypted by the key provider.
+    #[serde(with = "hex_bytes")]
+    pub encrypted_key: Vec<u8>,
+}
+
+impl std::fmt::Debug for ColumnKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColumnKey")
+            .field("field_id", &self.field_id)
+            .field("id", &self.id)
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The encrypted columns of a dataset.
+///
+/// The spec is stored as JSON under [`ENCRYPTION_SPEC_KEY`] in the manifest
+/// config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionSpec {
+    #[serde(default)]
+    pub scheme: EncryptionScheme,
+    pub columns: Vec<ColumnKey>,
+}
+
+impl EncryptionSpec {
+    pub fn try_new(scheme: EncryptionScheme, columns: Vec<ColumnKey>) -> Result<Self> {
+        if columns.is_empty() {
+            return Err(Error::invalid_input(
+                "An encryption spec needs at least one column",
+            ));
+        }
+        let mut field_ids = HashSet::new();
+        let mut ids = HashSet::new();
+        for column in &columns {
+            if !field_ids.insert(column.field_id) {
+                return Err(Error::invalid_input(format!(
+                    "Field {} has more than one encryption key",
+                    column.field_id
+                )));
+            }
+            if !ids.insert(column.id.as_str()) {
+                return Err(Error::invalid_input(format!(
+                    "Encryption key id {} is used by more than one column",
+                    column.id
+                )));
+            }
+        }
+        Ok(Self { scheme, columns })
+    }
+
+    /// The spec stored in a manifest config, if any.
+    pub fn try_from_config(config: &HashMap<String, String>) -> Result<Option<Self>> {
+        let Some(value) = config.get(ENCRYPTION_SPEC_KEY) else {
+            return Ok(None);
+        };
+        let spec: Self = serde_json::from_str(value).map_err(|err| {
+            Error::invalid_input(format!(
+                "Invalid encryption spec in {ENCRYPTION_SPEC_KEY}: {err}"
+            ))
+        })?;
+        Self::try_new(spec.scheme, spec.columns).map(Some)
+    }
+
+    /// The value stored under [`ENCRYPTION_SPEC_KEY`].
+    pub fn to_config_value(&self) -> String {
+        serde_json::to_string(self).expect("encryption specs are serializable")
+    }
+
+    /// The key of the field with id `field_id`, if it is encrypted.
+    pub fn column(&self, field_id: i32) -> Option<&ColumnKey> {
+        self.columns
+            .iter()
+            .find(|column| column.field_id == field_id)
+    }
+}
+
+/// Stores bytes as a hex string, which keeps the config readable.
+mod hex_bytes {
+    use std::fmt::Write;
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut hex = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            write!(hex, "{byte:02x}").expect("writing to a string cannot fail");
+        }
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+            return Err(D::Error::custom("invalid hex string"));
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|start| u8::from_str_radix(&hex[start..start + 2], 16).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(field_id: i32, id: &str) -> ColumnKey {
+        ColumnKey {
+            field_id,
+            id: id.to_string(),
+            key_id: "arn:aws:kms:us-east-1:111122223333:key/example".to_string(),
+            encrypted_key: vec![0, 1, 0xab, 0xff],
+        }
+    }
+
+    #[test]
+    fn test_spec_config_roundtrip() {
+        let spec = EncryptionSpec::try_new(
+            EncryptionScheme::ColumnAes256Ctr,
+            vec![column(1, "a"), column(3, "b")],
+        )
+        .unwrap();
+        let value = spec.to_config_value();
+        assert!(value.contains("\"encrypted_key\":\"0001abff\""), "{value}");
+        let config = HashMap::from([(ENCRYPTION_SPEC_KEY.to_string(), value)]);
+        let parsed = EncryptionSpec::try_from_config(&config).unwrap().unwrap();
+        assert_eq!(parsed, spec);
+        assert_eq!(parsed.column(3).unwrap().id, "b");
+        assert!(parsed.column(2).is_none());
+        assert_eq!(
+            EncryptionSpec::try_from_config(&HashMap::new()).unwrap(),
+            None
+        );
+
+        assert!(
+            EncryptionSpec::try_new(
+                EncryptionScheme::ColumnAes256Ctr,
+                vec![column(1, "a"), column(1, "b")]
+            )
+            .is_err()
+        );
+        assert!(
+            EncryptionSpec::try_new(
+                EncryptionScheme::ColumnAes256Ctr,
+                vec![column(1, "a"), column(2, "a")]
+            )
+            .is_err()
+        );
+        for invalid in [
+            "not json",
+            "{\"columns\":[]}",
+            "{\"columns\":[{\"field_id\":0,\"id\":\"a\",\"key_id\":\"k\",\"encrypted_key\":\"abc\"}]}",
+        ] {
+            let config = HashMap::from([(ENCRYPTION_SPEC_KEY.to_string(), invalid.to_string())]);
+            assert!(
+                EncryptionSpec::try_from_config(&config).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+}
@@ -13,7 +13,7 @@ use lance_io::utils::CachedFileSize;
 use object_store::path::Path;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::format::pb;
+use crate::format::{PartitionValue, pb};
 
 use crate::rowids::version::{
     RowDatasetVersionMeta, created_at_version_meta_to_pb, last_updated_at_version_meta_to_pb,
@@ -54,13 +54,64 @@ pub struct DataFile {
 
     /// The base path of the datafile, when the datafile is outside the dataset.
     pub base_id: Option<u32>,
+
+    /// Min/max statistics for the primitive top-level fields in this file.
+    ///
+    /// Empty when the writer did not collect statistics (e.g. legacy files or
+    /// files written by older versions of Lance).
+    pub zone_maps: Arc<[ColumnZoneMap]>,
+
+    /// The partition values shared by all rows of this file.
+    ///
+    /// Empty unless the file was written by a partitioned writer.
+    pub partition_values: Arc<[PartitionValue]>,
+}
+
+/// Min/max statistics for a single field within a [`DataFile`].
+///
+/// The extrema are kept in their serialized `lance-arrow-scalar` form so that
+/// loading a manifest does not need to decode them; they are only decoded when
+/// the scanner evaluates a filter against the fragment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, DeepSizeOf)]
+pub struct ColumnZoneMap {
+    /// The id of the field these statistics describe.
+    pub field_id: i32,
+    /// The smallest non-null value, or `None` if unknown (e.g. the field only
+    /// contains nulls or contains NaN values).
+    pub min: Option<Vec<u8>>,
+    /// The largest non-null value, or `None` if unknown.
+    pub max: Option<Vec<u8>>,
+    /// The number of null values in the field.
+    pub null_count: u64,
+}
+
+impl From<&ColumnZoneMap> for pb::ColumnZoneMap {
+    fn from(zone_map: &ColumnZoneMap) -> Self {
+        Self {
+            field_id: zone_map.field_id,
+            min: zone_map.min.clone().unwrap_or_default(),
+            max: zone_map.max.clone().unwrap_or_default(),
+            null_count: zone_map.null_count,
+        }
+    }
+}
+
+impl From<pb::ColumnZoneMap> for ColumnZoneMap {
+    fn from(proto: pb::ColumnZoneMap) -> Self {
+        Self {
+            field_id: proto.field_id,
+            min: (!proto.min.is_empty()).then_some(proto.min),
+            max: (!proto.max.is_empty()).then_some(proto.max),
+            null_count: proto.null_count,
+        }
+    }
 }
 
 // Custom Serialize: convert Arc<[i32]> to slice for transparent JSON output
 impl Serialize for DataFile {
     fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
         use serde::ser::SerializeStruct;
-        let mut s = serializer.serialize_struct("DataFile", 7)?;
+        let mut s = serializer.serialize_struct("DataFile", 9)?;
         s.serialize_field("path", &self.path)?;
         s.serialize_field("fields", self.fields.as_ref())?;
         s.serialize_field("column_indices", self.column_indices.as_ref())?;
@@ -68,6 +119,16 @@ impl Serialize for DataFile {
         s.serialize_field("file_minor_version", &self.file_minor_version)?;
         s.serialize_field("file_size_bytes", &self.file_size_bytes)?;
         s.serialize_field("base_id", &self.base_id)?;
+        if self.zone_maps.is_empty() {
+            s.skip_field("zone_maps")?;
+        } else {
+            s.serialize_field("zone_maps", self.zone_maps.as_ref())?;
+        }
+        if self.partition_values.is_empty() {
+            s.skip_field("partition_values")?;
+        } else {
+            s.serialize_field("partition_values", self.partition_values.as_ref())?;
+        }
         s.end()
     }
 }
@@ -87,6 +148,10 @@ impl<'de> Deserialize<'de> for DataFile {
             file_minor_version: u32,
             file_size_bytes: CachedFileSize,
             base_id: Option<u32>,
+            #[serde(default)]
+            zone_maps: Vec<ColumnZoneMap>,
+            #[serde(default)]
+            partition_values: Vec<PartitionValue>,
         }
 
         let helper = DataFileHelper::deserialize(deserializer)?;
@@ -98,6 +163,8 @@ impl<'de> Deserialize<'de> for DataFile {
             file_minor_version: helper.file_minor_version,
             file_size_bytes: helper.file_size_bytes,
             base_id: helper.base_id,
+            zone_maps: Arc::from(helper.zone_maps),
+            partition_values: Arc::from(helper.partition_values),
         })
     }
 }
@@ -120,6 +187,8 @@ impl DataFile {
             file_minor_version,
             file_size_bytes: file_size_bytes.into(),
             base_id,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         }
     }
 
@@ -137,6 +206,8 @@ impl DataFile {
             file_minor_version,
             file_size_bytes: Default::default(),
             base_id: None,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         }
     }
 
@@ -175,6 +246,35 @@ impl DataFile {
         )
     }
 
+    /// Attach the zone maps collected while writing this file.
+    pub fn with_zone_maps(mut self, zone_maps: Vec<ColumnZoneMap>) -> Self {
+        self.zone_maps = Arc::from(zone_maps);
+        self
+    }
+
+    /// Attach the partition values shared by all rows of this file.
+    pub fn with_partition_values(mut self, partition_values: Vec<PartitionValue>) -> Self {
+        self.partition_values = Arc::from(partition_values);
+        self
+    }
+
+    /// The partition values of `field_id`, if this file still stores the field.
+    pub fn partition_values(&self, field_id: i32) -> impl Iterator<Item = &PartitionValue> {
+        let stored = self.fields.contains(&field_id);
+        self.partition_values
+            .iter()
+            .filter(move |value| stored && value.field_id == field_id)
+    }
+
+    /// The zone map for `field_id`, if this file still stores the field and
+    /// statistics were collected for it.
+    pub fn zone_map(&self, field_id: i32) -> Option<&ColumnZoneMap> {
+        if !self.fields.contains(&field_id) {
+            return None;
+        }
+        self.zone_maps.iter().find(|z| z.field_id == field_id)
+    }
+
     pub fn schema(&self, full_schema: &Schema) -> Schema {
         full_schema.project_by_ids(&self.fields, false)
     }
@@ -213,6 +313,12 @@ impl From<&DataFile> for pb::DataFile {
             file_minor_version: df.file_minor_version,
             file_size_bytes: df.file_size_bytes.get().map_or(0, |v| v.get()),
             base_id: df.base_id,
+            zone_maps: df.zone_maps.iter().map(pb::ColumnZoneMap::from).collect(),
+            partition_values: df
+                .partition_values
+                .iter()
+                .map(pb::PartitionValue::from)
+                .collect(),
         }
     }
 }
@@ -229,6 +335,16 @@ impl TryFrom<pb::DataFile> for DataFile {
             file_minor_version: proto.file_minor_version,
             file_size_bytes: CachedFileSize::new(proto.file_size_bytes),
             base_id: proto.base_id,
+            zone_maps: proto
+                .zone_maps
+                .into_iter()
+                .map(ColumnZoneMap::from)
+                .collect(),
+            partition_values: proto
+                .partition_values
+                .into_iter()
+                .map(PartitionValue::try_from)
+                .collect::<Result<_>>()?,
         })
     }
 }
@@ -350,6 +466,16 @@ impl DataFileFieldInterner {
             file_minor_version: proto.file_minor_version,
             file_size_bytes: CachedFileSize::new(proto.file_size_bytes),
             base_id: proto.base_id,
+            zone_maps: proto
+                .zone_maps
+                .into_iter()
+                .map(ColumnZoneMap::from)
+                .collect(),
+            partition_values: proto
+                .partition_values
+                .into_iter()
+                .map(PartitionValue::try_from)
+                .collect::<Result<_>>()?,
         })
     }
 
@@ -786,6 +912,69 @@ mod tests {
         assert_eq!(fragment, fragment2);
     }
 
+    #[test]
+    fn test_roundtrip_zone_maps() {
+        let data_file = DataFile::new("foo.lance", vec![0, 1], vec![0, 1], 2, 1, None, None)
+            .with_zone_maps(vec![
+                ColumnZoneMap {
+                    field_id: 0,
+                    min: Some(vec![1, 2, 3]),
+                    max: Some(vec![4, 5, 6]),
+                    null_count: 7,
+                },
+                ColumnZoneMap {
+                    field_id: 1,
+                    min: None,
+                    max: None,
+                    null_count: 10,
+                },
+            ]);
+
+        let proto = pb::DataFile::from(&data_file);
+        assert_eq!(DataFile::try_from(proto.clone()).unwrap(), data_file);
+        let interned = DataFileFieldInterner::default()
+            .intern_data_file(proto)
+            .unwrap();
+        assert_eq!(interned, data_file);
+
+        let json = serde_json::to_string(&data_file).unwrap();
+        let from_json: DataFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json, data_file);
+
+        assert_eq!(data_file.zone_map(0).unwrap().null_count, 7);
+        assert!(data_file.zone_map(2).is_none());
+
+        // Zone maps of tombstoned fields no longer apply
+        let mut tombstoned = data_file;
+        tombstoned.fields = Arc::from([-2, 1]);
+        assert!(tombstoned.zone_map(0).is_none());
+        assert!(tombstoned.zone_map(1).is_some());
+    }
+
+    #[test]
+    fn test_roundtrip_partition_values() {
+        let data_file = DataFile::new("foo.lance", vec![0, 1], vec![0, 1], 2, 1, None, None)
+            .with_partition_values(vec![PartitionValue {
+                field_id: 1,
+                transform: crate::format::PartitionTransform::Hash { buckets: 4 },
+                value: vec![1, 2],
+            }]);
+
+        let proto = pb::DataFile::from(&data_file);
+        assert_eq!(DataFile::try_from(proto.clone()).unwrap(), data_file);
+        let interned = DataFileFieldInterner::default()
+            .intern_data_file(proto)
+            .unwrap();
+        assert_eq!(interned, data_file);
+
+        let json = serde_json::to_string(&data_file).unwrap();
+        let from_json: DataFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json, data_file);
+
+        assert_eq!(data_file.partition_values(1).count(), 1);
+        assert_eq!(data_file.partition_values(0).count(), 0);
+    }
+
     #[test]
     fn test_to_json() {
         let mut fragment = Fragment::new(123);
@@ -831,6 +1020,8 @@ mod tests {
             file_minor_version: MINOR_VERSION as u32,
             file_size_bytes: Default::default(),
             base_id: None,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         };
 
         let base_path = Path::from("base");
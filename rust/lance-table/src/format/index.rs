@@ -226,6 +226,47 @@ impl From<&IndexMetadata> for pb::IndexMetadata {
     }
 }
 
+/// An entry of the index directory of a manifest, pointing to the metadata of
+/// a single index stored in the manifest file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexDirectoryEntry {
+    pub uuid: Uuid,
+    pub name: String,
+    /// The file position of the `IndexMetadata` message of the index.
+    pub position: usize,
+}
+
+impl DeepSizeOf for IndexDirectoryEntry {
+    fn deep_size_of_children(&self, context: &mut lance_core::deepsize::Context) -> usize {
+        self.uuid.as_bytes().deep_size_of_children(context)
+            + self.name.deep_size_of_children(context)
+    }
+}
+
+impl TryFrom<pb::IndexDirectoryEntry> for IndexDirectoryEntry {
+    type Error = Error;
+
+    fn try_from(proto: pb::IndexDirectoryEntry) -> Result<Self> {
+        Ok(Self {
+            uuid: proto.uuid.as_ref().map(Uuid::try_from).ok_or_else(|| {
+                Error::invalid_input("uuid field does not exist in index directory".to_string())
+            })??,
+            name: proto.name,
+            position: proto.position as usize,
+        })
+    }
+}
+
+impl From<&IndexDirectoryEntry> for pb::IndexDirectoryEntry {
+    fn from(entry: &IndexDirectoryEntry) -> Self {
+        Self {
+            uuid: Some((&entry.uuid).into()),
+            name: entry.name.clone(),
+            position: entry.position as u64,
+        }
+    }
+}
+
 /// Returns a [`CacheCodec`](lance_core::cache::CacheCodec) for `Vec<IndexMetadata>`.
 ///
 /// Uses `pb::IndexSection` (which wraps `repeated IndexMetadata`) as the wire
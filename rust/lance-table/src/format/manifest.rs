@@ -11,15 +11,15 @@ use lance_io::traits::{ProtoStruct, Reader};
 use object_store::path::Path;
 use prost::Message;
 use prost_types::Timestamp;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Range;
 use std::sync::Arc;
 
-use super::Fragment;
+use super::{EncryptionSpec, Fragment, PartitionSpec};
 use crate::feature_flags::{FLAG_STABLE_ROW_IDS, has_deprecated_v2_feature_flag};
 use crate::format::fragment::DataFileFieldInterner;
 use crate::format::pb;
-use lance_core::cache::LanceCache;
+use lance_core::cache::{CacheCodecImpl, LanceCache};
 use lance_core::datatypes::Schema;
 use lance_core::{Error, Result};
 use lance_io::object_store::{ObjectStore, ObjectStoreRegistry};
@@ -57,6 +57,10 @@ pub struct Manifest {
     /// The file position of the index metadata.
     pub index_section: Option<usize>,
 
+    /// The file position of the index directory, which replaces the index
+    /// section when set. See [`EXTERNAL_INDEX_SECTION_KEY`].
+    pub index_directory: Option<usize>,
+
     /// The creation timestamp with nanosecond resolution as 128-bit integer
     pub timestamp_nanos: u128,
 
@@ -101,6 +105,41 @@ pub struct Manifest {
 
     /* external base paths */
     pub base_paths: HashMap<u32, BasePath>,
+
+    /// The manifest whose fragments this version is stored relative to, `None`
+    /// if the whole fragment list is stored. See [`DELTA_MANIFEST_MAX_DEPTH_KEY`].
+    pub delta_base: Option<DeltaBase>,
+}
+
+/// Table config key of the maximum number of consecutive delta manifests.
+///
+/// When set to a positive number, a commit stores only the fragments that were
+/// added, changed or removed since the previous version, so small appends to a
+/// dataset with many fragments do not rewrite the whole fragment list. Readers
+/// reconcile a delta manifest with the manifests it is based on, so after the
+/// given number of deltas the full fragment list is stored again. Cleanup
+/// keeps the manifests that retained versions are based on.
+pub const DELTA_MANIFEST_MAX_DEPTH_KEY: &str = "lance.delta_manifest.max_depth";
+
+/// Table config key to store the metadata of each index separately.
+///
+/// When set to `true`, commits write the metadata of every index as its own
+/// message in the manifest file, along with a small directory of the indices.
+/// Opening a dataset then no longer decodes the metadata of all indices and
+/// looking up an index by name or uuid only reads the metadata of that index,
+/// which matters for datasets with many indices.
+pub const EXTERNAL_INDEX_SECTION_KEY: &str = "lance.index_section.external";
+
+/// The manifest a delta manifest is stored relative to.
+#[derive(Debug, Clone, PartialEq, DeepSizeOf)]
+pub struct DeltaBase {
+    /// The version of the base manifest.
+    pub version: u64,
+    /// The number of consecutive delta manifests up to and including this one.
+    pub depth: u32,
+    /// The fragments of the base, to compute the delta from when the manifest
+    /// is written. `None` for manifests that were read.
+    pub fragments: Option<Arc<Vec<Fragment>>>,
 }
 
 // We use the most significant bit to indicate that a transaction is detached
@@ -183,6 +222,7 @@ impl Manifest {
             fragments,
             version_aux_data: 0,
             index_section: None,
+            index_directory: None,
             timestamp_nanos: 0,
             tag: None,
             reader_feature_flags: 0,
@@ -196,6 +236,7 @@ impl Manifest {
             config: HashMap::new(),
             table_metadata: HashMap::new(),
             base_paths,
+            delta_base: None,
         }
     }
 
@@ -205,6 +246,7 @@ impl Manifest {
         fragments: Arc<Vec<Fragment>>,
     ) -> Self {
         let fragment_offsets = compute_fragment_offsets(&fragments);
+        let delta_base = previous.next_delta_base();
 
         Self {
             schema,
@@ -214,7 +256,8 @@ impl Manifest {
             fragments,
             version_aux_data: 0,
             index_section: None, // Caller should update index if they want to keep them.
-            timestamp_nanos: 0,  // This will be set on commit
+            index_directory: None,
+            timestamp_nanos: 0, // This will be set on commit
             tag: None,
             reader_feature_flags: 0, // These will be set on commit
             writer_feature_flags: 0, // These will be set on commit
@@ -227,7 +270,129 @@ impl Manifest {
             config: previous.config.clone(),
             table_metadata: previous.table_metadata.clone(),
             base_paths: previous.base_paths.clone(),
+            delta_base,
+        }
+    }
+
+    /// The base of a delta manifest for the version after this one, `None` if
+    /// that version should store its whole fragment list.
+    fn next_delta_base(&self) -> Option<DeltaBase> {
+        let max_depth = self
+            .config
+            .get(DELTA_MANIFEST_MAX_DEPTH_KEY)
+            .and_then(|max_depth| max_depth.parse::<u32>().ok())?;
+        let depth = self.delta_base.as_ref().map_or(0, |base| base.depth) + 1;
+        if depth > max_depth || is_detached_version(self.version) {
+            return None;
+        }
+        Some(DeltaBase {
+            version: self.version,
+            depth,
+            fragments: Some(Arc::clone(&self.fragments)),
+        })
+    }
+
+    /// Whether the index metadata is stored behind an index directory, see
+    /// [`EXTERNAL_INDEX_SECTION_KEY`].
+    pub fn uses_index_directory(&self) -> bool {
+        self.config
+            .get(EXTERNAL_INDEX_SECTION_KEY)
+            .is_some_and(|value| value == "true")
+    }
+
+    /// Whether the manifest is stored as a delta of its [`DeltaBase`], which is
+    /// the case for new attached versions with a base.
+    pub fn is_stored_as_delta(&self) -> bool {
+        self.delta_base
+            .as_ref()
+            .is_some_and(|base| base.fragments.is_some())
+            && !is_detached_version(self.version)
+    }
+
+    /// The protobuf message stored for this manifest, with only the fragments
+    /// that differ from the base if [`Self::is_stored_as_delta`].
+    pub fn to_stored_proto(&self) -> pb::Manifest {
+        let Some((base, Some(base_fragments))) = self
+            .delta_base
+            .as_ref()
+            .filter(|_| self.is_stored_as_delta())
+            .map(|base| (base, base.fragments.as_ref()))
+        else {
+            return pb::Manifest::from(self);
+        };
+        let base_by_id = base_fragments
+            .iter()
+            .map(|fragment| (fragment.id, fragment))
+            .collect::<HashMap<_, _>>();
+        let ids = self
+            .fragments
+            .iter()
+            .map(|fragment| fragment.id)
+            .collect::<HashSet<_>>();
+        let changed = self
+            .fragments
+            .iter()
+            .filter(|fragment| {
+                base_by_id
+                    .get(&fragment.id)
+                    .is_none_or(|base_fragment| base_fragment != fragment)
+            })
+            .map(pb::DataFragment::from)
+            .collect();
+        let mut proto = self.proto_with_fragments(changed);
+        proto.delta_base = Some(pb::manifest::DeltaBase {
+            version: base.version,
+            removed_fragment_ids: base_fragments
+                .iter()
+                .map(|fragment| fragment.id)
+                .filter(|id| !ids.contains(id))
+                .collect(),
+            depth: base.depth,
+        });
+        proto
+    }
+
+    /// Decode a delta manifest, whose fragments are only those that changed
+    /// since `base`, with the full fragment list of its version.
+    pub fn try_from_delta(proto: pb::Manifest, base: &Self) -> Result<Self> {
+        let removed = proto
+            .delta_base
+            .as_ref()
+            .map(|delta_base| {
+                delta_base
+                    .removed_fragment_ids
+                    .iter()
+                    .copied()
+                    .collect::<HashSet<_>>()
+            })
+            .unwrap_or_default();
+        let mut manifest = Self::try_from(proto)?;
+        let base_version = manifest
+            .delta_base
+            .as_ref()
+            .map(|delta_base| delta_base.version);
+        if base_version != Some(base.version) {
+            return Err(Error::internal(format!(
+                "Manifest of version {} is not a delta of version {}",
+                manifest.version, base.version
+            )));
         }
+        let changed = manifest
+            .fragments
+            .iter()
+            .map(|fragment| fragment.id)
+            .collect::<HashSet<_>>();
+        let mut fragments = base
+            .fragments
+            .iter()
+            .filter(|fragment| !removed.contains(&fragment.id) && !changed.contains(&fragment.id))
+            .chain(manifest.fragments.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        fragments.sort_by_key(|fragment| fragment.id);
+        manifest.fragment_offsets = compute_fragment_offsets(&fragments);
+        manifest.fragments = Arc::new(fragments);
+        Ok(manifest)
     }
 
     /// Performs a shallow_clone of the manifest entirely in memory without:
@@ -271,6 +436,7 @@ impl Manifest {
             fragments: Arc::new(cloned_fragments),
             version_aux_data: self.version_aux_data,
             index_section: None, // These will be set on commit
+            index_directory: None,
             timestamp_nanos: self.timestamp_nanos,
             tag: None,
             reader_feature_flags: 0, // These will be set on commit
@@ -289,6 +455,7 @@ impl Manifest {
                 base_paths
             },
             table_metadata: self.table_metadata.clone(),
+            delta_base: None,
         }
     }
 
@@ -308,6 +475,16 @@ impl Manifest {
         self.timestamp_nanos = nanos;
     }
 
+    /// The partition spec writers route rows by, see [`PartitionSpec`].
+    pub fn partition_spec(&self) -> Result<Option<PartitionSpec>> {
+        PartitionSpec::try_from_config(&self.config)
+    }
+
+    /// The encrypted columns of the dataset, see [`EncryptionSpec`].
+    pub fn encryption_spec(&self) -> Result<Option<EncryptionSpec>> {
+        EncryptionSpec::try_from_config(&self.config)
+    }
+
     /// Get a mutable reference to the config
     pub fn config_mut(&mut self) -> &mut HashMap<String, String> {
         &mut self.config
@@ -909,6 +1086,7 @@ impl TryFrom<pb::Manifest> for Manifest {
             writer_version,
             version_aux_data: p.version_aux_data as usize,
             index_section: p.index_section.map(|i| i as usize),
+            index_directory: p.index_directory.map(|i| i as usize),
             timestamp_nanos: timestamp_nanos.unwrap_or(0),
             tag: if p.tag.is_empty() { None } else { Some(p.tag) },
             reader_feature_flags: p.reader_feature_flags,
@@ -931,34 +1109,46 @@ impl TryFrom<pb::Manifest> for Manifest {
                 .iter()
                 .map(|item| (item.id, item.clone().into()))
                 .collect(),
+            delta_base: p.delta_base.map(|delta_base| DeltaBase {
+                version: delta_base.version,
+                depth: delta_base.depth,
+                fragments: None,
+            }),
         })
     }
 }
 
 impl From<&Manifest> for pb::Manifest {
     fn from(m: &Manifest) -> Self {
-        let timestamp_nanos = if m.timestamp_nanos == 0 {
+        m.proto_with_fragments(m.fragments.iter().map(pb::DataFragment::from).collect())
+    }
+}
+
+impl Manifest {
+    /// The protobuf message of this manifest with the given fragments.
+    fn proto_with_fragments(&self, fragments: Vec<pb::DataFragment>) -> pb::Manifest {
+        let timestamp_nanos = if self.timestamp_nanos == 0 {
             None
         } else {
-            let nanos = m.timestamp_nanos % 1e9 as u128;
-            let seconds = ((m.timestamp_nanos - nanos) / 1e9 as u128) as i64;
+            let nanos = self.timestamp_nanos % 1e9 as u128;
+            let seconds = ((self.timestamp_nanos - nanos) / 1e9 as u128) as i64;
             Some(Timestamp {
                 seconds,
                 nanos: nanos as i32,
             })
         };
-        let fields_with_meta: FieldsWithMeta = (&m.schema).into();
-        Self {
+        let fields_with_meta: FieldsWithMeta = (&self.schema).into();
+        pb::Manifest {
             fields: fields_with_meta.fields.0,
-            schema_metadata: m
+            schema_metadata: self
                 .schema
                 .metadata
                 .iter()
                 .map(|(k, v)| (k.clone(), v.as_bytes().to_vec()))
                 .collect(),
-            version: m.version,
-            branch: m.branch.clone(),
-            writer_version: m
+            version: self.version,
+            branch: self.branch.clone(),
+            writer_version: self
                 .writer_version
                 .as_ref()
                 .map(|wv| pb::manifest::WriterVersion {
@@ -967,23 +1157,24 @@ impl From<&Manifest> for pb::Manifest {
                     prerelease: wv.prerelease.clone(),
                     build_metadata: wv.build_metadata.clone(),
                 }),
-            fragments: m.fragments.iter().map(pb::DataFragment::from).collect(),
-            table_metadata: m.table_metadata.clone(),
-            version_aux_data: m.version_aux_data as u64,
-            index_section: m.index_section.map(|i| i as u64),
+            fragments,
+            table_metadata: self.table_metadata.clone(),
+            version_aux_data: self.version_aux_data as u64,
+            index_section: self.index_section.map(|i| i as u64),
+            index_directory: self.index_directory.map(|i| i as u64),
             timestamp: timestamp_nanos,
-            tag: m.tag.clone().unwrap_or_default(),
-            reader_feature_flags: m.reader_feature_flags,
-            writer_feature_flags: m.writer_feature_flags,
-            max_fragment_id: m.max_fragment_id,
-            transaction_file: m.transaction_file.clone().unwrap_or_default(),
-            next_row_id: m.next_row_id,
+            tag: self.tag.clone().unwrap_or_default(),
+            reader_feature_flags: self.reader_feature_flags,
+            writer_feature_flags: self.writer_feature_flags,
+            max_fragment_id: self.max_fragment_id,
+            transaction_file: self.transaction_file.clone().unwrap_or_default(),
+            next_row_id: self.next_row_id,
             data_format: Some(pb::manifest::DataStorageFormat {
-                file_format: m.data_storage_format.file_format.clone(),
-                version: m.data_storage_format.version.clone(),
+                file_format: self.data_storage_format.file_format.clone(),
+                version: self.data_storage_format.version.clone(),
             }),
-            config: m.config.clone(),
-            base_paths: m
+            config: self.config.clone(),
+            base_paths: self
                 .base_paths
                 .values()
                 .map(|base_path| pb::BasePath {
@@ -993,8 +1184,37 @@ impl From<&Manifest> for pb::Manifest {
                     path: base_path.path.clone(),
                 })
                 .collect(),
-            transaction_section: m.transaction_section.map(|i| i as u64),
+            transaction_section: self.transaction_section.map(|i| i as u64),
+            // Set by `Manifest::to_stored_proto` for delta manifests.
+            delta_base: None,
+        }
+    }
+}
+
+/// Manifests are shared through external cache backends as their protobuf
+/// encoding.
+///
+/// Dictionary values of legacy dictionary fields are loaded from the manifest
+/// file separately and are not part of the encoding, so manifests with loaded
+/// dictionaries cannot be serialized.
+impl CacheCodecImpl for Manifest {
+    fn serialize(&self, writer: &mut dyn std::io::Write) -> Result<()> {
+        if self.schema.fields_pre_order().any(|field| {
+            field
+                .dictionary
+                .as_ref()
+                .is_some_and(|d| d.values.is_some())
+        }) {
+            return Err(Error::not_supported(
+                "Cannot serialize a manifest with loaded dictionary values",
+            ));
         }
+        writer.write_all(&pb::Manifest::from(self).encode_to_vec())?;
+        Ok(())
+    }
+
+    fn deserialize(data: &bytes::Bytes) -> Result<Self> {
+        Self::try_from(pb::Manifest::decode(data.as_ref())?)
     }
 }
 
@@ -1378,6 +1598,86 @@ mod tests {
         assert_eq!(manifest.config, config);
     }
 
+    #[test]
+    fn test_delta_manifest_roundtrip() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "a",
+            arrow_schema::DataType::Int64,
+            false,
+        )]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        let fragments = vec![
+            Fragment::with_file_legacy(0, "path1", &schema, Some(10)),
+            Fragment::with_file_legacy(1, "path2", &schema, Some(15)),
+            Fragment::with_file_legacy(2, "path3", &schema, Some(20)),
+        ];
+        let mut base = Manifest::new(
+            schema.clone(),
+            Arc::new(fragments),
+            DataStorageFormat::default(),
+            HashMap::new(),
+        );
+        base.config_mut()
+            .insert(DELTA_MANIFEST_MAX_DEPTH_KEY.to_string(), "1".to_string());
+
+        // Remove fragment 0, change fragment 1 and add fragment 3.
+        let fragments = vec![
+            Fragment::with_file_legacy(1, "path2", &schema, Some(12)),
+            Fragment::with_file_legacy(2, "path3", &schema, Some(20)),
+            Fragment::with_file_legacy(3, "path4", &schema, Some(5)),
+        ];
+        let delta = Manifest::new_from_previous(&base, schema.clone(), Arc::new(fragments));
+        assert!(delta.is_stored_as_delta());
+
+        let proto = delta.to_stored_proto();
+        let stored_ids = proto.fragments.iter().map(|f| f.id).collect::<Vec<_>>();
+        assert_eq!(stored_ids, vec![1, 3]);
+        let delta_base = proto.delta_base.as_ref().unwrap();
+        assert_eq!(delta_base.version, base.version);
+        assert_eq!(delta_base.removed_fragment_ids, vec![0]);
+
+        let decoded = Manifest::try_from_delta(proto, &base).unwrap();
+        assert_eq!(decoded.fragments, delta.fragments);
+        assert!(!decoded.is_stored_as_delta());
+
+        // The next version exceeds the maximum depth and stores all fragments.
+        let next = Manifest::new_from_previous(&decoded, schema, Arc::clone(&delta.fragments));
+        assert!(next.delta_base.is_none());
+        assert_eq!(next.to_stored_proto().fragments.len(), 3);
+    }
+
+    #[test]
+    fn test_manifest_cache_codec_roundtrip() {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "a",
+            arrow_schema::DataType::Int64,
+            false,
+        )]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        let fragments = vec![
+            Fragment::with_file_legacy(0, "path1", &schema, Some(10)),
+            Fragment::with_file_legacy(1, "path2", &schema, Some(15)),
+        ];
+        let mut manifest = Manifest::new(
+            schema,
+            Arc::new(fragments),
+            DataStorageFormat::default(),
+            HashMap::new(),
+        );
+        manifest.version = 7;
+        manifest
+            .config_mut()
+            .insert("lance.test".to_string(), "value".to_string());
+
+        let mut buf = Vec::new();
+        CacheCodecImpl::serialize(&manifest, &mut buf).unwrap();
+        let decoded = <Manifest as CacheCodecImpl>::deserialize(&bytes::Bytes::from(buf)).unwrap();
+        assert_eq!(decoded.version, manifest.version);
+        assert_eq!(decoded.schema, manifest.schema);
+        assert_eq!(decoded.fragments, manifest.fragments);
+        assert_eq!(decoded.config, manifest.config);
+    }
+
     #[test]
     fn test_manifest_summary() {
         // Step 1: test empty manifest summary
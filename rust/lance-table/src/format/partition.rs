@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Partitioning of the rows of a dataset by transforms of their values.
+//!
+//! A [`PartitionSpec`] stored in the manifest config makes writers route the
+//! rows of every write into separate data files per partition.  Each such file
+//! records the [`PartitionValue`]s its rows share, so the scanner can skip the
+//! fragments of other partitions.  The values describe their own transform, so
+//! files stay prunable after the spec changes.
+
+use std::collections::HashMap;
+
+use lance_core::deepsize::DeepSizeOf;
+use lance_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::format::pb;
+
+/// The manifest config key of the [`PartitionSpec`] used by writers.
+pub const PARTITION_SPEC_KEY: &str = "lance.partition_spec";
+
+/// How the values of a field are mapped to partitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, DeepSizeOf)]
+#[serde(rename_all = "snake_case")]
+pub enum PartitionTransform {
+    /// Every distinct value is a partition.
+    Identity,
+    /// Values are spread over `buckets` partitions by a hash of the value.
+    Hash { buckets: u32 },
+    /// Integer, date and timestamp values are grouped into ranges of `width`,
+    /// the partition of a value is `floor(value / width)`.
+    Range { width: u64 },
+}
+
+impl PartitionTransform {
+    fn validate(&self) -> Result<()> {
+        match self {
+            Self::Hash { buckets: 0 } => Err(Error::invalid_input(
+                "A hash partition transform needs at least one bucket",
+            )),
+            Self::Range { width: 0 } => Err(Error::invalid_input(
+                "A range partition transform needs a positive width",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A transform of a single field, see [`PartitionSpec`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartitionField {
+    /// The id of the top-level field the transform is applied to.
+    pub field_id: i32,
+    pub transform: PartitionTransform,
+}
+
+/// The partitioning of a dataset, rows share a partition when all transforms
+/// map them to the same values.
+///
+/// The spec is stored as JSON under [`PARTITION_SPEC_KEY`] in the manifest
+/// config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartitionSpec {
+    pub fields: Vec<PartitionField>,
+}
+
+impl PartitionSpec {
+    pub fn try_new(fields: Vec<PartitionField>) -> Result<Self> {
+        if fields.is_empty() {
+            return Err(Error::invalid_input(
+                "A partition spec needs at least one field",
+            ));
+        }
+        for field in &fields {
+            field.transform.validate()?;
+        }
+        Ok(Self { fields })
+    }
+
+    /// The spec stored in a manifest config, if any.
+    pub fn try_from_config(config: &HashMap<String, String>) -> Result<Option<Self>> {
+        let Some(value) = config.get(PARTITION_SPEC_KEY) else {
+            return Ok(None);
+        };
+        let spec: Self = serde_json::from_str(value).map_err(|err| {
+            Error::invalid_input(format!(
+                "Invalid partition spec in {PARTITION_SPEC_KEY}: {err}"
+            ))
+        })?;
+        Self::try_new(spec.fields).map(Some)
+    }
+
+    /// The value stored under [`PARTITION_SPEC_KEY`].
+    pub fn to_config_value(&self) -> String {
+        serde_json::to_string(self).expect("partition specs are serializable")
+    }
+}
+
+/// The value of a transform shared by all rows of a data file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, DeepSizeOf)]
+pub struct PartitionValue {
+    /// The id of the field the transform is applied to.
+    pub field_id: i32,
+    pub transform: PartitionTransform,
+    /// The transformed value in the `lance-arrow-scalar` format, a null scalar
+    /// when the field is null.
+    ///
+    /// Identity transforms keep the type of the field, hash transforms produce
+    /// a `UInt32` bucket and range transforms an `Int64` range number.
+    pub value: Vec<u8>,
+}
+
+impl From<&PartitionValue> for pb::PartitionValue {
+    fn from(value: &PartitionValue) -> Self {
+        let (hash_buckets, range_width) = match value.transform {
+            PartitionTransform::Identity => (0, 0),
+            PartitionTransform::Hash { buckets } => (buckets, 0),
+            PartitionTransform::Range { width } => (0, width),
+        };
+        Self {
+            field_id: value.field_id,
+            hash_buckets,
+            range_width,
+            value: value.value.clone(),
+        }
+    }
+}
+
+impl TryFrom<pb::PartitionValue> for PartitionValue {
+    type Error = Error;
+
+    fn try_from(proto: pb::PartitionValue) -> Result<Self> {
+        let transform = match (proto.hash_buckets, proto.range_width) {
+            (0, 0) => PartitionTransform::Identity,
+            (buckets, 0) => PartitionTransform::Hash { buckets },
+            (0, width) => PartitionTransform::Range { width },
+            _ => {
+                return Err(Error::invalid_input(format!(
+                    "Partition value of field {} has both a hash and a range transform",
+                    proto.field_id
+                )));
+            }
+        };
+        Ok(Self {
+            field_id: proto.field_id,
+            transform,
+            value: proto.value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_config_roundtrip() {
+        let spec = PartitionSpec::try_new(vec![
+            PartitionField {
+                field_id: 0,
+                transform: PartitionTransform::Identity,
+            },
+            PartitionField {
+                field_id: 2,
+                transform: PartitionTransform::Hash { buckets: 16 },
+            },
+            PartitionField {
+                field_id: 3,
+                transform: PartitionTransform::Range { width: 3600 },
+            },
+        ])
+        .unwrap();
+        let config = HashMap::from([(PARTITION_SPEC_KEY.to_string(), spec.to_config_value())]);
+        assert_eq!(PartitionSpec::try_from_config(&config).unwrap(), Some(spec));
+        assert_eq!(PartitionSpec::try_from_config(&HashMap::new()).unwrap(), None);
+
+        for invalid in [
+            "not json",
+            "{\"fields\":[]}",
+            "{\"fields\":[{\"field_id\":0,\"transform\":{\"hash\":{\"buckets\":0}}}]}",
+        ] {
+            let config = HashMap::from([(PARTITION_SPEC_KEY.to_string(), invalid.to_string())]);
+            assert!(PartitionSpec::try_from_config(&config).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_partition_value_roundtrip() {
+        for transform in [
+            PartitionTransform::Identity,
+            PartitionTransform::Hash { buckets: 8 },
+            PartitionTransform::Range { width: 10 },
+        ] {
+            let value = PartitionValue {
+                field_id: 4,
+                transform,
+                value: vec![1, 2, 3],
+            };
+            let proto = pb::PartitionValue::from(&value);
+            assert_eq!(PartitionValue::try_from(proto).unwrap(), value);
+        }
+
+        let proto = pb::PartitionValue {
+            field_id: 4,
+            hash_buckets: 8,
+            range_width: 10,
+            value: vec![],
+        };
+        assert!(PartitionValue::try_from(proto).is_err());
+    }
+}
@@ -50,7 +50,9 @@ pub mod dynamodb;
 pub mod external_manifest;
 
 use lance_core::{Error, Result};
-use lance_io::object_store::{ObjectStore, ObjectStoreExt, ObjectStoreParams};
+use lance_io::object_store::{
+    ObjectStore, ObjectStoreExt, ObjectStoreParams, registered_object_store_provider,
+};
 use lance_io::traits::{WriteExt, Writer};
 
 use crate::format::{IndexMetadata, Manifest, Transaction, is_detached_version};
@@ -60,7 +62,7 @@ use {
     self::external_manifest::{ExternalManifestCommitHandler, ExternalManifestStore},
     aws_credential_types::provider::ProvideCredentials,
     aws_credential_types::provider::error::CredentialsError,
-    lance_io::object_store::{StorageOptions, providers::aws::build_aws_credential},
+    lance_io::object_store::{StorageOptions, providers::aws::build_aws_credential_with_profile},
     object_store::aws::AmazonS3ConfigKey,
     object_store::aws::AwsCredentialProvider,
     std::borrow::Cow,
@@ -1074,6 +1076,8 @@ pub async fn commit_handler_from_url(
         "s3" | "gs" | "az" | "abfss" | "memory" | "oss" | "cos" | "shared-memory" => {
             Ok(Arc::new(ConditionalPutCommitHandler))
         }
+        // HDFS renames are atomic and fail if the destination exists
+        "hdfs" => Ok(Arc::new(RenameCommitHandler)),
         #[cfg(not(feature = "dynamodb"))]
         "s3+ddb" => Err(Error::invalid_input_source(
             "`s3+ddb://` scheme requires `dynamodb` feature to be enabled".into(),
@@ -1113,12 +1117,13 @@ pub async fn commit_handler_from_url(
             // Get accessor from the options
             let accessor = options.get_accessor();
 
-            let (aws_creds, region) = build_aws_credential(
+            let (aws_creds, region) = build_aws_credential_with_profile(
                 options.s3_credentials_refresh_offset,
                 options.aws_credentials.clone(),
                 Some(&storage_options),
                 region,
                 accessor,
+                storage_options_raw.aws_profile(),
             )
             .await?;
 
@@ -1133,7 +1138,12 @@ pub async fn commit_handler_from_url(
                 .await?,
             }))
         }
-        _ => Ok(Arc::new(UnsafeCommitHandler)),
+        scheme => match registered_object_store_provider(scheme) {
+            Some(provider) if provider.supports_conditional_put() => {
+                Ok(Arc::new(ConditionalPutCommitHandler))
+            }
+            _ => Ok(Arc::new(UnsafeCommitHandler)),
+        },
     }
 }
 
@@ -1961,6 +1971,14 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_commit_handler_from_url_hdfs() {
+        let handler = commit_handler_from_url("hdfs://namenode:8020/ds", &None)
+            .await
+            .unwrap();
+        assert_eq!(format!("{:?}", handler), "RenameCommitHandler");
+    }
+
     /// A [CommitLock] whose lease records whether it was released, so we can
     /// assert the lock does not leak when the commit future is cancelled.
     #[derive(Debug)]
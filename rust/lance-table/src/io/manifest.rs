@@ -19,13 +19,15 @@ use lance_core::{Error, Result, datatypes::Schema};
 use lance_io::{
     encodings::{Encoder, binary::BinaryEncoder, plain::PlainEncoder},
     object_store::ObjectStore,
-    traits::{WriteExt, Writer},
+    traits::{Reader, WriteExt, Writer},
     utils::read_message,
 };
 
-use crate::format::{DataStorageFormat, IndexMetadata, MAGIC, Manifest, Transaction, pb};
+use crate::format::{
+    DataStorageFormat, IndexDirectoryEntry, IndexMetadata, MAGIC, Manifest, Transaction, pb,
+};
 
-use super::commit::ManifestLocation;
+use super::commit::{ManifestLocation, ManifestNamingScheme};
 
 /// Read Manifest on URI.
 ///
@@ -105,7 +107,51 @@ pub async fn read_manifest(
     }
 
     let proto = pb::Manifest::decode(buf)?;
-    Manifest::try_from(proto)
+    match proto
+        .delta_base
+        .as_ref()
+        .map(|delta_base| delta_base.version)
+    {
+        Some(base_version) => {
+            let base_path = sibling_manifest_path(path, base_version)?;
+            let base = Box::pin(read_manifest(object_store, &base_path, None)).await?;
+            Manifest::try_from_delta(proto, &base)
+        }
+        None => Manifest::try_from(proto),
+    }
+}
+
+/// The path of the manifest of `version` in the directory of the manifest at
+/// `path`, named with the same naming scheme. `path` may be a staged manifest.
+fn sibling_manifest_path(path: &Path, version: u64) -> Result<Path> {
+    let parts = path.parts().collect::<Vec<_>>();
+    let scheme = parts.last().map(|filename| {
+        ManifestNamingScheme::detect_scheme(filename.as_ref())
+            .unwrap_or_else(|| ManifestNamingScheme::detect_scheme_staging(filename.as_ref()))
+    });
+    match scheme {
+        Some(scheme) if parts.len() >= 2 => {
+            let base = Path::from_iter(parts[..parts.len() - 2].iter().cloned());
+            Ok(scheme.manifest_path(&base, version))
+        }
+        _ => Err(Error::corrupt_file(
+            path.clone(),
+            "Delta manifest is not in a versions directory".to_string(),
+        )),
+    }
+}
+
+async fn open_manifest_file(
+    object_store: &ObjectStore,
+    location: &ManifestLocation,
+) -> Result<Box<dyn Reader>> {
+    if let Some(size) = location.size {
+        object_store
+            .open_with_size(&location.path, size as usize)
+            .await
+    } else {
+        object_store.open(&location.path).await
+    }
 }
 
 #[instrument(level = "debug", skip(object_store, manifest))]
@@ -114,14 +160,18 @@ pub async fn read_manifest_indexes(
     location: &ManifestLocation,
     manifest: &Manifest,
 ) -> Result<Vec<IndexMetadata>> {
-    if let Some(pos) = manifest.index_section.as_ref() {
-        let reader = if let Some(size) = location.size {
-            object_store
-                .open_with_size(&location.path, size as usize)
-                .await?
-        } else {
-            object_store.open(&location.path).await?
-        };
+    if let Some(pos) = manifest.index_directory {
+        let reader = open_manifest_file(object_store, location).await?;
+        let directory: pb::IndexDirectory = read_message(reader.as_ref(), pos).await?;
+        let mut indices = Vec::with_capacity(directory.entries.len());
+        for entry in directory.entries {
+            let index: pb::IndexMetadata =
+                read_message(reader.as_ref(), entry.position as usize).await?;
+            indices.push(IndexMetadata::try_from(index)?);
+        }
+        Ok(indices)
+    } else if let Some(pos) = manifest.index_section.as_ref() {
+        let reader = open_manifest_file(object_store, location).await?;
         let section: pb::IndexSection = read_message(reader.as_ref(), *pos).await?;
 
         let indices = section
@@ -135,6 +185,49 @@ pub async fn read_manifest_indexes(
     }
 }
 
+/// Read the index directory of a manifest.
+///
+/// Returns `None` if the manifest stores its indices in an index section, in
+/// which case [`read_manifest_indexes`] must be used.
+#[instrument(level = "debug", skip(object_store, manifest))]
+pub async fn read_manifest_index_directory(
+    object_store: &ObjectStore,
+    location: &ManifestLocation,
+    manifest: &Manifest,
+) -> Result<Option<Vec<IndexDirectoryEntry>>> {
+    let Some(pos) = manifest.index_directory else {
+        return Ok(None);
+    };
+    let reader = open_manifest_file(object_store, location).await?;
+    let directory: pb::IndexDirectory = read_message(reader.as_ref(), pos).await?;
+    directory
+        .entries
+        .into_iter()
+        .map(IndexDirectoryEntry::try_from)
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
+/// Read the metadata of the indices of `entries` from the index directory of
+/// the manifest at `location`.
+#[instrument(level = "debug", skip(object_store, entries))]
+pub async fn read_manifest_directory_indexes(
+    object_store: &ObjectStore,
+    location: &ManifestLocation,
+    entries: &[&IndexDirectoryEntry],
+) -> Result<Vec<IndexMetadata>> {
+    if entries.is_empty() {
+        return Ok(vec![]);
+    }
+    let reader = open_manifest_file(object_store, location).await?;
+    let mut indices = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let index: pb::IndexMetadata = read_message(reader.as_ref(), entry.position).await?;
+        indices.push(IndexMetadata::try_from(index)?);
+    }
+    Ok(indices)
+}
+
 async fn do_write_manifest(
     writer: &mut dyn Writer,
     manifest: &mut Manifest,
@@ -143,11 +236,31 @@ async fn do_write_manifest(
 ) -> Result<usize> {
     // Write indices if presented.
     if let Some(indices) = indices.as_ref() {
-        let section = pb::IndexSection {
-            indices: indices.iter().map(|i| i.into()).collect(),
-        };
-        let pos = writer.write_protobuf(&section).await?;
-        manifest.index_section = Some(pos);
+        if manifest.uses_index_directory() {
+            let mut entries = Vec::with_capacity(indices.len());
+            for index in indices {
+                let position = writer
+                    .write_protobuf(&pb::IndexMetadata::from(index))
+                    .await?;
+                entries.push(pb::IndexDirectoryEntry::from(&IndexDirectoryEntry {
+                    uuid: index.uuid,
+                    name: index.name.clone(),
+                    position,
+                }));
+            }
+            let pos = writer
+                .write_protobuf(&pb::IndexDirectory { entries })
+                .await?;
+            manifest.index_directory = Some(pos);
+            manifest.index_section = None;
+        } else {
+            let section = pb::IndexSection {
+                indices: indices.iter().map(|i| i.into()).collect(),
+            };
+            let pos = writer.write_protobuf(&section).await?;
+            manifest.index_section = Some(pos);
+            manifest.index_directory = None;
+        }
     }
 
     // Write inline transaction if presented.
@@ -158,7 +271,7 @@ async fn do_write_manifest(
         manifest.transaction_section = Some(pos);
     }
 
-    writer.write_struct(manifest).await
+    writer.write_protobuf(&manifest.to_stored_proto()).await
 }
 
 /// Write manifest to an open file.
@@ -5,5 +5,7 @@
 
 pub(crate) mod dataframe;
 pub(crate) mod logical_plan;
+pub(crate) mod sink;
 
-pub use dataframe::LanceTableProvider;
+pub use dataframe::{LanceScanOptions, LanceTableProvider, LanceTableProviderBuilder};
+pub use sink::LanceSinkExec;
@@ -3,6 +3,7 @@
 
 use std::{
     any::Any,
+    collections::HashMap,
     sync::{Arc, Mutex},
 };
 
@@ -10,50 +11,105 @@ use arrow_schema::{Schema, SchemaRef};
 use async_trait::async_trait;
 use datafusion::{
     catalog::{Session, streaming::StreamingTable},
+    common::{ColumnStatistics, Statistics, stats::Precision},
     dataframe::DataFrame,
     datasource::TableProvider,
     error::DataFusionError,
     execution::{TaskContext, context::SessionContext},
-    logical_expr::{Expr, TableProviderFilterPushDown, TableType},
+    logical_expr::{Expr, TableProviderFilterPushDown, TableType, Volatility, dml::InsertOp},
     physical_plan::{ExecutionPlan, SendableRecordBatchStream, streaming::PartitionStream},
 };
 use lance_arrow::SchemaExt;
+use lance_core::datatypes::Field as LanceField;
 use lance_core::{ROW_ADDR_FIELD, ROW_ID_FIELD};
 
-use crate::Dataset;
+use crate::dataset::scanner::Scanner;
+use crate::dataset::statistics::DatasetStatisticsExt;
+use crate::dataset::{WriteMode, WriteParams};
+use crate::{Dataset, Result};
+
+use super::LanceSinkExec;
 
 /// A [TableProvider] for Lance datasets.
 ///
 /// Note: Datafusion has no concept of "system columns".  As a result, you must specify
 /// which schema columns should be included in the table's schema when you create the
-/// provider.
+/// provider. The `_rowid` and `_rowaddr` columns are appended to the end of the schema,
+/// so they can be selected, filtered and joined on like any other column.
 ///
 /// This table provider should support:
 ///  - Filter pushdown
 ///  - Limit pushdown
 ///  - Projection pushdown
+///  - `INSERT INTO` and `INSERT OVERWRITE`
+///  - Row counts and column byte sizes for the optimizer, if built with
+///    [LanceTableProviderBuilder::with_statistics]
 ///
 /// Note that LanceDB also has a TableProvider implementation that should be preferred
 /// if you are working in LanceDB.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use lance::dataset::Dataset;
+/// # use lance::datafusion::LanceTableProvider;
+/// # async fn example(dataset: Arc<Dataset>) -> lance::Result<()> {
+/// let provider = LanceTableProvider::builder(dataset)
+///     .with_row_id(true)
+///     .with_version(3)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug)]
 pub struct LanceTableProvider {
     dataset: Arc<Dataset>,
     full_schema: Arc<Schema>,
     row_id_idx: Option<usize>,
     row_addr_idx: Option<usize>,
-    ordered: bool,
+    scan_options: LanceScanOptions,
+    statistics: Option<Statistics>,
 }
 
 impl LanceTableProvider {
+    /// Create a builder for a provider over `dataset`.
+    pub fn builder(dataset: Arc<Dataset>) -> LanceTableProviderBuilder {
+        LanceTableProviderBuilder::new(dataset)
+    }
+
+    #[deprecated(note = "Use LanceTableProvider::builder instead")]
     pub fn new(dataset: Arc<Dataset>, with_row_id: bool, with_row_addr: bool) -> Self {
-        Self::new_with_ordering(dataset, with_row_id, with_row_addr, true)
+        Self::from_dataset(
+            dataset,
+            with_row_id,
+            with_row_addr,
+            LanceScanOptions::default(),
+        )
     }
 
+    #[deprecated(note = "Use LanceTableProvider::builder with LanceScanOptions::scan_in_order")]
     pub fn new_with_ordering(
         dataset: Arc<Dataset>,
         with_row_id: bool,
         with_row_addr: bool,
         ordered: bool,
+    ) -> Self {
+        Self::from_dataset(
+            dataset,
+            with_row_id,
+            with_row_addr,
+            LanceScanOptions {
+                scan_in_order: Some(ordered),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn from_dataset(
+        dataset: Arc<Dataset>,
+        with_row_id: bool,
+        with_row_addr: bool,
+        scan_options: LanceScanOptions,
     ) -> Self {
         let mut full_schema = Schema::from(dataset.schema());
         let mut row_id_idx = None;
@@ -71,13 +127,198 @@ impl LanceTableProvider {
             full_schema: Arc::new(full_schema),
             row_id_idx,
             row_addr_idx,
-            ordered,
+            scan_options,
+            statistics: None,
         }
     }
 
+    /// Collect the statistics of the dataset for [TableProvider::statistics].
+    ///
+    /// The row count comes from the manifest and the byte sizes from the
+    /// column metadata of the data files. Lance does not record null counts or
+    /// value ranges, so those are unknown.
+    async fn collect_statistics(&self) -> Result<Statistics> {
+        let num_rows = self.dataset.count_rows(None).await?;
+        let data_stats = self.dataset.calculate_data_stats().await?;
+        let field_bytes = data_stats
+            .fields
+            .iter()
+            .map(|field| (field.id as i32, field.bytes_on_disk))
+            .collect::<HashMap<_, _>>();
+
+        let mut column_statistics = self
+            .dataset
+            .schema()
+            .fields
+            .iter()
+            .map(|field| ColumnStatistics {
+                byte_size: Precision::Inexact(total_field_bytes(field, &field_bytes) as usize),
+                ..ColumnStatistics::new_unknown()
+            })
+            .collect::<Vec<_>>();
+        // `_rowid` and `_rowaddr` are computed, never null, 8 byte values.
+        for _ in self.row_id_idx.iter().chain(&self.row_addr_idx) {
+            column_statistics.push(ColumnStatistics {
+                null_count: Precision::Exact(0),
+                byte_size: Precision::Inexact(num_rows * 8),
+                ..ColumnStatistics::new_unknown()
+            });
+        }
+        let total_byte_size = column_statistics
+            .iter()
+            .filter_map(|column| column.byte_size.get_value().copied())
+            .sum();
+        Ok(Statistics {
+            num_rows: Precision::Exact(num_rows),
+            total_byte_size: Precision::Inexact(total_byte_size),
+            column_statistics,
+        })
+    }
+
     pub fn dataset(&self) -> Arc<Dataset> {
         self.dataset.clone()
     }
+
+    /// The index of the `_rowid` column in the schema, if it is exposed.
+    pub fn row_id_column(&self) -> Option<usize> {
+        self.row_id_idx
+    }
+
+    /// The index of the `_rowaddr` column in the schema, if it is exposed.
+    pub fn row_address_column(&self) -> Option<usize> {
+        self.row_addr_idx
+    }
+}
+
+/// Scanner settings applied to every scan of a [LanceTableProvider].
+///
+/// Unset options keep the [Scanner] defaults.
+#[derive(Debug, Clone, Default)]
+pub struct LanceScanOptions {
+    /// See [Scanner::batch_size].
+    pub batch_size: Option<usize>,
+    /// See [Scanner::batch_readahead].
+    pub batch_readahead: Option<usize>,
+    /// See [Scanner::fragment_readahead].
+    pub fragment_readahead: Option<usize>,
+    /// See [Scanner::io_buffer_size].
+    pub io_buffer_size: Option<u64>,
+    /// See [Scanner::use_scalar_index].
+    pub use_scalar_index: Option<bool>,
+    /// See [Scanner::use_stats].
+    pub use_stats: Option<bool>,
+    /// See [Scanner::scan_in_order].
+    pub scan_in_order: Option<bool>,
+    /// See [Scanner::allow_repartition].
+    ///
+    /// If true, DataFusion may split each scan into up to `target_partitions` partitions
+    /// of whole fragments (see `datafusion.optimizer.repartition_file_scans`).
+    pub allow_repartition: Option<bool>,
+}
+
+impl LanceScanOptions {
+    fn apply(&self, scan: &mut Scanner) {
+        if let Some(batch_size) = self.batch_size {
+            scan.batch_size(batch_size);
+        }
+        if let Some(batch_readahead) = self.batch_readahead {
+            scan.batch_readahead(batch_readahead);
+        }
+        if let Some(fragment_readahead) = self.fragment_readahead {
+            scan.fragment_readahead(fragment_readahead);
+        }
+        if let Some(io_buffer_size) = self.io_buffer_size {
+            scan.io_buffer_size(io_buffer_size);
+        }
+        if let Some(use_scalar_index) = self.use_scalar_index {
+            scan.use_scalar_index(use_scalar_index);
+        }
+        if let Some(use_stats) = self.use_stats {
+            scan.use_stats(use_stats);
+        }
+        if let Some(scan_in_order) = self.scan_in_order {
+            scan.scan_in_order(scan_in_order);
+        }
+        if let Some(allow_repartition) = self.allow_repartition {
+            scan.allow_repartition(allow_repartition);
+        }
+    }
+}
+
+/// Builder for a [LanceTableProvider].
+#[derive(Debug, Clone)]
+pub struct LanceTableProviderBuilder {
+    dataset: Arc<Dataset>,
+    with_row_id: bool,
+    with_row_address: bool,
+    version: Option<u64>,
+    scan_options: LanceScanOptions,
+    with_statistics: bool,
+}
+
+impl LanceTableProviderBuilder {
+    pub fn new(dataset: Arc<Dataset>) -> Self {
+        Self {
+            dataset,
+            with_row_id: false,
+            with_row_address: false,
+            version: None,
+            scan_options: LanceScanOptions::default(),
+            with_statistics: false,
+        }
+    }
+
+    /// Expose the row id as a `_rowid` column.
+    pub fn with_row_id(mut self, with_row_id: bool) -> Self {
+        self.with_row_id = with_row_id;
+        self
+    }
+
+    /// Expose the row address as a `_rowaddr` column.
+    pub fn with_row_address(mut self, with_row_address: bool) -> Self {
+        self.with_row_address = with_row_address;
+        self
+    }
+
+    /// Read a specific version of the dataset instead of the given one.
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Settings for the scanner of each scan.
+    pub fn with_scan_options(mut self, scan_options: LanceScanOptions) -> Self {
+        self.scan_options = scan_options;
+        self
+    }
+
+    /// Collect the row count and column byte sizes of the dataset when the
+    /// provider is built, and report them to the optimizer.
+    ///
+    /// This reads the metadata of every data file, so it is off by default.
+    pub fn with_statistics(mut self, with_statistics: bool) -> Self {
+        self.with_statistics = with_statistics;
+        self
+    }
+
+    pub async fn build(self) -> Result<LanceTableProvider> {
+        let dataset = match self.version {
+            Some(version) if version != self.dataset.version().version => {
+                Arc::new(self.dataset.checkout_version(version).await?)
+            }
+            _ => self.dataset,
+        };
+        let mut provider = LanceTableProvider::from_dataset(
+            dataset,
+            self.with_row_id,
+            self.with_row_address,
+            self.scan_options,
+        );
+        if self.with_statistics {
+            provider.statistics = Some(provider.collect_statistics().await?);
+        }
+        Ok(provider)
+    }
 }
 
 #[async_trait]
@@ -94,6 +335,10 @@ impl TableProvider for LanceTableProvider {
         TableType::Base
     }
 
+    fn statistics(&self) -> Option<Statistics> {
+        self.statistics.clone()
+    }
+
     async fn scan(
         &self,
         _state: &dyn Session,
@@ -121,7 +366,14 @@ impl TableProvider for LanceTableProvider {
                     scan.project(&columns)?;
                 }
             }
-            _ => {}
+            None => {
+                if self.row_id_idx.is_some() {
+                    scan.with_row_id();
+                }
+                if self.row_addr_idx.is_some() {
+                    scan.with_row_address();
+                }
+            }
         }
 
         let combined_filter = match filters.len() {
@@ -139,25 +391,129 @@ impl TableProvider for LanceTableProvider {
             scan.filter_expr(combined_filter);
         }
         scan.limit(limit.map(|l| l as i64), None)?;
-        scan.scan_in_order(self.ordered);
+        self.scan_options.apply(&mut scan);
 
         scan.create_plan().await.map_err(DataFusionError::from)
     }
 
-    // Since we are using datafusion itself to apply the filters it should
-    // be safe to assume that we can exactly apply any of the given pushdown
-    // filters.
+    /// Append to or overwrite the dataset with the output of `input`.
+    ///
+    /// The data is committed as a new version of the dataset. This provider
+    /// keeps reading the version it was created with.
+    async fn insert_into(
+        &self,
+        _state: &dyn Session,
+        input: Arc<dyn ExecutionPlan>,
+        insert_op: InsertOp,
+    ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
+        let mode = match insert_op {
+            InsertOp::Append => WriteMode::Append,
+            InsertOp::Overwrite => WriteMode::Overwrite,
+            InsertOp::Replace => {
+                return Err(DataFusionError::NotImplemented(
+                    "REPLACE INTO is not supported for Lance datasets".to_string(),
+                ));
+            }
+        };
+        let system_columns = self
+            .row_id_idx
+            .into_iter()
+            .chain(self.row_addr_idx)
+            .collect();
+        Ok(Arc::new(
+            LanceSinkExec::new(
+                input,
+                self.dataset.clone(),
+                WriteParams {
+                    mode,
+                    ..Default::default()
+                },
+            )
+            .with_system_columns(system_columns),
+        ))
+    }
+
+    // The scanner evaluates pushed filters with datafusion itself, so any
+    // filter it can plan is applied exactly. Filters it can't plan are left
+    // to datafusion.
     fn supports_filters_pushdown(
         &self,
         filters: &[&Expr],
     ) -> datafusion::common::Result<Vec<TableProviderFilterPushDown>> {
         Ok(filters
             .iter()
-            .map(|_| TableProviderFilterPushDown::Exact)
+            .map(|filter| {
+                if is_pushable_filter(filter) {
+                    TableProviderFilterPushDown::Exact
+                } else {
+                    TableProviderFilterPushDown::Unsupported
+                }
+            })
             .collect())
     }
 }
 
+/// The bytes on disk of a field and its children.
+fn total_field_bytes(field: &LanceField, field_bytes: &HashMap<i32, u64>) -> u64 {
+    field_bytes.get(&field.id).copied().unwrap_or_default()
+        + field
+            .children
+            .iter()
+            .map(|child| total_field_bytes(child, field_bytes))
+            .sum::<u64>()
+}
+
+/// Whether the scanner can evaluate `expr` as (part of) a filter.
+///
+/// This covers comparisons, `IN` lists, `BETWEEN`, `IS [NOT] NULL` and the
+/// other `IS` tests, `LIKE` and non-volatile functions such as `starts_with`,
+/// and any boolean combination of them. Subqueries, aggregates, window
+/// functions, placeholders and volatile functions are not pushed down.
+fn is_pushable_filter(expr: &Expr) -> bool {
+    match expr {
+        Expr::Column(_) | Expr::Literal(..) => true,
+        Expr::BinaryExpr(binary) => {
+            is_pushable_filter(&binary.left) && is_pushable_filter(&binary.right)
+        }
+        Expr::Not(expr)
+        | Expr::Negative(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::IsTrue(expr)
+        | Expr::IsFalse(expr)
+        | Expr::IsUnknown(expr)
+        | Expr::IsNotTrue(expr)
+        | Expr::IsNotFalse(expr)
+        | Expr::IsNotUnknown(expr) => is_pushable_filter(expr),
+        Expr::Cast(cast) => is_pushable_filter(&cast.expr),
+        Expr::TryCast(cast) => is_pushable_filter(&cast.expr),
+        Expr::Between(between) => {
+            is_pushable_filter(&between.expr)
+                && is_pushable_filter(&between.low)
+                && is_pushable_filter(&between.high)
+        }
+        Expr::InList(in_list) => {
+            is_pushable_filter(&in_list.expr) && in_list.list.iter().all(is_pushable_filter)
+        }
+        Expr::Like(like) | Expr::SimilarTo(like) => {
+            is_pushable_filter(&like.expr) && is_pushable_filter(&like.pattern)
+        }
+        Expr::Case(case) => {
+            case.expr.iter().all(|expr| is_pushable_filter(expr))
+                && case
+                    .when_then_expr
+                    .iter()
+                    .all(|(when, then)| is_pushable_filter(when) && is_pushable_filter(then))
+                && case.else_expr.iter().all(|expr| is_pushable_filter(expr))
+        }
+        Expr::ScalarFunction(function) => {
+            function.func.signature().volatility != Volatility::Volatile
+                && function.args.iter().all(is_pushable_filter)
+        }
+        _ => false,
+    }
+}
+
 pub trait SessionContextExt {
     /// Creates a DataFrame for reading a Lance dataset
     fn read_lance(
@@ -225,10 +581,11 @@ impl SessionContextExt for SessionContext {
         with_row_id: bool,
         with_row_addr: bool,
     ) -> datafusion::common::Result<DataFrame> {
-        self.read_table(Arc::new(LanceTableProvider::new(
+        self.read_table(Arc::new(LanceTableProvider::from_dataset(
             dataset,
             with_row_id,
             with_row_addr,
+            LanceScanOptions::default(),
         )))
     }
 
@@ -238,11 +595,15 @@ impl SessionContextExt for SessionContext {
         with_row_id: bool,
         with_row_addr: bool,
     ) -> datafusion::common::Result<DataFrame> {
-        self.read_table(Arc::new(LanceTableProvider::new_with_ordering(
+        self.read_table(Arc::new(LanceTableProvider::from_dataset(
             dataset,
             with_row_id,
             with_row_addr,
-            false,
+            LanceScanOptions {
+                scan_in_order: Some(false),
+                allow_repartition: Some(true),
+                ..Default::default()
+            },
         )))
     }
 
@@ -292,7 +653,14 @@ mod tests {
 
         ctx.register_table(
             "foo",
-            Arc::new(LanceTableProvider::new(Arc::new(data), true, true)),
+            Arc::new(
+                LanceTableProvider::builder(Arc::new(data))
+                    .with_row_id(true)
+                    .with_row_address(true)
+                    .build()
+                    .await
+                    .unwrap(),
+            ),
         )
         .unwrap();
 
@@ -309,4 +677,391 @@ mod tests {
         // SUM(0..100) - SUM(0..50) = 3675
         assert_eq!(results.column(0).as_primitive::<Int64Type>().value(0), 3675);
     }
+
+    #[tokio::test]
+    pub async fn test_filter_pushdown() {
+        use arrow::array::{Int32Array, RecordBatch, RecordBatchIterator, StringArray};
+        use arrow_schema::{DataType, Field, Schema};
+        use datafusion::datasource::TableProvider;
+        use datafusion::logical_expr::{Expr, TableProviderFilterPushDown};
+
+        use crate::Dataset;
+
+        let test_uri = TempStrDir::default();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("x", DataType::Int32, false),
+            Field::new("s", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..100)),
+                Arc::new(StringArray::from_iter((0..100).map(|i| match i % 3 {
+                    0 => None,
+                    1 => Some(format!("apple{i}")),
+                    _ => Some(format!("banana{i}")),
+                }))),
+            ],
+        )
+        .unwrap();
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        let dataset = Dataset::write(reader, &test_uri, None).await.unwrap();
+        let provider = Arc::new(
+            LanceTableProvider::builder(Arc::new(dataset))
+                .build()
+                .await
+                .unwrap(),
+        );
+
+        let ctx = SessionContext::new();
+        ctx.register_table("foo", provider.clone()).unwrap();
+
+        let count = |filter: &'static str| {
+            let ctx = ctx.clone();
+            async move {
+                let sql = format!("SELECT x FROM foo WHERE {filter}");
+                let plan = ctx
+                    .sql(&format!("EXPLAIN {sql}"))
+                    .await
+                    .unwrap()
+                    .collect()
+                    .await
+                    .unwrap();
+                let plan = arrow::util::pretty::pretty_format_batches(&plan)
+                    .unwrap()
+                    .to_string();
+                assert!(!plan.contains("FilterExec"), "{filter}: {plan}");
+                ctx.sql(&sql)
+                    .await
+                    .unwrap()
+                    .collect()
+                    .await
+                    .unwrap()
+                    .iter()
+                    .map(|batch| batch.num_rows())
+                    .sum::<usize>()
+            }
+        };
+        assert_eq!(count("x IN (1, 2, 3, 200)").await, 3);
+        assert_eq!(count("x NOT IN (1, 2, 3)").await, 97);
+        assert_eq!(count("x BETWEEN 10 AND 19").await, 10);
+        assert_eq!(count("s IS NULL").await, 34);
+        assert_eq!(count("s IS NOT NULL AND x < 10").await, 6);
+        assert_eq!(count("starts_with(s, 'app')").await, 33);
+        assert_eq!(count("s LIKE 'ban%' OR (x >= 90 AND s IS NULL)").await, 37);
+        assert_eq!(count("NOT (x < 50 OR starts_with(s, 'app'))").await, 17);
+
+        let ctx = SessionContext::new();
+        let pushdown = |sql: &str| {
+            let expr = ctx
+                .parse_sql_expr(sql, &provider.schema().as_ref().clone().try_into().unwrap())
+                .unwrap();
+            provider
+                .supports_filters_pushdown(&[&expr])
+                .unwrap()
+                .remove(0)
+        };
+        assert_eq!(
+            pushdown("x IN (1, 2) AND s IS NOT NULL"),
+            TableProviderFilterPushDown::Exact
+        );
+        assert_eq!(
+            pushdown("x > 10 OR random() > 0.5"),
+            TableProviderFilterPushDown::Unsupported
+        );
+        assert_eq!(
+            provider
+                .supports_filters_pushdown(&[&Expr::Placeholder(
+                    datafusion::logical_expr::expr::Placeholder::new_with_field("$1".into(), None)
+                )])
+                .unwrap(),
+            vec![TableProviderFilterPushDown::Unsupported]
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_insert_into() {
+        use arrow::array::{Int32Array, RecordBatch, UInt64Array};
+        use arrow_schema::{DataType, Field, Schema};
+        use datafusion::datasource::MemTable;
+
+        use crate::Dataset;
+
+        let test_uri = TempStrDir::default();
+        let dataset = lance_datagen::gen_batch()
+            .col("x", array::step::<Int32Type>())
+            .into_dataset(&test_uri, FragmentCount::from(2), FragmentRowCount::from(5))
+            .await
+            .unwrap();
+        let version = dataset.version().version;
+
+        let ctx = SessionContext::new();
+        ctx.register_table(
+            "foo",
+            Arc::new(
+                LanceTableProvider::builder(Arc::new(dataset))
+                    .with_row_id(true)
+                    .build()
+                    .await
+                    .unwrap(),
+            ),
+        )
+        .unwrap();
+
+        // Each partition of the input is written, then committed together
+        let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int32, true)]));
+        let partitions = (0..4)
+            .map(|i| {
+                vec![
+                    RecordBatch::try_new(
+                        schema.clone(),
+                        vec![Arc::new(Int32Array::from_iter_values(i * 10..i * 10 + 10))],
+                    )
+                    .unwrap(),
+                ]
+            })
+            .collect();
+        ctx.register_table(
+            "source",
+            Arc::new(MemTable::try_new(schema, partitions).unwrap()),
+        )
+        .unwrap();
+
+        let count = |sql: &'static str| {
+            let ctx = ctx.clone();
+            async move {
+                let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+                batches[0]
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<UInt64Array>()
+                    .unwrap()
+                    .value(0)
+            }
+        };
+        assert_eq!(
+            count("INSERT INTO foo (x) SELECT x + 100 FROM source").await,
+            40
+        );
+
+        let dataset = Dataset::open(&test_uri).await.unwrap();
+        assert_eq!(dataset.version().version, version + 1);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 50);
+        assert_eq!(dataset.get_fragments().len(), 6);
+        assert_eq!(
+            dataset
+                .count_rows(Some("x >= 100 AND x < 140".to_string()))
+                .await
+                .unwrap(),
+            40
+        );
+
+        assert_eq!(count("INSERT INTO foo (x) VALUES (1), (2)").await, 2);
+        assert!(
+            ctx.sql("INSERT INTO foo (x, _rowid) VALUES (1, 1)")
+                .await
+                .unwrap()
+                .collect()
+                .await
+                .is_err()
+        );
+
+        assert_eq!(
+            count("INSERT OVERWRITE foo (x) SELECT x FROM source").await,
+            40
+        );
+        let dataset = Dataset::open(&test_uri).await.unwrap();
+        assert_eq!(dataset.version().version, version + 3);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 40);
+    }
+
+    #[tokio::test]
+    pub async fn test_builder() {
+        use datafusion::datasource::TableProvider;
+        use datafusion::execution::TaskContext;
+
+        use crate::datafusion::LanceScanOptions;
+        use crate::dataset::{InsertBuilder, WriteMode, WriteParams};
+
+        let test_uri = TempStrDir::default();
+        let data = lance_datagen::gen_batch()
+            .col("x", array::step::<Int32Type>())
+            .into_dataset(
+                &test_uri,
+                FragmentCount::from(2),
+                FragmentRowCount::from(50),
+            )
+            .await
+            .unwrap();
+        let version = data.version().version;
+        let batch = data.scan().try_into_batch().await.unwrap();
+        let data = InsertBuilder::new(Arc::new(data))
+            .with_params(&WriteParams {
+                mode: WriteMode::Append,
+                ..Default::default()
+            })
+            .execute(vec![batch])
+            .await
+            .unwrap();
+
+        // The row id can be joined on
+        let provider = LanceTableProvider::builder(Arc::new(data.clone()))
+            .with_row_id(true)
+            .with_row_address(true)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(provider.row_id_column(), Some(1));
+        assert_eq!(provider.row_address_column(), Some(2));
+        let ctx = SessionContext::new();
+        ctx.register_table("foo", Arc::new(provider)).unwrap();
+        let results = ctx
+            .sql(
+                "SELECT count(*) FROM foo a JOIN foo b ON a._rowid = b._rowid \
+                 WHERE a.x = b.x AND a._rowaddr = b._rowaddr",
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            results[0].column(0).as_primitive::<Int64Type>().value(0),
+            200
+        );
+
+        // Reading an older version
+        let provider = LanceTableProvider::builder(Arc::new(data.clone()))
+            .with_version(version)
+            .with_scan_options(LanceScanOptions {
+                batch_size: Some(7),
+                ..Default::default()
+            })
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(provider.dataset().version().version, version);
+        let ctx = SessionContext::new();
+        let plan = provider.scan(&ctx.state(), None, &[], None).await.unwrap();
+        let batches = datafusion::physical_plan::collect(plan, Arc::new(TaskContext::default()))
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 100);
+        assert!(batches.iter().all(|b| b.num_rows() <= 7));
+
+        // Without a projection, the exposed metadata columns are still scanned
+        let provider = LanceTableProvider::builder(Arc::new(data))
+            .with_row_id(true)
+            .build()
+            .await
+            .unwrap();
+        let plan = provider.scan(&ctx.state(), None, &[], None).await.unwrap();
+        assert_eq!(plan.schema().as_ref(), provider.schema().as_ref());
+    }
+
+    #[tokio::test]
+    pub async fn test_aggregate_statistics() {
+        let test_uri = TempStrDir::default();
+        let mut dataset = lance_datagen::gen_batch()
+            .col("x", array::step::<Int32Type>())
+            .into_dataset(
+                &test_uri,
+                FragmentCount::from(10),
+                FragmentRowCount::from(10),
+            )
+            .await
+            .unwrap();
+
+        let query = |dataset: crate::Dataset| async move {
+            let ctx = SessionContext::new();
+            let provider = LanceTableProvider::builder(Arc::new(dataset))
+                .build()
+                .await
+                .unwrap();
+            ctx.register_table("foo", Arc::new(provider)).unwrap();
+            let sql = "SELECT count(*), count(x), min(x), max(x) FROM foo";
+            let plan = ctx
+                .sql(&format!("EXPLAIN {sql}"))
+                .await
+                .unwrap()
+                .collect()
+                .await
+                .unwrap();
+            let plan = arrow::util::pretty::pretty_format_batches(&plan)
+                .unwrap()
+                .to_string();
+            let batch = ctx
+                .sql(sql)
+                .await
+                .unwrap()
+                .collect()
+                .await
+                .unwrap()
+                .remove(0);
+            let values = (
+                batch.column(0).as_primitive::<Int64Type>().value(0),
+                batch.column(1).as_primitive::<Int64Type>().value(0),
+                batch.column(2).as_primitive::<Int32Type>().value(0),
+                batch.column(3).as_primitive::<Int32Type>().value(0),
+            );
+            (plan.contains("PlaceholderRowExec"), values)
+        };
+
+        // Answered from the fragment zone maps without reading any data
+        assert_eq!(query(dataset.clone()).await, (true, (100, 100, 0, 99)));
+
+        // Deleted rows may have held the bounds, so the data is scanned
+        dataset.delete("x < 5 OR x = 99").await.unwrap();
+        assert_eq!(query(dataset).await, (false, (94, 94, 5, 98)));
+    }
+
+    #[tokio::test]
+    pub async fn test_statistics() {
+        use datafusion::common::stats::Precision;
+        use datafusion::datasource::TableProvider;
+
+        let test_uri = TempStrDir::default();
+        let mut dataset = lance_datagen::gen_batch()
+            .col("x", array::step::<Int32Type>())
+            .into_dataset(
+                &test_uri,
+                FragmentCount::from(4),
+                FragmentRowCount::from(10),
+            )
+            .await
+            .unwrap();
+        dataset.delete("x < 5").await.unwrap();
+        let dataset = Arc::new(dataset);
+
+        let provider = LanceTableProvider::builder(dataset.clone())
+            .build()
+            .await
+            .unwrap();
+        assert!(provider.statistics().is_none());
+
+        let provider = LanceTableProvider::builder(dataset)
+            .with_row_id(true)
+            .with_statistics(true)
+            .build()
+            .await
+            .unwrap();
+        let statistics = provider.statistics().unwrap();
+        assert_eq!(statistics.num_rows, Precision::Exact(35));
+        assert_eq!(statistics.column_statistics.len(), 2);
+        assert!(
+            statistics.column_statistics[0]
+                .byte_size
+                .get_value()
+                .unwrap()
+                > &0
+        );
+        assert_eq!(
+            statistics.column_statistics[1].null_count,
+            Precision::Exact(0)
+        );
+        assert_eq!(
+            statistics.column_statistics[1].byte_size,
+            Precision::Inexact(35 * 8)
+        );
+    }
 }
@@ -0,0 +1,424 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use arrow_array::{RecordBatch, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, ExecutionPlanProperties, PlanProperties,
+};
+use datafusion_physical_expr::{Distribution, EquivalenceProperties, Partitioning};
+use futures::StreamExt;
+use futures::future::try_join_all;
+
+use crate::Dataset;
+use crate::dataset::transaction::{Operation, Transaction};
+use crate::dataset::{CommitBuilder, InsertBuilder, WriteDestination, WriteParams};
+
+/// Writes the output of a plan into a Lance dataset.
+///
+/// This is the sink of `INSERT INTO` and `INSERT OVERWRITE` statements on a
+/// [`LanceTableProvider`](super::LanceTableProvider), and can be used on its
+/// own to compose Lance writes into larger plans, e.g. to create a dataset
+/// from a query or to refresh a materialized view.
+///
+/// The [`WriteParams::mode`] decides whether the input is appended to the
+/// destination, overwrites it, or creates a new dataset. Every input
+/// partition is written to new fragments concurrently. Once all partitions
+/// finish, the fragments are committed as a single transaction, so a failed
+/// write leaves the dataset unchanged. The output is a single row with the
+/// number of rows written, in a `count` column.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use datafusion::physical_plan::{ExecutionPlan, collect};
+/// # use datafusion::execution::TaskContext;
+/// # use lance::datafusion::LanceSinkExec;
+/// # use lance::dataset::{WriteMode, WriteParams};
+/// # async fn example(input: Arc<dyn ExecutionPlan>) -> datafusion::common::Result<()> {
+/// let sink = LanceSinkExec::new(
+///     input,
+///     "memory://table",
+///     WriteParams {
+///         mode: WriteMode::Overwrite,
+///         ..Default::default()
+///     },
+/// );
+/// collect(Arc::new(sink), Arc::new(TaskContext::default())).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct LanceSinkExec {
+    input: Arc<dyn ExecutionPlan>,
+    destination: SinkDestination,
+    params: WriteParams,
+    /// Input columns that are not stored, such as `_rowid`.
+    system_columns: Vec<usize>,
+    properties: Arc<PlanProperties>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+/// An owned [`WriteDestination`], so the plan does not borrow the uri.
+#[derive(Debug, Clone)]
+enum SinkDestination {
+    Dataset(Arc<Dataset>),
+    Uri(String),
+}
+
+impl SinkDestination {
+    fn as_write_destination(&self) -> WriteDestination<'_> {
+        match self {
+            Self::Dataset(dataset) => WriteDestination::Dataset(dataset.clone()),
+            Self::Uri(uri) => WriteDestination::Uri(uri),
+        }
+    }
+}
+
+impl LanceSinkExec {
+    pub fn new<'a>(
+        input: Arc<dyn ExecutionPlan>,
+        destination: impl Into<WriteDestination<'a>>,
+        params: WriteParams,
+    ) -> Self {
+        let destination = match destination.into() {
+            WriteDestination::Dataset(dataset) => SinkDestination::Dataset(dataset),
+            WriteDestination::Uri(uri) => SinkDestination::Uri(uri.to_string()),
+        };
+        Self::from_destination(input, destination, params, Vec::new())
+    }
+
+    fn from_destination(
+        input: Arc<dyn ExecutionPlan>,
+        destination: SinkDestination,
+        params: WriteParams,
+        system_columns: Vec<usize>,
+    ) -> Self {
+        let properties = Arc::new(PlanProperties::new(
+            EquivalenceProperties::new(count_schema()),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        ));
+        Self {
+            input,
+            destination,
+            params,
+            system_columns,
+            properties,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// Input columns to drop before writing, such as an exposed `_rowid`.
+    ///
+    /// The rows must not have values in these columns.
+    pub fn with_system_columns(mut self, system_columns: Vec<usize>) -> Self {
+        self.system_columns = system_columns;
+        self
+    }
+
+    /// The uri of the dataset that is written.
+    pub fn uri(&self) -> String {
+        self.destination.as_write_destination().uri()
+    }
+
+    /// The parameters of the write.
+    pub fn params(&self) -> &WriteParams {
+        &self.params
+    }
+
+    /// Remove the system columns from a batch, they must not have values.
+    fn strip_system_columns(system_columns: &[usize], batch: RecordBatch) -> DFResult<RecordBatch> {
+        if system_columns.is_empty() {
+            return Ok(batch);
+        }
+        let schema = batch.schema();
+        for idx in system_columns {
+            if batch.column(*idx).null_count() != batch.num_rows() {
+                return Err(DataFusionError::Plan(format!(
+                    "Cannot insert values into system column {}",
+                    schema.field(*idx).name()
+                )));
+            }
+        }
+        let columns = (0..batch.num_columns())
+            .filter(|idx| !system_columns.contains(idx))
+            .collect::<Vec<_>>();
+        Ok(batch.project(&columns)?)
+    }
+
+    /// Commit the transaction with the settings of the write.
+    async fn commit(
+        destination: WriteDestination<'_>,
+        params: &WriteParams,
+        transaction: Transaction,
+    ) -> crate::Result<Dataset> {
+        let mut builder = CommitBuilder::new(destination)
+            .use_stable_row_ids(params.enable_stable_row_ids)
+            .enable_v2_manifest_paths(params.enable_v2_manifest_paths)
+            .with_skip_auto_cleanup(params.skip_auto_cleanup);
+        if let Some(storage_format) = params.data_storage_version {
+            builder = builder.with_storage_format(storage_format);
+        }
+        if let Some(store_params) = params.store_params.as_ref() {
+            builder = builder.with_store_params(store_params.clone());
+        }
+        if let Some(session) = params.session.as_ref() {
+            builder = builder.with_session(session.clone());
+        }
+        if let Some(commit_handler) = params.commit_handler.as_ref() {
+            builder = builder.with_commit_handler(commit_handler.clone());
+        }
+        builder.execute(transaction).await
+    }
+
+    /// Combine the transactions written by each partition into one.
+    fn merge_transactions(transactions: Vec<Transaction>) -> DFResult<Option<Transaction>> {
+        let mut transactions = transactions.into_iter();
+        let Some(mut merged) = transactions.next() else {
+            return Ok(None);
+        };
+        for transaction in transactions {
+            match (&mut merged.operation, transaction.operation) {
+                (Operation::Append { fragments }, Operation::Append { fragments: more })
+                | (
+                    Operation::Overwrite { fragments, .. },
+                    Operation::Overwrite {
+                        fragments: more, ..
+                    },
+                ) => fragments.extend(more),
+                (merged, other) => {
+                    return Err(DataFusionError::Internal(format!(
+                        "Cannot combine {} and {} transactions of a write",
+                        merged, other
+                    )));
+                }
+            }
+        }
+        Ok(Some(merged))
+    }
+}
+
+fn count_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "count",
+        DataType::UInt64,
+        false,
+    )]))
+}
+
+impl DisplayAs for LanceSinkExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "LanceSink: uri={}, mode={:?}",
+                    self.uri(),
+                    self.params.mode
+                )
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "LanceSink[{}]", self.uri())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for LanceSinkExec {
+    fn name(&self) -> &str {
+        "LanceSinkExec"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        count_schema()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Internal(
+                "LanceSinkExec requires exactly one child".to_string(),
+            ));
+        }
+        Ok(Arc::new(Self::from_destination(
+            children[0].clone(),
+            self.destination.clone(),
+            self.params.clone(),
+            self.system_columns.clone(),
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.properties
+    }
+
+    fn supports_limit_pushdown(&self) -> bool {
+        false
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::UnspecifiedDistribution]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DFResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "LanceSinkExec has a single output partition, got {}",
+                partition
+            )));
+        }
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let num_rows = Arc::new(AtomicU64::new(0));
+        let inputs = (0..self.input.output_partitioning().partition_count())
+            .map(|partition| {
+                let system_columns = self.system_columns.clone();
+                let num_rows = num_rows.clone();
+                let stream = self.input.execute(partition, context.clone())?;
+                let schema = Arc::new(
+                    stream.schema().project(
+                        &(0..stream.schema().fields().len())
+                            .filter(|idx| !system_columns.contains(idx))
+                            .collect::<Vec<_>>(),
+                    )?,
+                );
+                let stream = stream.map(move |batch| {
+                    let batch = Self::strip_system_columns(&system_columns, batch?)?;
+                    num_rows.fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+                    Ok(batch)
+                });
+                Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+                    as SendableRecordBatchStream)
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+
+        let destination = self.destination.clone();
+        let params = self.params.clone();
+        let result = futures::stream::once(async move {
+            let transactions = try_join_all(inputs.into_iter().map(|input| {
+                let destination = destination.as_write_destination();
+                let params = &params;
+                async move {
+                    InsertBuilder::new(destination)
+                        .with_params(params)
+                        .execute_uncommitted_stream(input)
+                        .await
+                }
+            }))
+            .await?;
+            if let Some(transaction) = Self::merge_transactions(transactions)? {
+                Self::commit(destination.as_write_destination(), &params, transaction).await?;
+            }
+
+            let num_rows = num_rows.load(Ordering::Relaxed);
+            baseline_metrics.record_output(1);
+            Ok(RecordBatch::try_new(
+                count_schema(),
+                vec![Arc::new(UInt64Array::from(vec![num_rows]))],
+            )?)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            count_schema(),
+            result,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Int32Array, cast::AsArray, types::UInt64Type};
+    use datafusion::datasource::memory::MemorySourceConfig;
+    use datafusion::physical_plan::collect;
+    use lance_core::utils::tempfile::TempStrDir;
+
+    use super::*;
+    use crate::dataset::WriteMode;
+
+    #[tokio::test]
+    async fn test_sink_to_uri() {
+        let test_uri = TempStrDir::default();
+        let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int32, true)]));
+        let input = |partitions: i32| {
+            let partitions = (0..partitions)
+                .map(|i| {
+                    vec![
+                        RecordBatch::try_new(
+                            schema.clone(),
+                            vec![Arc::new(Int32Array::from_iter_values(i * 10..i * 10 + 10))],
+                        )
+                        .unwrap(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            MemorySourceConfig::try_new_exec(&partitions, schema.clone(), None).unwrap()
+                as Arc<dyn ExecutionPlan>
+        };
+        let write = |input: Arc<dyn ExecutionPlan>, mode: WriteMode| {
+            let sink = LanceSinkExec::new(
+                input,
+                test_uri.as_str(),
+                WriteParams {
+                    mode,
+                    ..Default::default()
+                },
+            );
+            async move {
+                let batches = collect(Arc::new(sink), Arc::new(TaskContext::default()))
+                    .await
+                    .unwrap();
+                batches[0].column(0).as_primitive::<UInt64Type>().value(0)
+            }
+        };
+
+        // Creates the dataset from all partitions in a single version
+        assert_eq!(write(input(3), WriteMode::Create).await, 30);
+        let dataset = Dataset::open(&test_uri).await.unwrap();
+        assert_eq!(dataset.version().version, 1);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 30);
+
+        // The dataset already exists
+        let sink = LanceSinkExec::new(input(1), test_uri.as_str(), WriteParams::default());
+        assert!(
+            collect(Arc::new(sink), Arc::new(TaskContext::default()))
+                .await
+                .is_err()
+        );
+
+        assert_eq!(write(input(2), WriteMode::Append).await, 20);
+        assert_eq!(write(input(1), WriteMode::Overwrite).await, 10);
+        let dataset = Dataset::open(&test_uri).await.unwrap();
+        assert_eq!(dataset.version().version, 3);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 10);
+    }
+}
@@ -32,6 +32,7 @@ use lance_file::datatypes::populate_schema_dictionary;
 use lance_file::reader::{FileReader, FileReaderOptions};
 use lance_file::version::LanceFileVersion;
 use lance_index::{IndexType, progress::IndexBuildProgress};
+use lance_io::object_store::encryption::KeyProvider;
 use lance_io::object_store::{
     ChainedWrappingObjectStore, LanceNamespaceStorageOptionsProvider, ObjectStore,
     ObjectStoreParams, StorageOptions, StorageOptionsAccessor, StorageOptionsProvider,
@@ -68,13 +69,18 @@ use std::pin::Pin;
 use std::sync::Arc;
 use tracing::{info, instrument};
 
-pub use archive::{VersionArchive, VersionArchiveConfig};
+pub use archive::{
+    VersionArchive, VersionArchiveConfig, VersionArchiveRollup, VersionHistoryEntry,
+    VersionHistoryFilter,
+};
 pub mod archive;
 pub(crate) mod blob;
 pub(crate) mod branch_location;
 pub mod builder;
 pub mod cleanup;
 pub mod delta;
+pub mod embedding;
+pub mod encryption;
 pub mod files;
 pub mod fragment;
 mod hash_joiner;
@@ -83,6 +89,7 @@ pub mod mem_wal;
 mod metadata;
 pub mod optimize;
 pub mod progress;
+pub mod properties;
 pub mod refs;
 pub(crate) mod rowids;
 pub mod scanner;
@@ -107,6 +114,7 @@ use self::transaction::{Operation, Transaction, TransactionBuilder, UpdateMapEnt
 use self::write::{cleanup_data_fragments, write_fragments_internal};
 use crate::dataset::branch_location::BranchLocation;
 use crate::dataset::cleanup::{CleanupPolicy, CleanupPolicyBuilder};
+use crate::dataset::properties::TableProperties;
 use crate::dataset::refs::{BranchContents, BranchIdentifier, Branches, Tags};
 use crate::dataset::sql::SqlQueryBuilder;
 use crate::datatypes::Schema;
@@ -124,7 +132,9 @@ pub use lance_core::ROW_ID;
 use lance_core::box_error;
 use lance_index::scalar::lance_format::LanceIndexStore;
 use lance_namespace::models::{DeclareTableRequest, DescribeTableRequest};
-use lance_table::feature_flags::{apply_feature_flags, can_read_dataset};
+use lance_table::feature_flags::{
+    apply_feature_flags, check_read_compatibility, check_write_compatibility,
+};
 use lance_table::io::deletion::{DELETIONS_DIR, relative_deletion_file_path};
 pub use schema_evolution::{
     BatchInfo, BatchUDF, ColumnAlteration, NewColumnTransform, UDFCheckpointStore,
@@ -141,8 +151,9 @@ pub use write::update::{UpdateBuilder, UpdateJob};
 #[allow(deprecated)]
 pub use write::{
     AutoCleanupParams, CommitBuilder, DEFAULT_COMMIT_TIMEOUT, DeleteBuilder, DeleteResult,
-    ExternalBlobMode, InsertBuilder, UncommittedDelete, WriteDestination, WriteMode, WriteParams,
-    WriteProgressFn, WriteStats, write_fragments,
+    ExternalBlobMode, InsertBuilder, PartitionColumn, UncommittedDelete, WriteDestination,
+    WriteMode, WriteParams, WriteProgressFn, WriteStats, WriteSummary, WriteSummaryFn,
+    write_fragments,
 };
 
 pub(crate) const INDICES_DIR: &str = "_indices";
@@ -283,6 +294,10 @@ pub struct ReadParams {
     /// per-scan basis via [`Scanner::batch_size_bytes`](crate::dataset::scanner::Scanner::batch_size_bytes) or
     /// [`Scanner::with_file_reader_options`](crate::dataset::scanner::Scanner::with_file_reader_options).
     pub file_reader_options: Option<FileReaderOptions>,
+
+    /// Decrypts the data keys of the encrypted columns of the dataset, see
+    /// [`encryption`].  Without it these columns cannot be read.
+    pub key_provider: Option<Arc<dyn KeyProvider>>,
 }
 
 impl ReadParams {
@@ -335,6 +350,12 @@ impl ReadParams {
         self.file_reader_options = Some(options);
         self
     }
+
+    /// Set the key provider of the encrypted columns.
+    pub fn key_provider(&mut self, key_provider: Arc<dyn KeyProvider>) -> &mut Self {
+        self.key_provider = Some(key_provider);
+        self
+    }
 }
 
 impl Default for ReadParams {
@@ -346,6 +367,7 @@ impl Default for ReadParams {
             store_options: None,
             commit_handler: None,
             file_reader_options: None,
+            key_provider: None,
         }
     }
 }
@@ -679,14 +701,7 @@ impl Dataset {
             read_struct(object_reader.as_ref(), offset).await
         }?;
 
-        if !can_read_dataset(manifest.reader_feature_flags) {
-            let message = format!(
-                "This dataset cannot be read by this version of Lance. \
-                 Please upgrade Lance to read this dataset.\n Flags: {}",
-                manifest.reader_feature_flags
-            );
-            return Err(Error::not_supported_source(message.into()));
-        }
+        check_read_compatibility(&manifest)?;
 
         // If indices were also in the last block, we can take the opportunity to
         // decode them now and cache them.
@@ -2472,6 +2487,28 @@ impl Dataset {
             .collect())
     }
 
+    /// Resolve stable row ids to their current row addresses in bulk.
+    ///
+    /// Returns one entry per input id, in the same order, with `None` for ids
+    /// whose rows have been deleted. Row ids survive compaction and updates,
+    /// so external indexes can store them and resolve them here before reading.
+    ///
+    /// Returns an error if the dataset was not created with stable row ids.
+    pub async fn resolve_row_ids(&self, row_ids: &[u64]) -> Result<Vec<Option<u64>>> {
+        rowids::resolve_row_ids(self, row_ids).await
+    }
+
+    /// Resolve row addresses to their stable row ids in bulk.
+    ///
+    /// This is the inverse of [`Self::resolve_row_ids`]. Returns one entry per
+    /// input address, in the same order, with `None` for addresses of deleted
+    /// rows or fragments not present in this version.
+    ///
+    /// Returns an error if the dataset was not created with stable row ids.
+    pub async fn resolve_row_addresses(&self, row_addrs: &[u64]) -> Result<Vec<Option<u64>>> {
+        rowids::resolve_row_addresses(self, row_addrs).await
+    }
+
     pub(crate) async fn filter_deleted_ids(&self, ids: &[u64]) -> Result<Vec<u64>> {
         let addresses = if let Some(row_id_index) = get_row_id_index(self).await? {
             let addresses = ids
@@ -3051,6 +3088,30 @@ impl Dataset {
         schema_evolution::alter_columns(self, alterations).await
     }
 
+    /// Add a virtual embedding column computed from `source` by an embedding function.
+    ///
+    /// The function must be registered on the dataset's session with
+    /// [`Session::register_embedding_function`]. The function name, source column and
+    /// function configuration are recorded in the field metadata. No data is written:
+    /// scans compute the embeddings on the fly until the column is materialized with
+    /// [`Self::materialize_embedding_column`].
+    pub async fn add_embedding_column(
+        &mut self,
+        column: &str,
+        source: &str,
+        function: &str,
+    ) -> Result<()> {
+        embedding::add_embedding_column(self, column, source, function).await
+    }
+
+    /// Backfill a virtual embedding column, writing the computed embeddings to new
+    /// data files.
+    ///
+    /// This is required before building a vector index on the column.
+    pub async fn materialize_embedding_column(&mut self, column: &str) -> Result<()> {
+        embedding::materialize_embedding_column(self, column).await
+    }
+
     /// Remove columns from the dataset.
     ///
     /// This is a metadata-only operation and does not remove the data from the
@@ -3237,6 +3298,25 @@ impl Dataset {
         &self.manifest.config
     }
 
+    /// Check that this version of Lance can read and write the dataset.
+    ///
+    /// Opening a dataset already fails if it cannot be read, so this can be
+    /// called before a write to find out whether it will be rejected.  The
+    /// returned [`Error::UnsupportedVersion`] lists the unknown feature flags
+    /// and, if known, the library version required to use them.
+    pub fn check_compatibility(&self) -> Result<()> {
+        check_read_compatibility(&self.manifest)?;
+        check_write_compatibility(&self.manifest)
+    }
+
+    /// Get the typed settings stored in the dataset config.
+    ///
+    /// Fails if a known key holds an invalid value, which can only be the case
+    /// for configs written by older versions of Lance.
+    pub fn table_properties(&self) -> Result<TableProperties> {
+        TableProperties::try_from_config(&self.manifest.config)
+    }
+
     /// Delete keys from the config.
     #[deprecated(
         note = "Use the new update_config(values, replace) method - pass None values to delete keys"
@@ -3284,6 +3364,9 @@ impl Dataset {
     ///
     /// Pass `None` for a value to remove that key.
     ///
+    /// The values of keys read by Lance are validated, see [`TableProperties`].
+    /// Unknown `lance.*` keys are accepted with a warning.
+    ///
     /// Use `.replace()` to replace the entire config map instead of merging.
     ///
     /// Returns the updated config map after the operation.
@@ -3360,6 +3443,21 @@ impl Dataset {
         Ok(())
     }
 
+    /// The well-known metadata of a field, e.g. its description or semantic type.
+    ///
+    /// See [`WellKnownMetadata`](lance_core::datatypes::WellKnownMetadata) for the
+    /// keys. Returns an error if the field does not exist or an entry is malformed.
+    pub fn well_known_field_metadata(
+        &self,
+        path: &str,
+    ) -> Result<lance_core::datatypes::WellKnownMetadata> {
+        let schema = self.schema();
+        schema
+            .field(path)
+            .ok_or_else(|| Error::field_not_found(path, schema.field_paths()))?
+            .well_known_metadata()
+    }
+
     /// Update field metadata
     ///
     /// ```
@@ -3489,7 +3587,7 @@ pub(crate) async fn write_manifest_file(
 
     manifest.update_max_fragment_id();
 
-    commit_handler
+    let location = commit_handler
         .commit(
             manifest,
             indices,
@@ -3499,7 +3597,12 @@ pub(crate) async fn write_manifest_file(
             naming_scheme,
             transaction.take().map(|tx| tx.into()),
         )
-        .await
+        .await?;
+    // The fragments of the base are only needed to write a delta manifest.
+    if let Some(delta_base) = &mut manifest.delta_base {
+        delta_base.fragments = None;
+    }
+    Ok(location)
 }
 
 impl Projectable for Dataset {
@@ -6,12 +6,18 @@
 //! This module provides version archive functionality for preserving version metadata
 //! when manifests are cleaned up.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-use arrow_array::{Array, BooleanArray, Int64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{
+    Array, BooleanArray, Int64Array, ListArray, RecordBatch, StringArray, UInt64Array,
+};
 use arrow_schema::{DataType, Field, Schema as ArrowSchema};
-use futures::stream::StreamExt;
+use chrono::{DateTime, Utc};
+use futures::future;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
 use lance_core::datatypes::Schema as LanceSchema;
 use lance_core::{Error, Result};
 use lance_file::previous::reader::FileReader as PreviousFileReader;
@@ -19,20 +25,46 @@ use lance_file::previous::writer::{
     FileWriter as PreviousFileWriter, FileWriterOptions as PreviousFileWriterOptions,
 };
 use lance_io::object_store::ObjectStore;
-use lance_table::format::{ManifestSummary, SelfDescribingFileReader};
+use lance_table::format::{IndexMetadata, Manifest, ManifestSummary, SelfDescribingFileReader};
+use lance_table::io::deletion::deletion_file_path;
 use lance_table::io::manifest::ManifestDescribing;
 use object_store::path::Path;
+use object_store::{Error as ObjectStoreError, ObjectStoreExt, PutMode, PutOptions, UpdateVersion};
+use roaring::RoaringBitmap;
+use serde::{Deserialize, Serialize};
+
+use super::transaction::{Operation, Transaction};
+use super::{Dataset, ManifestWriteConfig, write_manifest_file};
+use crate::index::{DatasetIndexExt, index_type_name};
+use crate::io::commit::read_transaction_file;
 
 pub const ARCHIVE_DIR: &str = "_archive";
 const VERSION_ARCHIVE_FILE_SUFFIX: &str = ".lance";
+/// Rollup files are not parsed as archive files, as their name before
+/// [`VERSION_ARCHIVE_FILE_SUFFIX`] is not a version number
+const ROLLUP_FILE_SUFFIX: &str = ".rollup.lance";
+/// The transaction property of a rolled-up entry holding the first version it
+/// stands for
+pub const ROLLUP_FIRST_VERSION_KEY: &str = "lance:rollup:first_version";
+/// The transaction property of a rolled-up entry holding the number of
+/// versions it stands for
+pub const ROLLUP_VERSION_COUNT_KEY: &str = "lance:rollup:version_count";
 const METADATA_KEY_DATASET_CREATED: &str = "lance:archive:dataset_created";
 const METADATA_KEY_CREATED_AT: &str = "lance:archive:created_at";
+/// The number of times a flush merges a concurrently written archive and
+/// retries before giving up
+const MAX_FLUSH_ATTEMPTS: usize = 20;
 
 /// Generate archive filename for a given version
 fn archive_filename(version: u64) -> String {
     format!("{:020}{}", version, VERSION_ARCHIVE_FILE_SUFFIX)
 }
 
+/// Generate rollup filename for a given version
+fn rollup_filename(version: u64) -> String {
+    format!("{:020}{}", version, ROLLUP_FILE_SUFFIX)
+}
+
 /// Private helper to get the Arrow schema for VersionArchiveEntry
 fn version_archive_entry_schema() -> Arc<ArrowSchema> {
     Arc::new(ArrowSchema::new(vec![
@@ -50,6 +82,12 @@ fn version_archive_entry_schema() -> Arc<ArrowSchema> {
         Field::new("read_version", DataType::UInt64, true),
         Field::new("operation_type", DataType::Utf8, true),
         Field::new("transaction_properties", DataType::Utf8, true),
+        Field::new("indices", DataType::Utf8, true),
+        Field::new(
+            "tags",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
     ]))
 }
 
@@ -99,6 +137,8 @@ fn version_archive_entries_to_record_batch(entries: &[VersionArchiveEntry]) -> R
     let mut read_version = Vec::with_capacity(capacity);
     let mut operation_type = Vec::with_capacity(capacity);
     let mut transaction_properties = Vec::with_capacity(capacity);
+    let mut indices = Vec::with_capacity(capacity);
+    let mut tags = ListBuilder::new(StringBuilder::new());
 
     // Single pass through entries - more cache friendly
     for s in entries {
@@ -115,6 +155,14 @@ fn version_archive_entries_to_record_batch(entries: &[VersionArchiveEntry]) -> R
         transaction_uuid.push(s.transaction_uuid.as_deref());
         read_version.push(s.read_version);
         operation_type.push(s.operation_type.as_deref());
+        tags.append_value(s.tags.iter().map(Some));
+        if s.indices.is_empty() {
+            indices.push(None);
+        } else {
+            indices.push(Some(serde_json::to_string(&s.indices).map_err(|e| {
+                Error::invalid_input(format!("Failed to serialize archived indices: {}", e))
+            })?));
+        }
 
         // Handle transaction_properties with size limit
         if s.transaction_properties.is_empty() {
@@ -176,6 +224,8 @@ fn version_archive_entries_to_record_batch(entries: &[VersionArchiveEntry]) -> R
             Arc::new(UInt64Array::from(read_version)),
             Arc::new(StringArray::from(operation_type)),
             Arc::new(StringArray::from(transaction_properties)),
+            Arc::new(StringArray::from(indices)),
+            Arc::new(tags.finish()),
         ],
     )
     .map_err(|e| Error::invalid_input(format!("Failed to create RecordBatch: {}", e)))
@@ -237,8 +287,34 @@ fn record_batch_to_version_archive_entries(
     let operation_type_col = get_column!(batch, "operation_type", StringArray, "String");
     let transaction_properties_col =
         get_column!(batch, "transaction_properties", StringArray, "String");
+    // Archives written before indices and tags were recorded lack these columns
+    let indices_col = batch
+        .column_by_name("indices")
+        .and_then(|col| col.as_any().downcast_ref::<StringArray>());
+    let tags_col = batch
+        .column_by_name("tags")
+        .and_then(|col| col.as_any().downcast_ref::<ListArray>());
 
     for i in 0..batch.num_rows() {
+        let indices = match indices_col.filter(|col| col.is_valid(i)) {
+            Some(col) => serde_json::from_str(col.value(i)).unwrap_or_else(|e| {
+                tracing::warn!("Failed to parse archived indices: {}", e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        let tags = match tags_col.filter(|col| col.is_valid(i)) {
+            Some(col) => {
+                let values = col.value(i);
+                let values = values
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .ok_or_else(|| Error::invalid_input("tags column is not a list of String"))?;
+                values.iter().flatten().map(str::to_string).collect()
+            }
+            None => Vec::new(),
+        };
+
         let transaction_properties: HashMap<String, String> = if transaction_properties_col
             .is_valid(i)
         {
@@ -284,6 +360,8 @@ fn record_batch_to_version_archive_entries(
                 .is_valid(i)
                 .then(|| operation_type_col.value(i).to_string()),
             transaction_properties,
+            indices,
+            tags,
         });
     }
 
@@ -301,6 +379,9 @@ pub struct VersionArchiveConfig {
 
     /// Maximum number of archive files to retain
     pub max_archive_files: usize,
+
+    /// How entries dropped from the archive are kept, `None` to delete them
+    pub rollup: Option<VersionArchiveRollup>,
 }
 
 impl Default for VersionArchiveConfig {
@@ -309,10 +390,95 @@ impl Default for VersionArchiveConfig {
             enabled: true,
             max_entries: 10000,
             max_archive_files: 2,
+            rollup: None,
         }
     }
 }
 
+/// Rollup policy for the entries dropped from the archive
+///
+/// Entries truncated by `max_entries` and the entries of archive files removed
+/// by `max_archive_files` are merged into a rollup file instead of being
+/// deleted.  Entries older than `after` are merged into one entry per
+/// `interval`, newer entries are kept as they are until they age.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionArchiveRollup {
+    /// Age after which entries are merged
+    pub after: Duration,
+    /// Length of the intervals merged into one entry
+    pub interval: Duration,
+}
+
+impl Default for VersionArchiveRollup {
+    fn default() -> Self {
+        Self {
+            after: Duration::from_secs(30 * 24 * 60 * 60),
+            interval: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+impl VersionArchiveRollup {
+    /// Merge the entries created before `cutoff_millis` into one entry per
+    /// interval.
+    ///
+    /// The entry of an interval is the entry of its last version, so its
+    /// summary is the state of the dataset at the end of the interval.  The
+    /// versions it stands for are recorded in its transaction properties, see
+    /// [`VersionArchiveEntry::rolled_up_versions`].  Entries already merged by
+    /// an earlier rollup are merged again without counting their versions twice.
+    fn roll_up(
+        &self,
+        entries: impl IntoIterator<Item = VersionArchiveEntry>,
+        cutoff_millis: i64,
+    ) -> Vec<VersionArchiveEntry> {
+        let mut by_version: BTreeMap<u64, VersionArchiveEntry> = BTreeMap::new();
+        for entry in entries {
+            match by_version.get(&entry.version) {
+                Some(existing) if existing.rolled_up_versions() >= entry.rolled_up_versions() => {}
+                _ => {
+                    by_version.insert(entry.version, entry);
+                }
+            }
+        }
+        let merged_ranges = by_version
+            .values()
+            .filter(|entry| entry.rolled_up_versions() > 1)
+            .map(|entry| entry.first_version()..entry.version)
+            .collect::<Vec<_>>();
+        by_version.retain(|version, _| !merged_ranges.iter().any(|range| range.contains(version)));
+
+        let interval_millis = (self.interval.as_millis() as i64).max(1);
+        let mut rolled_up: Vec<VersionArchiveEntry> = Vec::with_capacity(by_version.len());
+        for mut entry in by_version.into_values() {
+            let same_interval = |last: &mut VersionArchiveEntry| {
+                entry.timestamp_millis < cutoff_millis
+                    && last.timestamp_millis < cutoff_millis
+                    && last.timestamp_millis.div_euclid(interval_millis)
+                        == entry.timestamp_millis.div_euclid(interval_millis)
+            };
+            if let Some(last) = rolled_up.pop_if(same_interval) {
+                entry.transaction_properties.insert(
+                    ROLLUP_FIRST_VERSION_KEY.to_string(),
+                    last.first_version().to_string(),
+                );
+                entry.transaction_properties.insert(
+                    ROLLUP_VERSION_COUNT_KEY.to_string(),
+                    (last.rolled_up_versions() + entry.rolled_up_versions()).to_string(),
+                );
+                entry.is_tagged |= last.is_tagged;
+                for tag in last.tags {
+                    if !entry.tags.contains(&tag) {
+                        entry.tags.push(tag);
+                    }
+                }
+            }
+            rolled_up.push(entry);
+        }
+        rolled_up
+    }
+}
+
 impl VersionArchiveConfig {
     /// Read configuration from manifest config
     ///
@@ -331,6 +497,18 @@ impl VersionArchiveConfig {
             .unwrap_or(2)
             .max(1);
 
+        let rollup = config
+            .get("lance.version_archive.rollup_after")
+            .and_then(|v| humantime::parse_duration(v).ok())
+            .map(|after| VersionArchiveRollup {
+                after,
+                interval: config
+                    .get("lance.version_archive.rollup_interval")
+                    .and_then(|v| humantime::parse_duration(v).ok())
+                    .filter(|interval| !interval.is_zero())
+                    .unwrap_or(VersionArchiveRollup::default().interval),
+            });
+
         Self {
             enabled: config
                 .get("lance.version_archive.enabled")
@@ -338,6 +516,7 @@ impl VersionArchiveConfig {
                 .unwrap_or(true),
             max_entries,
             max_archive_files,
+            rollup,
         }
     }
 }
@@ -365,6 +544,127 @@ pub struct VersionArchiveEntry {
     pub operation_type: Option<String>,
     /// Additional properties from the transaction
     pub transaction_properties: HashMap<String, String>,
+    /// The indices present at this version
+    pub indices: Vec<ArchivedIndex>,
+    /// The names of the tags referring to this version, as of when it was
+    /// archived
+    pub tags: Vec<String>,
+}
+
+/// An index present at an archived version, see [`VersionArchiveEntry::indices`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedIndex {
+    /// The name of the index
+    pub name: String,
+    /// The type of the index, e.g. `BTree` or `IVF_PQ`
+    pub index_type: String,
+    /// The paths of the indexed columns
+    pub columns: Vec<String>,
+    /// The number of fragments of the version the index covers
+    pub indexed_fragments: u64,
+    /// The number of rows of the version the index covers
+    pub indexed_rows: u64,
+}
+
+impl ArchivedIndex {
+    /// The indices of `manifest`, one per index name, merging the segments of
+    /// each index.
+    pub fn from_metadata(manifest: &Manifest, indices: &[IndexMetadata]) -> Vec<Self> {
+        let mut by_name: BTreeMap<&str, (&IndexMetadata, RoaringBitmap)> = BTreeMap::new();
+        for index in indices {
+            let (_, bitmap) = by_name
+                .entry(index.name.as_str())
+                .or_insert_with(|| (index, RoaringBitmap::new()));
+            if let Some(fragment_bitmap) = &index.fragment_bitmap {
+                *bitmap |= fragment_bitmap;
+            }
+        }
+        by_name
+            .into_values()
+            .map(|(index, bitmap)| {
+                let covered = manifest
+                    .fragments
+                    .iter()
+                    .filter(|fragment| bitmap.contains(fragment.id as u32))
+                    .collect::<Vec<_>>();
+                Self {
+                    name: index.name.clone(),
+                    index_type: index_type_name(index),
+                    columns: index
+                        .fields
+                        .iter()
+                        .map(|field_id| {
+                            manifest
+                                .schema
+                                .field_path(*field_id)
+                                .unwrap_or_else(|_| field_id.to_string())
+                        })
+                        .collect(),
+                    indexed_fragments: covered.len() as u64,
+                    indexed_rows: covered
+                        .iter()
+                        .filter_map(|fragment| fragment.num_rows())
+                        .sum::<usize>() as u64,
+                }
+            })
+            .collect()
+    }
+}
+
+impl VersionArchiveEntry {
+    /// The entry of the version of `manifest`, created by `transaction`, with
+    /// the `indices` of the version.
+    ///
+    /// The entry is not tagged, [`Self::is_tagged`] and [`Self::tags`] are set
+    /// by the caller.
+    pub fn from_manifest(
+        manifest: &Manifest,
+        transaction: Option<&Transaction>,
+        indices: &[IndexMetadata],
+    ) -> Self {
+        let (transaction_uuid, read_version, operation_type, transaction_properties) =
+            match transaction {
+                Some(tx) => (
+                    Some(tx.uuid.clone()),
+                    Some(tx.read_version),
+                    Some(tx.operation.to_string()),
+                    tx.transaction_properties
+                        .as_deref()
+                        .cloned()
+                        .unwrap_or_default(),
+                ),
+                None => (None, None, None, HashMap::new()),
+            };
+        Self {
+            version: manifest.version,
+            timestamp_millis: manifest.timestamp().timestamp_millis(),
+            manifest_summary: manifest.summary(),
+            is_tagged: false,
+            transaction_uuid,
+            read_version,
+            operation_type,
+            transaction_properties,
+            indices: ArchivedIndex::from_metadata(manifest, indices),
+            tags: Vec::new(),
+        }
+    }
+
+    /// The number of versions the entry stands for, more than one if it was
+    /// merged by a rollup, see [`VersionArchiveRollup`]
+    pub fn rolled_up_versions(&self) -> u64 {
+        self.transaction_properties
+            .get(ROLLUP_VERSION_COUNT_KEY)
+            .and_then(|count| count.parse().ok())
+            .unwrap_or(1)
+    }
+
+    /// The first version the entry stands for, see [`Self::rolled_up_versions`]
+    pub fn first_version(&self) -> u64 {
+        self.transaction_properties
+            .get(ROLLUP_FIRST_VERSION_KEY)
+            .and_then(|version| version.parse().ok())
+            .unwrap_or(self.version)
+    }
 }
 
 /// Version archive with persistence capability
@@ -399,6 +699,22 @@ impl VersionArchive {
     async fn list_archive_files(
         object_store: &ObjectStore,
         archive_dir: &Path,
+    ) -> Result<Vec<(u64, Path)>> {
+        Self::list_files(object_store, archive_dir, VERSION_ARCHIVE_FILE_SUFFIX).await
+    }
+
+    async fn list_rollup_files(
+        object_store: &ObjectStore,
+        archive_dir: &Path,
+    ) -> Result<Vec<(u64, Path)>> {
+        Self::list_files(object_store, archive_dir, ROLLUP_FILE_SUFFIX).await
+    }
+
+    /// List the files named `{version}{suffix}`, newest first
+    async fn list_files(
+        object_store: &ObjectStore,
+        archive_dir: &Path,
+        suffix: &str,
     ) -> Result<Vec<(u64, Path)>> {
         let mut archives = Vec::new();
         let mut stream = object_store.list(Some(archive_dir.clone()));
@@ -406,7 +722,7 @@ impl VersionArchive {
             let meta = meta?;
             if let Some(filename) = meta.location.filename()
                 && let Some(version) = filename
-                    .strip_suffix(VERSION_ARCHIVE_FILE_SUFFIX)
+                    .strip_suffix(suffix)
                     .and_then(|s| s.parse::<u64>().ok())
             {
                 archives.push((version, meta.location));
@@ -418,12 +734,118 @@ impl VersionArchive {
     }
 
     /// Private helper to write an archive in Lance format
-    async fn write_archive(&self) -> Result<()> {
+    ///
+    /// The archive is written with a conditional put, so concurrent flushes
+    /// never overwrite each other's entries.  The entries of the newest archive
+    /// file, which another flush may have written since this archive was
+    /// loaded, are merged into this archive before writing, and the write is
+    /// retried whenever the put conflicts.  Entries truncated by the merges are
+    /// added to `truncated`.
+    ///
+    /// Stores without conditional puts are written unconditionally.
+    async fn write_archive(&mut self, truncated: &mut Vec<VersionArchiveEntry>) -> Result<()> {
         let archive_dir = self.archive_dir();
-        let filename = archive_filename(self.latest_version_number);
-        let path = archive_dir.join(filename);
+        for _ in 0..MAX_FLUSH_ATTEMPTS {
+            let mut mode = PutMode::Create;
+            let archives = Self::list_archive_files(&self.object_store, &archive_dir).await?;
+            if let Some((version, newest_path)) = archives.first() {
+                let meta = match self.object_store.inner.head(newest_path).await {
+                    Ok(meta) => meta,
+                    // Removed by a concurrent cleanup of old archives
+                    Err(ObjectStoreError::NotFound { .. }) => continue,
+                    Err(e) => return Err(e.into()),
+                };
+                match Self::read_archive(
+                    &self.base,
+                    self.object_store.clone(),
+                    newest_path,
+                    self.config,
+                )
+                .await
+                {
+                    Ok(newest) => {
+                        self.merge_entries(newest.versions.clone());
+                        truncated.extend(self.finalize_entries());
+                        if self.versions == newest.versions {
+                            // The newest archive already has all the entries
+                            return Ok(());
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to load archive file {}: {}", newest_path, e);
+                    }
+                }
+                if *version == self.latest_version_number {
+                    mode = if meta.e_tag.is_none() && meta.version.is_none() {
+                        // Without an e-tag the file cannot be updated conditionally
+                        PutMode::Overwrite
+                    } else {
+                        PutMode::Update(UpdateVersion {
+                            e_tag: meta.e_tag,
+                            version: meta.version,
+                        })
+                    };
+                }
+            }
+
+            let path = archive_dir.join(archive_filename(self.latest_version_number));
+            let memory_store = ObjectStore::memory();
+            let memory_path = Path::from("archive");
+            self.write_entries(&memory_store, &memory_path, &self.versions)
+                .await?;
+            let data = memory_store.read_one_all(&memory_path).await?;
+            let options = PutOptions {
+                mode,
+                ..Default::default()
+            };
+            match self
+                .object_store
+                .inner
+                .put_opts(&path, data.clone().into(), options)
+                .await
+            {
+                Ok(_) => return Ok(()),
+                Err(
+                    ObjectStoreError::AlreadyExists { .. } | ObjectStoreError::Precondition { .. },
+                ) => {
+                    continue;
+                }
+                Err(ObjectStoreError::NotImplemented { .. }) => {
+                    self.object_store.put(&path, &data).await?;
+                    return Ok(());
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(Error::too_much_write_contention(format!(
+            "Failed to flush the version archive after {} attempts due to concurrent flushes",
+            MAX_FLUSH_ATTEMPTS
+        )))
+    }
 
-        let batch = version_archive_entries_to_record_batch(&self.versions)?;
+    /// Merge entries written by a concurrent flush, the entries of this
+    /// archive win for versions in both
+    fn merge_entries(&mut self, entries: Vec<VersionArchiveEntry>) {
+        let versions = self
+            .versions
+            .iter()
+            .map(|entry| entry.version)
+            .collect::<HashSet<_>>();
+        self.versions.extend(
+            entries
+                .into_iter()
+                .filter(|entry| !versions.contains(&entry.version)),
+        );
+    }
+
+    /// Private helper to write entries to a file in the archive format
+    async fn write_entries(
+        &self,
+        object_store: &ObjectStore,
+        path: &Path,
+        entries: &[VersionArchiveEntry],
+    ) -> Result<()> {
+        let batch = version_archive_entries_to_record_batch(entries)?;
         let mut lance_schema = version_archive_entry_lance_schema()?;
         lance_schema.metadata.insert(
             METADATA_KEY_DATASET_CREATED.to_string(),
@@ -436,8 +858,8 @@ impl VersionArchive {
 
         let options = PreviousFileWriterOptions::default();
         let mut writer = PreviousFileWriter::<ManifestDescribing>::try_new(
-            &self.object_store,
-            &path,
+            object_store,
+            path,
             lance_schema,
             &options,
         )
@@ -561,7 +983,9 @@ impl VersionArchive {
     /// Scan all retained archive files.
     ///
     /// Entries are deduplicated by version.  If the same version exists in
-    /// multiple archive files then the newest archive file wins.
+    /// multiple archive files then the newest archive file wins.  The entries
+    /// of the rollup file are returned along with the newest `max_entries`
+    /// entries of the archive files, see [`VersionArchiveRollup`].
     pub async fn scan(
         base: Path,
         object_store: Arc<ObjectStore>,
@@ -592,6 +1016,17 @@ impl VersionArchive {
             let remove_count = entries.len() - config.max_entries;
             entries.drain(0..remove_count);
         }
+
+        let rollup = Self::load_rollup(&base, object_store, config).await?;
+        let oldest_version = entries.first().map(|entry| entry.version);
+        let mut rolled_up = rollup
+            .into_iter()
+            .filter(|entry| oldest_version.is_none_or(|oldest| entry.version < oldest))
+            .collect::<Vec<_>>();
+        if !rolled_up.is_empty() {
+            rolled_up.append(&mut entries);
+            entries = rolled_up;
+        }
         Ok(entries)
     }
 
@@ -604,10 +1039,11 @@ impl VersionArchive {
         self.versions.extend(entries.iter().cloned());
     }
 
-    /// Finalize the archive before flushing
-    fn finalize_entries(&mut self) {
+    /// Finalize the archive before flushing, returning the entries truncated
+    /// by `max_entries`
+    fn finalize_entries(&mut self) -> Vec<VersionArchiveEntry> {
         if self.versions.is_empty() {
-            return;
+            return Vec::new();
         }
 
         self.versions.sort_by_key(|v| v.version);
@@ -619,43 +1055,146 @@ impl VersionArchive {
                 .unwrap_or(0);
         }
 
+        let mut truncated = Vec::new();
         if self.versions.len() > self.config.max_entries {
             let remove_count = self.versions.len() - self.config.max_entries;
-            self.versions.drain(0..remove_count);
+            truncated.extend(self.versions.drain(0..remove_count));
         }
 
         self.latest_version_number = self.versions.iter().map(|v| v.version).max().unwrap_or(0);
         self.created_at_millis = chrono::Utc::now().timestamp_millis();
+        truncated
     }
 
     /// Flush the archive to storage
+    ///
+    /// With a rollup policy, the truncated entries and the entries of the
+    /// removed archive files are merged into the rollup file.
     pub async fn flush(&mut self) -> Result<()> {
-        self.finalize_entries();
+        let mut truncated = self.finalize_entries();
 
         if self.versions.is_empty() {
             return Ok(());
         }
 
-        self.write_archive().await?;
-        self.cleanup_old_archives().await?;
+        self.write_archive(&mut truncated).await?;
+        self.cleanup_old_archives(truncated).await?;
 
         Ok(())
     }
 
-    async fn cleanup_old_archives(&self) -> Result<()> {
+    async fn cleanup_old_archives(&self, truncated: Vec<VersionArchiveEntry>) -> Result<()> {
         let archive_dir = self.archive_dir();
         let archives = Self::list_archive_files(&self.object_store, &archive_dir).await?;
 
-        if archives.len() > self.config.max_archive_files {
-            // archives is sorted in descending order (newest first)
-            // skip the newest max_archive_files, delete the rest (older ones)
-            for (_, path) in archives.iter().skip(self.config.max_archive_files) {
-                if let Err(e) = self.object_store.delete(path).await {
-                    tracing::warn!("Failed to delete old archive file {}: {}", path, e);
+        // archives is sorted in descending order (newest first)
+        // skip the newest max_archive_files, remove the rest (older ones)
+        let expired = archives
+            .get(self.config.max_archive_files..)
+            .unwrap_or_default();
+        if let Some(rollup) = self.config.rollup
+            && (!expired.is_empty() || !truncated.is_empty())
+        {
+            self.roll_up(rollup, expired, truncated).await?;
+        }
+        self.delete_archive_files(expired).await;
+
+        Ok(())
+    }
+
+    /// Merge all archive files but the newest one into the rollup file and
+    /// remove them
+    ///
+    /// Uses the configured rollup policy, or [`VersionArchiveRollup::default`]
+    /// if none is configured.  Entries in the rollup file that aged past the
+    /// policy cutoff since they were rolled up are merged as well.
+    pub async fn compact(&self) -> Result<()> {
+        let archive_dir = self.archive_dir();
+        let archives = Self::list_archive_files(&self.object_store, &archive_dir).await?;
+        let older = archives.get(1..).unwrap_or_default();
+        self.roll_up(self.config.rollup.unwrap_or_default(), older, Vec::new())
+            .await?;
+        self.delete_archive_files(older).await;
+        Ok(())
+    }
+
+    async fn delete_archive_files(&self, archives: &[(u64, Path)]) {
+        for (_, path) in archives {
+            if let Err(e) = self.object_store.delete(path).await {
+                tracing::warn!("Failed to delete old archive file {}: {}", path, e);
+            }
+        }
+    }
+
+    /// Read the newest readable rollup file
+    async fn load_rollup(
+        base: &Path,
+        object_store: Arc<ObjectStore>,
+        config: VersionArchiveConfig,
+    ) -> Result<Vec<VersionArchiveEntry>> {
+        let archive_dir = base.clone().join(ARCHIVE_DIR);
+        for (_, path) in Self::list_rollup_files(&object_store, &archive_dir).await? {
+            match Self::read_archive(base, object_store.clone(), &path, config).await {
+                Ok(rollup) => return Ok(rollup.versions),
+                Err(e) => {
+                    tracing::warn!("Failed to load rollup file {}: {}", path, e);
+                }
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Merge `entries` and the entries of `archives` into the rollup file
+    ///
+    /// Entries still in this archive are left out.  The new rollup file is
+    /// written before the older ones are removed, so a failed rollup never
+    /// loses entries.
+    async fn roll_up(
+        &self,
+        rollup: VersionArchiveRollup,
+        archives: &[(u64, Path)],
+        mut entries: Vec<VersionArchiveEntry>,
+    ) -> Result<()> {
+        for (_, path) in archives {
+            match Self::read_archive(&self.base, self.object_store.clone(), path, self.config).await
+            {
+                Ok(archive) => entries.extend(archive.versions),
+                Err(e) => {
+                    tracing::warn!("Failed to load archive file {} for rollup: {}", path, e);
                 }
             }
         }
+        let archived = self
+            .versions
+            .iter()
+            .map(|entry| entry.version)
+            .collect::<HashSet<_>>();
+        entries.retain(|entry| !archived.contains(&entry.version));
 
+        let archive_dir = self.archive_dir();
+        let rollup_files = Self::list_rollup_files(&self.object_store, &archive_dir).await?;
+        if entries.is_empty() && rollup_files.is_empty() {
+            return Ok(());
+        }
+        entries
+            .extend(Self::load_rollup(&self.base, self.object_store.clone(), self.config).await?);
+        let cutoff_millis = chrono::Utc::now().timestamp_millis()
+            - i64::try_from(rollup.after.as_millis()).unwrap_or(i64::MAX);
+        let rolled_up = rollup.roll_up(entries, cutoff_millis);
+        let Some(latest) = rolled_up.last() else {
+            return Ok(());
+        };
+
+        let path = archive_dir.join(rollup_filename(latest.version));
+        self.write_entries(&self.object_store, &path, &rolled_up)
+            .await?;
+        for (_, old_path) in rollup_files {
+            if old_path != path
+                && let Err(e) = self.object_store.delete(&old_path).await
+            {
+                tracing::warn!("Failed to delete old rollup file {}: {}", old_path, e);
+            }
+        }
         Ok(())
     }
 
@@ -738,6 +1277,363 @@ impl VersionArchive {
     }
 }
 
+/// Which versions [`Dataset::version_history`] returns, all of them by default.
+#[derive(Debug, Clone, Default)]
+pub struct VersionHistoryFilter {
+    /// Only versions created at or after this time
+    pub start_time: Option<DateTime<Utc>>,
+    /// Only versions created before this time
+    pub end_time: Option<DateTime<Utc>>,
+    /// Only versions created by this operation, e.g. `Append`, compared
+    /// case-insensitively
+    pub operation_type: Option<String>,
+    /// Only the versions with this tag, see [`VersionArchiveEntry::tags`]
+    pub tag: Option<String>,
+}
+
+impl VersionHistoryFilter {
+    fn matches(&self, entry: &VersionArchiveEntry) -> bool {
+        if let Some(start_time) = self.start_time
+            && entry.timestamp_millis < start_time.timestamp_millis()
+        {
+            return false;
+        }
+        if let Some(end_time) = self.end_time
+            && entry.timestamp_millis >= end_time.timestamp_millis()
+        {
+            return false;
+        }
+        if let Some(tag) = &self.tag
+            && !entry.tags.contains(tag)
+        {
+            return false;
+        }
+        match &self.operation_type {
+            Some(operation_type) => entry
+                .operation_type
+                .as_ref()
+                .is_some_and(|entry_type| entry_type.eq_ignore_ascii_case(operation_type)),
+            None => true,
+        }
+    }
+}
+
+/// A version returned by [`Dataset::version_history`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionHistoryEntry {
+    pub entry: VersionArchiveEntry,
+    /// Whether the manifest of the version still exists, only then the version
+    /// can be checked out
+    pub is_live: bool,
+}
+
+impl Dataset {
+    /// The versions of the dataset matching `filter`, oldest first.
+    ///
+    /// Unlike [`Self::versions`] this includes the versions whose manifests
+    /// were removed by cleanup, as recorded in the version archive.  Live
+    /// versions that are not archived yet are read from their manifests while
+    /// the stream is consumed.  Entries merged by a [`VersionArchiveRollup`]
+    /// stand for all the versions of their interval.
+    pub async fn version_history(
+        &self,
+        filter: VersionHistoryFilter,
+    ) -> Result<BoxStream<'static, Result<VersionHistoryEntry>>> {
+        let live_versions: BTreeSet<u64> = self
+            .commit_handler
+            .list_manifest_locations(&self.base, &self.object_store, false)
+            .map_ok(|location| location.version)
+            .try_collect()
+            .await?;
+
+        let config = VersionArchiveConfig::from_config(&self.manifest.config);
+        let mut archived: BTreeMap<u64, VersionArchiveEntry> = if config.enabled {
+            VersionArchive::scan(self.base.clone(), self.object_store.clone(), config)
+                .await?
+                .into_iter()
+                .map(|entry| (entry.version, entry))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        let mut version_tags: HashMap<u64, Vec<String>> = HashMap::new();
+        for (name, tag) in self.tags().list().await? {
+            if tag.branch == self.manifest.branch {
+                version_tags.entry(tag.version).or_default().push(name);
+            }
+        }
+        for names in version_tags.values_mut() {
+            names.sort();
+        }
+
+        // Live versions have the tags referring to them now, archived versions
+        // the tags that referred to them when they were archived
+        let has_tag = |version: &u64, tag: &str| {
+            if live_versions.contains(version) {
+                version_tags
+                    .get(version)
+                    .is_some_and(|names| names.iter().any(|name| name == tag))
+            } else {
+                archived
+                    .get(version)
+                    .is_some_and(|entry| entry.tags.iter().any(|name| name == tag))
+            }
+        };
+        let versions = live_versions
+            .iter()
+            .chain(archived.keys())
+            .copied()
+            .filter(|version| filter.tag.as_ref().is_none_or(|tag| has_tag(version, tag)))
+            .collect::<BTreeSet<_>>();
+        let dataset = self.clone();
+        let history = stream::iter(versions)
+            .then(move |version| {
+                let archived_entry = archived.remove(&version);
+                let is_live = live_versions.contains(&version);
+                let tags = version_tags.get(&version).cloned().unwrap_or_default();
+                let dataset = dataset.clone();
+                async move {
+                    let mut entry = match archived_entry {
+                        Some(entry) => entry,
+                        None => {
+                            let dataset = dataset.checkout_version(version).await?;
+                            let transaction = dataset.read_transaction().await?;
+                            let indices = dataset.load_indices().await?;
+                            VersionArchiveEntry::from_manifest(
+                                &dataset.manifest,
+                                transaction.as_ref(),
+                                &indices,
+                            )
+                        }
+                    };
+                    // Tags only refer to live versions and may have changed
+                    // since the version was archived
+                    if is_live {
+                        entry.is_tagged = !tags.is_empty();
+                        entry.tags = tags;
+                    }
+                    Ok(VersionHistoryEntry { entry, is_live })
+                }
+            })
+            .try_filter(move |history_entry| future::ready(filter.matches(&history_entry.entry)));
+        Ok(history.boxed())
+    }
+
+    /// All versions of the dataset as record batches, oldest first.
+    ///
+    /// The batches have the columns of the archive files followed by an
+    /// `is_live` column, see [`VersionHistoryEntry`].  There is always at least
+    /// one batch, so the schema is known even if no version is returned.
+    pub async fn versions_as_batches(&self) -> Result<Vec<RecordBatch>> {
+        let history: Vec<VersionHistoryEntry> = self
+            .version_history(VersionHistoryFilter::default())
+            .await?
+            .try_collect()
+            .await?;
+        if history.is_empty() {
+            return Ok(vec![version_history_to_record_batch(&[])?]);
+        }
+        history
+            .chunks(VERSION_HISTORY_BATCH_SIZE)
+            .map(version_history_to_record_batch)
+            .collect()
+    }
+
+    /// Restore a version whose manifest was removed, making it the latest
+    /// version.
+    ///
+    /// The manifest is rebuilt by replaying the transaction files recorded in
+    /// the version archive, starting from the newest version below `version`
+    /// whose manifest is still present.  This only succeeds if cleanup kept
+    /// those transaction files as well as the data and deletion files of the
+    /// version, e.g. when the manifests were deleted by accident.  The rebuilt
+    /// manifest is written back as `version`, which is then restored like
+    /// [`Self::restore`].  Versions whose manifests are present are restored
+    /// directly.
+    pub async fn restore_from_archive(&mut self, version: u64) -> Result<()> {
+        let live_versions: BTreeSet<u64> = self
+            .commit_handler
+            .list_manifest_locations(&self.base, &self.object_store, false)
+            .map_ok(|location| location.version)
+            .try_collect()
+            .await?;
+        if !live_versions.contains(&version) {
+            let config = VersionArchiveConfig::from_config(&self.manifest.config);
+            let archived =
+                VersionArchive::scan(self.base.clone(), self.object_store.clone(), config)
+                    .await?
+                    .into_iter()
+                    .map(|entry| (entry.version, entry))
+                    .collect::<HashMap<_, _>>();
+            let (mut manifest, indices) = self
+                .replay_archived_versions(version, &live_versions, &archived)
+                .await?;
+            self.check_files_exist(&manifest).await?;
+            let config = ManifestWriteConfig {
+                timestamp: Some(archived_timestamp(&archived[&version])),
+                use_stable_row_ids: manifest.uses_stable_row_ids(),
+                ..Default::default()
+            };
+            write_manifest_file(
+                &self.object_store,
+                self.commit_handler.as_ref(),
+                &self.base,
+                &mut manifest,
+                if indices.is_empty() {
+                    None
+                } else {
+                    Some(indices)
+                },
+                &config,
+                self.manifest_location.naming_scheme,
+                None,
+            )
+            .await?;
+        }
+
+        let mut restored = self.checkout_version(version).await?;
+        restored.restore().await?;
+        *self = restored;
+        Ok(())
+    }
+
+    /// Rebuild the manifest and indices of `version` from the transaction
+    /// files of the archived versions since the newest live version below it.
+    async fn replay_archived_versions(
+        &self,
+        version: u64,
+        live_versions: &BTreeSet<u64>,
+        archived: &HashMap<u64, VersionArchiveEntry>,
+    ) -> Result<(Manifest, Vec<IndexMetadata>)> {
+        let base_version = live_versions.range(..version).next_back().copied();
+        let mut current = match base_version {
+            Some(base_version) => Some(self.load_live_version(base_version).await?),
+            None => None,
+        };
+        // Restore transactions may refer to any of the replayed versions
+        let mut replayed: HashMap<u64, (Manifest, Vec<IndexMetadata>)> = HashMap::new();
+        for replayed_version in base_version.map_or(1, |v| v + 1)..=version {
+            let entry = archived.get(&replayed_version).ok_or_else(|| {
+                Error::not_found(format!(
+                    "version {} is not in the version archive",
+                    replayed_version
+                ))
+            })?;
+            let (Some(uuid), Some(read_version)) = (&entry.transaction_uuid, entry.read_version)
+            else {
+                return Err(Error::invalid_input(format!(
+                    "the version archive has no transaction for version {}",
+                    replayed_version
+                )));
+            };
+            let transaction_file = format!("{}-{}.txn", read_version, uuid);
+            let transaction =
+                read_transaction_file(&self.object_store, &self.base, &transaction_file).await?;
+            let (mut manifest, indices) = match &transaction.operation {
+                Operation::Restore {
+                    version: restored_version,
+                } => {
+                    let (mut manifest, indices) = match replayed.get(restored_version) {
+                        Some(restored) => restored.clone(),
+                        None if live_versions.contains(restored_version) => {
+                            self.load_live_version(*restored_version).await?
+                        }
+                        None => {
+                            return Err(Error::not_found(format!(
+                                "version {} restored by version {} can not be rebuilt",
+                                restored_version, replayed_version
+                            )));
+                        }
+                    };
+                    manifest.transaction_file = Some(transaction_file);
+                    if let Some((current, _)) = &current {
+                        manifest.max_fragment_id =
+                            manifest.max_fragment_id.max(current.max_fragment_id);
+                    }
+                    (manifest, indices)
+                }
+                _ => {
+                    let config = ManifestWriteConfig {
+                        timestamp: Some(archived_timestamp(entry)),
+                        use_stable_row_ids: self.manifest.uses_stable_row_ids(),
+                        ..Default::default()
+                    };
+                    let (current_manifest, current_indices) = match &current {
+                        Some((manifest, indices)) => (Some(manifest), indices.clone()),
+                        None => (None, Vec::new()),
+                    };
+                    transaction.build_manifest(
+                        current_manifest,
+                        current_indices,
+                        &transaction_file,
+                        &config,
+                    )?
+                }
+            };
+            manifest.version = replayed_version;
+            // The manifest the delta would be based on may be gone as well
+            manifest.delta_base = None;
+            replayed.insert(replayed_version, (manifest.clone(), indices.clone()));
+            current = Some((manifest, indices));
+        }
+        current.ok_or_else(|| Error::invalid_input(format!("invalid version {}", version)))
+    }
+
+    /// The manifest and indices of a version whose manifest is present.
+    async fn load_live_version(&self, version: u64) -> Result<(Manifest, Vec<IndexMetadata>)> {
+        let dataset = self.checkout_version(version).await?;
+        let indices = dataset.load_indices().await?.as_ref().clone();
+        Ok((dataset.manifest.as_ref().clone(), indices))
+    }
+
+    /// Fail with the path of the first data or deletion file of `manifest`
+    /// that does not exist anymore.
+    async fn check_files_exist(&self, manifest: &Manifest) -> Result<()> {
+        for fragment in manifest.fragments.iter() {
+            for data_file in &fragment.files {
+                let path = self.data_file_dir(data_file)?.join(data_file.path.as_str());
+                let object_store = self.object_store_for_data_file(data_file).await?;
+                if !object_store.exists(&path).await? {
+                    return Err(Error::not_found(path.to_string()));
+                }
+            }
+            if let Some(deletion_file) = &fragment.deletion_file {
+                let path = deletion_file_path(&self.base, fragment.id, deletion_file);
+                if !self.object_store.exists(&path).await? {
+                    return Err(Error::not_found(path.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The time an archived version was created.
+fn archived_timestamp(entry: &VersionArchiveEntry) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_millis(entry.timestamp_millis.max(0) as u64)
+}
+
+/// The number of versions per batch of [`Dataset::versions_as_batches`].
+const VERSION_HISTORY_BATCH_SIZE: usize = 1024;
+
+/// Convert version history entries to a record batch with the archive columns
+/// and an `is_live` column.
+fn version_history_to_record_batch(history: &[VersionHistoryEntry]) -> Result<RecordBatch> {
+    let entries = history
+        .iter()
+        .map(|history_entry| history_entry.entry.clone())
+        .collect::<Vec<_>>();
+    let batch = version_archive_entries_to_record_batch(&entries)?;
+    let is_live = BooleanArray::from_iter(history.iter().map(|entry| Some(entry.is_live)));
+    let mut fields = batch.schema().fields().to_vec();
+    fields.push(Arc::new(Field::new("is_live", DataType::Boolean, false)));
+    let mut columns = batch.columns().to_vec();
+    columns.push(Arc::new(is_live));
+    RecordBatch::try_new(Arc::new(ArrowSchema::new(fields)), columns)
+        .map_err(|e| Error::invalid_input(format!("Failed to create RecordBatch: {}", e)))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -766,6 +1662,8 @@ mod tests {
             read_version: None,
             operation_type: None,
             transaction_properties: HashMap::new(),
+            indices: Vec::new(),
+            tags: Vec::new(),
         }
     }
 
@@ -946,6 +1844,24 @@ mod tests {
         assert!(!archive_config.enabled);
         assert_eq!(archive_config.max_entries, 100);
         assert_eq!(archive_config.max_archive_files, 5);
+        assert_eq!(archive_config.rollup, None);
+
+        config.insert(
+            "lance.version_archive.rollup_after".to_string(),
+            "7d".to_string(),
+        );
+        config.insert(
+            "lance.version_archive.rollup_interval".to_string(),
+            "1h".to_string(),
+        );
+        let archive_config = VersionArchiveConfig::from_config(&config);
+        assert_eq!(
+            archive_config.rollup,
+            Some(VersionArchiveRollup {
+                after: Duration::from_secs(7 * 24 * 60 * 60),
+                interval: Duration::from_secs(60 * 60),
+            })
+        );
     }
 
     #[test]
@@ -1080,6 +1996,102 @@ mod tests {
         assert_eq!(entries[2].version, 3);
     }
 
+    #[tokio::test]
+    async fn test_rollup_keeps_dropped_entries() {
+        let mut fixture = ArchiveTestFixture::new_with_config(VersionArchiveConfig {
+            max_entries: 2,
+            max_archive_files: 1,
+            rollup: Some(VersionArchiveRollup {
+                after: Duration::from_secs(24 * 60 * 60),
+                interval: Duration::from_secs(2),
+            }),
+            ..Default::default()
+        })
+        .await;
+        let scan = |archive: &VersionArchive| {
+            VersionArchive::scan(
+                archive.base.clone(),
+                archive.object_store.clone(),
+                *archive.config(),
+            )
+        };
+
+        // Versions 1 to 3 are truncated, 2 and 3 fall in the same interval
+        fixture.archive.add_entries(
+            &(1..=5)
+                .map(create_test_version_archive_entry)
+                .collect::<Vec<_>>(),
+        );
+        fixture.archive.flush().await.unwrap();
+        let entries = scan(&fixture.archive).await.unwrap();
+        let versions = entries.iter().map(|e| e.version).collect::<Vec<_>>();
+        assert_eq!(versions, vec![1, 3, 4, 5]);
+        assert_eq!(entries[1].first_version(), 2);
+        assert_eq!(entries[1].rolled_up_versions(), 2);
+        assert_eq!(entries[1].manifest_summary.total_rows, 300);
+
+        // Versions 4 and 5 are truncated and their archive file is removed,
+        // they are merged without counting them twice
+        fixture.archive.add_entries(&[
+            create_test_version_archive_entry(6),
+            create_test_version_archive_entry(7),
+        ]);
+        fixture.archive.flush().await.unwrap();
+        let entries = scan(&fixture.archive).await.unwrap();
+        let versions = entries.iter().map(|e| e.version).collect::<Vec<_>>();
+        assert_eq!(versions, vec![1, 3, 5, 6, 7]);
+        assert_eq!(entries[2].first_version(), 4);
+        assert_eq!(
+            entries.iter().map(|e| e.rolled_up_versions()).sum::<u64>(),
+            7
+        );
+
+        let archive_dir = fixture.archive.archive_dir();
+        let rollup_files =
+            VersionArchive::list_rollup_files(&fixture.archive.object_store, &archive_dir)
+                .await
+                .unwrap();
+        assert_eq!(rollup_files.len(), 1);
+        assert_eq!(rollup_files[0].0, 5);
+    }
+
+    #[tokio::test]
+    async fn test_compact_merges_older_archive_files() {
+        let mut fixture = ArchiveTestFixture::new_with_config(VersionArchiveConfig {
+            max_entries: 2,
+            max_archive_files: 3,
+            ..Default::default()
+        })
+        .await;
+        fixture.archive.add_entries(&[
+            create_test_version_archive_entry(1),
+            create_test_version_archive_entry(2),
+        ]);
+        fixture.archive.flush().await.unwrap();
+        fixture
+            .archive
+            .add_entries(&[create_test_version_archive_entry(3)]);
+        fixture.archive.flush().await.unwrap();
+
+        fixture.archive.compact().await.unwrap();
+
+        let archive_dir = fixture.archive.archive_dir();
+        let archives =
+            VersionArchive::list_archive_files(&fixture.archive.object_store, &archive_dir)
+                .await
+                .unwrap();
+        assert_eq!(archives.len(), 1);
+        let entries = VersionArchive::scan(
+            fixture.archive.base.clone(),
+            fixture.archive.object_store.clone(),
+            *fixture.archive.config(),
+        )
+        .await
+        .unwrap();
+        let versions = entries.iter().map(|e| e.version).collect::<Vec<_>>();
+        assert_eq!(versions, vec![1, 2, 3]);
+    }
+
     #[tokio::test]
     async fn test_scan_archive_respects_max_entries() {
         let mut fixture = ArchiveTestFixture::new_with_config(VersionArchiveConfig {
@@ -1109,6 +2121,75 @@ mod tests {
         assert_eq!(entries[1].version, 3);
     }
 
+    #[tokio::test]
+    async fn test_concurrent_flushes_merge_entries() {
+        let mut fixture = ArchiveTestFixture::new().await;
+        let load = |archive: &VersionArchive| {
+            VersionArchive::load_or_new(
+                archive.base.clone(),
+                archive.object_store.clone(),
+                *archive.config(),
+            )
+        };
+        let mut other = load(&fixture.archive).await.unwrap();
+
+        fixture.archive.add_entries(&[
+            create_test_version_archive_entry(1),
+            create_test_version_archive_entry(2),
+        ]);
+        fixture.archive.flush().await.unwrap();
+
+        // The other archive was loaded before the first flush, its flush merges
+        // the entries of the first one instead of dropping them
+        other.add_entries(&[create_test_version_archive_entry(3)]);
+        other.flush().await.unwrap();
+        let loaded = load(&fixture.archive).await.unwrap();
+        let versions = loaded
+            .versions
+            .iter()
+            .map(|e| e.version)
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec![1, 2, 3]);
+
+        // Both archives write the file of version 4, the second flush updates
+        // the file written by the first one
+        let mut first = load(&fixture.archive).await.unwrap();
+        let mut second = load(&fixture.archive).await.unwrap();
+        let entry = |source: &str| {
+            let mut entry = create_test_version_archive_entry(4);
+            entry
+                .transaction_properties
+                .insert("source".to_string(), source.to_string());
+            entry
+        };
+        first.add_entries(&[entry("first")]);
+        first.flush().await.unwrap();
+        second.add_entries(&[entry("second")]);
+        second.flush().await.unwrap();
+
+        let loaded = load(&fixture.archive).await.unwrap();
+        let versions = loaded
+            .versions
+            .iter()
+            .map(|e| e.version)
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec![1, 2, 3, 4]);
+        assert_eq!(
+            loaded.versions[3].transaction_properties.get("source"),
+            Some(&"second".to_string())
+        );
+
+        let archive_dir = fixture.archive.archive_dir();
+        let archives =
+            VersionArchive::list_archive_files(&fixture.archive.object_store, &archive_dir)
+                .await
+                .unwrap();
+        assert_eq!(
+            archives.iter().map(|(v, _)| *v).collect::<Vec<_>>(),
+            vec![4, 3]
+        );
+    }
+
     #[tokio::test]
     async fn test_load_newest_valid_archive() {
         let mut fixture = ArchiveTestFixture::new().await;
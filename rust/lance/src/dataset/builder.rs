@@ -13,6 +13,7 @@ use futures::FutureExt;
 use lance_core::utils::tracing::{DATASET_LOADING_EVENT, TRACE_DATASET_EVENTS};
 use lance_file::datatypes::populate_schema_dictionary;
 use lance_file::reader::FileReaderOptions;
+use lance_io::object_store::encryption::KeyProvider;
 use lance_io::object_store::{
     DEFAULT_CLOUD_IO_PARALLELISM, LanceNamespaceStorageOptionsProvider, ObjectStore,
     ObjectStoreParams, StorageOptions, StorageOptionsAccessor,
@@ -49,6 +50,8 @@ pub struct DatasetBuilder {
     version: Option<Ref>,
     table_uri: String,
     file_reader_options: Option<FileReaderOptions>,
+    /// Decrypts the data keys of the encrypted columns
+    key_provider: Option<Arc<dyn KeyProvider>>,
     /// Storage options that override user-provided options (e.g., from namespace client)
     storage_options_override: Option<HashMap<String, String>>,
     /// Runtime-only exact object store bindings keyed by base path URI.
@@ -72,6 +75,7 @@ impl std::fmt::Debug for DatasetBuilder {
             .field("version", &self.version)
             .field("table_uri", &self.table_uri)
             .field("file_reader_options", &self.file_reader_options)
+            .field("key_provider", &self.key_provider)
             .field(
                 "storage_options_override",
                 &self.storage_options_override.is_some(),
@@ -95,6 +99,7 @@ impl DatasetBuilder {
             version: None,
             manifest: None,
             file_reader_options: None,
+            key_provider: None,
             storage_options_override: None,
             base_store_params: HashMap::new(),
             namespace_managed: None,
@@ -493,6 +498,10 @@ impl DatasetBuilder {
             self.file_reader_options = Some(file_reader_options);
         }
 
+        if let Some(key_provider) = read_params.key_provider {
+            self.key_provider = Some(key_provider);
+        }
+
         self
     }
 
@@ -519,6 +528,13 @@ impl DatasetBuilder {
         self
     }
 
+    /// Decrypt the data keys of the encrypted columns with `key_provider`, see
+    /// [`encryption`](super::encryption).
+    pub fn with_key_provider(mut self, key_provider: Arc<dyn KeyProvider>) -> Self {
+        self.key_provider = Some(key_provider);
+        self
+    }
+
     /// Set exact object store params used as the dataset-level default binding.
     pub fn with_store_params(mut self, store_params: ObjectStoreParams) -> Self {
         self.options = store_params;
@@ -529,6 +545,7 @@ impl DatasetBuilder {
     pub async fn build_object_store(
         mut self,
     ) -> Result<(Arc<ObjectStore>, Path, Arc<dyn CommitHandler>)> {
+        self.apply_session_io_budget();
         let storage_options = self
             .options
             .storage_options()
@@ -595,7 +612,17 @@ impl DatasetBuilder {
     pub async fn load(self) -> Result<Dataset> {
         let uri = self.table_uri.clone();
         let target_ref = self.version.clone();
-        match self.load_impl().boxed().await {
+        let key_provider = self.key_provider.clone();
+        let result = async {
+            let mut dataset = self.load_impl().boxed().await?;
+            if let Some(key_provider) = key_provider {
+                dataset
+                    .resolve_encryption_keys(key_provider.as_ref())
+                    .await?;
+            }
+            Ok::<_, Error>(dataset)
+        };
+        match result.await {
             Ok(dataset) => {
                 info!(target: TRACE_DATASET_EVENTS, event=DATASET_LOADING_EVENT, uri=uri, target_ref = ?target_ref, version=dataset.manifest.version, status="success");
                 Ok(dataset)
@@ -607,6 +634,20 @@ impl DatasetBuilder {
         }
     }
 
+    // The session budget applies to every store of the dataset unless the params
+    // already carry their own budget.
+    fn apply_session_io_budget(&mut self) {
+        let Some(io_budget) = self.session.as_ref().and_then(|s| s.io_budget().cloned()) else {
+            return;
+        };
+        for params in std::iter::once(&mut self.options).chain(self.base_store_params.values_mut())
+        {
+            if params.io_budget.is_none() {
+                params.io_budget = Some(io_budget.clone());
+            }
+        }
+    }
+
     // Runtime per-base overrides are supplied as storage options, but the dataset
     // ultimately resolves object stores from ObjectStoreParams. Normalize once in
     // the builder so reads only need to look up the prepared params by base path.
@@ -668,6 +709,7 @@ impl DatasetBuilder {
         let manifest = self.manifest.take();
 
         let file_reader_options = self.file_reader_options.clone();
+        self.apply_session_io_budget();
         let store_params = self.options.clone();
         let base_store_params = (!self.base_store_params.is_empty())
             .then(|| Arc::new(std::mem::take(&mut self.base_store_params)));
@@ -129,6 +129,8 @@ struct CleanupInspection {
     version_archive_entries: Vec<VersionArchiveEntry>,
     /// Latest version archive file
     version_archive: VersionArchive,
+    /// The base versions of delta manifests by version.
+    delta_bases: HashMap<u64, u64>,
 }
 
 impl CleanupInspection {
@@ -145,8 +147,48 @@ impl CleanupInspection {
             earliest_retained_manifest_time: None,
             version_archive_entries: Vec::new(),
             version_archive,
+            delta_bases: HashMap::new(),
         })
     }
+
+    /// Keep the manifests that retained delta manifests are based on, directly
+    /// or through other delta manifests, since reading the retained versions
+    /// needs them. Returns the versions of the kept manifests that were old,
+    /// whose files must be added to the working set.
+    fn retain_delta_bases(&mut self) -> Vec<u64> {
+        let old_versions = self.old_manifests.values().copied().collect::<HashSet<_>>();
+        let mut required = HashSet::new();
+        for (version, base_version) in &self.delta_bases {
+            if old_versions.contains(version) {
+                continue;
+            }
+            let mut base_version = Some(*base_version);
+            while let Some(version) = base_version
+                && required.insert(version)
+            {
+                base_version = self.delta_bases.get(&version).copied();
+            }
+        }
+        let mut kept = Vec::new();
+        self.old_manifests.retain(|_, version| {
+            let is_required = required.contains(version);
+            if is_required {
+                kept.push(*version);
+            }
+            !is_required
+        });
+        kept
+    }
+
+    /// Track the commit time of a retained manifest.
+    fn retain_manifest_time(&mut self, commit_ts: DateTime<Utc>) {
+        if self
+            .earliest_retained_manifest_time
+            .is_none_or(|earliest| commit_ts < earliest)
+        {
+            self.earliest_retained_manifest_time = Some(commit_ts);
+        }
+    }
 }
 
 /// If a file cannot be verified then it will only be deleted if it is at least
@@ -211,6 +253,14 @@ impl<'a> CleanupTask<'a> {
         // by comparing archive entries with live manifests when scanning.
         for summary in &mut inspection.version_archive_entries {
             summary.is_tagged = tagged_versions.contains(&summary.version);
+            summary.tags = tags
+                .iter()
+                .filter(|(_, tag)| {
+                    tag.version == summary.version && tag.branch.as_ref() == current_branch.as_ref()
+                })
+                .map(|(name, _)| name.clone())
+                .collect();
+            summary.tags.sort();
         }
 
         // Add collected entries to archive (sorts internally)
@@ -244,9 +294,39 @@ impl<'a> CleanupTask<'a> {
                 self.process_manifest_file(location, &inspection, tagged_versions)
             })
             .await?;
+        let kept_bases = inspection.lock().unwrap().retain_delta_bases();
+        for version in kept_bases {
+            self.process_delta_base(version, &inspection).await?;
+        }
         Ok(inspection.into_inner().unwrap())
     }
 
+    /// Add the files of a kept delta base to the working set, so the base
+    /// version stays readable like the delta manifests based on it.
+    async fn process_delta_base(
+        &self,
+        version: u64,
+        inspection: &Mutex<CleanupInspection>,
+    ) -> Result<()> {
+        let location = self
+            .dataset
+            .commit_handler
+            .resolve_version_location(
+                &self.dataset.base,
+                version,
+                &self.dataset.object_store.inner,
+            )
+            .await?;
+        let manifest =
+            read_manifest(&self.dataset.object_store, &location.path, location.size).await?;
+        let indexes =
+            read_manifest_indexes(&self.dataset.object_store, &location, &manifest).await?;
+        let mut inspection = inspection.lock().unwrap();
+        self.process_manifest(&manifest, &indexes, true, &mut inspection)?;
+        inspection.retain_manifest_time(manifest.timestamp());
+        Ok(())
+    }
+
     async fn process_manifest_file(
         &self,
         location: ManifestLocation,
@@ -281,32 +361,15 @@ impl<'a> CleanupTask<'a> {
 
         let mut inspection = inspection.lock().unwrap();
 
-        if manifest.version > inspection.version_archive.latest_version_number {
-            let manifest_summary = manifest.summary();
-            let (transaction_uuid, read_version, operation_type, transaction_properties) =
-                match &transaction {
-                    Some(tx) => (
-                        Some(tx.uuid.clone()),
-                        Some(tx.read_version),
-                        Some(tx.operation.to_string()),
-                        tx.transaction_properties
-                            .as_deref()
-                            .cloned()
-                            .unwrap_or_default(),
-                    ),
-                    None => (None, None, None, HashMap::new()),
-                };
+        if let Some(delta_base) = &manifest.delta_base {
+            inspection
+                .delta_bases
+                .insert(manifest.version, delta_base.version);
+        }
 
-            let version_archive_entry = VersionArchiveEntry {
-                version: manifest.version,
-                timestamp_millis: manifest.timestamp().timestamp_millis(),
-                manifest_summary,
-                is_tagged: false,
-                transaction_uuid,
-                read_version,
-                operation_type,
-                transaction_properties,
-            };
+        if manifest.version > inspection.version_archive.latest_version_number {
+            let version_archive_entry =
+                VersionArchiveEntry::from_manifest(&manifest, transaction.as_ref(), &indexes);
             inspection
                 .version_archive_entries
                 .push(version_archive_entry);
@@ -324,14 +387,7 @@ impl<'a> CleanupTask<'a> {
                 .old_manifests
                 .insert(location.path.clone(), manifest.version);
         } else {
-            let commit_ts = manifest.timestamp();
-            if let Some(ts) = inspection.earliest_retained_manifest_time {
-                if commit_ts < ts {
-                    inspection.earliest_retained_manifest_time = Some(commit_ts);
-                }
-            } else {
-                inspection.earliest_retained_manifest_time = Some(commit_ts);
-            }
+            inspection.retain_manifest_time(manifest.timestamp());
         }
         Ok(())
     }
@@ -3994,6 +4050,8 @@ mod tests {
                 read_version: None,
                 operation_type: None,
                 transaction_properties: HashMap::new(),
+                indices: Vec::new(),
+                tags: Vec::new(),
             }
         }
 
@@ -0,0 +1,432 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Embedding columns derived from a source column by a registered embedding function.
+//!
+//! An embedding function is registered on the [`Session`](crate::session::Session) under a
+//! name.  [`Dataset::add_embedding_column`] adds a column whose values are computed by that
+//! function from a source column.  The function name, source column and function
+//! configuration are recorded in the field metadata so the column can be reproduced later.
+//!
+//! Newly added embedding columns are *virtual*: no data is written and scans compute the
+//! values on the fly.  [`Dataset::materialize_embedding_column`] backfills the values into
+//! data files, which is required before a vector index can be built on the column.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use async_trait::async_trait;
+use lance_core::datatypes::{Field, Schema};
+use lance_core::{Error, Result};
+use lance_table::format::Fragment;
+
+use super::Dataset;
+use super::transaction::{Operation, Transaction};
+use crate::dataset::NewColumnTransform;
+
+/// Field metadata key holding the name of the embedding function.
+pub const EMBEDDING_FUNCTION_META_KEY: &str = "lance-embedding:function";
+/// Field metadata key holding the name of the source column.
+pub const EMBEDDING_SOURCE_META_KEY: &str = "lance-embedding:source";
+/// Field metadata key holding the JSON encoded configuration of the embedding function.
+pub const EMBEDDING_CONFIG_META_KEY: &str = "lance-embedding:config";
+/// Field metadata key set to `true` while the embedding column has not been materialized.
+pub const EMBEDDING_VIRTUAL_META_KEY: &str = "lance-embedding:virtual";
+
+/// A function that computes embeddings for the values of a source column.
+///
+/// Implementations may compute the embeddings locally or call out to an external
+/// service.  The function is registered on a [`Session`](crate::session::Session) with
+/// [`Session::register_embedding_function`](crate::session::Session::register_embedding_function).
+#[async_trait]
+pub trait EmbeddingFunction: Send + Sync + Debug {
+    /// The data type of the embeddings, typically a `FixedSizeList<Float32>`.
+    fn output_type(&self) -> DataType;
+
+    /// Configuration of the function (model name, dimension, endpoint, ...).
+    ///
+    /// This is recorded in the field metadata of the embedding column so that the
+    /// embeddings can be reproduced.  It should not contain secrets.
+    fn config(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// Compute the embeddings for `source`.
+    ///
+    /// The returned array must have the same length as `source` and be of type
+    /// [`Self::output_type`].  Null source values should produce null embeddings.
+    async fn embed(&self, source: ArrayRef) -> Result<ArrayRef>;
+}
+
+/// The definition of an embedding column, as recorded in its field metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddingSpec {
+    /// The name the embedding function is registered under.
+    pub function: String,
+    /// The column the embeddings are computed from.
+    pub source: String,
+    /// The configuration of the embedding function when the column was created.
+    pub config: HashMap<String, String>,
+    /// Whether the values are computed at scan time rather than stored.
+    pub is_virtual: bool,
+}
+
+impl EmbeddingSpec {
+    /// Read the embedding spec from field metadata, if the field is an embedding column.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Result<Option<Self>> {
+        let Some(function) = metadata.get(EMBEDDING_FUNCTION_META_KEY) else {
+            return Ok(None);
+        };
+        let source = metadata.get(EMBEDDING_SOURCE_META_KEY).ok_or_else(|| {
+            Error::invalid_input(format!(
+                "embedding column metadata is missing the {EMBEDDING_SOURCE_META_KEY} key"
+            ))
+        })?;
+        let config = match metadata.get(EMBEDDING_CONFIG_META_KEY) {
+            Some(config) => serde_json::from_str(config).map_err(|e| {
+                Error::invalid_input(format!("invalid embedding configuration: {e}"))
+            })?,
+            None => HashMap::new(),
+        };
+        let is_virtual = metadata
+            .get(EMBEDDING_VIRTUAL_META_KEY)
+            .is_some_and(|v| v == "true");
+        Ok(Some(Self {
+            function: function.clone(),
+            source: source.clone(),
+            config,
+            is_virtual,
+        }))
+    }
+
+    /// Read the embedding spec of a dataset field, if it is an embedding column.
+    pub fn from_field(field: &Field) -> Result<Option<Self>> {
+        Self::from_metadata(&field.metadata)
+    }
+
+    /// Encode the spec as field metadata.
+    pub fn to_metadata(&self) -> HashMap<String, String> {
+        HashMap::from([
+            (
+                EMBEDDING_FUNCTION_META_KEY.to_string(),
+                self.function.clone(),
+            ),
+            (EMBEDDING_SOURCE_META_KEY.to_string(), self.source.clone()),
+            (
+                EMBEDDING_CONFIG_META_KEY.to_string(),
+                serde_json::to_string(&self.config).unwrap(),
+            ),
+            (
+                EMBEDDING_VIRTUAL_META_KEY.to_string(),
+                self.is_virtual.to_string(),
+            ),
+        ])
+    }
+}
+
+/// Look up an embedding function registered on the dataset's session.
+pub(crate) fn lookup_function(dataset: &Dataset, name: &str) -> Result<Arc<dyn EmbeddingFunction>> {
+    dataset.session.embedding_function(name).ok_or_else(|| {
+        Error::invalid_input(format!(
+            "Embedding function \"{name}\" is not registered in the session"
+        ))
+    })
+}
+
+/// Compute the embeddings of `source`, validating the output of the function.
+pub(crate) async fn compute_embeddings(
+    function: &dyn EmbeddingFunction,
+    source: ArrayRef,
+    expected_type: &DataType,
+) -> Result<ArrayRef> {
+    let num_rows = source.len();
+    let embeddings = function.embed(source).await?;
+    if embeddings.len() != num_rows {
+        return Err(Error::invalid_input(format!(
+            "Embedding function returned {} values for {} rows",
+            embeddings.len(),
+            num_rows
+        )));
+    }
+    if embeddings.data_type() != expected_type {
+        return Err(Error::invalid_input(format!(
+            "Embedding function returned values of type {} but the column has type {}",
+            embeddings.data_type(),
+            expected_type
+        )));
+    }
+    Ok(embeddings)
+}
+
+pub(super) async fn add_embedding_column(
+    dataset: &mut Dataset,
+    column: &str,
+    source: &str,
+    function_name: &str,
+) -> Result<()> {
+    let function = lookup_function(dataset, function_name)?;
+    if dataset.schema().field(source).is_none() {
+        return Err(Error::invalid_input(format!(
+            "Source column \"{source}\" does not exist in the dataset"
+        )));
+    }
+    if dataset.is_legacy_storage() {
+        return Err(Error::not_supported(
+            "Embedding columns require Lance file format 2.0 or later",
+        ));
+    }
+
+    let spec = EmbeddingSpec {
+        function: function_name.to_string(),
+        source: source.to_string(),
+        config: function.config(),
+        is_virtual: true,
+    };
+    let field =
+        ArrowField::new(column, function.output_type(), true).with_metadata(spec.to_metadata());
+    dataset
+        .add_columns(
+            NewColumnTransform::AllNulls(Arc::new(ArrowSchema::new(vec![field]))),
+            None,
+            None,
+        )
+        .await
+}
+
+pub(super) async fn materialize_embedding_column(
+    dataset: &mut Dataset,
+    column: &str,
+) -> Result<()> {
+    let field = dataset.schema().field(column).ok_or_else(|| {
+        Error::invalid_input(format!("Column \"{column}\" does not exist in the dataset"))
+    })?;
+    let mut spec = EmbeddingSpec::from_field(field)?.ok_or_else(|| {
+        Error::invalid_input(format!("Column \"{column}\" is not an embedding column"))
+    })?;
+    if !spec.is_virtual {
+        return Err(Error::invalid_input(format!(
+            "Embedding column \"{column}\" is already materialized"
+        )));
+    }
+    let field_id = field.id;
+    let data_type = field.data_type();
+    let function = lookup_function(dataset, &spec.function)?;
+
+    spec.is_virtual = false;
+    let mut schema = dataset.schema().clone();
+    schema.mut_field_by_id(field_id).unwrap().metadata = spec.to_metadata();
+    let write_schema = schema.project_by_ids(&[field_id], true);
+    let output_schema = Arc::new(ArrowSchema::from(&write_schema));
+
+    let mut fragments: Vec<Fragment> = Vec::with_capacity(dataset.get_fragments().len());
+    for fragment in dataset.get_fragments() {
+        let mut updater = fragment
+            .updater(
+                Some(&[spec.source.as_str()]),
+                Some((write_schema.clone(), schema.clone())),
+                None,
+            )
+            .await?;
+        while let Some(batch) = updater.next().await? {
+            let embeddings =
+                compute_embeddings(function.as_ref(), batch.column(0).clone(), &data_type).await?;
+            let batch = RecordBatch::try_new(output_schema.clone(), vec![embeddings])?;
+            updater.update(batch).await?;
+        }
+        fragments.push(updater.finish().await?);
+    }
+
+    let operation = Operation::Merge { fragments, schema };
+    let transaction = Transaction::new(dataset.manifest.version, operation, None);
+    dataset
+        .apply_commit(transaction, &Default::default(), &Default::default())
+        .await?;
+    Ok(())
+}
+
+/// The virtual embedding columns of `schema`, with their specs.
+pub(crate) fn virtual_embedding_columns(schema: &Schema) -> Result<Vec<(String, EmbeddingSpec)>> {
+    let mut columns = Vec::new();
+    for field in &schema.fields {
+        if let Some(spec) = EmbeddingSpec::from_field(field)?
+            && spec.is_virtual
+        {
+            columns.push((field.name.clone(), spec));
+        }
+    }
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Float32Type;
+    use arrow_array::{
+        FixedSizeListArray, Float32Array, Int32Array, RecordBatchIterator, StringArray,
+    };
+    use futures::TryStreamExt;
+    use lance_index::IndexType;
+    use lance_linalg::distance::MetricType;
+
+    use crate::dataset::WriteParams;
+    use crate::index::DatasetIndexExt;
+    use crate::index::vector::VectorIndexParams;
+    use crate::session::Session;
+
+    /// Embeds a string as `[len, first byte, 1.0]`.
+    #[derive(Debug)]
+    struct LengthEmbedding;
+
+    #[async_trait]
+    impl EmbeddingFunction for LengthEmbedding {
+        fn output_type(&self) -> DataType {
+            DataType::FixedSizeList(
+                Arc::new(ArrowField::new("item", DataType::Float32, true)),
+                3,
+            )
+        }
+
+        fn config(&self) -> HashMap<String, String> {
+            HashMap::from([("model".to_string(), "length".to_string())])
+        }
+
+        async fn embed(&self, source: ArrayRef) -> Result<ArrayRef> {
+            let values = source
+                .as_string::<i32>()
+                .iter()
+                .flat_map(|s| {
+                    let s = s.unwrap_or_default();
+                    [
+                        s.len() as f32,
+                        s.bytes().next().unwrap_or_default() as f32,
+                        1.0,
+                    ]
+                })
+                .collect::<Float32Array>();
+            Ok(Arc::new(FixedSizeListArray::try_new(
+                Arc::new(ArrowField::new("item", DataType::Float32, true)),
+                3,
+                Arc::new(values),
+                None,
+            )?))
+        }
+    }
+
+    async fn test_dataset(uri: &str) -> Dataset {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int32, false),
+            ArrowField::new("text", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..300)),
+                Arc::new(StringArray::from_iter_values(
+                    (0..300).map(|i| "x".repeat(i % 17 + 1)),
+                )),
+            ],
+        )
+        .unwrap();
+        let mut session = Session::default();
+        session
+            .register_embedding_function("length".to_string(), Arc::new(LengthEmbedding))
+            .unwrap();
+        let params = WriteParams {
+            max_rows_per_file: 100,
+            session: Some(Arc::new(session)),
+            ..Default::default()
+        };
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        Dataset::write(reader, uri, Some(params)).await.unwrap()
+    }
+
+    fn check_embeddings(batch: &RecordBatch) {
+        let ids = batch["id"].as_primitive::<arrow_array::types::Int32Type>();
+        let embeddings = batch["vec"].as_fixed_size_list();
+        assert_eq!(embeddings.null_count(), 0);
+        for (id, embedding) in ids.values().iter().zip(embeddings.iter()) {
+            let embedding = embedding.unwrap();
+            let embedding = embedding.as_primitive::<Float32Type>();
+            assert_eq!(embedding.value(0), (*id % 17 + 1) as f32);
+            assert_eq!(embedding.value(1), b'x' as f32);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_virtual_embedding_column() {
+        let test_dir = lance_core::utils::tempfile::TempStrDir::default();
+        let mut dataset = test_dataset(&test_dir).await;
+
+        let err = dataset
+            .add_embedding_column("vec", "text", "missing")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not registered"), "{err}");
+
+        dataset
+            .add_embedding_column("vec", "text", "length")
+            .await
+            .unwrap();
+        let field = dataset.schema().field("vec").unwrap();
+        let spec = EmbeddingSpec::from_field(field).unwrap().unwrap();
+        assert_eq!(spec.function, "length");
+        assert_eq!(spec.source, "text");
+        assert_eq!(spec.config["model"], "length");
+        assert!(spec.is_virtual);
+
+        // Embeddings are computed at scan time, even when the source is not projected
+        let batch = dataset
+            .scan()
+            .project(&["id", "vec"])
+            .unwrap()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(batch.num_columns(), 2);
+        assert_eq!(batch.num_rows(), 300);
+        check_embeddings(&batch);
+
+        // Vector indices require the column to be materialized
+        let params = VectorIndexParams::ivf_flat(2, MetricType::L2);
+        let err = dataset
+            .create_index(&["vec"], IndexType::Vector, None, &params, false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("materialize"), "{err}");
+
+        dataset.materialize_embedding_column("vec").await.unwrap();
+        let spec = EmbeddingSpec::from_field(dataset.schema().field("vec").unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(!spec.is_virtual);
+        let batches = dataset
+            .scan()
+            .project(&["id", "vec"])
+            .unwrap()
+            .try_into_stream()
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        for batch in &batches {
+            check_embeddings(batch);
+        }
+
+        let err = dataset
+            .materialize_embedding_column("vec")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already materialized"), "{err}");
+
+        dataset
+            .create_index(&["vec"], IndexType::Vector, None, &params, false)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Encrypted columns.
+//!
+//! Columns listed in [`EncryptionParams`] when a dataset is created or
+//! overwritten are encrypted in every data file with a data key of their own.
+//! The data keys are generated by a [`KeyProvider`], such as a KMS, and stored
+//! in the manifest config encrypted by the provider, see [`EncryptionSpec`].
+//! Readers pass the provider in [`ReadParams::key_provider`](super::ReadParams::key_provider)
+//! to decrypt the data keys when the dataset is opened.  Without it the
+//! encrypted columns cannot be read but the other columns can.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use lance_core::datatypes::Schema;
+use lance_file::encryption::EncryptionKey;
+use lance_file::reader::FileReaderOptions;
+use lance_table::format::{ColumnKey, EncryptionScheme, EncryptionSpec};
+use uuid::Uuid;
+
+pub use lance_io::object_store::encryption::{KeyProvider, LocalKeyProvider};
+
+use super::{Dataset, WriteMode, WriteParams};
+use crate::{Error, Result};
+
+/// A column to encrypt with a newly generated data key.
+#[derive(Debug, Clone)]
+struct NewColumnKey {
+    column: String,
+    key: EncryptionKey,
+    key_id: String,
+    encrypted_key: Vec<u8>,
+}
+
+/// The key provider of a write, along with the columns to encrypt when a
+/// dataset is created or overwritten.
+#[derive(Debug, Clone)]
+pub struct EncryptionParams {
+    key_provider: Arc<dyn KeyProvider>,
+    columns: Vec<NewColumnKey>,
+}
+
+impl EncryptionParams {
+    /// Params that only resolve the keys of the columns a dataset already
+    /// encrypts, e.g. to append to it.
+    pub fn new(key_provider: Arc<dyn KeyProvider>) -> Self {
+        Self {
+            key_provider,
+            columns: Vec::new(),
+        }
+    }
+
+    /// Encrypt the top-level `columns` of a new or overwritten dataset, each
+    /// with a new data key generated by the key provider.
+    pub async fn encrypt_columns(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<Self> {
+        for column in columns {
+            let data_key = self.key_provider.generate_data_key().await?;
+            self.columns.push(NewColumnKey {
+                column: column.into(),
+                key: EncryptionKey::new(Uuid::new_v4().to_string(), data_key.key),
+                key_id: data_key.key_id,
+                encrypted_key: data_key.encrypted_key,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn key_provider(&self) -> &Arc<dyn KeyProvider> {
+        &self.key_provider
+    }
+
+    /// The spec of the columns to encrypt, resolved against the written
+    /// `schema`.
+    pub(crate) fn spec(&self, schema: &Schema) -> Result<Option<EncryptionSpec>> {
+        if self.columns.is_empty() {
+            return Ok(None);
+        }
+        let columns = self
+            .columns
+            .iter()
+            .map(|new_column| {
+                let field = schema
+                    .fields
+                    .iter()
+                    .find(|field| field.name == new_column.column)
+                    .ok_or_else(|| {
+                        Error::invalid_input(format!(
+                            "Encrypted column {} is not a top-level column of the written data",
+                            new_column.column
+                        ))
+                    })?;
+                Ok(ColumnKey {
+                    field_id: field.id,
+                    id: new_column.key.id().to_string(),
+                    key_id: new_column.key_id.clone(),
+                    encrypted_key: new_column.encrypted_key.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        EncryptionSpec::try_new(EncryptionScheme::ColumnAes256Ctr, columns).map(Some)
+    }
+
+    /// The keys of the columns to encrypt.
+    pub(crate) fn new_keys(&self) -> impl Iterator<Item = &EncryptionKey> {
+        self.columns.iter().map(|new_column| &new_column.key)
+    }
+}
+
+/// Decrypts the data keys of `spec` with `key_provider`.
+pub async fn resolve_keys(
+    spec: &EncryptionSpec,
+    key_provider: &dyn KeyProvider,
+) -> Result<Vec<EncryptionKey>> {
+    let mut keys = Vec::with_capacity(spec.columns.len());
+    for column in &spec.columns {
+        let key = key_provider
+            .decrypt_data_key(&column.key_id, &column.encrypted_key)
+            .await?;
+        keys.push(EncryptionKey::new(column.id.clone(), key));
+    }
+    Ok(keys)
+}
+
+/// Adds `keys` to the keys readers are given, keys already present are kept.
+pub(crate) fn with_keys<'a>(
+    options: Option<FileReaderOptions>,
+    keys: impl IntoIterator<Item = &'a EncryptionKey>,
+) -> Option<FileReaderOptions> {
+    let mut keys = keys.into_iter().peekable();
+    if keys.peek().is_none() {
+        return options;
+    }
+    let mut options = options.unwrap_or_default();
+    for key in keys {
+        if !options
+            .encryption_keys
+            .iter()
+            .any(|existing| existing.id() == key.id())
+        {
+            options.encryption_keys.push(key.clone());
+        }
+    }
+    Some(options)
+}
+
+/// The keys of the encrypted columns of a file of `dataset` with `schema`,
+/// keyed by column name.
+///
+/// Keys resolved when the dataset was opened are used first, the others are
+/// decrypted with `key_provider`.
+pub(crate) async fn dataset_column_keys(
+    dataset: &Dataset,
+    schema: &Schema,
+    key_provider: Option<&Arc<dyn KeyProvider>>,
+) -> Result<HashMap<String, EncryptionKey>> {
+    let Some(spec) = dataset.manifest.encryption_spec()? else {
+        return Ok(HashMap::new());
+    };
+    let mut column_keys = HashMap::new();
+    for field in &schema.fields {
+        let Some(column) = spec.column(field.id) else {
+            continue;
+        };
+        let known_key = dataset.file_reader_options.as_ref().and_then(|options| {
+            options
+                .encryption_keys
+                .iter()
+                .find(|key| key.id() == column.id)
+        });
+        let key = match (known_key, key_provider) {
+            (Some(key), _) => key.clone(),
+            (None, Some(key_provider)) => EncryptionKey::new(
+                column.id.clone(),
+                key_provider
+                    .decrypt_data_key(&column.key_id, &column.encrypted_key)
+                    .await?,
+            ),
+            (None, None) => {
+                return Err(Error::invalid_input(format!(
+                    "Column {} is encrypted, writing it needs a key provider",
+                    field.name
+                )));
+            }
+        };
+        column_keys.insert(field.name.clone(), key);
+    }
+    Ok(column_keys)
+}
+
+/// The keys of the encrypted columns of a write of `schema`, keyed by column
+/// name.
+pub(crate) async fn write_column_keys(
+    dataset: Option<&Dataset>,
+    params: &WriteParams,
+    schema: &Schema,
+) -> Result<HashMap<String, EncryptionKey>> {
+    let encryption = params.encryption.as_ref();
+    if let Some(encryption) = encryption
+        && encryption.spec(schema)?.is_some()
+    {
+        if dataset.is_some() && matches!(params.mode, WriteMode::Append) {
+            return Err(Error::invalid_input(
+                "The encrypted columns can only be set when creating or overwriting a dataset",
+            ));
+        }
+        return Ok(encryption
+            .columns
+            .iter()
+            .map(|new_column| (new_column.column.clone(), new_column.key.clone()))
+            .collect());
+    }
+    match dataset {
+        Some(dataset) => {
+            dataset_column_keys(
+                dataset,
+                schema,
+                encryption.map(|encryption| &encryption.key_provider),
+            )
+            .await
+        }
+        None => Ok(HashMap::new()),
+    }
+}
+
+impl Dataset {
+    /// The encrypted columns of the dataset, if any.
+    pub fn encryption_spec(&self) -> Result<Option<EncryptionSpec>> {
+        self.manifest.encryption_spec()
+    }
+
+    /// Decrypts the data keys of the encrypted columns with `key_provider` so
+    /// that they can be read.
+    pub(crate) async fn resolve_encryption_keys(
+        &mut self,
+        key_provider: &dyn KeyProvider,
+    ) -> Result<()> {
+        let Some(spec) = self.manifest.encryption_spec()? else {
+            return Ok(());
+        };
+        let keys = resolve_keys(&spec, key_provider).await?;
+        self.file_reader_options = with_keys(self.file_reader_options.take(), &keys);
+        Ok(())
+    }
+}
@@ -1026,6 +1026,8 @@ mod tests {
             file_minor_version: 0,
             file_size_bytes: CachedFileSize::unknown(),
             base_id,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         };
 
         let fragment = Fragment {
@@ -3,8 +3,10 @@
 
 //! Wraps a Fragment of the dataset.
 
+pub(crate) mod partition;
 pub mod session;
 pub mod write;
+pub(crate) mod zone_map;
 
 use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -135,6 +137,9 @@ pub trait GenericFileReader: std::fmt::Debug + Send + Sync {
     /// Get storage statistics for this file (ignored by v1 reader)
     fn storage_stats(&self) -> Vec<(u32, u64)>;
 
+    /// Hint that the given ranges of rows will be read soon (ignored by v1 reader)
+    fn prefetch(&self, _ranges: &[Range<u64>]) {}
+
     // Helper functions to fallback to the legacy implementation while we
     // slowly migrate functionality over to the generic reader
 
@@ -487,6 +492,17 @@ mod v2_adapter {
             .boxed()
         }
 
+        fn prefetch(&self, ranges: &[Range<u64>]) {
+            // If the projection is invalid the read will fail, there is nothing to prefetch
+            if let Ok(projection) = ReaderProjection::from_field_ids(
+                self.reader.metadata().version(),
+                self.projection.as_ref(),
+                self.field_id_to_column_idx.as_ref(),
+            ) {
+                self.reader.prefetch(ranges, Some(&projection));
+            }
+        }
+
         fn storage_stats(&self) -> Vec<(u32, u64)> {
             let file_statistics = self.reader.file_statistics();
             let column_idx_to_field_id = self
@@ -2580,6 +2596,18 @@ impl FragmentReader {
         .await
     }
 
+    /// Hint that the given ranges of physical rows will be read soon
+    ///
+    /// The data of the projected columns is read in the background, so that a following
+    /// [`Self::read_ranges`] does not have to wait on storage.  See
+    /// [`FileScheduler::prefetch`](lance_io::scheduler::FileScheduler::prefetch).  This
+    /// has no effect on legacy files.
+    pub fn prefetch(&self, ranges: &[Range<u64>]) {
+        for reader in &self.readers {
+            reader.prefetch(ranges);
+        }
+    }
+
     // This method is a clone of new_read_impl but returns tasks instead of batches
     //
     // It also only supports v2 files
@@ -0,0 +1,337 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Partitioned writes.
+//!
+//! When a dataset has a [`PartitionSpec`], writers split every batch by the
+//! partition of its rows and keep a data file open per partition.  Each file
+//! records the [`PartitionValue`]s shared by its rows, which the zone map
+//! pruner uses to skip the fragments of other partitions.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::compute::{CastOptions, cast_with_options, take_record_batch};
+use arrow_array::builder::UInt32Builder;
+use arrow_array::types::Int64Type;
+use arrow_array::{Array, ArrayRef, RecordBatch, UInt32Array, cast::AsArray};
+use arrow_row::{RowConverter, SortField};
+use arrow_schema::DataType;
+use lance_arrow_scalar::ArrowScalar;
+use lance_core::datatypes::{Field, Schema};
+use lance_core::{Error, Result};
+use lance_table::format::{PartitionField, PartitionSpec, PartitionTransform, PartitionValue};
+use twox_hash::XxHash3_64;
+
+/// Checks that `transform` can be applied to the values of `field`.
+pub fn check_partition_field(field: &Field, transform: &PartitionTransform) -> Result<()> {
+    let data_type = field.data_type();
+    let supported = match transform {
+        PartitionTransform::Identity | PartitionTransform::Hash { .. } => {
+            data_type.is_primitive()
+                || matches!(
+                    data_type,
+                    DataType::Boolean | DataType::Utf8 | DataType::LargeUtf8
+                )
+        }
+        PartitionTransform::Range { .. } => {
+            data_type.is_integer()
+                || matches!(
+                    data_type,
+                    DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _)
+                )
+        }
+    };
+    if supported {
+        Ok(())
+    } else {
+        Err(Error::invalid_input(format!(
+            "Cannot partition column {} of type {data_type} by {transform:?}",
+            field.name
+        )))
+    }
+}
+
+/// The bucket of a non-null value in a hash transform with `buckets` buckets.
+///
+/// The hash covers the encoded scalar, so it is stable across platforms and
+/// versions, which the persisted partition values rely on.
+pub fn hash_bucket(value: &ArrowScalar, buckets: u32) -> Result<u32> {
+    let hash = XxHash3_64::oneshot(&value.encode()?);
+    Ok((hash % buckets as u64) as u32)
+}
+
+/// The smallest and largest value in range `index` of a range transform.
+pub fn range_bounds(index: i64, width: u64) -> Option<(i64, i64)> {
+    let width = i64::try_from(width).ok()?;
+    let low = index.checked_mul(width)?;
+    Some((low, low.checked_add(width - 1)?))
+}
+
+/// The transformed values of `column`, nulls stay null.
+fn transform_array(column: &ArrayRef, transform: &PartitionTransform) -> Result<ArrayRef> {
+    match transform {
+        PartitionTransform::Identity => Ok(column.clone()),
+        PartitionTransform::Hash { buckets } => {
+            let mut builder = UInt32Builder::with_capacity(column.len());
+            for row in 0..column.len() {
+                if column.is_null(row) {
+                    builder.append_null();
+                } else {
+                    let value = ArrowScalar::try_new(column, row)?;
+                    builder.append_value(hash_bucket(&value, *buckets)?);
+                }
+            }
+            Ok(Arc::new(builder.finish()))
+        }
+        PartitionTransform::Range { width } => {
+            let width = i64::try_from(*width).unwrap_or(i64::MAX);
+            // A safe cast would turn values beyond i64, e.g. large UInt64s,
+            // into null partitions that pruning treats as holding no values
+            let options = CastOptions {
+                safe: false,
+                ..Default::default()
+            };
+            let values = cast_with_options(column, &DataType::Int64, &options).map_err(|e| {
+                Error::invalid_input(format!(
+                    "Cannot compute the range partition of column values: {e}"
+                ))
+            })?;
+            let ranges = values
+                .as_primitive::<Int64Type>()
+                .unary::<_, Int64Type>(|value| value.div_euclid(width));
+            Ok(Arc::new(ranges))
+        }
+    }
+}
+
+/// Splits the batches of a write by the partition of their rows.
+pub struct PartitionRouter {
+    /// The partition fields along with the names of their columns
+    fields: Vec<(PartitionField, String)>,
+}
+
+impl PartitionRouter {
+    /// The router for writing `schema` with `spec`.
+    ///
+    /// Returns `None` if the written schema lacks a partition field, e.g. when
+    /// only some columns of the dataset are rewritten, as such files cannot
+    /// hold a single partition.
+    pub fn try_new(spec: &PartitionSpec, schema: &Schema) -> Result<Option<Self>> {
+        let mut fields = Vec::with_capacity(spec.fields.len());
+        for partition_field in &spec.fields {
+            let Some(field) = schema
+                .fields
+                .iter()
+                .find(|field| field.id == partition_field.field_id)
+            else {
+                return Ok(None);
+            };
+            check_partition_field(field, &partition_field.transform)?;
+            fields.push((partition_field.clone(), field.name.clone()));
+        }
+        Ok(Some(Self { fields }))
+    }
+
+    /// Splits `batch` into one batch per partition, along with the partition
+    /// values of its rows.  Partitions are returned in the order their first
+    /// row appears in.
+    pub fn split(&self, batch: &RecordBatch) -> Result<Vec<(Vec<PartitionValue>, RecordBatch)>> {
+        let keys = self
+            .fields
+            .iter()
+            .map(|(partition_field, name)| {
+                let column = batch.column_by_name(name).ok_or_else(|| {
+                    Error::invalid_input(format!(
+                        "The written data lacks the partition column {name}"
+                    ))
+                })?;
+                transform_array(column, &partition_field.transform)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let converter = RowConverter::new(
+            keys.iter()
+                .map(|key| SortField::new(key.data_type().clone()))
+                .collect(),
+        )?;
+        let rows = converter.convert_columns(&keys)?;
+        let mut groups: Vec<Vec<u32>> = Vec::new();
+        let mut group_of_key = HashMap::new();
+        for (row, key) in rows.iter().enumerate() {
+            let group = *group_of_key.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(row as u32);
+        }
+
+        let single_partition = groups.len() == 1;
+        groups
+            .into_iter()
+            .map(|indices| {
+                let first = indices[0] as usize;
+                let values = self
+                    .fields
+                    .iter()
+                    .zip(&keys)
+                    .map(|((partition_field, _), key)| {
+                        Ok(PartitionValue {
+                            field_id: partition_field.field_id,
+                            transform: partition_field.transform,
+                            value: ArrowScalar::try_new(key, first)?.encode()?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let batch = if single_partition {
+                    batch.clone()
+                } else {
+                    take_record_batch(batch, &UInt32Array::from(indices))?
+                };
+                Ok((values, batch))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::{Int64Array, StringArray, UInt64Array};
+    use arrow_schema::{Field as ArrowField, Schema as ArrowSchema};
+
+    fn batch() -> RecordBatch {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("region", DataType::Utf8, true),
+            ArrowField::new("ts", DataType::Int64, false),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("eu"),
+                    Some("us"),
+                    Some("eu"),
+                    None,
+                ])),
+                Arc::new(Int64Array::from(vec![5, 15, -5, 25])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn router(transforms: &[(i32, PartitionTransform)]) -> PartitionRouter {
+        let schema = Schema::try_from(batch().schema().as_ref()).unwrap();
+        let spec = PartitionSpec::try_new(
+            transforms
+                .iter()
+                .map(|(field_id, transform)| PartitionField {
+                    field_id: *field_id,
+                    transform: *transform,
+                })
+                .collect(),
+        )
+        .unwrap();
+        PartitionRouter::try_new(&spec, &schema).unwrap().unwrap()
+    }
+
+    fn decode(value: &PartitionValue) -> ArrowScalar {
+        ArrowScalar::decode(&value.value).unwrap()
+    }
+
+    #[test]
+    fn test_split_identity() {
+        let parts = router(&[(0, PartitionTransform::Identity)])
+            .split(&batch())
+            .unwrap();
+        let summary = parts
+            .iter()
+            .map(|(values, batch)| (decode(&values[0]), batch.num_rows()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (ArrowScalar::from("eu"), 2),
+                (ArrowScalar::from("us"), 1),
+                (ArrowScalar::new_null(&DataType::Utf8).unwrap(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_range() {
+        let parts = router(&[(1, PartitionTransform::Range { width: 10 })])
+            .split(&batch())
+            .unwrap();
+        let ranges = parts
+            .iter()
+            .map(|(values, _)| decode(&values[0]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            [0i64, 1, -1, 2].map(ArrowScalar::from).to_vec(),
+            "ranges round down, also for negative values"
+        );
+        assert_eq!(range_bounds(-1, 10), Some((-10, -1)));
+        assert_eq!(range_bounds(i64::MAX, 10), None);
+    }
+
+    #[test]
+    fn test_range_overflow() {
+        let transform = PartitionTransform::Range { width: 10 };
+        let column: ArrayRef = Arc::new(UInt64Array::from(vec![Some(15), None]));
+        let ranges = transform_array(&column, &transform).unwrap();
+        assert_eq!(ranges.as_primitive::<Int64Type>().value(0), 1);
+        assert!(ranges.is_null(1));
+
+        // Values beyond i64 fail the write instead of getting a null partition
+        let column: ArrayRef = Arc::new(UInt64Array::from(vec![5, u64::MAX]));
+        assert!(transform_array(&column, &transform).is_err());
+    }
+
+    #[test]
+    fn test_split_hash() {
+        let parts = router(&[(0, PartitionTransform::Hash { buckets: 4 })])
+            .split(&batch())
+            .unwrap();
+        // Equal values share a bucket and all buckets are in range
+        let eu = ArrowScalar::from("eu");
+        let eu_bucket = ArrowScalar::from(hash_bucket(&eu, 4).unwrap());
+        let eu_part = parts
+            .iter()
+            .find(|(values, _)| decode(&values[0]) == eu_bucket)
+            .unwrap();
+        assert!(eu_part.1.num_rows() >= 2);
+        for (values, _) in &parts {
+            let bucket = decode(&values[0]);
+            assert!(bucket.is_null() || bucket < ArrowScalar::from(4u32));
+        }
+        assert_eq!(
+            parts
+                .iter()
+                .map(|(_, batch)| batch.num_rows())
+                .sum::<usize>(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_unsupported_transform() {
+        let schema = Schema::try_from(batch().schema().as_ref()).unwrap();
+        let spec = PartitionSpec::try_new(vec![PartitionField {
+            field_id: 0,
+            transform: PartitionTransform::Range { width: 10 },
+        }])
+        .unwrap();
+        assert!(PartitionRouter::try_new(&spec, &schema).is_err());
+
+        // Writes without the partition column are not partitioned
+        let spec = PartitionSpec::try_new(vec![PartitionField {
+            field_id: 7,
+            transform: PartitionTransform::Identity,
+        }])
+        .unwrap();
+        assert!(PartitionRouter::try_new(&spec, &schema).unwrap().is_none());
+    }
+}
@@ -0,0 +1,890 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Automatic per-fragment zone maps.
+//!
+//! Every data file written in the v2 format records the min, max and null count of
+//! its primitive, boolean and string fields that are not nested in a list (see
+//! [`ColumnZoneMap`]).  The bounds of very long strings are not recorded.  Fields
+//! of structs are included so that, for example, the bounds of a bounding box column
+//! can rule out fragments for a spatial filter.  These statistics are
+//! stored with the fragment metadata in the manifest, so the scanner can rule out
+//! fragments for a filter without opening any data file and without the user
+//! having to create a zone map index.
+//!
+//! Data files written with a partition spec also record the partition values of
+//! their rows (see [`super::partition`]), which rule out fragments the same way.
+
+use std::cmp::Ordering;
+
+use arrow_array::{Array, ArrayRef, RecordBatch, cast::AsArray, make_array};
+use arrow_buffer::NullBuffer;
+use arrow_schema::DataType;
+use datafusion::common::ColumnStatistics;
+use datafusion::common::stats::Precision;
+use datafusion::logical_expr::{Between, BinaryExpr, Expr, Operator, expr::InList};
+use datafusion::scalar::ScalarValue;
+use lance_arrow_scalar::ArrowScalar;
+use lance_arrow_stats::StatisticsAccumulator;
+use lance_core::Result;
+use lance_core::datatypes::{Field, Schema, format_field_path};
+use lance_table::format::{ColumnZoneMap, Fragment, PartitionTransform, PartitionValue};
+
+use super::partition::{hash_bucket, range_bounds};
+
+/// The maximum size of an encoded zone map bound, larger bounds are left out so
+/// long strings do not bloat the manifest.
+const MAX_ENCODED_BOUND_LEN: usize = 256;
+
+/// Whether zone maps are collected for fields of `data_type`.
+fn has_zone_map(data_type: &DataType) -> bool {
+    data_type.is_primitive()
+        || matches!(
+            data_type,
+            DataType::Boolean | DataType::Utf8 | DataType::LargeUtf8
+        )
+}
+
+/// Collects zone maps for the scalar fields (at the top level or in structs) of a
+/// data file while batches are written to it.
+pub struct ZoneMapCollector {
+    /// (field id, path of field names, accumulator) for every tracked field
+    columns: Vec<(i32, Vec<String>, StatisticsAccumulator)>,
+}
+
+impl ZoneMapCollector {
+    pub fn new(schema: &Schema) -> Self {
+        let mut columns = Vec::new();
+        Self::collect_fields(&schema.fields, &mut Vec::new(), &mut columns);
+        Self { columns }
+    }
+
+    fn collect_fields(
+        fields: &[Field],
+        path: &mut Vec<String>,
+        columns: &mut Vec<(i32, Vec<String>, StatisticsAccumulator)>,
+    ) {
+        for field in fields {
+            path.push(field.name.clone());
+            let data_type = field.data_type();
+            if has_zone_map(&data_type) {
+                columns.push((
+                    field.id,
+                    path.clone(),
+                    StatisticsAccumulator::new(&data_type),
+                ));
+            } else if matches!(data_type, DataType::Struct(_)) {
+                Self::collect_fields(&field.children, path, columns);
+            }
+            path.pop();
+        }
+    }
+
+    pub fn update(&mut self, batch: &RecordBatch) -> Result<()> {
+        for (_, path, accumulator) in self.columns.iter_mut() {
+            if let Some(array) = Self::column_by_path(batch, path)? {
+                accumulator.update(&array)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The array of a (possibly nested) field, a struct field is null wherever one of
+    /// its parents is null
+    fn column_by_path(batch: &RecordBatch, path: &[String]) -> Result<Option<ArrayRef>> {
+        let Some(mut array) = batch.column_by_name(&path[0]).cloned() else {
+            return Ok(None);
+        };
+        for name in &path[1..] {
+            let Some(parent) = array.as_struct_opt() else {
+                return Ok(None);
+            };
+            let Some(child) = parent.column_by_name(name) else {
+                return Ok(None);
+            };
+            array = match NullBuffer::union(parent.nulls(), child.nulls()) {
+                Some(nulls) if parent.nulls().is_some() => {
+                    make_array(child.to_data().into_builder().nulls(Some(nulls)).build()?)
+                }
+                _ => child.clone(),
+            };
+        }
+        Ok(Some(array))
+    }
+
+    pub fn finish(&mut self) -> Result<Vec<ColumnZoneMap>> {
+        std::mem::take(&mut self.columns)
+            .into_iter()
+            .map(|(field_id, _, accumulator)| {
+                let stats = accumulator.finish();
+                // Range predicates can match NaN, so a zone with NaNs has no usable bounds
+                let has_nan = stats.nan_count.is_some_and(|count| count > 0);
+                let encode = |value: Option<ArrowScalar>| -> Result<Option<Vec<u8>>> {
+                    match value {
+                        Some(value) if !has_nan && !value.is_null() => {
+                            let bytes = value.encode()?;
+                            Ok((bytes.len() <= MAX_ENCODED_BOUND_LEN).then_some(bytes))
+                        }
+                        _ => Ok(None),
+                    }
+                };
+                Ok(ColumnZoneMap {
+                    field_id,
+                    min: encode(stats.min)?,
+                    max: encode(stats.max)?,
+                    null_count: stats.null_count,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Decoded zone map statistics for a single column of a fragment
+struct Zone {
+    min: ScalarValue,
+    max: ScalarValue,
+}
+
+impl Zone {
+    fn may_contain(&self, value: &ScalarValue) -> bool {
+        !matches!(value.partial_cmp(&self.min), Some(Ordering::Less))
+            && !matches!(value.partial_cmp(&self.max), Some(Ordering::Greater))
+    }
+
+    fn may_satisfy(&self, op: Operator, value: &ScalarValue) -> bool {
+        let max_cmp = self.max.partial_cmp(value);
+        let min_cmp = self.min.partial_cmp(value);
+        match op {
+            Operator::Eq => self.may_contain(value),
+            Operator::NotEq => {
+                !(min_cmp == Some(Ordering::Equal) && max_cmp == Some(Ordering::Equal))
+            }
+            Operator::Lt => !matches!(min_cmp, Some(Ordering::Greater | Ordering::Equal)),
+            Operator::LtEq => !matches!(min_cmp, Some(Ordering::Greater)),
+            Operator::Gt => !matches!(max_cmp, Some(Ordering::Less | Ordering::Equal)),
+            Operator::GtEq => !matches!(max_cmp, Some(Ordering::Less)),
+            _ => true,
+        }
+    }
+}
+
+/// Evaluates filters against the zone maps of a single fragment
+struct ZoneMapPruner<'a> {
+    fragment: &'a Fragment,
+    schema: &'a Schema,
+}
+
+impl ZoneMapPruner<'_> {
+    /// Returns false only if the zone maps prove no row can satisfy `expr`
+    fn may_match(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+                Operator::And => self.may_match(left) && self.may_match(right),
+                Operator::Or => self.may_match(left) || self.may_match(right),
+                _ => self.comparison_may_match(left, *op, right),
+            },
+            Expr::Between(Between {
+                expr,
+                negated: false,
+                low,
+                high,
+            }) => {
+                self.comparison_may_match(expr, Operator::GtEq, low)
+                    && self.comparison_may_match(expr, Operator::LtEq, high)
+            }
+            Expr::InList(InList {
+                expr,
+                list,
+                negated: false,
+            }) => list
+                .iter()
+                .any(|item| self.comparison_may_match(expr, Operator::Eq, item)),
+            Expr::IsNull(expr) => {
+                let has_nulls = match self.column_zone_map(expr) {
+                    Some((zone_map, _)) => zone_map.null_count > 0,
+                    None => true,
+                };
+                // A partition of a field is null exactly if its rows are null
+                has_nulls && self.partition_values(expr).all(partition_is_null)
+            }
+            Expr::IsNotNull(expr) => {
+                !self.all_null(expr) && !self.partition_values(expr).any(partition_is_null)
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether the zone map of a column reference proves it only holds nulls.
+    fn all_null(&self, expr: &Expr) -> bool {
+        self.column_zone_map(expr).is_some_and(|(zone_map, _)| {
+            self.fragment.physical_rows == Some(zone_map.null_count as usize)
+        })
+    }
+
+    fn comparison_may_match(&self, left: &Expr, op: Operator, right: &Expr) -> bool {
+        if !matches!(
+            op,
+            Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq
+        ) {
+            return true;
+        }
+        let (column, op, literal) = match (left, right) {
+            (column, Expr::Literal(literal, _)) => (column, op, literal),
+            (Expr::Literal(literal, _), column) => {
+                let Some(op) = op.swap() else {
+                    return true;
+                };
+                (column, op, literal)
+            }
+            _ => return true,
+        };
+        // A comparison with a null is never true.
+        if !literal.is_null() && self.all_null(column) {
+            return false;
+        }
+        let Some(field) = self.column_field(column) else {
+            return true;
+        };
+        let data_type = field.data_type();
+        let Some(literal) = coerce_literal(literal, &data_type) else {
+            return true;
+        };
+        self.partition_values(column)
+            .all(|value| partition_may_satisfy(value, &data_type, op, &literal))
+            && self
+                .column_zone(column)
+                .is_none_or(|(zone, _)| zone.may_satisfy(op, &literal))
+    }
+
+    /// The field of a column reference
+    fn column_field(&self, expr: &Expr) -> Option<&Field> {
+        match expr {
+            Expr::Column(column) => self.schema.field(&column.name),
+            _ => self.schema.field(&nested_column_path(expr)?),
+        }
+    }
+
+    /// The partition values of a column reference in the data files of the fragment
+    fn partition_values(&self, expr: &Expr) -> impl Iterator<Item = &PartitionValue> {
+        let field_id = self.column_field(expr).map(|field| field.id);
+        self.fragment.files.iter().flat_map(move |file| {
+            field_id
+                .map(|id| file.partition_values(id))
+                .into_iter()
+                .flatten()
+        })
+    }
+
+    /// The zone map of a column reference along with the column's field
+    fn column_zone_map(&self, expr: &Expr) -> Option<(&ColumnZoneMap, &Field)> {
+        let field = self.column_field(expr)?;
+        let zone_map = self
+            .fragment
+            .files
+            .iter()
+            .find_map(|file| file.zone_map(field.id))?;
+        Some((zone_map, field))
+    }
+
+    /// The zone of a column reference along with the column's data type
+    fn column_zone(&self, expr: &Expr) -> Option<(Zone, DataType)> {
+        let (zone_map, field) = self.column_zone_map(expr)?;
+        let data_type = field.data_type();
+        let zone = Zone {
+            min: decode_bound(&zone_map.min, &data_type)?,
+            max: decode_bound(&zone_map.max, &data_type)?,
+        };
+        Some((zone, data_type))
+    }
+}
+
+/// Decodes a zone map bound, `None` if it is missing or of another type
+fn decode_bound(bytes: &Option<Vec<u8>>, data_type: &DataType) -> Option<ScalarValue> {
+    to_scalar_value(&ArrowScalar::decode(bytes.as_deref()?).ok()?, data_type)
+}
+
+/// Converts a scalar of `data_type`, `None` if it is of another type
+fn to_scalar_value(scalar: &ArrowScalar, data_type: &DataType) -> Option<ScalarValue> {
+    if scalar.data_type() != data_type {
+        return None;
+    }
+    ScalarValue::try_from_array(scalar.as_array(), 0).ok()
+}
+
+/// Whether the rows of a partition are null, unknown values are not
+fn partition_is_null(value: &PartitionValue) -> bool {
+    ArrowScalar::decode(&value.value).is_ok_and(|scalar| scalar.is_null())
+}
+
+/// Whether a row of the partition with `value` may satisfy `column op literal`,
+/// where `literal` is not null and has the `data_type` of the column
+fn partition_may_satisfy(
+    value: &PartitionValue,
+    data_type: &DataType,
+    op: Operator,
+    literal: &ScalarValue,
+) -> bool {
+    let Ok(scalar) = ArrowScalar::decode(&value.value) else {
+        return true;
+    };
+    // The rows of a null partition are null, and a comparison with a null is never true.
+    if scalar.is_null() {
+        return false;
+    }
+    match value.transform {
+        PartitionTransform::Identity => match to_scalar_value(&scalar, data_type) {
+            Some(value) => Zone {
+                min: value.clone(),
+                max: value,
+            }
+            .may_satisfy(op, literal),
+            None => true,
+        },
+        PartitionTransform::Hash { buckets } => {
+            if op != Operator::Eq {
+                return true;
+            }
+            let Some(ScalarValue::UInt32(Some(bucket))) =
+                to_scalar_value(&scalar, &DataType::UInt32)
+            else {
+                return true;
+            };
+            let Some(literal) = literal
+                .to_array()
+                .ok()
+                .and_then(|array| ArrowScalar::try_from_array(array).ok())
+            else {
+                return true;
+            };
+            hash_bucket(&literal, buckets)
+                .ok()
+                .is_none_or(|literal_bucket| literal_bucket == bucket)
+        }
+        PartitionTransform::Range { width } => {
+            let (Some(ScalarValue::Int64(Some(index))), Ok(ScalarValue::Int64(Some(literal)))) = (
+                to_scalar_value(&scalar, &DataType::Int64),
+                literal.cast_to(&DataType::Int64),
+            ) else {
+                return true;
+            };
+            let Some((low, high)) = range_bounds(index, width) else {
+                return true;
+            };
+            Zone {
+                min: ScalarValue::Int64(Some(low)),
+                max: ScalarValue::Int64(Some(high)),
+            }
+            .may_satisfy(op, &ScalarValue::Int64(Some(literal)))
+        }
+    }
+}
+
+/// The path of a struct field access such as `get_field(get_field(a, 'b'), 'c')`
+fn nested_column_path(expr: &Expr) -> Option<String> {
+    let mut parts = Vec::new();
+    let mut expr = expr;
+    loop {
+        match expr {
+            Expr::ScalarFunction(func) if func.name() == "get_field" && func.args.len() == 2 => {
+                let Expr::Literal(ScalarValue::Utf8(Some(name)), _) = &func.args[1] else {
+                    return None;
+                };
+                parts.push(name.as_str());
+                expr = &func.args[0];
+            }
+            Expr::Column(column) => {
+                parts.push(column.name.as_str());
+                break;
+            }
+            _ => return None,
+        }
+    }
+    parts.reverse();
+    Some(format_field_path(&parts))
+}
+
+/// Casts `literal` to `data_type`, returning `None` if the cast is lossy.
+///
+/// Comparing against a rounded literal could prune fragments that contain matches.
+fn coerce_literal(literal: &ScalarValue, data_type: &DataType) -> Option<ScalarValue> {
+    if literal.is_null() {
+        return None;
+    }
+    if &literal.data_type() == data_type {
+        return Some(literal.clone());
+    }
+    let cast = literal.cast_to(data_type).ok()?;
+    let round_trip = cast.cast_to(&literal.data_type()).ok()?;
+    (&round_trip == literal).then_some(cast)
+}
+
+/// Returns false only if the zone maps of `fragment` prove that no row in the
+/// fragment can satisfy `filter`.
+///
+/// Fragments without zone maps, and filters that cannot be evaluated against
+/// min/max statistics, always return true.
+pub fn fragment_may_match(fragment: &Fragment, schema: &Schema, filter: &Expr) -> bool {
+    ZoneMapPruner { fragment, schema }.may_match(filter)
+}
+
+/// The statistics of `field` over `fragments`, gathered from their zone maps.
+///
+/// The bounds and the null count are only exact if no fragment has deleted
+/// rows, as a deleted row may hold the minimum, the maximum or a null.  Fields
+/// without zone maps in some fragment have unknown statistics.
+pub fn column_statistics(fragments: &[Fragment], field: &Field) -> ColumnStatistics {
+    let data_type = field.data_type();
+    let mut null_count = 0;
+    let mut bounds: Option<(ScalarValue, ScalarValue)> = None;
+    let mut has_bounds = true;
+    let mut has_deletions = false;
+    for fragment in fragments {
+        let Some(zone_map) = fragment
+            .files
+            .iter()
+            .find_map(|file| file.zone_map(field.id))
+        else {
+            return ColumnStatistics::new_unknown();
+        };
+        null_count += zone_map.null_count as usize;
+        has_deletions |= fragment
+            .deletion_file
+            .as_ref()
+            .is_some_and(|file| file.num_deleted_rows != Some(0));
+        match (
+            decode_bound(&zone_map.min, &data_type),
+            decode_bound(&zone_map.max, &data_type),
+        ) {
+            (Some(min), Some(max)) => {
+                bounds = Some(match bounds {
+                    None => (min, max),
+                    Some((lower, upper)) => (
+                        if matches!(min.partial_cmp(&lower), Some(Ordering::Less)) {
+                            min
+                        } else {
+                            lower
+                        },
+                        if matches!(max.partial_cmp(&upper), Some(Ordering::Greater)) {
+                            max
+                        } else {
+                            upper
+                        },
+                    ),
+                });
+            }
+            // A fragment of only nulls has no bounds, anything else (e.g. NaNs) makes
+            // the bounds of the column unknown
+            _ => has_bounds &= fragment.physical_rows == Some(zone_map.null_count as usize),
+        }
+    }
+    let mut statistics = ColumnStatistics::new_unknown();
+    statistics.null_count = Precision::Exact(null_count);
+    if let (true, Some((min, max))) = (has_bounds, bounds) {
+        statistics.min_value = Precision::Exact(min);
+        statistics.max_value = Precision::Exact(max);
+    }
+    if has_deletions {
+        statistics = statistics.to_inexact();
+    }
+    statistics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{Float32Array, Float64Array, Int32Array, StringArray, StructArray};
+    use arrow_schema::{Field as ArrowField, Fields, Schema as ArrowSchema};
+    use datafusion::functions::core::expr_fn::get_field;
+    use datafusion::prelude::{col, lit};
+    use lance_table::format::{
+        DataFile, DeletionFile, DeletionFileType, PartitionField, PartitionSpec,
+    };
+
+    use crate::dataset::fragment::partition::PartitionRouter;
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema::try_from(&ArrowSchema::new(vec![
+            ArrowField::new("i", DataType::Int32, true),
+            ArrowField::new("f", DataType::Float32, true),
+            ArrowField::new("s", DataType::Utf8, true),
+        ]))
+        .unwrap()
+    }
+
+    fn fragment_with(schema: &Schema, batch: &RecordBatch) -> Fragment {
+        let mut collector = ZoneMapCollector::new(schema);
+        collector.update(batch).unwrap();
+        let mut fragment = Fragment::new(0);
+        fragment.files.push(
+            DataFile::new("a.lance", vec![0, 1, 2], vec![0, 1, 2], 2, 1, None, None)
+                .with_zone_maps(collector.finish().unwrap()),
+        );
+        fragment
+    }
+
+    #[test]
+    fn test_collect_scalar_columns() {
+        let schema = schema();
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::from(&schema)),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(5), None, Some(10)])),
+                Arc::new(Float32Array::from(vec![1.0, f32::NAN, 2.0])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )
+        .unwrap();
+        let fragment = fragment_with(&schema, &batch);
+        let zone_maps = &fragment.files[0].zone_maps;
+
+        assert_eq!(zone_maps.len(), 3);
+        assert_eq!(zone_maps[0].field_id, 0);
+        assert_eq!(zone_maps[0].null_count, 1);
+        let min = ArrowScalar::decode(zone_maps[0].min.as_ref().unwrap()).unwrap();
+        assert_eq!(min, ArrowScalar::from(5i32));
+        let max = ArrowScalar::decode(zone_maps[0].max.as_ref().unwrap()).unwrap();
+        assert_eq!(max, ArrowScalar::from(10i32));
+
+        // NaN values make the bounds unusable
+        assert_eq!(zone_maps[1].field_id, 1);
+        assert!(zone_maps[1].min.is_none());
+        assert!(zone_maps[1].max.is_none());
+
+        assert_eq!(zone_maps[2].field_id, 2);
+        let max = ArrowScalar::decode(zone_maps[2].max.as_ref().unwrap()).unwrap();
+        assert_eq!(max, ArrowScalar::from("c"));
+
+        // Long strings have no bounds
+        let long = "x".repeat(MAX_ENCODED_BOUND_LEN);
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::from(&schema)),
+            vec![
+                Arc::new(Int32Array::from(vec![1])),
+                Arc::new(Float32Array::from(vec![1.0])),
+                Arc::new(StringArray::from(vec![long.as_str()])),
+            ],
+        )
+        .unwrap();
+        let fragment = fragment_with(&schema, &batch);
+        assert!(fragment.files[0].zone_maps[2].max.is_none());
+    }
+
+    #[test]
+    fn test_fragment_may_match() {
+        let schema = schema();
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::from(&schema)),
+            vec![
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+                Arc::new(Float32Array::from(vec![1.0, 2.0, 3.0])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )
+        .unwrap();
+        let fragment = fragment_with(&schema, &batch);
+        let may_match = |expr: Expr| fragment_may_match(&fragment, &schema, &expr);
+
+        assert!(may_match(col("i").eq(lit(20i32))));
+        assert!(!may_match(col("i").eq(lit(40i32))));
+        assert!(!may_match(col("i").lt(lit(10i32))));
+        assert!(may_match(col("i").lt_eq(lit(10i32))));
+        assert!(!may_match(col("i").gt(lit(30i32))));
+        assert!(may_match(col("i").gt_eq(lit(30i32))));
+        assert!(!may_match(lit(5i32).gt(col("i"))));
+        assert!(may_match(col("i").not_eq(lit(10i32))));
+        assert!(!may_match(col("i").between(lit(31i32), lit(40i32))));
+        assert!(may_match(col("i").between(lit(0i32), lit(10i32))));
+        assert!(!may_match(
+            col("i").in_list(vec![lit(1i32), lit(50i32)], false)
+        ));
+        assert!(may_match(
+            col("i").in_list(vec![lit(1i32), lit(50i32)], true)
+        ));
+        assert!(!may_match(col("i").is_null()));
+        assert!(!may_match(col("f").gt(lit(3.5f32))));
+
+        // Boolean combinations
+        assert!(!may_match(
+            col("i").gt(lit(100i32)).and(col("f").lt(lit(2.0f32)))
+        ));
+        assert!(may_match(
+            col("i").gt(lit(100i32)).or(col("f").lt(lit(2.0f32)))
+        ));
+        assert!(!may_match(
+            col("i").gt(lit(100i32)).or(col("f").lt(lit(0.0f32)))
+        ));
+
+        // Lossless literal casts are used, lossy ones are not
+        assert!(!may_match(col("i").gt(lit(30i64))));
+        assert!(may_match(col("i").lt(lit(10.5f64))));
+
+        // Strings
+        assert!(may_match(col("s").eq(lit("b"))));
+        assert!(!may_match(col("s").gt(lit("c"))));
+
+        // Unsupported expressions are never pruned
+        assert!(may_match(col("i").not_eq(col("i"))));
+        assert!(may_match(col("i").eq(lit(ScalarValue::Int32(None)))));
+    }
+
+    #[test]
+    fn test_all_null_column() {
+        let schema = schema();
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::from(&schema)),
+            vec![
+                Arc::new(Int32Array::from(vec![None, None])),
+                Arc::new(Float32Array::from(vec![1.0, 2.0])),
+                Arc::new(StringArray::from(vec![Some("a"), None])),
+            ],
+        )
+        .unwrap();
+        let mut fragment = fragment_with(&schema, &batch);
+        fragment.physical_rows = Some(2);
+        let may_match = |expr: Expr| fragment_may_match(&fragment, &schema, &expr);
+
+        // No comparison with a null is true
+        assert!(!may_match(col("i").eq(lit(1i32))));
+        assert!(!may_match(col("i").not_eq(lit(1i32))));
+        assert!(!may_match(col("i").is_not_null()));
+        assert!(may_match(col("i").is_null()));
+        assert!(may_match(Expr::BinaryExpr(BinaryExpr::new(
+            Box::new(col("i")),
+            Operator::IsDistinctFrom,
+            Box::new(lit(1i32)),
+        ))));
+
+        assert!(may_match(col("s").is_not_null()));
+        assert!(may_match(col("s").is_null()));
+    }
+
+    /// A fragment with the partition values of `batch`, which must hold a single
+    /// partition, and without zone maps
+    fn partitioned_fragment(
+        schema: &Schema,
+        batch: &RecordBatch,
+        spec: &PartitionSpec,
+    ) -> Fragment {
+        let router = PartitionRouter::try_new(spec, schema).unwrap().unwrap();
+        let mut parts = router.split(batch).unwrap();
+        assert_eq!(parts.len(), 1);
+        let (values, _) = parts.pop().unwrap();
+        let mut fragment = Fragment::new(0);
+        fragment.physical_rows = Some(batch.num_rows());
+        fragment.files.push(
+            DataFile::new("a.lance", vec![0, 1, 2], vec![0, 1, 2], 2, 1, None, None)
+                .with_partition_values(values),
+        );
+        fragment
+    }
+
+    #[test]
+    fn test_partition_pruning() {
+        let schema = schema();
+        let batch = |s: Option<&str>| {
+            RecordBatch::try_new(
+                Arc::new(ArrowSchema::from(&schema)),
+                vec![
+                    Arc::new(Int32Array::from(vec![15, 17])),
+                    Arc::new(Float32Array::from(vec![1.0, 2.0])),
+                    Arc::new(StringArray::from(vec![s, s])),
+                ],
+            )
+            .unwrap()
+        };
+        let spec = |transforms: Vec<(i32, PartitionTransform)>| {
+            PartitionSpec::try_new(
+                transforms
+                    .into_iter()
+                    .map(|(field_id, transform)| PartitionField {
+                        field_id,
+                        transform,
+                    })
+                    .collect(),
+            )
+            .unwrap()
+        };
+
+        let fragment = partitioned_fragment(
+            &schema,
+            &batch(Some("eu")),
+            &spec(vec![
+                (2, PartitionTransform::Identity),
+                (0, PartitionTransform::Range { width: 10 }),
+            ]),
+        );
+        let may_match = |expr: Expr| fragment_may_match(&fragment, &schema, &expr);
+        assert!(may_match(col("s").eq(lit("eu"))));
+        assert!(!may_match(col("s").eq(lit("us"))));
+        assert!(!may_match(col("s").lt(lit("eu"))));
+        assert!(!may_match(col("s").is_null()));
+        // The range holds 10 to 19, without zone maps values in between may match
+        assert!(may_match(col("i").eq(lit(12i32))));
+        assert!(!may_match(col("i").eq(lit(25i32))));
+        assert!(!may_match(col("i").lt(lit(10i32))));
+        assert!(may_match(col("i").gt_eq(lit(19i32))));
+        assert!(!may_match(col("i").gt(lit(19i32))));
+        assert!(!may_match(
+            col("i").in_list(vec![lit(1i32), lit(20i32)], false)
+        ));
+
+        let hashed = spec(vec![(2, PartitionTransform::Hash { buckets: 4 })]);
+        let fragment = partitioned_fragment(&schema, &batch(Some("eu")), &hashed);
+        let may_match = |expr: Expr| fragment_may_match(&fragment, &schema, &expr);
+        assert!(may_match(col("s").eq(lit("eu"))));
+        assert!(may_match(col("s").lt(lit("a"))));
+        let eu_bucket = hash_bucket(&ArrowScalar::from("eu"), 4).unwrap();
+        let other = ["a", "b", "c", "d", "e", "f", "g", "h"]
+            .into_iter()
+            .find(|s| hash_bucket(&ArrowScalar::from(*s), 4).unwrap() != eu_bucket)
+            .unwrap();
+        assert!(!may_match(col("s").eq(lit(other))));
+
+        // The partition of null values only holds nulls
+        let fragment = partitioned_fragment(&schema, &batch(None), &hashed);
+        let may_match = |expr: Expr| fragment_may_match(&fragment, &schema, &expr);
+        assert!(!may_match(col("s").eq(lit("eu"))));
+        assert!(!may_match(col("s").is_not_null()));
+        assert!(may_match(col("s").is_null()));
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        let bbox_fields = Fields::from(vec![
+            ArrowField::new("xmin", DataType::Float64, false),
+            ArrowField::new("xmax", DataType::Float64, false),
+        ]);
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new(
+            "bbox",
+            DataType::Struct(bbox_fields.clone()),
+            true,
+        )]);
+        let schema = Schema::try_from(&arrow_schema).unwrap();
+        // The values under the null struct must not widen the bounds
+        let bbox = StructArray::new(
+            bbox_fields,
+            vec![
+                Arc::new(Float64Array::from(vec![1.0, 2.0, -100.0])),
+                Arc::new(Float64Array::from(vec![3.0, 4.0, 100.0])),
+            ],
+            Some(NullBuffer::from(vec![true, true, false])),
+        );
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema), vec![Arc::new(bbox)]).unwrap();
+        let fragment = fragment_with(&schema, &batch);
+
+        let zone_maps = &fragment.files[0].zone_maps;
+        assert_eq!(zone_maps.len(), 2);
+        assert_eq!(zone_maps[0].null_count, 1);
+        let min = ArrowScalar::decode(zone_maps[0].min.as_ref().unwrap()).unwrap();
+        assert_eq!(min, ArrowScalar::from(1.0f64));
+
+        let may_match = |expr: Expr| fragment_may_match(&fragment, &schema, &expr);
+        let xmin = || get_field(col("bbox"), "xmin");
+        let xmax = || get_field(col("bbox"), "xmax");
+        assert!(may_match(xmin().lt_eq(lit(1.5f64))));
+        assert!(!may_match(xmin().lt_eq(lit(0.5f64))));
+        assert!(!may_match(xmax().gt_eq(lit(5.0f64))));
+        assert!(!may_match(
+            xmin().lt_eq(lit(10.0f64)).and(xmax().gt_eq(lit(5.0f64)))
+        ));
+    }
+
+    #[test]
+    fn test_tombstoned_field_not_pruned() {
+        let schema = schema();
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::from(&schema)),
+            vec![
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+                Arc::new(Float32Array::from(vec![1.0, 2.0, 3.0])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )
+        .unwrap();
+        let mut fragment = fragment_with(&schema, &batch);
+        // Field 0 was rewritten into a file without statistics
+        fragment.files[0].fields = Arc::from([-2, 1, 2]);
+        fragment
+            .files
+            .push(DataFile::new("b.lance", vec![0], vec![0], 2, 1, None, None));
+
+        assert!(fragment_may_match(
+            &fragment,
+            &schema,
+            &col("i").eq(lit(40i32))
+        ));
+    }
+
+    #[test]
+    fn test_column_statistics() {
+        let schema = schema();
+        let fragment = |i: Vec<Option<i32>>, f: Vec<f32>| {
+            let num_rows = i.len();
+            let batch = RecordBatch::try_new(
+                Arc::new(ArrowSchema::from(&schema)),
+                vec![
+                    Arc::new(Int32Array::from(i)),
+                    Arc::new(Float32Array::from(f)),
+                    Arc::new(StringArray::from(vec!["a"; num_rows])),
+                ],
+            )
+            .unwrap();
+            let mut fragment = fragment_with(&schema, &batch);
+            fragment.physical_rows = Some(num_rows);
+            fragment
+        };
+        let mut fragments = vec![
+            fragment(vec![Some(10), None, Some(30)], vec![1.0, 2.0, 3.0]),
+            fragment(vec![Some(-5), Some(20)], vec![f32::NAN, 0.0]),
+            fragment(vec![None], vec![4.0]),
+        ];
+        let field = |name: &str| schema.field(name).unwrap();
+
+        let statistics = column_statistics(&fragments, field("i"));
+        assert_eq!(statistics.null_count, Precision::Exact(2));
+        assert_eq!(
+            statistics.min_value,
+            Precision::Exact(ScalarValue::from(-5i32))
+        );
+        assert_eq!(
+            statistics.max_value,
+            Precision::Exact(ScalarValue::from(30i32))
+        );
+
+        // NaN values leave the bounds unknown
+        let statistics = column_statistics(&fragments, field("f"));
+        assert_eq!(statistics.null_count, Precision::Exact(0));
+        assert_eq!(statistics.min_value, Precision::Absent);
+        assert_eq!(statistics.max_value, Precision::Absent);
+
+        // Columns without zone maps have no statistics
+        let statistics = column_statistics(&fragments, field("s"));
+        assert_eq!(statistics.null_count, Precision::Absent);
+
+        // Deleted rows may hold the bounds
+        fragments[0].deletion_file = Some(DeletionFile {
+            read_version: 1,
+            id: 1,
+            file_type: DeletionFileType::Array,
+            num_deleted_rows: Some(1),
+            base_id: None,
+        });
+        let statistics = column_statistics(&fragments, field("i"));
+        assert_eq!(statistics.null_count, Precision::Inexact(2));
+        assert_eq!(
+            statistics.min_value,
+            Precision::Inexact(ScalarValue::from(-5i32))
+        );
+        assert_eq!(
+            statistics.max_value,
+            Precision::Inexact(ScalarValue::from(30i32))
+        );
+    }
+}
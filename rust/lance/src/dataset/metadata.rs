@@ -6,10 +6,20 @@ use std::collections::HashMap;
 use crate::dataset::transaction::{Operation, Transaction, UpdateMap, UpdateMapEntry};
 
 use super::Dataset;
+use super::properties::TableProperties;
 use crate::Result;
 use futures::future::BoxFuture;
 use lance_core::datatypes::FieldRef;
 use lance_core::datatypes::Schema;
+use lance_core::datatypes::WellKnownMetadata;
+
+fn validate_well_known_entries(entries: &[UpdateMapEntry]) -> Result<()> {
+    WellKnownMetadata::validate_entries(
+        entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.value.as_deref())),
+    )
+}
 
 /// Execute a metadata update operation on a dataset.
 /// This is moved from Dataset::update_op to keep metadata logic in this module.
@@ -72,6 +82,16 @@ impl<'a> std::future::IntoFuture for UpdateMetadataBuilder<'a> {
 
     fn into_future(self) -> Self::IntoFuture {
         Box::pin(async move {
+            if matches!(self.metadata_type, MetadataType::SchemaMetadata) {
+                validate_well_known_entries(&self.values)?;
+            }
+            if matches!(self.metadata_type, MetadataType::Config) {
+                TableProperties::validate_entries(
+                    self.values
+                        .iter()
+                        .map(|entry| (entry.key.as_str(), entry.value.as_deref())),
+                )?;
+            }
             let update_map = Self::create_update_map(self.values, self.replace);
 
             let operation = match self.metadata_type {
@@ -130,8 +150,10 @@ impl<'a> UpdateFieldMetadataBuilder<'a> {
         replace: bool,
     ) -> Result<Self> {
         let field_id = field.into().into_id(self.dataset.schema())?;
+        let update_entries = values.into_iter().map(Into::into).collect::<Vec<_>>();
+        validate_well_known_entries(&update_entries)?;
         let values = UpdateMap {
-            update_entries: values.into_iter().map(Into::into).collect(),
+            update_entries,
             replace,
         };
         self.field_metadata_updates.insert(field_id, values);
@@ -152,6 +174,16 @@ impl<'a> UpdateFieldMetadataBuilder<'a> {
     ) -> Result<Self> {
         self.apply(field, values, true)
     }
+
+    /// Set the well-known entries of the field metadata that are present in
+    /// `metadata`, other entries are left untouched.
+    pub fn update_well_known<'b>(
+        self,
+        field: impl Into<FieldRef<'b>> + 'b,
+        metadata: &WellKnownMetadata,
+    ) -> Result<Self> {
+        self.apply(field, metadata.to_metadata(), false)
+    }
 }
 
 impl<'a> std::future::IntoFuture for UpdateFieldMetadataBuilder<'a> {
@@ -240,6 +272,45 @@ mod tests {
         assert!(dataset.config().is_empty());
     }
 
+    #[tokio::test]
+    async fn test_update_config_validates_properties() {
+        use crate::dataset::properties::SCANNER_BATCH_SIZE_KEY;
+        use futures::TryStreamExt;
+
+        let data = gen_batch()
+            .col("i", array::step::<Int32Type>())
+            .into_reader_rows(RowCount::from(100), BatchCount::from(1));
+        let mut dataset = Dataset::write(data, "memory://", None).await.unwrap();
+
+        let err = dataset
+            .update_config([("lance.auto_cleanup.interval", "often")])
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("lance.auto_cleanup.interval"),
+            "{err}"
+        );
+        assert_eq!(dataset.version().version, 1);
+
+        dataset
+            .update_config([(SCANNER_BATCH_SIZE_KEY, "7")])
+            .await
+            .unwrap();
+        assert_eq!(
+            dataset.table_properties().unwrap().scanner.batch_size,
+            Some(7)
+        );
+        let batches = dataset
+            .scan()
+            .try_into_stream()
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(batches[0].num_rows(), 7);
+    }
+
     #[tokio::test]
     async fn test_update_table_metadata() {
         let data = gen_batch()
@@ -877,4 +948,61 @@ mod tests {
             assert!(dataset.schema().unenforced_clustering_key().is_empty());
         }
     }
+
+    #[tokio::test]
+    async fn test_well_known_field_metadata() {
+        use lance_core::datatypes::{LANCE_DESCRIPTION_KEY, LANCE_SEMANTIC_TYPE_KEY, SemanticType};
+
+        let mut dataset = test_dataset_nested().await;
+        assert!(
+            dataset
+                .well_known_field_metadata("name")
+                .unwrap()
+                .is_empty()
+        );
+
+        let metadata = WellKnownMetadata {
+            description: Some("The user name".to_string()),
+            semantic_type: Some(SemanticType::Text),
+            ..Default::default()
+        };
+        dataset
+            .update_field_metadata()
+            .update("name", [("other", "value")])
+            .unwrap()
+            .update_well_known("nested.sub_field", &metadata)
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(
+            dataset
+                .well_known_field_metadata("nested.sub_field")
+                .unwrap(),
+            metadata
+        );
+
+        // Malformed well-known entries are rejected before committing
+        let version = dataset.version().version;
+        let err = dataset
+            .update_field_metadata()
+            .update("name", [(LANCE_SEMANTIC_TYPE_KEY, "not-a-type")])
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidInput { .. }), "{err}");
+        let err = dataset
+            .update_schema_metadata([(LANCE_SEMANTIC_TYPE_KEY, "not-a-type")])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidInput { .. }), "{err}");
+        assert_eq!(dataset.version().version, version);
+
+        dataset
+            .update_schema_metadata([(LANCE_DESCRIPTION_KEY, "Users")])
+            .await
+            .unwrap();
+        assert_eq!(
+            dataset.schema().well_known_metadata().unwrap().description,
+            Some("Users".to_string())
+        );
+        assert!(dataset.well_known_field_metadata("missing").is_err());
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use lance_table::format::Fragment;
@@ -69,6 +70,42 @@ impl std::fmt::Debug for WriteProgressFn {
     }
 }
 
+/// Summary reported once per operation to the callback set via
+/// [`InsertBuilder::summary`](crate::dataset::InsertBuilder::summary).
+#[derive(Debug, Clone, Default)]
+pub struct WriteSummary {
+    /// Totals for the data files written by the operation.
+    pub stats: WriteStats,
+    /// Wall time spent writing data files.
+    pub write_time: Duration,
+    /// Wall time spent committing the transaction.  `None` if the operation
+    /// only wrote data files and left the commit to the caller.
+    pub commit_time: Option<Duration>,
+}
+
+/// An opaque wrapper around a write-summary closure.
+///
+/// Construct via [`InsertBuilder::summary`](crate::dataset::InsertBuilder::summary)
+/// or directly with [`WriteSummaryFn::new`].
+#[derive(Clone)]
+pub struct WriteSummaryFn(Arc<dyn Fn(&WriteSummary) + Send + Sync>);
+
+impl WriteSummaryFn {
+    pub fn new(f: impl Fn(&WriteSummary) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, summary: &WriteSummary) {
+        (self.0)(summary);
+    }
+}
+
+impl std::fmt::Debug for WriteSummaryFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteSummaryFn").finish_non_exhaustive()
+    }
+}
+
 /// By default, Progress tracker is Noop.
 #[derive(Debug, Clone, Default)]
 pub struct NoopFragmentWriteProgress {}
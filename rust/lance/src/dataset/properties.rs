@@ -0,0 +1,393 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Typed access to the dataset config.
+//!
+//! The manifest config is a plain string map and the features reading it
+//! usually fall back to their defaults when a key is misspelled or holds an
+//! invalid value.  [`TableProperties`] parses all keys Lance reads, which lets
+//! [`Dataset::update_config`](crate::Dataset::update_config) reject invalid
+//! values and warn about unknown `lance.*` keys before they are committed.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use humantime::parse_duration;
+use lance_table::format::{
+    DELTA_MANIFEST_MAX_DEPTH_KEY, ENCRYPTION_SPEC_KEY, EXTERNAL_INDEX_SECTION_KEY, EncryptionSpec,
+    PARTITION_SPEC_KEY, PartitionSpec,
+};
+use tracing::warn;
+
+use super::archive::{VersionArchiveConfig, VersionArchiveRollup};
+use super::optimize::{COMPACTION_CONFIG_PREFIX, CompactionOptions};
+use crate::{Error, Result};
+
+/// Prefix of the config keys reserved for Lance.
+pub const LANCE_CONFIG_PREFIX: &str = "lance.";
+
+pub const AUTO_CLEANUP_INTERVAL_KEY: &str = "lance.auto_cleanup.interval";
+pub const AUTO_CLEANUP_OLDER_THAN_KEY: &str = "lance.auto_cleanup.older_than";
+pub const AUTO_CLEANUP_RETAIN_VERSIONS_KEY: &str = "lance.auto_cleanup.retain_versions";
+pub const AUTO_CLEANUP_REFERENCED_BRANCH_KEY: &str = "lance.auto_cleanup.referenced_branch";
+pub const AUTO_CLEANUP_DELETE_RATE_LIMIT_KEY: &str = "lance.auto_cleanup.delete_rate_limit";
+
+pub const VERSION_ARCHIVE_ENABLED_KEY: &str = "lance.version_archive.enabled";
+pub const VERSION_ARCHIVE_MAX_ENTRIES_KEY: &str = "lance.version_archive.max_entries";
+pub const VERSION_ARCHIVE_MAX_ARCHIVE_FILES_KEY: &str = "lance.version_archive.max_archive_files";
+pub const VERSION_ARCHIVE_ROLLUP_AFTER_KEY: &str = "lance.version_archive.rollup_after";
+pub const VERSION_ARCHIVE_ROLLUP_INTERVAL_KEY: &str = "lance.version_archive.rollup_interval";
+
+/// The default batch size of scans, see [`Scanner::batch_size`](super::scanner::Scanner::batch_size).
+pub const SCANNER_BATCH_SIZE_KEY: &str = "lance.scanner.batch_size";
+/// The default fragment readahead of scans, see
+/// [`Scanner::fragment_readahead`](super::scanner::Scanner::fragment_readahead).
+pub const SCANNER_FRAGMENT_READAHEAD_KEY: &str = "lance.scanner.fragment_readahead";
+
+/// The fields of [`CompactionOptions`] that can be set in the config.
+const COMPACTION_FIELDS: &[&str] = &[
+    "target_rows_per_fragment",
+    "max_rows_per_group",
+    "max_bytes_per_file",
+    "materialize_deletions",
+    "materialize_deletions_threshold",
+    "defer_index_remap",
+    "batch_size",
+    "compaction_mode",
+    "binary_copy_read_batch_bytes",
+    "max_source_fragments",
+];
+
+const KNOWN_KEYS: &[&str] = &[
+    AUTO_CLEANUP_INTERVAL_KEY,
+    AUTO_CLEANUP_OLDER_THAN_KEY,
+    AUTO_CLEANUP_RETAIN_VERSIONS_KEY,
+    AUTO_CLEANUP_REFERENCED_BRANCH_KEY,
+    AUTO_CLEANUP_DELETE_RATE_LIMIT_KEY,
+    VERSION_ARCHIVE_ENABLED_KEY,
+    VERSION_ARCHIVE_MAX_ENTRIES_KEY,
+    VERSION_ARCHIVE_MAX_ARCHIVE_FILES_KEY,
+    VERSION_ARCHIVE_ROLLUP_AFTER_KEY,
+    VERSION_ARCHIVE_ROLLUP_INTERVAL_KEY,
+    DELTA_MANIFEST_MAX_DEPTH_KEY,
+    EXTERNAL_INDEX_SECTION_KEY,
+    PARTITION_SPEC_KEY,
+    ENCRYPTION_SPEC_KEY,
+    SCANNER_BATCH_SIZE_KEY,
+    SCANNER_FRAGMENT_READAHEAD_KEY,
+];
+
+/// Whether `key` is a `lance.*` key that no Lance feature reads.
+pub fn is_unknown_key(key: &str) -> bool {
+    if !key.starts_with(LANCE_CONFIG_PREFIX) || KNOWN_KEYS.contains(&key) {
+        return false;
+    }
+    key.strip_prefix(COMPACTION_CONFIG_PREFIX)
+        .is_none_or(|field| !COMPACTION_FIELDS.contains(&field))
+}
+
+fn parse_value<T>(config: &HashMap<String, String>, key: &str, expected: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    config
+        .get(key)
+        .map(|value| {
+            value.parse().map_err(|err| {
+                Error::invalid_input(format!(
+                    "Invalid value for {key}: '{value}' (expected {expected}): {err}"
+                ))
+            })
+        })
+        .transpose()
+}
+
+fn parse_positive<T>(config: &HashMap<String, String>, key: &str) -> Result<Option<T>>
+where
+    T: FromStr + Default + PartialEq,
+    T::Err: Display,
+{
+    let value = parse_value::<T>(config, key, "a positive integer")?;
+    if value.as_ref().is_some_and(|value| *value == T::default()) {
+        return Err(Error::invalid_input(format!(
+            "Invalid value for {key}: '0' (expected a positive integer)"
+        )));
+    }
+    Ok(value)
+}
+
+fn parse_duration_value(config: &HashMap<String, String>, key: &str) -> Result<Option<Duration>> {
+    config
+        .get(key)
+        .map(|value| {
+            parse_duration(value).map_err(|err| {
+                Error::invalid_input(format!(
+                    "Invalid value for {key}: '{value}' (expected a duration such as '7d'): {err}"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// The `lance.auto_cleanup.*` keys, see [`AutoCleanupParams`](super::AutoCleanupParams).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoCleanupProperties {
+    /// Cleanup runs every `interval` versions, on every commit if zero
+    pub interval: u64,
+    pub older_than: Option<Duration>,
+    pub retain_versions: Option<usize>,
+    pub referenced_branch: Option<bool>,
+    pub delete_rate_limit: Option<u64>,
+}
+
+impl AutoCleanupProperties {
+    /// The auto cleanup settings, `None` unless an interval is set.
+    pub fn try_from_config(config: &HashMap<String, String>) -> Result<Option<Self>> {
+        let older_than = parse_duration_value(config, AUTO_CLEANUP_OLDER_THAN_KEY)?;
+        let retain_versions = parse_value(
+            config,
+            AUTO_CLEANUP_RETAIN_VERSIONS_KEY,
+            "a non-negative integer",
+        )?;
+        let referenced_branch = parse_value(
+            config,
+            AUTO_CLEANUP_REFERENCED_BRANCH_KEY,
+            "'true' or 'false'",
+        )?;
+        let delete_rate_limit = parse_positive(config, AUTO_CLEANUP_DELETE_RATE_LIMIT_KEY)?;
+        let Some(interval) =
+            parse_value(config, AUTO_CLEANUP_INTERVAL_KEY, "a non-negative integer")?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            interval,
+            older_than,
+            retain_versions,
+            referenced_branch,
+            delete_rate_limit,
+        }))
+    }
+}
+
+/// The `lance.scanner.*` keys, defaults for scans that do not set them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScannerProperties {
+    pub batch_size: Option<usize>,
+    pub fragment_readahead: Option<usize>,
+}
+
+impl ScannerProperties {
+    pub fn try_from_config(config: &HashMap<String, String>) -> Result<Self> {
+        Ok(Self {
+            batch_size: parse_positive(config, SCANNER_BATCH_SIZE_KEY)?,
+            fragment_readahead: parse_positive(config, SCANNER_FRAGMENT_READAHEAD_KEY)?,
+        })
+    }
+}
+
+/// The settings stored in the config of a dataset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableProperties {
+    /// `lance.auto_cleanup.*`
+    pub auto_cleanup: Option<AutoCleanupProperties>,
+    /// The defaults for compaction, `lance.compaction.*` applied over
+    /// [`CompactionOptions::default`]
+    pub compaction: CompactionOptions,
+    /// `lance.version_archive.*`
+    pub version_archive: VersionArchiveConfig,
+    /// `lance.delta_manifest.max_depth`
+    pub delta_manifest_max_depth: Option<u32>,
+    /// `lance.index_section.external`
+    pub external_index_section: bool,
+    /// `lance.partition_spec`
+    pub partition_spec: Option<PartitionSpec>,
+    /// `lance.encryption`
+    pub encryption_spec: Option<EncryptionSpec>,
+    /// `lance.scanner.*`
+    pub scanner: ScannerProperties,
+}
+
+impl TableProperties {
+    /// Parses the known keys of `config`, failing on the first invalid value.
+    ///
+    /// Unknown keys are ignored.
+    pub fn try_from_config(config: &HashMap<String, String>) -> Result<Self> {
+        let archive_defaults = VersionArchiveConfig::default();
+        let rollup_interval = parse_duration_value(config, VERSION_ARCHIVE_ROLLUP_INTERVAL_KEY)?;
+        if rollup_interval.is_some_and(|interval| interval.is_zero()) {
+            return Err(Error::invalid_input(format!(
+                "Invalid value for {VERSION_ARCHIVE_ROLLUP_INTERVAL_KEY}: the interval must be positive"
+            )));
+        }
+        let rollup = parse_duration_value(config, VERSION_ARCHIVE_ROLLUP_AFTER_KEY)?.map(|after| {
+            VersionArchiveRollup {
+                after,
+                interval: rollup_interval.unwrap_or(VersionArchiveRollup::default().interval),
+            }
+        });
+        let version_archive = VersionArchiveConfig {
+            enabled: parse_value(config, VERSION_ARCHIVE_ENABLED_KEY, "'true' or 'false'")?
+                .unwrap_or(archive_defaults.enabled),
+            max_entries: parse_positive(config, VERSION_ARCHIVE_MAX_ENTRIES_KEY)?
+                .unwrap_or(archive_defaults.max_entries),
+            max_archive_files: parse_positive(config, VERSION_ARCHIVE_MAX_ARCHIVE_FILES_KEY)?
+                .unwrap_or(archive_defaults.max_archive_files),
+            rollup,
+        };
+        Ok(Self {
+            auto_cleanup: AutoCleanupProperties::try_from_config(config)?,
+            compaction: CompactionOptions::from_dataset_config(config)?,
+            version_archive,
+            delta_manifest_max_depth: parse_value(
+                config,
+                DELTA_MANIFEST_MAX_DEPTH_KEY,
+                "a non-negative integer",
+            )?,
+            external_index_section: parse_value(
+                config,
+                EXTERNAL_INDEX_SECTION_KEY,
+                "'true' or 'false'",
+            )?
+            .unwrap_or(false),
+            partition_spec: PartitionSpec::try_from_config(config)?,
+            encryption_spec: EncryptionSpec::try_from_config(config)?,
+            scanner: ScannerProperties::try_from_config(config)?,
+        })
+    }
+
+    /// The `lance.*` keys of `config` that no Lance feature reads.
+    pub fn unknown_keys(config: &HashMap<String, String>) -> impl Iterator<Item = &str> {
+        config
+            .keys()
+            .map(String::as_str)
+            .filter(|key| is_unknown_key(key))
+    }
+
+    /// Validates the values of a config update, removals are always valid.
+    ///
+    /// Unknown `lance.*` keys are accepted with a warning, as they are likely
+    /// typos or keys of features that were removed.
+    pub fn validate_entries<'a>(
+        entries: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+    ) -> Result<()> {
+        let mut config = HashMap::new();
+        for (key, value) in entries {
+            let Some(value) = value else {
+                continue;
+            };
+            if is_unknown_key(key) {
+                warn!(
+                    "Config key {} is not read by Lance, it may be misspelled or deprecated",
+                    key
+                );
+                continue;
+            }
+            config.insert(key.to_string(), value.to_string());
+        }
+        Self::try_from_config(&config).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_properties() {
+        let properties = TableProperties::try_from_config(&config(&[
+            (AUTO_CLEANUP_INTERVAL_KEY, "10"),
+            (AUTO_CLEANUP_OLDER_THAN_KEY, "7d"),
+            ("lance.compaction.target_rows_per_fragment", "1000"),
+            (VERSION_ARCHIVE_MAX_ENTRIES_KEY, "50"),
+            (VERSION_ARCHIVE_ROLLUP_AFTER_KEY, "30d"),
+            (SCANNER_BATCH_SIZE_KEY, "4096"),
+            ("lance.unknown", "ignored"),
+        ]))
+        .unwrap();
+        let auto_cleanup = properties.auto_cleanup.unwrap();
+        assert_eq!(auto_cleanup.interval, 10);
+        assert_eq!(
+            auto_cleanup.older_than,
+            Some(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(properties.compaction.target_rows_per_fragment, 1000);
+        assert_eq!(properties.version_archive.max_entries, 50);
+        assert!(properties.version_archive.enabled);
+        assert_eq!(
+            properties.version_archive.rollup,
+            Some(VersionArchiveRollup {
+                after: Duration::from_secs(30 * 24 * 60 * 60),
+                interval: Duration::from_secs(24 * 60 * 60),
+            })
+        );
+        assert_eq!(properties.scanner.batch_size, Some(4096));
+        assert_eq!(properties.scanner.fragment_readahead, None);
+        assert_eq!(properties.partition_spec, None);
+
+        let empty = TableProperties::try_from_config(&HashMap::new()).unwrap();
+        assert_eq!(empty.auto_cleanup, None);
+        assert_eq!(empty.compaction, CompactionOptions::default());
+        assert_eq!(empty.version_archive, VersionArchiveConfig::default());
+    }
+
+    #[test]
+    fn test_validate_entries() {
+        for (key, value) in [
+            (AUTO_CLEANUP_INTERVAL_KEY, "often"),
+            (AUTO_CLEANUP_OLDER_THAN_KEY, "a while"),
+            (AUTO_CLEANUP_DELETE_RATE_LIMIT_KEY, "0"),
+            ("lance.compaction.materialize_deletions", "maybe"),
+            (VERSION_ARCHIVE_ENABLED_KEY, "yes"),
+            (VERSION_ARCHIVE_MAX_ARCHIVE_FILES_KEY, "0"),
+            (VERSION_ARCHIVE_ROLLUP_AFTER_KEY, "soon"),
+            (VERSION_ARCHIVE_ROLLUP_INTERVAL_KEY, "0s"),
+            (DELTA_MANIFEST_MAX_DEPTH_KEY, "-1"),
+            (EXTERNAL_INDEX_SECTION_KEY, "on"),
+            (PARTITION_SPEC_KEY, "{}"),
+            (ENCRYPTION_SPEC_KEY, "{\"columns\":[]}"),
+            (SCANNER_FRAGMENT_READAHEAD_KEY, "0"),
+        ] {
+            assert!(
+                TableProperties::validate_entries([(key, Some(value))]).is_err(),
+                "{key}={value}"
+            );
+            // Removing an invalid value is always allowed
+            TableProperties::validate_entries([(key, None)]).unwrap();
+        }
+
+        TableProperties::validate_entries([
+            (SCANNER_BATCH_SIZE_KEY, Some("1024")),
+            ("lance.scaner.batch_size", Some("not validated")),
+            ("user.key", Some("anything")),
+        ])
+        .unwrap();
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let config = config(&[
+            ("lance.scaner.batch_size", "1"),
+            ("lance.compaction.target_rows", "1"),
+            ("lance.compaction.target_rows_per_fragment", "1"),
+            (PARTITION_SPEC_KEY, "{}"),
+            ("user.key", "1"),
+        ]);
+        let mut unknown = TableProperties::unknown_keys(&config).collect::<Vec<_>>();
+        unknown.sort();
+        assert_eq!(
+            unknown,
+            vec!["lance.compaction.target_rows", "lance.scaner.batch_size"]
+        );
+    }
+}
@@ -5,11 +5,13 @@ use super::Dataset;
 use crate::session::caches::{RowIdIndexKey, RowIdSequenceKey};
 use crate::{Error, Result};
 use futures::{Stream, StreamExt, TryFutureExt, TryStreamExt};
+use lance_core::utils::address::RowAddress;
 use lance_core::utils::deletion::DeletionVector;
 use lance_table::{
     format::{Fragment, RowIdMeta},
     rowids::{FragmentRowIdIndex, RowIdIndex, RowIdSequence, read_row_ids},
 };
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Load a row id sequence from the given dataset and fragment.
@@ -129,6 +131,79 @@ async fn load_row_id_index(dataset: &Dataset) -> Result<lance_table::rowids::Row
     Ok(index)
 }
 
+/// Resolve stable row ids to their current row addresses.
+///
+/// The result has one entry per input id, `None` if the row has been deleted
+/// or the id was never assigned.
+pub(super) async fn resolve_row_ids(
+    dataset: &Dataset,
+    row_ids: &[u64],
+) -> Result<Vec<Option<u64>>> {
+    let index = get_row_id_index(dataset).await?.ok_or_else(|| {
+        Error::not_supported("Resolving row ids requires a dataset with stable row ids")
+    })?;
+    Ok(row_ids
+        .iter()
+        .map(|id| index.get(*id).map(u64::from))
+        .collect())
+}
+
+/// Resolve row addresses to the stable row ids stored at them.
+///
+/// The result has one entry per input address, `None` if the address points
+/// at a deleted row or does not exist in the current version.
+pub(super) async fn resolve_row_addresses(
+    dataset: &Dataset,
+    row_addrs: &[u64],
+) -> Result<Vec<Option<u64>>> {
+    if !dataset.manifest.uses_stable_row_ids() {
+        return Err(Error::not_supported(
+            "Resolving row addresses requires a dataset with stable row ids",
+        ));
+    }
+
+    let fragment_ids = row_addrs
+        .iter()
+        .map(|addr| RowAddress::from(*addr).fragment_id() as u64)
+        .collect::<HashSet<_>>();
+    let fragments = dataset
+        .manifest
+        .fragments
+        .iter()
+        .filter(|frag| fragment_ids.contains(&frag.id))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let lookups = futures::stream::iter(fragments)
+        .map(|fragment| async move {
+            let sequence = load_row_id_sequence(dataset, &fragment).await?;
+            let deletion_vector = dataset
+                .get_fragment(fragment.id as usize)
+                .expect("Fragment should exist")
+                .get_deletion_vector()
+                .await?;
+            Ok::<_, Error>((fragment.id as u32, (sequence, deletion_vector)))
+        })
+        .buffer_unordered(dataset.object_store.io_parallelism())
+        .try_collect::<HashMap<_, _>>()
+        .await?;
+
+    Ok(row_addrs
+        .iter()
+        .map(|addr| {
+            let addr = RowAddress::from(*addr);
+            let (sequence, deletion_vector) = lookups.get(&addr.fragment_id())?;
+            if deletion_vector
+                .as_ref()
+                .is_some_and(|dv| dv.contains(addr.row_offset()))
+            {
+                return None;
+            }
+            sequence.get(addr.row_offset() as usize)
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Range;
@@ -670,4 +745,106 @@ mod test {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_resolve_row_ids_across_compaction() {
+        let mut dataset = lance_datagen::gen_batch()
+            .col("i", lance_datagen::array::step::<Int32Type>())
+            .into_ram_dataset_with_params(
+                FragmentCount::from(4),
+                FragmentRowCount::from(10),
+                Some(WriteParams {
+                    max_rows_per_file: 10,
+                    enable_stable_row_ids: true,
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+        delete(&mut dataset, "i % 3 = 0").await;
+
+        let row_ids = scan_rowid_map(&dataset)
+            .await
+            .into_keys()
+            .collect::<Vec<_>>();
+        let addrs_before = dataset.resolve_row_ids(&row_ids).await.unwrap();
+        compact(&mut dataset, 40).await;
+        let addrs_after = dataset.resolve_row_ids(&row_ids).await.unwrap();
+        assert_ne!(addrs_before, addrs_after);
+
+        // Addresses resolve back to the same row ids after compaction.
+        let addrs = addrs_after
+            .iter()
+            .map(|addr| addr.unwrap())
+            .collect::<Vec<_>>();
+        let resolved = dataset.resolve_row_addresses(&addrs).await.unwrap();
+        assert_eq!(
+            resolved,
+            row_ids.iter().map(|id| Some(*id)).collect::<Vec<_>>()
+        );
+
+        // Addresses match what a scan reports.
+        let mut scan = dataset.scan();
+        scan.with_row_id().with_row_address();
+        let batch = scan.try_into_batch().await.unwrap();
+        let scanned = batch[ROW_ID]
+            .as_primitive::<UInt64Type>()
+            .values()
+            .iter()
+            .zip(batch[ROW_ADDR].as_primitive::<UInt64Type>().values().iter())
+            .map(|(id, addr)| (*id, *addr))
+            .collect::<HashMap<_, _>>();
+        for (row_id, addr) in row_ids.iter().zip(addrs.iter()) {
+            assert_eq!(scanned[row_id], *addr);
+        }
+
+        // Deleted rows resolve to None.
+        let row_id_of_one = scan_rowid_map(&dataset)
+            .await
+            .into_iter()
+            .find_map(|(row_id, i)| (i == 1).then_some(row_id))
+            .unwrap();
+        let addr_of_one = dataset.resolve_row_ids(&[row_id_of_one]).await.unwrap()[0].unwrap();
+        delete(&mut dataset, "i = 1").await;
+        assert_eq!(
+            dataset.resolve_row_ids(&[row_id_of_one]).await.unwrap(),
+            vec![None]
+        );
+        assert_eq!(
+            dataset.resolve_row_addresses(&[addr_of_one]).await.unwrap(),
+            vec![None]
+        );
+
+        // Unassigned row ids and unknown addresses resolve to None.
+        let unknown_id = row_ids.iter().max().unwrap() + 100;
+        assert_eq!(
+            dataset.resolve_row_ids(&[unknown_id]).await.unwrap(),
+            vec![None]
+        );
+        let unknown_addr = u64::from(RowAddress::new_from_parts(999, 0));
+        assert_eq!(
+            dataset
+                .resolve_row_addresses(&[unknown_addr])
+                .await
+                .unwrap(),
+            vec![None]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_row_ids_requires_stable_row_ids() {
+        let dataset = lance_datagen::gen_batch()
+            .col("i", lance_datagen::array::step::<Int32Type>())
+            .into_ram_dataset(FragmentCount::from(1), FragmentRowCount::from(10))
+            .await
+            .unwrap();
+        assert!(matches!(
+            dataset.resolve_row_ids(&[0]).await,
+            Err(Error::NotSupported { .. })
+        ));
+        assert!(matches!(
+            dataset.resolve_row_addresses(&[0]).await,
+            Err(Error::NotSupported { .. })
+        ));
+    }
 }
@@ -83,6 +83,8 @@ use tracing::{Span, info_span, instrument};
 use uuid::Uuid;
 
 use super::Dataset;
+use super::properties::ScannerProperties;
+use crate::dataset::embedding::{lookup_function, virtual_embedding_columns};
 use crate::dataset::row_offsets_to_row_addresses;
 use crate::dataset::utils::SchemaAdapter;
 use crate::index::DatasetIndexInternalExt;
@@ -91,6 +93,7 @@ use crate::index::scalar_logical::scalar_index_fragment_bitmap;
 use crate::index::vector::utils::{
     default_distance_type_for, get_vector_dim, get_vector_type, validate_distance_type_for,
 };
+use crate::io::exec::embedding::{EmbeddingExec, VirtualEmbedding};
 use crate::io::exec::filtered_read::{FilteredReadExec, FilteredReadOptions};
 use crate::io::exec::fts::{
     BoostQueryExec, FlatMatchFilterExec, FlatMatchQueryExec, MatchQueryExec, PhraseQueryExec,
@@ -113,8 +116,10 @@ use crate::{Error, Result};
 use crate::{
     datatypes::Schema,
     io::exec::fts::{BoolSlot, BooleanQueryExec, build_boolean_query_children},
+    io::exec::fusion::{HybridFusionExec, RELEVANCE_SCORE_COL},
 };
 
+pub use crate::io::exec::fusion::FusionMethod;
 pub use lance_datafusion::exec::{ExecutionStatsCallback, ExecutionSummaryCounts};
 #[cfg(feature = "substrait")]
 use lance_datafusion::substrait::parse_substrait;
@@ -292,6 +297,7 @@ struct PlannedFilteredScan {
     filter_pushed_down: bool,
 }
 
+#[derive(Clone)]
 pub struct FilterPlan {
     // Query filter plan
     query_filter: Option<QueryFilter>,
@@ -743,6 +749,10 @@ pub struct Scanner {
     /// Optional full text search query
     full_text_query: Option<FullTextSearchQuery>,
 
+    /// If set, a search with both `nearest` and `full_text_query` runs both
+    /// searches and fuses their results (hybrid search).
+    fusion: Option<FusionMethod>,
+
     /// The batch size controls the maximum size of rows to return for each read.
     batch_size: Option<usize>,
 
@@ -759,6 +769,12 @@ pub struct Scanner {
     /// Number of bytes to allow to queue up in the I/O buffer
     io_buffer_size: Option<u64>,
 
+    /// Whether to prefetch the data of upcoming fragments
+    prefetch: bool,
+
+    /// Whether the read may be split into fragment-aligned partitions
+    allow_repartition: bool,
+
     limit: Option<i64>,
     offset: Option<i64>,
 
@@ -1027,6 +1043,8 @@ impl Scanner {
     pub fn new(dataset: Arc<Dataset>) -> Self {
         let projection_plan = ProjectionPlan::full(dataset.clone()).unwrap();
         let file_reader_options = dataset.file_reader_options.clone();
+        // Invalid values are rejected when the config is updated
+        let defaults = ScannerProperties::try_from_config(dataset.config()).unwrap_or_default();
         let mut scanner = Self {
             dataset,
             projection_plan,
@@ -1035,11 +1053,14 @@ impl Scanner {
             materialization_style: MaterializationStyle::Heuristic,
             filter: LanceFilter::default(),
             full_text_query: None,
-            batch_size: None,
+            fusion: None,
+            batch_size: defaults.batch_size,
             batch_size_bytes: None,
             batch_readahead: get_num_compute_intensive_cpus(),
-            fragment_readahead: None,
+            fragment_readahead: defaults.fragment_readahead,
             io_buffer_size: None,
+            prefetch: false,
+            allow_repartition: false,
             limit: None,
             offset: None,
             ordering: None,
@@ -1065,6 +1086,7 @@ impl Scanner {
             target_parallelism: None,
         };
         scanner.apply_blob_handling();
+        scanner.apply_embedding_sources();
         scanner
     }
 
@@ -1077,6 +1099,28 @@ impl Scanner {
         self.projection_plan.physical_projection = projection;
     }
 
+    /// Virtual embedding columns are computed from their source column, so the
+    /// source must be loaded whenever the embedding column is projected.
+    fn apply_embedding_sources(&mut self) {
+        let schema = self.projection_plan.physical_projection.to_bare_schema();
+        let sources = virtual_embedding_columns(&schema)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, spec)| spec.source)
+            .collect::<Vec<_>>();
+        if sources.is_empty() {
+            return;
+        }
+        if let Ok(projection) = self
+            .projection_plan
+            .physical_projection
+            .clone()
+            .union_columns(&sources, OnMissing::Ignore)
+        {
+            self.projection_plan.physical_projection = projection;
+        }
+    }
+
     pub fn blob_handling(&mut self, blob_handling: BlobHandling) -> &mut Self {
         self.blob_handling = blob_handling;
         self.apply_blob_handling();
@@ -1180,6 +1224,7 @@ impl Scanner {
             self.projection_plan.include_row_addr();
         }
         self.apply_blob_handling();
+        self.apply_embedding_sources();
         Ok(self)
     }
 
@@ -1288,6 +1333,28 @@ impl Scanner {
         Ok(self)
     }
 
+    /// Run a hybrid search, fusing the results of [`Self::nearest`] and
+    /// [`Self::full_text_search`] into a single ranked result.
+    ///
+    /// The vector search retrieves its `k` nearest neighbors and the full text search
+    /// its top `limit + offset` matches.  The candidates are combined with `method`
+    /// and returned in order of decreasing `_relevance_score`.  The `_distance` and
+    /// `_score` columns are null for rows only found by the other search.
+    ///
+    /// ```rust,ignore
+    /// let stream = dataset.scan()
+    ///     .nearest("vector", &query_vector, 20).unwrap()
+    ///     .full_text_search(FullTextSearchQuery::new("hello".to_string())).unwrap()
+    ///     .fusion(FusionMethod::default()).unwrap()
+    ///     .limit(Some(10), None).unwrap()
+    ///     .into_stream();
+    /// ```
+    pub fn fusion(&mut self, method: FusionMethod) -> Result<&mut Self> {
+        method.validate()?;
+        self.fusion = Some(method);
+        Ok(self)
+    }
+
     /// Set a filter using a Substrait ExtendedExpression message
     ///
     /// The message must contain exactly one expression and that expression
@@ -1389,6 +1456,37 @@ impl Scanner {
         self
     }
 
+    /// Set whether to prefetch the data of upcoming fragments (default: false)
+    ///
+    /// When enabled, the data a scan needs from a fragment is read in the background
+    /// as soon as the fragment is opened, at a lower priority than the reads of the
+    /// fragments being decoded.  Since fragments are opened ahead of time (see
+    /// [`Self::fragment_readahead`]) this keeps the store busy while the current
+    /// fragments are decoded, which improves the throughput of cold scans on high
+    /// latency stores.  The cost is up to one more I/O buffer (see
+    /// [`Self::io_buffer_size`]) of memory.  If the object store has a disk cache,
+    /// the prefetched data is written to it as well.
+    ///
+    /// Only used by scans of v2 files.
+    pub fn prefetch(&mut self, prefetch: bool) -> &mut Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Set whether the read may be split into one partition per group of fragments
+    /// (default: false)
+    ///
+    /// If true, the physical optimizer may repartition the read so that each partition
+    /// reads whole fragments and downstream operators run on the fragments in parallel.
+    /// Batches are then no longer returned in fragment order.  Reads with a limit or
+    /// offset are never repartitioned.
+    ///
+    /// Only used by scans of v2 files.
+    pub fn allow_repartition(&mut self, allow_repartition: bool) -> &mut Self {
+        self.allow_repartition = allow_repartition;
+        self
+    }
+
     /// Set the target number of partitions for the physical optimizer.
     ///
     /// Overrides the default (`get_num_compute_intensive_cpus()`). Used by
@@ -1564,6 +1662,9 @@ impl Scanner {
 
         let key = match &element_type {
             dt if dt == q.data_type() => q,
+            // bfloat16 vectors are searched with f32 queries, matching the f32 space
+            // their indices are trained in.
+            DataType::FixedSizeBinary(2) if q.data_type() == &DataType::Float32 => q,
             dt if dt.is_floating() => coerce_float_vector(
                 q.as_any().downcast_ref::<Float32Array>().unwrap(),
                 FloatType::try_from(dt)?,
@@ -1948,6 +2049,14 @@ impl Scanner {
             extra_columns.push(ArrowField::new(SCORE_COL, DataType::Float32, true));
         }
 
+        if self.is_hybrid_search() {
+            extra_columns.push(ArrowField::new(
+                RELEVANCE_SCORE_COL,
+                DataType::Float32,
+                true,
+            ));
+        }
+
         schema.merge(&ArrowSchema::new(extra_columns))
     }
 
@@ -2001,6 +2110,14 @@ impl Scanner {
                 let score_expr = expressions::col(SCORE_COL, current_schema)?;
                 output_expr.push((score_expr, SCORE_COL.to_string()));
             }
+            if self.is_hybrid_search()
+                && output_expr
+                    .iter()
+                    .all(|(_, name)| name != RELEVANCE_SCORE_COL)
+            {
+                let relevance_expr = expressions::col(RELEVANCE_SCORE_COL, current_schema)?;
+                output_expr.push((relevance_expr, RELEVANCE_SCORE_COL.to_string()));
+            }
         }
 
         // Batch nearest queries expose the synthetic `query_index` discriminator as
@@ -2402,6 +2519,13 @@ impl Scanner {
         // Check expr filter
         let filter_plan = if let Some(filter) = self.filter.expr_filter.as_ref() {
             let expr = filter.to_datafusion(self.dataset.schema(), filter_schema.as_ref())?;
+            // Spatial predicates on geometry columns with a bounding box column also get a
+            // filter on the bounds so that fragments can be pruned with zone maps
+            #[cfg(feature = "geo")]
+            let expr = lance_geo::bbox::add_bbox_filters(
+                expr,
+                &ArrowSchema::from(filter_schema.as_ref()),
+            )?;
             let index_info = self.dataset.scalar_index_info().await?;
             let filter_plan =
                 planner.create_filter_plan(expr.clone(), &index_info, use_scalar_index)?;
@@ -2556,6 +2680,9 @@ impl Scanner {
         let mut plan: Arc<dyn ExecutionPlan> = match (&self.nearest, &self.full_text_query) {
             (Some(_), None) => self.vector_search_source(&mut filter_plan).await?,
             (None, Some(query)) => self.fts_search_source(&mut filter_plan, query).await?,
+            (Some(_), Some(query)) if self.fusion.is_some() => {
+                self.hybrid_search_source(&mut filter_plan, query).await?
+            }
             (None, None) => {
                 if self.projection_plan.has_output_cols()
                     && self.projection_plan.physical_projection.is_empty()
@@ -2601,7 +2728,7 @@ impl Scanner {
             }
             _ => {
                 return Err(Error::invalid_input_source(
-                    "Cannot have both nearest and full text search".into(),
+                    "Cannot have both nearest and full text search without a fusion method".into(),
                 ));
             }
         };
@@ -2702,6 +2829,9 @@ impl Scanner {
         // Take remaining columns required for projection
         plan = self.take(plan, self.projection_plan.physical_projection.clone())?;
 
+        // Compute virtual embedding columns
+        plan = self.virtual_embeddings(plan)?;
+
         // Add system columns, if requested
         if self.projection_plan.must_add_row_offset {
             plan = Arc::new(AddRowOffsetExec::try_new(plan, self.dataset.clone()).await?);
@@ -2729,6 +2859,24 @@ impl Scanner {
         Ok(plan)
     }
 
+    fn virtual_embeddings(&self, plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = self.projection_plan.physical_projection.to_bare_schema();
+        let embeddings = virtual_embedding_columns(&schema)?
+            .into_iter()
+            .map(|(column, spec)| {
+                Ok(VirtualEmbedding {
+                    column,
+                    function: lookup_function(&self.dataset, &spec.function)?,
+                    source: spec.source,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if embeddings.is_empty() {
+            return Ok(plan);
+        }
+        Ok(Arc::new(EmbeddingExec::try_new(plan, embeddings)?))
+    }
+
     // Check if a filter plan references version columns
     fn filter_references_version_columns(&self, filter_plan: &ExprFilterPlan) -> bool {
         use lance_core::{ROW_CREATED_AT_VERSION, ROW_LAST_UPDATED_AT_VERSION};
@@ -2897,6 +3045,18 @@ impl Scanner {
             read_options = read_options.with_only_indexed_fragments();
         }
 
+        if !self.use_stats {
+            read_options = read_options.with_zone_map_pruning_disabled();
+        }
+
+        if self.prefetch {
+            read_options = read_options.with_prefetch();
+        }
+
+        if self.allow_repartition {
+            read_options = read_options.with_repartitioning();
+        }
+
         let result_format = self.index_expr_result_format();
         let index_input = filter_plan.index_query.clone().map(|index_query| {
             Arc::new(ScalarIndexExec::new(
@@ -3092,6 +3252,42 @@ impl Scanner {
         }
     }
 
+    fn is_hybrid_search(&self) -> bool {
+        self.fusion.is_some() && self.nearest.is_some() && self.full_text_query.is_some()
+    }
+
+    async fn hybrid_search_source(
+        &self,
+        filter_plan: &mut FilterPlan,
+        query: &FullTextSearchQuery,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        log::trace!("source is a hybrid search");
+        let Some(method) = self.fusion else {
+            return Err(Error::invalid_input("No fusion method".to_string()));
+        };
+        if self.is_batch_nearest {
+            return Err(Error::not_supported(
+                "Hybrid search does not support batch vector queries".to_string(),
+            ));
+        }
+        if filter_plan.query_filter.is_some() {
+            return Err(Error::not_supported(
+                "Hybrid search cannot be combined with a query filter".to_string(),
+            ));
+        }
+
+        // Both searches apply the same filter and leave the filter plan in the same state
+        // (prefiltered or refine-only), so the fts search can work on a copy.
+        let mut fts_filter_plan = filter_plan.clone();
+        let vector_plan = self.vector_search_source(filter_plan).await?;
+        let fts_plan = self.fts_search_source(&mut fts_filter_plan, query).await?;
+        Ok(Arc::new(HybridFusionExec::new(
+            vector_plan,
+            fts_plan,
+            method,
+        )))
+    }
+
     async fn vector_search_source(
         &self,
         filter_plan: &mut FilterPlan,
@@ -10969,6 +11165,56 @@ full_filter=name LIKE Utf8(\"test%2\"), refine_filter=name LIKE Utf8(\"test%2\")
         limit_offset_equivalency_test(&scanner).await;
     }
 
+    #[tokio::test]
+    async fn test_hybrid_search() {
+        let mut test_ds = TestVectorDataset::new(LanceFileVersion::Stable, false)
+            .await
+            .unwrap();
+        test_ds.make_fts_index().await.unwrap();
+        // Rows 5, 85, 165, 245 and 325 share this vector but only row 5 contains "5"
+        let query: Float32Array = (5 * 32..6 * 32).map(|v| v as f32).collect();
+
+        let mut scanner = test_ds.dataset.scan();
+        scanner
+            .nearest("vec", &query, 10)
+            .unwrap()
+            .full_text_search(FullTextSearchQuery::new("5".into()))
+            .unwrap();
+        assert!(scanner.try_into_batch().await.is_err());
+
+        scanner
+            .fusion(FusionMethod::default())
+            .unwrap()
+            .limit(Some(3), None)
+            .unwrap();
+        let batch = scanner.try_into_batch().await.unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch["i"].as_primitive::<Int32Type>().value(0), 5);
+        assert!(batch[DIST_COL].is_valid(0));
+        assert!(batch[SCORE_COL].is_valid(0));
+        let relevance = batch[RELEVANCE_SCORE_COL].as_primitive::<Float32Type>();
+        assert!(relevance.values().windows(2).all(|w| w[0] >= w[1]));
+
+        // With all the weight on the full text search the vector-only rows score 0
+        scanner
+            .fusion(FusionMethod::Weighted { vector_weight: 0.0 })
+            .unwrap()
+            .limit(None, None)
+            .unwrap();
+        let batch = scanner.try_into_batch().await.unwrap();
+        assert_eq!(batch.num_rows(), 10);
+        assert_eq!(batch["i"].as_primitive::<Int32Type>().value(0), 5);
+        let relevance = batch[RELEVANCE_SCORE_COL].as_primitive::<Float32Type>();
+        assert_eq!(relevance.value(0), 1.0);
+        assert!(relevance.values()[1..].iter().all(|r| *r == 0.0));
+
+        assert!(
+            scanner
+                .fusion(FusionMethod::Weighted { vector_weight: 2.0 })
+                .is_err()
+        );
+    }
+
     #[tokio::test]
     async fn test_fts_fast_search_excludes_unindexed_rows() {
         let mut test_ds = TestVectorDataset::new(LanceFileVersion::Stable, false)
@@ -11559,6 +11805,36 @@ full_filter=name LIKE Utf8(\"test%2\"), refine_filter=name LIKE Utf8(\"test%2\")
         assert_eq!(filtered.options().io_buffer_size_bytes, Some(7777));
     }
 
+    #[tokio::test]
+    async fn test_scan_with_prefetch() {
+        let data = lance_datagen::gen_batch()
+            .col("x", lance_datagen::array::step::<Int32Type>())
+            .col(
+                "s",
+                lance_datagen::array::rand_utf8(ByteCount::from(16), false),
+            )
+            .into_reader_rows(RowCount::from(100), BatchCount::from(10));
+        let write_params = WriteParams {
+            max_rows_per_file: 200,
+            ..Default::default()
+        };
+        let dataset = Dataset::write(data, "memory://test_scan_with_prefetch", Some(write_params))
+            .await
+            .unwrap();
+        assert_eq!(dataset.get_fragments().len(), 5);
+
+        let mut scanner = dataset.scan();
+        scanner.prefetch(true).fragment_readahead(2);
+        let plan = scanner.create_plan().await.unwrap();
+        let filtered = find_filtered_read(plan.as_ref())
+            .expect("expected a FilteredReadExec in the scan plan");
+        assert!(filtered.options().prefetch);
+
+        let expected = dataset.scan().try_into_batch().await.unwrap();
+        let actual = scanner.try_into_batch().await.unwrap();
+        assert_eq!(expected, actual);
+    }
+
     // The env var key scopes serial_test's lock so this test only blocks others
     // that touch LANCE_DEFAULT_IO_BUFFER_SIZE — unrelated tests still run in
     // parallel.
@@ -3,10 +3,13 @@
 
 use crate::Dataset;
 use crate::datafusion::LanceTableProvider;
+use crate::dataset::udtf::{LanceFragmentsUDTF, LanceVersionsUDTF};
 use crate::dataset::utils::SchemaAdapter;
+use crate::io::exec::vector_topk_pushdown::VectorTopKPushdown;
 use arrow_array::RecordBatch;
 use datafusion::dataframe::DataFrame;
 use datafusion::execution::SendableRecordBatchStream;
+use datafusion::execution::session_state::SessionStateBuilder;
 use datafusion::prelude::SessionContext;
 use futures::TryStreamExt;
 use lance_datafusion::udf::register_functions;
@@ -29,6 +32,9 @@ pub struct SqlQueryBuilder {
 
     /// If true, the query result will include the internal row address
     pub(crate) with_row_addr: bool,
+
+    /// If true, nearest neighbor queries are answered with a vector search
+    pub(crate) vector_search: bool,
 }
 
 impl SqlQueryBuilder {
@@ -39,6 +45,7 @@ impl SqlQueryBuilder {
             table_name: "dataset".to_string(),
             with_row_id: false,
             with_row_addr: false,
+            vector_search: false,
         }
     }
 
@@ -65,19 +72,38 @@ impl SqlQueryBuilder {
         self
     }
 
+    /// Specify if nearest neighbor queries should be answered with a vector search.
+    /// If true, queries such as `ORDER BY l2_distance(vec, [...]) LIMIT k` use the
+    /// vector index of the column. Results found through an index are approximate,
+    /// and rows with a null vector are never returned.
+    /// If not set, such queries sort the full table and return exact results.
+    pub fn with_vector_search(mut self, vector_search: bool) -> Self {
+        self.vector_search = vector_search;
+        self
+    }
+
     pub async fn build(self) -> lance_core::Result<SqlQuery> {
-        let ctx = SessionContext::new();
+        let mut state = SessionStateBuilder::new().with_default_features();
+        if self.vector_search {
+            state = state.with_physical_optimizer_rule(Arc::new(VectorTopKPushdown));
+        }
+        let state = state.build();
+        let ctx = SessionContext::new_with_state(state);
         let row_id = self.with_row_id;
         let row_addr = self.with_row_addr;
         ctx.register_table(
             self.table_name,
-            Arc::new(LanceTableProvider::new(
-                self.dataset.clone(),
-                row_id,
-                row_addr,
-            )),
+            Arc::new(
+                LanceTableProvider::builder(self.dataset.clone())
+                    .with_row_id(row_id)
+                    .with_row_address(row_addr)
+                    .build()
+                    .await?,
+            ),
         )?;
         register_functions(&ctx);
+        ctx.register_udtf("lance_versions", Arc::new(LanceVersionsUDTF));
+        ctx.register_udtf("lance_fragments", Arc::new(LanceFragmentsUDTF));
         let df = ctx.sql(&self.sql).await?;
         Ok(SqlQuery::new(df))
     }
@@ -365,4 +391,120 @@ mod tests {
         pretty_assertions::assert_eq!(batch.num_columns(), 1);
         pretty_assertions::assert_eq!(batch.column(0).as_primitive::<Int32Type>().value(0), 1);
     }
+
+    #[tokio::test]
+    async fn test_sql_vector_search() {
+        use crate::index::DatasetIndexExt;
+        use crate::index::vector::VectorIndexParams;
+        use arrow_array::{FixedSizeListArray, Float32Array, RecordBatchIterator};
+        use datafusion::physical_plan::displayable;
+        use lance_arrow::FixedSizeListArrayExt;
+        use lance_index::IndexType;
+        use lance_linalg::distance::MetricType;
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new(
+                "vec",
+                DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), 4),
+                true,
+            ),
+        ]));
+        let vectors = (0..100)
+            .flat_map(|i| [i as f32; 4])
+            .collect::<Float32Array>();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(0..100)),
+                Arc::new(FixedSizeListArray::try_new_from_values(vectors, 4).unwrap()),
+            ],
+        )
+        .unwrap();
+        let mut ds = Dataset::write(
+            RecordBatchIterator::new(vec![Ok(batch)], schema),
+            "memory://test_sql_vector_search",
+            None,
+        )
+        .await
+        .unwrap();
+        ds.create_index(
+            &["vec"],
+            IndexType::Vector,
+            None,
+            &VectorIndexParams::ivf_flat(2, MetricType::L2),
+            true,
+        )
+        .await
+        .unwrap();
+
+        let search = async |sql: &str| {
+            let df = ds
+                .sql(sql)
+                .with_vector_search(true)
+                .build()
+                .await
+                .unwrap()
+                .into_dataframe();
+            let plan = df.create_physical_plan().await.unwrap();
+            let plan = displayable(plan.as_ref()).indent(true).to_string();
+            let batches = ds
+                .sql(sql)
+                .with_vector_search(true)
+                .build()
+                .await
+                .unwrap()
+                .into_batch_records()
+                .await
+                .unwrap();
+            let ids = batches
+                .iter()
+                .flat_map(|batch| {
+                    batch
+                        .column(0)
+                        .as_primitive::<Int32Type>()
+                        .values()
+                        .to_vec()
+                })
+                .collect::<Vec<_>>();
+            (plan, ids)
+        };
+
+        let (plan, ids) = search(
+            "SELECT id, l2_distance(vec, [50.2, 50.2, 50.2, 50.2]) AS d FROM dataset ORDER BY d LIMIT 3",
+        )
+        .await;
+        assert!(plan.contains("VectorTopKRead: "), "{plan}");
+        assert_eq!(ids, vec![50, 51, 49]);
+
+        // Vector search is opt-in
+        let plan = ds
+            .sql("SELECT id FROM dataset ORDER BY l2_distance(vec, [50.2, 50.2, 50.2, 50.2]) LIMIT 3")
+            .build()
+            .await
+            .unwrap()
+            .into_dataframe()
+            .create_physical_plan()
+            .await
+            .unwrap();
+        let plan = displayable(plan.as_ref()).indent(true).to_string();
+        assert!(!plan.contains("VectorTopKRead"), "{plan}");
+
+        // The filter is applied before the search
+        let (plan, ids) = search(
+            "SELECT id FROM dataset WHERE id % 2 = 0 \
+             ORDER BY l2_distance(vec, [50.2, 50.2, 50.2, 50.2]) LIMIT 3",
+        )
+        .await;
+        assert!(plan.contains("prefilter="), "{plan}");
+        assert_eq!(ids, vec![50, 52, 48]);
+
+        // The farthest rows can't be found with a vector search
+        let (plan, ids) = search(
+            "SELECT id FROM dataset ORDER BY l2_distance(vec, [50.2, 50.2, 50.2, 50.2]) DESC LIMIT 2",
+        )
+        .await;
+        assert!(!plan.contains("VectorTopKRead"), "{plan}");
+        assert_eq!(ids, vec![0, 1]);
+    }
 }
@@ -16,10 +16,14 @@ use datafusion::common::{assert_contains, assert_not_contains};
 use geo_types::{Rect, coord, line_string};
 use geoarrow_array::{
     GeoArrowArray,
+    array::from_arrow_array,
     builder::{LineStringBuilder, PointBuilder, PolygonBuilder},
 };
-use geoarrow_schema::{Dimension, LineStringType, PointType, PolygonType};
+use geoarrow_schema::{
+    CoordType, Crs, Dimension, GeoArrowType, LineStringType, Metadata, PointType, PolygonType,
+};
 use lance_core::utils::tempfile::TempStrDir;
+use lance_file::version::LanceFileVersion;
 use lance_index::IndexType;
 use lance_index::scalar::ScalarIndexParams;
 
@@ -230,3 +234,312 @@ async fn test_geo_rtree_index() {
 
     assert_intersects_sql(&mut dataset, true).await;
 }
+
+#[tokio::test]
+async fn test_geo_cell_index() {
+    use crate::dataset::NewColumnTransform;
+    use arrow_array::types::Int64Type;
+
+    // 1. Creates points around San Francisco and Paris
+    let point_type = PointType::new(Dimension::XY, Default::default());
+    let schema = Arc::new(arrow_schema::Schema::new(vec![
+        point_type.clone().to_field("point", true),
+    ])) as arrow_schema::SchemaRef;
+
+    let mut point_builder = PointBuilder::new(point_type.clone());
+    for i in 0..100 {
+        let offset = (i / 2) as f64 * 1e-4;
+        let point = if i % 2 == 0 {
+            geo_types::point!(x: -122.41 + offset, y: 37.77 + offset)
+        } else {
+            geo_types::point!(x: 2.35 + offset, y: 48.86 + offset)
+        };
+        point_builder.push_point(Some(&point));
+    }
+    let batch =
+        RecordBatch::try_new(schema.clone(), vec![point_builder.finish().to_array_ref()]).unwrap();
+
+    let lance_path = TempStrDir::default();
+    let reader = RecordBatchIterator::new(vec![batch].into_iter().map(Ok), schema.clone());
+    let mut dataset = Dataset::write(reader, &lance_path, Some(Default::default()))
+        .await
+        .unwrap();
+
+    // 2. Materializes cell columns and indexes the geohash
+    dataset
+        .add_columns(
+            NewColumnTransform::SqlExpressions(vec![
+                ("geohash".into(), "st_geohash(point, 6)".into()),
+                ("h3".into(), "st_h3cell(point, 2)".into()),
+            ]),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    dataset
+        .create_index(
+            &["geohash"],
+            IndexType::BTree,
+            None,
+            &ScalarIndexParams::default(),
+            true,
+        )
+        .await
+        .unwrap();
+
+    // 3. Filters by a coarser geohash cell with the index
+    let sql = "SELECT point FROM dataset WHERE starts_with(geohash, '9q8')";
+    let num_rows: usize = dataset
+        .sql(sql)
+        .build()
+        .await
+        .unwrap()
+        .into_batch_records()
+        .await
+        .unwrap()
+        .iter()
+        .map(|b| b.num_rows())
+        .sum();
+    assert_eq!(num_rows, 50);
+    let batches = dataset
+        .sql(&format!("Explain {}", sql))
+        .build()
+        .await
+        .unwrap()
+        .into_batch_records()
+        .await
+        .unwrap();
+    assert_contains!(format!("{:?}", batches), "ScalarIndexQuery");
+
+    // 4. Aggregates by H3 cell
+    let batches = dataset
+        .sql("SELECT h3, count(*) AS n FROM dataset GROUP BY h3 ORDER BY h3")
+        .build()
+        .await
+        .unwrap()
+        .into_batch_records()
+        .await
+        .unwrap();
+    let batch = arrow_select::concat::concat_batches(&batches[0].schema(), &batches).unwrap();
+    assert_eq!(batch.num_rows(), 2);
+    assert_eq!(batch.column(0).null_count(), 0);
+    assert_eq!(
+        batch
+            .column(1)
+            .as_primitive::<Int64Type>()
+            .values()
+            .to_vec(),
+        vec![50, 50]
+    );
+}
+
+#[tokio::test]
+async fn test_geo_bbox_column_prunes_fragments() {
+    use arrow_array::{Float64Array, StructArray};
+    use arrow_schema::{DataType, Field, Fields};
+    use lance_geo::bbox::BBOX_COLUMN_META_KEY;
+
+    use crate::dataset::WriteParams;
+
+    let point_type = PointType::new(Dimension::XY, Default::default());
+    let point_field = point_type.clone().to_field("point", true);
+    let mut metadata = point_field.metadata().clone();
+    metadata.insert(BBOX_COLUMN_META_KEY.to_string(), "bbox".to_string());
+    let bbox_fields = Fields::from(
+        ["xmin", "ymin", "xmax", "ymax"]
+            .map(|name| Field::new(name, DataType::Float64, false))
+            .to_vec(),
+    );
+    let schema = Arc::new(arrow_schema::Schema::new(vec![
+        point_field.with_metadata(metadata),
+        Field::new("bbox", DataType::Struct(bbox_fields.clone()), true),
+    ]));
+
+    let num_rows = 100;
+    let mut point_builder = PointBuilder::new(point_type);
+    for i in 0..num_rows {
+        let i = i as f64;
+        point_builder.push_point(Some(&geo_types::point!(x: i, y: i)));
+    }
+    let coords = Arc::new(Float64Array::from_iter_values(
+        (0..num_rows).map(|i| i as f64),
+    ));
+    let bbox = StructArray::new(bbox_fields, vec![coords; 4], None);
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![point_builder.finish().to_array_ref(), Arc::new(bbox)],
+    )
+    .unwrap();
+
+    let lance_path = TempStrDir::default();
+    let reader = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+    let dataset = Dataset::write(
+        reader,
+        &lance_path,
+        Some(WriteParams {
+            max_rows_per_file: 25,
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(dataset.get_fragments().len(), 4);
+
+    let filter =
+        "st_intersects(point, ST_GeomFromText('POLYGON((10 10, 20 10, 20 20, 10 20, 10 10))'))";
+    let mut scanner = dataset.scan();
+    scanner.filter(filter).unwrap();
+    let batch = scanner.try_into_batch().await.unwrap();
+    let xs = batch["bbox"].as_struct()["xmin"]
+        .as_primitive::<Float64Type>()
+        .values()
+        .to_vec();
+    assert_eq!(xs, (10..=20).map(|i| i as f64).collect::<Vec<_>>());
+
+    let plan = scanner.analyze_plan().await.unwrap();
+    assert_contains!(&plan, "get_field(bbox, Utf8(\"xmin\"))");
+    assert_contains!(&plan, "fragments_scanned=1");
+}
+
+#[rstest::rstest]
+#[tokio::test]
+async fn test_geo_round_trip(
+    #[values(LanceFileVersion::V2_1, LanceFileVersion::V2_2)] version: LanceFileVersion,
+    #[values(CoordType::Interleaved, CoordType::Separated)] coord_type: CoordType,
+) {
+    use geo_types::{MultiLineString, MultiPoint, MultiPolygon, polygon};
+    use geoarrow_array::builder::{MultiLineStringBuilder, MultiPointBuilder, MultiPolygonBuilder};
+    use geoarrow_schema::{MultiLineStringType, MultiPointType, MultiPolygonType};
+
+    use crate::dataset::WriteParams;
+
+    let metadata = Arc::new(Metadata::new(
+        Crs::from_unknown_crs_type("EPSG:4326".into()),
+        None,
+    ));
+    let point_type = PointType::new(Dimension::XY, metadata.clone()).with_coord_type(coord_type);
+    let line_string_type =
+        LineStringType::new(Dimension::XY, metadata.clone()).with_coord_type(coord_type);
+    let polygon_type =
+        PolygonType::new(Dimension::XY, metadata.clone()).with_coord_type(coord_type);
+    let multi_point_type =
+        MultiPointType::new(Dimension::XY, metadata.clone()).with_coord_type(coord_type);
+    let multi_line_string_type =
+        MultiLineStringType::new(Dimension::XY, metadata.clone()).with_coord_type(coord_type);
+    let multi_polygon_type =
+        MultiPolygonType::new(Dimension::XY, metadata).with_coord_type(coord_type);
+
+    let num_rows = 50;
+    let mut points = PointBuilder::new(point_type.clone());
+    let mut line_strings = LineStringBuilder::new(line_string_type.clone());
+    let mut polygons = PolygonBuilder::new(polygon_type.clone());
+    let mut multi_points = MultiPointBuilder::new(multi_point_type.clone());
+    let mut multi_line_strings = MultiLineStringBuilder::new(multi_line_string_type.clone());
+    let mut multi_polygons = MultiPolygonBuilder::new(multi_polygon_type.clone());
+    for i in 0..num_rows {
+        let x = i as f64;
+        // Every fifth row is null
+        if i % 5 == 4 {
+            points.push_point(None::<&geo_types::Point>);
+            line_strings
+                .push_line_string(None::<&geo_types::LineString>)
+                .unwrap();
+            polygons.push_polygon(None::<&geo_types::Polygon>).unwrap();
+            multi_points.push_multi_point(None::<&MultiPoint>).unwrap();
+            multi_line_strings
+                .push_multi_line_string(None::<&MultiLineString>)
+                .unwrap();
+            multi_polygons
+                .push_multi_polygon(None::<&MultiPolygon>)
+                .unwrap();
+            continue;
+        }
+        let line_string =
+            line_string![(x: x, y: -x), (x: x + 1.0, y: x * 2.0), (x: x + 2.0, y: 0.5)];
+        let polygon =
+            polygon![(x: x, y: x), (x: x + 1.0, y: x), (x: x + 1.0, y: x + 1.0), (x: x, y: x)];
+        points.push_point(Some(&geo_types::point!(x: x, y: -x)));
+        line_strings.push_line_string(Some(&line_string)).unwrap();
+        polygons.push_polygon(Some(&polygon)).unwrap();
+        multi_points
+            .push_multi_point(Some(&MultiPoint::from(vec![(x, 1.0), (2.0, x)])))
+            .unwrap();
+        multi_line_strings
+            .push_multi_line_string(Some(&MultiLineString::new(vec![
+                line_string.clone(),
+                line_string,
+            ])))
+            .unwrap();
+        multi_polygons
+            .push_multi_polygon(Some(&MultiPolygon::new(vec![polygon; (i % 3) + 1])))
+            .unwrap();
+    }
+
+    let schema = Arc::new(arrow_schema::Schema::new(vec![
+        point_type.to_field("point", true),
+        line_string_type.to_field("linestring", true),
+        polygon_type.to_field("polygon", true),
+        multi_point_type.to_field("multipoint", true),
+        multi_line_string_type.to_field("multilinestring", true),
+        multi_polygon_type.to_field("multipolygon", true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            points.finish().to_array_ref(),
+            line_strings.finish().to_array_ref(),
+            polygons.finish().to_array_ref(),
+            multi_points.finish().to_array_ref(),
+            multi_line_strings.finish().to_array_ref(),
+            multi_polygons.finish().to_array_ref(),
+        ],
+    )
+    .unwrap();
+
+    let lance_path = TempStrDir::default();
+    let reader = RecordBatchIterator::new(vec![Ok(batch.clone())], schema.clone());
+    let dataset = Dataset::write(
+        reader,
+        &lance_path,
+        Some(WriteParams {
+            data_storage_version: Some(version),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+
+    // The geometries and their extension metadata (including the CRS) are preserved
+    let read = dataset.scan().try_into_batch().await.unwrap();
+    assert_eq!(read.schema(), schema);
+    assert_eq!(read, batch);
+
+    // Geometries can be read back as GeoArrow arrays
+    let field = read
+        .schema_ref()
+        .field_with_name("multipolygon")
+        .unwrap()
+        .clone();
+    let geo_array = from_arrow_array(read["multipolygon"].as_ref(), &field).unwrap();
+    assert_eq!(
+        geo_array.data_type(),
+        GeoArrowType::MultiPolygon(multi_polygon_type)
+    );
+    assert_eq!(geo_array.logical_null_count(), 10);
+
+    // Taking rows keeps the layout
+    let taken = dataset
+        .take(&[0, 7, 49], dataset.schema().clone())
+        .await
+        .unwrap();
+    assert_eq!(taken.schema(), schema);
+    assert_eq!(
+        taken,
+        arrow_select::take::take_record_batch(
+            &batch,
+            &arrow_array::UInt32Array::from(vec![0, 7, 49])
+        )
+        .unwrap()
+    );
+}
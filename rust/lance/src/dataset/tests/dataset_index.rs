@@ -13,13 +13,14 @@ use crate::index::vector::VectorIndexParams;
 use crate::session::Session;
 use crate::{Dataset, Error, Result};
 use lance_arrow::FixedSizeListArrayExt;
+use lance_arrow::bfloat16::{BFLOAT16_EXT_NAME, BFloat16Array};
 
 use crate::dataset::write::{WriteMode, WriteParams};
 use crate::index::DatasetIndexExt;
 use arrow::array::{AsArray, GenericListBuilder, GenericStringBuilder};
 use arrow::datatypes::UInt64Type;
 use arrow_array::RecordBatch;
-use arrow_array::{Array, GenericStringArray, StructArray, UInt64Array};
+use arrow_array::{Array, FixedSizeListArray, GenericStringArray, StructArray, UInt64Array};
 use arrow_array::{
     ArrayRef, Float32Array, Int32Array, RecordBatchIterator, StringArray,
     builder::StringDictionaryBuilder,
@@ -39,6 +40,9 @@ use lance_index::scalar::inverted::{
     query::{BooleanQuery, MatchQuery, Occur, Operator, PhraseQuery},
     tokenizer::InvertedIndexParams,
 };
+use lance_index::vector::{
+    hnsw::builder::HnswBuildParams, ivf::IvfBuildParams, sq::builder::SQBuildParams,
+};
 use lance_index::{FtsPrewarmOptions, PrewarmOptions};
 use lance_index::{IndexType, scalar::ScalarIndexParams, vector::DIST_COL};
 use lance_io::scheduler::{ScanScheduler, SchedulerConfig};
@@ -47,6 +51,7 @@ use lance_linalg::distance::MetricType;
 
 use datafusion::common::{assert_contains, assert_not_contains};
 use futures::{StreamExt, TryStreamExt};
+use half::bf16;
 use itertools::Itertools;
 use lance_arrow::json::ARROW_JSON_EXT_NAME;
 use lance_index::scalar::inverted::query::{FtsQuery, MultiMatchQuery};
@@ -390,6 +395,76 @@ async fn test_create_int8_index(
     assert!(fragment_bitmap.contains(0));
 }
 
+#[rstest]
+#[case::ivf_flat(VectorIndexParams::ivf_flat(4, MetricType::L2))]
+#[case::ivf_pq(VectorIndexParams::ivf_pq(4, 8, 4, MetricType::L2, 50))]
+#[case::ivf_sq(VectorIndexParams::with_ivf_sq_params(
+    MetricType::Cosine,
+    IvfBuildParams::new(4),
+    SQBuildParams::default(),
+))]
+#[case::ivf_rq(VectorIndexParams::ivf_rq(4, 1, MetricType::L2))]
+#[case::ivf_hnsw_flat(VectorIndexParams::ivf_hnsw(
+    MetricType::L2,
+    IvfBuildParams::new(4),
+    HnswBuildParams::default(),
+))]
+#[case::ivf_hnsw_sq(VectorIndexParams::with_ivf_hnsw_sq_params(
+    MetricType::L2,
+    IvfBuildParams::new(4),
+    HnswBuildParams::default(),
+    SQBuildParams::default(),
+))]
+#[tokio::test]
+async fn test_create_bfloat16_index(#[case] params: VectorIndexParams) {
+    let test_uri = TempStrDir::default();
+
+    let dimension = 16;
+    let item_field = Arc::new(
+        ArrowField::new("item", DataType::FixedSizeBinary(2), true)
+            .with_metadata([(ARROW_EXT_NAME_KEY.into(), BFLOAT16_EXT_NAME.into())].into()),
+    );
+    let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+        "embeddings",
+        DataType::FixedSizeList(item_field.clone(), dimension),
+        false,
+    )]));
+
+    let float_arr = generate_random_array(512 * dimension as usize);
+    let values =
+        BFloat16Array::from_iter_values(float_arr.values().iter().map(|v| bf16::from_f32(*v)));
+    let vectors =
+        FixedSizeListArray::new(item_field, dimension, Arc::new(values.into_inner()), None);
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(vectors.clone())]).unwrap();
+    let reader = RecordBatchIterator::new(vec![Ok(batch)], schema.clone());
+    let mut dataset = Dataset::write(reader, &test_uri, None).await.unwrap();
+
+    // Queries are given as f32 and are compared against the stored bf16 vectors.
+    let query = vectors.convert_to_floating_point().unwrap().value(7);
+    let nearest_row = async |dataset: &Dataset| {
+        let results = dataset
+            .scan()
+            .nearest("embeddings", query.as_ref(), 5)
+            .unwrap()
+            .distance_metric(params.metric_type)
+            .minimum_nprobes(4)
+            .with_row_id()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(results.num_rows(), 5);
+        results[ROW_ID].as_primitive::<UInt64Type>().value(0)
+    };
+    assert_eq!(nearest_row(&dataset).await, 7);
+
+    dataset
+        .create_index(&["embeddings"], IndexType::Vector, None, &params, true)
+        .await
+        .unwrap();
+    dataset.validate().await.unwrap();
+    assert_eq!(nearest_row(&dataset).await, 7);
+}
+
 #[tokio::test]
 async fn test_create_fts_index_with_empty_strings() {
     let test_uri = TempStrDir::default();
@@ -3611,3 +3686,82 @@ async fn test_legacy_dataset_uses_v2_0_for_indexes() {
         "Index files should never use legacy format, even for legacy datasets"
     );
 }
+
+#[tokio::test]
+async fn test_external_index_section() {
+    use lance_table::feature_flags::FLAG_INDEX_DIRECTORY;
+    use lance_table::format::EXTERNAL_INDEX_SECTION_KEY;
+
+    let test_uri = TempStrDir::default();
+    let data = gen_batch()
+        .col("a", array::step::<Int32Type>())
+        .col("b", array::step::<Int32Type>());
+    let mut dataset = Dataset::write(
+        data.into_reader_rows(RowCount::from(1000), BatchCount::from(1)),
+        &test_uri,
+        None,
+    )
+    .await
+    .unwrap();
+    dataset
+        .update_config([(EXTERNAL_INDEX_SECTION_KEY, "true")])
+        .await
+        .unwrap();
+    for column in ["a", "b"] {
+        dataset
+            .create_index(
+                &[column],
+                IndexType::Scalar,
+                Some(format!("{column}_idx")),
+                &ScalarIndexParams::default(),
+                false,
+            )
+            .await
+            .unwrap();
+    }
+    assert!(dataset.manifest.index_directory.is_some());
+    assert!(dataset.manifest.index_section.is_none());
+    assert_ne!(
+        dataset.manifest.reader_feature_flags & FLAG_INDEX_DIRECTORY,
+        0
+    );
+
+    // A fresh dataset only reads the metadata of the requested index
+    let dataset = Dataset::open(&test_uri).await.unwrap();
+    let b_idx = dataset.load_indices_by_name("b_idx").await.unwrap();
+    assert_eq!(b_idx.len(), 1);
+    assert_eq!(b_idx[0].fields, vec![1]);
+    let by_uuid = dataset.load_index(&b_idx[0].uuid).await.unwrap().unwrap();
+    assert_eq!(by_uuid.name, "b_idx");
+    assert!(
+        dataset
+            .load_indices_by_name("c_idx")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(dataset.load_indices().await.unwrap().len(), 2);
+    let rows = dataset
+        .count_rows(Some("a = 5 AND b = 5".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+
+    // Turning the setting off moves the indices back into the index section
+    let mut dataset = dataset;
+    dataset
+        .update_config([(EXTERNAL_INDEX_SECTION_KEY, "false")])
+        .await
+        .unwrap();
+    assert!(dataset.manifest.index_directory.is_none());
+    assert!(dataset.manifest.index_section.is_some());
+    assert_eq!(
+        dataset.manifest.reader_feature_flags & FLAG_INDEX_DIRECTORY,
+        0
+    );
+    let dataset = Dataset::open(&test_uri).await.unwrap();
+    assert_eq!(
+        dataset.load_indices_by_name("a_idx").await.unwrap().len(),
+        1
+    );
+}
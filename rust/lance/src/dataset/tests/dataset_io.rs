@@ -757,6 +757,125 @@ async fn test_write_params(
     }
 }
 
+#[tokio::test]
+async fn test_encrypted_columns() {
+    use crate::dataset::ReadParams;
+    use crate::dataset::encryption::{EncryptionParams, KeyProvider, LocalKeyProvider};
+
+    let test_dir = TempStdDir::default();
+    let test_uri = test_dir.to_str().unwrap();
+    let schema = Arc::new(ArrowSchema::new(vec![
+        ArrowField::new("id", DataType::Int32, false),
+        ArrowField::new("secret", DataType::Utf8, false),
+    ]));
+    let batch = |start: i32| {
+        let ids = start..start + 10;
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from_iter_values(ids.clone())),
+                Arc::new(StringArray::from_iter_values(
+                    ids.map(|id| format!("confidential-{id}")),
+                )),
+            ],
+        )
+        .unwrap()
+    };
+    let key_provider: Arc<dyn KeyProvider> =
+        Arc::new(LocalKeyProvider::new("arn:aws:kms:test", [7; 32]));
+
+    let write_params = WriteParams {
+        data_storage_version: Some(LanceFileVersion::V2_1),
+        encryption: Some(
+            EncryptionParams::new(key_provider.clone())
+                .encrypt_columns(["secret"])
+                .await
+                .unwrap(),
+        ),
+        ..Default::default()
+    };
+    let reader = RecordBatchIterator::new(vec![Ok(batch(0))], schema.clone());
+    let dataset = Dataset::write(reader, test_uri, Some(write_params))
+        .await
+        .unwrap();
+    let spec = dataset.encryption_spec().unwrap().unwrap();
+    assert_eq!(spec.columns.len(), 1);
+    assert_eq!(spec.columns[0].field_id, 1);
+    assert_eq!(spec.columns[0].key_id, "arn:aws:kms:test");
+    // The written dataset can read its own columns
+    assert_eq!(dataset.scan().try_into_batch().await.unwrap(), batch(0));
+
+    // The plaintext never reaches the data files
+    for entry in std::fs::read_dir(test_dir.join("data")).unwrap() {
+        let bytes = std::fs::read(entry.unwrap().path()).unwrap();
+        assert!(!bytes.windows(13).any(|window| window == b"confidential-"));
+    }
+
+    // Without the key provider only the other columns can be read
+    let dataset = Dataset::open(test_uri).await.unwrap();
+    let mut scan = dataset.scan();
+    scan.project(&["id"]).unwrap();
+    assert_eq!(scan.try_into_batch().await.unwrap().num_rows(), 10);
+    let mut scan = dataset.scan();
+    scan.project(&["secret"]).unwrap();
+    assert!(scan.try_into_batch().await.is_err());
+    // and nothing can be written to the encrypted columns
+    let reader = RecordBatchIterator::new(vec![Ok(batch(10))], schema.clone());
+    let append = WriteParams {
+        mode: WriteMode::Append,
+        ..Default::default()
+    };
+    assert!(
+        Dataset::write(reader, test_uri, Some(append.clone()))
+            .await
+            .is_err()
+    );
+
+    // A dataset opened with the key provider can be read and appended to
+    let dataset = DatasetBuilder::from_uri(test_uri)
+        .with_read_params(ReadParams {
+            key_provider: Some(key_provider.clone()),
+            ..Default::default()
+        })
+        .load()
+        .await
+        .unwrap();
+    let reader = RecordBatchIterator::new(vec![Ok(batch(10))], schema.clone());
+    let dataset = Dataset::write(reader, Arc::new(dataset), Some(append))
+        .await
+        .unwrap();
+    let batches = dataset
+        .scan()
+        .try_into_stream()
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        concat_batches(&schema, &batches).unwrap(),
+        concat_batches(&schema, &[batch(0), batch(10)]).unwrap()
+    );
+
+    // Columns can only be encrypted when the dataset is created or overwritten
+    let reader = RecordBatchIterator::new(vec![Ok(batch(20))], schema.clone());
+    let write_params = WriteParams {
+        mode: WriteMode::Append,
+        encryption: Some(
+            EncryptionParams::new(key_provider)
+                .encrypt_columns(["id"])
+                .await
+                .unwrap(),
+        ),
+        ..Default::default()
+    };
+    assert!(
+        Dataset::write(reader, test_uri, Some(write_params))
+            .await
+            .is_err()
+    );
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_write_manifest(
@@ -839,6 +958,18 @@ async fn test_write_manifest(
         feature_flags::FLAG_DELETION_FILES
     );
 
+    // The preflight check only looks at the flags of the opened version
+    dataset.check_compatibility().unwrap();
+    let mut newer = dataset.clone();
+    Arc::make_mut(&mut newer.manifest).writer_feature_flags |= FLAG_UNKNOWN;
+    assert!(matches!(
+        newer.check_compatibility(),
+        Err(Error::UnsupportedVersion {
+            unsupported_flags: FLAG_UNKNOWN,
+            ..
+        })
+    ));
+
     // Write with custom manifest
     manifest.writer_feature_flags |= FLAG_UNKNOWN; // Set another flag
     manifest.reader_feature_flags |= FLAG_UNKNOWN;
@@ -865,7 +996,13 @@ async fn test_write_manifest(
 
     // Check it rejects reading it
     let read_result = Dataset::open(&test_uri).await;
-    assert!(matches!(read_result, Err(Error::NotSupported { .. })));
+    assert!(matches!(
+        read_result,
+        Err(Error::UnsupportedVersion {
+            unsupported_flags: FLAG_UNKNOWN,
+            ..
+        })
+    ));
 
     // Check it rejects writing to it.
     let batches = vec![
@@ -887,7 +1024,13 @@ async fn test_write_manifest(
     )
     .await;
 
-    assert!(matches!(write_result, Err(Error::NotSupported { .. })));
+    assert!(matches!(
+        write_result,
+        Err(Error::UnsupportedVersion {
+            unsupported_flags: FLAG_UNKNOWN,
+            ..
+        })
+    ));
 }
 
 #[rstest]
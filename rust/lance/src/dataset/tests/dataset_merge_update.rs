@@ -875,6 +875,8 @@ async fn test_datafile_partial_replacement() {
         file_minor_version: minor,
         file_size_bytes: CachedFileSize::unknown(),
         base_id: None,
+        zone_maps: Arc::from([]),
+        partition_values: Arc::from([]),
     };
 
     let dataset = Dataset::commit(
@@ -936,6 +938,8 @@ async fn test_datafile_partial_replacement() {
         file_minor_version: minor,
         file_size_bytes: CachedFileSize::unknown(),
         base_id: None,
+        zone_maps: Arc::from([]),
+        partition_values: Arc::from([]),
     };
 
     let dataset = Dataset::commit(
@@ -1035,6 +1039,8 @@ async fn test_datafile_replacement_error() {
         file_minor_version: 0,
         file_size_bytes: CachedFileSize::unknown(),
         base_id: None,
+        zone_maps: Arc::from([]),
+        partition_values: Arc::from([]),
     };
 
     let new_data_file = DataFile {
@@ -620,3 +620,165 @@ async fn check_results(
         .unwrap();
     assert_eq!(ids.values(), expected_ids);
 }
+
+#[tokio::test]
+async fn test_partitioned_write_prunes_fragments() {
+    use std::sync::Mutex;
+
+    use lance_table::format::{PARTITION_SPEC_KEY, PartitionTransform};
+
+    use crate::dataset::scanner::ExecutionSummaryCounts;
+    use crate::dataset::{PartitionColumn, WriteMode};
+
+    let schema = Arc::new(ArrowSchema::new(vec![
+        ArrowField::new("region", DataType::Utf8, false),
+        ArrowField::new("id", DataType::Int32, false),
+    ]));
+    let batch = |ids: std::ops::Range<i32>| {
+        let regions = ids
+            .clone()
+            .map(|id| ["eu", "us", "ap"][id as usize % 3])
+            .collect::<Vec<_>>();
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(regions)),
+                Arc::new(Int32Array::from_iter_values(ids)),
+            ],
+        )
+        .unwrap()
+    };
+    let uri = "memory://test_partitioned_write_prunes_fragments";
+    let write_params = WriteParams {
+        partition_by: vec![
+            PartitionColumn::identity("region"),
+            PartitionColumn::range("id", 100),
+        ],
+        ..Default::default()
+    };
+    let mut dataset = Dataset::write(
+        RecordBatchIterator::new(vec![Ok(batch(0..300))], schema.clone()),
+        uri,
+        Some(write_params.clone()),
+    )
+    .await
+    .unwrap();
+    assert!(dataset.manifest.config.contains_key(PARTITION_SPEC_KEY));
+    // One fragment per region and range of ids
+    assert_eq!(dataset.get_fragments().len(), 9);
+
+    // Appends are routed by the partition spec of the dataset
+    dataset
+        .append(
+            RecordBatchIterator::new(vec![Ok(batch(300..330))], schema.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(dataset.get_fragments().len(), 12);
+    for fragment in dataset.get_fragments() {
+        let partition_values = &fragment.metadata().files[0].partition_values;
+        assert_eq!(partition_values.len(), 2);
+        assert_eq!(partition_values[0].transform, PartitionTransform::Identity);
+        assert_eq!(
+            partition_values[1].transform,
+            PartitionTransform::Range { width: 100 }
+        );
+    }
+
+    let stats: Arc<Mutex<Option<ExecutionSummaryCounts>>> = Arc::new(Mutex::new(None));
+    let stats_clone = stats.clone();
+    let mut scanner = dataset.scan();
+    scanner
+        .filter("region = 'eu' AND id >= 100")
+        .unwrap()
+        .scan_stats_callback(Arc::new(move |counts| {
+            *stats_clone.lock().unwrap() = Some(counts.clone());
+        }));
+    let batches = scanner
+        .try_into_stream()
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 76);
+    let stats = stats.lock().unwrap().take().unwrap();
+    assert_eq!(stats.fragments_scanned, 3);
+    assert_eq!(stats.fragments_pruned, 9);
+
+    // The partition columns can only be set with a new dataset
+    let err = Dataset::write(
+        RecordBatchIterator::new(vec![Ok(batch(0..3))], schema.clone()),
+        uri,
+        Some(WriteParams {
+            mode: WriteMode::Append,
+            ..write_params
+        }),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("partition columns"), "{err}");
+}
+
+#[tokio::test]
+async fn test_scan_stats_callback_summary() {
+    use std::sync::Mutex;
+
+    use crate::dataset::scanner::ExecutionSummaryCounts;
+
+    let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+        "i",
+        DataType::Int32,
+        false,
+    )]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(Int32Array::from_iter_values(0..300))],
+    )
+    .unwrap();
+    let write_params = WriteParams {
+        max_rows_per_file: 100,
+        ..Default::default()
+    };
+    let dataset = Dataset::write(
+        RecordBatchIterator::new(vec![Ok(batch)], schema),
+        "memory://test_scan_stats_callback_summary",
+        Some(write_params),
+    )
+    .await
+    .unwrap();
+
+    let stats: Arc<Mutex<Option<ExecutionSummaryCounts>>> = Arc::new(Mutex::new(None));
+    let stats_clone = stats.clone();
+    let mut scanner = dataset.scan();
+    scanner
+        .filter("i < 50")
+        .unwrap()
+        .scan_stats_callback(Arc::new(move |counts| {
+            *stats_clone.lock().unwrap() = Some(counts.clone());
+        }));
+    let batches = scanner
+        .try_into_stream()
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 50);
+
+    let stats = stats.lock().unwrap().take().unwrap();
+    assert_eq!(stats.output_rows, 50);
+    assert!(stats.rows_scanned >= 50);
+    assert_eq!(stats.fragments_scanned + stats.fragments_pruned, 3);
+    assert!(stats.bytes_read > 0);
+    assert!(stats.elapsed > std::time::Duration::ZERO);
+    assert!(
+        stats
+            .stage_times
+            .iter()
+            .any(|(name, _)| name == "FilteredReadExec"),
+        "{:?}",
+        stats.stage_times
+    );
+}
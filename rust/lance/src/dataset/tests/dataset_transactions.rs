@@ -245,7 +245,7 @@ pub(super) async fn execute_sql(
     let ctx = SessionContext::new();
     ctx.register_table(
         table,
-        Arc::new(LanceTableProvider::new(dataset, false, false)),
+        Arc::new(LanceTableProvider::builder(dataset).build().await?),
     )?;
     register_functions(&ctx);
 
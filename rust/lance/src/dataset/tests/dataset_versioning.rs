@@ -1028,6 +1028,8 @@ async fn test_versions_with_archive_partial() {
         read_version: None,
         operation_type: None,
         transaction_properties: HashMap::new(),
+        indices: Vec::new(),
+        tags: Vec::new(),
     }];
 
     archive.add_entries(&entries);
@@ -1099,6 +1101,8 @@ async fn test_versions_reads_live_manifests_not_retained_in_archive() {
             read_version: None,
             operation_type: None,
             transaction_properties: HashMap::new(),
+            indices: Vec::new(),
+            tags: Vec::new(),
         })
         .collect();
     archive.add_entries(&entries);
@@ -1226,6 +1230,8 @@ async fn test_versions_full_archive() {
             read_version: None,
             operation_type: None,
             transaction_properties: HashMap::new(),
+            indices: Vec::new(),
+            tags: Vec::new(),
         },
         VersionArchiveEntry {
             version: 2,
@@ -1236,6 +1242,8 @@ async fn test_versions_full_archive() {
             read_version: None,
             operation_type: None,
             transaction_properties: HashMap::new(),
+            indices: Vec::new(),
+            tags: Vec::new(),
         },
     ];
 
@@ -1272,3 +1280,399 @@ async fn test_versions_full_archive() {
         .collect();
     assert_eq!(cleaned_versions, vec![1]);
 }
+
+#[tokio::test]
+async fn test_version_history() {
+    use crate::dataset::VersionHistoryFilter;
+
+    let test_dir = TempStdDir::default();
+    let test_uri = test_dir.to_str().unwrap();
+    let data = lance_datagen::gen_batch()
+        .col("key", array::step::<Int32Type>())
+        .into_batch_rows(RowCount::from(10))
+        .unwrap();
+    let schema = data.schema();
+    let mut dataset = Dataset::write(
+        RecordBatchIterator::new([Ok(data.clone())], schema.clone()),
+        test_uri,
+        None,
+    )
+    .await
+    .unwrap();
+    for _ in 0..2 {
+        dataset
+            .append(
+                RecordBatchIterator::new([Ok(data.clone())], schema.clone()),
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    dataset.tags().create("prod", 2).await.unwrap();
+    dataset
+        .cleanup_old_versions(chrono::Duration::zero(), Some(true), Some(false))
+        .await
+        .unwrap();
+    assert_eq!(dataset.versions().await.unwrap().len(), 2);
+
+    let history = |filter: VersionHistoryFilter| {
+        let dataset = dataset.clone();
+        async move {
+            dataset
+                .version_history(filter)
+                .await
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap()
+        }
+    };
+
+    // The cleaned up version is still part of the history
+    let all = history(VersionHistoryFilter::default()).await;
+    assert_eq!(
+        all.iter()
+            .map(|entry| (entry.entry.version, entry.is_live, entry.entry.is_tagged))
+            .collect::<Vec<_>>(),
+        vec![(1, false, false), (2, true, true), (3, true, false)]
+    );
+    assert_eq!(all[0].entry.manifest_summary.total_rows, 10);
+    assert_eq!(all[2].entry.manifest_summary.total_rows, 30);
+
+    let appends = history(VersionHistoryFilter {
+        operation_type: Some("append".to_string()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(
+        appends
+            .iter()
+            .map(|entry| entry.entry.version)
+            .collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+
+    let tagged = history(VersionHistoryFilter {
+        tag: Some("prod".to_string()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(tagged.len(), 1);
+    assert_eq!(tagged[0].entry.version, 2);
+
+    let latest_time =
+        chrono::DateTime::from_timestamp_millis(all[2].entry.timestamp_millis).unwrap();
+    let recent = history(VersionHistoryFilter {
+        start_time: Some(latest_time),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(recent.last().unwrap().entry.version, 3);
+    assert!(
+        recent
+            .iter()
+            .all(|entry| entry.entry.timestamp_millis >= all[2].entry.timestamp_millis)
+    );
+    let older = history(VersionHistoryFilter {
+        end_time: Some(latest_time),
+        ..Default::default()
+    })
+    .await;
+    assert!(older.iter().all(|entry| entry.entry.version != 3));
+}
+
+#[tokio::test]
+async fn test_version_history_indices_and_tags() {
+    use crate::dataset::VersionHistoryFilter;
+    use crate::index::DatasetIndexExt;
+    use lance_index::IndexType;
+    use lance_index::scalar::ScalarIndexParams;
+
+    let test_dir = TempStdDir::default();
+    let test_uri = test_dir.to_str().unwrap();
+    let data = lance_datagen::gen_batch()
+        .col("key", array::step::<Int32Type>())
+        .into_batch_rows(RowCount::from(10))
+        .unwrap();
+    let schema = data.schema();
+    let mut dataset = Dataset::write(
+        RecordBatchIterator::new([Ok(data.clone())], schema.clone()),
+        test_uri,
+        None,
+    )
+    .await
+    .unwrap();
+    dataset
+        .create_index(
+            &["key"],
+            IndexType::BTree,
+            Some("key_idx".to_owned()),
+            &ScalarIndexParams::default(),
+            true,
+        )
+        .await
+        .unwrap();
+    dataset
+        .append(
+            RecordBatchIterator::new([Ok(data.clone())], schema.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+    let index_type = dataset.describe_indices(None).await.unwrap()[0]
+        .index_type()
+        .to_string();
+
+    // Version 2 is archived with its tags, then cleaned up once untagged
+    dataset.tags().create("prod", 2).await.unwrap();
+    dataset.tags().create("audit", 2).await.unwrap();
+    dataset
+        .cleanup_old_versions(chrono::Duration::zero(), Some(true), Some(false))
+        .await
+        .unwrap();
+    dataset.tags().delete("prod").await.unwrap();
+    dataset.tags().delete("audit").await.unwrap();
+    dataset
+        .cleanup_old_versions(chrono::Duration::zero(), Some(true), Some(false))
+        .await
+        .unwrap();
+
+    let history = dataset
+        .version_history(VersionHistoryFilter::default())
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|entry| (entry.entry.version, entry.is_live))
+            .collect::<Vec<_>>(),
+        vec![(1, false), (2, false), (3, true)]
+    );
+    assert!(history[0].entry.indices.is_empty());
+    let index = &history[1].entry.indices[0];
+    assert_eq!(index.name, "key_idx");
+    assert_eq!(index.index_type, index_type);
+    assert_eq!(index.columns, vec!["key".to_string()]);
+    assert_eq!((index.indexed_fragments, index.indexed_rows), (1, 10));
+    assert_eq!(history[1].entry.tags, vec!["audit", "prod"]);
+    // The appended fragment is not indexed
+    let index = &history[2].entry.indices[0];
+    assert_eq!((index.indexed_fragments, index.indexed_rows), (1, 10));
+    assert_eq!(history[2].entry.manifest_summary.total_fragments, 2);
+    assert!(history[2].entry.tags.is_empty());
+
+    let prod = dataset
+        .version_history(VersionHistoryFilter {
+            tag: Some("prod".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(prod.len(), 1);
+    assert_eq!(prod[0].entry.version, 2);
+}
+
+#[tokio::test]
+async fn test_restore_from_archive() {
+    use crate::dataset::archive::{VersionArchive, VersionArchiveConfig, VersionArchiveEntry};
+
+    let test_dir = TempStdDir::default();
+    let test_uri = test_dir.to_str().unwrap();
+    let data = lance_datagen::gen_batch()
+        .col("key", array::step::<Int32Type>())
+        .into_batch_rows(RowCount::from(10))
+        .unwrap();
+    let schema = data.schema();
+    let mut dataset = Dataset::write(
+        RecordBatchIterator::new([Ok(data.clone())], schema.clone()),
+        test_uri,
+        None,
+    )
+    .await
+    .unwrap();
+    for _ in 0..2 {
+        dataset
+            .append(
+                RecordBatchIterator::new([Ok(data.clone())], schema.clone()),
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    // Archive versions 1 and 2, then lose their manifests but keep the
+    // transaction and data files
+    let mut archive = VersionArchive::load_or_new(
+        dataset.base.clone(),
+        dataset.object_store.clone(),
+        VersionArchiveConfig::default(),
+    )
+    .await
+    .unwrap();
+    let mut timestamps = Vec::new();
+    for version in 1..=2 {
+        let old = dataset.checkout_version(version).await.unwrap();
+        timestamps.push(old.manifest.timestamp().timestamp_millis());
+        let transaction = old.read_transaction().await.unwrap();
+        archive.add_entries(&[VersionArchiveEntry::from_manifest(
+            &old.manifest,
+            transaction.as_ref(),
+            &[],
+        )]);
+        dataset
+            .object_store
+            .delete(&old.manifest_location.path)
+            .await
+            .unwrap();
+    }
+    archive.flush().await.unwrap();
+    assert!(dataset.checkout_version(2).await.is_err());
+
+    dataset.restore_from_archive(2).await.unwrap();
+    assert_eq!(dataset.version().version, 4);
+    assert_eq!(dataset.count_rows(None).await.unwrap(), 20);
+    let restored = dataset.checkout_version(2).await.unwrap();
+    assert_eq!(restored.count_rows(None).await.unwrap(), 20);
+    assert_eq!(
+        restored.manifest.timestamp().timestamp_millis(),
+        timestamps[1]
+    );
+
+    // Version 1 is only rebuilt when it is restored itself
+    assert!(dataset.checkout_version(1).await.is_err());
+    dataset.restore_from_archive(1).await.unwrap();
+    assert_eq!(dataset.version().version, 5);
+    assert_eq!(dataset.count_rows(None).await.unwrap(), 10);
+}
+
+#[tokio::test]
+async fn test_delta_manifests() {
+    use lance_table::feature_flags::FLAG_DELTA_MANIFEST;
+    use lance_table::format::DELTA_MANIFEST_MAX_DEPTH_KEY;
+
+    let test_uri = TempStrDir::default();
+    let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+        "i",
+        DataType::UInt32,
+        false,
+    )]));
+    let reader = |values: std::ops::Range<u32>| {
+        let data = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(UInt32Array::from_iter_values(values))],
+        )
+        .unwrap();
+        RecordBatchIterator::new(vec![Ok(data)], schema.clone())
+    };
+
+    let mut dataset = Dataset::write(reader(0..10), &test_uri, None)
+        .await
+        .unwrap();
+    dataset
+        .update_config([(DELTA_MANIFEST_MAX_DEPTH_KEY, "2")])
+        .await
+        .unwrap();
+    for start in [10, 20, 30] {
+        dataset
+            .append(reader(start..start + 10), None)
+            .await
+            .unwrap();
+    }
+    dataset.delete("i < 10").await.unwrap();
+    assert_eq!(dataset.version().version, 6);
+
+    // Versions 3 and 4 are deltas, version 5 is full again after the maximum
+    // depth, and version 6 removes a fragment of version 5.
+    let dataset = Dataset::open(&test_uri).await.unwrap();
+    let mut depths = Vec::new();
+    for version in 2..=6 {
+        let dataset = dataset.checkout_version(version).await.unwrap();
+        let manifest = dataset.manifest();
+        assert_eq!(
+            manifest.reader_feature_flags & FLAG_DELTA_MANIFEST != 0,
+            manifest.delta_base.is_some(),
+        );
+        let fragment_ids = manifest
+            .fragments
+            .iter()
+            .map(|fragment| fragment.id)
+            .collect::<Vec<_>>();
+        let expected_ids = match version {
+            2 => vec![0],
+            6 => vec![1, 2, 3],
+            _ => (0..version - 1).collect(),
+        };
+        assert_eq!(fragment_ids, expected_ids, "version {version}");
+        depths.push(manifest.delta_base.as_ref().map(|base| base.depth));
+    }
+    assert_eq!(depths, vec![None, Some(1), Some(2), None, Some(1)]);
+    assert_eq!(dataset.count_rows(None).await.unwrap(), 30);
+}
+
+#[tokio::test]
+async fn test_cleanup_keeps_delta_bases_readable() {
+    use lance_table::format::DELTA_MANIFEST_MAX_DEPTH_KEY;
+
+    let test_uri = TempStrDir::default();
+    let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+        "i",
+        DataType::UInt32,
+        false,
+    )]));
+    let reader = |values: std::ops::Range<u32>| {
+        let data = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(UInt32Array::from_iter_values(values))],
+        )
+        .unwrap();
+        RecordBatchIterator::new(vec![Ok(data)], schema.clone())
+    };
+
+    let mut dataset = Dataset::write(reader(0..10), &test_uri, None)
+        .await
+        .unwrap();
+    dataset
+        .update_config([(DELTA_MANIFEST_MAX_DEPTH_KEY, "2")])
+        .await
+        .unwrap();
+    dataset.append(reader(10..20), None).await.unwrap();
+    dataset.delete("i < 10").await.unwrap();
+    assert_eq!(dataset.version().version, 4);
+
+    // Version 4 is a delta of version 3, which is a delta of version 2.  Only
+    // version 2 references the data file of the deleted fragment.
+    dataset
+        .cleanup_old_versions(chrono::Duration::zero(), Some(true), Some(false))
+        .await
+        .unwrap();
+
+    let dataset = Dataset::open(&test_uri).await.unwrap();
+    assert_eq!(
+        dataset
+            .versions()
+            .await
+            .unwrap()
+            .iter()
+            .map(|version| version.version)
+            .collect::<Vec<_>>(),
+        vec![2, 3, 4]
+    );
+    assert!(dataset.checkout_version(1).await.is_err());
+    for (version, num_rows) in [(2, 10), (3, 20), (4, 10)] {
+        let batch = dataset
+            .checkout_version(version)
+            .await
+            .unwrap()
+            .scan()
+            .try_into_batch()
+            .await
+            .unwrap();
+        assert_eq!(batch.num_rows(), num_rows, "version {version}");
+    }
+}
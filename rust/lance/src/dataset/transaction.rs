@@ -1750,6 +1750,8 @@ impl Transaction {
         let mut manifest = read_manifest(object_store, &location.path, location.size).await?;
         manifest.set_timestamp(timestamp_to_nanos(config.timestamp));
         manifest.transaction_file = Some(tx_path.to_string());
+        // The restored version stores its full fragment list.
+        manifest.delta_base = None;
         let indices = read_manifest_indexes(object_store, &location, &manifest).await?;
         manifest.max_fragment_id = manifest
             .max_fragment_id
@@ -4094,6 +4096,8 @@ mod tests {
             file_minor_version: 0,
             file_size_bytes: CachedFileSize::new(1000),
             base_id: None,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         });
 
         // Add a data file with all fields tombstoned
@@ -4105,6 +4109,8 @@ mod tests {
             file_minor_version: 0,
             file_size_bytes: CachedFileSize::new(500),
             base_id: None,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         });
 
         // Add a data file with mixed tombstoned and valid fields
@@ -4116,6 +4122,8 @@ mod tests {
             file_minor_version: 0,
             file_size_bytes: CachedFileSize::new(750),
             base_id: None,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         });
 
         // Add another fully tombstoned file
@@ -4127,6 +4135,8 @@ mod tests {
             file_minor_version: 0,
             file_size_bytes: CachedFileSize::new(250),
             base_id: None,
+            zone_maps: Arc::from([]),
+            partition_values: Arc::from([]),
         });
 
         let mut fragments = vec![fragment];
@@ -2,10 +2,14 @@
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
 use crate::Dataset;
-use arrow_schema::{Schema, SchemaRef};
+use crate::dataset::builder::DatasetBuilder;
+use arrow_array::builder::{ListBuilder, MapBuilder, StringBuilder};
+use arrow_array::{ArrayRef, RecordBatch, TimestampMicrosecondArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
 use async_trait::async_trait;
 use datafusion::catalog::{Session, TableFunctionImpl, TableProvider};
 use datafusion::common::{DataFusionError, ScalarValue};
+use datafusion::datasource::memory::MemorySourceConfig;
 use datafusion_expr::{Expr, TableType};
 use datafusion_physical_plan::ExecutionPlan;
 use lance_arrow::SchemaExt;
@@ -255,6 +259,250 @@ impl FtsQueryUDTFBuilder {
     }
 }
 
+/// A table of dataset metadata, loaded when the table is scanned.
+#[derive(Debug)]
+struct MetadataTableProvider {
+    kind: MetadataTable,
+    uri: String,
+    version: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MetadataTable {
+    Versions,
+    Fragments,
+}
+
+impl MetadataTable {
+    fn schema(&self) -> SchemaRef {
+        match self {
+            Self::Versions => Arc::new(Schema::new(vec![
+                Field::new("version", DataType::UInt64, false),
+                Field::new(
+                    "timestamp",
+                    DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                    false,
+                ),
+                Field::new_map(
+                    "metadata",
+                    "entries",
+                    Field::new("keys", DataType::Utf8, false),
+                    Field::new("values", DataType::Utf8, true),
+                    false,
+                    false,
+                ),
+            ])),
+            Self::Fragments => Arc::new(Schema::new(vec![
+                Field::new("id", DataType::UInt64, false),
+                Field::new("physical_rows", DataType::UInt64, true),
+                Field::new("num_deleted_rows", DataType::UInt64, true),
+                Field::new("num_rows", DataType::UInt64, true),
+                Field::new(
+                    "data_files",
+                    DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+                    false,
+                ),
+            ])),
+        }
+    }
+}
+
+impl MetadataTableProvider {
+    async fn load(&self) -> lance_core::Result<RecordBatch> {
+        let mut builder = DatasetBuilder::from_uri(&self.uri);
+        if let Some(version) = self.version {
+            builder = builder.with_version(version);
+        }
+        let dataset = builder.load().await?;
+        let columns: Vec<ArrayRef> = match self.kind {
+            MetadataTable::Versions => {
+                let versions = dataset.versions().await?;
+                let mut metadata =
+                    MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+                for version in &versions {
+                    for (key, value) in &version.metadata {
+                        metadata.keys().append_value(key);
+                        metadata.values().append_value(value);
+                    }
+                    metadata.append(true)?;
+                }
+                vec![
+                    Arc::new(UInt64Array::from_iter_values(
+                        versions.iter().map(|version| version.version),
+                    )),
+                    Arc::new(
+                        TimestampMicrosecondArray::from_iter_values(
+                            versions
+                                .iter()
+                                .map(|version| version.timestamp.timestamp_micros()),
+                        )
+                        .with_timezone("UTC"),
+                    ),
+                    Arc::new(metadata.finish()),
+                ]
+            }
+            MetadataTable::Fragments => {
+                let fragments = dataset.fragments();
+                let mut data_files = ListBuilder::new(StringBuilder::new());
+                for fragment in fragments.iter() {
+                    for file in &fragment.files {
+                        data_files.values().append_value(&file.path);
+                    }
+                    data_files.append(true);
+                }
+                vec![
+                    Arc::new(UInt64Array::from_iter_values(
+                        fragments.iter().map(|fragment| fragment.id),
+                    )),
+                    Arc::new(UInt64Array::from_iter(
+                        fragments
+                            .iter()
+                            .map(|fragment| fragment.physical_rows.map(|rows| rows as u64)),
+                    )),
+                    Arc::new(UInt64Array::from_iter(fragments.iter().map(|fragment| {
+                        match &fragment.deletion_file {
+                            Some(deletion_file) => {
+                                deletion_file.num_deleted_rows.map(|rows| rows as u64)
+                            }
+                            None => Some(0),
+                        }
+                    }))),
+                    Arc::new(UInt64Array::from_iter(
+                        fragments
+                            .iter()
+                            .map(|fragment| fragment.num_rows().map(|rows| rows as u64)),
+                    )),
+                    Arc::new(data_files.finish()),
+                ]
+            }
+        };
+        Ok(RecordBatch::try_new(self.kind.schema(), columns)?)
+    }
+}
+
+#[async_trait]
+impl TableProvider for MetadataTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.kind.schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Temporary
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
+        let batch = self.load().await?;
+        Ok(MemorySourceConfig::try_new_exec(
+            &[vec![batch]],
+            self.schema(),
+            projection.cloned(),
+        )?)
+    }
+}
+
+/// The `lance_versions('uri')` table function, the versions of the dataset
+/// at `uri`.
+///
+/// Each row has the `version` number, the `timestamp` it was committed at and
+/// its `metadata`.
+///
+/// ```ignore
+/// ctx.register_udtf("lance_versions", Arc::new(LanceVersionsUDTF));
+/// let df = ctx
+///     .sql("SELECT version, timestamp FROM lance_versions('s3://bucket/table.lance')")
+///     .await?;
+/// ```
+#[derive(Debug)]
+pub struct LanceVersionsUDTF;
+
+impl TableFunctionImpl for LanceVersionsUDTF {
+    fn call(&self, expr: &[Expr]) -> datafusion::common::Result<Arc<dyn TableProvider>> {
+        let [uri] = expr else {
+            return Err(DataFusionError::Plan(
+                "lance_versions takes the uri of a dataset as its only parameter".to_string(),
+            ));
+        };
+        Ok(Arc::new(MetadataTableProvider {
+            kind: MetadataTable::Versions,
+            uri: uri_arg("lance_versions", uri)?,
+            version: None,
+        }))
+    }
+}
+
+/// The `lance_fragments('uri' [, version])` table function, the fragments of
+/// the dataset at `uri`, at its latest version or at `version`.
+///
+/// Each row has the fragment `id`, its `physical_rows`, `num_deleted_rows`
+/// and `num_rows`, and the paths of its `data_files`. Row counts are null
+/// when they are not recorded in the manifest.
+///
+/// ```ignore
+/// ctx.register_udtf("lance_fragments", Arc::new(LanceFragmentsUDTF));
+/// let df = ctx
+///     .sql("SELECT id, num_rows FROM lance_fragments('s3://bucket/table.lance', 3)")
+///     .await?;
+/// ```
+#[derive(Debug)]
+pub struct LanceFragmentsUDTF;
+
+impl TableFunctionImpl for LanceFragmentsUDTF {
+    fn call(&self, expr: &[Expr]) -> datafusion::common::Result<Arc<dyn TableProvider>> {
+        let (uri, version) = match expr {
+            [uri] => (uri, None),
+            [uri, version] => (uri, Some(version)),
+            _ => {
+                return Err(DataFusionError::Plan(
+                    "lance_fragments takes the uri of a dataset and an optional version as parameters"
+                        .to_string(),
+                ));
+            }
+        };
+        let version = version.map(version_arg).transpose()?;
+        Ok(Arc::new(MetadataTableProvider {
+            kind: MetadataTable::Fragments,
+            uri: uri_arg("lance_fragments", uri)?,
+            version,
+        }))
+    }
+}
+
+fn version_arg(expr: &Expr) -> datafusion::common::Result<u64> {
+    if let Expr::Literal(value, _) = expr
+        && value.data_type().is_integer()
+        && let Ok(ScalarValue::UInt64(Some(version))) = value.cast_to(&DataType::UInt64)
+    {
+        return Ok(version);
+    }
+    Err(DataFusionError::Plan(
+        "lance_fragments version should be a non-negative integer".to_string(),
+    ))
+}
+
+fn uri_arg(function: &str, expr: &Expr) -> datafusion::common::Result<String> {
+    match expr {
+        Expr::Literal(
+            ScalarValue::Utf8(Some(uri))
+            | ScalarValue::LargeUtf8(Some(uri))
+            | ScalarValue::Utf8View(Some(uri)),
+            _,
+        ) => Ok(uri.clone()),
+        _ => Err(DataFusionError::Plan(format!(
+            "{function} uri should be a string"
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Dataset;
@@ -348,4 +596,100 @@ mod tests {
             .iter()
             .for_each(|v| assert!([1u64, 2u64, 4u64].contains(&v.unwrap())));
     }
+
+    #[tokio::test]
+    async fn test_metadata_udtfs() {
+        use super::{LanceFragmentsUDTF, LanceVersionsUDTF};
+        use crate::dataset::{WriteMode, WriteParams};
+        use arrow_array::cast::AsArray;
+        use arrow_array::types::UInt64Type;
+        use datafusion::common::assert_batches_eq;
+
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let uri = tmp_dir.path().to_str().unwrap();
+        let batch = |values: std::ops::Range<i32>| {
+            let batch = RecordBatch::try_new(
+                arrow_schema::Schema::new(vec![Field::new("x", DataType::Int32, false)]).into(),
+                vec![Arc::new(Int32Array::from_iter_values(values))],
+            )
+            .unwrap();
+            let schema = batch.schema();
+            RecordBatchIterator::new(vec![Ok(batch)], schema)
+        };
+        let params = WriteParams {
+            max_rows_per_file: 5,
+            ..Default::default()
+        };
+        let mut dataset = Dataset::write(batch(0..10), uri, Some(params.clone()))
+            .await
+            .unwrap();
+        let params = WriteParams {
+            mode: WriteMode::Append,
+            ..params
+        };
+        Dataset::write(batch(10..15), uri, Some(params))
+            .await
+            .unwrap();
+        dataset.checkout_latest().await.unwrap();
+        dataset.delete("x < 2").await.unwrap();
+
+        let ctx = SessionContext::new();
+        ctx.register_udtf("lance_versions", Arc::new(LanceVersionsUDTF));
+        ctx.register_udtf("lance_fragments", Arc::new(LanceFragmentsUDTF));
+
+        let versions = ctx
+            .sql(&format!(
+                "SELECT version FROM lance_versions('{uri}') ORDER BY version"
+            ))
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let versions = versions[0].column(0).as_primitive::<UInt64Type>();
+        assert_eq!(versions.values(), &[1, 2, 3]);
+
+        let fragments = ctx
+            .sql(&format!(
+                "SELECT id, physical_rows, num_deleted_rows, num_rows, array_length(data_files) AS files \
+                 FROM lance_fragments('{uri}') ORDER BY id"
+            ))
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_batches_eq!(
+            [
+                "+----+---------------+------------------+----------+-------+",
+                "| id | physical_rows | num_deleted_rows | num_rows | files |",
+                "+----+---------------+------------------+----------+-------+",
+                "| 0  | 5             | 2                | 3        | 1     |",
+                "| 1  | 5             | 0                | 5        | 1     |",
+                "| 2  | 5             | 0                | 5        | 1     |",
+                "+----+---------------+------------------+----------+-------+",
+            ],
+            &fragments
+        );
+
+        let fragments = ctx
+            .sql(&format!(
+                "SELECT sum(num_rows) FROM lance_fragments('{uri}', 1)"
+            ))
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            fragments[0].column(0).as_primitive::<UInt64Type>().value(0),
+            10
+        );
+
+        let err = ctx
+            .sql(&format!("SELECT * FROM lance_fragments('{uri}', -1)"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("non-negative integer"), "{err}");
+    }
 }
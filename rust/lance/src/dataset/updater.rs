@@ -10,6 +10,7 @@ use lance_table::format::Fragment;
 use lance_table::utils::stream::ReadBatchFutStream;
 
 use super::Dataset;
+use super::encryption::dataset_column_keys;
 use super::fragment::FragmentReader;
 use super::scanner::get_default_batch_size;
 use super::write::{GenericWriter, open_writer};
@@ -146,11 +147,13 @@ impl Updater {
             .data_storage_format
             .lance_file_version()?;
 
+        let encrypted_columns = dataset_column_keys(self.dataset(), &schema, None).await?;
         open_writer(
             &self.fragment.dataset().object_store,
             &schema,
             &self.fragment.dataset().base,
             data_storage_version,
+            encrypted_columns,
         )
         .await
     }
@@ -8,7 +8,7 @@ use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use futures::{Stream, StreamExt, TryStreamExt};
 use lance_arrow::BLOB_META_KEY;
 use lance_core::datatypes::{
-    NullabilityComparison, OnMissing, OnTypeMismatch, SchemaCompareOptions,
+    Field, NullabilityComparison, OnMissing, OnTypeMismatch, SchemaCompareOptions,
 };
 use lance_core::error::LanceOptionExt;
 use lance_core::utils::tempfile::TempDir;
@@ -17,16 +17,22 @@ use lance_core::{Error, Result, datatypes::Schema};
 use lance_datafusion::chunker::{break_stream, chunk_stream};
 use lance_datafusion::spill::{SpillReceiver, SpillSender, create_replay_spill};
 use lance_datafusion::utils::StreamingWriteSource;
+use lance_encoding::compression_config::CompressionParams;
+use lance_encoding::encodings::physical::block::CompressionScheme;
+use lance_file::encryption::EncryptionKey;
 use lance_file::previous::writer::{
     FileWriter as PreviousFileWriter, ManifestProvider as PreviousManifestProvider,
 };
 use lance_file::version::LanceFileVersion;
 use lance_file::writer::{self as current_writer, FileWriterOptions};
 use lance_io::object_store::{ObjectStore, ObjectStoreParams, ObjectStoreRegistry};
-use lance_table::format::{BasePath, DataFile, Fragment};
+use lance_table::format::{
+    BasePath, DataFile, Fragment, PartitionField, PartitionSpec, PartitionTransform, PartitionValue,
+};
 use lance_table::io::commit::{CommitHandler, commit_handler_from_url};
 use lance_table::io::manifest::ManifestDescribing;
 use object_store::path::Path;
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::num::NonZero;
 use std::sync::Arc;
@@ -40,7 +46,10 @@ use crate::dataset::blob::{
 use crate::session::Session;
 
 use super::DATA_DIR;
+use super::encryption::{EncryptionParams, write_column_keys};
+use super::fragment::partition::{PartitionRouter, check_partition_field};
 use super::fragment::write::generate_random_filename;
+use super::fragment::zone_map::ZoneMapCollector;
 use super::progress::{NoopFragmentWriteProgress, WriteFragmentProgress};
 use super::transaction::Transaction;
 use super::utils::SchemaAdapter;
@@ -52,7 +61,7 @@ pub mod merge_insert;
 mod retry;
 pub mod update;
 
-pub use super::progress::{WriteProgressFn, WriteStats};
+pub use super::progress::{WriteProgressFn, WriteStats, WriteSummary, WriteSummaryFn};
 pub use commit::{CommitBuilder, DEFAULT_COMMIT_TIMEOUT};
 pub use delete::{DeleteBuilder, DeleteResult, UncommittedDelete};
 pub use insert::InsertBuilder;
@@ -186,6 +195,41 @@ impl Default for AutoCleanupParams {
     }
 }
 
+/// A column to partition a dataset by, see [`WriteParams::partition_by`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionColumn {
+    /// The name of a top-level column.
+    pub column: String,
+    pub transform: PartitionTransform,
+}
+
+impl PartitionColumn {
+    /// Partition by the values of `column`.
+    pub fn identity(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            transform: PartitionTransform::Identity,
+        }
+    }
+
+    /// Partition by a hash of `column` into `buckets` buckets.
+    pub fn hash(column: impl Into<String>, buckets: u32) -> Self {
+        Self {
+            column: column.into(),
+            transform: PartitionTransform::Hash { buckets },
+        }
+    }
+
+    /// Partition an integer, date or timestamp `column` into ranges of `width`,
+    /// in the unit of the column.
+    pub fn range(column: impl Into<String>, width: u64) -> Self {
+        Self {
+            column: column.into(),
+            transform: PartitionTransform::Range { width },
+        }
+    }
+}
+
 /// Dataset Write Parameters
 #[derive(Debug, Clone)]
 pub struct WriteParams {
@@ -313,6 +357,38 @@ pub struct WriteParams {
     /// When a pack file reaches this size, a new one is started.
     /// If not set, defaults to 1 GiB.
     pub blob_pack_file_size_threshold: Option<usize>,
+
+    /// Per-column (or per-type) compression settings, e.g. zstd level 9 for a cold
+    /// text column and lz4 for a hot embedding column.
+    ///
+    /// The settings are recorded as `lance-encoding:*` metadata on the matching fields
+    /// of the written schema.  When a dataset is created or overwritten that schema
+    /// becomes the dataset schema, so later appends and compactions keep using the same
+    /// settings.  Settings already present in the field metadata take precedence.
+    pub compression_params: Option<CompressionParams>,
+
+    /// The columns to partition a new or overwritten dataset by.
+    ///
+    /// The partition spec is stored in the manifest config under
+    /// [`PARTITION_SPEC_KEY`](lance_table::format::PARTITION_SPEC_KEY), and every later write of the dataset, including
+    /// updates and compaction, writes the rows of each partition to separate
+    /// fragments.  The scanner skips the fragments of partitions that cannot
+    /// match a filter.  Each write keeps a data file open per partition it
+    /// sees, so columns with many distinct values should be hashed.
+    ///
+    /// Partitioned writes require the v2 file format.  Empty by default, in
+    /// which case the spec of the dataset, if any, is used.
+    pub partition_by: Vec<PartitionColumn>,
+
+    /// The columns to encrypt in a new or overwritten dataset, and the key
+    /// provider that generates and decrypts their data keys.
+    ///
+    /// The encrypted data keys are stored in the manifest config under
+    /// [`ENCRYPTION_SPEC_KEY`](lance_table::format::ENCRYPTION_SPEC_KEY) and
+    /// every later write of the dataset encrypts the same columns.  Writes
+    /// to an encrypted dataset need the key provider, unless the dataset was
+    /// opened with it.  Column encryption requires file version 2.1 or later.
+    pub encryption: Option<EncryptionParams>,
 }
 
 impl Default for WriteParams {
@@ -342,6 +418,9 @@ impl Default for WriteParams {
             allow_external_blob_outside_bases: false,
             external_blob_mode: ExternalBlobMode::Reference,
             blob_pack_file_size_threshold: None,
+            compression_params: None,
+            partition_by: Vec::new(),
+            encryption: None,
         }
     }
 }
@@ -360,6 +439,36 @@ impl WriteParams {
         self.data_storage_version.unwrap_or_default()
     }
 
+    /// The partition spec given by [`Self::partition_by`], resolved against
+    /// the written `schema`.
+    pub fn partition_spec(&self, schema: &Schema) -> Result<Option<PartitionSpec>> {
+        if self.partition_by.is_empty() {
+            return Ok(None);
+        }
+        let fields = self
+            .partition_by
+            .iter()
+            .map(|partition_column| {
+                let field = schema
+                    .fields
+                    .iter()
+                    .find(|field| field.name == partition_column.column)
+                    .ok_or_else(|| {
+                        Error::invalid_input(format!(
+                            "Partition column {} is not a top-level column of the written data",
+                            partition_column.column
+                        ))
+                    })?;
+                check_partition_field(field, &partition_column.transform)?;
+                Ok(PartitionField {
+                    field_id: field.id,
+                    transform: partition_column.transform,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        PartitionSpec::try_new(fields).map(Some)
+    }
+
     pub fn store_registry(&self) -> Arc<ObjectStoreRegistry> {
         self.session
             .as_ref()
@@ -390,6 +499,16 @@ impl WriteParams {
         }
     }
 
+    /// Set the per-column compression settings for this WriteParams.
+    ///
+    /// See [`Self::compression_params`] for how the settings are persisted.
+    pub fn with_compression_params(self, compression_params: CompressionParams) -> Self {
+        Self {
+            compression_params: Some(compression_params),
+            ..self
+        }
+    }
+
     /// Set the initial_bases for this WriteParams.
     ///
     /// This specifies new base paths to register in the manifest during dataset creation.
@@ -520,6 +639,8 @@ pub async fn do_write_fragments(
         .unwrap_or_else(|| params.store_registry());
     let source_store_params = params.store_params.clone().unwrap_or_default();
 
+    let encrypted_columns = write_column_keys(dataset, &params, schema).await?;
+
     let writer_generator = WriterGenerator::new(
         object_store.clone(),
         base_dir,
@@ -532,7 +653,20 @@ pub async fn do_write_fragments(
         source_store_registry,
         source_store_params,
         params.blob_pack_file_size_threshold,
-    );
+    )
+    .with_encrypted_columns(encrypted_columns);
+    if let Some(router) = partition_router(dataset, &params, schema, storage_version)? {
+        return do_write_partitioned_fragments(
+            buffered_reader,
+            &router,
+            &writer_generator,
+            &object_store,
+            base_dir,
+            &params,
+        )
+        .await;
+    }
+
     let mut writer: Option<Box<dyn GenericWriter>> = None;
     let mut num_rows_in_current_file = 0;
     let mut fragments: Vec<Fragment> = Vec::new();
@@ -634,6 +768,158 @@ pub async fn do_write_fragments(
     Ok(fragments)
 }
 
+/// The router of a write: by [`WriteParams::partition_by`] for new and
+/// overwritten datasets, by the partition spec of the dataset otherwise.
+fn partition_router(
+    dataset: Option<&Dataset>,
+    params: &WriteParams,
+    schema: &Schema,
+    storage_version: LanceFileVersion,
+) -> Result<Option<PartitionRouter>> {
+    let spec = match params.partition_spec(schema)? {
+        Some(_) if dataset.is_some() && matches!(params.mode, WriteMode::Append) => {
+            return Err(Error::invalid_input(
+                "The partition columns can only be set when creating or overwriting a dataset",
+            ));
+        }
+        Some(spec) => spec,
+        None => {
+            let dataset_spec = dataset
+                .map(|dataset| dataset.manifest.partition_spec())
+                .transpose()?
+                .flatten();
+            let Some(spec) = dataset_spec else {
+                return Ok(None);
+            };
+            spec
+        }
+    };
+    let Some(router) = PartitionRouter::try_new(&spec, schema)? else {
+        return Ok(None);
+    };
+    if storage_version == LanceFileVersion::Legacy {
+        return Err(Error::invalid_input(
+            "Partitioned writes require the v2 file format",
+        ));
+    }
+    Ok(Some(router))
+}
+
+/// A data file of a partitioned write that is still open.
+struct PartitionFile {
+    writer: Box<dyn GenericWriter>,
+    /// The index of the file's fragment in the written fragments
+    fragment: usize,
+    num_rows: u32,
+}
+
+impl PartitionFile {
+    /// Finishes the file and adds it to its fragment.
+    async fn finish<'a>(
+        mut self,
+        partition_values: Vec<PartitionValue>,
+        fragments: &'a mut [Fragment],
+    ) -> Result<&'a Fragment> {
+        let (num_rows, data_file) = self.writer.finish().await?;
+        info!(target: TRACE_FILE_AUDIT, mode=AUDIT_MODE_CREATE, r#type=AUDIT_TYPE_DATA, path = &data_file.path);
+        debug_assert_eq!(num_rows, self.num_rows);
+        let fragment = &mut fragments[self.fragment];
+        fragment.physical_rows = Some(num_rows as usize);
+        fragment
+            .files
+            .push(data_file.with_partition_values(partition_values));
+        Ok(fragment)
+    }
+}
+
+/// Adds the last data file of `fragment` to the statistics of a write.
+fn record_written_file(stats: &mut WriteStats, fragment: &Fragment) {
+    let data_file = fragment.files.last();
+    stats.bytes_written += data_file
+        .and_then(|data_file| data_file.file_size_bytes.get())
+        .map_or(0, |size| size.get());
+    stats.rows_written += fragment.physical_rows.unwrap_or(0) as u64;
+    stats.files_written += 1;
+}
+
+/// Writes the rows of each partition to separate fragments.
+///
+/// A data file is kept open for every partition seen so far and is finished
+/// once it reaches the file size limits or the data ends.
+async fn do_write_partitioned_fragments(
+    mut data: impl Stream<Item = Result<Vec<RecordBatch>>> + Unpin,
+    router: &PartitionRouter,
+    writer_generator: &WriterGenerator,
+    object_store: &ObjectStore,
+    base_dir: &Path,
+    params: &WriteParams,
+) -> Result<Vec<Fragment>> {
+    let mut open_files: HashMap<Vec<PartitionValue>, PartitionFile> = HashMap::new();
+    let mut fragments: Vec<Fragment> = Vec::new();
+    let mut stats = WriteStats::default();
+
+    let loop_result: Result<()> = async {
+        let mut finished = Vec::new();
+        while let Some(batch_chunk) = data.next().await {
+            for batch in batch_chunk? {
+                for (partition_values, batch) in router.split(&batch)? {
+                    let file = match open_files.entry(partition_values.clone()) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            let (writer, fragment) = writer_generator.new_writer().await?;
+                            params.progress.begin(&fragment).await?;
+                            fragments.push(fragment);
+                            entry.insert(PartitionFile {
+                                writer,
+                                fragment: fragments.len() - 1,
+                                num_rows: 0,
+                            })
+                        }
+                    };
+                    file.writer.write(std::slice::from_ref(&batch)).await?;
+                    file.num_rows += batch.num_rows() as u32;
+                    if file.num_rows >= params.max_rows_per_file as u32
+                        || file.writer.tell().await? >= params.max_bytes_per_file as u64
+                    {
+                        finished.push(partition_values);
+                    }
+                }
+                for partition_values in finished.drain(..) {
+                    let file = open_files.remove(&partition_values).unwrap();
+                    let fragment = file.finish(partition_values, &mut fragments).await?;
+                    record_written_file(&mut stats, fragment);
+                    params.progress.complete(fragment).await?;
+                    if let Some(cb) = &params.write_progress {
+                        cb.call(stats.clone());
+                    }
+                }
+            }
+        }
+
+        // Finish the remaining files in the order they were opened
+        let mut remaining = open_files.drain().collect::<Vec<_>>();
+        remaining.sort_by_key(|(_, file)| file.fragment);
+        for (partition_values, file) in remaining {
+            let fragment = file.finish(partition_values, &mut fragments).await?;
+            record_written_file(&mut stats, fragment);
+            params.progress.complete(fragment).await?;
+        }
+        if let Some(cb) = &params.write_progress {
+            cb.call(stats.clone());
+        }
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = loop_result {
+        // Drop the writers so their in-progress files are cleaned up
+        open_files.clear();
+        cleanup_data_fragments(object_store, base_dir, &fragments).await;
+        return Err(e);
+    }
+    Ok(fragments)
+}
+
 /// Best-effort cleanup of data files for fragments that were written but not committed.
 ///
 /// Contract:
@@ -937,7 +1223,7 @@ pub async fn write_fragments_internal(
     params.max_rows_per_group = std::cmp::min(params.max_rows_per_group, params.max_rows_per_file);
     validate_external_blob_write_params(&params)?;
 
-    let (schema, storage_version) = if let Some(dataset) = dataset {
+    let (mut schema, storage_version) = if let Some(dataset) = dataset {
         match params.mode {
             WriteMode::Append | WriteMode::Create => {
                 // Append mode, so we need to check compatibility
@@ -984,6 +1270,10 @@ pub async fn write_fragments_internal(
         (converted_schema, params.storage_version_or_default())
     };
 
+    if let Some(compression_params) = &params.compression_params {
+        record_compression_params(&mut schema, compression_params)?;
+    }
+
     if storage_version < LanceFileVersion::V2_2 && schema.fields.iter().any(|f| f.is_blob_v2()) {
         return Err(Error::invalid_input(format!(
             "Blob v2 requires file version >= 2.2 (got {:?})",
@@ -1070,11 +1360,15 @@ struct V2WriterAdapter {
     path: String,
     base_id: Option<u32>,
     preprocessor: Option<BlobPreprocessor>,
+    zone_maps: ZoneMapCollector,
 }
 
 #[async_trait::async_trait]
 impl GenericWriter for V2WriterAdapter {
     async fn write(&mut self, batches: &[RecordBatch]) -> Result<()> {
+        for batch in batches {
+            self.zone_maps.update(batch)?;
+        }
         if let Some(pre) = self.preprocessor.as_mut() {
             let processed = preprocess_blob_batches(batches, pre).await?;
             for batch in processed {
@@ -1116,16 +1410,43 @@ impl GenericWriter for V2WriterAdapter {
             minor,
             NonZero::new(write_summary.size_bytes),
             self.base_id,
-        );
+        )
+        .with_zone_maps(self.zone_maps.finish()?);
         Ok((write_summary.num_rows as u32, data_file))
     }
 }
 
+/// Record compression settings as field metadata, which the encoder reads when
+/// choosing how to compress each column
+fn record_compression_params(schema: &mut Schema, params: &CompressionParams) -> Result<()> {
+    fn record(field: &mut Field, params: &CompressionParams) -> Result<()> {
+        let field_params = params.get_field_params(&field.name, &field.data_type());
+        if let Some(scheme) = &field_params.compression {
+            scheme.parse::<CompressionScheme>()?;
+        }
+        for (key, value) in field_params.to_field_metadata() {
+            field.metadata.entry(key).or_insert(value);
+        }
+        for child in &mut field.children {
+            record(child, params)?;
+        }
+        Ok(())
+    }
+
+    for field in &mut schema.fields {
+        record(field, params)?;
+    }
+    Ok(())
+}
+
+/// Opens a writer of a data file, the columns in `encrypted_columns` are
+/// encrypted with their key.
 pub async fn open_writer(
     object_store: &ObjectStore,
     schema: &Schema,
     base_dir: &Path,
     storage_version: LanceFileVersion,
+    encrypted_columns: HashMap<String, EncryptionKey>,
 ) -> Result<Box<dyn GenericWriter>> {
     open_writer_with_options(
         object_store,
@@ -1134,6 +1455,7 @@ pub async fn open_writer(
         storage_version,
         WriterOptions {
             add_data_dir: true,
+            encrypted_columns,
             ..Default::default()
         },
     )
@@ -1150,6 +1472,7 @@ struct WriterOptions {
     source_store_registry: Arc<ObjectStoreRegistry>,
     source_store_params: ObjectStoreParams,
     blob_pack_file_size_threshold: Option<usize>,
+    encrypted_columns: HashMap<String, EncryptionKey>,
 }
 
 async fn open_writer_with_options(
@@ -1168,6 +1491,7 @@ async fn open_writer_with_options(
         source_store_registry,
         source_store_params,
         blob_pack_file_size_threshold,
+        encrypted_columns,
     } = options;
 
     let data_file_key = generate_random_filename();
@@ -1182,6 +1506,11 @@ async fn open_writer_with_options(
     let full_path = data_dir.clone().join(filename.as_str());
 
     let writer = if storage_version == LanceFileVersion::Legacy {
+        if !encrypted_columns.is_empty() {
+            return Err(Error::invalid_input(
+                "Column encryption requires file version 2.1 or later",
+            ));
+        }
         Box::new(V1WriterAdapter {
             writer: PreviousFileWriter::<ManifestDescribing>::try_new(
                 object_store,
@@ -1201,6 +1530,7 @@ async fn open_writer_with_options(
             schema.clone(),
             FileWriterOptions {
                 format_version: Some(storage_version),
+                encrypted_columns,
                 ..Default::default()
             },
         )?;
@@ -1225,6 +1555,7 @@ async fn open_writer_with_options(
             path: filename,
             base_id,
             preprocessor,
+            zone_maps: ZoneMapCollector::new(schema),
         };
         Box::new(writer_adapter) as Box<dyn GenericWriter>
     };
@@ -1259,6 +1590,8 @@ struct WriterGenerator {
     source_store_registry: Arc<ObjectStoreRegistry>,
     source_store_params: ObjectStoreParams,
     blob_pack_file_size_threshold: Option<usize>,
+    /// The keys of the encrypted columns, keyed by column name
+    encrypted_columns: HashMap<String, EncryptionKey>,
     /// Counter for round-robin selection
     next_base_index: AtomicUsize,
 }
@@ -1290,10 +1623,20 @@ impl WriterGenerator {
             source_store_registry,
             source_store_params,
             blob_pack_file_size_threshold,
+            encrypted_columns: HashMap::new(),
             next_base_index: AtomicUsize::new(0),
         }
     }
 
+    /// Encrypt the columns in `encrypted_columns` with their key.
+    pub fn with_encrypted_columns(
+        mut self,
+        encrypted_columns: HashMap<String, EncryptionKey>,
+    ) -> Self {
+        self.encrypted_columns = encrypted_columns;
+        self
+    }
+
     /// Select the next target base using round-robin strategy.
     /// TODO: In the future, we can develop different strategies for selecting target bases
     fn select_target_base(&self) -> Option<&TargetBaseInfo> {
@@ -1324,6 +1667,7 @@ impl WriterGenerator {
                     source_store_registry: self.source_store_registry.clone(),
                     source_store_params: self.source_store_params.clone(),
                     blob_pack_file_size_threshold: self.blob_pack_file_size_threshold,
+                    encrypted_columns: self.encrypted_columns.clone(),
                 },
             )
             .await?
@@ -1342,6 +1686,7 @@ impl WriterGenerator {
                     source_store_registry: self.source_store_registry.clone(),
                     source_store_params: self.source_store_params.clone(),
                     blob_pack_file_size_threshold: self.blob_pack_file_size_threshold,
+                    encrypted_columns: self.encrypted_columns.clone(),
                 },
             )
             .await?
@@ -3006,6 +3351,82 @@ mod tests {
         assert_eq!(dataset.schema().fields.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_compression_params_recorded_in_schema() {
+        use arrow_array::record_batch;
+        use lance_encoding::compression_config::CompressionFieldParams;
+        use lance_encoding::constants::{COMPRESSION_LEVEL_META_KEY, COMPRESSION_META_KEY};
+
+        let mut compression_params = CompressionParams::new();
+        compression_params.columns.insert(
+            "text".to_string(),
+            CompressionFieldParams {
+                compression: Some("zstd".to_string()),
+                compression_level: Some(9),
+                ..Default::default()
+            },
+        );
+        compression_params.columns.insert(
+            "emb*".to_string(),
+            CompressionFieldParams {
+                compression: Some("lz4".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let batch = record_batch!(
+            ("text", Utf8, ["a", "b", "c"]),
+            ("embedding", Float32, [1.0, 2.0, 3.0]),
+            ("id", Int32, [1, 2, 3])
+        )
+        .unwrap();
+
+        let dataset = InsertBuilder::new("memory://")
+            .with_params(&WriteParams::default().with_compression_params(compression_params))
+            .execute(vec![batch.clone()])
+            .await
+            .unwrap();
+
+        let check_schema = |dataset: &Dataset| {
+            let text = dataset.schema().field("text").unwrap();
+            assert_eq!(text.metadata[COMPRESSION_META_KEY], "zstd");
+            assert_eq!(text.metadata[COMPRESSION_LEVEL_META_KEY], "9");
+            let embedding = dataset.schema().field("embedding").unwrap();
+            assert_eq!(embedding.metadata[COMPRESSION_META_KEY], "lz4");
+            assert!(!embedding.metadata.contains_key(COMPRESSION_LEVEL_META_KEY));
+            let id = dataset.schema().field("id").unwrap();
+            assert!(id.metadata.is_empty());
+        };
+        check_schema(&dataset);
+
+        // Appends without explicit settings keep using the recorded ones
+        let dataset = InsertBuilder::new(Arc::new(dataset))
+            .with_params(&WriteParams {
+                mode: WriteMode::Append,
+                ..Default::default()
+            })
+            .execute(vec![batch.clone()])
+            .await
+            .unwrap();
+        check_schema(&dataset);
+        assert_eq!(dataset.count_rows(None).await.unwrap(), 6);
+
+        // Unknown schemes are rejected up front
+        let mut compression_params = CompressionParams::new();
+        compression_params.columns.insert(
+            "text".to_string(),
+            CompressionFieldParams {
+                compression: Some("snappy".to_string()),
+                ..Default::default()
+            },
+        );
+        let result = InsertBuilder::new("memory://")
+            .with_params(&WriteParams::default().with_compression_params(compression_params))
+            .execute(vec![batch])
+            .await;
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_disk_full_error() {
         use std::io::{self, ErrorKind};
@@ -550,6 +550,8 @@ mod tests {
                 file_minor_version: minor_version,
                 file_size_bytes: CachedFileSize::new(100),
                 base_id: None,
+                zone_maps: Arc::from([]),
+                partition_values: Arc::from([]),
             }],
             deletion_file: None,
             row_id_meta: None,
@@ -3,6 +3,7 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use arrow_array::{RecordBatch, RecordBatchIterator};
 use datafusion::execution::SendableRecordBatchStream;
@@ -13,14 +14,15 @@ use lance_core::{ROW_ADDR, ROW_ID, ROW_OFFSET};
 use lance_datafusion::utils::StreamingWriteSource;
 use lance_file::version::LanceFileVersion;
 use lance_io::object_store::ObjectStore;
-use lance_table::feature_flags::can_write_dataset;
-use lance_table::format::Fragment;
+use lance_table::feature_flags::check_write_compatibility;
+use lance_table::format::{ENCRYPTION_SPEC_KEY, Fragment, PARTITION_SPEC_KEY};
 use lance_table::io::commit::CommitHandler;
 use object_store::path::Path;
 
 use crate::Dataset;
 use crate::dataset::ReadParams;
 use crate::dataset::builder::DatasetBuilder;
+use crate::dataset::encryption::with_keys;
 use crate::dataset::transaction::{Operation, Transaction, TransactionBuilder};
 use crate::dataset::write::{validate_and_resolve_target_bases, write_fragments_internal};
 use crate::{Error, Result};
@@ -31,7 +33,7 @@ use super::WriteMode;
 use super::WriteParams;
 use super::commit::CommitBuilder;
 use super::resolve_commit_handler;
-use crate::dataset::progress::{WriteProgressFn, WriteStats};
+use crate::dataset::progress::{WriteProgressFn, WriteStats, WriteSummary, WriteSummaryFn};
 
 /// Insert or create a new dataset.
 ///
@@ -49,6 +51,7 @@ pub struct InsertBuilder<'a> {
     // TODO: make these parameters a part of the builder, and add specific methods.
     params: Option<&'a WriteParams>,
     write_progress: Option<WriteProgressFn>,
+    summary: Option<WriteSummaryFn>,
 }
 
 impl<'a> InsertBuilder<'a> {
@@ -57,6 +60,7 @@ impl<'a> InsertBuilder<'a> {
             dest: dest.into(),
             params: None,
             write_progress: None,
+            summary: None,
         }
     }
 
@@ -77,12 +81,23 @@ impl<'a> InsertBuilder<'a> {
         self
     }
 
+    /// Register a callback that is invoked once when the operation finishes.
+    ///
+    /// The callback receives a [`WriteSummary`] with the totals of the data
+    /// written and the wall time spent writing and committing, so callers can
+    /// log per-operation performance without enabling tracing.  It is not
+    /// invoked if the operation fails.
+    pub fn summary(mut self, callback: impl Fn(&WriteSummary) + Send + Sync + 'static) -> Self {
+        self.summary = Some(WriteSummaryFn::new(callback));
+        self
+    }
+
     /// Execute the insert operation with the given data.
     ///
     /// This writes the data fragments and commits them into the dataset.
     pub async fn execute(&self, data: Vec<RecordBatch>) -> Result<Dataset> {
-        let (transaction, context) = self.write_uncommitted_impl(data).await?;
-        Self::do_commit(&context, transaction).await
+        let (transaction, context, summary) = self.write_uncommitted_impl(data).await?;
+        self.commit_and_report(&context, transaction, summary).await
     }
 
     /// Execute the insert operation with the given stream.
@@ -98,8 +113,9 @@ impl<'a> InsertBuilder<'a> {
         stream: SendableRecordBatchStream,
         schema: Schema,
     ) -> Result<Dataset> {
-        let (transaction, context) = self.write_uncommitted_stream_impl(stream, schema).await?;
-        Self::do_commit(&context, transaction).await
+        let (transaction, context, summary) =
+            self.write_uncommitted_stream_impl(stream, schema).await?;
+        self.commit_and_report(&context, transaction, summary).await
     }
 
     /// Write data files, but don't commit the transaction yet.
@@ -127,7 +143,33 @@ impl<'a> InsertBuilder<'a> {
     /// # }
     /// ```
     pub async fn execute_uncommitted(&self, data: Vec<RecordBatch>) -> Result<Transaction> {
-        self.write_uncommitted_impl(data).await.map(|(t, _)| t)
+        let (transaction, _, summary) = self.write_uncommitted_impl(data).await?;
+        self.report_summary(&summary);
+        Ok(transaction)
+    }
+
+    async fn commit_and_report(
+        &self,
+        context: &WriteContext<'_>,
+        transaction: Transaction,
+        mut summary: WriteSummary,
+    ) -> Result<Dataset> {
+        let start = Instant::now();
+        let mut dataset = Self::do_commit(context, transaction).await?;
+        if let Some(encryption) = &context.params.encryption {
+            // The keys of newly encrypted columns are already known
+            dataset.file_reader_options =
+                with_keys(dataset.file_reader_options.take(), encryption.new_keys());
+        }
+        summary.commit_time = Some(start.elapsed());
+        self.report_summary(&summary);
+        Ok(dataset)
+    }
+
+    fn report_summary(&self, summary: &WriteSummary) {
+        if let Some(cb) = &self.summary {
+            cb.call(summary);
+        }
     }
 
     async fn do_commit(context: &WriteContext<'_>, transaction: Transaction) -> Result<Dataset> {
@@ -153,7 +195,7 @@ impl<'a> InsertBuilder<'a> {
     async fn write_uncommitted_impl(
         &self,
         data: Vec<RecordBatch>,
-    ) -> Result<(Transaction, WriteContext<'_>)> {
+    ) -> Result<(Transaction, WriteContext<'_>, WriteSummary)> {
         // TODO: This should be able to split the data up based on max_rows_per_file
         // and write in parallel. https://github.com/lance-format/lance/issues/1980
         if data.is_empty() {
@@ -180,7 +222,8 @@ impl<'a> InsertBuilder<'a> {
         source: impl StreamingWriteSource,
     ) -> Result<Transaction> {
         let (stream, schema) = source.into_stream_and_schema().await?;
-        let (transaction, _) = self.write_uncommitted_stream_impl(stream, schema).await?;
+        let (transaction, _, summary) = self.write_uncommitted_stream_impl(stream, schema).await?;
+        self.report_summary(&summary);
         Ok(transaction)
     }
 
@@ -188,7 +231,7 @@ impl<'a> InsertBuilder<'a> {
         &self,
         stream: SendableRecordBatchStream,
         schema: Schema,
-    ) -> Result<(Transaction, WriteContext<'_>)> {
+    ) -> Result<(Transaction, WriteContext<'_>, WriteSummary)> {
         let mut context = self.resolve_context().await?;
 
         info!(
@@ -204,6 +247,7 @@ impl<'a> InsertBuilder<'a> {
         let target_base_info =
             validate_and_resolve_target_bases(&mut context.params, existing_base_paths).await?;
 
+        let start = Instant::now();
         let (written_fragments, written_schema) = write_fragments_internal(
             context.dest.dataset(),
             context.object_store.clone(),
@@ -215,9 +259,48 @@ impl<'a> InsertBuilder<'a> {
         )
         .await?;
 
+        let summary = WriteSummary {
+            stats: Self::write_stats(&written_fragments),
+            write_time: start.elapsed(),
+            commit_time: None,
+        };
+
         let transaction = Self::build_transaction(written_schema, written_fragments, &context)?;
 
-        Ok((transaction, context))
+        Ok((transaction, context, summary))
+    }
+
+    fn write_stats(fragments: &[Fragment]) -> WriteStats {
+        let data_files = fragments.iter().flat_map(|fragment| fragment.files.iter());
+        WriteStats {
+            bytes_written: data_files
+                .clone()
+                .map(|file| file.file_size_bytes.get().map_or(0, |s| s.get()))
+                .sum(),
+            rows_written: fragments
+                .iter()
+                .map(|fragment| fragment.physical_rows.unwrap_or(0) as u64)
+                .sum(),
+            files_written: data_files.count() as u32,
+        }
+    }
+
+    /// Adds the partition and encryption specs given by the write params to
+    /// the config of a new or overwritten dataset.
+    fn insert_spec_values(
+        upsert_values: &mut HashMap<String, String>,
+        schema: &Schema,
+        context: &WriteContext<'_>,
+    ) -> Result<()> {
+        if let Some(spec) = context.params.partition_spec(schema)? {
+            upsert_values.insert(PARTITION_SPEC_KEY.to_string(), spec.to_config_value());
+        }
+        if let Some(encryption) = &context.params.encryption
+            && let Some(spec) = encryption.spec(schema)?
+        {
+            upsert_values.insert(ENCRYPTION_SPEC_KEY.to_string(), spec.to_config_value());
+        }
+        Ok(())
     }
 
     fn build_transaction(
@@ -243,6 +326,7 @@ impl<'a> InsertBuilder<'a> {
                         format_duration(duration).to_string(),
                     );
                 }
+                Self::insert_spec_values(&mut upsert_values, &schema, context)?;
                 let config_upsert_values = if upsert_values.is_empty() {
                     None
                 } else {
@@ -256,12 +340,21 @@ impl<'a> InsertBuilder<'a> {
                     initial_bases: context.params.initial_bases.clone(),
                 }
             }
-            WriteMode::Overwrite => Operation::Overwrite {
-                schema,
-                fragments,
-                config_upsert_values: None,
-                initial_bases: context.params.initial_bases.clone(),
-            },
+            WriteMode::Overwrite => {
+                let mut upsert_values = HashMap::new();
+                Self::insert_spec_values(&mut upsert_values, &schema, context)?;
+                let config_upsert_values = if upsert_values.is_empty() {
+                    None
+                } else {
+                    Some(upsert_values)
+                };
+                Operation::Overwrite {
+                    schema,
+                    fragments,
+                    config_upsert_values,
+                    initial_bases: context.params.initial_bases.clone(),
+                }
+            }
             WriteMode::Append => Operation::Append { fragments },
         };
 
@@ -332,15 +425,8 @@ impl<'a> InsertBuilder<'a> {
         }
 
         // Feature flags
-        if let WriteDestination::Dataset(dataset) = &context.dest
-            && !can_write_dataset(dataset.manifest.writer_feature_flags)
-        {
-            let message = format!(
-                "This dataset cannot be written by this version of Lance. \
-                Please upgrade Lance to write to this dataset.\n Flags: {}",
-                dataset.manifest.writer_feature_flags
-            );
-            return Err(Error::not_supported_source(message.into()));
+        if let WriteDestination::Dataset(dataset) = &context.dest {
+            check_write_compatibility(&dataset.manifest)?;
         }
 
         Ok(())
@@ -351,6 +437,12 @@ impl<'a> InsertBuilder<'a> {
         if let Some(cb) = self.write_progress.clone() {
             params.write_progress = Some(cb);
         }
+        if let Some(io_budget) = params.session.as_ref().and_then(|s| s.io_budget().cloned()) {
+            let store_params = params.store_params.get_or_insert_with(Default::default);
+            if store_params.io_budget.is_none() {
+                store_params.io_budget = Some(io_budget);
+            }
+        }
         let (object_store, base_path, commit_handler) = match &self.dest {
             WriteDestination::Dataset(dataset) => (
                 dataset.object_store.clone(),
@@ -447,6 +539,7 @@ mod test {
     use lance_arrow::BLOB_META_KEY;
 
     use crate::session::Session;
+    use lance_io::scheduler::{IoBudget, IoBudgetConfig};
 
     use super::*;
 
@@ -468,6 +561,34 @@ mod test {
         assert_eq!(Arc::as_ptr(&dataset.session()), Arc::as_ptr(&session));
     }
 
+    #[tokio::test]
+    async fn test_session_io_budget() {
+        let io_budget = IoBudget::new(IoBudgetConfig::default().with_max_concurrent_iops(4));
+        let session =
+            Arc::new(Session::new(0, 0, Default::default()).with_io_budget(io_budget.clone()));
+        let dataset = InsertBuilder::new("memory://budget")
+            .with_params(&WriteParams {
+                session: Some(session.clone()),
+                ..Default::default()
+            })
+            .execute_stream(RecordBatchIterator::new(
+                vec![],
+                Arc::new(Schema::new(vec![Field::new("col", DataType::Int32, false)])),
+            ))
+            .await
+            .unwrap();
+        let store_budget = dataset.object_store.io_budget().unwrap();
+        assert!(Arc::ptr_eq(store_budget, &io_budget));
+
+        let dataset = crate::dataset::builder::DatasetBuilder::from_uri("memory://budget")
+            .with_session(session)
+            .load()
+            .await
+            .unwrap();
+        let store_budget = dataset.object_store.io_budget().unwrap();
+        assert!(Arc::ptr_eq(store_budget, &io_budget));
+    }
+
     #[tokio::test]
     async fn test_write_empty_struct() {
         // Regresses a 2.1 issue where empty structs did not get assigned any columns
@@ -708,4 +829,56 @@ mod test {
         assert_eq!(last.rows_written, 300, "all 300 rows must be reported");
         assert_eq!(last.files_written, 1, "a single file should be written");
     }
+
+    #[tokio::test]
+    async fn test_write_summary_callback() {
+        use std::sync::Mutex;
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_iter_values(0..300))],
+        )
+        .unwrap();
+        let params = WriteParams {
+            max_rows_per_file: 100,
+            ..Default::default()
+        };
+
+        let summaries: Arc<Mutex<Vec<crate::dataset::WriteSummary>>> =
+            Arc::new(Mutex::new(Vec::new()));
+        let summaries_clone = summaries.clone();
+        let dataset = InsertBuilder::new("memory://test_write_summary")
+            .with_params(&params)
+            .summary(move |summary| summaries_clone.lock().unwrap().push(summary.clone()))
+            .execute(vec![batch.clone()])
+            .await
+            .unwrap();
+
+        {
+            let summaries = summaries.lock().unwrap();
+            assert_eq!(summaries.len(), 1, "summary must be reported exactly once");
+            let summary = &summaries[0];
+            assert_eq!(summary.stats.rows_written, 300);
+            assert_eq!(summary.stats.files_written, 3);
+            assert!(summary.stats.bytes_written > 0);
+            assert!(summary.commit_time.is_some());
+        }
+
+        // Uncommitted writes report no commit time
+        summaries.lock().unwrap().clear();
+        let summaries_clone = summaries.clone();
+        InsertBuilder::new(Arc::new(dataset))
+            .with_params(&WriteParams {
+                mode: WriteMode::Append,
+                ..Default::default()
+            })
+            .summary(move |summary| summaries_clone.lock().unwrap().push(summary.clone()))
+            .execute_uncommitted(vec![batch])
+            .await
+            .unwrap();
+        let summaries = summaries.lock().unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].stats.rows_written, 300);
+        assert!(summaries[0].commit_time.is_none());
+    }
 }
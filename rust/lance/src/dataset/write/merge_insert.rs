@@ -52,6 +52,7 @@ use crate::{
     Dataset,
     datafusion::dataframe::SessionContextExt,
     dataset::{
+        encryption::dataset_column_keys,
         fragment::{FileFragment, FragReadConfig},
         transaction::{Operation, Transaction},
         write::{merge_insert::logical_plan::MergeInsertPlanner, open_writer},
@@ -1059,11 +1060,14 @@ impl MergeInsertJob {
                         .manifest()
                         .data_storage_format
                         .lance_file_version()?;
+                    let encrypted_columns =
+                        dataset_column_keys(&dataset, &write_schema, None).await?;
                     let mut writer = open_writer(
                         &dataset.object_store,
                         &write_schema,
                         &dataset.base,
                         data_storage_version,
+                        encrypted_columns,
                     )
                     .await?;
 
@@ -57,8 +57,10 @@ use lance_io::utils::{
     read_version,
 };
 use lance_table::format::{Fragment, SelfDescribingFileReader};
-use lance_table::format::{IndexMetadata, list_index_files_with_sizes};
-use lance_table::io::manifest::read_manifest_indexes;
+use lance_table::format::{IndexDirectoryEntry, IndexMetadata, list_index_files_with_sizes};
+use lance_table::io::manifest::{
+    read_manifest_directory_indexes, read_manifest_index_directory, read_manifest_indexes,
+};
 use roaring::RoaringBitmap;
 use scalar::index_matches_criteria;
 use serde_json::json;
@@ -93,7 +95,7 @@ use crate::index::mem_wal::open_mem_wal_index;
 pub use crate::index::prefilter::{FilterLoader, PreFilter};
 use crate::index::scalar::{IndexDetails, fetch_index_details, load_training_data};
 pub use crate::index::vector::{LogicalIvfView, LogicalVectorIndex};
-use crate::session::index_caches::{FragReuseIndexKey, IndexMetadataKey};
+use crate::session::index_caches::{FragReuseIndexKey, IndexDirectoryKey, IndexMetadataKey};
 use crate::{Error, Result, dataset::Dataset};
 pub use create::CreateIndexBuilder;
 pub use lance_index::IndexDescription;
@@ -691,6 +693,33 @@ struct IndexDescriptionImpl {
     rows_indexed: u64,
 }
 
+/// The display name of the type of an index, e.g. `BTree` or `IVF_PQ`.
+pub(crate) fn index_type_name(metadata: &IndexMetadata) -> String {
+    if let Some(system_type) = lance_index::infer_system_index_type(metadata) {
+        // System indices (frag-reuse, mem-wal) are identified by name, not
+        // by index details, so this must be checked before the plugin lookup.
+        system_type.to_string()
+    } else if let Some(details) = metadata.index_details.clone().map(IndexDetails) {
+        if details.is_vector() {
+            derive_vector_index_type(&details.0)
+        } else {
+            // Fall back to a name derived from the type URL when no plugin
+            // is registered, so a known type URL is never reported as the
+            // opaque "Unknown".
+            details
+                .get_plugin()
+                .map(|p| p.name().to_string())
+                .unwrap_or_else(|_| display_type_from_url(details.0.type_url.as_str()).to_string())
+        }
+    } else if segment_has_vector_details(metadata) {
+        // Legacy vector indices predate VectorIndexDetails and are
+        // recognized by their monolithic index file name.
+        "Vector".to_string()
+    } else {
+        "Unknown".to_string()
+    }
+}
+
 impl IndexDescriptionImpl {
     async fn try_new(segments: Vec<IndexMetadata>, dataset: &Dataset) -> Result<Self> {
         if segments.is_empty() {
@@ -735,32 +764,7 @@ impl IndexDescriptionImpl {
             }
         }
 
-        let index_type =
-            if let Some(system_type) = lance_index::infer_system_index_type(example_metadata) {
-                // System indices (frag-reuse, mem-wal) are identified by name, not
-                // by index details, so this must be checked before the plugin lookup.
-                system_type.to_string()
-            } else if let Some(details) = details.as_ref() {
-                if details.is_vector() {
-                    derive_vector_index_type(&details.0)
-                } else {
-                    // Fall back to a name derived from the type URL when no plugin
-                    // is registered, so a known type URL is never reported as the
-                    // opaque "Unknown".
-                    details
-                        .get_plugin()
-                        .map(|p| p.name().to_string())
-                        .unwrap_or_else(|_| {
-                            display_type_from_url(details.0.type_url.as_str()).to_string()
-                        })
-                }
-            } else if segment_has_vector_details(example_metadata) {
-                // Legacy vector indices predate VectorIndexDetails and are
-                // recognized by their monolithic index file name.
-                "Vector".to_string()
-            } else {
-                "Unknown".to_string()
-            };
+        let index_type = index_type_name(example_metadata);
 
         let mut fragment_rows = HashMap::with_capacity(dataset.manifest.fragments.len());
         for fragment in dataset.iter_fragments() {
@@ -872,6 +876,94 @@ impl IndexDescription for IndexDescriptionImpl {
     }
 }
 
+/// Remaps the fragment bitmaps of `indices` through the fragment reuse index.
+async fn remap_with_frag_reuse_index(
+    dataset: &Dataset,
+    frag_reuse_index_meta: &IndexMetadata,
+    indices: &mut [IndexMetadata],
+) -> Result<()> {
+    let fri_key = FragReuseIndexKey {
+        uuid: &frag_reuse_index_meta.uuid,
+    };
+    let frag_reuse_index = dataset
+        .index_cache
+        .get_or_insert_with_key(fri_key, || async move {
+            let index_details =
+                load_frag_reuse_index_details(dataset, frag_reuse_index_meta).await?;
+            open_frag_reuse_index(frag_reuse_index_meta.uuid, index_details.as_ref()).await
+        })
+        .await?;
+    for idx in indices.iter_mut() {
+        if let Some(bitmap) = idx.fragment_bitmap.as_mut() {
+            frag_reuse_index.remap_fragment_bitmap(bitmap)?;
+        }
+    }
+    Ok(())
+}
+
+/// Loads the indices whose index directory entry `matches`, without reading
+/// the metadata of the other indices.
+///
+/// Returns `None` if the manifest has no index directory, or the metadata of
+/// all indices is cached already, in which case the indices should be picked
+/// from [`DatasetIndexExt::load_indices`].
+async fn load_directory_indices(
+    dataset: &Dataset,
+    matches: impl Fn(&IndexDirectoryEntry) -> bool,
+) -> Result<Option<Vec<IndexMetadata>>> {
+    if dataset.manifest.index_directory.is_none() {
+        return Ok(None);
+    }
+    let version = dataset.version().version;
+    if dataset
+        .index_cache
+        .get_with_key(&IndexMetadataKey { version })
+        .await
+        .is_some()
+    {
+        return Ok(None);
+    }
+    let directory = dataset
+        .index_cache
+        .get_or_insert_with_key(IndexDirectoryKey { version }, || async move {
+            read_manifest_index_directory(
+                &dataset.object_store,
+                &dataset.manifest_location,
+                &dataset.manifest,
+            )
+            .await
+            .map(Option::unwrap_or_default)
+        })
+        .await?;
+
+    let selected = directory
+        .iter()
+        .filter(|entry| matches(entry))
+        .collect::<Vec<_>>();
+    let mut indices = read_manifest_directory_indexes(
+        &dataset.object_store,
+        &dataset.manifest_location,
+        &selected,
+    )
+    .await?;
+    retain_supported_indices(&mut indices);
+    infer_missing_vector_details(dataset, &mut indices).await;
+
+    if let Some(entry) = directory
+        .iter()
+        .find(|entry| entry.name == FRAG_REUSE_INDEX_NAME)
+    {
+        let frag_reuse_index_meta = read_manifest_directory_indexes(
+            &dataset.object_store,
+            &dataset.manifest_location,
+            &[entry],
+        )
+        .await?;
+        remap_with_frag_reuse_index(dataset, &frag_reuse_index_meta[0], &mut indices).await?;
+    }
+    Ok(Some(indices))
+}
+
 #[async_trait]
 impl DatasetIndexExt for Dataset {
     type IndexBuilder<'a> = CreateIndexBuilder<'a>;
@@ -1095,29 +1187,36 @@ impl DatasetIndexExt for Dataset {
         if let Some(frag_reuse_index_meta) =
             indices.iter().find(|idx| idx.name == FRAG_REUSE_INDEX_NAME)
         {
-            let fri_key = FragReuseIndexKey {
-                uuid: &frag_reuse_index_meta.uuid,
-            };
-            let frag_reuse_index = self
-                .index_cache
-                .get_or_insert_with_key(fri_key, || async move {
-                    let index_details =
-                        load_frag_reuse_index_details(self, frag_reuse_index_meta).await?;
-                    open_frag_reuse_index(frag_reuse_index_meta.uuid, index_details.as_ref()).await
-                })
-                .await?;
-            let mut indices = indices.as_ref().clone();
-            for idx in indices.iter_mut() {
-                if let Some(bitmap) = idx.fragment_bitmap.as_mut() {
-                    frag_reuse_index.remap_fragment_bitmap(bitmap)?;
-                }
-            }
-            Ok(Arc::new(indices))
+            let mut remapped = indices.as_ref().clone();
+            remap_with_frag_reuse_index(self, frag_reuse_index_meta, &mut remapped).await?;
+            Ok(Arc::new(remapped))
         } else {
             Ok(indices)
         }
     }
 
+    async fn load_index(&self, uuid: &Uuid) -> Result<Option<IndexMetadata>> {
+        if let Some(indices) = load_directory_indices(self, |entry| entry.uuid == *uuid).await? {
+            return Ok(indices.into_iter().next());
+        }
+        self.load_indices()
+            .await
+            .map(|indices| indices.iter().find(|idx| idx.uuid == *uuid).cloned())
+    }
+
+    async fn load_indices_by_name(&self, name: &str) -> Result<Vec<IndexMetadata>> {
+        if let Some(indices) = load_directory_indices(self, |entry| entry.name == name).await? {
+            return Ok(indices);
+        }
+        self.load_indices().await.map(|indices| {
+            indices
+                .iter()
+                .filter(|idx| idx.name == name)
+                .cloned()
+                .collect()
+        })
+    }
+
     async fn merge_existing_index_segments(
         &self,
         source_segments: Vec<IndexMetadata>,
@@ -2014,7 +2113,10 @@ impl DatasetIndexInternalExt for Dataset {
 
                 match index_metadata.index_type.as_str() {
                     "IVF_FLAT" => match element_type {
-                        DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+                        DataType::Float16
+                        | DataType::Float32
+                        | DataType::Float64
+                        | DataType::FixedSizeBinary(2) => {
                             let ivf = IVFIndex::<FlatIndex, FlatQuantizer>::try_new(
                                 self.object_store.clone(),
                                 index_dir,
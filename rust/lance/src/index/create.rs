@@ -5,6 +5,7 @@ use crate::{
     Error, Result,
     dataset::{
         Dataset,
+        embedding::EmbeddingSpec,
         transaction::{Operation, TransactionBuilder},
     },
     index::{
@@ -153,6 +154,13 @@ impl<'a> CreateIndexBuilder<'a> {
         let quoted_column: String = format_field_path(&names);
         let column = quoted_column.as_str();
 
+        // Index builds read stored values, virtual embeddings must be backfilled first
+        if EmbeddingSpec::from_field(field)?.is_some_and(|spec| spec.is_virtual) {
+            return Err(Error::index(format!(
+                "CreateIndex: column '{column}' is a virtual embedding column, call materialize_embedding_column to backfill it before building an index"
+            )));
+        }
+
         // If train is true but dataset is empty, automatically set train to false
         let train = if self.train {
             self.dataset.count_rows(None).await? > 0
@@ -654,7 +654,10 @@ pub(crate) async fn build_distributed_vector_index(
 
     match index_type {
         IndexType::IvfFlat => match element_type {
-            DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            DataType::Float16
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::FixedSizeBinary(2) => {
                 let ivf_model = make_ivf_model();
 
                 let summary = IvfIndexBuilder::<FlatIndex, FlatQuantizer>::new(
@@ -957,7 +960,10 @@ pub(crate) async fn build_vector_index(
 
     match index_type {
         IndexType::IvfFlat => match element_type {
-            DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            DataType::Float16
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::FixedSizeBinary(2) => {
                 let summary = IvfIndexBuilder::<FlatIndex, FlatQuantizer>::new(
                     dataset.clone(),
                     column.to_owned(),
@@ -1777,7 +1777,10 @@ pub(crate) async fn remap_index_file_v3(
     let (_, element_type) = get_vector_type(dataset.schema(), &column)?;
     match index.sub_index_type() {
         (SubIndexType::Flat, QuantizationType::Flat) => match element_type {
-            DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            DataType::Float16
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::FixedSizeBinary(2) => {
                 IvfIndexBuilder::<FlatIndex, FlatQuantizer>::new_remapper(
                     dataset, column, index_dir, index,
                 )?
@@ -3298,9 +3301,10 @@ fn train_ivf_kmeans_step_arrow_array_no_loss(
             values.as_primitive::<Float64Type>(),
             &step_options,
         )?,
-        (DataType::Int8, DistanceType::L2)
-        | (DataType::Int8, DistanceType::Dot)
-        | (DataType::Int8, DistanceType::Cosine) => {
+        (
+            DataType::Int8 | DataType::FixedSizeBinary(2),
+            DistanceType::L2 | DistanceType::Dot | DistanceType::Cosine,
+        ) => {
             let data = data.convert_to_floating_point()?;
             train_ivf_kmeans_step::<Float32Type>(
                 centroids,
@@ -4506,9 +4510,10 @@ async fn train_ivf_model(
             )
             .await
         }
-        (DataType::Int8, DistanceType::L2)
-        | (DataType::Int8, DistanceType::Dot)
-        | (DataType::Int8, DistanceType::Cosine) => {
+        (
+            DataType::Int8 | DataType::FixedSizeBinary(2),
+            DistanceType::L2 | DistanceType::Dot | DistanceType::Cosine,
+        ) => {
             do_train_ivf_model::<Float32Type>(
                 centroids,
                 data.convert_to_floating_point()?
@@ -12,6 +12,7 @@ use arrow_array::{Array, ArrayRef, FixedSizeListArray, RecordBatch, cast::AsArra
 use arrow_buffer::{Buffer, MutableBuffer};
 use futures::{Stream, StreamExt, TryStreamExt, stream};
 use lance_arrow::DataTypeExt;
+use lance_arrow::bfloat16::is_bfloat16_field;
 use lance_core::datatypes::Schema;
 use lance_linalg::distance::DistanceType;
 use log::{info, warn};
@@ -171,7 +172,8 @@ fn infer_vector_dim_impl(data_type: &arrow::datatypes::DataType, in_list: bool)
 
 /// Checks whether the given column is with a valid vector type
 /// returns the vector type (FixedSizeList for vectors, or List for multivectors),
-/// and element type (Float16/Float32/Float64 or UInt8 for binary vectors).
+/// and element type (Float16/Float32/Float64, FixedSizeBinary(2) for bfloat16, or UInt8 for
+/// binary vectors).
 pub fn get_vector_type(
     schema: &Schema,
     column: &str,
@@ -204,7 +206,8 @@ pub fn validate_distance_type_for(
         arrow_schema::DataType::Int8
         | arrow_schema::DataType::Float16
         | arrow_schema::DataType::Float32
-        | arrow_schema::DataType::Float64 => {
+        | arrow_schema::DataType::Float64
+        | arrow_schema::DataType::FixedSizeBinary(2) => {
             matches!(
                 distance_type,
                 DistanceType::L2 | DistanceType::Cosine | DistanceType::Dot
@@ -245,8 +248,13 @@ fn infer_vector_element_type_impl(
                 | arrow::datatypes::DataType::Float64
                 | arrow::datatypes::DataType::UInt8
                 | arrow::datatypes::DataType::Int8 => Ok(element_field.data_type().clone()),
+                arrow::datatypes::DataType::FixedSizeBinary(2)
+                    if is_bfloat16_field(element_field) =>
+                {
+                    Ok(element_field.data_type().clone())
+                }
                 _ => Err(Error::index(format!(
-                    "vector element is not expected type (Float16/Float32/Float64 or UInt8): {:?}",
+                    "vector element is not expected type (Float16/Float32/Float64/BFloat16 or UInt8): {:?}",
                     element_field.data_type()
                 ))),
             }
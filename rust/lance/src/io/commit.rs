@@ -37,6 +37,7 @@ use lance_table::format::{
 use lance_table::io::commit::{
     CommitConfig, CommitError, CommitHandler, ManifestLocation, ManifestNamingScheme,
 };
+use lance_table::io::manifest::read_manifest_indexes;
 use rand::{Rng, rng};
 
 use super::ObjectStore;
@@ -65,6 +66,7 @@ use log;
 use object_store::ObjectStoreExt;
 use object_store::path::Path;
 use prost::Message;
+use tracing::instrument;
 
 pub mod conflict_resolver;
 #[cfg(all(feature = "dynamodb_tests", test))]
@@ -76,7 +78,6 @@ pub mod namespace_manifest;
 mod s3_test;
 
 /// Read the transaction data from a transaction file.
-#[cfg(test)]
 pub(crate) async fn read_transaction_file(
     object_store: &ObjectStore,
     base_path: &Path,
@@ -191,22 +192,12 @@ async fn do_commit_new_dataset(
                 transaction_file.clone(),
             );
 
-            let updated_indices = if let Some(index_section_pos) = source_manifest.index_section {
-                let reader = object_store.open(&source_manifest_location.path).await?;
-                let section: pb::IndexSection =
-                    lance_io::utils::read_message(reader.as_ref(), index_section_pos).await?;
-                section
-                    .indices
-                    .into_iter()
-                    .map(|index_pb| {
-                        let mut index = IndexMetadata::try_from(index_pb)?;
-                        index.base_id = Some(new_base_id);
-                        Ok(index)
-                    })
-                    .collect::<Result<Vec<_>>>()?
-            } else {
-                vec![]
-            };
+            let mut updated_indices =
+                read_manifest_indexes(object_store, &source_manifest_location, &source_manifest)
+                    .await?;
+            for index in &mut updated_indices {
+                index.base_id = Some(new_base_id);
+            }
             (new_manifest, updated_indices)
         } else {
             // Deep clone: build a manifest that references local files (no external bases)
@@ -215,6 +206,7 @@ async fn do_commit_new_dataset(
             new_manifest.branch = None;
             new_manifest.tag = None;
             new_manifest.index_section = None; // will be rewritten below
+            new_manifest.index_directory = None;
             let mut new_frags = new_manifest.fragments.as_ref().clone();
             for f in &mut new_frags {
                 for df in &mut f.files {
@@ -227,20 +219,11 @@ async fn do_commit_new_dataset(
             new_manifest.fragments = Arc::new(new_frags);
 
             // Indices: keep metadata but normalize base to local
-            let mut updated_indices = Vec::new();
-            if let Some(index_section_pos) = source_manifest.index_section {
-                let reader = object_store.open(&source_manifest_location.path).await?;
-                let section: pb::IndexSection =
-                    lance_io::utils::read_message(reader.as_ref(), index_section_pos).await?;
-                updated_indices = section
-                    .indices
-                    .into_iter()
-                    .map(|index_pb| {
-                        let mut index = IndexMetadata::try_from(index_pb)?;
-                        index.base_id = None;
-                        Ok(index)
-                    })
-                    .collect::<Result<Vec<_>>>()?;
+            let mut updated_indices =
+                read_manifest_indexes(object_store, &source_manifest_location, &source_manifest)
+                    .await?;
+            for index in &mut updated_indices {
+                index.base_id = None;
             }
             (new_manifest, updated_indices)
         }
@@ -298,6 +281,7 @@ async fn do_commit_new_dataset(
 }
 
 #[allow(clippy::too_many_arguments)]
+#[instrument(skip_all)]
 pub(crate) async fn commit_new_dataset(
     object_store: &ObjectStore,
     commit_handler: &dyn CommitHandler,
@@ -908,6 +892,7 @@ async fn load_and_sort_new_transactions(
 
 /// Attempt to commit a transaction, with retries and conflict resolution.
 #[allow(clippy::too_many_arguments)]
+#[instrument(skip_all, fields(read_version = transaction.read_version))]
 pub(crate) async fn commit_transaction(
     dataset: &Dataset,
     object_store: &ObjectStore,
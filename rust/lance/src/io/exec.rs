@@ -9,11 +9,13 @@
 pub mod ann_proto;
 pub mod count_from_mask;
 pub mod count_pushdown;
+pub mod embedding;
 mod filter;
 pub mod filtered_read;
 #[cfg(feature = "substrait")]
 pub mod filtered_read_proto;
 pub mod fts;
+pub mod fusion;
 pub(crate) mod knn;
 mod optimizer;
 mod projection;
@@ -27,6 +29,7 @@ mod take;
 #[cfg(test)]
 pub mod testing;
 pub mod utils;
+pub mod vector_topk_pushdown;
 
 pub use filter::LanceFilterExec;
 pub use knn::{ANNIvfPartitionExec, ANNIvfSubIndexExec, KNNVectorDistanceExec};
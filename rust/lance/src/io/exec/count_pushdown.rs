@@ -51,6 +51,7 @@ use datafusion::physical_plan::{
     ExecutionPlan, ExecutionPlanProperties,
     aggregates::{AggregateExec, AggregateMode, PhysicalGroupBy},
     coalesce_partitions::CoalescePartitionsExec,
+    coop::CooperativeExec,
     projection::ProjectionExec,
     repartition::RepartitionExec,
     union::UnionExec,
@@ -205,7 +206,15 @@ fn try_rewrite(agg: &AggregateExec) -> DFResult<Option<Arc<dyn ExecutionPlan>>>
     let dataset = filtered_read.dataset().clone();
     let dataset_fragments: RoaringBitmap =
         dataset.fragments().iter().map(|f| f.id as u32).collect();
-    let prefilter_input = filtered_read.index_input().cloned();
+    // DataFusion wraps leaf nodes such as the `ScalarIndexExec` in a
+    // `CooperativeExec`, which only yields to the runtime between batches.
+    let prefilter_input = filtered_read.index_input().map(|input| {
+        let mut input = input.clone();
+        while let Some(inner) = input.as_any().downcast_ref::<CooperativeExec>() {
+            input = inner.input().clone();
+        }
+        input
+    });
 
     // If there is a prefilter, inspect its ScalarIndexExpr leaves:
     //   - Refuse to fire if any leaf is inexact (`needs_recheck`). The
@@ -372,9 +381,10 @@ fn build_scan_branch(
 }
 
 /// Walk through row-preserving wrappers (`RepartitionExec`,
-/// `CoalesceBatchesExec`, and identity-or-empty `ProjectionExec`) that
-/// DataFusion's planner inserts between an `AggregateExec` and the leaf, and
-/// return the underlying `FilteredReadExec` if one is reached.
+/// `CoalesceBatchesExec`, `CooperativeExec`, and identity-or-empty
+/// `ProjectionExec`) that DataFusion's planner inserts between an
+/// `AggregateExec` and the leaf, and return the underlying `FilteredReadExec`
+/// if one is reached.
 ///
 /// "Row-preserving" here means the wrapper changes neither the number of rows
 /// nor the predicate applied to them — it may reshape partitions, batches, or
@@ -397,6 +407,8 @@ fn strip_row_preserving_wrappers(plan: &Arc<dyn ExecutionPlan>) -> Option<&Filte
                 inner.input()
             } else if let Some(inner) = current.as_any().downcast_ref::<CoalescePartitionsExec>() {
                 inner.input()
+            } else if let Some(inner) = current.as_any().downcast_ref::<CooperativeExec>() {
+                inner.input()
             } else if let Some(proj) = current.as_any().downcast_ref::<ProjectionExec>() {
                 // Only walk through projections that are row-preserving: every
                 // output expression is a direct column reference back to the
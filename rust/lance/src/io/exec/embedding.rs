@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::SendableRecordBatchStream;
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties};
+use datafusion_physical_plan::Statistics;
+use datafusion_physical_plan::execution_plan::CardinalityEffect;
+use datafusion_physical_plan::stream::RecordBatchStreamAdapter;
+use futures::StreamExt;
+use lance_core::{Error as LanceError, Result as LanceResult};
+
+use crate::dataset::embedding::{EmbeddingFunction, compute_embeddings};
+
+/// A virtual embedding column computed by [`EmbeddingExec`].
+#[derive(Debug, Clone)]
+pub struct VirtualEmbedding {
+    /// The name of the embedding column
+    pub column: String,
+    /// The name of the source column
+    pub source: String,
+    pub function: Arc<dyn EmbeddingFunction>,
+}
+
+/// Fills virtual embedding columns by running their embedding function on the
+/// source column.
+///
+/// The input must contain both the embedding column (typically all null, since
+/// virtual columns have no data files) and its source column.  The embedding
+/// column is replaced in place so the output schema matches the input schema.
+#[derive(Debug)]
+pub struct EmbeddingExec {
+    input: Arc<dyn ExecutionPlan>,
+    embeddings: Vec<VirtualEmbedding>,
+    /// (column index, source index) for each embedding
+    positions: Vec<(usize, usize)>,
+}
+
+impl EmbeddingExec {
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        embeddings: Vec<VirtualEmbedding>,
+    ) -> LanceResult<Self> {
+        let schema = input.schema();
+        let positions = embeddings
+            .iter()
+            .map(|embedding| {
+                let index_of = |name: &str| {
+                    schema.index_of(name).map_err(|_| {
+                        LanceError::internal(format!(
+                            "EmbeddingExec input does not have a {name} column"
+                        ))
+                    })
+                };
+                Ok((index_of(&embedding.column)?, index_of(&embedding.source)?))
+            })
+            .collect::<LanceResult<Vec<_>>>()?;
+        Ok(Self {
+            input,
+            embeddings,
+            positions,
+        })
+    }
+}
+
+impl DisplayAs for EmbeddingExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let columns = self
+            .embeddings
+            .iter()
+            .map(|e| format!("{}={:?}({})", e.column, e.function, e.source))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "Embedding: {}", columns)
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "Embedding\ncolumns={}", columns)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for EmbeddingExec {
+    fn name(&self) -> &str {
+        "EmbeddingExec"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        self.input.properties()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn partition_statistics(&self, partition: Option<usize>) -> Result<Statistics> {
+        self.input.partition_statistics(partition)
+    }
+
+    fn supports_limit_pushdown(&self) -> bool {
+        true
+    }
+
+    fn cardinality_effect(&self) -> CardinalityEffect {
+        CardinalityEffect::Equal
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if children.len() != 1 {
+            return Err(DataFusionError::Internal(
+                "EmbeddingExec: invalid number of children".into(),
+            ));
+        }
+        Ok(Arc::new(Self::try_new(
+            children.into_iter().next().unwrap(),
+            self.embeddings.clone(),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<datafusion::execution::TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let input_stream = self.input.execute(partition, context)?;
+        let schema = self.schema();
+        let embeddings = self.embeddings.clone();
+        let positions = self.positions.clone();
+        let stream = input_stream.then(move |batch| {
+            let schema = schema.clone();
+            let embeddings = embeddings.clone();
+            let positions = positions.clone();
+            async move {
+                let batch = batch?;
+                let mut columns = batch.columns().to_vec();
+                for (embedding, (column_idx, source_idx)) in embeddings.iter().zip(positions) {
+                    columns[column_idx] = compute_embeddings(
+                        embedding.function.as_ref(),
+                        batch.column(source_idx).clone(),
+                        schema.field(column_idx).data_type(),
+                    )
+                    .await?;
+                }
+                Ok(RecordBatch::try_new(schema, columns)?)
+            }
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+}
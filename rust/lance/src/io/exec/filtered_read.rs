@@ -9,6 +9,8 @@ use std::{ops::Range, sync::Arc};
 
 use arrow_array::RecordBatch;
 use arrow_schema::SchemaRef;
+use datafusion::common::ColumnStatistics;
+use datafusion::common::config::ConfigOptions;
 use datafusion::common::runtime::SpawnedTask;
 use datafusion::common::stats::Precision;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
@@ -34,8 +36,8 @@ use lance_core::utils::tokio::get_num_compute_intensive_cpus;
 use lance_core::{Error, Result, datatypes::Projection};
 use lance_datafusion::planner::Planner;
 use lance_datafusion::utils::{
-    ExecutionPlanMetricsSetExt, FRAGMENTS_SCANNED_METRIC, RANGES_SCANNED_METRIC,
-    ROWS_SCANNED_METRIC, TASK_WAIT_TIME_METRIC,
+    ExecutionPlanMetricsSetExt, FRAGMENTS_PRUNED_METRIC, FRAGMENTS_SCANNED_METRIC,
+    RANGES_SCANNED_METRIC, ROWS_SCANNED_METRIC, TASK_WAIT_TIME_METRIC,
 };
 use lance_file::reader::FileReaderOptions;
 use lance_index::scalar::expression::FilterPlan;
@@ -51,6 +53,7 @@ use tokio::sync::{Mutex as AsyncMutex, OnceCell};
 use tracing::{Instrument, instrument};
 
 use crate::Dataset;
+use crate::dataset::fragment::zone_map::{column_statistics, fragment_may_match};
 use crate::dataset::fragment::{FileFragment, FragReadConfig};
 use crate::dataset::rowids::load_row_id_sequence;
 use crate::dataset::scanner::{
@@ -101,6 +104,7 @@ struct ScopedFragmentRead {
     filter: Option<Expr>,
     priority: u32,
     scan_scheduler: Arc<ScanScheduler>,
+    prefetch: bool,
 }
 
 impl ScopedFragmentRead {
@@ -189,6 +193,7 @@ impl<I: Iterator<Item = u64> + Send> Iterator for DvToValidRanges<I> {
 /// reported on partition 0
 pub struct FilteredReadGlobalMetrics {
     fragments_scanned: Count,
+    fragments_pruned: Count,
     ranges_scanned: Count,
     rows_scanned: Count,
     io_metrics: IoMetrics,
@@ -198,6 +203,7 @@ impl FilteredReadGlobalMetrics {
     pub fn new(metrics: &ExecutionPlanMetricsSet) -> Self {
         Self {
             fragments_scanned: metrics.new_count(FRAGMENTS_SCANNED_METRIC, 0),
+            fragments_pruned: metrics.new_count(FRAGMENTS_PRUNED_METRIC, 0),
             ranges_scanned: metrics.new_count(RANGES_SCANNED_METRIC, 0),
             rows_scanned: metrics.new_count(ROWS_SCANNED_METRIC, 0),
             io_metrics: IoMetrics::new(metrics, 0),
@@ -297,6 +303,26 @@ pub enum FilteredReadThreadingMode {
     ///
     /// The number of partitions is specified by the parameter.
     MultiplePartitions(usize),
+
+    /// Like `MultiplePartitions` but each partition reads its own group of whole
+    /// fragments instead of sharing the batches of all fragments.  A fragment is never
+    /// split across partitions and each partition returns its fragments in order.
+    ///
+    /// This is the mode the node switches to when DataFusion repartitions the scan (see
+    /// [`FilteredReadOptions::with_repartitioning`]).
+    ///
+    /// The number of partitions is specified by the parameter.
+    FragmentPartitions(usize),
+}
+
+impl FilteredReadThreadingMode {
+    /// The number of output partitions of the scan
+    pub fn num_partitions(&self) -> usize {
+        match self {
+            Self::OnePartitionMultipleThreads(_) => 1,
+            Self::MultiplePartitions(n) | Self::FragmentPartitions(n) => *n,
+        }
+    }
 }
 
 /// The stream of filtered rows that satisfies the FilteredReadExec node
@@ -311,10 +337,11 @@ pub enum FilteredReadThreadingMode {
 struct FilteredReadStream {
     /// The schema of the output of the scan
     output_schema: SchemaRef,
-    /// The stream of filtered rows, expressed as a stream of tasks (batch futures)
+    /// The streams of filtered rows, expressed as streams of tasks (batch futures)
     ///
-    /// This stream can be shared by multiple partitions
-    task_stream: Arc<AsyncMutex<BoxStream<'static, Result<ReadBatchFut>>>>,
+    /// There is one stream per fragment group when the partitions are aligned with the
+    /// fragments, otherwise a single stream is shared by all partitions
+    task_streams: Vec<Arc<AsyncMutex<BoxStream<'static, Result<ReadBatchFut>>>>>,
     /// The scan scheduler for the scan
     scan_scheduler: Arc<ScanScheduler>,
     /// The global metrics for the scan
@@ -400,6 +427,17 @@ impl FilteredReadStream {
         };
         let scan_scheduler = ScanScheduler::new(obj_store, scheduler_config);
 
+        // Fragments the plan doesn't read anything from were pruned by the index, the zone
+        // maps, or the scan range
+        let fragments_to_read = plan
+            .rows
+            .values()
+            .filter(|ranges| !ranges.is_empty())
+            .count();
+        global_metrics
+            .fragments_pruned
+            .add(loaded_fragments.len().saturating_sub(fragments_to_read));
+
         // Get scan_range_after_filter from the plan
         let scan_range_after_filter = plan.scan_range_after_filter.clone();
 
@@ -412,26 +450,42 @@ impl FilteredReadStream {
             scan_scheduler.clone(),
         );
 
-        let global_metrics_clone = global_metrics.clone();
-
-        let fragment_streams = futures::stream::iter(scoped_fragments)
-            .map({
-                let scan_range_after_filter = scan_range_after_filter.clone();
-                move |scoped_fragment| {
-                    let metrics = global_metrics_clone.clone();
-                    let limit = scan_range_after_filter.as_ref().map(|r| r.end);
-                    SpawnedTask::spawn(
-                        Self::read_fragment(scoped_fragment, metrics, limit).in_current_span(),
-                    )
-                    .map(|thread_result| thread_result.unwrap())
+        let fragment_soft_limit = scan_range_after_filter.as_ref().map(|r| r.end);
+        let task_streams = match threading_mode {
+            FilteredReadThreadingMode::FragmentPartitions(num_partitions) => {
+                // Deal the fragments out round-robin so that pruned fragments don't leave
+                // some partitions with much more work than others
+                let mut groups = (0..num_partitions).map(|_| Vec::new()).collect::<Vec<_>>();
+                for (idx, scoped_fragment) in scoped_fragments.into_iter().enumerate() {
+                    groups[idx % num_partitions].push(scoped_fragment);
                 }
-            })
-            .buffered(fragment_readahead);
-        let task_stream = fragment_streams.try_flatten().boxed();
+                let group_readahead = fragment_readahead.div_ceil(num_partitions);
+                groups
+                    .into_iter()
+                    .map(|group| {
+                        Self::fragments_task_stream(
+                            group,
+                            global_metrics.clone(),
+                            fragment_soft_limit,
+                            group_readahead,
+                        )
+                    })
+                    .collect()
+            }
+            _ => vec![Self::fragments_task_stream(
+                scoped_fragments,
+                global_metrics.clone(),
+                fragment_soft_limit,
+                fragment_readahead,
+            )],
+        };
 
         Ok(Self {
             output_schema,
-            task_stream: Arc::new(AsyncMutex::new(task_stream)),
+            task_streams: task_streams
+                .into_iter()
+                .map(|task_stream| Arc::new(AsyncMutex::new(task_stream)))
+                .collect(),
             scan_scheduler,
             metrics: global_metrics,
             active_partitions_counter: Arc::new(AtomicUsize::new(0)),
@@ -440,6 +494,27 @@ impl FilteredReadStream {
         })
     }
 
+    // Reads the given fragments, `fragment_readahead` at a time, into a single stream of tasks
+    fn fragments_task_stream(
+        scoped_fragments: Vec<ScopedFragmentRead>,
+        global_metrics: Arc<FilteredReadGlobalMetrics>,
+        fragment_soft_limit: Option<u64>,
+        fragment_readahead: usize,
+    ) -> BoxStream<'static, Result<ReadBatchFut>> {
+        futures::stream::iter(scoped_fragments)
+            .map(move |scoped_fragment| {
+                let metrics = global_metrics.clone();
+                SpawnedTask::spawn(
+                    Self::read_fragment(scoped_fragment, metrics, fragment_soft_limit)
+                        .in_current_span(),
+                )
+                .map(|thread_result| thread_result.unwrap())
+            })
+            .buffered(fragment_readahead)
+            .try_flatten()
+            .boxed()
+    }
+
     async fn load_fragment(
         dataset: Arc<Dataset>,
         frag: Fragment,
@@ -558,6 +633,20 @@ impl FilteredReadStream {
                 }
             }
 
+            // Deleted rows are materialized even if they would not pass the filter, so we
+            // can only prune when they are excluded.
+            if !options.disable_zone_map_pruning
+                && !options.with_deleted_rows
+                && let Some(filter) = &options.full_filter
+                && !fragment_may_match(fragment.metadata(), fragment.schema(), filter)
+            {
+                log::trace!(
+                    "Skipping fragment {} because its zone maps rule out the filter",
+                    fragment.id()
+                );
+                continue;
+            }
+
             // Apply index and apply scan range after filter if applicable
             Self::apply_index_to_fragment(
                 evaluated_index,
@@ -680,6 +769,7 @@ impl FilteredReadStream {
                     filter,
                     priority: priority as u32,
                     scan_scheduler: scan_scheduler.clone(),
+                    prefetch: options.prefetch,
                 });
             }
         }
@@ -944,6 +1034,9 @@ impl FilteredReadStream {
     // generally fine because grabbing a task is cheap (unless we are waiting on I/O).
     //
     // If the threading mode is `MultiplePartitions` then we may operate on the data out-of-order.
+    //
+    // If the threading mode is `FragmentPartitions` then there is one task stream per partition
+    // and each partition polls its own stream (in the same way as `MultiplePartitions`).
     fn get_stream(
         &self,
         metrics: &ExecutionPlanMetricsSet,
@@ -962,7 +1055,7 @@ impl FilteredReadStream {
             FilteredReadThreadingMode::OnePartitionMultipleThreads(num_threads) => {
                 assert_eq!(partition, 0);
                 let output_schema = self.output_schema.clone();
-                let task_stream = self.task_stream.clone();
+                let task_stream = self.task_streams[0].clone();
                 let partition_metrics_clone = partition_metrics.clone();
                 let futures_stream = futures::stream::try_unfold(task_stream, {
                     move |task_stream| {
@@ -1019,10 +1112,11 @@ impl FilteredReadStream {
 
                 Box::pin(RecordBatchStreamAdapter::new(output_schema, batch_stream))
             }
-            FilteredReadThreadingMode::MultiplePartitions(num_partitions) => {
+            FilteredReadThreadingMode::MultiplePartitions(num_partitions)
+            | FilteredReadThreadingMode::FragmentPartitions(num_partitions) => {
                 assert!(partition < num_partitions);
                 let output_schema = self.output_schema.clone();
-                let task_stream = self.task_stream.clone();
+                let task_stream = self.task_streams[partition % self.task_streams.len()].clone();
                 let global_metrics_clone = global_metrics.clone();
                 let scan_scheduler_clone = scan_scheduler.clone();
                 let batch_stream = futures::stream::try_unfold(task_stream, {
@@ -1109,6 +1203,10 @@ impl FilteredReadStream {
         // the row ids are not contiguous
         fragment_read_task.ranges.sort_by_key(|r| r.start);
 
+        if fragment_read_task.prefetch {
+            fragment_reader.prefetch(&fragment_read_task.ranges);
+        }
+
         let physical_filter = fragment_read_task
             .filter
             .map(|filter| {
@@ -1295,6 +1393,13 @@ pub struct FilteredReadOptions {
     pub io_buffer_size_bytes: Option<u64>,
     /// If true, skip fragments that are not covered by the scalar index result.
     pub only_indexed_fragments: bool,
+    /// If true, read fragments even when their zone maps prove that no row can
+    /// satisfy the filter.
+    pub disable_zone_map_pruning: bool,
+    /// If true, the data of each fragment is prefetched as soon as the fragment is opened.
+    pub prefetch: bool,
+    /// If true, the read may be split into fragment-aligned partitions by the optimizer.
+    pub allow_repartition: bool,
 }
 
 impl FilteredReadOptions {
@@ -1324,6 +1429,9 @@ impl FilteredReadOptions {
             full_filter: None,
             io_buffer_size_bytes: None,
             only_indexed_fragments: false,
+            disable_zone_map_pruning: false,
+            prefetch: false,
+            allow_repartition: false,
             threading_mode: FilteredReadThreadingMode::OnePartitionMultipleThreads(
                 get_num_compute_intensive_cpus(),
             ),
@@ -1482,6 +1590,36 @@ impl FilteredReadOptions {
         self.only_indexed_fragments = true;
         self
     }
+
+    /// Read every fragment, even those whose zone maps rule out the filter.
+    pub fn with_zone_map_pruning_disabled(mut self) -> Self {
+        self.disable_zone_map_pruning = true;
+        self
+    }
+
+    /// Prefetch the data of each fragment as soon as it is opened.
+    ///
+    /// Fragments are opened ahead of the decoder (see [`Self::with_fragment_readahead`])
+    /// so this warms the data of upcoming fragments while the current ones are decoded.
+    /// The prefetched data is held in memory, up to the size of the I/O buffer, in
+    /// addition to the I/O buffer itself.
+    pub fn with_prefetch(mut self) -> Self {
+        self.prefetch = true;
+        self
+    }
+
+    /// Allow DataFusion to split the read into one partition per group of fragments.
+    ///
+    /// When the physical optimizer asks for more partitions (see
+    /// [`ExecutionPlan::repartitioned`]) the node switches to
+    /// [`FilteredReadThreadingMode::FragmentPartitions`] so that downstream operators run on
+    /// the fragments in parallel.  The rows are then no longer returned in fragment order.
+    ///
+    /// Reads with a scan range are never repartitioned.
+    pub fn with_repartitioning(mut self) -> Self {
+        self.allow_repartition = true;
+        self
+    }
 }
 
 /// A plan node that reads a dataset, applying an optional filter and projection.
@@ -1587,6 +1725,7 @@ impl FilteredReadExec {
             if matches!(
                 options.threading_mode,
                 FilteredReadThreadingMode::MultiplePartitions(_)
+                    | FilteredReadThreadingMode::FragmentPartitions(_)
             ) {
                 return Err(Error::not_supported_source(
                     "scan_range_after_filter not yet supported with multiple partitions"
@@ -1596,14 +1735,18 @@ impl FilteredReadExec {
             }
         }
         let output_schema = Arc::new(options.projection.to_arrow_schema());
-        let num_partitions = match options.threading_mode {
-            FilteredReadThreadingMode::OnePartitionMultipleThreads(_) => 1,
-            FilteredReadThreadingMode::MultiplePartitions(n) => n,
+        let num_partitions = options.threading_mode.num_partitions();
+        let partitioning = match options.threading_mode {
+            // Each partition holds whole fragments, which DataFusion has no name for
+            FilteredReadThreadingMode::FragmentPartitions(n) => {
+                Partitioning::UnknownPartitioning(n)
+            }
+            _ => Partitioning::RoundRobinBatch(num_partitions),
         };
 
         let properties = Arc::new(PlanProperties::new(
             EquivalenceProperties::new(output_schema),
-            Partitioning::RoundRobinBatch(num_partitions),
+            partitioning,
             EmissionType::Incremental,
             Boundedness::Bounded,
         ));
@@ -1939,10 +2082,19 @@ impl ExecutionPlan for FilteredReadExec {
                 } else {
                     total_rows
                 };
+            // Without a full filter, an after-filter range can only come with a refine filter
+            // or an index input, which may drop more rows, so the count is only an upper bound
+            let total_rows =
+                if let Some(scan_range_after_filter) = &self.options.scan_range_after_filter {
+                    total_rows.min(scan_range_after_filter.end - scan_range_after_filter.start)
+                } else {
+                    total_rows
+                };
 
             let total_rows = if partition.is_some() {
                 match self.options.threading_mode {
-                    FilteredReadThreadingMode::MultiplePartitions(num_partitions) => {
+                    FilteredReadThreadingMode::MultiplePartitions(num_partitions)
+                    | FilteredReadThreadingMode::FragmentPartitions(num_partitions) => {
                         total_rows / num_partitions as u64
                     }
                     // Pretty sure this shouldn't be encountered in practice
@@ -1952,8 +2104,36 @@ impl ExecutionPlan for FilteredReadExec {
                 total_rows
             };
 
+            // The zone maps of the fragments bound the columns of any subset of their rows
+            let dataset_schema = self.dataset.schema();
+            let column_statistics = self
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| {
+                    let Some(field) = dataset_schema.field(field.name()) else {
+                        return ColumnStatistics::new_unknown();
+                    };
+                    let statistics = column_statistics(&fragments, field);
+                    if partition.is_some()
+                        || self.options.scan_range_before_filter.is_some()
+                        || self.options.scan_range_after_filter.is_some()
+                    {
+                        statistics.to_inexact()
+                    } else {
+                        statistics
+                    }
+                })
+                .collect();
+
+            let num_rows = if self.options.scan_range_after_filter.is_some() {
+                Precision::Inexact(total_rows as usize)
+            } else {
+                Precision::Exact(total_rows as usize)
+            };
             return Ok(Statistics {
-                num_rows: Precision::Exact(total_rows as usize),
+                num_rows,
+                column_statistics,
                 ..datafusion::physical_plan::Statistics::new_unknown(self.schema().as_ref())
             });
         };
@@ -2053,6 +2233,43 @@ impl ExecutionPlan for FilteredReadExec {
         }
     }
 
+    fn repartitioned(
+        &self,
+        target_partitions: usize,
+        _config: &ConfigOptions,
+    ) -> DataFusionResult<Option<Arc<dyn ExecutionPlan>>> {
+        if !self.options.allow_repartition
+            || self.options.scan_range_before_filter.is_some()
+            || self.options.scan_range_after_filter.is_some()
+            || !matches!(
+                self.options.threading_mode,
+                FilteredReadThreadingMode::OnePartitionMultipleThreads(_)
+            )
+        {
+            return Ok(None);
+        }
+
+        // Fragments are never split so there is no point in more partitions than fragments
+        let num_fragments = self
+            .options
+            .fragments
+            .as_ref()
+            .map(|fragments| fragments.len())
+            .unwrap_or_else(|| self.dataset.fragments().len());
+        let num_partitions = target_partitions.min(num_fragments);
+        if num_partitions <= 1 {
+            return Ok(None);
+        }
+
+        let mut options = self.options.clone();
+        options.threading_mode = FilteredReadThreadingMode::FragmentPartitions(num_partitions);
+        Ok(Some(Arc::new(Self::try_new(
+            self.dataset.clone(),
+            options,
+            self.index_input.clone(),
+        )?)))
+    }
+
     fn execute(
         &self,
         partition: usize,
@@ -2087,6 +2304,7 @@ impl ExecutionPlan for FilteredReadExec {
         if matches!(
             self.options.threading_mode,
             FilteredReadThreadingMode::MultiplePartitions(_)
+                | FilteredReadThreadingMode::FragmentPartitions(_)
         ) {
             return None;
         }
@@ -2795,14 +3013,14 @@ mod tests {
 
         // With a filter, we don't know the exact count but DF can make some guesses
 
-        // In this case DF recognizes the expression as simple and without column stats it errs on
-        // the side of nothing getting filtered out.
+        // In this case DF recognizes the expression as simple and estimates the selectivity from
+        // the column bounds in the zone maps (0..400, so about half the rows)
         let options = base_options
             .clone()
             .with_filter_plan(fixture.filter_plan("not_indexed >= 200", false).await);
         let plan = fixture.make_plan(options).await;
         let stats = plan.partition_statistics(None).unwrap();
-        assert_eq!(stats.num_rows, Precision::Inexact(250));
+        assert_eq!(stats.num_rows, Precision::Inexact(125));
 
         // In this case DF doesn't recognize the expression as simple and so it assumes a default
         // selectivity of 0.2
@@ -2828,8 +3046,32 @@ mod tests {
             );
         let plan = fixture.make_plan(options).await;
         let stats = plan.partition_statistics(None).unwrap();
-        assert_eq!(stats.num_rows, Precision::Inexact(250));
+        assert_eq!(stats.num_rows, Precision::Inexact(125));
         assert_eq!(stats.column_statistics.len(), 1);
+
+        // With only an index input, an after-filter range bounds the count
+        let options = base_options
+            .clone()
+            .with_filter_plan(fixture.filter_plan("fully_indexed >= 200", true).await)
+            .with_scan_range_after_filter(0..10)
+            .unwrap();
+        let index_input = fixture.index_input(&options).await;
+        assert!(index_input.is_some());
+        let options = FilteredReadOptions {
+            full_filter: None,
+            refine_filter: None,
+            ..options
+        };
+        let plan =
+            FilteredReadExec::try_new(fixture.dataset.clone(), options, index_input).unwrap();
+        let stats = plan.partition_statistics(None).unwrap();
+        assert_eq!(stats.num_rows, Precision::Inexact(10));
+        assert!(
+            stats
+                .column_statistics
+                .iter()
+                .all(|stats| !stats.min_value.is_exact().unwrap_or_default())
+        );
     }
 
     #[test_log::test(tokio::test)]
@@ -3737,8 +3979,10 @@ mod tests {
         let fixture = TestFixture::new().await;
         // not_indexed values in the fixture go up to ~400; this filter matches nothing
         let filter_plan = fixture.filter_plan("not_indexed > 10000", false).await;
-        let options =
-            FilteredReadOptions::basic_full_read(&fixture.dataset).with_filter_plan(filter_plan);
+        // Zone maps would rule out every fragment, so disable them to force a read
+        let options = FilteredReadOptions::basic_full_read(&fixture.dataset)
+            .with_filter_plan(filter_plan)
+            .with_zone_map_pruning_disabled();
         let filtered_read =
             Arc::new(FilteredReadExec::try_new(fixture.dataset.clone(), options, None).unwrap());
 
@@ -3778,6 +4022,94 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_zone_maps_prune_fragments() {
+        let fixture = TestFixture::new().await;
+        // Only fragment 3 (values 300..400) can contain matches
+        let filter_plan = fixture.filter_plan("not_indexed >= 350", false).await;
+
+        for (disable_pruning, expected_fragments) in [(false, 1), (true, 3)] {
+            let mut options = FilteredReadOptions::basic_full_read(&fixture.dataset)
+                .with_filter_plan(filter_plan.clone())
+                .with_projection(
+                    fixture
+                        .dataset
+                        .empty_projection()
+                        .union_column("not_indexed", OnMissing::Error)
+                        .unwrap(),
+                );
+            if disable_pruning {
+                options = options.with_zone_map_pruning_disabled();
+            }
+            let filtered_read = Arc::new(
+                FilteredReadExec::try_new(fixture.dataset.clone(), options, None).unwrap(),
+            );
+            let batches = filtered_read
+                .execute(0, Arc::new(TaskContext::default()))
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            let batch = concat_batches(&batches[0].schema(), &batches).unwrap();
+            assert_eq!(
+                batch["not_indexed"].as_ref(),
+                &UInt32Array::from_iter_values(350..400) as &dyn Array
+            );
+
+            let fragments_scanned = filtered_read
+                .metrics()
+                .unwrap()
+                .sum_by_name("fragments_scanned")
+                .map(|v| v.as_usize())
+                .unwrap_or(0);
+            assert_eq!(fragments_scanned, expected_fragments);
+            let fragments_pruned = filtered_read
+                .metrics()
+                .unwrap()
+                .sum_by_name("fragments_pruned")
+                .map(|v| v.as_usize())
+                .unwrap_or(0);
+            assert_eq!(fragments_pruned, 3 - expected_fragments);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repartitioned_by_fragment() {
+        let fixture = TestFixture::new().await;
+        let options = FilteredReadOptions::basic_full_read(&fixture.dataset).with_projection(
+            fixture
+                .dataset
+                .empty_projection()
+                .union_column("not_indexed", OnMissing::Error)
+                .unwrap(),
+        );
+        let config = ConfigOptions::default();
+
+        // Repartitioning must be allowed explicitly
+        let plan = fixture.make_plan(options.clone()).await;
+        assert!(plan.repartitioned(8, &config).unwrap().is_none());
+
+        // There are only 3 fragments to spread across the partitions
+        let plan = fixture.make_plan(options.with_repartitioning()).await;
+        let plan = plan.repartitioned(8, &config).unwrap().unwrap();
+        assert_eq!(plan.output_partitioning().partition_count(), 3);
+
+        // Each partition reads whole fragments
+        for (partition, expected) in [(0, 0..100), (1, 250..300), (2, 300..400)] {
+            let batches = plan
+                .execute(partition, Arc::new(TaskContext::default()))
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            let batch = concat_batches(&plan.schema(), &batches).unwrap();
+            assert_eq!(
+                batch["not_indexed"].as_ref(),
+                &UInt32Array::from_iter_values(expected) as &dyn Array
+            );
+        }
+    }
+
     /// Test that direct execution gives the same result as get_plan + execute_with_plan
     #[test_log::test(tokio::test)]
     async fn test_plan_round_trip() {
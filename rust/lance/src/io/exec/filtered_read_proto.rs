@@ -404,6 +404,9 @@ fn threading_mode_to_proto(mode: &FilteredReadThreadingMode) -> pb::FilteredRead
         FilteredReadThreadingMode::MultiplePartitions(n) => {
             pb::filtered_read_threading_mode_proto::Mode::MultiplePartitions(*n as u64)
         }
+        FilteredReadThreadingMode::FragmentPartitions(n) => {
+            pb::filtered_read_threading_mode_proto::Mode::FragmentPartitions(*n as u64)
+        }
     };
     pb::FilteredReadThreadingModeProto {
         mode: Some(mode_oneof),
@@ -420,6 +423,9 @@ fn threading_mode_from_proto(
         Some(pb::filtered_read_threading_mode_proto::Mode::MultiplePartitions(n)) => {
             Ok(FilteredReadThreadingMode::MultiplePartitions(*n as usize))
         }
+        Some(pb::filtered_read_threading_mode_proto::Mode::FragmentPartitions(n)) => {
+            Ok(FilteredReadThreadingMode::FragmentPartitions(*n as usize))
+        }
         None => Err(Error::invalid_input_source(
             "Missing threading mode in proto".into(),
         )),
@@ -514,6 +520,11 @@ mod tests {
         let proto = threading_mode_to_proto(&mode);
         let back = threading_mode_from_proto(&proto).unwrap();
         assert_eq!(mode, back);
+
+        let mode = FilteredReadThreadingMode::FragmentPartitions(3);
+        let proto = threading_mode_to_proto(&mode);
+        let back = threading_mode_from_proto(&proto).unwrap();
+        assert_eq!(mode, back);
     }
 
     #[test]
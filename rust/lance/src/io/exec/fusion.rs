@@ -0,0 +1,438 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Fusion of vector and full text search results for hybrid search.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+use arrow::datatypes::{Float32Type, UInt64Type};
+use arrow_array::{Float32Array, RecordBatch, UInt64Array, cast::AsArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties, execute_stream,
+};
+use datafusion_physical_expr::{Distribution, EquivalenceProperties, Partitioning};
+use futures::{StreamExt, TryStreamExt, stream};
+use lance_core::{Error, ROW_ID, ROW_ID_FIELD, Result};
+use lance_index::scalar::inverted::SCORE_COL;
+use lance_index::vector::DIST_COL;
+
+/// Column holding the fused relevance of a hybrid search result.  Higher is better.
+pub const RELEVANCE_SCORE_COL: &str = "_relevance_score";
+
+pub static HYBRID_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        ROW_ID_FIELD.clone(),
+        Field::new(DIST_COL, DataType::Float32, true),
+        Field::new(SCORE_COL, DataType::Float32, true),
+        Field::new(RELEVANCE_SCORE_COL, DataType::Float32, false),
+    ]))
+});
+
+/// How the results of the vector search and the full text search are combined
+/// in a hybrid search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FusionMethod {
+    /// Reciprocal rank fusion.
+    ///
+    /// Each result scores `1 / (k + rank)` in every result list it appears in, where
+    /// `rank` starts at 1.  Only the order of each list matters, so distances and
+    /// BM25 scores never need to be made comparable.
+    ReciprocalRank { k: f32 },
+    /// A weighted sum of min-max normalized scores.
+    ///
+    /// Distances are normalized so that the nearest result scores 1 and the farthest
+    /// scores [`MIN_NORMALIZED_SCORE`], BM25 scores so that the best match scores 1.
+    /// The vector score is weighted by `vector_weight` and the full text score by
+    /// `1 - vector_weight`.  A result missing from one of the lists scores 0 in that
+    /// list, so it ranks below the worst result that list did return.
+    Weighted { vector_weight: f32 },
+}
+
+impl Default for FusionMethod {
+    fn default() -> Self {
+        Self::ReciprocalRank { k: 60.0 }
+    }
+}
+
+impl FusionMethod {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::ReciprocalRank { k } if !(k.is_finite() && *k >= 0.0) => Err(
+                Error::invalid_input(format!("RRF k must be a non-negative number, got {k}")),
+            ),
+            Self::Weighted { vector_weight } if !(0.0..=1.0).contains(vector_weight) => {
+                Err(Error::invalid_input(format!(
+                    "vector_weight must be between 0 and 1, got {vector_weight}"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn fuse(&self, vector: &[(u64, f32)], fts: &[(u64, f32)]) -> HashMap<u64, f32> {
+        let mut fused = HashMap::with_capacity(vector.len() + fts.len());
+        match self {
+            Self::ReciprocalRank { k } => {
+                for results in [vector, fts] {
+                    for (rank, (row_id, _)) in results.iter().enumerate() {
+                        *fused.entry(*row_id).or_insert(0.0) += 1.0 / (k + rank as f32 + 1.0);
+                    }
+                }
+            }
+            Self::Weighted { vector_weight } => {
+                for (row_id, score) in normalize(vector, false) {
+                    *fused.entry(row_id).or_insert(0.0) += vector_weight * score;
+                }
+                for (row_id, score) in normalize(fts, true) {
+                    *fused.entry(row_id).or_insert(0.0) += (1.0 - vector_weight) * score;
+                }
+            }
+        }
+        fused
+    }
+}
+
+impl std::fmt::Display for FusionMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReciprocalRank { k } => write!(f, "rrf(k={k})"),
+            Self::Weighted { vector_weight } => {
+                write!(f, "weighted(vector_weight={vector_weight})")
+            }
+        }
+    }
+}
+
+/// The normalized score of the worst result of a list in [`FusionMethod::Weighted`].
+///
+/// It is above 0 so the worst result still scores higher than a row that is missing
+/// from the list.
+pub const MIN_NORMALIZED_SCORE: f32 = 0.01;
+
+/// Min-max normalize values into [`MIN_NORMALIZED_SCORE`, 1] so that the best value
+/// maps to 1.
+///
+/// If all values are equal they are all considered the best.
+fn normalize(
+    results: &[(u64, f32)],
+    higher_is_better: bool,
+) -> impl Iterator<Item = (u64, f32)> + '_ {
+    let (min, max) = results
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), (_, s)| {
+            (min.min(*s), max.max(*s))
+        });
+    let range = max - min;
+    results.iter().map(move |(row_id, score)| {
+        let normalized = if range > 0.0 {
+            (score - min) / range
+        } else {
+            1.0
+        };
+        let normalized = if higher_is_better || range <= 0.0 {
+            normalized
+        } else {
+            1.0 - normalized
+        };
+        (
+            *row_id,
+            MIN_NORMALIZED_SCORE + (1.0 - MIN_NORMALIZED_SCORE) * normalized,
+        )
+    })
+}
+
+/// Collect `(row_id, value)` pairs from the search results, in input order.
+///
+/// Null values (e.g. rows that could not be scored) are dropped.
+fn collect_results(batches: &[RecordBatch], value_col: &str) -> DataFusionResult<Vec<(u64, f32)>> {
+    let mut results = Vec::new();
+    for batch in batches {
+        let (Some(row_ids), Some(values)) = (
+            batch.column_by_name(ROW_ID),
+            batch.column_by_name(value_col),
+        ) else {
+            return Err(DataFusionError::Internal(format!(
+                "hybrid search input is missing the {ROW_ID} or {value_col} column"
+            )));
+        };
+        let row_ids = row_ids.as_primitive::<UInt64Type>();
+        let values = values.as_primitive::<Float32Type>();
+        results.extend(
+            row_ids
+                .values()
+                .iter()
+                .zip(values.iter())
+                .filter_map(|(row_id, value)| value.map(|v| (*row_id, v))),
+        );
+    }
+    Ok(results)
+}
+
+/// Combines the results of a vector search and a full text search into a single
+/// result list ranked by [`RELEVANCE_SCORE_COL`].
+///
+/// The vector input must provide `_rowid` and `_distance`, the full text input
+/// `_rowid` and `_score`.  Both inputs are bounded by their own limit so the node
+/// buffers them in memory.  All partitions of each input are read, so the inputs
+/// don't need to be coalesced first.
+///
+/// The output has `_rowid`, `_distance`, `_score` and `_relevance_score` sorted by
+/// decreasing relevance.  `_distance` / `_score` are null for rows only found by
+/// the other search.
+#[derive(Debug)]
+pub struct HybridFusionExec {
+    vector: Arc<dyn ExecutionPlan>,
+    fts: Arc<dyn ExecutionPlan>,
+    method: FusionMethod,
+
+    properties: Arc<PlanProperties>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl HybridFusionExec {
+    pub fn new(
+        vector: Arc<dyn ExecutionPlan>,
+        fts: Arc<dyn ExecutionPlan>,
+        method: FusionMethod,
+    ) -> Self {
+        let properties = Arc::new(PlanProperties::new(
+            EquivalenceProperties::new(HYBRID_SCHEMA.clone()),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        ));
+        Self {
+            vector,
+            fts,
+            method,
+            properties,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    pub fn method(&self) -> &FusionMethod {
+        &self.method
+    }
+}
+
+impl DisplayAs for HybridFusionExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "HybridFusion: method={}", self.method)
+            }
+            DisplayFormatType::TreeRender => {
+                write!(f, "HybridFusion\nmethod={}", self.method)
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for HybridFusionExec {
+    fn name(&self) -> &str {
+        "HybridFusionExec"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.vector, &self.fts]
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        // Ranks are only meaningful over the complete, ordered result lists.
+        vec![Distribution::SinglePartition, Distribution::SinglePartition]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false, false]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.len() != 2 {
+            return Err(DataFusionError::Internal(
+                "HybridFusionExec requires exactly two children".to_string(),
+            ));
+        }
+        let fts = children.pop().unwrap();
+        let vector = children.pop().unwrap();
+        Ok(Arc::new(Self::new(vector, fts, self.method)))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "HybridFusionExec has a single output partition, got {partition}"
+            )));
+        }
+        // The plan is built by the scanner rather than the physical optimizer, so
+        // the required input distribution is not enforced.  Read every partition.
+        let vector = execute_stream(self.vector.clone(), context.clone())?;
+        let fts = execute_stream(self.fts.clone(), context)?;
+        let method = self.method;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let stream = stream::once(async move {
+            let (vector, fts) =
+                futures::try_join!(vector.try_collect::<Vec<_>>(), fts.try_collect::<Vec<_>>())?;
+
+            let _timer = baseline_metrics.elapsed_compute().timer();
+            let mut vector = collect_results(&vector, DIST_COL)?;
+            let mut fts = collect_results(&fts, SCORE_COL)?;
+            // Ranks are derived from these orders, so don't rely on the inputs being sorted.
+            vector.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+            fts.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+            let distances = vector.iter().copied().collect::<HashMap<_, _>>();
+            let scores = fts.iter().copied().collect::<HashMap<_, _>>();
+
+            let mut fused = method.fuse(&vector, &fts).into_iter().collect::<Vec<_>>();
+            // Break ties by row id so the output is deterministic.
+            fused.sort_unstable_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then(a_id.cmp(b_id)));
+            baseline_metrics.record_output(fused.len());
+
+            let row_ids = UInt64Array::from_iter_values(fused.iter().map(|(row_id, _)| *row_id));
+            let dist = Float32Array::from_iter(
+                fused
+                    .iter()
+                    .map(|(row_id, _)| distances.get(row_id).copied()),
+            );
+            let score = Float32Array::from_iter(
+                fused.iter().map(|(row_id, _)| scores.get(row_id).copied()),
+            );
+            let relevance = Float32Array::from_iter_values(fused.iter().map(|(_, r)| *r));
+            Ok(RecordBatch::try_new(
+                HYBRID_SCHEMA.clone(),
+                vec![
+                    Arc::new(row_ids),
+                    Arc::new(dist),
+                    Arc::new(score),
+                    Arc::new(relevance),
+                ],
+            )?)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream.boxed(),
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.properties
+    }
+
+    fn supports_limit_pushdown(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let vector = [(1, 0.1), (2, 0.2), (3, 0.3)];
+        let fts = [(3, 9.0), (4, 5.0)];
+        let fused = FusionMethod::ReciprocalRank { k: 0.0 }.fuse(&vector, &fts);
+        assert_eq!(fused.len(), 4);
+        assert_eq!(fused[&1], 1.0);
+        assert_eq!(fused[&2], 0.5);
+        // Third in the vector list and first in the fts list
+        assert_eq!(fused[&3], 1.0 / 3.0 + 1.0);
+        assert_eq!(fused[&4], 0.5);
+    }
+
+    #[test]
+    fn test_weighted_fusion() {
+        let vector = [(1, 0.0), (2, 1.0)];
+        let fts = [(2, 4.0), (3, 2.0)];
+        let fused = FusionMethod::Weighted {
+            vector_weight: 0.25,
+        }
+        .fuse(&vector, &fts);
+        let assert_close = |row_id: u64, expected: f32| {
+            assert!(
+                (fused[&row_id] - expected).abs() < 1e-6,
+                "row {row_id}: {} != {expected}",
+                fused[&row_id]
+            );
+        };
+        assert_close(1, 0.25);
+        assert_close(2, 0.25 * MIN_NORMALIZED_SCORE + 0.75);
+        // The worst fts match still beats a row missing from the fts results
+        assert_close(3, 0.75 * MIN_NORMALIZED_SCORE);
+        assert!(fused[&3] > 0.0);
+
+        // A single match is the best match of its list
+        let fused = FusionMethod::Weighted { vector_weight: 0.5 }.fuse(&[], &[(7, 3.0)]);
+        assert_eq!(fused[&7], 0.5);
+
+        assert!(
+            FusionMethod::Weighted { vector_weight: 1.5 }
+                .validate()
+                .is_err()
+        );
+        assert!(FusionMethod::default().validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fusion_reads_all_input_partitions() {
+        use datafusion::datasource::memory::MemorySourceConfig;
+        use datafusion::physical_plan::collect;
+
+        let input = |value_col: &str, partitions: Vec<Vec<(u64, f32)>>| {
+            let schema = Arc::new(Schema::new(vec![
+                ROW_ID_FIELD.clone(),
+                Field::new(value_col, DataType::Float32, true),
+            ]));
+            let partitions = partitions
+                .into_iter()
+                .map(|rows| {
+                    vec![
+                        RecordBatch::try_new(
+                            schema.clone(),
+                            vec![
+                                Arc::new(UInt64Array::from_iter_values(rows.iter().map(|r| r.0))),
+                                Arc::new(Float32Array::from_iter_values(rows.iter().map(|r| r.1))),
+                            ],
+                        )
+                        .unwrap(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            MemorySourceConfig::try_new_exec(&partitions, schema, None).unwrap()
+                as Arc<dyn ExecutionPlan>
+        };
+        let vector = input(DIST_COL, vec![vec![(1, 0.1)], vec![(2, 0.2)]]);
+        let fts = input(SCORE_COL, vec![vec![(3, 2.0)], vec![(2, 5.0)], vec![]]);
+        let fusion = HybridFusionExec::new(vector, fts, FusionMethod::default());
+
+        let batches = collect(Arc::new(fusion), Arc::new(TaskContext::default()))
+            .await
+            .unwrap();
+        let row_ids = batches
+            .iter()
+            .flat_map(|batch| batch[ROW_ID].as_primitive::<UInt64Type>().values().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(row_ids, vec![2, 1, 3]);
+    }
+}
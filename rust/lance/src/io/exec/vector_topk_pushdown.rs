@@ -0,0 +1,466 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+//! Physical optimizer rule that answers `ORDER BY <distance>(vector, query)
+//! LIMIT k` with a vector search.
+//!
+//! DataFusion plans a nearest neighbor query written in SQL as a top-k sort
+//! over a full scan:
+//!
+//! ```text
+//! SortExec(fetch = k, expr = [l2_distance(vector, query) ASC])
+//!   └── ProjectionExec / CooperativeExec / ...
+//!         └── FilteredReadExec(full_filter = …)
+//! ```
+//!
+//! The rule replaces the read with a [`VectorTopKReadExec`], which runs the
+//! scanner's vector search for the `k` nearest rows, using the vector index
+//! when there is one and applying the filter of the read as a prefilter:
+//!
+//! ```text
+//! SortExec(fetch = k, expr = [l2_distance(vector, query) ASC])
+//!   └── ProjectionExec / CooperativeExec / ...
+//!         └── VectorTopKReadExec(column = vector, k, filter = …)
+//! ```
+//!
+//! Everything above the read is kept, so the distance is still computed and
+//! sorted on exactly as the query asked; only the rows reaching the sort
+//! change. As with any vector search, the rows returned through an index are
+//! approximate nearest neighbors, and rows with a null vector are never
+//! returned.
+//!
+//! The rule only fires when every node between the sort and the read is
+//! row-preserving, so that the `k` nearest rows of the read are also the `k`
+//! nearest rows reaching the sort.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field as ArrowField, Schema as ArrowSchema, SchemaRef};
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::execution::{SendableRecordBatchStream, TaskContext};
+use datafusion::logical_expr::Expr;
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+#[allow(deprecated)]
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    ColumnarValue, DisplayAs, DisplayFormatType, ExecutionPlan, PlanProperties,
+    coalesce_partitions::CoalescePartitionsExec, coop::CooperativeExec, execute_stream,
+    projection::ProjectionExec, repartition::RepartitionExec, sorts::sort::SortExec,
+};
+use datafusion::scalar::ScalarValue;
+use datafusion_physical_expr::utils::collect_columns;
+use datafusion_physical_expr::{
+    EquivalenceProperties, Partitioning, PhysicalExpr, ScalarFunctionExpr, expressions::Column,
+};
+use futures::{StreamExt, TryStreamExt};
+use lance_core::{ROW_ADDR, ROW_ID};
+use lance_datafusion::udf::distance::VectorDistance;
+use lance_linalg::distance::DistanceType;
+
+use super::filtered_read::FilteredReadExec;
+use super::take::TakeExec;
+use crate::Dataset;
+
+/// Physical optimizer rule that rewrites the read below a top-k sort on a
+/// vector distance into a [`VectorTopKReadExec`].
+///
+/// The distance must be one of the vector distance functions registered by
+/// [`lance_datafusion::udf::register_functions`], applied to a vector column
+/// of the dataset and a constant query vector, and sorted ascending with
+/// nulls last.
+#[derive(Debug)]
+pub struct VectorTopKPushdown;
+
+impl PhysicalOptimizerRule for VectorTopKPushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        Ok(plan
+            .transform_down(|plan| {
+                let Some(sort) = plan.as_any().downcast_ref::<SortExec>() else {
+                    return Ok(Transformed::no(plan));
+                };
+                if let Some(input) = try_rewrite(sort)? {
+                    return Ok(Transformed::yes(plan.with_new_children(vec![input])?));
+                }
+                Ok(Transformed::no(plan))
+            })?
+            .data)
+    }
+
+    fn name(&self) -> &str {
+        "vector_topk_pushdown"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// A nearest neighbor query found in a sort expression.
+#[derive(Debug, Clone)]
+struct VectorQuery {
+    column: String,
+    query: ArrayRef,
+    distance_type: DistanceType,
+}
+
+impl VectorQuery {
+    /// Match `<distance>(column, <constant>)`.
+    fn try_new(expr: &Arc<dyn PhysicalExpr>) -> Option<Self> {
+        let function = expr.as_any().downcast_ref::<ScalarFunctionExpr>()?;
+        let distance = function
+            .fun()
+            .inner()
+            .as_any()
+            .downcast_ref::<VectorDistance>()?;
+        let [vector, query] = function.args() else {
+            return None;
+        };
+        let column = vector.as_any().downcast_ref::<Column>()?;
+        if !collect_columns(query).is_empty() {
+            return None;
+        }
+        let empty = RecordBatch::new_empty(Arc::new(ArrowSchema::empty()));
+        let ColumnarValue::Scalar(ScalarValue::FixedSizeList(query)) =
+            query.evaluate(&empty).ok()?
+        else {
+            return None;
+        };
+        if query.is_null(0) {
+            return None;
+        }
+        Some(Self {
+            column: column.name().to_string(),
+            query: query.value(0),
+            distance_type: distance.distance_type(),
+        })
+    }
+}
+
+/// What is known about the sort expression at some node of the input.
+enum Target {
+    /// The sort expression, in terms of the output of the node.
+    Expr(Arc<dyn PhysicalExpr>),
+    /// The vector column is added by a node above, only row-preserving nodes
+    /// that don't touch columns may follow.
+    Resolved(VectorQuery),
+}
+
+fn try_rewrite(sort: &SortExec) -> DFResult<Option<Arc<dyn ExecutionPlan>>> {
+    let Some(k) = sort.fetch() else {
+        return Ok(None);
+    };
+    let [sort_expr] = sort.expr().as_ref() else {
+        return Ok(None);
+    };
+    // A vector search returns the nearest rows first and skips null vectors
+    if sort_expr.options.descending || sort_expr.options.nulls_first {
+        return Ok(None);
+    }
+    push_down(sort.input(), Target::Expr(sort_expr.expr.clone()), k)
+}
+
+/// Walk through row-preserving nodes down to the [`FilteredReadExec`] and
+/// replace it, rebuilding the nodes in between.
+fn push_down(
+    plan: &Arc<dyn ExecutionPlan>,
+    target: Target,
+    k: usize,
+) -> DFResult<Option<Arc<dyn ExecutionPlan>>> {
+    if let Some(read) = plan.as_any().downcast_ref::<FilteredReadExec>() {
+        let query = match target {
+            Target::Expr(expr) => VectorQuery::try_new(&expr),
+            Target::Resolved(query) => Some(query),
+        };
+        return Ok(query.and_then(|query| VectorTopKReadExec::try_replace(read, query, k)));
+    }
+
+    let child_target = if let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() {
+        let Target::Expr(expr) = target else {
+            return Ok(None);
+        };
+        match projection.projection_expr().unproject_expr(&expr) {
+            Ok(expr) => Target::Expr(expr),
+            Err(_) => return Ok(None),
+        }
+    } else if let Some(take) = plan.as_any().downcast_ref::<TakeExec>() {
+        // TakeExec appends the columns it fetches to those of its input
+        let input_width = take.children()[0].schema().fields().len();
+        match target {
+            Target::Expr(expr)
+                if collect_columns(&expr)
+                    .iter()
+                    .any(|column| column.index() >= input_width) =>
+            {
+                let Some(query) = VectorQuery::try_new(&expr) else {
+                    return Ok(None);
+                };
+                Target::Resolved(query)
+            }
+            target => target,
+        }
+    } else if plan.as_any().is::<RepartitionExec>()
+        || plan.as_any().is::<CoalescePartitionsExec>()
+        || plan.as_any().is::<CooperativeExec>()
+        || {
+            #[allow(deprecated)]
+            plan.as_any().is::<CoalesceBatchesExec>()
+        }
+    {
+        target
+    } else {
+        return Ok(None);
+    };
+
+    let Some(input) = push_down(plan.children()[0], child_target, k)? else {
+        return Ok(None);
+    };
+    Ok(Some(plan.clone().with_new_children(vec![input])?))
+}
+
+/// Reads the `k` rows of a dataset nearest to a query vector, with the
+/// vector search of the scanner.
+///
+/// This replaces a [`FilteredReadExec`] below a top-k sort on the distance to
+/// the query, see [`VectorTopKPushdown`]. It outputs the same columns as the
+/// read it replaces, and the filter of that read is applied as a prefilter of
+/// the search.
+#[derive(Debug)]
+pub struct VectorTopKReadExec {
+    search: VectorSearch,
+    properties: Arc<PlanProperties>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+/// The vector search run by a [`VectorTopKReadExec`].
+#[derive(Debug, Clone)]
+struct VectorSearch {
+    dataset: Arc<Dataset>,
+    query: VectorQuery,
+    k: usize,
+    filter: Option<Expr>,
+    batch_size: Option<u32>,
+    /// The columns of the replaced read.
+    schema: SchemaRef,
+}
+
+impl VectorTopKReadExec {
+    /// Build the replacement of `read`, if the search can produce its output.
+    fn try_replace(
+        read: &FilteredReadExec,
+        query: VectorQuery,
+        k: usize,
+    ) -> Option<Arc<dyn ExecutionPlan>> {
+        let options = read.options();
+        if options.scan_range_before_filter.is_some()
+            || options.scan_range_after_filter.is_some()
+            || options.with_deleted_rows
+            || options.fragments.is_some()
+            || options.only_indexed_fragments
+        {
+            return None;
+        }
+
+        let dataset = read.dataset();
+        let is_vector_column = dataset
+            .schema()
+            .field(&query.column)
+            .is_some_and(|field| matches!(field.data_type(), DataType::FixedSizeList(_, _)));
+        if !is_vector_column {
+            return None;
+        }
+        // The search must be able to produce every column of the read
+        let schema = read.schema();
+        if schema.fields().is_empty() {
+            return None;
+        }
+        let projectable = schema.fields().iter().all(|field| {
+            field.name() == ROW_ID
+                || field.name() == ROW_ADDR
+                || dataset
+                    .schema()
+                    .field(field.name())
+                    .is_some_and(|ds_field| {
+                        ArrowField::from(ds_field).data_type() == field.data_type()
+                    })
+        });
+        if !projectable {
+            return None;
+        }
+
+        let properties = Arc::new(PlanProperties::new(
+            EquivalenceProperties::new(schema.clone()),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        ));
+        Some(Arc::new(Self {
+            search: VectorSearch {
+                dataset: dataset.clone(),
+                query,
+                k,
+                filter: options.full_filter.clone(),
+                batch_size: options.batch_size,
+                schema,
+            },
+            properties,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }))
+    }
+}
+
+impl VectorSearch {
+    async fn create_plan(&self) -> crate::Result<Arc<dyn ExecutionPlan>> {
+        let mut scanner = self.dataset.scan();
+        let mut columns = Vec::with_capacity(self.schema.fields().len());
+        for field in self.schema.fields() {
+            match field.name().as_str() {
+                ROW_ID => {
+                    scanner.with_row_id();
+                }
+                ROW_ADDR => {
+                    scanner.with_row_address();
+                }
+                name => columns.push(name),
+            }
+        }
+        scanner.project(&columns)?;
+        if let Some(filter) = &self.filter {
+            scanner.filter_expr(filter.clone());
+        }
+        if let Some(batch_size) = self.batch_size {
+            scanner.batch_size(batch_size as usize);
+        }
+        scanner
+            .prefilter(true)
+            .nearest(&self.query.column, self.query.query.as_ref(), self.k)?
+            .distance_metric(self.query.distance_type);
+        scanner.create_plan().await
+    }
+
+    /// Select the columns of the read from a batch of search results.
+    fn project(&self, batch: RecordBatch) -> DFResult<RecordBatch> {
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| {
+                batch.column_by_name(field.name()).cloned().ok_or_else(|| {
+                    DataFusionError::Internal(format!(
+                        "Vector search output is missing column {}",
+                        field.name()
+                    ))
+                })
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+impl DisplayAs for VectorTopKReadExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "VectorTopKRead: uri={}, column={}, k={}, distance_type={}",
+                    self.search.dataset.uri(),
+                    self.search.query.column,
+                    self.search.k,
+                    self.search.query.distance_type
+                )?;
+                if let Some(filter) = &self.search.filter {
+                    write!(f, ", prefilter={}", filter)?;
+                }
+                Ok(())
+            }
+            DisplayFormatType::TreeRender => {
+                write!(
+                    f,
+                    "VectorTopKRead\ncolumn={}\nk={}",
+                    self.search.query.column, self.search.k
+                )
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for VectorTopKReadExec {
+    fn name(&self) -> &str {
+        "VectorTopKReadExec"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.search.schema.clone()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if !children.is_empty() {
+            return Err(DataFusionError::Internal(
+                "VectorTopKReadExec does not have children".to_string(),
+            ));
+        }
+        Ok(self)
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.properties
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DFResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "VectorTopKReadExec has a single output partition, got {}",
+                partition
+            )));
+        }
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let search = self.search.clone();
+        let stream = futures::stream::once({
+            let search = search.clone();
+            async move {
+                let plan = search.create_plan().await?;
+                execute_stream(plan, context)
+            }
+        })
+        .try_flatten()
+        .map(move |batch| {
+            let batch = search.project(batch?)?;
+            baseline_metrics.record_output(batch.num_rows());
+            Ok(batch)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+}
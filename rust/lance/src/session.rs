@@ -4,12 +4,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use lance_core::cache::{CacheBackend, LanceCache};
+use lance_core::cache::{CacheBackend, CacheUsage, LanceCache};
 use lance_core::deepsize::DeepSizeOf;
 use lance_core::{Error, Result};
 use lance_index::IndexType;
 use lance_io::object_store::ObjectStoreRegistry;
+use lance_io::scheduler::{IoBudget, IoBudgetConfig};
 
+use crate::dataset::embedding::EmbeddingFunction;
 use crate::dataset::{DEFAULT_INDEX_CACHE_SIZE, DEFAULT_METADATA_CACHE_SIZE};
 use crate::session::caches::GlobalMetadataCache;
 use crate::session::index_caches::GlobalIndexCache;
@@ -20,6 +22,87 @@ pub(crate) mod caches;
 pub mod index_caches;
 pub(crate) mod index_extension;
 
+/// Resource budgets shared by every dataset opened with a [`Session`]
+///
+/// The caches and I/O limits are global to the session, so a process serving
+/// many datasets can bound its total memory and I/O with a single session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionConfig {
+    /// The capacity of the index cache, in bytes
+    pub index_cache_size_bytes: usize,
+    /// The capacity of the metadata cache, in bytes
+    pub metadata_cache_size_bytes: usize,
+    /// The max number of IOPS in flight across all datasets, `None` means unlimited
+    pub io_parallelism: Option<usize>,
+    /// The max number of bytes read per second across all datasets, `None` means unlimited
+    pub max_bytes_per_second: Option<u64>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            index_cache_size_bytes: DEFAULT_INDEX_CACHE_SIZE,
+            metadata_cache_size_bytes: DEFAULT_METADATA_CACHE_SIZE,
+            io_parallelism: None,
+            max_bytes_per_second: None,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn with_index_cache_size_bytes(self, index_cache_size_bytes: usize) -> Self {
+        Self {
+            index_cache_size_bytes,
+            ..self
+        }
+    }
+
+    pub fn with_metadata_cache_size_bytes(self, metadata_cache_size_bytes: usize) -> Self {
+        Self {
+            metadata_cache_size_bytes,
+            ..self
+        }
+    }
+
+    pub fn with_io_parallelism(self, io_parallelism: usize) -> Self {
+        Self {
+            io_parallelism: Some(io_parallelism),
+            ..self
+        }
+    }
+
+    pub fn with_max_bytes_per_second(self, max_bytes_per_second: u64) -> Self {
+        Self {
+            max_bytes_per_second: Some(max_bytes_per_second),
+            ..self
+        }
+    }
+
+    fn io_budget_config(&self) -> IoBudgetConfig {
+        IoBudgetConfig {
+            max_concurrent_iops: self.io_parallelism,
+            max_bytes_per_second: self.max_bytes_per_second,
+        }
+    }
+}
+
+/// A snapshot of the resources used by a [`Session`], see [`Session::resource_usage`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionResourceUsage {
+    /// Entries and bytes currently held by the index cache
+    pub index_cache: CacheUsage,
+    /// The capacity of the index cache, `None` if it uses a custom backend
+    pub index_cache_capacity: Option<usize>,
+    /// Entries and bytes currently held by the metadata cache
+    pub metadata_cache: CacheUsage,
+    /// The capacity of the metadata cache, `None` if it uses a custom backend
+    pub metadata_cache_capacity: Option<usize>,
+    /// The I/O limits shared by datasets of the session, if any
+    pub io_budget: Option<IoBudgetConfig>,
+    /// The number of IOPS that can be started without waiting, if IOPS are limited
+    pub available_iops: Option<usize>,
+}
+
 /// A user session holds the runtime state for a [`crate::Dataset`]
 ///
 /// A session will be created automatically when a Dataset is opened.  However, you
@@ -33,6 +116,9 @@ pub(crate) mod index_extension;
 ///  - The index cache is used to cache opened indices and will cache index data
 ///  - The metadata cache is used to cache a variety of dataset metadata (more
 ///    details can be found in the [performance guide](https://lance.org/guide/performance/)
+///
+/// Use [`Session::from_config`] to create a session whose cache sizes and I/O
+/// limits are shared by all datasets opened with it.
 #[derive(Clone)]
 pub struct Session {
     /// Global cache for opened indices.
@@ -52,7 +138,16 @@ pub struct Session {
 
     pub(crate) index_extensions: HashMap<(IndexType, String), Arc<dyn IndexExtension>>,
 
+    embedding_functions: HashMap<String, Arc<dyn EmbeddingFunction>>,
+
     store_registry: Arc<ObjectStoreRegistry>,
+
+    /// I/O budget shared by every dataset opened with this session.
+    io_budget: Option<Arc<IoBudget>>,
+
+    /// Configured cache capacities, `None` when a custom backend is used.
+    index_cache_capacity: Option<usize>,
+    metadata_cache_capacity: Option<usize>,
 }
 
 impl DeepSizeOf for Session {
@@ -83,6 +178,11 @@ impl std::fmt::Debug for Session {
                 "index_extensions",
                 &self.index_extensions.keys().collect::<Vec<_>>(),
             )
+            .field(
+                "embedding_functions",
+                &self.embedding_functions.keys().collect::<Vec<_>>(),
+            )
+            .field("io_budget", &self.io_budget.as_ref().map(|b| b.config()))
             .finish()
     }
 }
@@ -106,7 +206,29 @@ impl Session {
             index_cache: GlobalIndexCache(LanceCache::with_capacity(index_cache_size)),
             metadata_cache: GlobalMetadataCache(LanceCache::with_capacity(metadata_cache_size)),
             index_extensions: HashMap::new(),
+            embedding_functions: HashMap::new(),
+            store_registry,
+            io_budget: None,
+            index_cache_capacity: Some(index_cache_size),
+            metadata_cache_capacity: Some(metadata_cache_size),
+        }
+    }
+
+    /// Create a session from a [`SessionConfig`].
+    ///
+    /// Every dataset opened with the session shares its caches and, if any
+    /// I/O limits are configured, a single [`IoBudget`].
+    pub fn from_config(config: SessionConfig, store_registry: Arc<ObjectStoreRegistry>) -> Self {
+        let session = Self::new(
+            config.index_cache_size_bytes,
+            config.metadata_cache_size_bytes,
             store_registry,
+        );
+        let io_budget_config = config.io_budget_config();
+        if io_budget_config == IoBudgetConfig::default() {
+            session
+        } else {
+            session.with_io_budget(IoBudget::new(io_budget_config))
         }
     }
 
@@ -123,10 +245,43 @@ impl Session {
             index_cache: GlobalIndexCache(LanceCache::with_backend(index_cache_backend)),
             metadata_cache: GlobalMetadataCache(LanceCache::with_capacity(metadata_cache_size)),
             index_extensions: HashMap::new(),
+            embedding_functions: HashMap::new(),
             store_registry,
+            io_budget: None,
+            index_cache_capacity: None,
+            metadata_cache_capacity: Some(metadata_cache_size),
         }
     }
 
+    /// Use a custom backend for the metadata cache.
+    ///
+    /// This can be used to give some kinds of metadata their own capacity, e.g.
+    /// with [`MokaCacheBackend::with_type_capacity`](lance_core::cache::MokaCacheBackend::with_type_capacity),
+    /// so that manifests are not evicted by a large number of deletion files.
+    pub fn with_metadata_cache_backend(mut self, backend: Arc<dyn CacheBackend>) -> Self {
+        self.metadata_cache = GlobalMetadataCache(LanceCache::with_backend(backend));
+        self.metadata_cache_capacity = None;
+        self
+    }
+
+    /// Limit the I/O of all datasets opened with this session.
+    ///
+    /// The IOPS and bandwidth limits of the budget are shared by all scans of
+    /// those datasets, which prevents a process that opens many datasets from
+    /// saturating the network or getting throttled by the object store.
+    /// Datasets opened with an explicit
+    /// [`ObjectStoreParams::io_budget`](lance_io::object_store::ObjectStoreParams::io_budget)
+    /// keep their own budget.
+    pub fn with_io_budget(mut self, io_budget: Arc<IoBudget>) -> Self {
+        self.io_budget = Some(io_budget);
+        self
+    }
+
+    /// Get the I/O budget shared by datasets opened with this session, if any.
+    pub fn io_budget(&self) -> Option<&Arc<IoBudget>> {
+        self.io_budget.as_ref()
+    }
+
     /// Register a new index extension.
     ///
     /// A name can only be registered once per type of index extension.
@@ -171,6 +326,29 @@ impl Session {
         Ok(())
     }
 
+    /// Register an embedding function.
+    ///
+    /// Embedding columns refer to the function by name, see
+    /// [`crate::Dataset::add_embedding_column`]. A name can only be registered once.
+    pub fn register_embedding_function(
+        &mut self,
+        name: String,
+        function: Arc<dyn EmbeddingFunction>,
+    ) -> Result<()> {
+        if self.embedding_functions.contains_key(&name) {
+            return Err(Error::invalid_input(format!(
+                "embedding function {name} is already registered"
+            )));
+        }
+        self.embedding_functions.insert(name, function);
+        Ok(())
+    }
+
+    /// Get a registered embedding function by name.
+    pub fn embedding_function(&self, name: &str) -> Option<Arc<dyn EmbeddingFunction>> {
+        self.embedding_functions.get(name).cloned()
+    }
+
     /// Return the current size of the session in bytes
     ///
     /// Keep in mind that this is not trivial to compute, as we will need to walk the caches
@@ -200,6 +378,30 @@ impl Session {
         &self.metadata_cache.0
     }
 
+    /// Fetch the resources currently used by the session
+    ///
+    /// Unlike [`Self::size_bytes`] this does not walk the caches, it reports the
+    /// sizes tracked by the cache backends.
+    pub async fn resource_usage(&self) -> SessionResourceUsage {
+        SessionResourceUsage {
+            index_cache: CacheUsage {
+                num_entries: self.index_cache.0.size().await,
+                size_bytes: self.index_cache.0.size_bytes().await,
+            },
+            index_cache_capacity: self.index_cache_capacity,
+            metadata_cache: CacheUsage {
+                num_entries: self.metadata_cache.0.size().await,
+                size_bytes: self.metadata_cache.0.size_bytes().await,
+            },
+            metadata_cache_capacity: self.metadata_cache_capacity,
+            io_budget: self.io_budget.as_ref().map(|budget| *budget.config()),
+            available_iops: self
+                .io_budget
+                .as_ref()
+                .and_then(|budget| budget.available_iops()),
+        }
+    }
+
     /// Fetch statistics for the metadata cache
     pub async fn metadata_cache_stats(&self) -> lance_core::cache::CacheStats {
         self.metadata_cache.0.stats().await
@@ -209,6 +411,21 @@ impl Session {
     pub async fn index_cache_stats(&self) -> lance_core::cache::CacheStats {
         self.index_cache.0.stats().await
     }
+
+    /// Fetch statistics for the metadata cache, broken down by the type of entry
+    /// (e.g. `"Manifest"`, `"DeletionVector"`)
+    pub async fn metadata_cache_stats_by_type(
+        &self,
+    ) -> HashMap<&'static str, lance_core::cache::CacheStats> {
+        self.metadata_cache.0.stats_by_type().await
+    }
+
+    /// Fetch statistics for the index cache, broken down by the type of entry
+    pub async fn index_cache_stats_by_type(
+        &self,
+    ) -> HashMap<&'static str, lance_core::cache::CacheStats> {
+        self.index_cache.0.stats_by_type().await
+    }
 }
 
 impl Default for Session {
@@ -251,4 +468,58 @@ mod tests {
                 .is_none()
         );
     }
+
+    #[tokio::test]
+    async fn test_session_config_shared_across_datasets() {
+        use crate::dataset::{Dataset, WriteParams};
+        use arrow_array::{Int32Array, RecordBatch, RecordBatchIterator};
+        use arrow_schema::{DataType, Field, Schema};
+
+        let config = SessionConfig::default()
+            .with_index_cache_size_bytes(1024 * 1024)
+            .with_metadata_cache_size_bytes(2 * 1024 * 1024)
+            .with_io_parallelism(4);
+        let session = Arc::new(Session::from_config(config, Default::default()));
+
+        let usage = session.resource_usage().await;
+        assert_eq!(usage.index_cache_capacity, Some(1024 * 1024));
+        assert_eq!(usage.metadata_cache_capacity, Some(2 * 1024 * 1024));
+        assert_eq!(usage.metadata_cache.num_entries, 0);
+        assert_eq!(
+            usage.io_budget,
+            Some(IoBudgetConfig::default().with_max_concurrent_iops(4))
+        );
+        assert_eq!(usage.available_iops, Some(4));
+
+        let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int32, false)]));
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![1, 2]))])
+                .unwrap();
+        let mut datasets = Vec::new();
+        for uri in ["memory://a", "memory://b"] {
+            let reader = RecordBatchIterator::new(vec![Ok(batch.clone())], schema.clone());
+            let params = WriteParams {
+                session: Some(session.clone()),
+                ..Default::default()
+            };
+            datasets.push(Dataset::write(reader, uri, Some(params)).await.unwrap());
+        }
+        let budgets = datasets
+            .iter()
+            .map(|dataset| dataset.object_store.io_budget().unwrap().clone())
+            .collect::<Vec<_>>();
+        assert!(Arc::ptr_eq(&budgets[0], &budgets[1]));
+        assert!(Arc::ptr_eq(&budgets[0], session.io_budget().unwrap()));
+
+        // Both datasets cache their metadata in the session
+        let usage = session.resource_usage().await;
+        assert!(usage.metadata_cache.num_entries >= 2);
+        assert!(usage.metadata_cache.size_bytes > 0);
+    }
+
+    #[test]
+    fn test_session_config_without_io_limits() {
+        let session = Session::from_config(SessionConfig::default(), Default::default());
+        assert!(session.io_budget().is_none());
+    }
 }
@@ -14,7 +14,7 @@ use std::{borrow::Cow, ops::Deref};
 
 use lance_core::deepsize::{Context, DeepSizeOf};
 use lance_core::{
-    cache::{CacheKey, LanceCache},
+    cache::{CacheCodec, CacheKey, LanceCache},
     utils::deletion::DeletionVector,
 };
 use lance_select::RowAddrMask;
@@ -80,6 +80,9 @@ impl CacheKey for ManifestKey<'_> {
     fn type_name() -> &'static str {
         "Manifest"
     }
+    fn codec() -> Option<CacheCodec> {
+        Some(CacheCodec::from_impl::<Manifest>())
+    }
 }
 
 #[derive(Debug)]
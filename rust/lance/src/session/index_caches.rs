@@ -15,7 +15,7 @@ use std::{borrow::Cow, ops::Deref, sync::Arc};
 use lance_core::cache::{CacheKey, LanceCache};
 use lance_core::deepsize::{Context, DeepSizeOf};
 use lance_index::frag_reuse::FragReuseIndex;
-use lance_table::format::IndexMetadata;
+use lance_table::format::{IndexDirectoryEntry, IndexMetadata};
 use uuid::Uuid;
 
 /// A type-safe wrapper around a LanceCache that enforces namespaces for index data.
@@ -115,6 +115,25 @@ impl CacheKey for IndexMetadataKey {
     }
 }
 
+/// The index directory of a dataset version, see
+/// [`EXTERNAL_INDEX_SECTION_KEY`](lance_table::format::EXTERNAL_INDEX_SECTION_KEY).
+#[derive(Debug)]
+pub struct IndexDirectoryKey {
+    pub version: u64,
+}
+
+impl CacheKey for IndexDirectoryKey {
+    type ValueType = Vec<IndexDirectoryEntry>;
+
+    fn key(&self) -> Cow<'_, str> {
+        Cow::Owned(self.version.to_string())
+    }
+
+    fn type_name() -> &'static str {
+        "Vec<IndexDirectoryEntry>"
+    }
+}
+
 pub struct ProstAny(pub Arc<prost_types::Any>);
 
 impl DeepSizeOf for ProstAny {
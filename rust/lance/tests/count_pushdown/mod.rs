@@ -64,7 +64,7 @@ async fn make_indexed_dataset() -> (Arc<Dataset>, TempStrDir) {
 
 /// Build a `SessionContext` configured with the Lance physical optimizer rule
 /// for aggregate pushdown, then register `dataset` under the name `t`.
-fn lance_aware_context(dataset: Arc<Dataset>) -> SessionContext {
+async fn lance_aware_context(dataset: Arc<Dataset>) -> SessionContext {
     let state = SessionStateBuilder::new()
         .with_default_features()
         .with_physical_optimizer_rule(Arc::new(CountPushdown))
@@ -72,7 +72,7 @@ fn lance_aware_context(dataset: Arc<Dataset>) -> SessionContext {
     let ctx = SessionContext::new_with_state(state);
     ctx.register_table(
         "t",
-        Arc::new(LanceTableProvider::new(dataset, false, false)),
+        Arc::new(LanceTableProvider::builder(dataset).build().await.unwrap()),
     )
     .unwrap();
     ctx
@@ -123,7 +123,7 @@ async fn sql_count_star_with_indexed_filter() {
     // `CountFromMaskExec` while the outer `AggregateExec(Final)` keeps
     // doing the cross-partition combine.
     let (dataset, _tmp) = make_indexed_dataset().await;
-    let ctx = lance_aware_context(dataset);
+    let ctx = lance_aware_context(dataset).await;
 
     let df = ctx
         .sql("SELECT COUNT(*) FROM t WHERE x < 25")
@@ -145,7 +145,7 @@ async fn sql_unfiltered_count_star_uses_statistics() {
     // reaches an `AggregateExec` for our rule to look at. Pin that
     // behaviour: the rule should not fire, and the answer is correct.
     let (dataset, _tmp) = make_indexed_dataset().await;
-    let ctx = lance_aware_context(dataset);
+    let ctx = lance_aware_context(dataset).await;
 
     let df = ctx.sql("SELECT COUNT(*) FROM t").await.unwrap();
     let plan = df.create_physical_plan().await.unwrap();
@@ -166,7 +166,7 @@ async fn sql_count_distinct_does_not_fire_yet() {
     // need their own rule (e.g. over a bitmap-index dictionary). This test
     // pins the not-firing behaviour and the scaffold for the future test.
     let (dataset, _tmp) = make_indexed_dataset().await;
-    let ctx = lance_aware_context(dataset);
+    let ctx = lance_aware_context(dataset).await;
 
     let df = ctx
         .sql("SELECT COUNT(DISTINCT x) FROM t WHERE x < 25")